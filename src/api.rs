@@ -0,0 +1,248 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::error::{DatadogError, Result};
+use crate::handlers::logs::LogsHandler;
+use crate::handlers::metrics::MetricsHandler;
+
+/// Typed entry point for embedding: the same handlers the MCP tools call, taking request
+/// structs and returning response structs instead of JSON arguments and results
+#[derive(Clone)]
+pub struct Datadog {
+    client: Arc<dyn DatadogApi>,
+}
+
+impl Datadog {
+    /// Usually over a `DatadogClient`; any other `DatadogApi` backend works too
+    pub fn new(client: impl DatadogApi + 'static) -> Self {
+        Self {
+            client: Arc::new(client),
+        }
+    }
+
+    pub fn client(&self) -> &Arc<dyn DatadogApi> {
+        &self.client
+    }
+
+    /// Same as the `datadog_metrics_query` tool
+    pub async fn query_metrics(
+        &self,
+        request: MetricsQueryRequest,
+    ) -> Result<MetricsQueryResponse> {
+        let result = MetricsHandler::query(self.client.clone(), &request.into()).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Same as the `datadog_logs_search` tool
+    pub async fn search_logs(&self, request: LogsSearchRequest) -> Result<LogsSearchResponse> {
+        let result = LogsHandler::search(self.client.clone(), &request.into()).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+}
+
+/// Tool arguments as JSON, e.g. to hand a typed request to an MCP client
+fn to_arguments(request: impl Serialize) -> Value {
+    serde_json::to_value(request).unwrap_or_default()
+}
+
+/// Tool arguments parsed into a request; unknown arguments are ignored, wrong types rejected
+fn from_arguments<T: DeserializeOwned>(arguments: Value, tool: &str) -> Result<T> {
+    serde_json::from_value(arguments)
+        .map_err(|e| DatadogError::InvalidInput(format!("Invalid {} arguments: {}", tool, e)))
+}
+
+/// Arguments of `datadog_metrics_query`; times take the same expressions as the tool
+/// ("1 hour ago", ISO 8601, Unix seconds)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricsQueryRequest {
+    pub query: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_points: Option<usize>,
+    /// Time-shifted overlay, e.g. "1w"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compare_to: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summarize: Option<bool>,
+}
+
+impl MetricsQueryRequest {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn between(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.from = Some(from.into());
+        self.to = Some(to.into());
+        self
+    }
+}
+
+impl From<MetricsQueryRequest> for Value {
+    fn from(request: MetricsQueryRequest) -> Self {
+        to_arguments(request)
+    }
+}
+
+impl TryFrom<Value> for MetricsQueryRequest {
+    type Error = DatadogError;
+
+    fn try_from(arguments: Value) -> Result<Self> {
+        from_arguments(arguments, "datadog_metrics_query")
+    }
+}
+
+/// Arguments of `datadog_logs_search`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LogsSearchRequest {
+    pub query: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// Tag filter patterns, e.g. "env:,service:" or "!kube_" (see `TagSelector`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_filter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_exclusions: Option<bool>,
+    /// Custom attributes to return, e.g. "@http.status_code"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<String>,
+}
+
+impl LogsSearchRequest {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn between(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.from = Some(from.into());
+        self.to = Some(to.into());
+        self
+    }
+}
+
+impl From<LogsSearchRequest> for Value {
+    fn from(request: LogsSearchRequest) -> Self {
+        to_arguments(request)
+    }
+}
+
+impl TryFrom<Value> for LogsSearchRequest {
+    type Error = DatadogError;
+
+    fn try_from(arguments: Value) -> Result<Self> {
+        from_arguments(arguments, "datadog_logs_search")
+    }
+}
+
+/// The `{data, pagination, meta}` envelope every tool result shares
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolResponse<T> {
+    pub data: T,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+pub type MetricsQueryResponse = ToolResponse<Vec<QueriedSeries>>;
+pub type LogsSearchResponse = ToolResponse<Vec<LogEntry>>;
+
+/// One series of a metrics query result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueriedSeries {
+    pub metric: Option<String>,
+    pub scope: Option<String>,
+    /// Absent when the request asked for a summary instead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<SeriesPoints>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<i64>,
+    /// `summary`, `comparison`, `unit` and anything else the result carries
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesPoints {
+    pub count: usize,
+    pub data: Vec<SeriesPoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesPoint {
+    /// Formatted UTC time
+    pub timestamp: String,
+    pub value: Option<f64>,
+}
+
+/// One log of a search result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// The requested `attributes`, keyed by path
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub attributes: Map<String, Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_request_round_trip() {
+        let request = MetricsQueryRequest {
+            max_points: Some(100),
+            ..MetricsQueryRequest::new("avg:system.cpu.user{*}").between("1 hour ago", "now")
+        };
+        let arguments = Value::from(request.clone());
+        assert_eq!(
+            arguments,
+            json!({
+                "query": "avg:system.cpu.user{*}",
+                "from": "1 hour ago",
+                "to": "now",
+                "max_points": 100
+            })
+        );
+        assert_eq!(MetricsQueryRequest::try_from(arguments).unwrap(), request);
+    }
+
+    #[test]
+    fn test_request_rejects_wrong_types() {
+        let error = LogsSearchRequest::try_from(json!({"query": "status:error", "limit": "ten"}))
+            .unwrap_err();
+        assert!(matches!(error, DatadogError::InvalidInput(_)));
+        assert!(error.to_string().contains("datadog_logs_search"));
+
+        assert!(LogsSearchRequest::try_from(json!({"limit": 5})).is_err());
+    }
+}
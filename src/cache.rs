@@ -1,6 +1,7 @@
 use serde::Serialize;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
@@ -30,10 +31,42 @@ impl<T> CacheEntry<T> {
     }
 }
 
+/// Lookup counters since startup, kept across `clear`
+#[derive(Default)]
+struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    stale_hits: AtomicU64,
+    evictions: AtomicU64,
+    coalesced: AtomicU64,
+}
+
+/// Snapshot of one cache for `datadog_cache_stats`
+#[derive(Debug, Serialize)]
+pub struct CacheStats {
+    pub entries: usize,
+    /// Past the TTL, kept only as a fallback for failed refreshes
+    pub expired_entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub stale_hits: u64,
+    pub evictions: u64,
+    /// Misses served by a concurrent call's fetch instead of a request of their own
+    pub coalesced: u64,
+    pub hit_rate: Option<f64>,
+    /// Serialized JSON size of the cached data, a rough measure of memory use
+    pub estimated_bytes: usize,
+}
+
 pub struct GenericCache<T> {
     entries: Arc<RwLock<HashMap<String, CacheEntry<T>>>>,
     ttl: Duration,
+    /// How long entries are kept in total; the part past `ttl` serves only `get_stale`
+    retention: Duration,
     max_entries: usize,
+    counters: CacheCounters,
+    /// One lock per key being fetched, so concurrent misses share a single upstream request
+    in_flight: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
 }
 
 impl<T: Serialize> GenericCache<T> {
@@ -41,31 +74,82 @@ impl<T: Serialize> GenericCache<T> {
         Self {
             entries: Arc::new(RwLock::new(HashMap::new())),
             ttl,
+            retention: ttl,
             max_entries,
+            counters: CacheCounters::default(),
+            in_flight: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Keep expired entries for `stale_ttl` beyond the TTL as a fallback when refreshes fail
+    pub fn with_stale_ttl(mut self, stale_ttl: Duration) -> Self {
+        self.retention = self.ttl + stale_ttl;
+        self
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn stale_ttl(&self) -> Duration {
+        self.retention - self.ttl
+    }
+
     pub async fn get(&self, key: &str) -> Option<Arc<T>> {
+        let found = self.lookup(key).await;
+        let counter = match found {
+            Some(_) => &self.counters.hits,
+            None => &self.counters.misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    /// Fresh entry without touching the hit/miss counters
+    async fn lookup(&self, key: &str) -> Option<Arc<T>> {
         let mut cache = self.entries.write().await;
 
         if let Some(entry) = cache.get_mut(key) {
             if entry.age() < self.ttl {
                 return Some(entry.access());
             }
-            cache.remove(key);
+            if entry.age() >= self.retention {
+                cache.remove(key);
+            }
             log::debug!("Cache expired: {}", key);
         }
         None
     }
 
+    /// Entry regardless of TTL (within the stale retention), with its age
+    pub async fn get_stale(&self, key: &str) -> Option<(Arc<T>, Duration)> {
+        let mut cache = self.entries.write().await;
+
+        let stale = cache
+            .get_mut(key)
+            .filter(|entry| entry.age() < self.retention)
+            .map(|entry| (entry.access(), entry.age()));
+        if stale.is_some() {
+            self.counters.stale_hits.fetch_add(1, Ordering::Relaxed);
+        }
+        stale
+    }
+
     pub async fn set(&self, key: String, data: T) {
+        self.insert(key, data).await;
+    }
+
+    async fn insert(&self, key: String, data: T) -> Arc<T> {
         let mut cache = self.entries.write().await;
 
         if cache.len() >= self.max_entries && !cache.contains_key(&key) {
             self.evict_lru(&mut cache);
         }
 
-        cache.insert(key, CacheEntry::new(data));
+        let entry = CacheEntry::new(data);
+        let data = Arc::clone(&entry.data);
+        cache.insert(key, entry);
+        data
     }
 
     pub async fn get_or_fetch<F, Fut>(&self, key: &str, fetch_fn: F) -> crate::error::Result<Arc<T>>
@@ -78,12 +162,34 @@ impl<T: Serialize> GenericCache<T> {
             return Ok(cached);
         }
 
-        log::debug!("Cache miss: {}", key);
-        let data = fetch_fn().await?;
-        self.set(key.to_string(), data).await;
+        let flight = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key.to_string())
+            .or_default()
+            .clone();
+        let _guard = flight.lock().await;
+
+        // Another call fetched the key while this one waited for the lock. After a failed
+        // fetch nothing is cached, so the next waiter tries again itself.
+        if let Some(cached) = self.lookup(key).await {
+            log::debug!("Cache miss coalesced: {}", key);
+            self.counters.coalesced.fetch_add(1, Ordering::Relaxed);
+            return Ok(cached);
+        }
 
-        // Fetch again to return Arc
-        Ok(self.get(key).await.expect("Just inserted"))
+        log::debug!("Cache miss: {}", key);
+        let result = match fetch_fn().await {
+            Ok(data) => Ok(self.insert(key.to_string(), data).await),
+            Err(e) => Err(e),
+        };
+
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        if in_flight.get(key).is_some_and(|f| Arc::ptr_eq(f, &flight)) {
+            in_flight.remove(key);
+        }
+        result
     }
 
     fn evict_lru(&self, cache: &mut HashMap<String, CacheEntry<T>>) {
@@ -93,6 +199,7 @@ impl<T: Serialize> GenericCache<T> {
             .map(|(key, _)| key.clone())
         {
             cache.remove(&lru_key);
+            self.counters.evictions.fetch_add(1, Ordering::Relaxed);
             log::debug!("Evicted LRU cache entry: {}", lru_key);
         }
     }
@@ -102,7 +209,7 @@ impl<T: Serialize> GenericCache<T> {
         let initial_count = cache.len();
 
         cache.retain(|key, entry| {
-            let keep = entry.age() < self.ttl;
+            let keep = entry.age() < self.retention;
             if !keep {
                 log::debug!("Expired cache entry: {}", key);
             }
@@ -111,6 +218,35 @@ impl<T: Serialize> GenericCache<T> {
 
         initial_count - cache.len()
     }
+
+    /// Drop every entry (stale fallbacks included); returns how many were removed
+    pub async fn clear(&self) -> usize {
+        let mut cache = self.entries.write().await;
+        let removed = cache.len();
+        cache.clear();
+        removed
+    }
+
+    pub async fn stats(&self) -> CacheStats {
+        let cache = self.entries.read().await;
+        let hits = self.counters.hits.load(Ordering::Relaxed);
+        let misses = self.counters.misses.load(Ordering::Relaxed);
+
+        CacheStats {
+            entries: cache.len(),
+            expired_entries: cache.values().filter(|e| e.age() >= self.ttl).count(),
+            hits,
+            misses,
+            stale_hits: self.counters.stale_hits.load(Ordering::Relaxed),
+            evictions: self.counters.evictions.load(Ordering::Relaxed),
+            coalesced: self.counters.coalesced.load(Ordering::Relaxed),
+            hit_rate: (hits + misses > 0).then(|| hits as f64 / (hits + misses) as f64),
+            estimated_bytes: cache
+                .values()
+                .map(|e| serde_json::to_vec(&*e.data).map_or(0, |v| v.len()))
+                .sum(),
+        }
+    }
 }
 
 use crate::datadog::models::*;
@@ -121,22 +257,58 @@ pub struct DataCache {
     events: GenericCache<Vec<Event>>,
 }
 
+/// How long expired lists remain available as a fallback when a refresh fails
+const STALE_TTL_SECS: u64 = 3600;
+
+/// Names accepted by `DataCache::clear`, as reported by `DataCache::stats`
+pub const CACHE_NAMES: &[&str] = &["dashboards", "monitors", "events"];
+
 impl DataCache {
     pub fn new(ttl_seconds: u64) -> Self {
         let ttl = Duration::from_secs(ttl_seconds);
+        let stale_ttl = Duration::from_secs(STALE_TTL_SECS);
         let max_entries = 100;
 
         Self {
-            dashboards: GenericCache::new(ttl, max_entries),
-            monitors: GenericCache::new(ttl, max_entries),
-            events: GenericCache::new(ttl, max_entries),
+            dashboards: GenericCache::new(ttl, max_entries).with_stale_ttl(stale_ttl),
+            monitors: GenericCache::new(ttl, max_entries).with_stale_ttl(stale_ttl),
+            events: GenericCache::new(ttl, max_entries).with_stale_ttl(stale_ttl),
         }
     }
 
+    /// TTL shared by the dashboards, monitors and events caches
+    pub fn ttl(&self) -> Duration {
+        self.dashboards.ttl()
+    }
+
+    pub fn stale_ttl(&self) -> Duration {
+        self.dashboards.stale_ttl()
+    }
+
+    /// Response meta flagging data served from cache after a failed refresh
+    pub fn stale_meta(age: Duration, error: &crate::error::DatadogError) -> serde_json::Value {
+        serde_json::json!({
+            "stale": true,
+            "stale_age_secs": age.as_secs(),
+            "warning": format!(
+                "Refresh failed ({}); showing cached data from {}s ago",
+                error,
+                age.as_secs()
+            ),
+        })
+    }
+
     pub async fn set_dashboards(&self, key: String, data: Vec<DashboardSummary>) {
         self.dashboards.set(key, data).await
     }
 
+    pub async fn get_stale_dashboards(
+        &self,
+        key: &str,
+    ) -> Option<(Arc<Vec<DashboardSummary>>, Duration)> {
+        self.dashboards.get_stale(key).await
+    }
+
     pub async fn get_or_fetch_dashboards<F, Fut>(
         &self,
         key: &str,
@@ -153,6 +325,10 @@ impl DataCache {
         self.monitors.set(key, data).await
     }
 
+    pub async fn get_stale_monitors(&self, key: &str) -> Option<(Arc<Vec<Monitor>>, Duration)> {
+        self.monitors.get_stale(key).await
+    }
+
     pub async fn get_or_fetch_monitors<F, Fut>(
         &self,
         key: &str,
@@ -169,6 +345,10 @@ impl DataCache {
         self.events.set(key, data).await
     }
 
+    pub async fn get_stale_events(&self, key: &str) -> Option<(Arc<Vec<Event>>, Duration)> {
+        self.events.get_stale(key).await
+    }
+
     pub async fn get_or_fetch_events<F, Fut>(
         &self,
         key: &str,
@@ -181,6 +361,47 @@ impl DataCache {
         self.events.get_or_fetch(key, fetch).await
     }
 
+    /// Stats of each cache, in `CACHE_NAMES` order
+    pub async fn named_stats(&self) -> Vec<(&'static str, CacheStats)> {
+        vec![
+            ("dashboards", self.dashboards.stats().await),
+            ("monitors", self.monitors.stats().await),
+            ("events", self.events.stats().await),
+        ]
+    }
+
+    /// Per-cache counters, entry counts and size estimates
+    pub async fn stats(&self) -> serde_json::Value {
+        let caches: serde_json::Map<String, serde_json::Value> = self
+            .named_stats()
+            .await
+            .into_iter()
+            .map(|(name, stats)| (name.to_string(), serde_json::json!(stats)))
+            .collect();
+        serde_json::json!({
+            "ttl_secs": self.ttl().as_secs(),
+            "stale_ttl_secs": self.stale_ttl().as_secs(),
+            "caches": caches,
+        })
+    }
+
+    /// Empty one cache by name, or all of them; returns how many entries were removed
+    pub async fn clear(&self, name: Option<&str>) -> crate::error::Result<usize> {
+        match name {
+            Some("dashboards") => Ok(self.dashboards.clear().await),
+            Some("monitors") => Ok(self.monitors.clear().await),
+            Some("events") => Ok(self.events.clear().await),
+            Some(other) => Err(crate::error::DatadogError::InvalidInput(format!(
+                "Unknown cache '{}'. Expected one of: {}",
+                other,
+                CACHE_NAMES.join(", ")
+            ))),
+            None => Ok(self.dashboards.clear().await
+                + self.monitors.clear().await
+                + self.events.clear().await),
+        }
+    }
+
     pub async fn cleanup_all_expired(&self) -> usize {
         let mut total = 0;
         total += self.dashboards.cleanup_expired().await;
@@ -286,6 +507,106 @@ mod tests {
         assert_eq!(removed, 2);
     }
 
+    #[tokio::test]
+    async fn test_cache_stale_fallback() {
+        let cache: GenericCache<String> = GenericCache::new(Duration::from_millis(50), 100)
+            .with_stale_ttl(Duration::from_secs(60));
+
+        cache.set("key1".to_string(), "value1".to_string()).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Expired for normal reads, still available as stale data
+        assert_eq!(cache.get("key1").await, None);
+        let (value, age) = cache.get_stale("key1").await.unwrap();
+        assert_eq!(&**value, "value1");
+        assert!(age >= Duration::from_millis(50));
+
+        // Cleanup keeps entries within the stale window
+        assert_eq!(cache.cleanup_expired().await, 0);
+        assert!(cache.get_stale("missing").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cache_get_or_fetch_single_flight() {
+        use std::sync::atomic::AtomicUsize;
+
+        let cache: Arc<GenericCache<i32>> =
+            Arc::new(GenericCache::new(Duration::from_secs(60), 100));
+        let fetches = Arc::new(AtomicUsize::new(0));
+
+        let calls: Vec<_> = (0..5)
+            .map(|_| {
+                let cache = cache.clone();
+                let fetches = fetches.clone();
+                tokio::spawn(async move {
+                    cache
+                        .get_or_fetch("monitors", || async {
+                            fetches.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            Ok(42)
+                        })
+                        .await
+                })
+            })
+            .collect();
+
+        for call in calls {
+            assert_eq!(*call.await.unwrap().unwrap(), 42);
+        }
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.stats().await.coalesced, 4);
+    }
+
+    #[tokio::test]
+    async fn test_cache_get_or_fetch_failure_not_shared() {
+        let cache: GenericCache<i32> = GenericCache::new(Duration::from_secs(60), 100);
+
+        let failed = cache
+            .get_or_fetch("key1", || async {
+                Err(crate::error::DatadogError::TimeoutError)
+            })
+            .await;
+        assert!(failed.is_err());
+
+        let result = cache.get_or_fetch("key1", || async { Ok(7) }).await;
+        assert_eq!(*result.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_cache_stats_and_clear() {
+        let cache: GenericCache<String> = GenericCache::new(Duration::from_secs(60), 1);
+
+        cache.set("key1".to_string(), "value1".to_string()).await;
+        assert!(cache.get("key1").await.is_some());
+        assert!(cache.get("missing").await.is_none());
+        let fetched = cache.get_or_fetch("key2", || async { Ok("v2".to_string()) });
+        assert_eq!(&**fetched.await.unwrap(), "v2");
+
+        let stats = cache.stats().await;
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.hit_rate, Some(1.0 / 3.0));
+        assert_eq!(stats.estimated_bytes, "\"v2\"".len());
+
+        assert_eq!(cache.clear().await, 1);
+        assert!(cache.get_stale("key2").await.is_none());
+        assert_eq!(cache.stats().await.entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_data_cache_clear_by_name() {
+        let cache = DataCache::new(60);
+        cache.set_monitors("m".to_string(), vec![]).await;
+        cache.set_events("e".to_string(), vec![]).await;
+
+        assert_eq!(cache.clear(Some("monitors")).await.unwrap(), 1);
+        assert_eq!(cache.stats().await["caches"]["events"]["entries"], 1);
+        assert_eq!(cache.clear(None).await.unwrap(), 1);
+        assert!(cache.clear(Some("hosts")).await.is_err());
+    }
+
     #[test]
     fn test_create_cache_key() {
         let key1 = create_cache_key("/api/metrics", &json!({"query": "cpu"}));
@@ -0,0 +1,59 @@
+use futures::future::BoxFuture;
+use serde_json::Value;
+
+use crate::error::{DatadogError, Result};
+
+/// One raw Datadog API call
+#[derive(Debug, Clone)]
+pub struct ApiRequest<'a> {
+    pub method: reqwest::Method,
+    /// Path below the API host, e.g. `/api/v1/query`
+    pub endpoint: &'a str,
+    pub query: Vec<(&'a str, String)>,
+    pub body: Option<Value>,
+    /// Whether resending after a transient failure is harmless
+    pub retry_safe: bool,
+    /// Whether it creates or changes data in Datadog (a dry run stops these)
+    pub mutating: bool,
+}
+
+/// What handlers need from Datadog: a way to send raw requests plus the org's settings.
+/// `DatadogClient` implements it over HTTP; tests and alternative backends (fixtures,
+/// recorded sessions) can stand in for it. The typed endpoints (`query_metrics`,
+/// `search_logs`, ...) are defined on `dyn DatadogApi` in `endpoints.rs`.
+pub trait DatadogApi: Send + Sync {
+    /// Send `request` and return the JSON body of a successful response; failures come
+    /// back as the usual `DatadogError`s (`AuthError`, `ApiError`, `RateLimitError`, ...)
+    fn send_json<'a>(&'a self, request: ApiRequest<'a>) -> BoxFuture<'a, Result<Value>>;
+
+    fn site(&self) -> &str;
+
+    fn base_url(&self) -> &str;
+
+    fn writes_allowed(&self) -> bool;
+
+    fn get_tag_filter(&self) -> Option<&str>;
+
+    /// Tag filter for a product, falling back to the global DD_TAG_FILTER
+    fn get_tag_filter_for(&self, product: &str) -> Option<&str> {
+        let _ = product;
+        self.get_tag_filter()
+    }
+
+    /// Requests a tool runs at once when it fans out (see `batch::execute_batch`)
+    fn max_concurrency(&self) -> usize {
+        super::batch::DEFAULT_MAX_CONCURRENCY
+    }
+
+    /// Last four characters of the application key, as Datadog lists keys
+    fn app_key_last4(&self) -> &str;
+
+    /// Fail before any mutating request when writes are disabled
+    fn ensure_writes_allowed(&self) -> Result<()> {
+        if self.writes_allowed() {
+            Ok(())
+        } else {
+            Err(DatadogError::WritesDisabled)
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use futures::future::{BoxFuture, join_all};
+use tokio::sync::Semaphore;
+
+use crate::error::Result;
+
+/// Requests in flight at once per batch unless DD_MAX_CONCURRENCY says otherwise
+pub const DEFAULT_MAX_CONCURRENCY: usize = 8;
+
+/// One API request of a batch, typically a `DatadogApi` endpoint call
+pub type ApiCall<'a, T> = BoxFuture<'a, Result<T>>;
+
+/// Run `calls` concurrently, at most `max_concurrency` at a time, returning their outcomes
+/// in order. Each call still goes through the client's rate limiter, retries and circuit
+/// breaker; a failing call doesn't cancel the others.
+pub async fn execute_batch<T>(
+    calls: Vec<ApiCall<'_, T>>,
+    max_concurrency: usize,
+) -> Vec<Result<T>> {
+    let semaphore = Semaphore::new(max_concurrency.max(1));
+
+    join_all(calls.into_iter().map(|call| async {
+        // The semaphore is never closed, so acquiring only waits
+        let _permit = semaphore.acquire().await.ok();
+        call.await
+    }))
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DatadogError;
+    use futures::FutureExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_execute_batch_bounds_concurrency() {
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        let calls: Vec<ApiCall<'_, usize>> = (0..10)
+            .map(|i| {
+                let (running, peak) = (&running, &peak);
+                async move {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Ok(i)
+                }
+                .boxed()
+            })
+            .collect();
+
+        let results = execute_batch(calls, 3).await;
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        let values: Vec<usize> = results.into_iter().map(|r| r.unwrap()).collect();
+        assert_eq!(values, (0..10).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_execute_batch_partial_failure() {
+        let calls: Vec<ApiCall<'_, u32>> = vec![
+            async { Ok(1) }.boxed(),
+            async {
+                Err(DatadogError::ApiError {
+                    status: 400,
+                    body: "x".to_string(),
+                })
+            }
+            .boxed(),
+            async { Ok(3) }.boxed(),
+        ];
+
+        let results = execute_batch(calls, DEFAULT_MAX_CONCURRENCY).await;
+        assert_eq!(results.iter().filter(|r| r.is_err()).count(), 1);
+        assert_eq!(results[2].as_ref().unwrap(), &3);
+
+        let error = results[1].as_ref().unwrap_err().details();
+        assert_eq!(error["code"], "bad_request");
+    }
+}
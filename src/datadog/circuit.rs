@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::{DatadogError, Result};
+
+/// Consecutive transient failures of one endpoint family before its circuit opens
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+
+/// How long an open circuit fast-fails calls before letting a trial request through
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default)]
+struct FamilyState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Per-endpoint-family circuit breaker: after `threshold` consecutive transient failures
+/// (5xx, 429, timeouts, network errors) calls to that family fail immediately for `cooldown`.
+/// The first call after the cooldown is a trial; one more failure reopens the circuit.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    families: Mutex<HashMap<String, FamilyState>>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(DEFAULT_FAILURE_THRESHOLD, DEFAULT_COOLDOWN)
+    }
+}
+
+impl CircuitBreaker {
+    /// A threshold of 0 disables the breaker
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            families: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Endpoint family an API path belongs to, e.g. `/api/v1/monitor/123` → `/api/v1/monitor`
+    pub fn family(endpoint: &str) -> String {
+        let segments: Vec<&str> = endpoint
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .take(3)
+            .collect();
+        format!("/{}", segments.join("/"))
+    }
+
+    /// Fail fast with `ServiceUnavailable` while the family's circuit is open
+    pub fn check(&self, family: &str) -> Result<()> {
+        if self.threshold == 0 {
+            return Ok(());
+        }
+
+        let mut families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(state) = families.get_mut(family) else {
+            return Ok(());
+        };
+
+        match state.open_until {
+            Some(until) if until > Instant::now() => Err(DatadogError::ServiceUnavailable {
+                family: family.to_string(),
+                retry_after: until - Instant::now(),
+            }),
+            Some(_) => {
+                // Half-open: let this call through, a single failure trips it again
+                state.open_until = None;
+                state.consecutive_failures = self.threshold - 1;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Count an attempt's outcome. Only transient errors count as failures; a 4xx means the
+    /// API is up and resets the count like a success.
+    pub fn record<T>(&self, family: &str, result: &Result<T>) {
+        if self.threshold == 0 {
+            return;
+        }
+
+        let mut families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Err(e) if e.is_transient() => {
+                let state = families.entry(family.to_string()).or_default();
+                state.consecutive_failures += 1;
+                if state.consecutive_failures >= self.threshold {
+                    state.open_until = Some(Instant::now() + self.cooldown);
+                    log::warn!(
+                        "Circuit open for {} after {} consecutive failures; fast-failing for {}s",
+                        family,
+                        state.consecutive_failures,
+                        self.cooldown.as_secs()
+                    );
+                }
+            }
+            _ => {
+                families.remove(family);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_error() -> Result<()> {
+        Err(DatadogError::ApiError {
+            status: 503,
+            body: "down".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_family() {
+        assert_eq!(
+            CircuitBreaker::family("/api/v1/monitor/123"),
+            "/api/v1/monitor"
+        );
+        assert_eq!(
+            CircuitBreaker::family("/api/v2/logs/events/search"),
+            "/api/v2/logs"
+        );
+        assert_eq!(CircuitBreaker::family("/api/v1/query"), "/api/v1/query");
+    }
+
+    #[test]
+    fn test_trips_after_threshold() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+        let family = "/api/v1/monitor";
+
+        for _ in 0..2 {
+            breaker.record(family, &server_error());
+        }
+        assert!(breaker.check(family).is_ok());
+
+        breaker.record(family, &server_error());
+        assert!(matches!(
+            breaker.check(family),
+            Err(DatadogError::ServiceUnavailable { .. })
+        ));
+
+        // Other families are unaffected
+        assert!(breaker.check("/api/v1/dashboard").is_ok());
+    }
+
+    #[test]
+    fn test_client_errors_reset_count() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        let family = "/api/v1/monitor";
+
+        breaker.record(family, &server_error());
+        breaker.record::<()>(
+            family,
+            &Err(DatadogError::ApiError {
+                status: 404,
+                body: "x".to_string(),
+            }),
+        );
+        breaker.record(family, &server_error());
+        assert!(breaker.check(family).is_ok());
+    }
+
+    #[test]
+    fn test_half_open_after_cooldown() {
+        let breaker = CircuitBreaker::new(2, Duration::ZERO);
+        let family = "/api/v1/monitor";
+
+        breaker.record(family, &server_error());
+        breaker.record(family, &server_error());
+
+        // Cooldown elapsed: one trial call goes through, and its failure reopens the circuit
+        assert!(breaker.check(family).is_ok());
+        breaker.record(family, &server_error());
+        let state = breaker.families.lock().unwrap();
+        assert!(state[family].open_until.is_some());
+    }
+
+    #[test]
+    fn test_disabled() {
+        let breaker = CircuitBreaker::new(0, Duration::from_secs(60));
+        for _ in 0..10 {
+            breaker.record("/api/v1/monitor", &server_error());
+        }
+        assert!(breaker.check("/api/v1/monitor").is_ok());
+    }
+}
@@ -1,25 +1,181 @@
-use reqwest::{Client, Response, StatusCode};
+use futures::future::BoxFuture;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Certificate, Client, Response, StatusCode};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
-use std::time::Duration;
-
-use super::models::*;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::api::{ApiRequest, DatadogApi};
+use super::batch;
+use super::circuit::{self, CircuitBreaker};
+use super::rate_limit::RateLimiter;
 use super::retry;
 use crate::error::{DatadogError, Result};
+use crate::handlers::common::TagSelector;
+use crate::redact::{redact, register_secret};
+use crate::telemetry::telemetry;
 
+/// Whole-request timeout, overridable with DD_HTTP_TIMEOUT_SECS
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
 
+/// TCP/TLS connect timeout, overridable with DD_CONNECT_TIMEOUT_SECS
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+tokio::task_local! {
+    /// Budget for each API request of the current tool call (its `timeout_secs` argument)
+    static CALL_TIMEOUT: Duration;
+}
+
+/// Products whose tag filter can be overridden with DD_TAG_FILTER_<PRODUCT>
+pub const TAG_FILTER_PRODUCTS: &[&str] = &["logs", "spans", "hosts", "rum", "processes"];
+
+/// A tag filter default read from `var`, refused at startup rather than on every call when
+/// it does not parse (e.g. a bad `re:` pattern)
+fn checked_tag_filter(var: &str, filter: Option<String>) -> Result<Option<String>> {
+    if let Some(filter) = &filter {
+        TagSelector::parse(filter).map_err(|e| {
+            let reason = match e {
+                DatadogError::InvalidInput(reason) => reason,
+                e => e.to_string(),
+            };
+            DatadogError::InvalidInput(format!("{}: {}", var, reason))
+        })?;
+    }
+    Ok(filter)
+}
+
+/// HTTP/2 PING interval that keeps idle connections warm between tool calls
+const KEEPALIVE_INTERVAL_SECS: u64 = 30;
+const KEEPALIVE_TIMEOUT_SECS: u64 = 10;
+
+/// How long an idle pooled connection is kept before being dropped
+const POOL_IDLE_TIMEOUT_SECS: u64 = 300;
+
+/// Log submission endpoint, served by the site's logs intake host rather than its API host
+pub(super) const LOGS_INTAKE_ENDPOINT: &str = "/api/v2/logs";
+
 pub struct DatadogClient {
     client: Client,
+    timeout: Duration,
+    connect_timeout: Duration,
+    root_certificates: Vec<Certificate>,
     api_key: String,
     app_key: String,
+    site: String,
     base_url: String,
+    /// Host of the logs intake, e.g. `https://http-intake.logs.datadoghq.com`
+    intake_url: String,
     tag_filter: Option<String>,
+    product_tag_filters: HashMap<&'static str, String>,
+    allow_writes: bool,
+    extra_headers: HeaderMap,
+    circuit: CircuitBreaker,
+    rate_limiter: RateLimiter,
+    max_concurrency: usize,
 }
 
 impl DatadogClient {
+    /// Client of the default org: the shared settings plus DD_API_URL and DD_EXTRA_HEADERS
     pub fn new(api_key: String, app_key: String, site: Option<String>) -> Result<Self> {
-        Self::with_tag_filter(api_key, app_key, site, std::env::var("DD_TAG_FILTER").ok())
+        let mut client = Self::with_shared_settings(api_key, app_key, site)?;
+
+        if let Ok(spec) = std::env::var("DD_EXTRA_HEADERS") {
+            client = client.with_headers(Self::parse_headers(&spec)?);
+        }
+
+        // Preview and partner programs are sometimes served from non-standard hosts
+        if let Ok(url) = std::env::var("DD_API_URL") {
+            client = client.with_api_url(&url);
+        }
+
+        Ok(client)
+    }
+
+    /// Client configured from the settings every org shares (mode, tag filters, timeouts,
+    /// CA bundle, circuit breaker, rate limits); host and header overrides are per org
+    pub fn with_shared_settings(
+        api_key: String,
+        app_key: String,
+        site: Option<String>,
+    ) -> Result<Self> {
+        // DD_MCP_MODE takes precedence; DD_ALLOW_WRITES is the older switch for the same thing
+        let allow_writes = match std::env::var("DD_MCP_MODE") {
+            Ok(mode) => match mode.trim().to_lowercase().replace('-', "_").as_str() {
+                // Replayed calls never reach Datadog, so there is nothing to write to
+                "read_only" | "replay" => false,
+                "read_write" => true,
+                _ => {
+                    return Err(DatadogError::InvalidInput(format!(
+                        "Invalid DD_MCP_MODE '{}': expected read_only, read_write or replay",
+                        mode
+                    )));
+                }
+            },
+            Err(_) => std::env::var("DD_ALLOW_WRITES")
+                .map(|v| matches!(v.trim().to_lowercase().as_str(), "true" | "1" | "yes"))
+                .unwrap_or(false),
+        };
+
+        let tag_filter = |var: &str| checked_tag_filter(var, std::env::var(var).ok());
+        let mut client =
+            Self::with_tag_filter(api_key, app_key, site, tag_filter("DD_TAG_FILTER")?)?
+                .with_writes(allow_writes);
+
+        for product in TAG_FILTER_PRODUCTS {
+            let var = format!("DD_TAG_FILTER_{}", product.to_uppercase());
+            if let Some(filter) = tag_filter(&var)? {
+                client = client.with_product_tag_filter(product, filter);
+            }
+        }
+
+        let env_positive = |var: &str| {
+            std::env::var(var)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .filter(|n| *n > 0)
+        };
+        let timeout = env_positive("DD_HTTP_TIMEOUT_SECS");
+        let connect_timeout = env_positive("DD_CONNECT_TIMEOUT_SECS");
+        if timeout.is_some() || connect_timeout.is_some() {
+            client = client.with_timeouts(
+                Duration::from_secs(timeout.unwrap_or(DEFAULT_TIMEOUT_SECS)),
+                Duration::from_secs(connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS)),
+            )?;
+        }
+
+        // Corporate proxies that intercept TLS re-sign traffic with their own root CA
+        if let Ok(path) = std::env::var("DD_CA_BUNDLE") {
+            client = client.with_ca_bundle(&path)?;
+        }
+
+        let threshold = std::env::var("DD_CIRCUIT_BREAKER_THRESHOLD")
+            .ok()
+            .and_then(|v| v.trim().parse::<u32>().ok());
+        let cooldown = env_positive("DD_CIRCUIT_BREAKER_COOLDOWN_SECS");
+        if threshold.is_some() || cooldown.is_some() {
+            client = client.with_circuit_breaker(
+                threshold.unwrap_or(circuit::DEFAULT_FAILURE_THRESHOLD),
+                cooldown.map_or(circuit::DEFAULT_COOLDOWN, Duration::from_secs),
+            );
+        }
+
+        let max_rps =
+            match std::env::var("DD_MAX_RPS") {
+                Ok(v) => Some(v.trim().parse::<f64>().map_err(|_| {
+                    DatadogError::InvalidInput(format!("Invalid DD_MAX_RPS '{}'", v))
+                })?),
+                Err(_) => None,
+            };
+        let budgets = std::env::var("DD_RATE_LIMITS").unwrap_or_default();
+        client = client.with_rate_limiter(RateLimiter::new(max_rps, &budgets)?);
+
+        if let Some(max) = env_positive("DD_MAX_CONCURRENCY") {
+            client = client.with_max_concurrency(max as usize);
+        }
+
+        Ok(client)
     }
 
     pub fn with_tag_filter(
@@ -30,42 +186,217 @@ impl DatadogClient {
     ) -> Result<Self> {
         let site = site.unwrap_or_else(|| "datadoghq.com".to_string());
         let base_url = format!("https://api.{}", site);
+        register_secret(&api_key);
+        register_secret(&app_key);
+        let intake_url = format!("https://http-intake.logs.{}", site);
 
-        let client = Client::builder()
-            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
-            .build()
-            .map_err(DatadogError::NetworkError)?;
+        let timeout = Duration::from_secs(DEFAULT_TIMEOUT_SECS);
+        let connect_timeout = Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS);
+        let client = Self::http_client(timeout, connect_timeout, &[])?;
 
         Ok(Self {
             client,
+            timeout,
+            connect_timeout,
+            root_certificates: Vec::new(),
             api_key,
             app_key,
+            site,
             base_url,
+            intake_url,
             tag_filter,
+            product_tag_filters: HashMap::new(),
+            allow_writes: false,
+            extra_headers: HeaderMap::new(),
+            circuit: CircuitBreaker::default(),
+            rate_limiter: RateLimiter::default(),
+            max_concurrency: batch::DEFAULT_MAX_CONCURRENCY,
         })
     }
 
-    pub fn get_tag_filter(&self) -> Option<&str> {
-        self.tag_filter.as_deref()
+    /// Proxies come from HTTPS_PROXY / HTTP_PROXY / ALL_PROXY and NO_PROXY, which reqwest
+    /// reads from the environment unless told otherwise
+    fn http_client(
+        timeout: Duration,
+        connect_timeout: Duration,
+        root_certificates: &[Certificate],
+    ) -> Result<Client> {
+        root_certificates
+            .iter()
+            .fold(Client::builder(), |builder, cert| {
+                builder.add_root_certificate(cert.clone())
+            })
+            .timeout(timeout)
+            .connect_timeout(connect_timeout)
+            .http2_adaptive_window(true)
+            .http2_keep_alive_interval(Duration::from_secs(KEEPALIVE_INTERVAL_SECS))
+            .http2_keep_alive_timeout(Duration::from_secs(KEEPALIVE_TIMEOUT_SECS))
+            .http2_keep_alive_while_idle(true)
+            .tcp_keepalive(Duration::from_secs(KEEPALIVE_INTERVAL_SECS))
+            .pool_idle_timeout(Duration::from_secs(POOL_IDLE_TIMEOUT_SECS))
+            .build()
+            .map_err(DatadogError::NetworkError)
+    }
+
+    /// Replace the default request and connect timeouts
+    pub fn with_timeouts(mut self, timeout: Duration, connect_timeout: Duration) -> Result<Self> {
+        self.client = Self::http_client(timeout, connect_timeout, &self.root_certificates)?;
+        self.timeout = timeout;
+        self.connect_timeout = connect_timeout;
+        Ok(self)
+    }
+
+    /// Trust the root certificates in the PEM file at `path` (DD_CA_BUNDLE) on top of the
+    /// built-in roots
+    pub fn with_ca_bundle(mut self, path: &str) -> Result<Self> {
+        let invalid = |reason: String| {
+            DatadogError::InvalidInput(format!("Invalid DD_CA_BUNDLE '{}': {}", path, reason))
+        };
+        let pem = std::fs::read(path).map_err(|e| invalid(e.to_string()))?;
+        let certificates =
+            Certificate::from_pem_bundle(&pem).map_err(|e| invalid(e.to_string()))?;
+        if certificates.is_empty() {
+            return Err(invalid("no PEM certificates found".to_string()));
+        }
+
+        self.client = Self::http_client(self.timeout, self.connect_timeout, &certificates)?;
+        self.root_certificates = certificates;
+        Ok(self)
+    }
+
+    /// Fast-fail an endpoint family for `cooldown` after `threshold` consecutive
+    /// transient failures (0 disables)
+    pub fn with_circuit_breaker(mut self, threshold: u32, cooldown: Duration) -> Self {
+        self.circuit = CircuitBreaker::new(threshold, cooldown);
+        self
+    }
+
+    /// Throttle requests client-side (see DD_MAX_RPS / DD_RATE_LIMITS)
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Requests a tool runs at once when it fans out (see `batch::execute_batch`)
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// Run `future` (one tool call) with every API request it makes, retries included,
+    /// bounded by `timeout`; None keeps the client defaults
+    pub async fn with_call_timeout<F: std::future::Future>(
+        timeout: Option<Duration>,
+        future: F,
+    ) -> F::Output {
+        match timeout {
+            Some(timeout) => CALL_TIMEOUT.scope(timeout, future).await,
+            None => future.await,
+        }
+    }
+
+    /// Enable tools that create or modify Datadog resources (off unless DD_MCP_MODE=read_write
+    /// or DD_ALLOW_WRITES is set)
+    pub fn with_writes(mut self, allow_writes: bool) -> Self {
+        self.allow_writes = allow_writes;
+        self
+    }
+
+    /// Headers sent on every request, replacing defaults of the same name
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.extra_headers = headers;
+        self
+    }
+
+    /// Send requests to `url` instead of the site's API and logs intake hosts
+    pub fn with_api_url(mut self, url: &str) -> Self {
+        self.base_url = url.trim_end_matches('/').to_string();
+        self.intake_url = self.base_url.clone();
+        self
+    }
+
+    /// Parse "Name=value,Other-Name=value" (as in DD_EXTRA_HEADERS) into a header map
+    pub fn parse_headers(spec: &str) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, value) = entry.split_once('=').ok_or_else(|| {
+                DatadogError::InvalidInput(format!(
+                    "Invalid header '{}': expected Name=value",
+                    entry
+                ))
+            })?;
+
+            let name = HeaderName::from_bytes(name.trim().as_bytes()).map_err(|_| {
+                DatadogError::InvalidInput(format!("Invalid header name '{}'", name.trim()))
+            })?;
+            let value = HeaderValue::from_str(value.trim()).map_err(|_| {
+                DatadogError::InvalidInput(format!("Invalid value for header '{}'", name))
+            })?;
+
+            headers.insert(name, value);
+        }
+
+        Ok(headers)
     }
 
-    async fn request<T: DeserializeOwned>(
+    /// Override the global tag filter for one product (see TAG_FILTER_PRODUCTS)
+    pub fn with_product_tag_filter(mut self, product: &'static str, filter: String) -> Self {
+        self.product_tag_filters.insert(product, filter);
+        self
+    }
+
+    async fn send<T: DeserializeOwned>(
         &self,
         method: reqwest::Method,
         endpoint: &str,
         query: Option<Vec<(&str, String)>>,
         body: Option<impl Serialize>,
+        retry_safe: bool,
     ) -> Result<T> {
-        let url = format!("{}{}", self.base_url, endpoint);
+        match CALL_TIMEOUT.try_with(|timeout| *timeout).ok() {
+            Some(timeout) => tokio::time::timeout(
+                timeout,
+                self.send_with_retries(method, endpoint, query, body, retry_safe, Some(timeout)),
+            )
+            .await
+            .map_err(|_| DatadogError::TimeoutError)?,
+            None => {
+                self.send_with_retries(method, endpoint, query, body, retry_safe, None)
+                    .await
+            }
+        }
+    }
+
+    async fn send_with_retries<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        endpoint: &str,
+        query: Option<Vec<(&str, String)>>,
+        body: Option<impl Serialize>,
+        retry_safe: bool,
+        timeout: Option<Duration>,
+    ) -> Result<T> {
+        let host = if endpoint == LOGS_INTAKE_ENDPOINT {
+            &self.intake_url
+        } else {
+            &self.base_url
+        };
+        let url = format!("{}{}", host, endpoint);
+        let family = CircuitBreaker::family(endpoint);
 
         let mut retries = 0;
         loop {
+            self.circuit.check(&family)?;
+            self.rate_limiter.acquire(endpoint).await;
+
             let mut request = self
                 .client
                 .request(method.clone(), &url)
                 .header("DD-API-KEY", &self.api_key)
                 .header("DD-APPLICATION-KEY", &self.app_key)
-                .header("Content-Type", "application/json");
+                .header("Content-Type", "application/json")
+                .headers(self.extra_headers.clone());
 
             if let Some(ref params) = query {
                 for (key, value) in params {
@@ -77,24 +408,78 @@ impl DatadogClient {
                 request = request.json(data);
             }
 
-            let response = request.send().await?;
+            // Lets a call run longer than the client-wide timeout when asked to
+            if let Some(timeout) = timeout {
+                request = request.timeout(timeout);
+            }
+
+            let started = Instant::now();
+            let response = match request.send().await {
+                Ok(response) => response,
+                Err(e) => {
+                    let error: Result<T> = Err(if e.is_timeout() {
+                        DatadogError::TimeoutError
+                    } else {
+                        DatadogError::NetworkError(e)
+                    });
+                    self.circuit.record(&family, &error);
+                    Self::record_attempt(&method, endpoint, &family, started, &error);
+                    return error;
+                }
+            };
+
+            let result = self.handle_response(response).await;
+            self.circuit.record(&family, &result);
+            Self::record_attempt(&method, endpoint, &family, started, &result);
 
-            match self.handle_response(response).await {
+            match result {
                 Ok(data) => return Ok(data),
                 Err(e) => {
-                    if !retry::should_retry(retries) {
+                    if !retry_safe || !retry::should_retry(retries) {
                         return Err(e);
                     }
 
                     retries += 1;
+                    telemetry().record_retry(&family);
 
-                    // Exponential backoff
-                    tokio::time::sleep(retry::calculate_backoff(retries)).await;
+                    // Rate-limit reset when announced, exponential backoff otherwise
+                    match retry::retry_delay(&e, retries) {
+                        Some(delay) => tokio::time::sleep(delay).await,
+                        None => return Err(e),
+                    }
                 }
             }
         }
     }
 
+    /// Count one HTTP attempt in the server telemetry and emit it as a debug event
+    fn record_attempt<T>(
+        method: &reqwest::Method,
+        endpoint: &str,
+        family: &str,
+        started: Instant,
+        result: &Result<T>,
+    ) {
+        let elapsed = started.elapsed();
+        telemetry().record_api(family, elapsed, result.is_ok());
+        let elapsed_ms = elapsed.as_millis() as u64;
+        match result {
+            Ok(_) => tracing::debug!(
+                %method,
+                endpoint = redact(endpoint),
+                elapsed_ms,
+                "Datadog API request"
+            ),
+            Err(e) => tracing::debug!(
+                %method,
+                endpoint = redact(endpoint),
+                elapsed_ms,
+                error = redact(&e.to_string()),
+                "Datadog API request failed"
+            ),
+        }
+    }
+
     async fn handle_response<T: DeserializeOwned>(&self, response: Response) -> Result<T> {
         let status = response.status();
 
@@ -104,382 +489,88 @@ impl DatadogClient {
                 .await
                 .map_err(DatadogError::NetworkError)
         } else {
+            let reset_after = retry::parse_reset_after(response.headers());
+            // Error bodies can echo the request, keys included
             let error_text = response
                 .text()
                 .await
+                .map(|text| redact(&text))
                 .unwrap_or_else(|_| "Unknown error".to_string());
 
             match status {
-                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
-                    Err(DatadogError::AuthError(error_text))
-                }
-                StatusCode::TOO_MANY_REQUESTS => Err(DatadogError::RateLimitError),
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(DatadogError::AuthError {
+                    status: Some(status.as_u16()),
+                    message: error_text,
+                }),
+                StatusCode::TOO_MANY_REQUESTS => Err(DatadogError::RateLimitError { reset_after }),
                 StatusCode::REQUEST_TIMEOUT => Err(DatadogError::TimeoutError),
-                _ => Err(DatadogError::ApiError(format!(
-                    "HTTP {}: {}",
-                    status, error_text
-                ))),
+                _ => Err(DatadogError::ApiError {
+                    status: status.as_u16(),
+                    body: error_text,
+                }),
             }
         }
     }
+}
 
-    // ============= Metrics API =============
-
-    pub async fn query_metrics(&self, query: &str, from: i64, to: i64) -> Result<MetricsResponse> {
-        let params = vec![
-            ("query", query.to_string()),
-            ("from", from.to_string()),
-            ("to", to.to_string()),
-        ];
-
-        self.request(
-            reqwest::Method::GET,
-            "/api/v1/query",
-            Some(params),
-            None::<()>,
-        )
-        .await
-    }
-
-    // ============= Logs API =============
-
-    pub async fn search_logs(
-        &self,
-        query: &str,
-        from: &str,
-        to: &str,
-        limit: Option<i32>,
-    ) -> Result<LogsResponse> {
-        let body = serde_json::json!({
-            "filter": {
-                "query": query,
-                "from": from,
-                "to": to
-            },
-            "page": {
-                "limit": limit.unwrap_or(10)
-            },
-            "sort": "timestamp"
-        });
-
-        self.request(
-            reqwest::Method::POST,
-            "/api/v2/logs/events/search",
-            None,
-            Some(body),
-        )
-        .await
+impl DatadogApi for DatadogClient {
+    fn send_json<'a>(&'a self, request: ApiRequest<'a>) -> BoxFuture<'a, Result<Value>> {
+        Box::pin(self.send(
+            request.method,
+            request.endpoint,
+            Some(request.query),
+            request.body,
+            request.retry_safe,
+        ))
     }
 
-    // ============= Monitors API =============
-
-    pub async fn list_monitors(
-        &self,
-        tags: Option<String>,
-        monitor_tags: Option<String>,
-        page: Option<i32>,
-        page_size: Option<i32>,
-    ) -> Result<Vec<Monitor>> {
-        let mut params = vec![];
-
-        if let Some(t) = tags {
-            params.push(("tags", t));
-        }
-        if let Some(mt) = monitor_tags {
-            params.push(("monitor_tags", mt));
-        }
-        if let Some(p) = page {
-            params.push(("page", p.to_string()));
-        }
-        if let Some(ps) = page_size {
-            params.push(("page_size", ps.to_string()));
-        }
-
-        self.request(
-            reqwest::Method::GET,
-            "/api/v1/monitor",
-            if params.is_empty() {
-                None
-            } else {
-                Some(params)
-            },
-            None::<()>,
-        )
-        .await
+    fn site(&self) -> &str {
+        &self.site
     }
 
-    pub async fn get_monitor(&self, monitor_id: i64) -> Result<Monitor> {
-        let endpoint = format!("/api/v1/monitor/{}", monitor_id);
-
-        self.request(reqwest::Method::GET, &endpoint, None, None::<()>)
-            .await
+    fn base_url(&self) -> &str {
+        &self.base_url
     }
 
-    // ============= Events API =============
-
-    pub async fn query_events(
-        &self,
-        start: i64,
-        end: i64,
-        priority: Option<String>,
-        sources: Option<String>,
-        tags: Option<String>,
-    ) -> Result<EventsResponse> {
-        let mut params = vec![("start", start.to_string()), ("end", end.to_string())];
-
-        if let Some(p) = priority {
-            params.push(("priority", p));
-        }
-        if let Some(s) = sources {
-            params.push(("sources", s));
-        }
-        if let Some(t) = tags {
-            params.push(("tags", t));
-        }
-
-        self.request(
-            reqwest::Method::GET,
-            "/api/v1/events",
-            Some(params),
-            None::<()>,
-        )
-        .await
+    fn writes_allowed(&self) -> bool {
+        self.allow_writes
     }
 
-    // ============= Infrastructure/Hosts API =============
-
-    pub async fn list_hosts(
-        &self,
-        filter: Option<String>,
-        from: Option<i64>,
-        sort_field: Option<String>,
-        sort_dir: Option<String>,
-        start: Option<i32>,
-        count: Option<i32>,
-    ) -> Result<HostsResponse> {
-        let mut params = vec![];
-
-        if let Some(f) = filter {
-            params.push(("filter", f));
-        }
-        if let Some(f) = from {
-            params.push(("from", f.to_string()));
-        }
-        if let Some(sf) = sort_field {
-            params.push(("sort_field", sf));
-        }
-        if let Some(sd) = sort_dir {
-            params.push(("sort_dir", sd));
-        }
-        if let Some(s) = start {
-            params.push(("start", s.to_string()));
-        }
-        if let Some(c) = count {
-            params.push(("count", c.to_string()));
-        }
-
-        self.request(
-            reqwest::Method::GET,
-            "/api/v1/hosts",
-            if params.is_empty() {
-                None
-            } else {
-                Some(params)
-            },
-            None::<()>,
-        )
-        .await
+    fn get_tag_filter(&self) -> Option<&str> {
+        self.tag_filter.as_deref()
     }
 
-    // ============= Dashboard API Methods =============
-
-    /// List all dashboards
-    pub async fn list_dashboards(&self) -> Result<DashboardsResponse> {
-        self.request(
-            reqwest::Method::GET,
-            "/api/v1/dashboard",
-            None::<Vec<(&str, String)>>,
-            None::<()>,
-        )
-        .await
+    fn get_tag_filter_for(&self, product: &str) -> Option<&str> {
+        self.product_tag_filters
+            .get(product)
+            .map(String::as_str)
+            .or_else(|| self.get_tag_filter())
     }
 
-    /// Get a specific dashboard by ID
-    pub async fn get_dashboard(&self, dashboard_id: &str) -> Result<Dashboard> {
-        let url = format!("/api/v1/dashboard/{}", dashboard_id);
-        self.request(
-            reqwest::Method::GET,
-            &url,
-            None::<Vec<(&str, String)>>,
-            None::<()>,
-        )
-        .await
+    fn max_concurrency(&self) -> usize {
+        self.max_concurrency
     }
 
-    // ============= APM Spans API Methods =============
-
-    /// List spans using the GET endpoint
-    pub async fn list_spans(
-        &self,
-        query: &str,
-        from: &str,
-        to: &str,
-        limit: Option<i32>,
-        cursor: Option<String>,
-        sort: Option<String>,
-    ) -> Result<serde_json::Value> {
-        let mut params = vec![
-            ("filter[query]", query.to_string()),
-            ("filter[from]", from.to_string()),
-            ("filter[to]", to.to_string()),
-            ("page[limit]", limit.unwrap_or(10).to_string()),
-        ];
-
-        // Add optional parameters
-        if let Some(cursor_val) = cursor {
-            params.push(("page[cursor]", cursor_val));
-        }
-        if let Some(sort_val) = sort {
-            params.push(("sort", sort_val));
-        }
-
-        self.request(
-            reqwest::Method::GET,
-            "/api/v2/spans/events",
-            Some(params),
-            None::<()>,
-        )
-        .await
-    }
-
-    // ============= Service Catalog API Methods =============
-
-    /// Get service catalog with proper pagination
-    pub async fn get_service_catalog(
-        &self,
-        page_size: Option<i32>,
-        page_number: Option<i32>,
-        filter_env: Option<String>,
-    ) -> Result<ServicesResponse> {
-        let mut params = vec![];
-
-        // Use Datadog's pagination format for v2 API
-        if let Some(size) = page_size {
-            params.push(("page[size]", size.to_string()));
-        }
-
-        if let Some(number) = page_number {
-            params.push(("page[number]", number.to_string()));
-        }
-
-        if let Some(env) = filter_env {
-            params.push(("filter[env]", env));
-        }
-
-        self.request(
-            reqwest::Method::GET,
-            "/api/v2/services/definitions",
-            if params.is_empty() {
-                None
-            } else {
-                Some(params)
-            },
-            None::<()>,
-        )
-        .await
-    }
-
-    // ============= Logs Analytics API Methods =============
-
-    /// Aggregate log events into buckets and compute metrics
-    pub async fn aggregate_logs(
-        &self,
-        query: &str,
-        from: &str,
-        to: &str,
-        compute: Option<Vec<LogsCompute>>,
-        group_by: Option<Vec<LogsGroupBy>>,
-        timezone: Option<String>,
-    ) -> Result<serde_json::Value> {
-        let mut body = serde_json::json!({
-            "filter": {
-                "query": query,
-                "from": from,
-                "to": to
-            }
-        });
-
-        if let Some(comp) = compute {
-            body["compute"] = serde_json::to_value(comp)?;
-        }
-
-        if let Some(gb) = group_by {
-            body["group_by"] = serde_json::to_value(gb)?;
-        }
-
-        if let Some(tz) = timezone {
-            body["options"] = serde_json::json!({"timezone": tz});
-        }
-
-        // Debug: log request body
-        log::debug!(
-            "Logs aggregate request body: {}",
-            serde_json::to_string_pretty(&body).unwrap_or_default()
-        );
-
-        self.request(
-            reqwest::Method::POST,
-            "/api/v2/logs/analytics/aggregate",
-            None,
-            Some(body),
-        )
-        .await
-    }
-
-    // ============= RUM API Methods =============
-
-    /// Search RUM events
-    pub async fn search_rum_events(
-        &self,
-        query: &str,
-        from: &str,
-        to: &str,
-        limit: Option<i32>,
-        cursor: Option<String>,
-        sort: Option<String>,
-    ) -> Result<RumEventsResponse> {
-        let mut body = serde_json::json!({
-            "filter": {
-                "query": query,
-                "from": from,
-                "to": to
-            },
-            "page": {
-                "limit": limit.unwrap_or(10)
-            }
-        });
-
-        if let Some(s) = sort {
-            body["sort"] = serde_json::json!(s);
-        }
-
-        if let Some(c) = cursor {
-            body["page"]["cursor"] = serde_json::json!(c);
-        }
-
-        self.request(
-            reqwest::Method::POST,
-            "/api/v2/rum/events/search",
-            None,
-            Some(body),
-        )
-        .await
+    fn app_key_last4(&self) -> &str {
+        let start = self
+            .app_key
+            .char_indices()
+            .rev()
+            .nth(3)
+            .map_or(0, |(i, _)| i);
+        &self.app_key[start..]
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::datadog::models::DashboardDefinition;
+
+    /// The typed endpoints are defined on `dyn DatadogApi`
+    fn endpoints(client: &DatadogClient) -> &dyn DatadogApi {
+        client
+    }
 
     #[tokio::test]
     async fn test_client_new_with_default_site() {
@@ -506,6 +597,28 @@ mod tests {
         assert_eq!(client.base_url, "https://api.datadoghq.eu");
     }
 
+    #[test]
+    fn test_with_ca_bundle_rejects_invalid_files() {
+        let client = || DatadogClient::new("key".to_string(), "app".to_string(), None).unwrap();
+
+        let Err(error) = client().with_ca_bundle("/nonexistent/ca.pem") else {
+            panic!("missing CA bundle accepted");
+        };
+        assert!(
+            error
+                .to_string()
+                .contains("Invalid DD_CA_BUNDLE '/nonexistent/ca.pem'")
+        );
+
+        let path = std::env::temp_dir().join("mcp-datadog-test-ca.pem");
+        std::fs::write(&path, "not a certificate").unwrap();
+        let Err(error) = client().with_ca_bundle(path.to_str().unwrap()) else {
+            panic!("CA bundle without certificates accepted");
+        };
+        assert!(error.to_string().contains("no PEM certificates found"));
+        std::fs::remove_file(path).unwrap();
+    }
+
     #[test]
     fn test_client_regional_urls() {
         let regions = vec![
@@ -540,6 +653,38 @@ mod tests {
         assert_eq!(client.get_tag_filter(), Some("env:,service:"));
     }
 
+    #[test]
+    fn test_product_tag_filter_override() {
+        let client = DatadogClient::with_tag_filter(
+            "key".to_string(),
+            "app".to_string(),
+            None,
+            Some("env:,service:".to_string()),
+        )
+        .unwrap()
+        .with_product_tag_filter("hosts", "*".to_string());
+
+        assert_eq!(client.get_tag_filter_for("hosts"), Some("*"));
+        assert_eq!(client.get_tag_filter_for("logs"), Some("env:,service:"));
+    }
+
+    #[test]
+    fn test_checked_tag_filter() {
+        assert_eq!(
+            checked_tag_filter("DD_TAG_FILTER_LOGS", Some("env:,!kube_".to_string())).unwrap(),
+            Some("env:,!kube_".to_string())
+        );
+        assert_eq!(checked_tag_filter("DD_TAG_FILTER", None).unwrap(), None);
+
+        let error =
+            checked_tag_filter("DD_TAG_FILTER_HOSTS", Some("re:(env".to_string())).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("Invalid input: DD_TAG_FILTER_HOSTS: Invalid tag filter regex")
+        );
+    }
+
     #[test]
     fn test_no_tag_filter() {
         let client =
@@ -549,6 +694,20 @@ mod tests {
         assert_eq!(client.get_tag_filter(), None);
     }
 
+    #[test]
+    fn test_writes_disabled_by_default() {
+        let client =
+            DatadogClient::with_tag_filter("key".to_string(), "app".to_string(), None, None)
+                .unwrap();
+        assert!(matches!(
+            client.ensure_writes_allowed(),
+            Err(DatadogError::WritesDisabled)
+        ));
+
+        let client = client.with_writes(true);
+        assert!(client.ensure_writes_allowed().is_ok());
+    }
+
     #[tokio::test]
     async fn test_handle_response_success() {
         use wiremock::matchers::{method, path};
@@ -575,7 +734,7 @@ mod tests {
         }
 
         let result: Result<TestResponse> = client
-            .request(reqwest::Method::GET, "/api/v1/test", None, None::<()>)
+            .send(reqwest::Method::GET, "/api/v1/test", None, None::<()>, true)
             .await;
 
         assert!(result.is_ok());
@@ -601,13 +760,14 @@ mod tests {
         client.base_url = mock_server.uri();
 
         let result: Result<serde_json::Value> = client
-            .request(reqwest::Method::GET, "/api/v1/test", None, None::<()>)
+            .send(reqwest::Method::GET, "/api/v1/test", None, None::<()>, true)
             .await;
 
         assert!(result.is_err());
         match result.unwrap_err() {
-            DatadogError::AuthError(msg) => {
-                assert!(msg.contains("Unauthorized"));
+            DatadogError::AuthError { status, message } => {
+                assert_eq!(status, Some(401));
+                assert!(message.contains("Unauthorized"));
             }
             _ => panic!("Expected AuthError"),
         }
@@ -630,13 +790,14 @@ mod tests {
         client.base_url = mock_server.uri();
 
         let result: Result<serde_json::Value> = client
-            .request(reqwest::Method::GET, "/api/v1/test", None, None::<()>)
+            .send(reqwest::Method::GET, "/api/v1/test", None, None::<()>, true)
             .await;
 
         assert!(result.is_err());
         match result.unwrap_err() {
-            DatadogError::AuthError(msg) => {
-                assert!(msg.contains("Forbidden"));
+            DatadogError::AuthError { status, message } => {
+                assert_eq!(status, Some(403));
+                assert!(message.contains("Forbidden"));
             }
             _ => panic!("Expected AuthError"),
         }
@@ -659,16 +820,209 @@ mod tests {
         client.base_url = mock_server.uri();
 
         let result: Result<serde_json::Value> = client
-            .request(reqwest::Method::GET, "/api/v1/test", None, None::<()>)
+            .send(reqwest::Method::GET, "/api/v1/test", None, None::<()>, true)
             .await;
 
         assert!(result.is_err());
         match result.unwrap_err() {
-            DatadogError::RateLimitError => {}
+            DatadogError::RateLimitError { .. } => {}
             _ => panic!("Expected RateLimitError"),
         }
     }
 
+    #[tokio::test]
+    async fn test_rate_limit_retry_after() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/test"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/test"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"ok": true})))
+            .mount(&mock_server)
+            .await;
+
+        let mut client = DatadogClient::new("key".to_string(), "app".to_string(), None).unwrap();
+        client.base_url = mock_server.uri();
+
+        let started = std::time::Instant::now();
+        let result: serde_json::Value = client
+            .send(reqwest::Method::GET, "/api/v1/test", None, None::<()>, true)
+            .await
+            .unwrap();
+        assert_eq!(result["ok"], true);
+        assert!(started.elapsed() >= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_client_error_not_retried() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        for status in [400, 401, 403, 404, 422] {
+            let mock_server = MockServer::start().await;
+
+            Mock::given(method("GET"))
+                .and(path("/api/v1/test"))
+                .respond_with(ResponseTemplate::new(status).set_body_string("rejected"))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+
+            let mut client =
+                DatadogClient::new("key".to_string(), "app".to_string(), None).unwrap();
+            client.base_url = mock_server.uri();
+
+            let started = std::time::Instant::now();
+            let result: Result<serde_json::Value> = client
+                .send(reqwest::Method::GET, "/api/v1/test", None, None::<()>, true)
+                .await;
+            assert!(result.is_err_and(|e| !e.is_retryable()));
+            assert!(started.elapsed() < Duration::from_secs(1));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_non_idempotent_write_sent_once() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        // A timed-out POST may still have created the event, so it must not be resent
+        Mock::given(method("POST"))
+            .and(path("/api/v1/events"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(2)))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/dashboard"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut client = DatadogClient::new("key".to_string(), "app".to_string(), None)
+            .unwrap()
+            .with_writes(true)
+            .with_timeouts(Duration::from_millis(200), Duration::from_secs(1))
+            .unwrap();
+        client.base_url = mock_server.uri();
+
+        let error = endpoints(&client)
+            .create_event(&serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, DatadogError::TimeoutError));
+
+        let definition: DashboardDefinition = serde_json::from_value(
+            serde_json::json!({"title": "t", "layout_type": "ordered", "widgets": []}),
+        )
+        .unwrap();
+        let error = endpoints(&client)
+            .create_dashboard(&definition)
+            .await
+            .unwrap_err();
+        assert_eq!(error.http_status(), Some(503));
+    }
+
+    #[tokio::test]
+    async fn test_submit_logs_uses_intake_host() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let api = MockServer::start().await;
+        let intake = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v2/logs"))
+            .respond_with(ResponseTemplate::new(202).set_body_string("{}"))
+            .expect(1)
+            .mount(&intake)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v2/series"))
+            .respond_with(ResponseTemplate::new(202).set_body_string(r#"{"errors":[]}"#))
+            .expect(1)
+            .mount(&api)
+            .await;
+
+        let client = DatadogClient::new("key".to_string(), "app".to_string(), None).unwrap();
+        assert_eq!(client.intake_url, "https://http-intake.logs.datadoghq.com");
+        let mut client = client.with_api_url(&api.uri());
+        client.intake_url = intake.uri();
+
+        // Read-only mode doesn't stop self-reporting
+        endpoints(&client)
+            .submit_logs(&serde_json::json!([{"message": "hi"}]))
+            .await
+            .unwrap();
+        endpoints(&client)
+            .submit_series(&serde_json::json!({"series": []}))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_validate() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/validate"))
+            .respond_with(ResponseTemplate::new(403).set_body_string(r#"{"errors":["Forbidden"]}"#))
+            .mount(&mock_server)
+            .await;
+
+        let mut client =
+            DatadogClient::new("key".to_string(), "app_key_1234".to_string(), None).unwrap();
+        client.base_url = mock_server.uri();
+
+        let error = endpoints(&client).validate().await.unwrap_err();
+        assert!(matches!(error, DatadogError::AuthError { .. }));
+        assert_eq!(client.app_key_last4(), "1234");
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_fast_fails() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/test"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("down"))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let mut client = DatadogClient::new("key".to_string(), "app".to_string(), None)
+            .unwrap()
+            .with_circuit_breaker(2, Duration::from_secs(60));
+        client.base_url = mock_server.uri();
+
+        // The second failure trips the circuit, which ends the retry loop early
+        for _ in 0..2 {
+            let result: Result<serde_json::Value> = client
+                .send(reqwest::Method::GET, "/api/v1/test", None, None::<()>, true)
+                .await;
+            assert!(matches!(
+                result,
+                Err(DatadogError::ServiceUnavailable { .. })
+            ));
+        }
+    }
+
     #[tokio::test]
     async fn test_handle_response_timeout() {
         use wiremock::matchers::{method, path};
@@ -686,7 +1040,7 @@ mod tests {
         client.base_url = mock_server.uri();
 
         let result: Result<serde_json::Value> = client
-            .request(reqwest::Method::GET, "/api/v1/test", None, None::<()>)
+            .send(reqwest::Method::GET, "/api/v1/test", None, None::<()>, true)
             .await;
 
         assert!(result.is_err());
@@ -696,6 +1050,41 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_call_timeout() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/slow"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({}))
+                    .set_delay(Duration::from_secs(3)),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let mut client = DatadogClient::new("key".to_string(), "app".to_string(), None).unwrap();
+        client.base_url = mock_server.uri();
+
+        let result: Result<serde_json::Value> = DatadogClient::with_call_timeout(
+            Some(Duration::from_secs(1)),
+            client.send(reqwest::Method::GET, "/api/v1/slow", None, None::<()>, true),
+        )
+        .await;
+        assert!(matches!(result, Err(DatadogError::TimeoutError)));
+
+        let result: Result<serde_json::Value> = DatadogClient::with_call_timeout(
+            None,
+            client.send(reqwest::Method::GET, "/api/v1/slow", None, None::<()>, true),
+        )
+        .await;
+        assert!(result.is_ok());
+    }
+
     #[tokio::test]
     async fn test_handle_response_server_error() {
         use wiremock::matchers::{method, path};
@@ -713,14 +1102,14 @@ mod tests {
         client.base_url = mock_server.uri();
 
         let result: Result<serde_json::Value> = client
-            .request(reqwest::Method::GET, "/api/v1/test", None, None::<()>)
+            .send(reqwest::Method::GET, "/api/v1/test", None, None::<()>, true)
             .await;
 
         assert!(result.is_err());
         match result.unwrap_err() {
-            DatadogError::ApiError(msg) => {
-                assert!(msg.contains("HTTP 500"));
-                assert!(msg.contains("Internal server error"));
+            DatadogError::ApiError { status, body } => {
+                assert_eq!(status, 500);
+                assert!(body.contains("Internal server error"));
             }
             _ => panic!("Expected ApiError"),
         }
@@ -754,7 +1143,7 @@ mod tests {
         client.base_url = mock_server.uri();
 
         let result: Result<serde_json::Value> = client
-            .request(reqwest::Method::GET, "/api/v1/test", None, None::<()>)
+            .send(reqwest::Method::GET, "/api/v1/test", None, None::<()>, true)
             .await;
 
         assert!(result.is_ok());
@@ -785,7 +1174,7 @@ mod tests {
         client.base_url = mock_server.uri();
 
         let result: Result<serde_json::Value> = client
-            .request(reqwest::Method::GET, "/api/v1/test", None, None::<()>)
+            .send(reqwest::Method::GET, "/api/v1/test", None, None::<()>, true)
             .await;
 
         assert!(result.is_err());
@@ -816,10 +1205,23 @@ mod tests {
         client.base_url = mock_server.uri();
 
         let result: Result<serde_json::Value> = client
-            .request(reqwest::Method::GET, "/api/v1/test", None, None::<()>)
+            .send(reqwest::Method::GET, "/api/v1/test", None, None::<()>, true)
             .await;
 
         assert!(result.is_ok());
         assert_eq!(call_count.load(Ordering::SeqCst), 1);
     }
+
+    #[test]
+    fn test_parse_headers() {
+        let headers =
+            DatadogClient::parse_headers("DD-ORG-ID=12345, X-Preview-Feature = on ,").unwrap();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["dd-org-id"], "12345");
+        assert_eq!(headers["x-preview-feature"], "on");
+
+        assert!(DatadogClient::parse_headers("").unwrap().is_empty());
+        assert!(DatadogClient::parse_headers("missing-value").is_err());
+        assert!(DatadogClient::parse_headers("bad header=1").is_err());
+    }
 }
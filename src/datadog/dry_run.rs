@@ -0,0 +1,130 @@
+use futures::future::BoxFuture;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use super::api::{ApiRequest, DatadogApi};
+use crate::error::{DatadogError, Result};
+
+/// Backend of a `dry_run: true` write tool call: reads go through, and the first write is
+/// returned unsent as `DatadogError::DryRun`. None of the write tools has a Datadog
+/// validation endpoint, so only the tool's own argument checks have run by then
+pub struct DryRun {
+    client: Arc<dyn DatadogApi>,
+}
+
+impl DryRun {
+    pub fn new(client: Arc<dyn DatadogApi>) -> Self {
+        Self { client }
+    }
+
+    /// The tool result of a dry run: the stopped request in place of the write's outcome
+    pub fn outcome(result: Result<Value>) -> Result<Value> {
+        match result {
+            Err(DatadogError::DryRun(request)) => Ok(json!({
+                "data": {
+                    "dry_run": true,
+                    "request": request,
+                },
+                "meta": {
+                    "note": "Nothing was sent to Datadog; call again without dry_run to apply this request"
+                }
+            })),
+            result => result,
+        }
+    }
+}
+
+impl DatadogApi for DryRun {
+    fn send_json<'a>(&'a self, request: ApiRequest<'a>) -> BoxFuture<'a, Result<Value>> {
+        Box::pin(async move {
+            if !request.mutating {
+                return self.client.send_json(request).await;
+            }
+
+            let query: serde_json::Map<String, Value> = request
+                .query
+                .iter()
+                .map(|(key, value)| (key.to_string(), json!(value)))
+                .collect();
+            Err(DatadogError::DryRun(json!({
+                "method": request.method.as_str(),
+                "endpoint": request.endpoint,
+                "query": query,
+                "body": request.body,
+            })))
+        })
+    }
+
+    fn site(&self) -> &str {
+        self.client.site()
+    }
+
+    fn base_url(&self) -> &str {
+        self.client.base_url()
+    }
+
+    fn writes_allowed(&self) -> bool {
+        self.client.writes_allowed()
+    }
+
+    fn get_tag_filter(&self) -> Option<&str> {
+        self.client.get_tag_filter()
+    }
+
+    fn get_tag_filter_for(&self, product: &str) -> Option<&str> {
+        self.client.get_tag_filter_for(product)
+    }
+
+    fn max_concurrency(&self) -> usize {
+        self.client.max_concurrency()
+    }
+
+    fn app_key_last4(&self) -> &str {
+        self.client.app_key_last4()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn write(method: reqwest::Method, endpoint: &str) -> ApiRequest<'_> {
+        ApiRequest {
+            method,
+            endpoint,
+            query: Vec::new(),
+            body: Some(json!({"title": "Deploy"})),
+            retry_safe: false,
+            mutating: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_is_not_sent() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/events"))
+            .respond_with(ResponseTemplate::new(202).set_body_json(json!({"status": "ok"})))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let client = DatadogClient::new("key".to_string(), "app".to_string(), None)
+            .unwrap()
+            .with_api_url(&mock_server.uri());
+        let dry_run = DryRun::new(Arc::new(client));
+
+        let result = DryRun::outcome(
+            dry_run
+                .send_json(write(reqwest::Method::POST, "/api/v1/events"))
+                .await,
+        )
+        .unwrap();
+        assert_eq!(result["data"]["dry_run"], true);
+        assert_eq!(result["data"]["request"]["endpoint"], "/api/v1/events");
+        assert_eq!(result["data"]["request"]["body"]["title"], "Deploy");
+    }
+}
@@ -0,0 +1,1176 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use super::api::{ApiRequest, DatadogApi};
+use super::client::LOGS_INTAKE_ENDPOINT;
+use super::models::*;
+use super::retry;
+use crate::error::{DatadogError, Result};
+use crate::redact::redact;
+use crate::utils::encode_segment;
+
+/// Typed Datadog endpoints, available on any [`DatadogApi`] backend
+impl dyn DatadogApi + '_ {
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        endpoint: &str,
+        query: Option<Vec<(&str, String)>>,
+        body: Option<impl Serialize>,
+    ) -> Result<T> {
+        self.send(method, endpoint, query, body, true, false).await
+    }
+
+    /// Mutating request: requires DD_MCP_MODE=read_write and is only retried when resending is
+    /// harmless, so a timeout after the server applied the change can't create a duplicate
+    async fn write_request<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        endpoint: &str,
+        body: Option<impl Serialize>,
+        idempotent: bool,
+    ) -> Result<T> {
+        self.write_request_with_query(method, endpoint, None, body, idempotent)
+            .await
+    }
+
+    /// `write_request` with query parameters
+    async fn write_request_with_query<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        endpoint: &str,
+        query: Option<Vec<(&str, String)>>,
+        body: Option<impl Serialize>,
+        idempotent: bool,
+    ) -> Result<T> {
+        self.ensure_writes_allowed()?;
+
+        let retry_safe = retry::is_retry_safe(&method, idempotent);
+        self.send(method, endpoint, query, body, retry_safe, true)
+            .await
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        endpoint: &str,
+        query: Option<Vec<(&str, String)>>,
+        body: Option<impl Serialize>,
+        retry_safe: bool,
+        mutating: bool,
+    ) -> Result<T> {
+        let request = ApiRequest {
+            method,
+            endpoint,
+            query: query.unwrap_or_default(),
+            body: body.map(serde_json::to_value).transpose()?,
+            retry_safe,
+            mutating,
+        };
+        let response = self.send_json(request).await?;
+        Ok(serde_json::from_value(response)?)
+    }
+
+    // ============= Authentication API =============
+
+    /// Check the API key; a rejected key is an `AuthError`
+    pub async fn validate(&self) -> Result<ValidateResponse> {
+        let response: ValidateResponse = self
+            .request(
+                reqwest::Method::GET,
+                "/api/v1/validate",
+                None::<Vec<(&str, String)>>,
+                None::<()>,
+            )
+            .await?;
+
+        if response.valid {
+            Ok(response)
+        } else {
+            Err(DatadogError::AuthError {
+                status: None,
+                message: format!("API key rejected by {}", self.site()),
+            })
+        }
+    }
+
+    /// Application keys of the user owning DD_APP_KEY, with their scopes
+    pub async fn list_current_user_application_keys(&self) -> Result<ApplicationKeysResponse> {
+        self.request(
+            reqwest::Method::GET,
+            "/api/v2/current_user/application_keys",
+            Some(vec![("page[size]", "100".to_string())]),
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Organization API keys, newest first; `filter` matches key names
+    pub async fn list_api_keys(
+        &self,
+        page: usize,
+        page_size: usize,
+        filter: Option<String>,
+    ) -> Result<ApiKeysResponse> {
+        let mut params = vec![
+            ("page[number]", page.to_string()),
+            ("page[size]", page_size.to_string()),
+            ("sort", "-created_at".to_string()),
+            ("include", "created_by".to_string()),
+        ];
+        if let Some(f) = filter {
+            params.push(("filter", f));
+        }
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v2/api_keys",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Application keys of every user in the organization, newest first
+    pub async fn list_application_keys(
+        &self,
+        page: usize,
+        page_size: usize,
+        filter: Option<String>,
+    ) -> Result<ApplicationKeysResponse> {
+        let mut params = vec![
+            ("page[number]", page.to_string()),
+            ("page[size]", page_size.to_string()),
+            ("sort", "-created_at".to_string()),
+            ("include", "owned_by".to_string()),
+        ];
+        if let Some(f) = filter {
+            params.push(("filter", f));
+        }
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v2/application_keys",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    // ============= Organizations API =============
+
+    /// The organization of the keys in use and, for a parent org, its child orgs
+    pub async fn list_orgs(&self) -> Result<OrgsResponse> {
+        self.request(
+            reqwest::Method::GET,
+            "/api/v1/org",
+            None::<Vec<(&str, String)>>,
+            None::<()>,
+        )
+        .await
+    }
+
+    pub async fn get_org(&self, public_id: &str) -> Result<OrgResponse> {
+        let endpoint = format!("/api/v1/org/{}", encode_segment(public_id)?);
+
+        self.request(
+            reqwest::Method::GET,
+            &endpoint,
+            None::<Vec<(&str, String)>>,
+            None::<()>,
+        )
+        .await
+    }
+
+    // ============= Metrics API =============
+
+    pub async fn query_metrics(&self, query: &str, from: i64, to: i64) -> Result<MetricsResponse> {
+        let params = vec![
+            ("query", query.to_string()),
+            ("from", from.to_string()),
+            ("to", to.to_string()),
+        ];
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v1/query",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Search metric names (substring match) reported in the last 24 hours
+    pub async fn search_metrics(&self, query: &str) -> Result<MetricSearchResponse> {
+        let params = vec![("q", format!("metrics:{}", query))];
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v1/search",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    pub async fn get_metric_metadata(&self, metric_name: &str) -> Result<MetricMetadata> {
+        let endpoint = format!("/api/v1/metrics/{}", encode_segment(metric_name)?);
+
+        self.request(reqwest::Method::GET, &endpoint, None, None::<()>)
+            .await
+    }
+
+    /// List every tag (key:value) submitted for a metric
+    pub async fn get_metric_all_tags(&self, metric_name: &str) -> Result<MetricAllTagsResponse> {
+        let endpoint = format!("/api/v2/metrics/{}/all-tags", encode_segment(metric_name)?);
+
+        self.request(reqwest::Method::GET, &endpoint, None, None::<()>)
+            .await
+    }
+
+    /// Tag keys and aggregations actively queried for a metric over the lookback window
+    pub async fn get_metric_active_configurations(
+        &self,
+        metric_name: &str,
+        window_seconds: Option<i64>,
+    ) -> Result<MetricActiveConfigurationsResponse> {
+        let endpoint = format!(
+            "/api/v2/metrics/{}/active-configurations",
+            encode_segment(metric_name)?
+        );
+        let params = window_seconds.map(|w| vec![("window[seconds]", w.to_string())]);
+
+        self.request(reqwest::Method::GET, &endpoint, params, None::<()>)
+            .await
+    }
+
+    /// Submit metric points (v2 series). Not gated by DD_MCP_MODE here, since the server's
+    /// self-report uses it too; `datadog_metrics_submit` is gated by the router as a
+    /// `.mutating()` tool. Never retried, since a resent count would be counted twice.
+    pub async fn submit_series(&self, body: &serde_json::Value) -> Result<serde_json::Value> {
+        self.send(
+            reqwest::Method::POST,
+            "/api/v2/series",
+            None,
+            Some(body),
+            false,
+            true,
+        )
+        .await
+    }
+
+    // ============= Logs API =============
+
+    /// Send log entries to the logs intake; like [`Self::submit_series`], ungated and not retried
+    pub async fn submit_logs(&self, entries: &serde_json::Value) -> Result<serde_json::Value> {
+        self.send(
+            reqwest::Method::POST,
+            LOGS_INTAKE_ENDPOINT,
+            None,
+            Some(entries),
+            false,
+            true,
+        )
+        .await
+    }
+
+    pub async fn search_logs(
+        &self,
+        query: &str,
+        from: &str,
+        to: &str,
+        limit: Option<i32>,
+    ) -> Result<LogsResponse> {
+        let body = serde_json::json!({
+            "filter": {
+                "query": query,
+                "from": from,
+                "to": to
+            },
+            "page": {
+                "limit": limit.unwrap_or(10)
+            },
+            "sort": "timestamp"
+        });
+
+        self.request(
+            reqwest::Method::POST,
+            "/api/v2/logs/events/search",
+            None,
+            Some(body),
+        )
+        .await
+    }
+
+    /// List log indexes with their filters and exclusion filters
+    pub async fn list_logs_indexes(&self) -> Result<LogsIndexesResponse> {
+        self.request(
+            reqwest::Method::GET,
+            "/api/v1/logs/config/indexes",
+            None::<Vec<(&str, String)>>,
+            None::<()>,
+        )
+        .await
+    }
+
+    /// List logs-based metrics with their filters and group-bys
+    pub async fn list_logs_metrics(&self) -> Result<LogsMetricsResponse> {
+        self.request(
+            reqwest::Method::GET,
+            "/api/v2/logs/config/metrics",
+            None::<Vec<(&str, String)>>,
+            None::<()>,
+        )
+        .await
+    }
+
+    pub async fn get_logs_metric(&self, metric_id: &str) -> Result<LogsMetricResponse> {
+        let endpoint = format!("/api/v2/logs/config/metrics/{}", encode_segment(metric_id)?);
+
+        self.request(
+            reqwest::Method::GET,
+            &endpoint,
+            None::<Vec<(&str, String)>>,
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Create a logs-based metric (write operation)
+    pub async fn create_logs_metric(&self, body: &serde_json::Value) -> Result<serde_json::Value> {
+        self.write_request(
+            reqwest::Method::POST,
+            "/api/v2/logs/config/metrics",
+            Some(body),
+            false,
+        )
+        .await
+    }
+
+    // ============= Monitors API =============
+
+    pub async fn list_monitors(
+        &self,
+        tags: Option<String>,
+        monitor_tags: Option<String>,
+        page: Option<i32>,
+        page_size: Option<i32>,
+    ) -> Result<Vec<Monitor>> {
+        let mut params = vec![("with_downtimes", "true".to_string())];
+
+        if let Some(t) = tags {
+            params.push(("tags", t));
+        }
+        if let Some(mt) = monitor_tags {
+            params.push(("monitor_tags", mt));
+        }
+        if let Some(p) = page {
+            params.push(("page", p.to_string()));
+        }
+        if let Some(ps) = page_size {
+            params.push(("page_size", ps.to_string()));
+        }
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v1/monitor",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    pub async fn get_monitor(&self, monitor_id: i64) -> Result<Monitor> {
+        let endpoint = format!("/api/v1/monitor/{}", monitor_id);
+
+        self.request(reqwest::Method::GET, &endpoint, None, None::<()>)
+            .await
+    }
+
+    /// Monitor with the state of every group (`group_states=all`)
+    pub async fn get_monitor_with_group_states(&self, monitor_id: i64) -> Result<Monitor> {
+        let endpoint = format!("/api/v1/monitor/{}", monitor_id);
+        let params = vec![("group_states", "all".to_string())];
+
+        self.request(reqwest::Method::GET, &endpoint, Some(params), None::<()>)
+            .await
+    }
+
+    /// Faceted monitor search with server-side pagination (`page` is 0-based)
+    pub async fn search_monitors(
+        &self,
+        query: Option<String>,
+        page: usize,
+        per_page: usize,
+        sort: Option<String>,
+    ) -> Result<MonitorSearchResponse> {
+        let mut params = vec![
+            ("page", page.to_string()),
+            ("per_page", per_page.to_string()),
+        ];
+
+        if let Some(q) = query {
+            params.push(("query", q));
+        }
+        if let Some(s) = sort {
+            params.push(("sort", s));
+        }
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v1/monitor/search",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    // ============= Events API =============
+
+    pub async fn query_events(
+        &self,
+        start: i64,
+        end: i64,
+        priority: Option<String>,
+        sources: Option<String>,
+        tags: Option<String>,
+    ) -> Result<EventsResponse> {
+        let mut params = vec![("start", start.to_string()), ("end", end.to_string())];
+
+        if let Some(p) = priority {
+            params.push(("priority", p));
+        }
+        if let Some(s) = sources {
+            params.push(("sources", s));
+        }
+        if let Some(t) = tags {
+            params.push(("tags", t));
+        }
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v1/events",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Search events with the v2 query syntax, newest first
+    pub async fn search_events(
+        &self,
+        query: &str,
+        from: &str,
+        to: &str,
+        limit: usize,
+        cursor: Option<String>,
+    ) -> Result<EventsSearchResponse> {
+        let mut body = serde_json::json!({
+            "filter": {
+                "query": query,
+                "from": from,
+                "to": to
+            },
+            "sort": "-timestamp",
+            "page": {
+                "limit": limit
+            }
+        });
+
+        if let Some(c) = cursor {
+            body["page"]["cursor"] = serde_json::json!(c);
+        }
+
+        self.request(
+            reqwest::Method::POST,
+            "/api/v2/events/search",
+            None,
+            Some(body),
+        )
+        .await
+    }
+
+    /// Post an event to the event stream (write operation)
+    pub async fn create_event(&self, body: &serde_json::Value) -> Result<serde_json::Value> {
+        self.write_request(reqwest::Method::POST, "/api/v1/events", Some(body), false)
+            .await
+    }
+
+    // ============= Infrastructure/Hosts API =============
+
+    pub async fn list_hosts(
+        &self,
+        filter: Option<String>,
+        from: Option<i64>,
+        sort_field: Option<String>,
+        sort_dir: Option<String>,
+        start: Option<i32>,
+        count: Option<i32>,
+    ) -> Result<HostsResponse> {
+        let mut params = vec![];
+
+        if let Some(f) = filter {
+            params.push(("filter", f));
+        }
+        if let Some(f) = from {
+            params.push(("from", f.to_string()));
+        }
+        if let Some(sf) = sort_field {
+            params.push(("sort_field", sf));
+        }
+        if let Some(sd) = sort_dir {
+            params.push(("sort_dir", sd));
+        }
+        if let Some(s) = start {
+            params.push(("start", s.to_string()));
+        }
+        if let Some(c) = count {
+            params.push(("count", c.to_string()));
+        }
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v1/hosts",
+            if params.is_empty() {
+                None
+            } else {
+                Some(params)
+            },
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Mute a host (write operation); `end` is a Unix timestamp, None mutes indefinitely
+    pub async fn mute_host(
+        &self,
+        host_name: &str,
+        end: Option<i64>,
+        message: Option<String>,
+        override_existing: bool,
+    ) -> Result<HostMuteResponse> {
+        let mut body = serde_json::json!({ "override": override_existing });
+        if let Some(end) = end {
+            body["end"] = serde_json::json!(end);
+        }
+        if let Some(message) = message {
+            body["message"] = serde_json::json!(message);
+        }
+
+        let endpoint = format!("/api/v1/host/{}/mute", encode_segment(host_name)?);
+
+        // With override, repeating the mute just re-applies the same state
+        self.write_request(
+            reqwest::Method::POST,
+            &endpoint,
+            Some(body),
+            override_existing,
+        )
+        .await
+    }
+
+    /// Unmute a host (write operation)
+    pub async fn unmute_host(&self, host_name: &str) -> Result<HostMuteResponse> {
+        let endpoint = format!("/api/v1/host/{}/unmute", encode_segment(host_name)?);
+
+        // A retried unmute fails on the already-unmuted host, hiding the original success
+        self.write_request(reqwest::Method::POST, &endpoint, None::<()>, false)
+            .await
+    }
+
+    // ============= Host Tags API =============
+
+    /// Tags of a host, from every source or only `source` (e.g. `users`, `chef`)
+    pub async fn get_host_tags(
+        &self,
+        host_name: &str,
+        source: Option<String>,
+    ) -> Result<HostTagsResponse> {
+        let endpoint = format!("/api/v1/tags/hosts/{}", encode_segment(host_name)?);
+        let params = source.map(|s| vec![("source", s)]);
+
+        self.request(reqwest::Method::GET, &endpoint, params, None::<()>)
+            .await
+    }
+
+    /// Add tags to a host (POST) or replace its tags from `source` (PUT) (write operation)
+    pub async fn update_host_tags(
+        &self,
+        host_name: &str,
+        tags: &[String],
+        source: Option<String>,
+        replace: bool,
+    ) -> Result<HostTagsResponse> {
+        let endpoint = format!("/api/v1/tags/hosts/{}", encode_segment(host_name)?);
+        let params = source.map(|s| vec![("source", s)]);
+        let body = serde_json::json!({ "host": host_name, "tags": tags });
+        let method = if replace {
+            reqwest::Method::PUT
+        } else {
+            reqwest::Method::POST
+        };
+
+        // Adding a tag the host already has leaves it unchanged, so both are safe to resend
+        self.write_request_with_query(method, &endpoint, params, Some(body), true)
+            .await
+    }
+
+    // ============= Dashboard API Methods =============
+
+    /// List all dashboards
+    pub async fn list_dashboards(&self) -> Result<DashboardsResponse> {
+        self.request(
+            reqwest::Method::GET,
+            "/api/v1/dashboard",
+            None::<Vec<(&str, String)>>,
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Get a specific dashboard by ID
+    pub async fn get_dashboard(&self, dashboard_id: &str) -> Result<Dashboard> {
+        let url = format!("/api/v1/dashboard/{}", encode_segment(dashboard_id)?);
+        self.request(
+            reqwest::Method::GET,
+            &url,
+            None::<Vec<(&str, String)>>,
+            None::<()>,
+        )
+        .await
+    }
+
+    /// A dashboard exactly as Datadog returns it, for writes that must keep
+    /// fields the typed [`Dashboard`] does not model
+    pub async fn get_dashboard_json(&self, dashboard_id: &str) -> Result<serde_json::Value> {
+        let url = format!("/api/v1/dashboard/{}", encode_segment(dashboard_id)?);
+        self.request(
+            reqwest::Method::GET,
+            &url,
+            None::<Vec<(&str, String)>>,
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Dashboards shared by invite or public URL
+    pub async fn list_shared_dashboards(
+        &self,
+        start: usize,
+        count: usize,
+    ) -> Result<DashboardsResponse> {
+        let params = vec![
+            ("filter[shared]", "true".to_string()),
+            ("start", start.to_string()),
+            ("count", count.to_string()),
+        ];
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v1/dashboard",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Create a dashboard (write operation)
+    pub async fn create_dashboard(&self, definition: &impl Serialize) -> Result<Dashboard> {
+        self.write_request(
+            reqwest::Method::POST,
+            "/api/v1/dashboard",
+            Some(definition),
+            false,
+        )
+        .await
+    }
+
+    /// Replace a dashboard with the given full definition (write operation)
+    pub async fn update_dashboard(
+        &self,
+        dashboard_id: &str,
+        definition: &serde_json::Value,
+    ) -> Result<Dashboard> {
+        let endpoint = format!("/api/v1/dashboard/{}", encode_segment(dashboard_id)?);
+        self.write_request(reqwest::Method::PUT, &endpoint, Some(definition), true)
+            .await
+    }
+
+    // ============= Powerpacks API Methods =============
+
+    /// Reusable widget groups, offset-paginated
+    pub async fn list_powerpacks(&self, offset: usize, limit: usize) -> Result<PowerpacksResponse> {
+        let params = vec![
+            ("page[offset]", offset.to_string()),
+            ("page[limit]", limit.to_string()),
+        ];
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v2/powerpacks",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    // ============= Notebooks API Methods =============
+
+    /// List notebooks without cell contents
+    pub async fn list_notebooks(
+        &self,
+        query: Option<String>,
+        author_handle: Option<String>,
+        start: usize,
+        count: usize,
+    ) -> Result<NotebooksResponse> {
+        let mut params = vec![
+            ("start", start.to_string()),
+            ("count", count.to_string()),
+            ("include_cells", "false".to_string()),
+        ];
+
+        if let Some(q) = query {
+            params.push(("query", q));
+        }
+        if let Some(author) = author_handle {
+            params.push(("author_handle", author));
+        }
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v1/notebooks",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    pub async fn get_notebook(&self, notebook_id: i64) -> Result<NotebookResponse> {
+        let endpoint = format!("/api/v1/notebooks/{}", notebook_id);
+
+        self.request(reqwest::Method::GET, &endpoint, None, None::<()>)
+            .await
+    }
+
+    // ============= SLO API Methods =============
+
+    /// List service level objectives
+    pub async fn list_slos(&self) -> Result<serde_json::Value> {
+        self.request(
+            reqwest::Method::GET,
+            "/api/v1/slo",
+            None::<Vec<(&str, String)>>,
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Get a specific SLO by ID
+    pub async fn get_slo(&self, slo_id: &str) -> Result<serde_json::Value> {
+        let url = format!("/api/v1/slo/{}", encode_segment(slo_id)?);
+        self.request(
+            reqwest::Method::GET,
+            &url,
+            None::<Vec<(&str, String)>>,
+            None::<()>,
+        )
+        .await
+    }
+
+    // ============= APM Spans API Methods =============
+
+    /// List spans using the GET endpoint
+    pub async fn list_spans(
+        &self,
+        query: &str,
+        from: &str,
+        to: &str,
+        limit: Option<i32>,
+        cursor: Option<String>,
+        sort: Option<String>,
+    ) -> Result<SpansResponse> {
+        let mut params = vec![
+            ("filter[query]", query.to_string()),
+            ("filter[from]", from.to_string()),
+            ("filter[to]", to.to_string()),
+            ("page[limit]", limit.unwrap_or(10).to_string()),
+        ];
+
+        // Add optional parameters
+        if let Some(cursor_val) = cursor {
+            params.push(("page[cursor]", cursor_val));
+        }
+        if let Some(sort_val) = sort {
+            params.push(("sort", sort_val));
+        }
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v2/spans/events",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Aggregate spans into buckets and compute metrics
+    pub async fn aggregate_spans(
+        &self,
+        query: &str,
+        from: &str,
+        to: &str,
+        compute: serde_json::Value,
+        group_by: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let body = serde_json::json!({
+            "data": {
+                "type": "aggregate_request",
+                "attributes": {
+                    "filter": {
+                        "query": query,
+                        "from": from,
+                        "to": to
+                    },
+                    "compute": compute,
+                    "group_by": group_by
+                }
+            }
+        });
+
+        self.request(
+            reqwest::Method::POST,
+            "/api/v2/spans/analytics/aggregate",
+            None,
+            Some(body),
+        )
+        .await
+    }
+
+    /// List APM retention filters (which ingested spans are indexed)
+    pub async fn list_apm_retention_filters(&self) -> Result<serde_json::Value> {
+        self.request(
+            reqwest::Method::GET,
+            "/api/v2/apm/config/retention-filters",
+            None::<Vec<(&str, String)>>,
+            None::<()>,
+        )
+        .await
+    }
+
+    // ============= Processes API Methods =============
+
+    /// Live process summaries, filtered by cmdline search and comma-separated tags
+    pub async fn list_processes(
+        &self,
+        search: Option<String>,
+        tags: Option<String>,
+        limit: usize,
+        cursor: Option<String>,
+    ) -> Result<ProcessesResponse> {
+        let mut params = vec![("page[limit]", limit.to_string())];
+
+        if let Some(s) = search {
+            params.push(("search", s));
+        }
+        if let Some(t) = tags {
+            params.push(("tags", t));
+        }
+        if let Some(c) = cursor {
+            params.push(("page[cursor]", c));
+        }
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v2/processes",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    // ============= Service Catalog API Methods =============
+
+    /// Get service catalog with proper pagination
+    pub async fn get_service_catalog(
+        &self,
+        page_size: Option<i32>,
+        page_number: Option<i32>,
+        filter_env: Option<String>,
+    ) -> Result<ServicesResponse> {
+        let mut params = vec![];
+
+        // Use Datadog's pagination format for v2 API
+        if let Some(size) = page_size {
+            params.push(("page[size]", size.to_string()));
+        }
+
+        if let Some(number) = page_number {
+            params.push(("page[number]", number.to_string()));
+        }
+
+        if let Some(env) = filter_env {
+            params.push(("filter[env]", env));
+        }
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v2/services/definitions",
+            if params.is_empty() {
+                None
+            } else {
+                Some(params)
+            },
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Software Catalog entities with their full v3 schema included
+    pub async fn list_catalog_entities(
+        &self,
+        kind: Option<&str>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<CatalogEntitiesResponse> {
+        let mut params = vec![
+            ("page[limit]", limit.to_string()),
+            ("page[offset]", offset.to_string()),
+            ("include", "schema".to_string()),
+        ];
+
+        if let Some(kind) = kind {
+            params.push(("filter[kind]", kind.to_string()));
+        }
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v2/catalog/entity",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    // ============= Logs Analytics API Methods =============
+
+    /// Aggregate log events into buckets and compute metrics
+    pub async fn aggregate_logs(
+        &self,
+        query: &str,
+        from: &str,
+        to: &str,
+        compute: Option<Vec<LogsCompute>>,
+        group_by: Option<Vec<LogsGroupBy>>,
+        timezone: Option<String>,
+    ) -> Result<serde_json::Value> {
+        let mut body = serde_json::json!({
+            "filter": {
+                "query": query,
+                "from": from,
+                "to": to
+            }
+        });
+
+        if let Some(comp) = compute {
+            body["compute"] = serde_json::to_value(comp)?;
+        }
+
+        if let Some(gb) = group_by {
+            body["group_by"] = serde_json::to_value(gb)?;
+        }
+
+        if let Some(tz) = timezone {
+            body["options"] = serde_json::json!({"timezone": tz});
+        }
+
+        // Debug: log request body
+        log::debug!(
+            "Logs aggregate request body: {}",
+            redact(&serde_json::to_string_pretty(&body).unwrap_or_default())
+        );
+
+        self.request(
+            reqwest::Method::POST,
+            "/api/v2/logs/analytics/aggregate",
+            None,
+            Some(body),
+        )
+        .await
+    }
+
+    // ============= Usage API Methods =============
+
+    /// Get hourly usage attribution by tag for a usage type (hours formatted as YYYY-MM-DDTHH)
+    pub async fn get_hourly_usage_attribution(
+        &self,
+        start_hr: &str,
+        end_hr: &str,
+        usage_type: &str,
+        next_record_id: Option<String>,
+    ) -> Result<HourlyUsageAttributionResponse> {
+        let mut params = vec![
+            ("start_hr", start_hr.to_string()),
+            ("end_hr", end_hr.to_string()),
+            ("usage_type", usage_type.to_string()),
+        ];
+
+        if let Some(id) = next_record_id {
+            params.push(("next_record_id", id));
+        }
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v1/usage/hourly-attribution",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Get monthly usage summary across products (months formatted as YYYY-MM)
+    pub async fn get_usage_summary(
+        &self,
+        start_month: &str,
+        end_month: Option<&str>,
+    ) -> Result<UsageSummaryResponse> {
+        let mut params = vec![("start_month", start_month.to_string())];
+
+        if let Some(end) = end_month {
+            params.push(("end_month", end.to_string()));
+        }
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v1/usage/summary",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Get monthly cost per product; `cost_kind` is "estimated_cost" or "historical_cost"
+    pub async fn get_usage_cost(
+        &self,
+        cost_kind: &str,
+        start_month: &str,
+        end_month: Option<&str>,
+    ) -> Result<CostByOrgResponse> {
+        let mut params = vec![
+            ("start_month", start_month.to_string()),
+            ("view", "summary".to_string()),
+        ];
+
+        if let Some(end) = end_month {
+            params.push(("end_month", end.to_string()));
+        }
+
+        let endpoint = format!("/api/v2/usage/{}", encode_segment(cost_kind)?);
+        self.request(reqwest::Method::GET, &endpoint, Some(params), None::<()>)
+            .await
+    }
+
+    // ============= Security Monitoring API Methods =============
+
+    /// List detection rules (one page)
+    pub async fn list_security_rules(
+        &self,
+        page_size: usize,
+        page_number: usize,
+    ) -> Result<SecurityRulesResponse> {
+        let params = vec![
+            ("page[size]", page_size.to_string()),
+            ("page[number]", page_number.to_string()),
+        ];
+
+        self.request(
+            reqwest::Method::GET,
+            "/api/v2/security_monitoring/rules",
+            Some(params),
+            None::<()>,
+        )
+        .await
+    }
+
+    pub async fn get_security_rule(&self, rule_id: &str) -> Result<SecurityRule> {
+        let endpoint = format!(
+            "/api/v2/security_monitoring/rules/{}",
+            encode_segment(rule_id)?
+        );
+
+        self.request(reqwest::Method::GET, &endpoint, None, None::<()>)
+            .await
+    }
+
+    // ============= Error Tracking API Methods =============
+
+    /// Search error tracking issues ordered by occurrence count (timestamps in milliseconds)
+    pub async fn search_error_tracking_issues(
+        &self,
+        query: &str,
+        from_ms: i64,
+        to_ms: i64,
+        track: &str,
+    ) -> Result<ErrorTrackingSearchResponse> {
+        let body = serde_json::json!({
+            "data": {
+                "type": "search_request",
+                "attributes": {
+                    "query": query,
+                    "from": from_ms,
+                    "to": to_ms,
+                    "track": track,
+                    "order_by": "TOTAL_COUNT"
+                }
+            }
+        });
+
+        self.request(
+            reqwest::Method::POST,
+            "/api/v2/error-tracking/issues/search",
+            Some(vec![("include", "issue".to_string())]),
+            Some(body),
+        )
+        .await
+    }
+
+    // ============= RUM API Methods =============
+
+    /// Search RUM events
+    pub async fn search_rum_events(
+        &self,
+        query: &str,
+        from: &str,
+        to: &str,
+        limit: Option<i32>,
+        cursor: Option<String>,
+        sort: Option<String>,
+    ) -> Result<RumEventsResponse> {
+        let mut body = serde_json::json!({
+            "filter": {
+                "query": query,
+                "from": from,
+                "to": to
+            },
+            "page": {
+                "limit": limit.unwrap_or(10)
+            }
+        });
+
+        if let Some(s) = sort {
+            body["sort"] = serde_json::json!(s);
+        }
+
+        if let Some(c) = cursor {
+            body["page"]["cursor"] = serde_json::json!(c);
+        }
+
+        self.request(
+            reqwest::Method::POST,
+            "/api/v2/rum/events/search",
+            None,
+            Some(body),
+        )
+        .await
+    }
+}
@@ -1,5 +1,14 @@
+pub mod api;
+pub mod batch;
+mod circuit;
 pub mod client;
+pub mod dry_run;
+mod endpoints;
 pub mod models;
+pub mod query;
+mod rate_limit;
+pub mod replay;
 mod retry;
 
+pub use api::DatadogApi;
 pub use client::DatadogClient;
@@ -1,6 +1,132 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+// ============= Authentication Models =============
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidateResponse {
+    pub valid: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApplicationKeysResponse {
+    pub data: Vec<ApplicationKey>,
+    /// Key owners, when requested with `include=owned_by`
+    #[serde(default)]
+    pub included: Vec<KeyUser>,
+    pub meta: Option<KeysMeta>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApplicationKey {
+    pub id: String,
+    pub attributes: Option<ApplicationKeyAttributes>,
+    pub relationships: Option<KeyRelationships>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApplicationKeyAttributes {
+    pub name: Option<String>,
+    pub last4: Option<String>,
+    pub created_at: Option<String>,
+    /// None when the key has every permission of its owner
+    pub scopes: Option<Vec<String>>,
+}
+
+/// API keys; the key itself is never deserialized, only its last four characters
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiKeysResponse {
+    pub data: Vec<ApiKey>,
+    /// Key creators, when requested with `include=created_by`
+    #[serde(default)]
+    pub included: Vec<KeyUser>,
+    pub meta: Option<KeysMeta>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: String,
+    pub attributes: Option<ApiKeyAttributes>,
+    pub relationships: Option<KeyRelationships>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiKeyAttributes {
+    pub name: Option<String>,
+    pub last4: Option<String>,
+    pub created_at: Option<String>,
+    pub modified_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeyRelationships {
+    pub created_by: Option<KeyRelationship>,
+    pub owned_by: Option<KeyRelationship>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeyRelationship {
+    pub data: Option<KeyRelationshipData>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeyRelationshipData {
+    pub id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeyUser {
+    pub id: String,
+    pub attributes: Option<KeyUserAttributes>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeyUserAttributes {
+    pub handle: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeysMeta {
+    pub page: Option<KeysPage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeysPage {
+    pub total_filtered_count: Option<i64>,
+}
+
+// ============= Organizations Models =============
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrgsResponse {
+    #[serde(default)]
+    pub orgs: Vec<Org>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrgResponse {
+    pub org: Org,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Org {
+    pub public_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub created: Option<String>,
+    pub subscription: Option<OrgPlan>,
+    pub billing: Option<OrgPlan>,
+    /// SAML, widget sharing and other org-wide settings, only returned for one org
+    pub settings: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrgPlan {
+    #[serde(rename = "type")]
+    pub plan_type: Option<String>,
+}
+
 // ============= Metrics Models =============
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -53,6 +179,72 @@ pub struct Unit {
     pub id: Option<i64>,
 }
 
+// ============= Metric Metadata Models =============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricSearchResponse {
+    pub results: MetricSearchResults,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricSearchResults {
+    #[serde(default)]
+    pub metrics: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricMetadata {
+    pub description: Option<String>,
+    pub integration: Option<String>,
+    pub per_unit: Option<String>,
+    pub short_name: Option<String>,
+    pub statsd_interval: Option<i64>,
+    #[serde(rename = "type")]
+    pub metric_type: Option<String>,
+    pub unit: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricAllTagsResponse {
+    pub data: MetricAllTagsData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricAllTagsData {
+    pub id: Option<String>,
+    pub attributes: Option<MetricAllTagsAttributes>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricAllTagsAttributes {
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricActiveConfigurationsResponse {
+    pub data: MetricActiveConfigurationsData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricActiveConfigurationsData {
+    pub attributes: Option<MetricActiveConfigurationsAttributes>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricActiveConfigurationsAttributes {
+    #[serde(default)]
+    pub active_tags: Vec<String>,
+    #[serde(default)]
+    pub active_aggregations: Vec<MetricActiveAggregation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricActiveAggregation {
+    pub space: Option<String>,
+    pub time: Option<String>,
+}
+
 // ============= Logs Models =============
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -116,6 +308,62 @@ pub struct Monitor {
     pub org_id: Option<i64>,
     pub restricted_roles: Option<Vec<String>>,
     pub matching_downtimes: Option<Vec<serde_json::Value>>,
+    /// Per-group states, present when fetched with `group_states`
+    pub state: Option<MonitorState>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorState {
+    pub groups: Option<HashMap<String, MonitorGroupState>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorGroupState {
+    pub name: Option<String>,
+    pub status: Option<String>,
+    pub last_triggered_ts: Option<i64>,
+    pub last_nodata_ts: Option<i64>,
+    pub last_notified_ts: Option<i64>,
+    pub last_resolved_ts: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorSearchResponse {
+    pub monitors: Vec<MonitorSearchResult>,
+    pub metadata: Option<MonitorSearchMetadata>,
+    /// Facet counts keyed by facet (status, type, tag, muted)
+    pub counts: Option<HashMap<String, Vec<MonitorSearchCount>>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorSearchResult {
+    pub id: i64,
+    pub name: Option<String>,
+    pub status: Option<String>,
+    #[serde(rename = "type")]
+    pub monitor_type: Option<String>,
+    pub query: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub classification: Option<String>,
+    pub priority: Option<i64>,
+    pub last_triggered_ts: Option<i64>,
+    pub scopes: Option<Vec<String>>,
+    pub creator: Option<Creator>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorSearchMetadata {
+    pub page: Option<i64>,
+    pub page_count: Option<i64>,
+    pub per_page: Option<i64>,
+    pub total_count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorSearchCount {
+    /// String facet value, or a boolean for `muted`
+    pub name: serde_json::Value,
+    pub count: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -174,6 +422,39 @@ pub struct Event {
     pub url: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventsSearchResponse {
+    pub data: Option<Vec<EventV2>>,
+    pub meta: Option<EventsSearchMeta>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventV2 {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: Option<String>,
+    pub attributes: Option<EventV2Attributes>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventV2Attributes {
+    pub timestamp: Option<String>,
+    pub message: Option<String>,
+    pub tags: Option<Vec<String>>,
+    /// Event fields (title, priority, status, evt, hostname, ...)
+    pub attributes: Option<HashMap<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventsSearchMeta {
+    pub page: Option<EventsSearchPage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventsSearchPage {
+    pub after: Option<String>,
+}
+
 // ============= Infrastructure/Hosts Models =============
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -197,6 +478,21 @@ pub struct Host {
     pub sources: Option<Vec<String>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostMuteResponse {
+    pub action: Option<String>,
+    pub hostname: Option<String>,
+    pub message: Option<String>,
+    pub end: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostTagsResponse {
+    pub host: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
 // ============= Dashboards Models =============
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -234,6 +530,20 @@ pub struct Dashboard {
     pub tags: Option<Vec<String>>,
 }
 
+/// Body of dashboard create and update requests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardDefinition {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub layout_type: String,
+    pub widgets: Vec<Widget>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_variables: Option<Vec<TemplateVariable>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthorInfo {
     pub name: Option<String>,
@@ -244,16 +554,20 @@ pub struct AuthorInfo {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemplateVariable {
     pub name: String,
-    #[serde(rename = "default")]
+    #[serde(rename = "default", skip_serializing_if = "Option::is_none")]
     pub default_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub prefix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub available_values: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Widget {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<i64>,
     pub definition: WidgetDefinition,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub layout: Option<WidgetLayout>,
 }
 
@@ -261,9 +575,13 @@ pub struct Widget {
 pub struct WidgetDefinition {
     #[serde(rename = "type")]
     pub widget_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub title_size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub title_align: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub requests: Option<Vec<serde_json::Value>>,
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
@@ -277,6 +595,156 @@ pub struct WidgetLayout {
     pub height: i32,
 }
 
+// ============= Powerpacks Models =============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerpacksResponse {
+    pub data: Vec<Powerpack>,
+    pub meta: Option<PowerpacksMeta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerpacksMeta {
+    pub pagination: Option<PowerpacksPagination>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerpacksPagination {
+    pub total: Option<usize>,
+    pub next_offset: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Powerpack {
+    pub id: String,
+    pub attributes: PowerpackAttributes,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerpackAttributes {
+    pub name: String,
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub template_variables: Option<Vec<PowerpackTemplateVariable>>,
+    pub group_widget: Option<PowerpackGroupWidget>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerpackTemplateVariable {
+    pub name: String,
+    pub defaults: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerpackGroupWidget {
+    pub definition: PowerpackGroupDefinition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerpackGroupDefinition {
+    pub title: Option<String>,
+    pub layout_type: Option<String>,
+    #[serde(default)]
+    pub widgets: Vec<Widget>,
+}
+
+// ============= Notebooks Models =============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotebooksResponse {
+    pub data: Vec<Notebook>,
+    pub meta: Option<NotebooksMeta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotebooksMeta {
+    pub page: Option<NotebooksPage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotebooksPage {
+    pub total_count: Option<usize>,
+    pub total_filtered_count: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotebookResponse {
+    pub data: Notebook,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notebook {
+    pub id: i64,
+    pub attributes: NotebookAttributes,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotebookAttributes {
+    pub name: String,
+    pub author: Option<NotebookAuthor>,
+    pub cells: Option<Vec<NotebookCell>>,
+    pub created: Option<String>,
+    pub modified: Option<String>,
+    pub status: Option<String>,
+    pub time: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotebookAuthor {
+    pub handle: Option<String>,
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotebookCell {
+    pub id: Option<String>,
+    pub attributes: Option<NotebookCellAttributes>,
+}
+
+/// Cell definitions vary by type (markdown, timeseries, log_stream, ...), kept as raw JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotebookCellAttributes {
+    pub definition: serde_json::Value,
+}
+
+// ============= Processes Models =============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessesResponse {
+    pub data: Vec<ProcessSummary>,
+    pub meta: Option<ProcessesMeta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessSummary {
+    pub id: Option<String>,
+    pub attributes: Option<ProcessSummaryAttributes>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessSummaryAttributes {
+    pub cmdline: Option<String>,
+    pub host: Option<String>,
+    pub pid: Option<i64>,
+    pub ppid: Option<i64>,
+    pub start: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub timestamp: Option<String>,
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessesMeta {
+    pub page: Option<ProcessesPage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessesPage {
+    pub after: Option<String>,
+    pub size: Option<i64>,
+}
+
 // ============= APM Services Models =============
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -369,6 +837,132 @@ pub struct ServicesLinks {
     pub next: Option<String>,
 }
 
+// ============= Software Catalog (v3) Models =============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogEntitiesResponse {
+    pub data: Vec<CatalogEntity>,
+    pub included: Option<Vec<CatalogIncluded>>,
+    pub links: Option<ServicesLinks>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogEntity {
+    pub id: Option<String>,
+    pub attributes: Option<CatalogEntityAttributes>,
+    pub relationships: Option<CatalogEntityRelationships>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogEntityAttributes {
+    pub api_version: Option<String>,
+    pub kind: Option<String>,
+    pub name: Option<String>,
+    pub namespace: Option<String>,
+    pub display_name: Option<String>,
+    pub owner: Option<String>,
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogEntityRelationships {
+    pub schema: Option<CatalogRelationship>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogRelationship {
+    pub data: Option<CatalogRelationshipData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogRelationshipData {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub data_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogIncluded {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub included_type: Option<String>,
+    pub attributes: Option<CatalogIncludedAttributes>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogIncludedAttributes {
+    pub schema: Option<EntityV3>,
+}
+
+/// A schema v3 entity definition (`apiVersion: v3`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityV3 {
+    pub api_version: Option<String>,
+    pub kind: Option<String>,
+    pub metadata: Option<EntityV3Metadata>,
+    /// Kind-specific; parsed into ServiceSpec, DatastoreSpec, QueueSpec or SystemSpec
+    pub spec: Option<serde_json::Value>,
+    pub integrations: Option<serde_json::Value>,
+    pub datadog: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityV3Metadata {
+    pub name: Option<String>,
+    pub display_name: Option<String>,
+    pub namespace: Option<String>,
+    pub owner: Option<String>,
+    pub additional_owners: Option<Vec<serde_json::Value>>,
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub contacts: Option<Vec<ServiceContact>>,
+    pub links: Option<Vec<ServiceLink>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceSpec {
+    pub lifecycle: Option<String>,
+    pub tier: Option<String>,
+    #[serde(rename = "type")]
+    pub service_type: Option<String>,
+    pub languages: Option<Vec<String>>,
+    pub depends_on: Option<Vec<String>>,
+    pub component_of: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatastoreSpec {
+    pub lifecycle: Option<String>,
+    pub tier: Option<String>,
+    #[serde(rename = "type")]
+    pub datastore_type: Option<String>,
+    pub component_of: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueSpec {
+    pub lifecycle: Option<String>,
+    pub tier: Option<String>,
+    #[serde(rename = "type")]
+    pub queue_type: Option<String>,
+    pub component_of: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemSpec {
+    pub lifecycle: Option<String>,
+    pub tier: Option<String>,
+    pub components: Option<Vec<String>>,
+}
+
 // ============= Logs Analytics Models =============
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -405,6 +999,343 @@ pub struct LogsGroupBySort {
     pub metric: Option<String>,
 }
 
+// ============= Logs Indexes Models =============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogsIndexesResponse {
+    pub indexes: Vec<LogsIndex>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogsIndex {
+    pub name: String,
+    pub filter: Option<LogsIndexFilter>,
+    pub exclusion_filters: Option<Vec<LogsExclusionFilter>>,
+    pub num_retention_days: Option<i64>,
+    pub daily_limit: Option<i64>,
+    pub is_rate_limited: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogsIndexFilter {
+    pub query: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogsExclusionFilter {
+    pub name: String,
+    pub is_enabled: Option<bool>,
+    pub filter: Option<LogsExclusionFilterRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogsExclusionFilterRule {
+    pub query: Option<String>,
+    pub sample_rate: Option<f64>,
+}
+
+// ============= Logs-based Metrics Models =============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogsMetricsResponse {
+    #[serde(default)]
+    pub data: Vec<LogsMetric>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogsMetricResponse {
+    pub data: LogsMetric,
+}
+
+/// A logs-based metric; its ID is the metric name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogsMetric {
+    pub id: String,
+    pub attributes: Option<LogsMetricAttributes>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogsMetricAttributes {
+    pub compute: Option<LogsMetricCompute>,
+    pub filter: Option<LogsIndexFilter>,
+    #[serde(default)]
+    pub group_by: Vec<LogsMetricGroupBy>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogsMetricCompute {
+    /// "count" or "distribution"
+    pub aggregation_type: Option<String>,
+    /// Measure a distribution is computed over (e.g., "@duration")
+    pub path: Option<String>,
+    pub include_percentiles: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogsMetricGroupBy {
+    pub path: String,
+    pub tag_name: Option<String>,
+}
+
+// ============= Usage Models =============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HourlyUsageAttributionResponse {
+    pub usage: Option<Vec<HourlyUsageAttribution>>,
+    pub metadata: Option<UsageAttributionMetadata>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HourlyUsageAttribution {
+    pub hour: Option<String>,
+    pub tags: Option<HashMap<String, Option<Vec<String>>>>,
+    pub total_usage_sum: Option<f64>,
+    pub usage_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageAttributionMetadata {
+    pub pagination: Option<UsageAttributionPagination>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageAttributionPagination {
+    pub next_record_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageSummaryResponse {
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub usage: Option<Vec<UsageSummaryDate>>,
+}
+
+/// One month of usage; Datadog reports several hundred per-product fields, kept as raw values
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageSummaryDate {
+    pub date: Option<String>,
+    #[serde(flatten)]
+    pub fields: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostByOrgResponse {
+    pub data: Option<Vec<CostByOrg>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostByOrg {
+    pub id: Option<String>,
+    pub attributes: Option<CostByOrgAttributes>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostByOrgAttributes {
+    pub date: Option<String>,
+    pub org_name: Option<String>,
+    pub total_cost: Option<f64>,
+    pub charges: Option<Vec<CostCharge>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostCharge {
+    pub product_name: Option<String>,
+    pub charge_type: Option<String>,
+    pub cost: Option<f64>,
+}
+
+// ============= Security Monitoring Models =============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityRulesResponse {
+    pub data: Vec<SecurityRule>,
+    pub meta: Option<SecurityRulesMeta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityRulesMeta {
+    pub page: Option<SecurityRulesPage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityRulesPage {
+    pub total_count: Option<usize>,
+    pub total_filtered_count: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityRule {
+    pub id: String,
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub rule_type: Option<String>,
+    pub is_enabled: Option<bool>,
+    pub is_default: Option<bool>,
+    pub is_deleted: Option<bool>,
+    pub queries: Option<Vec<SecurityRuleQuery>>,
+    pub cases: Option<Vec<SecurityRuleCase>>,
+    pub options: Option<serde_json::Value>,
+    pub message: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub created_at: Option<i64>,
+    pub version: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityRuleQuery {
+    pub name: Option<String>,
+    pub query: Option<String>,
+    pub aggregation: Option<String>,
+    pub group_by_fields: Option<Vec<String>>,
+    pub distinct_fields: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityRuleCase {
+    pub name: Option<String>,
+    pub status: Option<String>,
+    pub condition: Option<String>,
+    pub notifications: Option<Vec<String>>,
+}
+
+// ============= Error Tracking Models =============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorTrackingSearchResponse {
+    pub data: Option<Vec<ErrorTrackingResult>>,
+    pub included: Option<Vec<ErrorTrackingIssue>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorTrackingResult {
+    pub id: String,
+    pub attributes: Option<ErrorTrackingResultAttributes>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorTrackingResultAttributes {
+    pub total_count: Option<i64>,
+    pub impacted_sessions: Option<i64>,
+    pub impacted_users: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorTrackingIssue {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub issue_type: Option<String>,
+    pub attributes: Option<ErrorTrackingIssueAttributes>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorTrackingIssueAttributes {
+    pub error_message: Option<String>,
+    pub error_type: Option<String>,
+    pub file_path: Option<String>,
+    pub function_name: Option<String>,
+    pub first_seen: Option<i64>,
+    pub last_seen: Option<i64>,
+    pub first_seen_version: Option<String>,
+    pub last_seen_version: Option<String>,
+    pub service: Option<String>,
+    pub platform: Option<String>,
+    pub state: Option<String>,
+    pub is_crash: Option<bool>,
+    pub languages: Option<Vec<String>>,
+}
+
+// ============= Spans Models =============
+
+/// IDs Datadog sends either as strings or as numbers
+fn string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        match Option::<serde_json::Value>::deserialize(deserializer)? {
+            Some(serde_json::Value::String(s)) if !s.is_empty() => Some(s),
+            Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        },
+    )
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpansResponse {
+    #[serde(default)]
+    pub data: Vec<SpanEvent>,
+    pub meta: Option<SpansMeta>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpansMeta {
+    pub page: Option<SpansPage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpansPage {
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpanEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub event_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<SpanAttributes>,
+}
+
+/// Attributes of a v2 span event; absent fields stay absent when serialized back, so a
+/// span round-trips to the payload Datadog sent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpanAttributes {
+    #[serde(
+        default,
+        deserialize_with = "string_or_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub span_id: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "string_or_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub parent_id: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "string_or_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub trace_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_timestamp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_timestamp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Span metadata and metrics: `duration` (ns), `error`, `http`, `operation_name`, ...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
 // ============= RUM Models =============
 
 #[derive(Debug, Serialize, Deserialize)]
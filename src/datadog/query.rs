@@ -0,0 +1,517 @@
+use crate::error::DatadogError;
+
+/// Space aggregators accepted before the metric name (percentiles are handled separately)
+const SPACE_AGGREGATORS: &[&str] = &["avg", "sum", "min", "max"];
+
+/// Words commonly written in place of a space aggregator, and the aggregator they mean
+const AGGREGATOR_ALIASES: &[(&str, &str)] = &[
+    ("mean", "avg"),
+    ("average", "avg"),
+    ("total", "sum"),
+    ("minimum", "min"),
+    ("maximum", "max"),
+];
+
+/// Time aggregators accepted as the first .rollup() argument
+const ROLLUP_METHODS: &[&str] = &["avg", "sum", "min", "max", "count"];
+
+/// Functions that may follow a metric scope
+const KNOWN_FUNCTIONS: &[&str] = &["rollup", "as_count", "as_rate", "fill"];
+
+/// What Datadog's metrics API says about a query it cannot parse
+const PARSE_ERROR: &str = "Error parsing query";
+
+/// Findings from the local syntax check
+#[derive(Debug, Default)]
+pub struct QueryReport {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '.'
+}
+
+fn is_percentile(agg: &str) -> bool {
+    agg.strip_prefix('p')
+        .is_some_and(|p| !p.is_empty() && p.parse::<f64>().is_ok())
+}
+
+/// The space aggregator `agg` was probably meant to be
+fn suggest_aggregator(agg: &str) -> Option<&'static str> {
+    let agg = agg.to_ascii_lowercase();
+    SPACE_AGGREGATORS
+        .iter()
+        .find(|known| **known == agg)
+        .copied()
+        .or_else(|| {
+            AGGREGATOR_ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == agg)
+                .map(|(_, known)| *known)
+        })
+}
+
+/// Check that braces and parentheses are balanced and braces are not nested
+fn check_balance(query: &str) -> Option<String> {
+    let mut stack: Vec<(char, usize)> = Vec::new();
+
+    for (pos, c) in query.char_indices() {
+        match c {
+            '{' => {
+                if stack.iter().any(|(open, _)| *open == '{') {
+                    return Some(format!("Nested '{{' at position {}", pos));
+                }
+                stack.push((c, pos));
+            }
+            '(' => stack.push((c, pos)),
+            '}' | ')' => {
+                let expected = if c == '}' { '{' } else { '(' };
+                match stack.pop() {
+                    Some((open, _)) if open == expected => {}
+                    Some((open, open_pos)) => {
+                        return Some(format!(
+                            "'{}' at position {} does not close '{}' opened at position {}",
+                            c, pos, open, open_pos
+                        ));
+                    }
+                    None => return Some(format!("Unexpected '{}' at position {}", c, pos)),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    stack
+        .first()
+        .map(|(open, pos)| format!("Unclosed '{}' opened at position {}", open, pos))
+}
+
+/// Whether each byte offset lies outside a {scope}, so tag values are never linted as syntax
+fn outside_scope(query: &str) -> Vec<bool> {
+    let mut depth = 0;
+    let mut outside = vec![true; query.len()];
+    for (pos, c) in query.char_indices() {
+        if c == '{' {
+            depth += 1;
+        }
+        outside[pos] = depth == 0;
+        if c == '}' {
+            depth -= 1;
+        }
+    }
+    outside
+}
+
+/// Check every `aggregator:metric{scope}` term
+fn check_terms(query: &str, outside: &[bool], report: &mut QueryReport) {
+    let bytes = query.as_bytes();
+
+    for (pos, c) in query.char_indices() {
+        if c != ':' || !outside[pos] {
+            continue;
+        }
+
+        let agg_start = query[..pos]
+            .rfind(|c: char| !c.is_ascii_alphanumeric() && c != '.')
+            .map_or(0, |i| i + 1);
+        let agg = &query[agg_start..pos];
+        if !SPACE_AGGREGATORS.contains(&agg) && !is_percentile(agg) {
+            let hint = match suggest_aggregator(agg) {
+                Some(known) => format!("; did you mean '{}:'?", known),
+                None => " (expected avg, sum, min, max or a percentile like p95)".to_string(),
+            };
+            report.errors.push(format!(
+                "Unknown space aggregator '{}' at position {}{}",
+                agg, agg_start, hint
+            ));
+        }
+
+        let name_end = query[pos + 1..]
+            .find(|c: char| !is_name_char(c))
+            .map_or(query.len(), |i| pos + 1 + i);
+        let metric = &query[pos + 1..name_end];
+        if metric.is_empty() {
+            report
+                .errors
+                .push(format!("Missing metric name after '{}:'", agg));
+            continue;
+        }
+
+        if bytes.get(name_end) != Some(&b'{') {
+            let (metric, hint) = match metric.rsplit_once('.') {
+                Some((name, func)) if bytes.get(name_end) == Some(&b'(') => (
+                    name,
+                    format!(
+                        "; functions like .{}() go after the scope, e.g. {}{{*}}.{}(...)",
+                        func, name, func
+                    ),
+                ),
+                _ => (metric, String::new()),
+            };
+            report.errors.push(format!(
+                "Metric '{}' must be followed by a scope such as {{*}}{}",
+                metric, hint
+            ));
+        }
+    }
+
+    // Metric names without an aggregator prefix fall back to avg
+    for (pos, c) in query.char_indices() {
+        if c != '{' {
+            continue;
+        }
+
+        let before = query[..pos].trim_end();
+        if before.ends_with(" by") || before == "by" || before.ends_with(')') {
+            continue;
+        }
+
+        let term_start = before
+            .rfind(|c: char| !is_name_char(c) && c != ':')
+            .map_or(0, |i| i + 1);
+        let term = &before[term_start..];
+        if term.is_empty() {
+            report
+                .errors
+                .push(format!("Scope at position {} has no metric name", pos));
+        } else if !term.contains(':') {
+            report.warnings.push(format!(
+                "No space aggregator on '{}'; Datadog defaults to avg (prefix with avg:, sum:, min: or max:)",
+                term
+            ));
+        }
+    }
+
+    for (pos, _) in query.match_indices("{}") {
+        report.errors.push(format!(
+            "Empty scope at position {}; use {{*}} for all sources",
+            pos
+        ));
+    }
+}
+
+/// Check `.function(...)` calls after scopes, including rollup arguments
+fn check_functions(query: &str, outside: &[bool], report: &mut QueryReport) {
+    for (pos, _) in query.match_indices('.') {
+        if !outside[pos] {
+            continue;
+        }
+
+        let rest = &query[pos + 1..];
+        let name_len = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+        let name = &rest[..name_len];
+        if name.is_empty() || !rest[name_len..].starts_with('(') {
+            continue;
+        }
+
+        // Dots inside a metric name are only functions when followed by '('
+        if !matches!(query[..pos].chars().last(), Some('}') | Some(')')) {
+            continue;
+        }
+
+        if !KNOWN_FUNCTIONS.contains(&name) {
+            report.warnings.push(format!(
+                "Unknown function '.{}()'; expected one of .rollup(), .as_count(), .as_rate(), .fill()",
+                name
+            ));
+            continue;
+        }
+
+        if name == "rollup" {
+            let args_start = pos + 1 + name_len + 1;
+            let args = query[args_start..]
+                .find(')')
+                .map_or("", |end| &query[args_start..args_start + end]);
+            check_rollup_args(args, report);
+        }
+    }
+
+    let rollups = query.matches(".rollup(").count();
+    let terms = query
+        .char_indices()
+        .filter(|(pos, c)| *c == '{' && !query[..*pos].trim_end().ends_with("by"))
+        .count();
+    if rollups > terms.max(1) {
+        report.errors.push(format!(
+            "Found {} .rollup() calls for {} metric term(s); use at most one rollup per term",
+            rollups, terms
+        ));
+    }
+}
+
+fn check_rollup_args(args: &str, report: &mut QueryReport) {
+    let args: Vec<&str> = args.split(',').map(str::trim).collect();
+
+    match args.as_slice() {
+        [interval] if interval.parse::<u64>().is_ok() => {}
+        [method] | [method, _] if !ROLLUP_METHODS.contains(method) => {
+            report.errors.push(format!(
+                "Unknown rollup method '{}' (expected avg, sum, min, max or count)",
+                method
+            ));
+        }
+        [_] => {}
+        [_, interval] => {
+            if !interval.parse::<u64>().is_ok_and(|i| i > 0) {
+                report.errors.push(format!(
+                    "Rollup interval '{}' must be a positive number of seconds",
+                    interval
+                ));
+            }
+        }
+        _ => report.errors.push(
+            "rollup() takes at most two arguments: .rollup(method, interval_seconds)".to_string(),
+        ),
+    }
+}
+
+/// Check that every `by` is followed by a tag group
+fn check_group_by(query: &str, outside: &[bool], report: &mut QueryReport) {
+    for (pos, _) in query.match_indices(" by") {
+        if !outside[pos] {
+            continue;
+        }
+
+        let after = query[pos + 3..].trim_start();
+        if !after.starts_with('{') {
+            report.errors.push(format!(
+                "'by' at position {} must be followed by tags in braces, e.g. by {{host}}",
+                pos + 1
+            ));
+        }
+    }
+}
+
+/// Check a metrics query's syntax (aggregators, metric names, scopes, functions) locally
+pub fn check(query: &str) -> QueryReport {
+    let mut report = QueryReport::default();
+
+    if query.trim().is_empty() {
+        report.errors.push("Query is empty".to_string());
+        return report;
+    }
+
+    // Unbalanced delimiters make every other check unreliable
+    if let Some(error) = check_balance(query) {
+        report.errors.push(error);
+        return report;
+    }
+
+    let outside = outside_scope(query);
+    check_terms(query, &outside, &mut report);
+    check_functions(query, &outside, &mut report);
+    check_group_by(query, &outside, &mut report);
+
+    report
+}
+
+/// Refuse a query the local check finds errors in before it is sent
+pub fn validate(query: &str) -> crate::error::Result<()> {
+    let report = check(query);
+    if report.errors.is_empty() {
+        return Ok(());
+    }
+    Err(DatadogError::QuerySyntax {
+        query: query.to_string(),
+        datadog: None,
+        problems: report.errors,
+    })
+}
+
+/// The tool error for a failed metrics query: Datadog's parse errors become `QuerySyntax`
+/// with whatever the local check finds in `query`, other errors are returned unchanged
+pub fn explain(query: &str, error: DatadogError) -> DatadogError {
+    let message = match &error {
+        DatadogError::ApiError { status: 400, body } => match error.api_errors() {
+            Some(serde_json::Value::Array(errors)) => errors
+                .iter()
+                .map(|e| e.as_str().map_or_else(|| e.to_string(), String::from))
+                .collect::<Vec<_>>()
+                .join("; "),
+            _ => body.clone(),
+        },
+        DatadogError::QueryError(message) => message.clone(),
+        _ => return error,
+    };
+    if !message.contains(PARSE_ERROR) {
+        return error;
+    }
+
+    let report = check(query);
+    DatadogError::QuerySyntax {
+        query: query.to_string(),
+        datadog: Some(message),
+        problems: report.errors.into_iter().chain(report.warnings).collect(),
+    }
+}
+
+/// A parse error Datadog reported inside a successful response, as the `QuerySyntax` error
+/// `explain` makes of it
+pub fn response_error(query: &str, error: Option<&str>) -> Option<DatadogError> {
+    error
+        .filter(|error| error.contains(PARSE_ERROR))
+        .map(|error| explain(query, DatadogError::QueryError(error.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_valid_queries() {
+        for query in [
+            "avg:system.cpu.user{*}",
+            "sum:trace.http.request.hits{service:web,env:prod} by {resource_name}.as_count()",
+            "avg:system.load.1{host:web-1}.rollup(max, 300)",
+            "p95:trace.http.request.duration{service:api}",
+            "sum:a.errors{*}.as_count() / sum:a.hits{*}.as_count()",
+        ] {
+            let report = check(query);
+            assert!(report.errors.is_empty(), "{}: {:?}", query, report.errors);
+            assert!(
+                report.warnings.is_empty(),
+                "{}: {:?}",
+                query,
+                report.warnings
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_unbalanced() {
+        let report = check("avg:system.cpu.user{host:a");
+        assert_eq!(report.errors, vec!["Unclosed '{' opened at position 19"]);
+
+        let report = check("avg:system.cpu.user{*}.rollup(sum, 60");
+        assert!(report.errors[0].contains("Unclosed '('"));
+
+        let report = check("avg:system.cpu.user{*})");
+        assert!(report.errors[0].contains("Unexpected ')'"));
+    }
+
+    #[test]
+    fn test_check_unknown_aggregator() {
+        let report = check("mean:system.cpu.user{*}");
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("'mean'"));
+        assert!(report.errors[0].contains("did you mean 'avg:'?"));
+
+        let report = check("AVG:system.cpu.user{*}");
+        assert!(report.errors[0].contains("did you mean 'avg:'?"));
+
+        let report = check("median:system.cpu.user{*}");
+        assert!(report.errors[0].contains("expected avg, sum, min, max"));
+    }
+
+    #[test]
+    fn test_check_rollup_before_scope() {
+        let report = check("avg:system.cpu.user.rollup(sum, 60){*}");
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("'system.cpu.user'"));
+        assert!(report.errors[0].contains(".rollup()"));
+    }
+
+    #[test]
+    fn test_check_rollup_arguments() {
+        let report = check("avg:system.cpu.user{*}.rollup(median, 60)");
+        assert!(report.errors[0].contains("'median'"));
+
+        let report = check("avg:system.cpu.user{*}.rollup(sum, 1m)");
+        assert!(report.errors[0].contains("'1m'"));
+
+        assert!(check("avg:system.cpu.user{*}.rollup(60)").errors.is_empty());
+    }
+
+    #[test]
+    fn test_check_scope_problems() {
+        let report = check("avg:system.cpu.user");
+        assert!(report.errors[0].contains("must be followed by a scope"));
+
+        let report = check("avg:system.cpu.user{}");
+        assert!(report.errors[0].contains("Empty scope"));
+
+        let report = check("system.cpu.user{*}");
+        assert!(report.errors.is_empty());
+        assert!(report.warnings[0].contains("defaults to avg"));
+    }
+
+    #[test]
+    fn test_check_group_by_and_functions() {
+        let report = check("avg:system.cpu.user{*} by host");
+        assert!(report.errors[0].contains("'by'"));
+
+        let report = check("avg:system.cpu.user{*}.as_percent()");
+        assert!(report.warnings[0].contains(".as_percent()"));
+    }
+
+    #[test]
+    fn test_check_ignores_tag_values() {
+        let report = check("avg:system.cpu.user{url:http://x.y/a.b(c),env:prod}");
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("avg:system.cpu.user{*}").is_ok());
+
+        let error = validate("mean:system.cpu.user{*}").unwrap_err();
+        assert_eq!(error.code(), "query_syntax");
+        assert_eq!(
+            error.details()["query_problems"][0],
+            "Unknown space aggregator 'mean' at position 0; did you mean 'avg:'?"
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let parse_error = || {
+            DatadogError::ApiError {
+            status: 400,
+            body: r#"{"errors": ["Error parsing query: unable to parse avg:system.cpu.user{*}.rollup(median, 60)"]}"#
+                .to_string(),
+        }
+        };
+
+        let error = explain("avg:system.cpu.user{*}.rollup(median, 60)", parse_error());
+        assert_eq!(error.code(), "query_syntax");
+        let details = error.details();
+        assert!(
+            details["query_problems"][0]
+                .as_str()
+                .unwrap()
+                .contains("'median'")
+        );
+        assert!(
+            details["datadog_errors"][0]
+                .as_str()
+                .unwrap()
+                .starts_with("Error parsing query")
+        );
+
+        // Nothing found locally: Datadog's message is all there is
+        let error = explain("avg:system.cpu.user{*}", parse_error());
+        assert!(error.to_string().contains("unable to parse"));
+
+        let error = explain(
+            "avg:system.cpu.user{*}",
+            DatadogError::QueryError("Error parsing query: bad".to_string()),
+        );
+        assert_eq!(error.code(), "query_syntax");
+
+        let error = explain(
+            "avg:system.cpu.user{*}",
+            DatadogError::ApiError {
+                status: 400,
+                body: r#"{"errors": ["Invalid time range"]}"#.to_string(),
+            },
+        );
+        assert_eq!(error.code(), "bad_request");
+
+        assert!(response_error("avg:x{*}", Some("Error parsing query: bad")).is_some());
+        assert!(response_error("avg:x{*}", Some("No data")).is_none());
+        assert!(response_error("avg:x{*}", None).is_none());
+    }
+}
@@ -0,0 +1,160 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::{DatadogError, Result};
+
+#[derive(Debug)]
+struct Bucket {
+    /// Tokens added per second
+    rate: f64,
+    /// Burst size: one second's worth of requests, at least one
+    capacity: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl Bucket {
+    fn new(rate: f64) -> Self {
+        let capacity = rate.max(1.0);
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    /// Take a token, or return how long until one is available
+    fn try_take(&mut self) -> std::result::Result<(), Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.refilled_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+}
+
+/// Client-side token buckets keeping request rates under the org's Datadog limits: one
+/// global bucket (DD_MAX_RPS) plus optional budgets for endpoint prefixes (DD_RATE_LIMITS).
+/// Requests wait for a token rather than failing.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    global: Option<Mutex<Bucket>>,
+    /// (endpoint prefix, bucket), longest prefix first
+    endpoints: Vec<(String, Mutex<Bucket>)>,
+}
+
+impl RateLimiter {
+    /// `max_rps` bounds all requests; `budgets` is "/api/v2/logs=2,/api/v1/query=5"
+    pub fn new(max_rps: Option<f64>, budgets: &str) -> Result<Self> {
+        let positive = |rate: f64, what: &str| {
+            if rate.is_finite() && rate > 0.0 {
+                Ok(rate)
+            } else {
+                Err(DatadogError::InvalidInput(format!(
+                    "Invalid rate for {}: expected requests per second > 0",
+                    what
+                )))
+            }
+        };
+
+        let global = max_rps
+            .map(|rate| positive(rate, "DD_MAX_RPS"))
+            .transpose()?
+            .map(|rate| Mutex::new(Bucket::new(rate)));
+
+        let mut endpoints = Vec::new();
+        for entry in budgets.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (prefix, rate) = entry.split_once('=').ok_or_else(|| {
+                DatadogError::InvalidInput(format!(
+                    "Invalid rate limit '{}': expected /api/path=requests_per_second",
+                    entry
+                ))
+            })?;
+            let prefix = prefix.trim();
+            let rate = rate.trim().parse::<f64>().map_err(|_| {
+                DatadogError::InvalidInput(format!("Invalid rate for '{}'", prefix))
+            })?;
+            let rate = positive(rate, prefix)?;
+            endpoints.push((prefix.to_string(), Mutex::new(Bucket::new(rate))));
+        }
+        endpoints.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+        Ok(Self { global, endpoints })
+    }
+
+    /// Wait until `endpoint` may be requested under its prefix budget and the global limit
+    pub async fn acquire(&self, endpoint: &str) {
+        let budget = self
+            .endpoints
+            .iter()
+            .find(|(prefix, _)| endpoint.starts_with(prefix.as_str()))
+            .map(|(_, bucket)| bucket);
+
+        for bucket in budget.into_iter().chain(self.global.as_ref()) {
+            loop {
+                let wait = bucket.lock().unwrap_or_else(|e| e.into_inner()).try_take();
+                match wait {
+                    Ok(()) => break,
+                    Err(wait) => tokio::time::sleep(wait).await,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let limiter = RateLimiter::new(None, "").unwrap();
+        assert!(limiter.global.is_none() && limiter.endpoints.is_empty());
+
+        let limiter =
+            RateLimiter::new(Some(10.0), "/api/v2/logs=2, /api/v2/logs/events=1").unwrap();
+        assert!(limiter.global.is_some());
+        assert_eq!(limiter.endpoints[0].0, "/api/v2/logs/events");
+
+        assert!(RateLimiter::new(Some(0.0), "").is_err());
+        assert!(RateLimiter::new(None, "/api/v2/logs").is_err());
+        assert!(RateLimiter::new(None, "/api/v2/logs=fast").is_err());
+        assert!(RateLimiter::new(None, "/api/v2/logs=-1").is_err());
+    }
+
+    #[test]
+    fn test_bucket() {
+        let mut bucket = Bucket::new(2.0);
+        assert!(bucket.try_take().is_ok());
+        assert!(bucket.try_take().is_ok());
+
+        let wait = bucket.try_take().unwrap_err();
+        assert!(wait > Duration::ZERO && wait <= Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_waits_for_budget() {
+        let limiter = RateLimiter::new(None, "/api/v1/query=10").unwrap();
+
+        let started = Instant::now();
+        for _ in 0..12 {
+            limiter.acquire("/api/v1/query").await;
+        }
+        // 10 burst tokens, then two more at 10/s
+        assert!(started.elapsed() >= Duration::from_millis(150));
+
+        // Other endpoints aren't limited
+        let started = Instant::now();
+        for _ in 0..50 {
+            limiter.acquire("/api/v1/monitor").await;
+        }
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,355 @@
+use futures::future::BoxFuture;
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::api::{ApiRequest, DatadogApi};
+use super::client::DatadogClient;
+use crate::error::{DatadogError, Result};
+use crate::redact::redact_value;
+use crate::scrub::Scrubber;
+
+/// Directory replayed from when DD_REPLAY_DIR is unset
+const DEFAULT_REPLAY_DIR: &str = "recordings";
+
+/// Whether DD_MCP_MODE=replay: API calls are answered from recordings, never sent
+pub fn replay_mode() -> bool {
+    std::env::var("DD_MCP_MODE")
+        .map(|mode| mode.trim().eq_ignore_ascii_case("replay"))
+        .unwrap_or(false)
+}
+
+/// `client` as the backend the environment asks for: replaying from DD_REPLAY_DIR in replay
+/// mode, recording into DD_RECORD_DIR when set, else the client itself. Recordings of an
+/// additional org (`org`) live in a subdirectory named after it.
+pub fn from_env(client: DatadogClient, org: Option<&str>) -> Arc<dyn DatadogApi> {
+    let dir = |base: String| match org {
+        Some(org) => Path::new(&base).join(org),
+        None => PathBuf::from(base),
+    };
+
+    if replay_mode() {
+        let base = std::env::var("DD_REPLAY_DIR").unwrap_or_else(|_| DEFAULT_REPLAY_DIR.into());
+        Arc::new(Recorded::replay(client, dir(base)))
+    } else if let Ok(base) = std::env::var("DD_RECORD_DIR") {
+        // An invalid DD_SCRUB_PATTERN stops the server at startup, before anything is recorded
+        let scrubber = Scrubber::from_env().ok().flatten();
+        Arc::new(Recorded::record(client, dir(base)).with_scrubber(scrubber))
+    } else {
+        Arc::new(client)
+    }
+}
+
+/// Query and body keys holding a point in time; those change on every run, so their values
+/// are left out of a recording's key
+const TIME_KEYS: &[&str] = &["from", "to", "timestamp", "start", "end"];
+
+fn is_time_key(key: &str) -> bool {
+    TIME_KEYS.contains(&key)
+}
+
+/// `value` with the values of its time keys replaced by a placeholder
+fn without_times(value: &Value) -> Value {
+    match value {
+        Value::Array(items) => items.iter().map(without_times).collect(),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| {
+                let value = if is_time_key(key) {
+                    json!("<time>")
+                } else {
+                    without_times(value)
+                };
+                (key.clone(), value)
+            })
+            .collect(),
+        value => value.clone(),
+    }
+}
+
+/// FNV-1a, stable across builds unlike the std hashers
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The request as stored next to its response, and the key (file name) it is filed under
+fn describe(request: &ApiRequest<'_>) -> (Value, String) {
+    let query: Vec<Value> = request
+        .query
+        .iter()
+        .map(|(key, value)| json!([key, value]))
+        .collect();
+    let described = json!({
+        "method": request.method.as_str(),
+        "endpoint": request.endpoint,
+        "query": query,
+        "body": request.body,
+    });
+    let keyed = json!({
+        "method": described["method"],
+        "endpoint": described["endpoint"],
+        "query": request
+            .query
+            .iter()
+            .map(|(key, value)| {
+                let value = if is_time_key(key) { "<time>" } else { value };
+                json!([key, value])
+            })
+            .collect::<Vec<_>>(),
+        "body": request.body.as_ref().map(without_times),
+    });
+    let key = format!("{:016x}", fnv1a(keyed.to_string().as_bytes()));
+    (described, key)
+}
+
+/// A `DatadogClient` that saves each successful response as `<dir>/<key>.json` (recording)
+/// or answers from those files without sending anything (replay). Settings such as tag
+/// filters and write mode still come from the client.
+///
+/// Recordings are written with configured keys redacted and, when DD_SCRUB_PII or
+/// DD_SCRUB_PATTERN is set, scrubbed with the same rules as tool output. Without those
+/// settings they hold raw API responses (log lines, user emails, host names): treat the
+/// directory as sensitive.
+pub struct Recorded {
+    client: DatadogClient,
+    dir: PathBuf,
+    replay: bool,
+    scrubber: Option<Scrubber>,
+}
+
+impl Recorded {
+    pub fn record(client: DatadogClient, dir: PathBuf) -> Self {
+        Self {
+            client,
+            dir,
+            replay: false,
+            scrubber: None,
+        }
+    }
+
+    /// Scrub recordings with `scrubber` before they are written
+    pub fn with_scrubber(mut self, scrubber: Option<Scrubber>) -> Self {
+        self.scrubber = scrubber;
+        self
+    }
+
+    pub fn replay(client: DatadogClient, dir: PathBuf) -> Self {
+        Self {
+            client,
+            dir,
+            replay: true,
+            scrubber: None,
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    fn replayed(&self, request: &ApiRequest<'_>) -> Result<Value> {
+        let (_, key) = describe(request);
+        let path = self.path(&key);
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            return Err(DatadogError::ApiError {
+                status: 404,
+                body: format!(
+                    "No recording of {} {} in {} (expected {})",
+                    request.method,
+                    request.endpoint,
+                    self.dir.display(),
+                    path.display()
+                ),
+            });
+        };
+
+        let mut recording: Value = serde_json::from_str(&text)?;
+        Ok(recording["response"].take())
+    }
+
+    /// Failures to save are logged, never returned: the call itself succeeded
+    fn save(&self, request: &ApiRequest<'_>, response: &Value) {
+        let (described, key) = describe(request);
+        let mut recording = json!({ "request": described, "response": response });
+        redact_value(&mut recording);
+        if let Some(scrubber) = &self.scrubber {
+            scrubber.scrub_value(&mut recording);
+        }
+        let saved = std::fs::create_dir_all(&self.dir).and_then(|_| {
+            std::fs::write(
+                self.path(&key),
+                serde_json::to_string_pretty(&recording).unwrap_or_default(),
+            )
+        });
+        if let Err(e) = saved {
+            log::warn!(
+                "Could not record {} {} in {}: {}",
+                request.method,
+                request.endpoint,
+                self.dir.display(),
+                e
+            );
+        }
+    }
+}
+
+impl DatadogApi for Recorded {
+    fn send_json<'a>(&'a self, request: ApiRequest<'a>) -> BoxFuture<'a, Result<Value>> {
+        Box::pin(async move {
+            if self.replay {
+                return self.replayed(&request);
+            }
+
+            let response = self.client.send_json(request.clone()).await?;
+            self.save(&request, &response);
+            Ok(response)
+        })
+    }
+
+    fn site(&self) -> &str {
+        self.client.site()
+    }
+
+    fn base_url(&self) -> &str {
+        self.client.base_url()
+    }
+
+    fn writes_allowed(&self) -> bool {
+        self.client.writes_allowed()
+    }
+
+    fn get_tag_filter(&self) -> Option<&str> {
+        self.client.get_tag_filter()
+    }
+
+    fn get_tag_filter_for(&self, product: &str) -> Option<&str> {
+        self.client.get_tag_filter_for(product)
+    }
+
+    fn max_concurrency(&self) -> usize {
+        self.client.max_concurrency()
+    }
+
+    fn app_key_last4(&self) -> &str {
+        self.client.app_key_last4()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn request<'a>(query: Vec<(&'a str, String)>, body: Option<Value>) -> ApiRequest<'a> {
+        ApiRequest {
+            method: reqwest::Method::POST,
+            endpoint: "/api/v2/logs/events/search",
+            query,
+            body,
+            retry_safe: true,
+            mutating: false,
+        }
+    }
+
+    #[test]
+    fn test_key_ignores_times() {
+        let key = |query, body| describe(&request(query, body)).1;
+
+        let earlier = key(
+            vec![("from", "1700000000".to_string())],
+            Some(json!({"filter": {"query": "status:error", "from": "2024-01-01T00:00:00Z"}})),
+        );
+        let later = key(
+            vec![("from", "1700003600".to_string())],
+            Some(json!({"filter": {"query": "status:error", "from": "2024-01-01T01:00:00Z"}})),
+        );
+        let other_query = key(
+            vec![("from", "1700003600".to_string())],
+            Some(json!({"filter": {"query": "status:warn", "from": "2024-01-01T01:00:00Z"}})),
+        );
+
+        assert_eq!(earlier, later);
+        assert_ne!(earlier, other_query);
+        assert_eq!(earlier.len(), 16);
+    }
+
+    #[test]
+    fn test_key_keeps_other_large_values() {
+        let key = |query, body| describe(&request(query, body)).1;
+
+        // Large IDs and numeric thresholds are part of what is asked, not when
+        assert_ne!(
+            key(vec![("monitor_id", "1700000000".to_string())], None),
+            key(vec![("monitor_id", "1700003600".to_string())], None)
+        );
+        assert_ne!(
+            key(
+                vec![],
+                Some(json!({"filter": {"min_bytes": 5_000_000_000u64}}))
+            ),
+            key(
+                vec![],
+                Some(json!({"filter": {"min_bytes": 6_000_000_000u64}}))
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v2/logs/events/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"data": [1, 2]})))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir =
+            std::env::temp_dir().join(format!("mcp-datadog-recordings-{}", uuid::Uuid::new_v4()));
+        let client = || DatadogClient::new("key".to_string(), "app".to_string(), None).unwrap();
+
+        let recorder = Recorded::record(client().with_api_url(&mock_server.uri()), dir.clone());
+        let recorded = recorder
+            .send_json(request(vec![], Some(json!({"from": 1_700_000_000}))))
+            .await
+            .unwrap();
+
+        // Never reaches the mock server: it expects exactly the one recorded call
+        let replayer = Recorded::replay(client().with_api_url(&mock_server.uri()), dir.clone());
+        let replayed = replayer
+            .send_json(request(vec![], Some(json!({"from": 1_700_086_400}))))
+            .await
+            .unwrap();
+        assert_eq!(replayed, recorded);
+
+        // Configured keys never reach the recording on disk
+        crate::redact::register_secret("recordedsecret0123456789");
+        recorder.save(
+            &request(vec![("page", "3".to_string())], None),
+            &json!({"data": [{"message": "key recordedsecret0123456789 leaked"}]}),
+        );
+        let saved: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
+            .collect();
+        assert!(
+            saved
+                .iter()
+                .all(|text| !text.contains("recordedsecret0123456789"))
+        );
+
+        let error = replayer
+            .send_json(request(vec![("page", "2".to_string())], None))
+            .await
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("No recording of POST /api/v2/logs/events/search")
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,4 +1,9 @@
-use std::time::Duration;
+use chrono::{DateTime, Utc};
+use reqwest::Method;
+use reqwest::header::HeaderMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::DatadogError;
 
 /// Maximum number of retry attempts for failed API requests
 pub const MAX_RETRIES: u32 = 3;
@@ -18,6 +23,76 @@ pub fn should_retry(current_retry: u32) -> bool {
     current_retry < MAX_RETRIES
 }
 
+/// Longest rate-limit reset worth waiting for; beyond it the error is returned to the caller
+pub const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// Wait announced by a 429 response: `Retry-After` (seconds or HTTP date), else Datadog's
+/// `X-RateLimit-Reset` (seconds until the window resets)
+pub fn parse_reset_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+    };
+
+    if let Some(value) = header("retry-after") {
+        if let Ok(secs) = value.parse::<u64>() {
+            return Some(Duration::from_secs(secs));
+        }
+        if let Ok(date) = DateTime::parse_from_rfc2822(value) {
+            let secs = (date.with_timezone(&Utc) - Utc::now()).num_seconds().max(0);
+            return Some(Duration::from_secs(secs as u64));
+        }
+    }
+
+    header("x-ratelimit-reset")
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+/// Random extra wait of up to a quarter of `delay`, so concurrent calls don't retry in lockstep
+fn jitter(delay: Duration) -> Duration {
+    let max_millis = (delay.as_millis() / 4) as u64;
+    if max_millis == 0 {
+        return Duration::ZERO;
+    }
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or_default();
+    Duration::from_millis(seed % max_millis)
+}
+
+/// Wait before resending after `error` on attempt `retry_count`, or None when the error
+/// is terminal (see `DatadogError::is_retryable`) or its rate-limit reset is too far off
+pub fn retry_delay(error: &DatadogError, retry_count: u32) -> Option<Duration> {
+    if !error.is_retryable() {
+        return None;
+    }
+
+    let delay = match error {
+        DatadogError::RateLimitError {
+            reset_after: Some(reset_after),
+        } => {
+            if *reset_after > MAX_RATE_LIMIT_WAIT {
+                return None;
+            }
+            (*reset_after).max(Duration::from_secs(1))
+        }
+        _ => calculate_backoff(retry_count),
+    };
+    Some(delay + jitter(delay))
+}
+
+/// Check if a failed request can be resent without risking a duplicate side effect
+///
+/// Idempotent methods (GET, PUT, DELETE, ...) always qualify. POST and PATCH only
+/// qualify when the caller knows the endpoint sets state rather than creating a resource.
+pub fn is_retry_safe(method: &Method, idempotent: bool) -> bool {
+    idempotent || method.is_idempotent()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -60,4 +135,82 @@ mod tests {
     fn test_max_retries_constant() {
         assert_eq!(MAX_RETRIES, 3);
     }
+
+    #[test]
+    fn test_parse_reset_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_reset_after(&headers), None);
+
+        headers.insert("x-ratelimit-reset", "7".parse().unwrap());
+        assert_eq!(parse_reset_after(&headers), Some(Duration::from_secs(7)));
+
+        // Retry-After takes precedence
+        headers.insert("retry-after", "3".parse().unwrap());
+        assert_eq!(parse_reset_after(&headers), Some(Duration::from_secs(3)));
+
+        headers.insert(
+            "retry-after",
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(parse_reset_after(&headers), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let rate_limited = DatadogError::RateLimitError {
+            reset_after: Some(Duration::from_secs(5)),
+        };
+        let delay = retry_delay(&rate_limited, 1).unwrap();
+        assert!(delay >= Duration::from_secs(5) && delay <= Duration::from_millis(6250));
+
+        let delay = retry_delay(&DatadogError::RateLimitError { reset_after: None }, 2).unwrap();
+        assert!(delay >= Duration::from_secs(4) && delay <= Duration::from_secs(5));
+
+        let long_reset = DatadogError::RateLimitError {
+            reset_after: Some(Duration::from_secs(3600)),
+        };
+        assert_eq!(retry_delay(&long_reset, 1), None);
+
+        assert!(retry_delay(&DatadogError::TimeoutError, 1).is_some());
+        assert!(
+            retry_delay(
+                &DatadogError::ApiError {
+                    status: 502,
+                    body: "x".to_string(),
+                },
+                1
+            )
+            .is_some()
+        );
+        assert_eq!(
+            retry_delay(
+                &DatadogError::ApiError {
+                    status: 400,
+                    body: "x".to_string(),
+                },
+                1
+            ),
+            None
+        );
+        assert_eq!(
+            retry_delay(
+                &DatadogError::AuthError {
+                    status: None,
+                    message: "forbidden".to_string(),
+                },
+                1
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_retry_safe() {
+        assert!(is_retry_safe(&Method::GET, false));
+        assert!(is_retry_safe(&Method::PUT, false));
+        assert!(is_retry_safe(&Method::DELETE, false));
+        assert!(!is_retry_safe(&Method::POST, false));
+        assert!(!is_retry_safe(&Method::PATCH, false));
+        assert!(is_retry_safe(&Method::POST, true));
+    }
 }
@@ -1,12 +1,41 @@
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum DatadogError {
+    /// Non-success HTTP response other than auth failures, 408 and 429
+    #[error("API request failed: HTTP {status}: {body}")]
+    ApiError { status: u16, body: String },
+
+    /// Errors Datadog reports inside a successful response (e.g. a metrics query error)
     #[error("API request failed: {0}")]
-    ApiError(String),
+    QueryError(String),
+
+    /// A metrics query that failed the local syntax check or that Datadog could not parse
+    #[error("Invalid metrics query '{query}': {}", if problems.is_empty() {
+        datadog.clone().unwrap_or_default()
+    } else {
+        problems.join("; ")
+    })]
+    QuerySyntax {
+        query: String,
+        /// Datadog's parse error, when the query was sent
+        datadog: Option<String>,
+        /// What the local check (`datadog::query`) found, each with a suggested fix
+        problems: Vec<String>,
+    },
 
-    #[error("Authentication failed: {0}")]
-    AuthError(String),
+    #[error("Authentication failed: {}", match status {
+        Some(status) => format!("HTTP {}: {}", status, message),
+        None => message.clone(),
+    })]
+    AuthError {
+        /// 401 or 403 when the API refused the request; None for other rejections
+        status: Option<u16>,
+        message: String,
+    },
 
     #[error("Invalid date format: {0}")]
     DateParseError(String),
@@ -20,22 +49,400 @@ pub enum DatadogError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
-    #[error("Rate limit exceeded")]
-    RateLimitError,
+    #[error(
+        "Write operations are disabled (read-only mode); set DD_MCP_MODE=read_write or DD_ALLOW_WRITES=true to enable them"
+    )]
+    WritesDisabled,
+
+    #[error("Rate limit exceeded{}", reset_after.map(|d| format!(" (resets in {}s)", d.as_secs())).unwrap_or_default())]
+    RateLimitError {
+        /// Wait announced by Retry-After / X-RateLimit-Reset, when sent
+        reset_after: Option<Duration>,
+    },
 
     #[error("Timeout occurred")]
     TimeoutError,
+
+    #[error(
+        "Datadog API unavailable: {family} failed repeatedly, calls are paused for {}s",
+        retry_after.as_secs().max(1)
+    )]
+    ServiceUnavailable {
+        /// Endpoint family whose circuit is open, e.g. `/api/v1/monitor`
+        family: String,
+        retry_after: Duration,
+    },
+
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    /// A write stopped unsent by a `dry_run: true` call, with the request it would have made
+    #[error("Dry run: {} {} was not sent", .0["method"].as_str().unwrap_or_default(), .0["endpoint"].as_str().unwrap_or_default())]
+    DryRun(Value),
 }
 
 pub type Result<T> = std::result::Result<T, DatadogError>;
 
+/// What a tool consumer can do about a failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// Bad query, time range or identifier: rephrase the call
+    UserFixable,
+    /// Rate limit, timeout, network or 5xx: retry later
+    Transient,
+    /// Credentials, permissions or disabled features: escalate to whoever runs the server
+    Configuration,
+    /// Unexpected response or local failure: report it
+    Internal,
+}
+
+impl ErrorCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCategory::UserFixable => "user_fixable",
+            ErrorCategory::Transient => "transient",
+            ErrorCategory::Configuration => "configuration",
+            ErrorCategory::Internal => "internal",
+        }
+    }
+
+    pub fn hint(&self) -> &'static str {
+        match self {
+            ErrorCategory::UserFixable => {
+                "Fix the arguments (query, time range, IDs) and try again"
+            }
+            ErrorCategory::Transient => {
+                "Temporary failure; retrying the same call later may succeed"
+            }
+            ErrorCategory::Configuration => {
+                "Check the server's API/application keys, their permissions and settings; retrying won't help"
+            }
+            ErrorCategory::Internal => "Unexpected failure; retrying is unlikely to help",
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        *self == ErrorCategory::Transient
+    }
+}
+
+impl std::fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl DatadogError {
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            DatadogError::WritesDisabled => ErrorCategory::Configuration,
+            DatadogError::InvalidInput(_)
+            | DatadogError::DateParseError(_)
+            | DatadogError::QuerySyntax { .. } => ErrorCategory::UserFixable,
+            DatadogError::AuthError { .. } => ErrorCategory::Configuration,
+            DatadogError::RateLimitError { .. }
+            | DatadogError::TimeoutError
+            | DatadogError::ServiceUnavailable { .. } => ErrorCategory::Transient,
+            DatadogError::NetworkError(e) if e.is_decode() => ErrorCategory::Internal,
+            DatadogError::NetworkError(_) => ErrorCategory::Transient,
+            DatadogError::ApiError { status: 500.., .. } => ErrorCategory::Transient,
+            // 4xx other than auth/rate limit, and query errors reported in a 200 body
+            DatadogError::ApiError { .. } | DatadogError::QueryError(_) => {
+                ErrorCategory::UserFixable
+            }
+            DatadogError::JsonError(_) | DatadogError::IoError(_) => ErrorCategory::Internal,
+            // Only seen when a dry run escapes the router, which turns it into a result
+            DatadogError::DryRun(_) => ErrorCategory::UserFixable,
+        }
+    }
+
+    /// Failures likely to clear on their own: rate limits, timeouts, network and 5xx errors
+    pub fn is_transient(&self) -> bool {
+        self.category().is_retryable()
+    }
+
+    /// HTTP status of the failed API response, when there was one
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            DatadogError::ApiError { status, .. } => Some(*status),
+            DatadogError::AuthError { status, .. } => *status,
+            DatadogError::RateLimitError { .. } => Some(429),
+            _ => None,
+        }
+    }
+
+    /// Datadog's error body (its `errors` list when present) from a failed API response
+    pub fn api_errors(&self) -> Option<Value> {
+        let body = match self {
+            DatadogError::ApiError { body, .. } => body,
+            DatadogError::AuthError {
+                status: Some(_),
+                message,
+            } => message,
+            DatadogError::QuerySyntax {
+                datadog: Some(message),
+                ..
+            } => return Some(json!([message])),
+            _ => return None,
+        };
+        let body: Value = serde_json::from_str(body).ok()?;
+        Some(body.get("errors").cloned().unwrap_or(body))
+    }
+
+    /// Stable machine-readable kind, finer-grained than `category()`
+    pub fn code(&self) -> &'static str {
+        match self {
+            DatadogError::WritesDisabled => "writes_disabled",
+            DatadogError::InvalidInput(_) => "invalid_input",
+            DatadogError::DateParseError(_) => "invalid_time",
+            DatadogError::AuthError {
+                status: Some(403), ..
+            } => "forbidden",
+            DatadogError::AuthError { .. } => "unauthorized",
+            DatadogError::RateLimitError { .. } => "rate_limited",
+            DatadogError::TimeoutError => "timeout",
+            DatadogError::ServiceUnavailable { .. } => "circuit_open",
+            DatadogError::NetworkError(e) if e.is_decode() => "unexpected_response",
+            DatadogError::NetworkError(_) => "network",
+            DatadogError::ApiError { status: 404, .. } => "not_found",
+            DatadogError::ApiError { status: 500.., .. } => "server_error",
+            DatadogError::ApiError { .. } => "bad_request",
+            DatadogError::QueryError(_) => "query_error",
+            DatadogError::QuerySyntax { .. } => "query_syntax",
+            DatadogError::JsonError(_) => "unexpected_response",
+            DatadogError::IoError(_) => "io",
+            DatadogError::DryRun(_) => "dry_run",
+        }
+    }
+
+    /// Wait the API asked for before trying again
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            DatadogError::RateLimitError { reset_after } => *reset_after,
+            DatadogError::ServiceUnavailable { retry_after, .. } => Some(*retry_after),
+            _ => None,
+        }
+    }
+
+    /// What to do about this particular error
+    pub fn suggestion(&self) -> &'static str {
+        match self.code() {
+            "writes_disabled" => {
+                "Start the server with DD_MCP_MODE=read_write (or DD_ALLOW_WRITES=true) to enable write tools"
+            }
+            "invalid_time" => {
+                "Use a relative time ('1 hour ago'), ISO 8601 or a Unix timestamp in seconds"
+            }
+            "forbidden" => "The application key lacks the permission or scope this endpoint needs",
+            "unauthorized" => "DD_API_KEY/DD_APP_KEY are invalid or don't match DD_SITE",
+            "rate_limited" => "Wait for the rate limit to reset (see retry_after_secs), then retry",
+            "circuit_open" => {
+                "Datadog has been failing for this endpoint; retry after retry_after_secs"
+            }
+            "not_found" => "Check the ID or name; list the resources to find valid ones",
+            "query_syntax" => {
+                "Fix the problems listed in query_problems; datadog_metrics_validate checks a query without running it"
+            }
+            "bad_request" | "query_error" => {
+                "Fix the query or arguments as described in datadog_errors/message"
+            }
+            _ => self.category().hint(),
+        }
+    }
+
+    /// Structured payload reported for a failed tool call
+    pub fn details(&self) -> Value {
+        let category = self.category();
+        let mut details = json!({
+            "category": category,
+            "code": self.code(),
+            "retryable": category.is_retryable(),
+            "message": self.to_string(),
+            "hint": self.suggestion(),
+        });
+        if let Some(status) = self.http_status() {
+            details["http_status"] = json!(status);
+        }
+        if let Some(errors) = self.api_errors() {
+            details["datadog_errors"] = errors;
+        }
+        if let DatadogError::QuerySyntax { problems, .. } = self {
+            details["query_problems"] = json!(problems);
+        }
+        if let Some(retry_after) = self.retry_after() {
+            details["retry_after_secs"] = json!(retry_after.as_secs().max(1));
+        }
+        details
+    }
+
+    /// Whether resending the identical request may succeed (429, 408 and 5xx responses).
+    /// Everything else is terminal: 4xx, auth and decode failures repeat on every attempt.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            DatadogError::RateLimitError { .. } | DatadogError::TimeoutError => true,
+            DatadogError::ApiError { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_transient() {
+        assert!(DatadogError::RateLimitError { reset_after: None }.is_transient());
+        assert!(DatadogError::TimeoutError.is_transient());
+        assert!(
+            DatadogError::ApiError {
+                status: 503,
+                body: "down".to_string(),
+            }
+            .is_transient()
+        );
+        assert!(
+            !DatadogError::ApiError {
+                status: 400,
+                body: "bad".to_string(),
+            }
+            .is_transient()
+        );
+        assert!(
+            !DatadogError::AuthError {
+                status: None,
+                message: "forbidden".to_string(),
+            }
+            .is_transient()
+        );
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(DatadogError::RateLimitError { reset_after: None }.is_retryable());
+        assert!(DatadogError::TimeoutError.is_retryable());
+        assert!(
+            DatadogError::ApiError {
+                status: 500,
+                body: "x".to_string(),
+            }
+            .is_retryable()
+        );
+        assert!(
+            !DatadogError::ApiError {
+                status: 404,
+                body: "x".to_string(),
+            }
+            .is_retryable()
+        );
+        assert!(
+            !DatadogError::ApiError {
+                status: 422,
+                body: "x".to_string(),
+            }
+            .is_retryable()
+        );
+        assert!(
+            !DatadogError::AuthError {
+                status: None,
+                message: "forbidden".to_string(),
+            }
+            .is_retryable()
+        );
+        assert!(!DatadogError::InvalidInput("bad".to_string()).is_retryable());
+    }
+
+    #[test]
+    fn test_details() {
+        let error = DatadogError::ApiError {
+            status: 400,
+            body: r#"{"errors": ["Error parsing query"]}"#.to_string(),
+        };
+        let details = error.details();
+        assert_eq!(details["code"], "bad_request");
+        assert_eq!(details["category"], "user_fixable");
+        assert_eq!(details["http_status"], 400);
+        assert_eq!(details["datadog_errors"], json!(["Error parsing query"]));
+        assert!(details.get("retry_after_secs").is_none());
+
+        let error = DatadogError::ApiError {
+            status: 404,
+            body: "missing".to_string(),
+        };
+        assert_eq!(error.code(), "not_found");
+        assert_eq!(error.api_errors(), None);
+
+        let error = DatadogError::AuthError {
+            status: Some(403),
+            message: "{}".to_string(),
+        };
+        assert_eq!(error.code(), "forbidden");
+        assert_eq!(error.http_status(), Some(403));
+
+        let details = DatadogError::RateLimitError {
+            reset_after: Some(Duration::from_secs(9)),
+        }
+        .details();
+        assert_eq!(details["code"], "rate_limited");
+        assert_eq!(details["retryable"], true);
+        assert_eq!(details["http_status"], 429);
+        assert_eq!(details["retry_after_secs"], 9);
+
+        assert_eq!(
+            DatadogError::QueryError("Query failed: bad metric".to_string()).code(),
+            "query_error"
+        );
+        assert_eq!(DatadogError::WritesDisabled.code(), "writes_disabled");
+        assert_eq!(
+            DatadogError::InvalidInput("Mention of DD_ALLOW_WRITES".to_string()).code(),
+            "invalid_input"
+        );
+    }
+
+    #[test]
+    fn test_category() {
+        assert_eq!(
+            DatadogError::InvalidInput("Missing 'query' parameter".to_string()).category(),
+            ErrorCategory::UserFixable
+        );
+        assert_eq!(
+            DatadogError::DateParseError("yesterday-ish".to_string()).category(),
+            ErrorCategory::UserFixable
+        );
+        assert_eq!(
+            DatadogError::ApiError {
+                status: 404,
+                body: "no such monitor".to_string(),
+            }
+            .category(),
+            ErrorCategory::UserFixable
+        );
+        assert_eq!(
+            DatadogError::AuthError {
+                status: None,
+                message: "forbidden".to_string(),
+            }
+            .category(),
+            ErrorCategory::Configuration
+        );
+        assert_eq!(
+            DatadogError::WritesDisabled.category(),
+            ErrorCategory::Configuration
+        );
+        assert_eq!(
+            DatadogError::JsonError(serde_json::from_str::<u8>("x").unwrap_err()).category(),
+            ErrorCategory::Internal
+        );
+        assert_eq!(
+            serde_json::to_value(ErrorCategory::UserFixable).unwrap(),
+            "user_fixable"
+        );
+    }
+
     #[test]
     fn test_api_error_display() {
-        let error = DatadogError::ApiError("Test error".to_string());
+        let error = DatadogError::QueryError("Test error".to_string());
         let error_msg = format!("{}", error);
         assert!(error_msg.contains("API request failed"));
         assert!(error_msg.contains("Test error"));
@@ -43,7 +450,10 @@ mod tests {
 
     #[test]
     fn test_auth_error_display() {
-        let error = DatadogError::AuthError("Invalid credentials".to_string());
+        let error = DatadogError::AuthError {
+            status: None,
+            message: "Invalid credentials".to_string(),
+        };
         let error_msg = format!("{}", error);
         assert!(error_msg.contains("Authentication failed"));
         assert!(error_msg.contains("Invalid credentials"));
@@ -65,9 +475,27 @@ mod tests {
 
     #[test]
     fn test_rate_limit_error_display() {
-        let error = DatadogError::RateLimitError;
-        let error_msg = format!("{}", error);
-        assert!(error_msg.contains("Rate limit exceeded"));
+        let error = DatadogError::RateLimitError { reset_after: None };
+        assert_eq!(format!("{}", error), "Rate limit exceeded");
+
+        let error = DatadogError::RateLimitError {
+            reset_after: Some(Duration::from_secs(12)),
+        };
+        assert_eq!(format!("{}", error), "Rate limit exceeded (resets in 12s)");
+    }
+
+    #[test]
+    fn test_service_unavailable() {
+        let error = DatadogError::ServiceUnavailable {
+            family: "/api/v1/monitor".to_string(),
+            retry_after: Duration::from_secs(20),
+        };
+        assert_eq!(
+            format!("{}", error),
+            "Datadog API unavailable: /api/v1/monitor failed repeatedly, calls are paused for 20s"
+        );
+        assert!(error.is_transient());
+        assert!(!error.is_retryable());
     }
 
     #[test]
@@ -77,6 +505,13 @@ mod tests {
         assert!(error_msg.contains("Timeout occurred"));
     }
 
+    #[test]
+    fn test_io_error_conversion() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::AddrInUse, "address in use");
+        let error = DatadogError::from(io_error);
+        assert!(format!("{}", error).contains("I/O error"));
+    }
+
     #[test]
     fn test_json_error_conversion() {
         let json_str = "invalid json {";
@@ -97,8 +532,12 @@ mod tests {
 
     #[test]
     fn test_error_debug_format() {
-        let error = DatadogError::ApiError("test".to_string());
+        let error = DatadogError::ApiError {
+            status: 400,
+            body: "test".to_string(),
+        };
         let debug_str = format!("{:?}", error);
         assert!(debug_str.contains("ApiError"));
+        assert!(debug_str.contains("400"));
     }
 }
@@ -0,0 +1,337 @@
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::{ResponseFormatter, SPANS_RETENTION_DAYS, TimeHandler, TimeParams};
+
+/// Default number of resources returned
+const DEFAULT_RESOURCE_LIMIT: usize = 25;
+
+/// Upper bound on resource buckets requested from the aggregate API
+const MAX_RESOURCE_LIMIT: usize = 1000;
+
+/// Columns accepted by `sort_by`
+const SORT_COLUMNS: &[&str] = &["hits", "errors", "error_rate", "avg_ms", "p95_ms", "p99_ms"];
+
+pub struct ApmResourcesHandler;
+
+impl TimeHandler for ApmResourcesHandler {
+    fn retention_days(&self) -> Option<i64> {
+        Some(SPANS_RETENTION_DAYS)
+    }
+}
+impl ResponseFormatter for ApmResourcesHandler {}
+
+/// Per-resource request statistics
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ResourceStats {
+    resource: String,
+    hits: f64,
+    errors: f64,
+    avg_ms: Option<f64>,
+    p95_ms: Option<f64>,
+    p99_ms: Option<f64>,
+}
+
+impl ResourceStats {
+    fn error_rate(&self) -> f64 {
+        if self.hits > 0.0 {
+            self.errors / self.hits
+        } else {
+            0.0
+        }
+    }
+
+    fn column(&self, column: &str) -> f64 {
+        match column {
+            "errors" => self.errors,
+            "error_rate" => self.error_rate(),
+            "avg_ms" => self.avg_ms.unwrap_or(0.0),
+            "p95_ms" => self.p95_ms.unwrap_or(0.0),
+            "p99_ms" => self.p99_ms.unwrap_or(0.0),
+            _ => self.hits,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let round = |v: f64| (v * 1000.0).round() / 1000.0;
+        json!({
+            "resource": self.resource,
+            "hits": self.hits as i64,
+            "errors": self.errors as i64,
+            "error_rate": round(self.error_rate()),
+            "avg_ms": self.avg_ms.map(round),
+            "p95_ms": self.p95_ms.map(round),
+            "p99_ms": self.p99_ms.map(round),
+        })
+    }
+}
+
+impl ApmResourcesHandler {
+    fn build_query(service: &str, params: &Value) -> String {
+        let mut query = format!("service:{}", service);
+        if let Some(env) = params["env"].as_str() {
+            query.push_str(&format!(" env:{}", env));
+        }
+        if let Some(operation) = params["operation"].as_str() {
+            query.push_str(&format!(" operation_name:{}", operation));
+        }
+        if let Some(extra) = params["query"].as_str().filter(|q| !q.trim().is_empty()) {
+            query.push(' ');
+            query.push_str(extra);
+        }
+        query
+    }
+
+    fn bucket_resource(bucket: &Value) -> Option<String> {
+        let by = bucket["attributes"]["by"]
+            .as_object()
+            .or_else(|| bucket["by"].as_object())?;
+        match by.get("resource_name")? {
+            Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+
+    pub(crate) fn bucket_compute<'a>(bucket: &'a Value, id: &str) -> &'a Value {
+        let attributes = &bucket["attributes"]["compute"];
+        if attributes.is_null() {
+            &bucket["computes"][id]
+        } else {
+            &attributes[id]
+        }
+    }
+
+    pub(crate) fn buckets(response: &Value) -> &[Value] {
+        response["data"]
+            .as_array()
+            .or_else(|| response["data"]["buckets"].as_array())
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Merge latency/hit buckets with error-count buckets (durations are nanoseconds)
+    fn merge_buckets(all: &Value, errors: &Value) -> Vec<ResourceStats> {
+        let to_ms = |v: &Value| v.as_f64().map(|ns| ns / 1_000_000.0);
+
+        let mut stats: Vec<ResourceStats> = Self::buckets(all)
+            .iter()
+            .filter_map(|bucket| {
+                Some(ResourceStats {
+                    resource: Self::bucket_resource(bucket)?,
+                    hits: Self::bucket_compute(bucket, "c0").as_f64().unwrap_or(0.0),
+                    errors: 0.0,
+                    avg_ms: to_ms(Self::bucket_compute(bucket, "c1")),
+                    p95_ms: to_ms(Self::bucket_compute(bucket, "c2")),
+                    p99_ms: to_ms(Self::bucket_compute(bucket, "c3")),
+                })
+            })
+            .collect();
+
+        let error_counts: HashMap<String, f64> = Self::buckets(errors)
+            .iter()
+            .filter_map(|bucket| {
+                Some((
+                    Self::bucket_resource(bucket)?,
+                    Self::bucket_compute(bucket, "c0").as_f64().unwrap_or(0.0),
+                ))
+            })
+            .collect();
+
+        for stat in &mut stats {
+            if let Some(count) = error_counts.get(&stat.resource) {
+                stat.errors = *count;
+            }
+        }
+
+        stats
+    }
+
+    fn sort_stats(stats: &mut [ResourceStats], column: &str, ascending: bool) {
+        stats.sort_by(|a, b| {
+            let ordering = a.column(column).total_cmp(&b.column(column));
+            if ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+    }
+
+    pub async fn stats(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = ApmResourcesHandler;
+
+        let service = params["service"]
+            .as_str()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| DatadogError::InvalidInput("Missing 'service' parameter".to_string()))?;
+
+        let sort_by = params["sort_by"].as_str().unwrap_or("hits");
+        if !SORT_COLUMNS.contains(&sort_by) {
+            return Err(DatadogError::InvalidInput(format!(
+                "Invalid 'sort_by' value '{}' (expected one of: {})",
+                sort_by,
+                SORT_COLUMNS.join(", ")
+            )));
+        }
+        let ascending = params["order"].as_str() == Some("asc");
+        let limit = params["limit"]
+            .as_u64()
+            .map(|l| l as usize)
+            .unwrap_or(DEFAULT_RESOURCE_LIMIT)
+            .clamp(1, MAX_RESOURCE_LIMIT);
+
+        let time = handler.parse_time(params, 2)?;
+        let TimeParams::Timestamp { from, to } = time;
+        let from = handler.timestamp_to_iso8601(from)?;
+        let to = handler.timestamp_to_iso8601(to)?;
+
+        let query = Self::build_query(service, params);
+        let group_by = json!([{
+            "facet": "resource_name",
+            "limit": MAX_RESOURCE_LIMIT,
+            "sort": {"aggregation": "count", "order": "desc"}
+        }]);
+
+        let compute = json!([
+            {"aggregation": "count", "type": "total"},
+            {"aggregation": "avg", "metric": "@duration", "type": "total"},
+            {"aggregation": "pc95", "metric": "@duration", "type": "total"},
+            {"aggregation": "pc99", "metric": "@duration", "type": "total"}
+        ]);
+        let error_compute = json!([{"aggregation": "count", "type": "total"}]);
+        let error_query = format!("{} status:error", query);
+
+        let (all, errors) = tokio::try_join!(
+            client.aggregate_spans(&query, &from, &to, compute, group_by.clone()),
+            client.aggregate_spans(&error_query, &from, &to, error_compute, group_by),
+        )?;
+
+        let mut stats = Self::merge_buckets(&all, &errors);
+        let total_resources = stats.len();
+        Self::sort_stats(&mut stats, sort_by, ascending);
+        stats.truncate(limit);
+
+        let meta = json!({
+            "service": service,
+            "query": query,
+            "from": from,
+            "to": to,
+            "sort_by": sort_by,
+            "order": if ascending { "asc" } else { "desc" },
+            "total_resources": total_resources,
+        });
+
+        Ok(handler.format_list(
+            json!(stats.iter().map(ResourceStats::to_json).collect::<Vec<_>>()),
+            None,
+            Some(meta),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+
+    fn bucket(resource: &str, computes: Value) -> Value {
+        json!({
+            "type": "bucket",
+            "attributes": {
+                "by": {"resource_name": resource},
+                "compute": computes
+            }
+        })
+    }
+
+    #[test]
+    fn test_build_query() {
+        let params =
+            json!({"env": "prod", "operation": "http.request", "query": "@http.status_code:5*"});
+        assert_eq!(
+            ApmResourcesHandler::build_query("web", &params),
+            "service:web env:prod operation_name:http.request @http.status_code:5*"
+        );
+        assert_eq!(
+            ApmResourcesHandler::build_query("web", &json!({})),
+            "service:web"
+        );
+    }
+
+    #[test]
+    fn test_merge_buckets() {
+        let all = json!({"data": [
+            bucket("GET /users", json!({"c0": 100, "c1": 2_000_000.0, "c2": 5_000_000.0, "c3": 9_000_000.0})),
+            bucket("POST /orders", json!({"c0": 10, "c1": 50_000_000.0, "c2": 80_000_000.0, "c3": 95_000_000.0}))
+        ]});
+        let errors = json!({"data": [bucket("POST /orders", json!({"c0": 4}))]});
+
+        let stats = ApmResourcesHandler::merge_buckets(&all, &errors);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].hits, 100.0);
+        assert_eq!(stats[0].errors, 0.0);
+        assert_eq!(stats[0].avg_ms, Some(2.0));
+        assert_eq!(stats[1].errors, 4.0);
+        assert_eq!(stats[1].p99_ms, Some(95.0));
+        assert_eq!(stats[1].error_rate(), 0.4);
+    }
+
+    #[test]
+    fn test_sort_stats_by_column() {
+        let all = json!({"data": [
+            bucket("a", json!({"c0": 100, "c2": 1_000_000.0})),
+            bucket("b", json!({"c0": 5, "c2": 30_000_000.0})),
+            bucket("c", json!({"c0": 50, "c2": 10_000_000.0}))
+        ]});
+        let mut stats = ApmResourcesHandler::merge_buckets(&all, &json!({}));
+
+        ApmResourcesHandler::sort_stats(&mut stats, "p95_ms", false);
+        let order: Vec<&str> = stats.iter().map(|s| s.resource.as_str()).collect();
+        assert_eq!(order, vec!["b", "c", "a"]);
+
+        ApmResourcesHandler::sort_stats(&mut stats, "hits", true);
+        let order: Vec<&str> = stats.iter().map(|s| s.resource.as_str()).collect();
+        assert_eq!(order, vec!["b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_resource_stats_json() {
+        let stats = ResourceStats {
+            resource: "GET /".to_string(),
+            hits: 3.0,
+            errors: 1.0,
+            avg_ms: Some(1.23456),
+            p95_ms: None,
+            p99_ms: None,
+        };
+        let value = stats.to_json();
+        assert_eq!(value["hits"], 3);
+        assert_eq!(value["error_rate"], 0.333);
+        assert_eq!(value["avg_ms"], 1.235);
+        assert!(value["p95_ms"].is_null());
+    }
+
+    #[test]
+    fn test_missing_service_and_invalid_sort() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let client = Arc::new(
+                DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None)
+                    .unwrap(),
+            );
+
+            let result = ApmResourcesHandler::stats(client.clone(), &json!({})).await;
+            assert!(result.is_err());
+
+            let result = ApmResourcesHandler::stats(
+                client,
+                &json!({"service": "web", "sort_by": "latency"}),
+            )
+            .await;
+            assert!(result.is_err());
+        });
+    }
+}
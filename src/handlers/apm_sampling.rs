@@ -0,0 +1,297 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::MetricsResponse;
+use crate::error::Result;
+use crate::handlers::common::{ResponseFormatter, TimeHandler, TimeParams};
+
+/// Estimated usage metric for spans ingested into Datadog
+const INGESTED_SPANS_METRIC: &str = "datadog.estimated_usage.apm.ingested_spans";
+
+/// Estimated usage metric for spans kept by retention filters
+const INDEXED_SPANS_METRIC: &str = "datadog.estimated_usage.apm.indexed_spans";
+
+/// Below this indexed/ingested ratio most traces are only reachable through Live Search
+const LOW_INDEXED_RATIO: f64 = 0.01;
+
+pub struct ApmSamplingHandler;
+
+impl TimeHandler for ApmSamplingHandler {}
+impl ResponseFormatter for ApmSamplingHandler {}
+
+impl ApmSamplingHandler {
+    fn metric_scope(params: &Value) -> String {
+        let scope: Vec<String> = [("service", "service"), ("env", "env")]
+            .iter()
+            .filter_map(|(param, tag)| {
+                params[*param]
+                    .as_str()
+                    .filter(|v| !v.is_empty())
+                    .map(|v| format!("{}:{}", tag, v))
+            })
+            .collect();
+
+        if scope.is_empty() {
+            "*".to_string()
+        } else {
+            scope.join(",")
+        }
+    }
+
+    fn series_total(points: Option<&Vec<Vec<Option<f64>>>>) -> f64 {
+        points
+            .map(|points| {
+                points
+                    .iter()
+                    .filter_map(|p| p.get(1).copied().flatten())
+                    .sum()
+            })
+            .unwrap_or(0.0)
+    }
+
+    fn response_total(response: &MetricsResponse) -> f64 {
+        response
+            .series
+            .iter()
+            .map(|s| Self::series_total(s.pointlist.as_ref()))
+            .sum()
+    }
+
+    fn totals_by_tag(response: &MetricsResponse, tag: &str) -> BTreeMap<String, i64> {
+        let prefix = format!("{}:", tag);
+        let mut totals = BTreeMap::new();
+
+        for series in &response.series {
+            let key = series
+                .tag_set
+                .as_ref()
+                .and_then(|tags| tags.iter().find_map(|t| t.strip_prefix(&prefix)))
+                .unwrap_or("unknown")
+                .to_string();
+            *totals.entry(key).or_insert(0) += Self::series_total(series.pointlist.as_ref()) as i64;
+        }
+
+        totals
+    }
+
+    /// Whether a retention filter query could select spans of the service
+    fn filter_may_match(query: &str, service: Option<&str>) -> bool {
+        let query = query.trim();
+        if query.is_empty() || query == "*" {
+            return true;
+        }
+
+        match service {
+            Some(service) => {
+                !query.contains("service:") || query.contains(&format!("service:{}", service))
+            }
+            None => true,
+        }
+    }
+
+    fn summarize_retention_filters(response: &Value, service: Option<&str>) -> Vec<Value> {
+        response["data"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+            .iter()
+            .map(|filter| {
+                let attrs = &filter["attributes"];
+                let query = attrs["filter"]["query"].as_str().unwrap_or("*");
+                json!({
+                    "id": filter["id"],
+                    "name": attrs["name"],
+                    "type": attrs["filter_type"],
+                    "enabled": attrs["enabled"],
+                    "query": query,
+                    "rate": attrs["rate"],
+                    "may_match": Self::filter_may_match(query, service),
+                })
+            })
+            .collect()
+    }
+
+    fn diagnose(
+        ingested: f64,
+        indexed: f64,
+        filters: &[Value],
+        service: Option<&str>,
+    ) -> Vec<String> {
+        let mut notes = Vec::new();
+
+        if ingested == 0.0 {
+            notes.push(
+                "No spans were ingested in this window; check that the tracer is enabled and the service/env tags match"
+                    .to_string(),
+            );
+            return notes;
+        }
+
+        let ratio = indexed / ingested;
+        if ratio < LOW_INDEXED_RATIO {
+            notes.push(format!(
+                "Only {:.2}% of ingested spans are indexed; unindexed traces are only searchable in Live Search for 15 minutes",
+                ratio * 100.0
+            ));
+        }
+
+        let active_matching = filters
+            .iter()
+            .filter(|f| f["enabled"].as_bool() != Some(false) && f["may_match"] == json!(true))
+            .count();
+        if active_matching == 0 {
+            notes.push(format!(
+                "No enabled retention filter appears to match {}; its spans are not retained beyond Live Search",
+                service.unwrap_or("these spans")
+            ));
+        }
+
+        notes
+    }
+
+    pub async fn diagnostics(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = ApmSamplingHandler;
+
+        let time = handler.parse_time(params, 1)?;
+        let TimeParams::Timestamp { from, to } = time;
+
+        let service = params["service"].as_str().filter(|s| !s.is_empty());
+        let scope = Self::metric_scope(params);
+
+        let ingested_query = format!("sum:{}{{{}}}.as_count()", INGESTED_SPANS_METRIC, scope);
+        let indexed_query = format!("sum:{}{{{}}}.as_count()", INDEXED_SPANS_METRIC, scope);
+        let reason_query = format!(
+            "sum:{}{{{}}} by {{ingestion_reason}}.as_count()",
+            INGESTED_SPANS_METRIC, scope
+        );
+
+        let (ingested, indexed, by_reason, filters) = tokio::try_join!(
+            client.query_metrics(&ingested_query, from, to),
+            client.query_metrics(&indexed_query, from, to),
+            client.query_metrics(&reason_query, from, to),
+            client.list_apm_retention_filters(),
+        )?;
+
+        let ingested_total = Self::response_total(&ingested);
+        let indexed_total = Self::response_total(&indexed);
+        let retention_filters = Self::summarize_retention_filters(&filters, service);
+        let notes = Self::diagnose(ingested_total, indexed_total, &retention_filters, service);
+
+        let indexed_ratio = if ingested_total > 0.0 {
+            Some((indexed_total / ingested_total * 10000.0).round() / 10000.0)
+        } else {
+            None
+        };
+
+        let data = json!({
+            "ingestion": {
+                "ingested_spans": ingested_total as i64,
+                "indexed_spans": indexed_total as i64,
+                "indexed_ratio": indexed_ratio,
+                "by_ingestion_reason": Self::totals_by_tag(&by_reason, "ingestion_reason"),
+            },
+            "retention_filters": retention_filters,
+            "notes": notes,
+        });
+
+        let meta = json!({
+            "scope": scope,
+            "from": crate::utils::format_timestamp(from),
+            "to": crate::utils::format_timestamp(to),
+        });
+
+        Ok(handler.format_list(data, None, Some(meta)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics_response(series: Value) -> MetricsResponse {
+        serde_json::from_value(json!({
+            "status": "ok",
+            "res_type": "time_series",
+            "from_date": 0,
+            "to_date": 0,
+            "query": "q",
+            "series": series
+        }))
+        .unwrap()
+    }
+
+    fn series(tag_set: &[&str], values: &[f64]) -> Value {
+        json!({
+            "metric": INGESTED_SPANS_METRIC,
+            "scope": "*",
+            "expression": "e",
+            "tag_set": tag_set,
+            "pointlist": values.iter().enumerate().map(|(i, v)| json!([i as f64, v])).collect::<Vec<_>>()
+        })
+    }
+
+    #[test]
+    fn test_metric_scope() {
+        assert_eq!(ApmSamplingHandler::metric_scope(&json!({})), "*");
+        assert_eq!(
+            ApmSamplingHandler::metric_scope(&json!({"service": "web", "env": "prod"})),
+            "service:web,env:prod"
+        );
+    }
+
+    #[test]
+    fn test_totals() {
+        let response = metrics_response(json!([
+            series(&["ingestion_reason:auto"], &[10.0, 5.0]),
+            series(&["ingestion_reason:rule"], &[2.0]),
+        ]));
+
+        assert_eq!(ApmSamplingHandler::response_total(&response), 17.0);
+
+        let by_reason = ApmSamplingHandler::totals_by_tag(&response, "ingestion_reason");
+        assert_eq!(by_reason["auto"], 15);
+        assert_eq!(by_reason["rule"], 2);
+    }
+
+    #[test]
+    fn test_filter_may_match() {
+        assert!(ApmSamplingHandler::filter_may_match("*", Some("web")));
+        assert!(ApmSamplingHandler::filter_may_match(
+            "service:web @http.status_code:500",
+            Some("web")
+        ));
+        assert!(ApmSamplingHandler::filter_may_match(
+            "status:error",
+            Some("web")
+        ));
+        assert!(!ApmSamplingHandler::filter_may_match(
+            "service:api",
+            Some("web")
+        ));
+    }
+
+    #[test]
+    fn test_diagnose_low_ratio_and_no_filters() {
+        let filters = ApmSamplingHandler::summarize_retention_filters(
+            &json!({"data": [{
+                "id": "f1",
+                "attributes": {"name": "api errors", "enabled": true, "rate": 1.0, "filter": {"query": "service:api"}}
+            }]}),
+            Some("web"),
+        );
+
+        let notes = ApmSamplingHandler::diagnose(100_000.0, 10.0, &filters, Some("web"));
+        assert_eq!(notes.len(), 2);
+        assert!(notes[0].contains("0.01%"));
+        assert!(notes[1].contains("web"));
+    }
+
+    #[test]
+    fn test_diagnose_nothing_ingested() {
+        let notes = ApmSamplingHandler::diagnose(0.0, 0.0, &[], None);
+        assert_eq!(notes.len(), 1);
+        assert!(notes[0].contains("No spans were ingested"));
+    }
+}
@@ -1,13 +1,247 @@
 use crate::error::{DatadogError, Result};
-use crate::utils::parse_time;
+use crate::utils::{format_timestamp, parse_range, parse_time};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use std::cell::RefCell;
 use std::collections::HashMap;
 
 /// Response filtering constants
 pub const DEFAULT_STACK_TRACE_LINES: usize = 10;
 pub const MAX_STRING_LENGTH: usize = 100;
 
+/// Upper bound for the `precision` option; f64 carries about 17 significant digits
+pub const MAX_PRECISION: u32 = 15;
+
+/// Round to `precision` significant digits without dropping integer digits,
+/// so 12.345678 becomes 12.3 at precision 3 while timestamps stay intact
+pub fn round_significant(value: f64, precision: u32) -> f64 {
+    if !value.is_finite() || value == 0.0 {
+        return value;
+    }
+
+    let magnitude = value.abs().log10().floor() as i32 + 1;
+    let decimals = (precision.min(MAX_PRECISION) as i32 - magnitude).max(0);
+    if decimals == 0 {
+        return value.round();
+    }
+
+    let factor = 10f64.powi(decimals);
+    let rounded = (value * factor).round() / factor;
+    if rounded.is_finite() { rounded } else { value }
+}
+
+/// Nearest-rank percentile (0-100) of ascending `sorted` values
+pub fn percentile(sorted: &[f64], pct: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((pct / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+    sorted.get(rank.min(sorted.len() - 1)).copied()
+}
+
+/// Apply `round_significant` to every float in a response; integers are left alone
+pub fn apply_precision(value: &mut Value, precision: u32) {
+    match value {
+        Value::Number(n) if n.is_f64() => {
+            if let Some(rounded) = n
+                .as_f64()
+                .and_then(|f| serde_json::Number::from_f64(round_significant(f, precision)))
+            {
+                *n = rounded;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| apply_precision(v, precision)),
+        Value::Object(map) => map.values_mut().for_each(|v| apply_precision(v, precision)),
+        _ => {}
+    }
+}
+
+/// Lower bound for the `max_response_bytes` option, leaving room for the truncation notice
+pub const MIN_RESPONSE_BYTES: u64 = 1024;
+
+/// Drop trailing items of the response's `data` array until the pretty-printed response fits
+/// in `max_bytes`, and say so in `meta`. Responses without a `data` array are left alone.
+/// Returns whether anything was dropped.
+pub fn apply_size_budget(response: &mut Value, max_bytes: usize) -> bool {
+    let size = |v: &Value| serde_json::to_string_pretty(v).map_or(0, |s| s.len());
+    let original_bytes = size(response);
+    if original_bytes <= max_bytes {
+        return false;
+    }
+    let Some(items) = response["data"].as_array().cloned() else {
+        return false;
+    };
+    if !response["meta"].is_object() {
+        response["meta"] = json!({});
+    }
+
+    let total = items.len();
+    let keep = |n: usize, response: &mut Value| {
+        response["data"] = Value::Array(items[..n].to_vec());
+        let meta = &mut response["meta"];
+        meta["truncated"] = json!(true);
+        meta["total_items"] = json!(total);
+        meta["returned_items"] = json!(n);
+        meta["original_bytes"] = json!(original_bytes);
+        meta["max_response_bytes"] = json!(max_bytes);
+        meta["hint"] = json!(format!(
+            "Response exceeded {} bytes, so only the first {} of {} items are shown. Narrow the query or time range, lower limit, or raise max_response_bytes.",
+            max_bytes, n, total
+        ));
+        size(response) <= max_bytes
+    };
+
+    // Largest prefix that fits, found by binary search over the item count
+    let (mut low, mut high) = (0, total);
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+        if keep(mid, response) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    keep(low, response);
+    true
+}
+
+/// How a tool result is written into the MCP text content (the `format` argument)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    Markdown,
+    Csv,
+}
+
+impl OutputFormat {
+    pub const NAMES: &'static [&'static str] = &["json", "markdown", "csv"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(OutputFormat::Json),
+            "markdown" => Some(OutputFormat::Markdown),
+            "csv" => Some(OutputFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// Flatten nested objects of a row into dotted columns ("attributes.service")
+fn flatten_row(prefix: &str, value: &Value, row: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let column = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_row(&column, value, row);
+            }
+        }
+        _ => row.push((prefix.to_string(), value.clone())),
+    }
+}
+
+/// Cell text: strings unquoted, scalar lists comma-joined, other nesting as compact JSON
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) if items.iter().all(|v| !v.is_object() && !v.is_array()) => {
+            items.iter().map(cell_text).collect::<Vec<_>>().join(", ")
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Render a tool result as text. Markdown and CSV turn the `data` list into a table, one
+/// row per item, with the other top-level fields (pagination, meta) as compact JSON
+/// lines; results without a `data` list stay pretty-printed JSON.
+pub fn render_response(response: &Value, format: OutputFormat) -> String {
+    let pretty = || {
+        serde_json::to_string_pretty(response)
+            .unwrap_or_else(|_| "Error formatting response".to_string())
+    };
+    let (OutputFormat::Markdown | OutputFormat::Csv, Some(items)) =
+        (format, response["data"].as_array())
+    else {
+        return pretty();
+    };
+
+    let rows: Vec<Vec<(String, Value)>> = items
+        .iter()
+        .map(|item| {
+            let mut row = Vec::new();
+            flatten_row(if item.is_object() { "" } else { "value" }, item, &mut row);
+            row
+        })
+        .collect();
+    let mut columns: Vec<&str> = Vec::new();
+    for (column, _) in rows.iter().flatten() {
+        if !columns.contains(&column.as_str()) {
+            columns.push(column);
+        }
+    }
+    let header = columns.iter().map(|c| c.to_string()).collect();
+    let body = rows.iter().map(|row| {
+        columns
+            .iter()
+            .map(|c| {
+                row.iter()
+                    .find(|(k, _)| k == c)
+                    .map_or(String::new(), |(_, v)| cell_text(v))
+            })
+            .collect()
+    });
+    let table: Vec<Vec<String>> = std::iter::once(header).chain(body).collect();
+    let extras: Vec<(&String, &Value)> = response
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| key.as_str() != "data")
+        .collect();
+
+    let mut out = Vec::new();
+    match format {
+        OutputFormat::Markdown => {
+            let escape = |s: &String| s.replace('|', "\\|").replace('\n', " ");
+            for (i, row) in table.iter().enumerate() {
+                let row: Vec<String> = row.iter().map(escape).collect();
+                out.push(format!("| {} |", row.join(" | ")));
+                if i == 0 {
+                    out.push(format!("|{}", " --- |".repeat(columns.len())));
+                }
+            }
+            if !extras.is_empty() {
+                out.push(String::new());
+            }
+            for (key, value) in extras {
+                out.push(format!("**{}**: `{}`", key, value));
+            }
+        }
+        OutputFormat::Csv => {
+            // Before the header, as comments, so the rest parses as plain CSV
+            for (key, value) in extras {
+                out.push(format!("# {}: {}", key, value));
+            }
+            let quote = |s: &String| {
+                if s.contains([',', '"', '\n', '\r']) {
+                    format!("\"{}\"", s.replace('"', "\"\""))
+                } else {
+                    s.clone()
+                }
+            };
+            for row in &table {
+                out.push(row.iter().map(quote).collect::<Vec<_>>().join(","));
+            }
+        }
+        OutputFormat::Json => unreachable!("handled above"),
+    }
+    out.join("\n")
+}
+
 /// Unified pagination structure
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PaginationInfo {
@@ -72,16 +306,121 @@ pub enum TimeParams {
     Timestamp { from: i64, to: i64 },
 }
 
+/// Longest retention, in days, of the products whose windows are clamped
+pub const METRICS_RETENTION_DAYS: i64 = 455; // 15 months
+pub const LOGS_RETENTION_DAYS: i64 = 455; // Flex Logs; standard indexes keep less
+pub const SPANS_RETENTION_DAYS: i64 = 30;
+pub const RUM_RETENTION_DAYS: i64 = 30;
+
+/// Datadog limit on metric name length
+pub const MAX_METRIC_NAME_LEN: usize = 200;
+
+/// Check a metric name a tool is about to create or write to
+pub fn validate_metric_name(name: &str) -> Result<()> {
+    if name.len() > MAX_METRIC_NAME_LEN
+        || !name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+    {
+        return Err(DatadogError::InvalidInput(format!(
+            "Invalid metric name '{}': must start with a letter and contain only letters, digits, '_' and '.'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+tokio::task_local! {
+    /// Warnings raised while handling the current tool call
+    static CALL_WARNINGS: RefCell<Vec<String>>;
+}
+
+/// Run `future` (one tool call), returning its output with the warnings it raised
+pub async fn collect_warnings<F: std::future::Future>(future: F) -> (F::Output, Vec<String>) {
+    CALL_WARNINGS
+        .scope(RefCell::new(Vec::new()), async {
+            let output = future.await;
+            (output, CALL_WARNINGS.with(|warnings| warnings.take()))
+        })
+        .await
+}
+
+/// Report a warning in the current tool call's meta.warnings (logged outside a tool call)
+pub fn push_warning(message: String) {
+    if let Err(e) = CALL_WARNINGS.try_with(|warnings| warnings.borrow_mut().push(message.clone())) {
+        log::warn!("{} ({})", message, e);
+    }
+}
+
+/// Append `warnings` to the response's meta.warnings
+pub fn attach_warnings(response: &mut Value, warnings: Vec<String>) {
+    if warnings.is_empty()
+        || !response.is_object()
+        || !(response["meta"].is_object() || response["meta"].is_null())
+    {
+        return;
+    }
+    match response["meta"]["warnings"].as_array_mut() {
+        Some(existing) => existing.extend(warnings.into_iter().map(Value::String)),
+        None => response["meta"]["warnings"] = json!(warnings),
+    }
+}
+
 pub trait TimeHandler {
+    /// How far back the product keeps data; windows reaching further are clamped
+    fn retention_days(&self) -> Option<i64> {
+        None
+    }
+
     /// Parse time parameters from request - always returns timestamps
+    /// A `range` shorthand ("last 15m", "today", "now-6h") takes precedence over from/to.
+    /// A reversed window is swapped and one beyond the retention clamped, with a warning.
     fn parse_time(&self, params: &Value, _api_version: u8) -> Result<TimeParams> {
-        let from_str = params["from"].as_str().unwrap_or("1 hour ago").to_string();
+        let (from, to) = match params["range"].as_str() {
+            Some(range) => parse_range(range)?,
+            None => {
+                let from_str = params["from"].as_str().unwrap_or("1 hour ago");
+                let to_str = params["to"].as_str().unwrap_or("now");
+
+                // Always parse to timestamps - individual APIs handle their own format conversion
+                (parse_time(from_str)?, parse_time(to_str)?)
+            }
+        };
 
-        let to_str = params["to"].as_str().unwrap_or("now").to_string();
+        let (mut from, to) = if from > to {
+            push_warning(format!(
+                "'from' ({}) was after 'to' ({}); the window was swapped",
+                format_timestamp(from),
+                format_timestamp(to)
+            ));
+            (to, from)
+        } else {
+            (from, to)
+        };
+
+        if let Some(days) = self.retention_days() {
+            let earliest = chrono::Utc::now().timestamp() - days * 86400;
+            if to < earliest {
+                return Err(DatadogError::InvalidInput(format!(
+                    "The window {} - {} ends before the {}-day retention; the oldest available data is from {}",
+                    format_timestamp(from),
+                    format_timestamp(to),
+                    days,
+                    format_timestamp(earliest)
+                )));
+            }
+            if from < earliest {
+                push_warning(format!(
+                    "'from' ({}) is beyond the {}-day retention; clamped to {}",
+                    format_timestamp(from),
+                    days,
+                    format_timestamp(earliest)
+                ));
+                from = earliest;
+            }
+        }
 
-        // Always parse to timestamps - individual APIs handle their own format conversion
-        let from = parse_time(&from_str)?;
-        let to = parse_time(&to_str)?;
         Ok(TimeParams::Timestamp { from, to })
     }
 
@@ -116,22 +455,108 @@ pub trait Paginator {
     }
 }
 
+/// One comma-separated entry of a tag filter
+#[derive(Debug, Clone)]
+pub enum TagPattern {
+    Prefix(String),
+    Suffix(String),
+    Contains(String),
+    Regex(regex::Regex),
+}
+
+impl TagPattern {
+    /// `re:<regex>`, `*suffix`, `*substring*`, or a prefix (optionally written `prefix*`)
+    fn parse(pattern: &str) -> Result<Self> {
+        if let Some(regex) = pattern.strip_prefix("re:") {
+            return regex::Regex::new(regex)
+                .map(TagPattern::Regex)
+                .map_err(|e| {
+                    DatadogError::InvalidInput(format!(
+                        "Invalid tag filter regex '{}': {}",
+                        regex, e
+                    ))
+                });
+        }
+
+        let leading = pattern.starts_with('*');
+        let trailing = pattern.ends_with('*');
+        let text = pattern.trim_matches('*').to_string();
+        Ok(match (leading, trailing) {
+            (true, true) => TagPattern::Contains(text),
+            (true, false) => TagPattern::Suffix(text),
+            _ => TagPattern::Prefix(text),
+        })
+    }
+
+    fn matches(&self, tag: &str) -> bool {
+        match self {
+            TagPattern::Prefix(prefix) => tag.starts_with(prefix.as_str()),
+            TagPattern::Suffix(suffix) => tag.ends_with(suffix.as_str()),
+            TagPattern::Contains(text) => tag.contains(text.as_str()),
+            TagPattern::Regex(regex) => regex.is_match(tag),
+        }
+    }
+}
+
+/// A parsed `tag_filter` argument (or DD_TAG_FILTER default):
+/// - "*" = all tags (no filtering)
+/// - "" = no tags
+/// - otherwise comma-separated patterns: `env:` (prefix), `*-canary` (suffix),
+///   `*kube*` (contains), `re:^env:(prod|staging)$` (regex); a leading `!` excludes
+///   matching tags instead. A tag is kept when it matches an inclusion pattern (or there
+///   are only exclusions) and no exclusion pattern. Regexes cannot contain commas.
+#[derive(Debug, Clone)]
+pub enum TagSelector {
+    All,
+    Nothing,
+    Patterns {
+        include: Vec<TagPattern>,
+        exclude: Vec<TagPattern>,
+    },
+}
+
+impl TagSelector {
+    pub fn parse(filter: &str) -> Result<Self> {
+        match filter.trim() {
+            "*" => return Ok(TagSelector::All),
+            "" => return Ok(TagSelector::Nothing),
+            _ => {}
+        }
+
+        let mut include = Vec::new();
+        let mut exclude = Vec::new();
+        for pattern in filter.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match pattern.strip_prefix('!') {
+                Some(excluded) => exclude.push(TagPattern::parse(excluded)?),
+                None => include.push(TagPattern::parse(pattern)?),
+            }
+        }
+        Ok(TagSelector::Patterns { include, exclude })
+    }
+
+    pub fn matches(&self, tag: &str) -> bool {
+        match self {
+            TagSelector::All => true,
+            TagSelector::Nothing => false,
+            TagSelector::Patterns { include, exclude } => {
+                (include.is_empty() || include.iter().any(|p| p.matches(tag)))
+                    && !exclude.iter().any(|p| p.matches(tag))
+            }
+        }
+    }
+}
+
 pub trait TagFilter {
-    /// Filter tags based on filter mode
-    /// - "*" = return all tags (no filtering)
-    /// - "" = return empty vec (exclude all tags)
-    /// - "prefix1:,prefix2:" = return only tags starting with specified prefixes
-    fn filter_tags(&self, tags: &[String], filter: &str) -> Vec<String> {
+    /// Tags the filter keeps (see `TagSelector`)
+    fn filter_tags(&self, tags: &[String], filter: &TagSelector) -> Vec<String> {
         match filter {
-            "*" => tags.to_vec(),
-            "" => Vec::new(),
-            filter => {
-                let prefixes: Vec<&str> = filter.split(',').map(str::trim).collect();
-                tags.iter()
-                    .filter(|tag| prefixes.iter().any(|p| tag.starts_with(p)))
-                    .cloned()
-                    .collect()
-            }
+            TagSelector::All => tags.to_vec(),
+            TagSelector::Nothing => Vec::new(),
+            filter => tags
+                .iter()
+                .filter(|tag| filter.matches(tag))
+                .cloned()
+                .collect(),
         }
     }
 
@@ -139,21 +564,16 @@ pub trait TagFilter {
     fn filter_tags_map(
         &self,
         tags_map: Option<&HashMap<String, Vec<String>>>,
-        filter: &str,
+        filter: &TagSelector,
     ) -> Option<HashMap<String, Vec<String>>> {
         match filter {
-            "*" => tags_map.cloned(),
-            "" => None,
+            TagSelector::All => tags_map.cloned(),
+            TagSelector::Nothing => None,
             filter => tags_map.map(|map| {
-                let prefixes: Vec<&str> = filter.split(',').map(str::trim).collect();
                 let mut filtered_map = HashMap::new();
 
                 for (source, tags) in map.iter() {
-                    let filtered_tags: Vec<String> = tags
-                        .iter()
-                        .filter(|tag| prefixes.iter().any(|p| tag.starts_with(p)))
-                        .cloned()
-                        .collect();
+                    let filtered_tags = self.filter_tags(tags, filter);
 
                     if !filtered_tags.is_empty() {
                         filtered_map.insert(source.clone(), filtered_tags);
@@ -187,6 +607,36 @@ pub trait ResponseFilter {
         }
     }
 
+    /// Keep only the dotted paths listed in the `fields` argument (e.g. `["id",
+    /// "attributes.service"]`) of each item; without `fields` items are returned whole.
+    /// Paths an item lacks are left out rather than set to null.
+    fn project_fields(&self, items: Vec<Value>, params: &Value) -> Vec<Value> {
+        let Some(fields) = params["fields"].as_array().filter(|f| !f.is_empty()) else {
+            return items;
+        };
+        let paths: Vec<Vec<&str>> = fields
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|f| f.split('.').collect())
+            .collect();
+
+        items
+            .iter()
+            .map(|item| {
+                let mut projected = json!({});
+                for path in &paths {
+                    // Intermediate values exist in `item`, so they are objects here too
+                    if let Some(value) = path.iter().try_fold(item, |v, key| v.get(key)) {
+                        *path
+                            .iter()
+                            .fold(&mut projected, |target, key| &mut target[*key]) = value.clone();
+                    }
+                }
+                projected
+            })
+            .collect()
+    }
+
     /// Truncate long strings (>max_len chars)
     fn truncate_long_string(&self, s: &str, max_len: usize) -> String {
         if s.len() <= max_len {
@@ -234,6 +684,161 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_round_significant() {
+        assert_eq!(round_significant(12.345678901234567, 3), 12.3);
+        assert_eq!(round_significant(0.000123456, 3), 0.000123);
+        assert_eq!(round_significant(-98.765, 2), -99.0);
+        // Integer digits are never rounded away
+        assert_eq!(round_significant(1712345678123.0, 3), 1712345678123.0);
+        assert_eq!(round_significant(0.0, 3), 0.0);
+        assert!(round_significant(f64::NAN, 3).is_nan());
+    }
+
+    #[test]
+    fn test_project_fields() {
+        struct Handler;
+        impl ResponseFilter for Handler {}
+
+        let items = vec![
+            json!({"id": "a", "attributes": {"service": "web", "status": "error", "tags": ["env:prod"]}}),
+            json!({"id": "b", "attributes": {"status": "ok"}}),
+        ];
+
+        let params = json!({"fields": ["id", "attributes.service", "attributes.status"]});
+        let projected = Handler.project_fields(items.clone(), &params);
+        assert_eq!(
+            projected[0],
+            json!({"id": "a", "attributes": {"service": "web", "status": "error"}})
+        );
+        assert_eq!(
+            projected[1],
+            json!({"id": "b", "attributes": {"status": "ok"}})
+        );
+
+        let unchanged = Handler.project_fields(items.clone(), &json!({}));
+        assert_eq!(unchanged, items);
+    }
+
+    #[test]
+    fn test_tag_selector() {
+        struct Handler;
+        impl TagFilter for Handler {}
+
+        let tags: Vec<String> = [
+            "env:prod",
+            "env:staging-canary",
+            "kube_namespace:web",
+            "service:web",
+            "team:kube-platform",
+        ]
+        .iter()
+        .map(|t| t.to_string())
+        .collect();
+        let kept = |filter: &str| Handler.filter_tags(&tags, &TagSelector::parse(filter).unwrap());
+
+        assert_eq!(kept("*").len(), 5);
+        assert!(kept("").is_empty());
+        assert_eq!(
+            kept("env:,service:"),
+            ["env:prod", "env:staging-canary", "service:web"]
+        );
+        assert_eq!(kept("env:*"), ["env:prod", "env:staging-canary"]);
+        assert_eq!(kept("*-canary"), ["env:staging-canary"]);
+        assert_eq!(kept("*kube*"), ["kube_namespace:web", "team:kube-platform"]);
+        assert_eq!(
+            kept("!kube_,!team:"),
+            ["env:prod", "env:staging-canary", "service:web"]
+        );
+        assert_eq!(kept("env:,!*-canary"), ["env:prod"]);
+        assert_eq!(kept("re:^env:(prod|staging)$"), ["env:prod"]);
+        assert_eq!(
+            kept("!re:^(env|service):"),
+            ["kube_namespace:web", "team:kube-platform"]
+        );
+
+        let error = TagSelector::parse("re:env:(prod").unwrap_err();
+        assert!(matches!(error, DatadogError::InvalidInput(_)));
+    }
+
+    #[test]
+    fn test_render_response() {
+        let response = json!({
+            "data": [
+                {"id": 1, "name": "CPU high", "tags": ["env:prod", "team:a"], "creator": {"handle": "kim"}},
+                {"id": 2, "name": "Disk, full", "status": "Alert"}
+            ],
+            "pagination": {"page": 0, "has_next": false}
+        });
+
+        let markdown = render_response(&response, OutputFormat::Markdown);
+        let lines: Vec<&str> = markdown.lines().collect();
+        // Columns in first-seen order; serde_json keeps object keys sorted
+        assert_eq!(lines[0], "| creator.handle | id | name | tags | status |");
+        assert_eq!(lines[1], "| --- | --- | --- | --- | --- |");
+        assert_eq!(lines[2], "| kim | 1 | CPU high | env:prod, team:a |  |");
+        assert_eq!(lines[5], r#"**pagination**: `{"has_next":false,"page":0}`"#);
+
+        let csv = render_response(&response, OutputFormat::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], r#"# pagination: {"has_next":false,"page":0}"#);
+        assert_eq!(lines[1], "creator.handle,id,name,tags,status");
+        assert_eq!(lines[2], r#"kim,1,CPU high,"env:prod, team:a","#);
+        assert_eq!(lines[3], r#",2,"Disk, full",,Alert"#);
+
+        // Detail results have no table form
+        let detail = json!({"data": {"id": 1}});
+        assert_eq!(
+            render_response(&detail, OutputFormat::Csv),
+            serde_json::to_string_pretty(&detail).unwrap()
+        );
+        assert_eq!(OutputFormat::parse("yaml"), None);
+    }
+
+    #[test]
+    fn test_apply_size_budget() {
+        let items: Vec<Value> = (0..100)
+            .map(|i| json!({"message": format!("log line {}", i)}))
+            .collect();
+        let mut response = json!({"data": items, "meta": {"query": "*"}});
+
+        assert!(apply_size_budget(&mut response, 2000));
+        let returned = response["data"].as_array().unwrap().len();
+        assert!(returned > 0 && returned < 100);
+        assert!(serde_json::to_string_pretty(&response).unwrap().len() <= 2000);
+        assert_eq!(response["meta"]["truncated"], true);
+        assert_eq!(response["meta"]["total_items"], 100);
+        assert_eq!(response["meta"]["returned_items"], returned);
+        assert_eq!(response["meta"]["query"], "*");
+        assert!(
+            response["meta"]["hint"]
+                .as_str()
+                .unwrap()
+                .contains("Narrow the query")
+        );
+
+        // Already within budget, or nothing to cut
+        let mut small = json!({"data": [1, 2, 3]});
+        assert!(!apply_size_budget(&mut small, 2000));
+        assert!(small.get("meta").is_none());
+        let mut detail = json!({"data": {"text": "x".repeat(5000)}});
+        assert!(!apply_size_budget(&mut detail, 2000));
+    }
+
+    #[test]
+    fn test_apply_precision() {
+        let mut value = json!({
+            "data": [{"pointlist": [[1712345678000.0, 12.345678]], "count": 7}],
+            "meta": {"ratio": 0.333333333}
+        });
+        apply_precision(&mut value, 3);
+
+        assert_eq!(value["data"][0]["pointlist"][0][0], 1712345678000.0);
+        assert_eq!(value["data"][0]["pointlist"][0][1], 12.3);
+        assert_eq!(value["data"][0]["count"], 7);
+        assert_eq!(value["meta"]["ratio"], 0.333);
+    }
+
     struct TestHandler;
     impl TimeHandler for TestHandler {}
     impl Paginator for TestHandler {}
@@ -256,6 +861,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_time_handler_range() {
+        let handler = TestHandler;
+        let params = json!({"range": "last 15m", "from": "1609459200"});
+
+        let TimeParams::Timestamp { from, to } = handler.parse_time(&params, 1).unwrap();
+        assert_eq!(to - from, 900);
+
+        assert!(handler.parse_time(&json!({"range": "soon"}), 1).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_time_handler_swaps_reversed_window() {
+        let handler = TestHandler;
+        let params = json!({"from": "1609462800", "to": "1609459200"});
+
+        let (result, warnings) = collect_warnings(async { handler.parse_time(&params, 1) }).await;
+        let TimeParams::Timestamp { from, to } = result.unwrap();
+        assert_eq!((from, to), (1609459200, 1609462800));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("was swapped"));
+    }
+
+    struct RetainedHandler;
+    impl TimeHandler for RetainedHandler {
+        fn retention_days(&self) -> Option<i64> {
+            Some(30)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_time_handler_retention() {
+        let handler = RetainedHandler;
+
+        let (result, warnings) =
+            collect_warnings(async { handler.parse_time(&json!({"from": "60 days ago"}), 1) })
+                .await;
+        let TimeParams::Timestamp { from, to } = result.unwrap();
+        assert!((to - from - 30 * 86400).abs() < 5);
+        assert!(warnings[0].contains("30-day retention; clamped"));
+
+        let Err(error) =
+            handler.parse_time(&json!({"from": "60 days ago", "to": "45 days ago"}), 1)
+        else {
+            panic!("expected a retention error");
+        };
+        assert!(
+            error
+                .to_string()
+                .contains("ends before the 30-day retention")
+        );
+    }
+
+    #[test]
+    fn test_attach_warnings() {
+        let mut response = json!({"data": [], "meta": {"warnings": ["first"]}});
+        attach_warnings(&mut response, vec!["second".to_string()]);
+        assert_eq!(response["meta"]["warnings"], json!(["first", "second"]));
+
+        let mut response = json!({"data": []});
+        attach_warnings(&mut response, vec!["only".to_string()]);
+        assert_eq!(response["meta"]["warnings"], json!(["only"]));
+
+        let mut response = json!({"data": []});
+        attach_warnings(&mut response, vec![]);
+        assert!(response.get("meta").is_none());
+    }
+
     #[test]
     fn test_time_handler_defaults() {
         let handler = TestHandler;
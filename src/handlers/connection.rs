@@ -0,0 +1,107 @@
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::ApplicationKeysResponse;
+use crate::error::{DatadogError, Result};
+
+pub struct ConnectionHandler;
+
+impl ConnectionHandler {
+    fn error_json(error: &DatadogError) -> Value {
+        json!({
+            "error": error.to_string(),
+            "hint": error.suggestion(),
+        })
+    }
+
+    /// The listed key matching DD_APP_KEY, found by its last four characters
+    fn application_key_json(keys: &ApplicationKeysResponse, last4: &str) -> Value {
+        let key = keys
+            .data
+            .iter()
+            .filter_map(|key| key.attributes.as_ref().map(|attrs| (key, attrs)))
+            .find(|(_, attrs)| attrs.last4.as_deref() == Some(last4));
+
+        match key {
+            Some((key, attrs)) => json!({
+                "valid": true,
+                "id": key.id,
+                "name": attrs.name,
+                "last4": last4,
+                "created_at": attrs.created_at,
+                "scopes": attrs.scopes.as_ref().map_or(json!("unrestricted"), |s| json!(s)),
+            }),
+            None => json!({
+                "valid": true,
+                "last4": last4,
+                "scopes": null,
+                "note": "Key not among the current user's application keys (service account or org key); scopes unknown",
+            }),
+        }
+    }
+
+    /// Auth status of the configured keys: API key validity, and the application key's
+    /// owner-listed name and scopes
+    pub async fn status(client: Arc<dyn DatadogApi>, _params: &Value) -> Result<Value> {
+        let api_key = match client.validate().await {
+            Ok(_) => json!({"valid": true}),
+            Err(e) => {
+                let mut status = Self::error_json(&e);
+                status["valid"] = json!(false);
+                status
+            }
+        };
+
+        let application_key = match client.list_current_user_application_keys().await {
+            Ok(keys) => Self::application_key_json(&keys, client.app_key_last4()),
+            Err(e) => {
+                let mut status = Self::error_json(&e);
+                // 403 here means the key works but lacks permission to list keys
+                status["valid"] = match e {
+                    DatadogError::AuthError { .. } if api_key["valid"] == true => Value::Null,
+                    _ => json!(false),
+                };
+                status
+            }
+        };
+
+        let connected = api_key["valid"] == true && application_key["valid"] != false;
+
+        Ok(json!({
+            "connected": connected,
+            "site": client.site(),
+            "api_url": client.base_url(),
+            "mode": if client.writes_allowed() { "read_write" } else { "read_only" },
+            "api_key": api_key,
+            "application_key": application_key,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_application_key_json() {
+        let keys: ApplicationKeysResponse = serde_json::from_value(json!({
+            "data": [
+                {"id": "k1", "attributes": {"name": "ci", "last4": "aaaa", "scopes": null}},
+                {"id": "k2", "attributes": {"name": "mcp", "last4": "b1c2", "scopes": ["metrics_read"]}}
+            ]
+        }))
+        .unwrap();
+
+        let key = ConnectionHandler::application_key_json(&keys, "b1c2");
+        assert_eq!(key["name"], "mcp");
+        assert_eq!(key["scopes"], json!(["metrics_read"]));
+
+        let key = ConnectionHandler::application_key_json(&keys, "aaaa");
+        assert_eq!(key["scopes"], "unrestricted");
+
+        let key = ConnectionHandler::application_key_json(&keys, "zzzz");
+        assert!(key["scopes"].is_null());
+        assert!(key["note"].is_string());
+    }
+}
@@ -0,0 +1,968 @@
+use futures::FutureExt;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::batch::{ApiCall, execute_batch};
+use crate::datadog::models::{MetricSeries, TemplateVariable};
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::{ResponseFormatter, TimeHandler, TimeParams};
+use crate::handlers::logs_aggregate::LogsAggregateHandler;
+use crate::handlers::metrics::MetricsHandler;
+
+/// Widgets that render one reduced number per query (or per group)
+const VALUE_WIDGETS: &[&str] = &["query_value", "toplist"];
+
+/// Toplist rows returned when the caller gives no limit
+const DEFAULT_TOPLIST_LIMIT: usize = 10;
+
+pub struct DashboardWidgetsHandler;
+
+impl TimeHandler for DashboardWidgetsHandler {}
+impl ResponseFormatter for DashboardWidgetsHandler {}
+
+/// A `$name` template variable and the text it expands to
+pub(crate) struct TemplateReplacement {
+    pub(crate) name: String,
+    pub(crate) value: String,
+    scoped: String,
+}
+
+/// Tool calls and leftovers produced from one widget definition
+#[derive(Default)]
+pub(crate) struct Translation {
+    pub(crate) tool_calls: Vec<Value>,
+    pub(crate) unsupported: Vec<Value>,
+}
+
+impl DashboardWidgetsHandler {
+    /// Depth-first search for a widget by id, descending into group widgets
+    fn find_widget(widgets: &[Value], widget_id: i64) -> Option<&Value> {
+        widgets.iter().find_map(|widget| {
+            if widget["id"].as_i64() == Some(widget_id) {
+                return Some(&widget["definition"]);
+            }
+            widget["definition"]["widgets"]
+                .as_array()
+                .and_then(|nested| Self::find_widget(nested, widget_id))
+        })
+    }
+
+    /// Convert a widget live span (e.g., '4h', '1w', '3mo') into a relative time expression
+    pub(crate) fn live_span_to_from(span: &str) -> Option<String> {
+        let split = span.find(|c: char| !c.is_ascii_digit())?;
+        let (count, unit) = span.split_at(split);
+        let count: u32 = count.parse().ok()?;
+
+        let unit = match unit {
+            "m" => "minute",
+            "h" => "hour",
+            "d" => "day",
+            "w" => "week",
+            "mo" => "month",
+            "y" => "year",
+            _ => return None,
+        };
+
+        Some(format!(
+            "{} {}{} ago",
+            count,
+            unit,
+            if count == 1 { "" } else { "s" }
+        ))
+    }
+
+    pub(crate) fn template_replacements(
+        dashboard_vars: &[TemplateVariable],
+        overrides: &Value,
+    ) -> Vec<TemplateReplacement> {
+        let mut replacements: Vec<TemplateReplacement> = dashboard_vars
+            .iter()
+            .map(|var| {
+                (
+                    var.name.clone(),
+                    var.prefix.clone(),
+                    var.default_value.clone(),
+                )
+            })
+            .chain(
+                overrides
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .filter(|(name, _)| !dashboard_vars.iter().any(|v| &v.name == *name))
+                    .map(|(name, _)| (name.clone(), None, None)),
+            )
+            .map(|(name, prefix, default)| {
+                let value = overrides[&name]
+                    .as_str()
+                    .map(String::from)
+                    .or(default)
+                    .unwrap_or_else(|| "*".to_string());
+                let scoped = match prefix.as_deref() {
+                    Some(prefix) if value != "*" && !prefix.is_empty() => {
+                        format!("{}:{}", prefix, value)
+                    }
+                    _ => value.clone(),
+                };
+                TemplateReplacement {
+                    name,
+                    value,
+                    scoped,
+                }
+            })
+            .collect();
+
+        // Longest names first so $env never clobbers $environment
+        replacements.sort_by_key(|r| std::cmp::Reverse(r.name.len()));
+        replacements
+    }
+
+    fn apply_templates(query: &str, replacements: &[TemplateReplacement]) -> String {
+        replacements.iter().fold(query.to_string(), |query, r| {
+            query
+                .replace(&format!("${}.value", r.name), &r.value)
+                .replace(&format!("${}", r.name), &r.scoped)
+        })
+    }
+
+    /// Replace whole-word query names in a formula with their metric queries
+    fn inline_formula(formula: &str, queries: &[(String, String)]) -> Option<String> {
+        let mut result = String::new();
+        let mut token = String::new();
+        let mut used = false;
+
+        for c in formula.chars().chain(std::iter::once(' ')) {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                token.push(c);
+                continue;
+            }
+            if !token.is_empty() {
+                match queries.iter().find(|(name, _)| *name == token) {
+                    Some((_, query)) => {
+                        result.push_str(query);
+                        used = true;
+                    }
+                    None if token.chars().all(|c| c.is_ascii_digit()) => result.push_str(&token),
+                    // Formula functions (e.g., top(), anomalies()) have no v1 equivalent
+                    None => return None,
+                }
+                token.clear();
+            }
+            result.push(c);
+        }
+
+        used.then(|| result.trim_end().to_string())
+    }
+
+    fn logs_compute(
+        aggregation: &str,
+        metric: Option<&str>,
+        interval: &Value,
+        timeseries: bool,
+    ) -> Value {
+        let mut compute = json!({
+            "aggregation": aggregation,
+            "type": if timeseries { "timeseries" } else { "total" },
+        });
+        if let Some(metric) = metric {
+            compute["metric"] = json!(metric);
+        }
+        if let Some(interval) = interval.as_i64() {
+            compute["interval"] = json!(format!("{}ms", interval));
+        } else if let Some(interval) = interval.as_str() {
+            compute["interval"] = json!(interval);
+        }
+        compute
+    }
+
+    fn logs_group_by(group_by: &Value, metric_key: &str) -> Vec<Value> {
+        group_by
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|group| {
+                let facet = group["facet"].as_str()?;
+                let mut entry = json!({ "facet": facet });
+                if let Some(limit) = group["limit"].as_i64() {
+                    entry["limit"] = json!(limit);
+                }
+                if let Some(sort) = group["sort"].as_object() {
+                    let mut sort_json = json!({
+                        "order": sort.get("order").and_then(Value::as_str).unwrap_or("desc"),
+                        "aggregation": sort.get("aggregation").and_then(Value::as_str).unwrap_or("count"),
+                        "type": "measure",
+                    });
+                    if let Some(metric) = sort.get(metric_key).and_then(Value::as_str) {
+                        sort_json["metric"] = json!(metric);
+                    }
+                    entry["sort"] = sort_json;
+                }
+                Some(entry)
+            })
+            .collect()
+    }
+
+    /// Formula-style logs query (`data_source: logs`)
+    fn logs_arguments(query: &Value, timeseries: bool) -> Value {
+        let compute = &query["compute"];
+        let mut arguments = json!({
+            "query": query["search"]["query"].as_str().filter(|q| !q.is_empty()).unwrap_or("*"),
+            "compute": [Self::logs_compute(
+                compute["aggregation"].as_str().unwrap_or("count"),
+                compute["metric"].as_str(),
+                &compute["interval"],
+                timeseries,
+            )],
+        });
+        let group_by = Self::logs_group_by(&query["group_by"], "metric");
+        if !group_by.is_empty() {
+            arguments["group_by"] = json!(group_by);
+        }
+        arguments
+    }
+
+    /// Legacy widget `log_query` request
+    fn legacy_logs_arguments(log_query: &Value, timeseries: bool) -> Value {
+        let compute = &log_query["compute"];
+        let mut arguments = json!({
+            "query": log_query["search"]["query"].as_str().filter(|q| !q.is_empty()).unwrap_or("*"),
+            "compute": [Self::logs_compute(
+                compute["aggregation"].as_str().unwrap_or("count"),
+                compute["facet"].as_str(),
+                &compute["interval"],
+                timeseries,
+            )],
+        });
+        let group_by = Self::logs_group_by(&log_query["group_by"], "facet");
+        if !group_by.is_empty() {
+            arguments["group_by"] = json!(group_by);
+        }
+        arguments
+    }
+
+    fn translate_request(request: &Value, timeseries: bool, out: &mut Translation) {
+        if let Some(query) = request["q"].as_str() {
+            out.tool_calls.push(json!({
+                "tool": "datadog_metrics_query",
+                "arguments": { "query": query },
+            }));
+            return;
+        }
+
+        if request["log_query"].is_object() {
+            out.tool_calls.push(json!({
+                "tool": "datadog_logs_aggregate",
+                "arguments": Self::legacy_logs_arguments(&request["log_query"], timeseries),
+            }));
+            return;
+        }
+
+        let Some(queries) = request["queries"].as_array() else {
+            out.unsupported.push(json!({
+                "request": request,
+                "reason": "Request has no recognizable query",
+            }));
+            return;
+        };
+
+        let metrics: Vec<(String, String)> = queries
+            .iter()
+            .filter(|q| q["data_source"] == "metrics")
+            .filter_map(|q| {
+                Some((
+                    q["name"].as_str()?.to_string(),
+                    q["query"].as_str()?.to_string(),
+                ))
+            })
+            .collect();
+
+        // Metric-only formulas map onto v1 query arithmetic
+        let formulas: Vec<&Value> = request["formulas"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .collect();
+        let mut inlined = Vec::new();
+        if metrics.len() == queries.len() && !formulas.is_empty() {
+            for formula in &formulas {
+                let expression = formula["formula"].as_str().unwrap_or_default();
+                match Self::inline_formula(expression, &metrics) {
+                    Some(query) => inlined.push(json!({
+                        "tool": "datadog_metrics_query",
+                        "arguments": { "query": query },
+                        "source": formula.get("alias").filter(|a| a.is_string()).unwrap_or(&formula["formula"]),
+                    })),
+                    None => {
+                        inlined.clear();
+                        break;
+                    }
+                }
+            }
+        }
+        if !inlined.is_empty() {
+            out.tool_calls.extend(inlined);
+            return;
+        }
+
+        for query in queries {
+            let name = &query["name"];
+            match query["data_source"].as_str() {
+                Some("metrics") => out.tool_calls.push(json!({
+                    "tool": "datadog_metrics_query",
+                    "arguments": { "query": query["query"] },
+                    "source": name,
+                })),
+                Some("logs") => out.tool_calls.push(json!({
+                    "tool": "datadog_logs_aggregate",
+                    "arguments": Self::logs_arguments(query, timeseries),
+                    "source": name,
+                })),
+                other => out.unsupported.push(json!({
+                    "source": name,
+                    "data_source": other,
+                    "reason": "No equivalent tool for this data source",
+                })),
+            }
+        }
+
+        let plain_formulas: Vec<&str> = formulas
+            .iter()
+            .filter_map(|f| f["formula"].as_str())
+            .filter(|f| !queries.iter().any(|q| q["name"].as_str() == Some(*f)))
+            .collect();
+        if !plain_formulas.is_empty() {
+            out.unsupported.push(json!({
+                "formulas": plain_formulas,
+                "reason": "Formulas must be computed from the individual query results",
+            }));
+        }
+    }
+
+    pub(crate) fn translate(definition: &Value) -> Translation {
+        let mut out = Translation::default();
+        let timeseries = definition["type"] == "timeseries";
+
+        match &definition["requests"] {
+            Value::Array(requests) => {
+                for request in requests {
+                    Self::translate_request(request, timeseries, &mut out);
+                }
+            }
+            // Scatterplot and similar widgets key their requests by axis
+            Value::Object(requests) => {
+                for request in requests.values() {
+                    Self::translate_request(request, timeseries, &mut out);
+                }
+            }
+            _ => out.unsupported.push(json!({
+                "widget_type": definition["type"],
+                "reason": "Widget has no data requests",
+            })),
+        }
+
+        out
+    }
+
+    /// Widget definition and dashboard template variables from a raw widget or dashboard_id + widget_id
+    async fn load_widget(
+        client: &dyn DatadogApi,
+        params: &Value,
+    ) -> Result<(Value, Vec<TemplateVariable>)> {
+        if let Some(widget) = params["widget"].as_object() {
+            let definition = widget
+                .get("definition")
+                .unwrap_or(&params["widget"])
+                .clone();
+            Ok((definition, Vec::new()))
+        } else {
+            let dashboard_id = params["dashboard_id"].as_str().ok_or_else(|| {
+                DatadogError::InvalidInput(
+                    "Provide either 'widget' or both 'dashboard_id' and 'widget_id'".to_string(),
+                )
+            })?;
+            let widget_id = params["widget_id"].as_i64().ok_or_else(|| {
+                DatadogError::InvalidInput("Missing 'widget_id' parameter".to_string())
+            })?;
+
+            let dashboard = client.get_dashboard(dashboard_id).await?;
+            let widgets = serde_json::to_value(&dashboard.widgets)?;
+            let definition = widgets
+                .as_array()
+                .and_then(|widgets| Self::find_widget(widgets, widget_id))
+                .cloned()
+                .ok_or_else(|| {
+                    DatadogError::InvalidInput(format!(
+                        "Widget {} not found in dashboard {}",
+                        widget_id, dashboard_id
+                    ))
+                })?;
+            Ok((definition, dashboard.template_variables.unwrap_or_default()))
+        }
+    }
+
+    /// Explicit range or time window, else the widget's live span, else the last hour
+    fn time_window(params: &Value, definition: &Value) -> Result<(String, String)> {
+        if let Some(range) = params["range"].as_str() {
+            let (from, to) = crate::utils::parse_range(range)?;
+            return Ok((from.to_string(), to.to_string()));
+        }
+
+        let from = params["from"]
+            .as_str()
+            .map(String::from)
+            .or_else(|| {
+                definition["time"]["live_span"]
+                    .as_str()
+                    .and_then(Self::live_span_to_from)
+            })
+            .unwrap_or_else(|| "1 hour ago".to_string());
+        let to = params["to"].as_str().unwrap_or("now").to_string();
+        Ok((from, to))
+    }
+
+    /// Expand template variables in each generated call's query
+    fn expand_calls(translation: &mut Translation, replacements: &[TemplateReplacement]) {
+        for call in &mut translation.tool_calls {
+            let arguments = &mut call["arguments"];
+            if let Some(query) = arguments["query"].as_str() {
+                arguments["query"] = json!(Self::apply_templates(query, replacements));
+            }
+        }
+    }
+
+    /// Expand template variables and set the time window on each generated call
+    fn prepare_calls(
+        translation: &mut Translation,
+        replacements: &[TemplateReplacement],
+        from: &str,
+        to: &str,
+    ) {
+        Self::expand_calls(translation, replacements);
+        for call in &mut translation.tool_calls {
+            call["arguments"]["from"] = json!(from);
+            call["arguments"]["to"] = json!(to);
+        }
+    }
+
+    /// Runnable tool calls for a widget definition, template variables expanded, no time window
+    pub(crate) fn widget_queries(
+        definition: &Value,
+        replacements: &[TemplateReplacement],
+    ) -> Vec<Value> {
+        let mut translation = Self::translate(definition);
+        Self::expand_calls(&mut translation, replacements);
+        translation.tool_calls
+    }
+
+    pub async fn widget_query(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = DashboardWidgetsHandler;
+
+        let (definition, template_variables) = Self::load_widget(client.as_ref(), params).await?;
+        let (from, to) = Self::time_window(params, &definition)?;
+
+        let replacements =
+            Self::template_replacements(&template_variables, &params["template_variables"]);
+        let mut translation = Self::translate(&definition);
+        Self::prepare_calls(&mut translation, &replacements, &from, &to);
+
+        let data = json!({
+            "widget": {
+                "type": definition["type"],
+                "title": definition["title"],
+            },
+            "tool_calls": translation.tool_calls,
+            "unsupported": translation.unsupported,
+        });
+
+        Ok(handler.format_detail(data))
+    }
+
+    /// Run one translated tool call through the handler behind that tool
+    async fn execute_call(client: Arc<dyn DatadogApi>, call: &Value) -> Result<Value> {
+        let arguments = &call["arguments"];
+        match call["tool"].as_str() {
+            Some("datadog_metrics_query") => MetricsHandler::query(client, arguments).await,
+            Some("datadog_logs_aggregate") => {
+                LogsAggregateHandler::aggregate(client, arguments).await
+            }
+            other => Err(DatadogError::InvalidInput(format!(
+                "No handler to run tool {}",
+                other.unwrap_or("(none)")
+            ))),
+        }
+    }
+
+    pub async fn run_widget(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = DashboardWidgetsHandler;
+
+        let (definition, template_variables) = Self::load_widget(client.as_ref(), params).await?;
+        let (from, to) = Self::time_window(params, &definition)?;
+
+        let replacements =
+            Self::template_replacements(&template_variables, &params["template_variables"]);
+        let mut translation = Self::translate(&definition);
+        Self::prepare_calls(&mut translation, &replacements, &from, &to);
+
+        // A failing query is reported next to the others instead of failing the widget
+        let calls: Vec<ApiCall<'_, _>> = translation
+            .tool_calls
+            .iter()
+            .map(|call| Self::execute_call(client.clone(), call).boxed())
+            .collect();
+        let outcomes = execute_batch(calls, client.max_concurrency()).await;
+        let results: Vec<Value> = translation
+            .tool_calls
+            .iter()
+            .zip(outcomes)
+            .map(|(call, outcome)| {
+                let mut result = json!({
+                    "tool": call["tool"],
+                    "query": call["arguments"]["query"],
+                    "source": call["source"],
+                });
+                match outcome {
+                    Ok(output) => {
+                        result["data"] = output["data"].clone();
+                        if let Some(meta) = output.get("meta") {
+                            result["meta"] = meta.clone();
+                        }
+                    }
+                    Err(e) => {
+                        result["error"] = e.details();
+                    }
+                }
+                result
+            })
+            .collect();
+
+        let data = json!({
+            "widget": {
+                "type": definition["type"],
+                "title": definition["title"],
+            },
+            "from": from,
+            "to": to,
+            "results": results,
+            "unsupported": translation.unsupported,
+        });
+
+        Ok(handler.format_detail(data))
+    }
+
+    /// Reduce a series the way query_value and toplist widgets do
+    fn reduce(points: &[f64], aggregator: &str) -> Option<f64> {
+        if points.is_empty() {
+            return None;
+        }
+
+        Some(match aggregator {
+            "last" => *points.last()?,
+            "sum" | "area" => points.iter().sum(),
+            "min" => points.iter().copied().fold(f64::INFINITY, f64::min),
+            "max" => points.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            _ => points.iter().sum::<f64>() / points.len() as f64,
+        })
+    }
+
+    /// Reducer set on the request, or on its first query for formula requests
+    fn request_aggregator(request: &Value) -> &str {
+        request["aggregator"]
+            .as_str()
+            .or_else(|| request["queries"][0]["aggregator"].as_str())
+            .unwrap_or("avg")
+    }
+
+    /// Color a palette renders as: 'white_on_red' is red, 'red_on_white' is red too
+    fn palette_color(palette: &str) -> &str {
+        if palette.starts_with("custom") {
+            return "custom";
+        }
+        let color = match palette.split_once("_on_") {
+            Some((text, "white")) => text,
+            Some((_, background)) => background,
+            None => palette,
+        };
+        color.trim_start_matches("light_")
+    }
+
+    /// First conditional format whose comparison holds, as the dashboard evaluates them
+    fn evaluate_formats(value: f64, formats: &Value) -> Option<Value> {
+        formats.as_array()?.iter().find_map(|format| {
+            let threshold = format["value"].as_f64()?;
+            let matched = match format["comparator"].as_str()? {
+                ">" => value > threshold,
+                ">=" => value >= threshold,
+                "<" => value < threshold,
+                "<=" => value <= threshold,
+                _ => false,
+            };
+            matched.then(|| {
+                let palette = format["palette"].as_str().unwrap_or_default();
+                json!({
+                    "comparator": format["comparator"],
+                    "value": threshold,
+                    "palette": palette,
+                    "color": Self::palette_color(palette),
+                })
+            })
+        })
+    }
+
+    /// Reduced value of one series with its conditional-format outcome
+    fn evaluate_series(series: &MetricSeries, aggregator: &str, formats: &Value) -> Value {
+        let points: Vec<f64> = series
+            .pointlist
+            .iter()
+            .flatten()
+            .filter_map(|p| p.get(1).copied().flatten())
+            .collect();
+        let value = Self::reduce(&points, aggregator);
+        let format = value.and_then(|v| Self::evaluate_formats(v, formats));
+
+        json!({
+            "scope": series.scope,
+            "value": value,
+            "color": format.as_ref().map_or(json!("default"), |f| f["color"].clone()),
+            "conditional_format": format,
+        })
+    }
+
+    pub async fn widget_value(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = DashboardWidgetsHandler;
+
+        let (definition, template_variables) = Self::load_widget(client.as_ref(), params).await?;
+        let widget_type = definition["type"].as_str().unwrap_or_default();
+        if !VALUE_WIDGETS.contains(&widget_type) {
+            return Err(DatadogError::InvalidInput(format!(
+                "Widget type '{}' has no single-value rendering (expected one of: {}); use datadog_dashboards_widget_query",
+                widget_type,
+                VALUE_WIDGETS.join(", ")
+            )));
+        }
+        let limit = params["limit"]
+            .as_u64()
+            .map(|l| l as usize)
+            .unwrap_or(DEFAULT_TOPLIST_LIMIT);
+
+        let (from, to) = Self::time_window(params, &definition)?;
+        let TimeParams::Timestamp {
+            from: from_ts,
+            to: to_ts,
+        } = handler.parse_time(&json!({"from": from, "to": to}), 1)?;
+        let replacements =
+            Self::template_replacements(&template_variables, &params["template_variables"]);
+
+        let mut results = Vec::new();
+        let mut unsupported = Vec::new();
+        for request in definition["requests"].as_array().into_iter().flatten() {
+            let aggregator = Self::request_aggregator(request);
+            let formats = &request["conditional_formats"];
+
+            let mut translation = Translation::default();
+            Self::translate_request(request, false, &mut translation);
+            Self::prepare_calls(&mut translation, &replacements, &from, &to);
+            unsupported.extend(translation.unsupported);
+
+            for call in translation.tool_calls {
+                let query = match call["arguments"]["query"].as_str() {
+                    Some(query) if call["tool"] == "datadog_metrics_query" => query,
+                    _ => {
+                        unsupported.push(json!({
+                            "tool_call": call,
+                            "reason": "Only metric queries are evaluated; run this call directly",
+                        }));
+                        continue;
+                    }
+                };
+
+                let response = client.query_metrics(query, from_ts, to_ts).await?;
+                let mut series: Vec<Value> = response
+                    .series
+                    .iter()
+                    .map(|s| Self::evaluate_series(s, aggregator, formats))
+                    .collect();
+
+                let mut result = json!({
+                    "query": query,
+                    "aggregator": aggregator,
+                });
+                if widget_type == "toplist" {
+                    series.sort_by(|a, b| {
+                        let value = |v: &Value| v["value"].as_f64().unwrap_or(f64::NEG_INFINITY);
+                        value(b).total_cmp(&value(a))
+                    });
+                    series.truncate(limit);
+                    result["top"] = json!(series);
+                } else {
+                    // A query value widget renders the first series only
+                    let first = series.into_iter().next().unwrap_or(json!({"value": null}));
+                    result["value"] = first["value"].clone();
+                    result["color"] = first["color"].clone();
+                    result["conditional_format"] = first["conditional_format"].clone();
+                }
+                results.push(result);
+            }
+        }
+
+        let data = json!({
+            "widget": {
+                "type": widget_type,
+                "title": definition["title"],
+            },
+            "from": crate::utils::format_timestamp(from_ts),
+            "to": crate::utils::format_timestamp(to_ts),
+            "results": results,
+            "unsupported": unsupported,
+        });
+
+        Ok(handler.format_detail(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+
+    #[test]
+    fn test_live_span_to_from() {
+        assert_eq!(
+            DashboardWidgetsHandler::live_span_to_from("4h").as_deref(),
+            Some("4 hours ago")
+        );
+        assert_eq!(
+            DashboardWidgetsHandler::live_span_to_from("1w").as_deref(),
+            Some("1 week ago")
+        );
+        assert_eq!(
+            DashboardWidgetsHandler::live_span_to_from("3mo").as_deref(),
+            Some("3 months ago")
+        );
+        assert_eq!(DashboardWidgetsHandler::live_span_to_from("alert"), None);
+    }
+
+    #[test]
+    fn test_find_nested_widget() {
+        let widgets = vec![json!({
+            "id": 1,
+            "definition": {
+                "type": "group",
+                "widgets": [{"id": 2, "definition": {"type": "timeseries", "title": "CPU"}}]
+            }
+        })];
+
+        let found = DashboardWidgetsHandler::find_widget(&widgets, 2).unwrap();
+        assert_eq!(found["title"], "CPU");
+        assert!(DashboardWidgetsHandler::find_widget(&widgets, 3).is_none());
+    }
+
+    #[test]
+    fn test_template_replacements() {
+        let vars: Vec<TemplateVariable> = serde_json::from_value(json!([
+            {"name": "env", "prefix": "env", "default": "prod"},
+            {"name": "service", "prefix": "service", "default": "*"}
+        ]))
+        .unwrap();
+
+        let replacements =
+            DashboardWidgetsHandler::template_replacements(&vars, &json!({"env": "staging"}));
+        let query = DashboardWidgetsHandler::apply_templates(
+            "avg:system.cpu.user{$env,$service} by {host}",
+            &replacements,
+        );
+        assert_eq!(query, "avg:system.cpu.user{env:staging,*} by {host}");
+    }
+
+    #[test]
+    fn test_translate_metric_formula() {
+        let definition = json!({
+            "type": "timeseries",
+            "requests": [{
+                "queries": [
+                    {"data_source": "metrics", "name": "errors", "query": "sum:trace.http.request.errors{*}"},
+                    {"data_source": "metrics", "name": "hits", "query": "sum:trace.http.request.hits{*}"}
+                ],
+                "formulas": [{"formula": "errors / hits * 100", "alias": "error rate"}]
+            }]
+        });
+
+        let translation = DashboardWidgetsHandler::translate(&definition);
+        assert_eq!(translation.tool_calls.len(), 1);
+        assert_eq!(
+            translation.tool_calls[0]["arguments"]["query"],
+            "sum:trace.http.request.errors{*} / sum:trace.http.request.hits{*} * 100"
+        );
+        assert_eq!(translation.tool_calls[0]["source"], "error rate");
+    }
+
+    #[test]
+    fn test_translate_formula_function_falls_back_to_queries() {
+        let definition = json!({
+            "type": "toplist",
+            "requests": [{
+                "queries": [{"data_source": "metrics", "name": "query1", "query": "avg:system.cpu.user{*} by {host}"}],
+                "formulas": [{"formula": "top(query1, 10, 'mean', 'desc')"}]
+            }]
+        });
+
+        let translation = DashboardWidgetsHandler::translate(&definition);
+        assert_eq!(translation.tool_calls.len(), 1);
+        assert_eq!(translation.tool_calls[0]["source"], "query1");
+        assert_eq!(translation.unsupported.len(), 1);
+    }
+
+    #[test]
+    fn test_translate_logs_queries() {
+        let definition = json!({
+            "type": "toplist",
+            "requests": [
+                {
+                    "queries": [{
+                        "data_source": "logs",
+                        "name": "query1",
+                        "search": {"query": "service:web status:error"},
+                        "compute": {"aggregation": "count"},
+                        "group_by": [{"facet": "@http.url_details.path", "limit": 10, "sort": {"aggregation": "count", "order": "desc"}}]
+                    }],
+                    "formulas": [{"formula": "query1"}]
+                },
+                {
+                    "log_query": {
+                        "search": {"query": "source:nginx"},
+                        "compute": {"aggregation": "avg", "facet": "@duration"}
+                    }
+                }
+            ]
+        });
+
+        let translation = DashboardWidgetsHandler::translate(&definition);
+        assert!(translation.unsupported.is_empty());
+        assert_eq!(translation.tool_calls.len(), 2);
+
+        let first = &translation.tool_calls[0];
+        assert_eq!(first["tool"], "datadog_logs_aggregate");
+        assert_eq!(first["arguments"]["query"], "service:web status:error");
+        assert_eq!(first["arguments"]["compute"][0]["type"], "total");
+        assert_eq!(first["arguments"]["group_by"][0]["limit"], 10);
+
+        let second = &translation.tool_calls[1];
+        assert_eq!(second["arguments"]["compute"][0]["aggregation"], "avg");
+        assert_eq!(second["arguments"]["compute"][0]["metric"], "@duration");
+    }
+
+    #[test]
+    fn test_reduce() {
+        let points = [4.0, 1.0, 7.0, 2.0];
+        assert_eq!(DashboardWidgetsHandler::reduce(&points, "avg"), Some(3.5));
+        assert_eq!(DashboardWidgetsHandler::reduce(&points, "last"), Some(2.0));
+        assert_eq!(DashboardWidgetsHandler::reduce(&points, "sum"), Some(14.0));
+        assert_eq!(DashboardWidgetsHandler::reduce(&points, "min"), Some(1.0));
+        assert_eq!(DashboardWidgetsHandler::reduce(&points, "max"), Some(7.0));
+        assert_eq!(DashboardWidgetsHandler::reduce(&[], "avg"), None);
+    }
+
+    #[test]
+    fn test_evaluate_formats_first_match_wins() {
+        let formats = json!([
+            {"comparator": ">", "value": 90, "palette": "white_on_red"},
+            {"comparator": ">", "value": 70, "palette": "black_on_light_yellow"},
+            {"comparator": "<=", "value": 70, "palette": "green_on_white"}
+        ]);
+
+        let red = DashboardWidgetsHandler::evaluate_formats(95.0, &formats).unwrap();
+        assert_eq!(red["color"], "red");
+        let yellow = DashboardWidgetsHandler::evaluate_formats(80.0, &formats).unwrap();
+        assert_eq!(yellow["color"], "yellow");
+        let green = DashboardWidgetsHandler::evaluate_formats(70.0, &formats).unwrap();
+        assert_eq!(green["color"], "green");
+        assert!(DashboardWidgetsHandler::evaluate_formats(1.0, &json!([])).is_none());
+    }
+
+    #[test]
+    fn test_evaluate_series() {
+        let series: MetricSeries = serde_json::from_value(json!({
+            "metric": "system.cpu.user",
+            "display_name": null,
+            "unit": null,
+            "pointlist": [[1000.0, 50.0], [2000.0, null], [3000.0, 90.0]],
+            "scope": "host:web-1",
+            "expression": "avg:system.cpu.user{host:web-1}",
+            "tag_set": null,
+            "aggr": "avg",
+            "interval": 20,
+            "length": 3,
+            "start": 1000,
+            "end": 3000,
+            "attributes": null,
+            "query_index": 0
+        }))
+        .unwrap();
+        let formats = json!([{"comparator": ">=", "value": 70, "palette": "white_on_red"}]);
+
+        let avg = DashboardWidgetsHandler::evaluate_series(&series, "avg", &formats);
+        assert_eq!(avg["value"], 70.0);
+        assert_eq!(avg["color"], "red");
+
+        let min = DashboardWidgetsHandler::evaluate_series(&series, "min", &formats);
+        assert_eq!(min["value"], 50.0);
+        assert_eq!(min["color"], "default");
+    }
+
+    #[tokio::test]
+    async fn test_widget_value_rejects_timeseries() {
+        let client = Arc::new(
+            DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None).unwrap(),
+        );
+
+        let result = DashboardWidgetsHandler::widget_value(
+            client,
+            &json!({"widget": {"definition": {"type": "timeseries", "requests": []}}}),
+        )
+        .await;
+        assert!(result.unwrap_err().to_string().contains("query_value"));
+    }
+
+    #[tokio::test]
+    async fn test_run_widget_reports_unsupported_queries() {
+        let client = Arc::new(
+            DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None).unwrap(),
+        );
+
+        let result = DashboardWidgetsHandler::run_widget(
+            client.clone(),
+            &json!({"widget": {"definition": {
+                "type": "timeseries",
+                "title": "Requests",
+                "time": {"live_span": "4h"},
+                "requests": [{"queries": [{"data_source": "spans", "name": "a"}]}]
+            }}}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["data"]["results"], json!([]));
+        assert_eq!(result["data"]["unsupported"][0]["data_source"], "spans");
+        assert_eq!(result["data"]["from"], "4 hours ago");
+
+        let call = json!({"tool": "datadog_spans_search", "arguments": {}});
+        assert!(
+            DashboardWidgetsHandler::execute_call(client, &call)
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_translate_unsupported_data_source() {
+        let definition = json!({
+            "type": "query_value",
+            "requests": [{
+                "queries": [{"data_source": "rum", "name": "query1", "search": {"query": "@type:view"}}]
+            }]
+        });
+
+        let translation = DashboardWidgetsHandler::translate(&definition);
+        assert!(translation.tool_calls.is_empty());
+        assert_eq!(translation.unsupported[0]["data_source"], "rum");
+    }
+}
@@ -1,10 +1,24 @@
-use serde_json::{Value, json};
+use serde_json::{Map, Value, json};
 use std::sync::Arc;
 
 use crate::cache::DataCache;
-use crate::datadog::DatadogClient;
-use crate::error::Result;
+use crate::datadog::DatadogApi;
+use crate::datadog::models::{Dashboard, DashboardDefinition, TemplateVariable, Widget};
+use crate::error::{DatadogError, Result};
 use crate::handlers::common::{Paginator, ResponseFormatter};
+use crate::handlers::dashboard_widgets::{DashboardWidgetsHandler, TemplateReplacement};
+
+const LAYOUT_TYPES: &[&str] = &["ordered", "free"];
+
+/// Fields Datadog sets on a dashboard itself; they are dropped before writing one back
+const READ_ONLY_FIELDS: &[&str] = &[
+    "id",
+    "url",
+    "author_handle",
+    "author_name",
+    "created_at",
+    "modified_at",
+];
 
 pub struct DashboardsHandler;
 
@@ -49,7 +63,7 @@ impl DashboardsHandler {
     }
 
     pub async fn list(
-        client: Arc<DatadogClient>,
+        client: Arc<dyn DatadogApi>,
         cache: Arc<DataCache>,
         params: &Value,
     ) -> Result<Value> {
@@ -58,13 +72,28 @@ impl DashboardsHandler {
 
         let cache_key = crate::cache::create_cache_key("dashboards", &json!({}));
 
+        let mut meta = None;
         let all_dashboards = if page == 0 {
-            let response = client.list_dashboards().await?;
-            let dashboards = response.dashboards;
-            cache.set_dashboards(cache_key.clone(), dashboards).await;
-            cache
-                .get_or_fetch_dashboards(&cache_key, || async { unreachable!("Just inserted") })
-                .await?
+            match client.list_dashboards().await {
+                Ok(response) => {
+                    cache
+                        .set_dashboards(cache_key.clone(), response.dashboards)
+                        .await;
+                    cache
+                        .get_or_fetch_dashboards(&cache_key, || async {
+                            unreachable!("Just inserted")
+                        })
+                        .await?
+                }
+                Err(e) if e.is_transient() => match cache.get_stale_dashboards(&cache_key).await {
+                    Some((dashboards, age)) => {
+                        meta = Some(DataCache::stale_meta(age, &e));
+                        dashboards
+                    }
+                    None => return Err(e),
+                },
+                Err(e) => return Err(e),
+            }
         } else {
             cache
                 .get_or_fetch_dashboards(&cache_key, || async {
@@ -81,7 +110,7 @@ impl DashboardsHandler {
         if start >= total_count {
             let data = json!([]);
             let pagination = handler.format_pagination(page, page_size, total_count);
-            return Ok(handler.format_list(data, Some(pagination), None));
+            return Ok(handler.format_list(data, Some(pagination), meta));
         }
 
         let paginated_dashboards = &all_dashboards[start..end];
@@ -89,10 +118,31 @@ impl DashboardsHandler {
 
         let pagination = handler.format_pagination(page, page_size, total_count);
 
+        Ok(handler.format_list(data, Some(pagination), meta))
+    }
+
+    /// Dashboards shared by invite or public URL; the API reports no total, so `has_next`
+    /// is inferred from a full page
+    pub async fn shared_list(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = DashboardsHandler;
+        let (page, page_size) = handler.parse_pagination(params);
+
+        let response = client
+            .list_shared_dashboards(page * page_size, page_size)
+            .await?;
+
+        let returned = response.dashboards.len();
+        let data = json!(response.dashboards);
+        let pagination = json!({
+            "page": page,
+            "page_size": page_size,
+            "has_next": returned == page_size
+        });
+
         Ok(handler.format_list(data, Some(pagination), None))
     }
 
-    pub async fn get(client: Arc<DatadogClient>, params: &Value) -> Result<Value> {
+    pub async fn get(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
         let handler = DashboardsHandler;
         let dashboard_id = params["dashboard_id"].as_str().ok_or_else(|| {
             crate::error::DatadogError::InvalidInput("Missing 'dashboard_id' parameter".to_string())
@@ -100,6 +150,13 @@ impl DashboardsHandler {
 
         let response = client.get_dashboard(dashboard_id).await?;
 
+        // Widget queries come back with $variables expanded so they can be run as-is
+        let template_variables = response.template_variables.clone().unwrap_or_default();
+        let replacements = DashboardWidgetsHandler::template_replacements(
+            &template_variables,
+            &params["template_variables"],
+        );
+
         let data = json!({
             "id": response.id,
             "title": response.title,
@@ -115,33 +172,313 @@ impl DashboardsHandler {
                 "handle": author.handle,
                 "email": author.email
             })),
-            "template_variables": response.template_variables.as_ref().map(|vars| {
-                vars.iter().map(|var| json!({
-                    "name": var.name,
-                    "default": var.default_value,
-                    "prefix": var.prefix,
-                    "available_values": var.available_values
-                })).collect::<Vec<_>>()
-            }).unwrap_or_default(),
+            "template_variables": template_variables.iter().map(|var| json!({
+                "name": var.name,
+                "default": var.default_value,
+                "value": replacements.iter().find(|r| r.name == var.name).map(|r| &r.value),
+                "prefix": var.prefix,
+                "available_values": var.available_values
+            })).collect::<Vec<_>>(),
             "widgets_summary": json!({
                 "total_widgets": response.widgets.len(),
                 "widget_types": Self::collect_widget_types(&response.widgets),
-                "widgets": response.widgets.iter().map(|widget| json!({
-                    "id": widget.id,
-                    "type": widget.definition.widget_type,
-                    "title": widget.definition.title,
-                    "layout": widget.layout.as_ref().map(|l| json!({
-                        "x": l.x,
-                        "y": l.y,
-                        "width": l.width,
-                        "height": l.height
-                    }))
-                })).collect::<Vec<_>>()
+                "widgets": response.widgets.iter()
+                    .map(|widget| Self::widget_summary(widget, &replacements))
+                    .collect::<Vec<_>>()
             })
         });
 
         Ok(handler.format_detail(data))
     }
+
+    /// Widget id, type, title and layout plus its runnable queries; groups list their children
+    fn widget_summary(widget: &Widget, replacements: &[TemplateReplacement]) -> Value {
+        let mut summary = json!({
+            "id": widget.id,
+            "type": widget.definition.widget_type,
+            "title": widget.definition.title,
+            "layout": widget.layout.as_ref().map(|l| json!({
+                "x": l.x,
+                "y": l.y,
+                "width": l.width,
+                "height": l.height
+            }))
+        });
+
+        if let Ok(definition) = serde_json::to_value(&widget.definition) {
+            let queries = DashboardWidgetsHandler::widget_queries(&definition, replacements);
+            if !queries.is_empty() {
+                summary["queries"] = json!(queries);
+            }
+        }
+
+        if let Some(nested) = widget.definition.extra.get("widgets")
+            && let Ok(nested) = serde_json::from_value::<Vec<Widget>>(nested.clone())
+        {
+            summary["widgets"] = json!(
+                nested
+                    .iter()
+                    .map(|w| Self::widget_summary(w, replacements))
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        summary
+    }
+
+    /// Typed array parameter (widgets, template variables), None when absent
+    fn parse_array<T: serde::de::DeserializeOwned>(
+        params: &Value,
+        name: &str,
+    ) -> Result<Option<T>> {
+        match &params[name] {
+            Value::Null => Ok(None),
+            value @ Value::Array(_) => serde_json::from_value(value.clone())
+                .map(Some)
+                .map_err(|e| DatadogError::InvalidInput(format!("Invalid '{}': {}", name, e))),
+            _ => Err(DatadogError::InvalidInput(format!(
+                "'{}' must be an array",
+                name
+            ))),
+        }
+    }
+
+    fn string_list(params: &Value, name: &str) -> Option<Vec<String>> {
+        params[name].as_array().map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(String::from)
+                .collect()
+        })
+    }
+
+    /// Free layouts position every widget explicitly; ordered layouts flow them automatically
+    fn validate_definition(definition: &DashboardDefinition) -> Result<()> {
+        if definition.title.trim().is_empty() {
+            return Err(DatadogError::InvalidInput(
+                "Dashboard 'title' must not be empty".to_string(),
+            ));
+        }
+        if !LAYOUT_TYPES.contains(&definition.layout_type.as_str()) {
+            return Err(DatadogError::InvalidInput(format!(
+                "Invalid layout_type '{}' (expected one of: {})",
+                definition.layout_type,
+                LAYOUT_TYPES.join(", ")
+            )));
+        }
+        if definition.layout_type == "free" && definition.widgets.iter().any(|w| w.layout.is_none())
+        {
+            return Err(DatadogError::InvalidInput(
+                "Every widget needs a 'layout' (x, y, width, height) on a free layout dashboard"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Definition for a new dashboard from tool arguments
+    fn new_definition(params: &Value) -> Result<DashboardDefinition> {
+        let title = params["title"]
+            .as_str()
+            .ok_or_else(|| DatadogError::InvalidInput("Missing 'title' parameter".to_string()))?;
+
+        let definition = DashboardDefinition {
+            title: title.to_string(),
+            description: params["description"].as_str().map(String::from),
+            layout_type: params["layout_type"]
+                .as_str()
+                .unwrap_or("ordered")
+                .to_string(),
+            widgets: Self::parse_array(params, "widgets")?.unwrap_or_default(),
+            template_variables: Self::parse_array(params, "template_variables")?,
+            tags: Self::string_list(params, "tags"),
+        };
+        Self::validate_definition(&definition)?;
+        Ok(definition)
+    }
+
+    /// A fetched dashboard without the fields Datadog sets itself, ready to write back;
+    /// everything else, including fields the typed models don't carry, is kept
+    fn writable(dashboard: Value) -> Result<Map<String, Value>> {
+        let mut body: Map<String, Value> = serde_json::from_value(dashboard)?;
+        for field in READ_ONLY_FIELDS {
+            body.remove(*field);
+        }
+        Ok(body)
+    }
+
+    /// Checks a raw dashboard body the same way as a new definition
+    fn validate_body(body: &Map<String, Value>) -> Result<()> {
+        let definition: DashboardDefinition =
+            serde_json::from_value(Value::Object(body.clone()))
+                .map_err(|e| DatadogError::InvalidInput(format!("Invalid dashboard: {}", e)))?;
+        Self::validate_definition(&definition)
+    }
+
+    /// Title, description and tags from the arguments, when given
+    fn apply_common(body: &mut Map<String, Value>, params: &Value) {
+        if let Some(title) = params["title"].as_str() {
+            body.insert("title".to_string(), json!(title));
+        }
+        if let Some(description) = params["description"].as_str() {
+            body.insert("description".to_string(), json!(description));
+        }
+        if let Some(tags) = Self::string_list(params, "tags") {
+            body.insert("tags".to_string(), json!(tags));
+        }
+    }
+
+    /// An existing dashboard with only the given arguments changed;
+    /// `widgets` replaces all widgets, `add_widgets` appends to them
+    fn updated_definition(dashboard: Value, params: &Value) -> Result<Value> {
+        let mut body = Self::writable(dashboard)?;
+        Self::apply_common(&mut body, params);
+
+        if let Some(widgets) = Self::parse_array::<Vec<Value>>(params, "widgets")? {
+            body.insert("widgets".to_string(), Value::Array(widgets));
+        }
+        if let Some(added) = Self::parse_array::<Vec<Value>>(params, "add_widgets")? {
+            match body.get_mut("widgets") {
+                Some(Value::Array(widgets)) => widgets.extend(added),
+                _ => {
+                    body.insert("widgets".to_string(), Value::Array(added));
+                }
+            }
+        }
+        if let Some(variables) = Self::parse_array::<Vec<Value>>(params, "template_variables")? {
+            body.insert("template_variables".to_string(), Value::Array(variables));
+        }
+
+        Self::validate_body(&body)?;
+        Ok(Value::Object(body))
+    }
+
+    /// Copy of a dashboard; widget ids are dropped so the copy gets its own, and
+    /// `template_variable_defaults` ({"service": "checkout"}) retargets the copy
+    fn cloned_definition(dashboard: Value, params: &Value) -> Result<Value> {
+        let mut body = Self::writable(dashboard)?;
+        let title = body
+            .get("title")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        body.insert("title".to_string(), json!(format!("{} (copy)", title)));
+        Self::apply_common(&mut body, params);
+
+        if let Some(defaults) = params["template_variable_defaults"].as_object() {
+            let mut variables = match body.remove("template_variables") {
+                Some(Value::Array(variables)) => variables,
+                _ => Vec::new(),
+            };
+            for (name, value) in defaults {
+                let value = value.as_str().ok_or_else(|| {
+                    DatadogError::InvalidInput(format!(
+                        "Default for template variable '{}' must be a string",
+                        name
+                    ))
+                })?;
+                let variable = variables
+                    .iter_mut()
+                    .find(|v| v["name"].as_str() == Some(name))
+                    .and_then(Value::as_object_mut);
+                match variable {
+                    // Newer dashboards carry `defaults` (a list) in place of the deprecated `default`
+                    Some(variable) if variable.contains_key("defaults") => {
+                        variable.insert("defaults".to_string(), json!([value]));
+                        variable.remove("default");
+                    }
+                    Some(variable) => {
+                        variable.insert("default".to_string(), json!(value));
+                    }
+                    None => {
+                        return Err(DatadogError::InvalidInput(format!(
+                            "Dashboard has no template variable '{}' (available: {})",
+                            name,
+                            variables
+                                .iter()
+                                .filter_map(|v| v["name"].as_str())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )));
+                    }
+                }
+            }
+            body.insert("template_variables".to_string(), Value::Array(variables));
+        }
+
+        if let Some(Value::Array(widgets)) = body.get_mut("widgets") {
+            for widget in widgets.iter_mut().filter_map(Value::as_object_mut) {
+                widget.remove("id");
+            }
+        }
+
+        Self::validate_body(&body)?;
+        Ok(Value::Object(body))
+    }
+
+    /// What a write returned: enough to open the dashboard and confirm its contents
+    fn written_json(dashboard: &Dashboard) -> Value {
+        json!({
+            "id": dashboard.id,
+            "title": dashboard.title,
+            "url": dashboard.url,
+            "layout_type": dashboard.layout_type,
+            "total_widgets": dashboard.widgets.len(),
+            "widget_types": Self::collect_widget_types(&dashboard.widgets),
+            "template_variables": dashboard.template_variables.as_ref().map(|vars| {
+                vars.iter().map(|var: &TemplateVariable| json!({
+                    "name": var.name,
+                    "default": var.default_value
+                })).collect::<Vec<_>>()
+            }).unwrap_or_default(),
+            "modified_at": dashboard.modified_at
+        })
+    }
+
+    fn required_dashboard_id(params: &Value) -> Result<&str> {
+        params["dashboard_id"].as_str().ok_or_else(|| {
+            DatadogError::InvalidInput("Missing 'dashboard_id' parameter".to_string())
+        })
+    }
+
+    pub async fn create(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = DashboardsHandler;
+
+        let definition = Self::new_definition(params)?;
+        let dashboard = client.create_dashboard(&definition).await?;
+
+        Ok(handler.format_detail(Self::written_json(&dashboard)))
+    }
+
+    pub async fn update(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = DashboardsHandler;
+        let dashboard_id = Self::required_dashboard_id(params)?;
+
+        // Fail before reading when the update could never be written
+        client.ensure_writes_allowed()?;
+
+        let current = client.get_dashboard_json(dashboard_id).await?;
+        let definition = Self::updated_definition(current, params)?;
+        let dashboard = client.update_dashboard(dashboard_id, &definition).await?;
+
+        Ok(handler.format_detail(Self::written_json(&dashboard)))
+    }
+
+    pub async fn clone(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = DashboardsHandler;
+        let dashboard_id = Self::required_dashboard_id(params)?;
+
+        client.ensure_writes_allowed()?;
+
+        let source = client.get_dashboard_json(dashboard_id).await?;
+        let definition = Self::cloned_definition(source, params)?;
+        let dashboard = client.create_dashboard(&definition).await?;
+
+        let mut data = Self::written_json(&dashboard);
+        data["cloned_from"] = json!(dashboard_id);
+        Ok(handler.format_detail(data))
+    }
 }
 
 #[cfg(test)]
@@ -149,6 +486,178 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    fn source_json() -> Value {
+        json!({
+            "id": "abc-123",
+            "title": "Service overview",
+            "layout_type": "ordered",
+            "url": "/dashboard/abc-123",
+            "author_handle": "alice@example.com",
+            "template_variables": [{"name": "service", "prefix": "service", "default": "*"}],
+            "widgets": [
+                {"id": 11, "definition": {"type": "timeseries", "title": "Latency", "requests": []}},
+                {"id": 12, "definition": {"type": "note", "content": "Runbook"}}
+            ],
+            "tags": ["team:core"]
+        })
+    }
+
+    fn source_dashboard() -> Dashboard {
+        serde_json::from_value(source_json()).unwrap()
+    }
+
+    #[test]
+    fn test_widget_summary_expands_templates() {
+        let widget: Widget = serde_json::from_value(json!({
+            "id": 1,
+            "definition": {
+                "type": "group",
+                "title": "Service",
+                "widgets": [{
+                    "id": 2,
+                    "definition": {
+                        "type": "timeseries",
+                        "requests": [{"q": "avg:trace.http.request.duration{$service,$env}"}]
+                    }
+                }]
+            }
+        }))
+        .unwrap();
+        let replacements = DashboardWidgetsHandler::template_replacements(
+            &source_dashboard().template_variables.unwrap(),
+            &json!({"service": "checkout", "env": "prod"}),
+        );
+
+        let summary = DashboardsHandler::widget_summary(&widget, &replacements);
+        assert!(summary.get("queries").is_none());
+        assert_eq!(
+            summary["widgets"][0]["queries"][0]["arguments"]["query"],
+            "avg:trace.http.request.duration{service:checkout,prod}"
+        );
+    }
+
+    #[test]
+    fn test_new_definition() {
+        let definition = DashboardsHandler::new_definition(&json!({
+            "title": "Checkout",
+            "widgets": [{"definition": {"type": "note", "content": "hi"}}],
+            "tags": ["team:payments"]
+        }))
+        .unwrap();
+        assert_eq!(definition.layout_type, "ordered");
+        assert_eq!(definition.widgets.len(), 1);
+
+        // Absent optionals are left out of the request body entirely
+        let body = serde_json::to_value(&definition).unwrap();
+        assert!(body.get("description").is_none());
+        assert!(body["widgets"][0].get("id").is_none());
+        assert_eq!(body["widgets"][0]["definition"]["content"], "hi");
+
+        assert!(DashboardsHandler::new_definition(&json!({})).is_err());
+        assert!(
+            DashboardsHandler::new_definition(&json!({"title": "x", "widgets": [{}]})).is_err()
+        );
+        assert!(
+            DashboardsHandler::new_definition(&json!({
+                "title": "x",
+                "layout_type": "free",
+                "widgets": [{"definition": {"type": "note"}}]
+            }))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_updated_definition() {
+        let body = DashboardsHandler::updated_definition(
+            source_json(),
+            &json!({
+                "title": "Service overview v2",
+                "add_widgets": [{"definition": {"type": "query_value", "requests": []}}]
+            }),
+        )
+        .unwrap();
+        assert_eq!(body["title"], "Service overview v2");
+        assert_eq!(body["widgets"].as_array().unwrap().len(), 3);
+        assert_eq!(body["widgets"][0]["id"], 11);
+        assert_eq!(body["tags"], json!(["team:core"]));
+        assert!(body.get("id").is_none());
+        assert!(body.get("author_handle").is_none());
+
+        assert!(
+            DashboardsHandler::updated_definition(source_json(), &json!({"widgets": [{}]}))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_unmodelled_fields_survive_update_and_clone() {
+        let mut source = source_json();
+        source["reflow_type"] = json!("fixed");
+        source["notify_list"] = json!(["@oncall"]);
+        source["restricted_roles"] = json!(["role-1"]);
+        source["template_variable_presets"] = json!([{"name": "checkout", "template_variables": [{"name": "service", "value": "checkout"}]}]);
+        source["template_variables"][0]["defaults"] = json!(["*"]);
+        source["widgets"][0]["layout"] =
+            json!({"x": 0, "y": 0, "width": 4, "height": 2, "is_column_break": true});
+
+        let updated =
+            DashboardsHandler::updated_definition(source.clone(), &json!({"title": "v2"})).unwrap();
+        for field in [
+            "reflow_type",
+            "notify_list",
+            "restricted_roles",
+            "template_variable_presets",
+        ] {
+            assert_eq!(updated[field], source[field], "{} was dropped", field);
+        }
+        assert_eq!(updated["widgets"][0]["layout"]["is_column_break"], true);
+        assert_eq!(updated["template_variables"][0]["defaults"], json!(["*"]));
+
+        let cloned = DashboardsHandler::cloned_definition(
+            source.clone(),
+            &json!({"template_variable_defaults": {"service": "checkout"}}),
+        )
+        .unwrap();
+        assert_eq!(cloned["reflow_type"], "fixed");
+        assert_eq!(
+            cloned["template_variable_presets"],
+            source["template_variable_presets"]
+        );
+        assert_eq!(cloned["widgets"][0]["layout"]["is_column_break"], true);
+        assert_eq!(
+            cloned["template_variables"][0]["defaults"],
+            json!(["checkout"])
+        );
+        assert!(cloned["template_variables"][0].get("default").is_none());
+    }
+
+    #[test]
+    fn test_cloned_definition() {
+        let body = DashboardsHandler::cloned_definition(
+            source_json(),
+            &json!({"template_variable_defaults": {"service": "checkout"}}),
+        )
+        .unwrap();
+        assert_eq!(body["title"], "Service overview (copy)");
+        assert!(
+            body["widgets"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .all(|w| w.get("id").is_none())
+        );
+        assert_eq!(body["template_variables"][0]["default"], "checkout");
+
+        assert!(
+            DashboardsHandler::cloned_definition(
+                source_json(),
+                &json!({"template_variable_defaults": {"env": "prod"}}),
+            )
+            .is_err()
+        );
+    }
+
     #[test]
     fn test_pagination_parameters() {
         let handler = DashboardsHandler;
@@ -0,0 +1,288 @@
+use futures::FutureExt;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::batch::{ApiCall, execute_batch};
+use crate::datadog::models::{ErrorTrackingIssueAttributes, ErrorTrackingSearchResponse};
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::{
+    DEFAULT_STACK_TRACE_LINES, MAX_STRING_LENGTH, ResponseFilter, ResponseFormatter, TimeHandler,
+    TimeParams,
+};
+use crate::redact::redact;
+
+const TRACKS: &[&str] = &["trace", "logs", "rum"];
+
+const DEFAULT_ISSUE_LIMIT: usize = 10;
+const MAX_ISSUE_LIMIT: usize = 100;
+
+/// Top issues that get a representative stack trace (one extra search each)
+const DEFAULT_STACK_SAMPLES: usize = 3;
+const MAX_STACK_SAMPLES: usize = 10;
+
+pub struct ErrorTrackingHandler;
+
+impl TimeHandler for ErrorTrackingHandler {}
+impl ResponseFilter for ErrorTrackingHandler {}
+impl ResponseFormatter for ErrorTrackingHandler {}
+
+impl ErrorTrackingHandler {
+    fn format_ms(ms: Option<i64>) -> Option<String> {
+        ms.map(|ms| crate::utils::format_timestamp(ms / 1000))
+    }
+
+    /// Join search results (counts) with their included issue details, keeping result order
+    fn group_issues(&self, response: &ErrorTrackingSearchResponse, limit: usize) -> Vec<Value> {
+        let issues: HashMap<&str, &ErrorTrackingIssueAttributes> = response
+            .included
+            .iter()
+            .flatten()
+            .filter(|i| i.issue_type.as_deref().is_none_or(|t| t == "issue"))
+            .filter_map(|i| Some((i.id.as_str(), i.attributes.as_ref()?)))
+            .collect();
+
+        response
+            .data
+            .iter()
+            .flatten()
+            .take(limit)
+            .map(|result| {
+                let counts = result.attributes.as_ref();
+                let mut issue = json!({
+                    "issue_id": result.id,
+                    "count": counts.and_then(|c| c.total_count),
+                });
+
+                if let Some(sessions) = counts.and_then(|c| c.impacted_sessions) {
+                    issue["impacted_sessions"] = json!(sessions);
+                }
+                if let Some(users) = counts.and_then(|c| c.impacted_users) {
+                    issue["impacted_users"] = json!(users);
+                }
+
+                if let Some(details) = issues.get(result.id.as_str()) {
+                    issue["error_type"] = json!(details.error_type);
+                    issue["message"] = json!(
+                        details
+                            .error_message
+                            .as_deref()
+                            .map(|m| self.truncate_long_string(m, MAX_STRING_LENGTH))
+                    );
+                    issue["service"] = json!(details.service);
+                    issue["location"] = json!({
+                        "file": details.file_path,
+                        "function": details.function_name,
+                    });
+                    issue["first_seen"] = json!(Self::format_ms(details.first_seen));
+                    issue["last_seen"] = json!(Self::format_ms(details.last_seen));
+                    issue["first_seen_version"] = json!(details.first_seen_version);
+                    issue["last_seen_version"] = json!(details.last_seen_version);
+                    issue["state"] = json!(details.state);
+                    issue["platform"] = json!(details.platform);
+                    if details.is_crash == Some(true) {
+                        issue["is_crash"] = json!(true);
+                    }
+                }
+
+                issue
+            })
+            .collect()
+    }
+
+    /// Latest error event for an issue on the given track, as its stack trace
+    async fn sample_stack(
+        client: &dyn DatadogApi,
+        track: &str,
+        issue_id: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<Option<String>> {
+        let query = format!("@issue.id:{}", issue_id);
+        let latest = Some("-timestamp".to_string());
+
+        let stack = match track {
+            "logs" => client
+                .search_logs(&query, from, to, Some(1))
+                .await?
+                .data
+                .and_then(|d| d.into_iter().next())
+                .and_then(|e| e.attributes)
+                .and_then(|a| a.attributes)
+                .and_then(|a| a.get("error")?.get("stack")?.as_str().map(String::from)),
+            "rum" => client
+                .search_rum_events(&query, from, to, Some(1), None, latest)
+                .await?
+                .data
+                .and_then(|d| d.into_iter().next())
+                .and_then(|e| e.attributes)
+                .and_then(|a| a.error)
+                .and_then(|e| e.stack),
+            _ => client
+                .list_spans(&query, from, to, Some(1), None, latest)
+                .await?
+                .data
+                .into_iter()
+                .next()
+                .and_then(|e| e.attributes?.custom)
+                .and_then(|c| c["error"]["stack"].as_str().map(String::from)),
+        };
+
+        Ok(stack)
+    }
+
+    pub async fn issues(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = ErrorTrackingHandler;
+
+        let query = params["query"].as_str().unwrap_or("*");
+        let track = params["track"].as_str().unwrap_or("logs");
+        if !TRACKS.contains(&track) {
+            return Err(DatadogError::InvalidInput(format!(
+                "Invalid track '{}' (expected one of: {})",
+                track,
+                TRACKS.join(", ")
+            )));
+        }
+        let limit = params["limit"]
+            .as_u64()
+            .map(|l| l as usize)
+            .unwrap_or(DEFAULT_ISSUE_LIMIT)
+            .min(MAX_ISSUE_LIMIT);
+        let stack_samples = params["stack_samples"]
+            .as_u64()
+            .map(|s| s as usize)
+            .unwrap_or(DEFAULT_STACK_SAMPLES)
+            .min(MAX_STACK_SAMPLES);
+
+        let TimeParams::Timestamp { from, to } = handler.parse_time(params, 1)?;
+
+        let response = client
+            .search_error_tracking_issues(query, from * 1000, to * 1000, track)
+            .await?;
+        let mut issues = handler.group_issues(&response, limit);
+
+        // Stack samples are best-effort; a failed lookup leaves the issue without one
+        let from_iso = handler.timestamp_to_iso8601(from)?;
+        let to_iso = handler.timestamp_to_iso8601(to)?;
+        let calls: Vec<ApiCall<'_, _>> = issues
+            .iter()
+            .take(stack_samples)
+            .map(|issue| {
+                let issue_id = issue["issue_id"].as_str().unwrap_or_default().to_string();
+                let client = client.as_ref();
+                let (from_iso, to_iso) = (&from_iso, &to_iso);
+                async move { Self::sample_stack(client, track, &issue_id, from_iso, to_iso).await }
+                    .boxed()
+            })
+            .collect();
+        let samples = execute_batch(calls, client.max_concurrency()).await;
+
+        for (issue, sample) in issues.iter_mut().zip(samples) {
+            match sample {
+                Ok(Some(stack)) => {
+                    issue["stack"] = json!(if handler.should_truncate_stack_trace(params) {
+                        handler.truncate_stack_trace(&stack, DEFAULT_STACK_TRACE_LINES)
+                    } else {
+                        stack
+                    });
+                }
+                Ok(None) => {}
+                Err(e) => log::debug!(
+                    "No stack sample for {}: {}",
+                    issue["issue_id"],
+                    redact(&e.to_string())
+                ),
+            }
+        }
+
+        let total_occurrences: i64 = issues.iter().filter_map(|i| i["count"].as_i64()).sum();
+        let meta = json!({
+            "query": query,
+            "track": track,
+            "issues": issues.len(),
+            "occurrences": total_occurrences,
+            "from": crate::utils::format_timestamp(from),
+            "to": crate::utils::format_timestamp(to),
+        });
+
+        Ok(handler.format_list(json!(issues), None, Some(meta)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+
+    fn response() -> ErrorTrackingSearchResponse {
+        serde_json::from_value(json!({
+            "data": [
+                {
+                    "id": "issue-1",
+                    "type": "error_tracking_search_result",
+                    "attributes": {"total_count": 120, "impacted_users": 7}
+                },
+                {
+                    "id": "issue-2",
+                    "type": "error_tracking_search_result",
+                    "attributes": {"total_count": 4}
+                }
+            ],
+            "included": [
+                {
+                    "id": "issue-1",
+                    "type": "issue",
+                    "attributes": {
+                        "error_type": "NullPointerException",
+                        "error_message": "x".repeat(600),
+                        "service": "checkout",
+                        "file_path": "Cart.java",
+                        "function_name": "total",
+                        "first_seen": 1_700_000_000_000i64,
+                        "last_seen": 1_700_003_600_000i64,
+                        "state": "OPEN",
+                        "is_crash": false
+                    }
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_group_issues() {
+        let handler = ErrorTrackingHandler;
+        let issues = handler.group_issues(&response(), 10);
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0]["issue_id"], "issue-1");
+        assert_eq!(issues[0]["count"], 120);
+        assert_eq!(issues[0]["impacted_users"], 7);
+        assert_eq!(issues[0]["error_type"], "NullPointerException");
+        assert_eq!(issues[0]["location"]["function"], "total");
+        assert_eq!(issues[0]["first_seen"], "2023-11-14 22:13:20 UTC");
+        assert!(issues[0].get("is_crash").is_none());
+        assert!(issues[0]["message"].as_str().unwrap().len() < 600);
+
+        // Issues missing from `included` still report their counts
+        assert_eq!(issues[1]["count"], 4);
+        assert!(issues[1].get("error_type").is_none());
+    }
+
+    #[test]
+    fn test_group_issues_limit() {
+        let handler = ErrorTrackingHandler;
+        assert_eq!(handler.group_issues(&response(), 1).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_track() {
+        let client = Arc::new(
+            DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None).unwrap(),
+        );
+
+        let result = ErrorTrackingHandler::issues(client, &json!({"track": "apm"})).await;
+        assert!(result.unwrap_err().to_string().contains("track"));
+    }
+}
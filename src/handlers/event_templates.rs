@@ -0,0 +1,315 @@
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::ResponseFormatter;
+
+/// Parameters consumed by the tool itself rather than filled into placeholders
+const RESERVED_PARAMS: &[&str] = &["template", "tags"];
+
+/// An event shape with `{placeholder}` fields filled from tool arguments
+#[derive(Debug, Clone, Deserialize)]
+pub struct EventTemplate {
+    pub title: String,
+    pub text: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub alert_type: Option<String>,
+    #[serde(default)]
+    pub priority: Option<String>,
+    #[serde(default)]
+    pub source_type_name: Option<String>,
+}
+
+impl EventTemplate {
+    fn new(title: &str, text: &str, tags: &[&str], alert_type: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            text: text.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            alert_type: Some(alert_type.to_string()),
+            priority: None,
+            source_type_name: None,
+        }
+    }
+}
+
+pub struct EventTemplatesHandler;
+
+impl ResponseFormatter for EventTemplatesHandler {}
+
+impl EventTemplatesHandler {
+    fn builtin_templates() -> BTreeMap<String, EventTemplate> {
+        BTreeMap::from([
+            (
+                "deploy".to_string(),
+                EventTemplate::new(
+                    "Deploy {version}",
+                    "Deployed version {version}",
+                    &["event_type:deploy", "version:{version}"],
+                    "info",
+                ),
+            ),
+            (
+                "rollback".to_string(),
+                EventTemplate::new(
+                    "Rollback to {version}",
+                    "Rolled back to version {version}",
+                    &["event_type:rollback", "version:{version}"],
+                    "warning",
+                ),
+            ),
+            (
+                "maintenance".to_string(),
+                EventTemplate::new(
+                    "Maintenance: {summary}",
+                    "{summary}",
+                    &["event_type:maintenance"],
+                    "info",
+                ),
+            ),
+        ])
+    }
+
+    /// Parse a JSON object of name -> template
+    fn parse_templates(content: &str) -> Result<BTreeMap<String, EventTemplate>> {
+        serde_json::from_str(content)
+            .map_err(|e| DatadogError::InvalidInput(format!("Invalid event templates: {}", e)))
+    }
+
+    /// Built-in templates overlaid with those from the DD_EVENT_TEMPLATES file
+    fn load_templates() -> Result<BTreeMap<String, EventTemplate>> {
+        let mut templates = Self::builtin_templates();
+
+        if let Ok(path) = std::env::var("DD_EVENT_TEMPLATES") {
+            let content = std::fs::read_to_string(&path)?;
+            templates.extend(Self::parse_templates(&content)?);
+        }
+
+        Ok(templates)
+    }
+
+    /// Replace `{name}` with the string or number argument of the same name
+    fn render(input: &str, params: &Value, missing: &mut BTreeSet<String>) -> String {
+        let mut out = String::with_capacity(input.len());
+        let mut rest = input;
+
+        while let Some(open) = rest.find('{') {
+            out.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+
+            let name = after.find('}').map(|close| &after[..close]).filter(|n| {
+                !n.is_empty() && n.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            });
+
+            let Some(name) = name else {
+                out.push('{');
+                rest = after;
+                continue;
+            };
+
+            match &params[name] {
+                Value::String(s) => out.push_str(s),
+                Value::Number(n) => out.push_str(&n.to_string()),
+                _ => {
+                    missing.insert(name.to_string());
+                }
+            }
+            rest = &after[name.len() + 1..];
+        }
+
+        out.push_str(rest);
+        out
+    }
+
+    /// Template tags always apply; caller tags may only add keys the template does not set
+    fn merge_tags(template_tags: Vec<String>, extra: &[String]) -> (Vec<String>, Vec<String>) {
+        let tag_key = |tag: &str| tag.split(':').next().unwrap_or(tag).to_string();
+        let enforced: BTreeSet<String> = template_tags.iter().map(|t| tag_key(t)).collect();
+
+        let mut tags = template_tags;
+        let mut dropped = Vec::new();
+        for tag in extra {
+            if enforced.contains(&tag_key(tag)) {
+                dropped.push(tag.clone());
+            } else if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+
+        (tags, dropped)
+    }
+
+    fn build_event(template: &EventTemplate, params: &Value) -> Result<(Value, Vec<String>)> {
+        let mut missing = BTreeSet::new();
+
+        let title = Self::render(&template.title, params, &mut missing);
+        let text = Self::render(&template.text, params, &mut missing);
+        let template_tags: Vec<String> = template
+            .tags
+            .iter()
+            .map(|t| Self::render(t, params, &mut missing))
+            .collect();
+
+        if !missing.is_empty() {
+            return Err(DatadogError::InvalidInput(format!(
+                "Missing template parameters: {}",
+                missing.into_iter().collect::<Vec<_>>().join(", ")
+            )));
+        }
+
+        let extra: Vec<String> = params["tags"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|t| t.as_str().map(String::from))
+            .collect();
+        let (tags, dropped) = Self::merge_tags(template_tags, &extra);
+
+        let mut event = json!({
+            "title": title,
+            "text": text,
+            "tags": tags,
+        });
+        if let Some(alert_type) = &template.alert_type {
+            event["alert_type"] = json!(alert_type);
+        }
+        if let Some(priority) = &template.priority {
+            event["priority"] = json!(priority);
+        }
+        if let Some(source) = &template.source_type_name {
+            event["source_type_name"] = json!(source);
+        }
+
+        Ok((event, dropped))
+    }
+
+    pub async fn post(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = EventTemplatesHandler;
+
+        let templates = Self::load_templates()?;
+        let name = params["template"].as_str().unwrap_or_default();
+        let template = templates.get(name).ok_or_else(|| {
+            DatadogError::InvalidInput(format!(
+                "Unknown event template '{}' (available: {})",
+                name,
+                templates.keys().cloned().collect::<Vec<_>>().join(", ")
+            ))
+        })?;
+
+        let (event, dropped) = Self::build_event(template, params)?;
+        let response = client.create_event(&event).await?;
+
+        let unused: Vec<&String> = params
+            .as_object()
+            .into_iter()
+            .flat_map(|o| o.keys())
+            .filter(|k| !RESERVED_PARAMS.contains(&k.as_str()))
+            .filter(|k| {
+                let placeholder = format!("{{{}}}", k);
+                !template.title.contains(&placeholder)
+                    && !template.text.contains(&placeholder)
+                    && !template.tags.iter().any(|t| t.contains(&placeholder))
+            })
+            .collect();
+
+        let mut data = json!({
+            "template": name,
+            "event": event,
+            "id": response["event"]["id"],
+            "url": response["event"]["url"],
+        });
+        if !dropped.is_empty() {
+            data["dropped_tags"] = json!(dropped);
+        }
+        if !unused.is_empty() {
+            data["unused_params"] = json!(unused);
+        }
+
+        Ok(handler.format_detail(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+
+    #[test]
+    fn test_render_placeholders() {
+        let mut missing = BTreeSet::new();
+        let rendered = EventTemplatesHandler::render(
+            "Deploy {service} {version} to {env} {not a placeholder}",
+            &json!({"service": "web", "version": "1.2.3", "env": 3}),
+            &mut missing,
+        );
+        assert_eq!(rendered, "Deploy web 1.2.3 to 3 {not a placeholder}");
+        assert!(missing.is_empty());
+
+        EventTemplatesHandler::render("{version} {region}", &json!({}), &mut missing);
+        assert_eq!(
+            missing.into_iter().collect::<Vec<_>>(),
+            vec!["region", "version"]
+        );
+    }
+
+    #[test]
+    fn test_build_deploy_event() {
+        let templates = EventTemplatesHandler::builtin_templates();
+        let (event, dropped) = EventTemplatesHandler::build_event(
+            &templates["deploy"],
+            &json!({
+                "template": "deploy",
+                "version": "1.2.3",
+                "tags": ["service:web", "version:9.9.9", "event_type:deploy"]
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(event["title"], "Deploy 1.2.3");
+        assert_eq!(event["alert_type"], "info");
+        assert_eq!(
+            event["tags"],
+            json!(["event_type:deploy", "version:1.2.3", "service:web"])
+        );
+        assert_eq!(dropped, vec!["version:9.9.9", "event_type:deploy"]);
+    }
+
+    #[test]
+    fn test_build_event_missing_params() {
+        let templates = EventTemplatesHandler::builtin_templates();
+        let err = EventTemplatesHandler::build_event(&templates["deploy"], &json!({}))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("version"));
+    }
+
+    #[test]
+    fn test_parse_templates() {
+        let templates = EventTemplatesHandler::parse_templates(
+            r#"{"migration": {"title": "Migration {name}", "text": "Ran {name}", "tags": ["team:db"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(templates["migration"].tags, vec!["team:db"]);
+        assert!(templates["migration"].alert_type.is_none());
+
+        assert!(EventTemplatesHandler::parse_templates(r#"{"bad": {"title": "x"}}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_post_requires_writes() {
+        let client = Arc::new(
+            DatadogClient::with_tag_filter("key".to_string(), "app".to_string(), None, None)
+                .unwrap(),
+        );
+
+        let result =
+            EventTemplatesHandler::post(client, &json!({"template": "deploy", "version": "1.0"}))
+                .await;
+        assert!(result.unwrap_err().to_string().contains("DD_ALLOW_WRITES"));
+    }
+}
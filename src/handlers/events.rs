@@ -2,9 +2,35 @@ use serde_json::{Value, json};
 use std::sync::Arc;
 
 use crate::cache::DataCache;
-use crate::datadog::DatadogClient;
-use crate::error::Result;
-use crate::handlers::common::{Paginator, ResponseFormatter, TimeHandler, TimeParams};
+use crate::datadog::DatadogApi;
+use crate::datadog::models::EventV2;
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::{
+    PaginationInfo, Paginator, ResponseFormatter, TimeHandler, TimeParams, push_warning,
+};
+
+/// Limits enforced by the v1 events intake
+const MAX_TITLE_LENGTH: usize = 100;
+const MAX_TEXT_LENGTH: usize = 4000;
+const MAX_TAG_LENGTH: usize = 200;
+
+const ALERT_TYPES: &[&str] = &[
+    "error",
+    "warning",
+    "info",
+    "success",
+    "user_update",
+    "recommendation",
+    "snapshot",
+];
+const PRIORITIES: &[&str] = &["normal", "low"];
+
+/// Largest page the v2 events search accepts
+const MAX_V2_PAGE_SIZE: usize = 1000;
+
+/// Events scanned by `sources` unless the caller asks for more (each page is one request)
+const DEFAULT_SOURCE_SCAN: usize = 5000;
+const MAX_SOURCE_SCAN: usize = 20000;
 
 pub struct EventsHandler;
 
@@ -13,13 +39,230 @@ impl Paginator for EventsHandler {}
 impl ResponseFormatter for EventsHandler {}
 
 impl EventsHandler {
+    /// `api_version` argument, else DD_EVENTS_API_VERSION, else v2
+    fn api_version(params: &Value) -> Result<String> {
+        let version = params["api_version"]
+            .as_str()
+            .map(String::from)
+            .or_else(|| std::env::var("DD_EVENTS_API_VERSION").ok())
+            .unwrap_or_else(|| "v2".to_string());
+
+        match version.trim_start_matches('v') {
+            "1" => Ok("v1".to_string()),
+            "2" => Ok("v2".to_string()),
+            _ => Err(DatadogError::InvalidInput(format!(
+                "Invalid api_version '{}' (expected v1 or v2)",
+                version
+            ))),
+        }
+    }
+
+    /// v2 search query from the full-text query plus the v1-style sources/tags filters
+    fn search_query(params: &Value) -> String {
+        let split = |name: &str| -> Vec<String> {
+            params[name]
+                .as_str()
+                .into_iter()
+                .flat_map(|list| list.split(','))
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(String::from)
+                .collect()
+        };
+
+        let mut terms: Vec<String> = params["query"]
+            .as_str()
+            .map(str::trim)
+            .filter(|q| !q.is_empty() && *q != "*")
+            .map(String::from)
+            .into_iter()
+            .collect();
+
+        let sources = split("sources");
+        match sources.len() {
+            0 => {}
+            1 => terms.push(format!("source:{}", sources[0])),
+            _ => terms.push(format!("source:({})", sources.join(" OR "))),
+        }
+        terms.extend(split("tags"));
+        // Filtered in the search itself so pages and the cursor only see matching events
+        if let Some(priority) = params["priority"].as_str().filter(|p| !p.is_empty()) {
+            terms.push(format!("priority:{}", priority));
+        }
+
+        if terms.is_empty() {
+            "*".to_string()
+        } else {
+            terms.join(" ")
+        }
+    }
+
+    /// Flatten a v2 event into the same shape the v1 path returns
+    pub(crate) fn v2_event_json(event: &EventV2) -> Value {
+        let attributes = event.attributes.as_ref();
+        let fields = attributes.and_then(|a| a.attributes.as_ref());
+        let field = |name: &str| fields.and_then(|f| f.get(name)).cloned();
+
+        json!({
+            "id": field("evt").and_then(|e| e.get("id").cloned()).unwrap_or(json!(event.id)),
+            "title": field("title"),
+            "text": attributes.and_then(|a| a.message.clone()),
+            "date": attributes.and_then(|a| a.timestamp.clone()),
+            "priority": field("priority"),
+            "host": field("host").or_else(|| field("hostname")),
+            "source": field("source_type_name")
+                .or_else(|| field("evt").and_then(|e| e.get("source_id").cloned())),
+            "alert_type": field("status").or_else(|| field("alert_type")),
+            "tags": attributes.and_then(|a| a.tags.clone()),
+        })
+    }
+
     pub async fn query(
-        client: Arc<DatadogClient>,
+        client: Arc<dyn DatadogApi>,
+        cache: Arc<DataCache>,
+        params: &Value,
+    ) -> Result<Value> {
+        match Self::api_version(params)?.as_str() {
+            "v1" => Self::query_v1(client, cache, params).await,
+            _ => Self::query_v2(client, params).await,
+        }
+    }
+
+    async fn query_v2(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = EventsHandler;
+
+        let TimeParams::Timestamp { from, to } = handler.parse_time(params, 2)?;
+        let from_iso = handler.timestamp_to_iso8601(from)?;
+        let to_iso = handler.timestamp_to_iso8601(to)?;
+
+        let (_, page_size) = handler.parse_pagination(params);
+        let limit = page_size.min(MAX_V2_PAGE_SIZE);
+        let cursor = params["cursor"].as_str().map(String::from);
+        let query = Self::search_query(params);
+        if params["page"].as_u64().is_some_and(|page| page > 0) {
+            push_warning(
+                "'page' is ignored by the v2 events API; pass pagination.next_cursor as 'cursor', or api_version 'v1' for page numbers".to_string(),
+            );
+        }
+
+        let response = client
+            .search_events(&query, &from_iso, &to_iso, limit, cursor)
+            .await?;
+
+        let events: Vec<Value> = response
+            .data
+            .iter()
+            .flatten()
+            .map(Self::v2_event_json)
+            .collect();
+
+        let next_cursor = response.meta.and_then(|m| m.page).and_then(|p| p.after);
+        let mut pagination = json!(PaginationInfo::from_cursor(
+            events.len(),
+            limit,
+            next_cursor.is_some()
+        ));
+        if let Some(cursor) = next_cursor {
+            pagination["next_cursor"] = json!(cursor);
+        }
+        let meta = json!({
+            "api_version": "v2",
+            "query": query,
+            "from": crate::utils::format_timestamp(from),
+            "to": crate::utils::format_timestamp(to)
+        });
+
+        Ok(handler.format_list(json!(events), Some(pagination), Some(meta)))
+    }
+
+    /// Count events per source, keeping the newest event seen for each (events arrive newest first)
+    fn count_sources(events: &[Value]) -> Vec<Value> {
+        let mut sources: Vec<(String, usize, Value, Value)> = Vec::new();
+        for event in events {
+            let source = match &event["source"] {
+                Value::String(s) => s.clone(),
+                Value::Null => "unknown".to_string(),
+                other => other.to_string(),
+            };
+            match sources.iter_mut().find(|(s, ..)| *s == source) {
+                Some(entry) => entry.1 += 1,
+                None => sources.push((source, 1, event["date"].clone(), event["title"].clone())),
+            }
+        }
+
+        sources.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        sources
+            .into_iter()
+            .map(|(source, count, last_seen, title)| {
+                json!({
+                    "source": source,
+                    "count": count,
+                    "last_seen": last_seen,
+                    "example_title": title,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn sources(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = EventsHandler;
+
+        let mut window = params.clone();
+        if window["from"].is_null() {
+            window["from"] = json!("7 days ago");
+        }
+        let TimeParams::Timestamp { from, to } = handler.parse_time(&window, 2)?;
+        let from_iso = handler.timestamp_to_iso8601(from)?;
+        let to_iso = handler.timestamp_to_iso8601(to)?;
+
+        let max_events = params["max_events"]
+            .as_u64()
+            .map(|m| m as usize)
+            .unwrap_or(DEFAULT_SOURCE_SCAN)
+            .clamp(1, MAX_SOURCE_SCAN);
+        let query = Self::search_query(params);
+
+        let mut events = Vec::new();
+        let mut cursor = None;
+        loop {
+            let limit = (max_events - events.len()).min(MAX_V2_PAGE_SIZE);
+            let response = client
+                .search_events(&query, &from_iso, &to_iso, limit, cursor)
+                .await?;
+            events.extend(response.data.iter().flatten().map(Self::v2_event_json));
+
+            cursor = response.meta.and_then(|m| m.page).and_then(|p| p.after);
+            if cursor.is_none() || events.len() >= max_events {
+                break;
+            }
+        }
+
+        let sources = Self::count_sources(&events);
+        let meta = json!({
+            "query": query,
+            "events_scanned": events.len(),
+            // Counts cover only the newest events when the window holds more
+            "truncated": cursor.is_some(),
+            "from": crate::utils::format_timestamp(from),
+            "to": crate::utils::format_timestamp(to),
+        });
+
+        Ok(handler.format_list(json!(sources), None, Some(meta)))
+    }
+
+    async fn query_v1(
+        client: Arc<dyn DatadogApi>,
         cache: Arc<DataCache>,
         params: &Value,
     ) -> Result<Value> {
         let handler = EventsHandler;
 
+        if params["query"].as_str().is_some_and(|q| !q.is_empty()) {
+            return Err(DatadogError::InvalidInput(
+                "Full-text 'query' requires api_version v2".to_string(),
+            ));
+        }
+
         let priority = params["priority"].as_str().map(|s| s.to_string());
 
         let sources = params["sources"].as_str().map(|s| s.to_string());
@@ -46,15 +289,28 @@ impl EventsHandler {
             }),
         );
 
+        let mut stale = None;
         let events = if page == 0 {
-            let response = client
+            match client
                 .query_events(start, end, priority.clone(), sources.clone(), tags.clone())
-                .await?;
-            let events = response.events.unwrap_or_default();
-            cache.set_events(cache_key.clone(), events).await;
-            cache
-                .get_or_fetch_events(&cache_key, || async { unreachable!("Just inserted") })
-                .await?
+                .await
+            {
+                Ok(response) => {
+                    let events = response.events.unwrap_or_default();
+                    cache.set_events(cache_key.clone(), events).await;
+                    cache
+                        .get_or_fetch_events(&cache_key, || async { unreachable!("Just inserted") })
+                        .await?
+                }
+                Err(e) if e.is_transient() => match cache.get_stale_events(&cache_key).await {
+                    Some((events, age)) => {
+                        stale = Some(DataCache::stale_meta(age, &e));
+                        events
+                    }
+                    None => return Err(e),
+                },
+                Err(e) => return Err(e),
+            }
         } else {
             cache
                 .get_or_fetch_events(&cache_key, || async {
@@ -87,18 +343,120 @@ impl EventsHandler {
         );
 
         let pagination = handler.format_pagination(page, page_size, events.len());
-        let meta = json!({
+        let mut meta = json!({
+            "api_version": "v1",
             "from": crate::utils::format_timestamp(start),
             "to": crate::utils::format_timestamp(end)
         });
+        if let Some(Value::Object(stale)) = stale {
+            meta.as_object_mut().unwrap().extend(stale);
+        }
 
         Ok(handler.format_list(data, Some(pagination), Some(meta)))
     }
+
+    fn one_of(params: &Value, name: &str, allowed: &[&str]) -> Result<Option<String>> {
+        match params[name].as_str() {
+            Some(value) if !allowed.contains(&value) => Err(DatadogError::InvalidInput(format!(
+                "Invalid {} '{}' (expected one of: {})",
+                name,
+                value,
+                allowed.join(", ")
+            ))),
+            value => Ok(value.map(String::from)),
+        }
+    }
+
+    /// Tags as an array or comma-separated string
+    pub(crate) fn parse_tags(tags: &Value) -> Result<Vec<String>> {
+        let tags: Vec<String> = match tags {
+            Value::Array(items) => items
+                .iter()
+                .filter_map(|t| t.as_str())
+                .map(|t| t.trim().to_string())
+                .collect(),
+            Value::String(list) => list.split(',').map(|t| t.trim().to_string()).collect(),
+            _ => Vec::new(),
+        };
+        let tags: Vec<String> = tags.into_iter().filter(|t| !t.is_empty()).collect();
+
+        if let Some(tag) = tags
+            .iter()
+            .find(|t| t.len() > MAX_TAG_LENGTH || t.contains(char::is_whitespace))
+        {
+            return Err(DatadogError::InvalidInput(format!(
+                "Invalid tag '{}': tags cannot contain whitespace or exceed {} characters",
+                tag, MAX_TAG_LENGTH
+            )));
+        }
+
+        Ok(tags)
+    }
+
+    /// Validate caller input into a v1 event body
+    fn build_event(params: &Value) -> Result<Value> {
+        let title = params["title"]
+            .as_str()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| DatadogError::InvalidInput("Missing 'title' parameter".to_string()))?;
+        if title.chars().count() > MAX_TITLE_LENGTH {
+            return Err(DatadogError::InvalidInput(format!(
+                "Event title exceeds {} characters",
+                MAX_TITLE_LENGTH
+            )));
+        }
+
+        let text = params["text"].as_str().unwrap_or_default();
+        if text.chars().count() > MAX_TEXT_LENGTH {
+            return Err(DatadogError::InvalidInput(format!(
+                "Event text exceeds {} characters",
+                MAX_TEXT_LENGTH
+            )));
+        }
+
+        let mut event = json!({
+            "title": title,
+            "text": text,
+            "tags": Self::parse_tags(&params["tags"])?,
+        });
+
+        if let Some(alert_type) = Self::one_of(params, "alert_type", ALERT_TYPES)? {
+            event["alert_type"] = json!(alert_type);
+        }
+        if let Some(priority) = Self::one_of(params, "priority", PRIORITIES)? {
+            event["priority"] = json!(priority);
+        }
+        if let Some(date) = params["date_happened"].as_str() {
+            event["date_happened"] = json!(crate::utils::parse_time(date)?);
+        }
+        for field in ["host", "aggregation_key", "source_type_name"] {
+            if let Some(value) = params[field].as_str().filter(|v| !v.is_empty()) {
+                event[field] = json!(value);
+            }
+        }
+
+        Ok(event)
+    }
+
+    pub async fn post(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = EventsHandler;
+
+        let event = Self::build_event(params)?;
+        let response = client.create_event(&event).await?;
+
+        Ok(handler.format_detail(json!({
+            "id": response["event"]["id"],
+            "url": response["event"]["url"],
+            "event": event,
+        })))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::datadog::DatadogClient;
     use serde_json::json;
 
     #[test]
@@ -119,6 +477,129 @@ mod tests {
         assert_eq!(params["tags"].as_str(), Some("env:prod,service:api"));
     }
 
+    #[test]
+    fn test_build_event() {
+        let event = EventsHandler::build_event(&json!({
+            "title": "Deployed checkout v1.2.3",
+            "text": "Rolled out by CI",
+            "tags": "service:checkout, env:prod",
+            "alert_type": "success",
+            "date_happened": "1700000000"
+        }))
+        .unwrap();
+
+        assert_eq!(event["tags"], json!(["service:checkout", "env:prod"]));
+        assert_eq!(event["alert_type"], "success");
+        assert_eq!(event["date_happened"], 1700000000);
+        assert!(event.get("priority").is_none());
+    }
+
+    #[test]
+    fn test_build_event_validation() {
+        assert!(EventsHandler::build_event(&json!({"title": " "})).is_err());
+        assert!(EventsHandler::build_event(&json!({"title": "x".repeat(101)})).is_err());
+        assert!(
+            EventsHandler::build_event(&json!({"title": "Deploy", "alert_type": "critical"}))
+                .is_err()
+        );
+        assert!(
+            EventsHandler::build_event(&json!({"title": "Deploy", "tags": ["bad tag"]})).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_requires_writes() {
+        let client = Arc::new(
+            DatadogClient::with_tag_filter("key".to_string(), "app".to_string(), None, None)
+                .unwrap(),
+        );
+
+        let result = EventsHandler::post(client, &json!({"title": "Deploy"})).await;
+        assert!(result.unwrap_err().to_string().contains("DD_ALLOW_WRITES"));
+    }
+
+    #[test]
+    fn test_api_version() {
+        assert_eq!(
+            EventsHandler::api_version(&json!({"api_version": "v1"})).unwrap(),
+            "v1"
+        );
+        assert_eq!(
+            EventsHandler::api_version(&json!({"api_version": "2"})).unwrap(),
+            "v2"
+        );
+        assert!(EventsHandler::api_version(&json!({"api_version": "v3"})).is_err());
+    }
+
+    #[test]
+    fn test_search_query() {
+        assert_eq!(EventsHandler::search_query(&json!({})), "*");
+        assert_eq!(
+            EventsHandler::search_query(&json!({
+                "query": "deploy",
+                "sources": "github,jenkins",
+                "tags": "env:prod, service:api"
+            })),
+            "deploy source:(github OR jenkins) env:prod service:api"
+        );
+        assert_eq!(
+            EventsHandler::search_query(&json!({"sources": "my_app"})),
+            "source:my_app"
+        );
+        assert_eq!(
+            EventsHandler::search_query(&json!({"query": "deploy", "priority": "low"})),
+            "deploy priority:low"
+        );
+    }
+
+    #[test]
+    fn test_v2_event_json() {
+        let event: EventV2 = serde_json::from_value(json!({
+            "id": "AAAAAY",
+            "type": "event",
+            "attributes": {
+                "timestamp": "2024-01-01T00:00:00Z",
+                "message": "Deployed v1.2.3",
+                "tags": ["env:prod"],
+                "attributes": {
+                    "title": "Deploy checkout",
+                    "priority": "normal",
+                    "status": "info",
+                    "hostname": "web-1",
+                    "evt": {"id": "7321", "source_id": 1}
+                }
+            }
+        }))
+        .unwrap();
+
+        let data = EventsHandler::v2_event_json(&event);
+        assert_eq!(data["id"], "7321");
+        assert_eq!(data["title"], "Deploy checkout");
+        assert_eq!(data["text"], "Deployed v1.2.3");
+        assert_eq!(data["host"], "web-1");
+        assert_eq!(data["alert_type"], "info");
+        assert_eq!(data["tags"], json!(["env:prod"]));
+    }
+
+    #[test]
+    fn test_count_sources() {
+        let events = vec![
+            json!({"source": "github", "date": "2024-01-03T00:00:00Z", "title": "PR merged"}),
+            json!({"source": "jenkins", "date": "2024-01-02T00:00:00Z", "title": "Build"}),
+            json!({"source": "github", "date": "2024-01-01T00:00:00Z", "title": "Push"}),
+            json!({"source": null, "title": "Mystery"}),
+        ];
+
+        let sources = EventsHandler::count_sources(&events);
+        assert_eq!(sources.len(), 3);
+        assert_eq!(sources[0]["source"], "github");
+        assert_eq!(sources[0]["count"], 2);
+        assert_eq!(sources[0]["last_seen"], "2024-01-03T00:00:00Z");
+        assert_eq!(sources[0]["example_title"], "PR merged");
+        assert_eq!(sources[1]["source"], "jenkins");
+        assert_eq!(sources[2]["source"], "unknown");
+    }
+
     #[test]
     fn test_pagination_parameters() {
         let handler = EventsHandler;
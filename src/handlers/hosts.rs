@@ -1,11 +1,46 @@
+use futures::FutureExt;
 use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
 
-use crate::datadog::DatadogClient;
-use crate::error::Result;
+use crate::datadog::DatadogApi;
+use crate::datadog::batch::{ApiCall, execute_batch};
+use crate::datadog::models::{Host, HostTagsResponse, HostsResponse, MetricSeries};
+use crate::error::{DatadogError, Result};
 use crate::handlers::common::{
-    PaginationInfo, ResponseFilter, ResponseFormatter, TagFilter, TimeHandler, TimeParams,
+    PaginationInfo, ResponseFilter, ResponseFormatter, TagFilter, TagSelector, TimeHandler,
+    TimeParams,
 };
+use crate::handlers::events::EventsHandler;
+
+/// Standard system metric bundle fetched for a host snapshot: (category, query template)
+const HOST_METRIC_BUNDLE: &[(&str, &str)] = &[
+    ("cpu", "avg:system.cpu.user{host:HOST}"),
+    ("cpu", "avg:system.cpu.system{host:HOST}"),
+    ("cpu", "avg:system.cpu.iowait{host:HOST}"),
+    ("cpu", "avg:system.load.norm.1{host:HOST}"),
+    ("memory", "avg:system.mem.pct_usable{host:HOST}"),
+    ("memory", "avg:system.mem.used{host:HOST}"),
+    ("memory", "avg:system.swap.pct_free{host:HOST}"),
+    ("disk", "max:system.disk.in_use{host:HOST} by {device}"),
+    ("disk", "avg:system.io.util{host:HOST} by {device}"),
+    ("network", "sum:system.net.bytes_rcvd{host:HOST}"),
+    ("network", "sum:system.net.bytes_sent{host:HOST}"),
+    ("network", "sum:system.net.packets_in.error{host:HOST}"),
+];
+
+/// Group of the hosts without a tag for the `group_by` key
+const UNTAGGED_GROUP: &str = "(none)";
+
+/// Hosts returned unless `count` says otherwise, and per request (the API's maximum)
+const DEFAULT_HOST_COUNT: usize = 100;
+const HOSTS_PAGE_SIZE: usize = 1000;
+
+/// Upper bound on hosts fetched by one call; also the default `count` when grouping
+const MAX_HOST_COUNT: usize = 25_000;
+
+/// Ways `datadog_host_tags_update` changes a host's tags
+const TAG_UPDATE_MODES: &[&str] = &["add", "replace", "remove"];
 
 pub struct HostsHandler;
 
@@ -14,8 +49,114 @@ impl TagFilter for HostsHandler {}
 impl ResponseFilter for HostsHandler {}
 impl ResponseFormatter for HostsHandler {}
 
+/// Arguments of a host list request shared by all of its pages
+struct HostsQuery {
+    filter: Option<String>,
+    from: i64,
+    sort_field: Option<String>,
+    sort_dir: Option<String>,
+}
+
 impl HostsHandler {
-    pub async fn list(client: Arc<DatadogClient>, params: &Value) -> Result<Value> {
+    async fn fetch_page(
+        client: &dyn DatadogApi,
+        query: &HostsQuery,
+        start: usize,
+        count: usize,
+    ) -> Result<HostsResponse> {
+        client
+            .list_hosts(
+                query.filter.clone(),
+                Some(query.from),
+                query.sort_field.clone(),
+                query.sort_dir.clone(),
+                Some(start as i32),
+                Some(count as i32),
+            )
+            .await
+    }
+
+    /// Up to `count` hosts from offset `start`. Past the API's 1000 hosts per request, the
+    /// first page tells how many match and the remaining pages are fetched concurrently.
+    async fn fetch(
+        client: &dyn DatadogApi,
+        query: &HostsQuery,
+        start: usize,
+        count: usize,
+    ) -> Result<HostsResponse> {
+        let mut response =
+            Self::fetch_page(client, query, start, count.min(HOSTS_PAGE_SIZE)).await?;
+
+        let end = (start + count).min(response.total_matching.max(0) as usize);
+        let fetched_to = start + response.host_list.len();
+        if response.host_list.len() < HOSTS_PAGE_SIZE || fetched_to >= end {
+            return Ok(response);
+        }
+
+        let calls: Vec<ApiCall<'_, HostsResponse>> = (fetched_to..end)
+            .step_by(HOSTS_PAGE_SIZE)
+            .map(|offset| {
+                Self::fetch_page(client, query, offset, HOSTS_PAGE_SIZE.min(end - offset)).boxed()
+            })
+            .collect();
+        for page in execute_batch(calls, client.max_concurrency()).await {
+            let page = page?;
+            response.total_returned += page.total_returned;
+            response.host_list.extend(page.host_list);
+        }
+
+        Ok(response)
+    }
+
+    /// Host counts with up/down/muted breakdown per value of the `key` tag, from any tag
+    /// source; a host tagged with several values counts in each, largest groups first
+    fn group_hosts(hosts: &[Host], key: &str) -> Vec<Value> {
+        #[derive(Default)]
+        struct Group {
+            count: usize,
+            up: usize,
+            muted: usize,
+        }
+
+        let mut groups: HashMap<String, Group> = HashMap::new();
+        for host in hosts {
+            let values: BTreeSet<&str> = host
+                .tags_by_source
+                .iter()
+                .flat_map(|sources| sources.values().flatten())
+                .filter_map(|tag| tag.strip_prefix(key)?.strip_prefix(':'))
+                .collect();
+            let values = if values.is_empty() {
+                vec![UNTAGGED_GROUP]
+            } else {
+                values.into_iter().collect()
+            };
+
+            for value in values {
+                let group = groups.entry(value.to_string()).or_default();
+                group.count += 1;
+                group.up += usize::from(host.up);
+                group.muted += usize::from(host.is_muted);
+            }
+        }
+
+        let mut groups: Vec<(String, Group)> = groups.into_iter().collect();
+        groups.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(&b.0)));
+        groups
+            .into_iter()
+            .map(|(value, group)| {
+                json!({
+                    "value": value,
+                    "count": group.count,
+                    "up": group.up,
+                    "down": group.count - group.up,
+                    "muted": group.muted,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn list(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
         let handler = HostsHandler;
 
         let filter = params["filter"].as_str().map(|s| s.to_string());
@@ -26,31 +167,57 @@ impl HostsHandler {
 
         let time = handler.parse_time(params, 1)?;
         let TimeParams::Timestamp { from, .. } = time;
-        let from = Some(from);
 
         let start = params["start"].as_i64().unwrap_or(0) as usize;
 
-        let count = params["count"].as_i64().unwrap_or(100) as usize;
+        // Grouping looks at the whole fleet unless told otherwise
+        let default_count = if params["group_by"].is_string() {
+            MAX_HOST_COUNT
+        } else {
+            DEFAULT_HOST_COUNT
+        };
+        let count = params["count"]
+            .as_u64()
+            .map(|c| c as usize)
+            .unwrap_or(default_count)
+            .clamp(1, MAX_HOST_COUNT);
 
-        let response = client
-            .list_hosts(
-                filter,
-                from,
-                sort_field,
-                sort_dir,
-                Some(start as i32),
-                Some(count as i32),
-            )
-            .await?;
+        let query = HostsQuery {
+            filter,
+            from,
+            sort_field,
+            sort_dir,
+        };
+        let response = Self::fetch(client.as_ref(), &query, start, count).await?;
+
+        if let Some(key) = params["group_by"].as_str().filter(|k| !k.is_empty()) {
+            let key = key.trim_end_matches(':');
+            let groups = Self::group_hosts(&response.host_list, key);
+            let mut meta = json!({
+                "group_by": key,
+                "groups": groups.len(),
+                "hosts_grouped": response.host_list.len(),
+                "total_matching": response.total_matching,
+            });
+            if (response.host_list.len() as i64) < response.total_matching {
+                meta["note"] = json!(format!(
+                    "Only {} of {} matching hosts were grouped; raise count or page with start",
+                    response.host_list.len(),
+                    response.total_matching
+                ));
+            }
+            return Ok(handler.format_list(json!(groups), None, Some(meta)));
+        }
 
         // Get tag filter (same pattern as logs/spans)
         let tag_filter = params["tag_filter"]
             .as_str()
-            .or_else(|| client.get_tag_filter())
+            .or_else(|| client.get_tag_filter_for("hosts"))
             .unwrap_or("*");
+        let tag_filter = TagSelector::parse(tag_filter)?;
 
-        let data = json!(response.host_list.iter().map(|host| {
-            let filtered_tags = handler.filter_tags_map(host.tags_by_source.as_ref(), tag_filter);
+        let data = handler.project_fields(response.host_list.iter().map(|host| {
+            let filtered_tags = handler.filter_tags_map(host.tags_by_source.as_ref(), &tag_filter);
 
             // Remove empty tags field if filter results in empty
             let mut host_json = json!({
@@ -71,7 +238,7 @@ impl HostsHandler {
                 }
 
             host_json
-        }).collect::<Vec<_>>());
+        }).collect(), params);
 
         // Use PaginationInfo for consistent pagination structure
         let pagination =
@@ -82,11 +249,262 @@ impl HostsHandler {
             "pagination": pagination
         }))
     }
+
+    fn series_summary(series: &MetricSeries) -> Value {
+        let values: Vec<f64> = series
+            .pointlist
+            .as_ref()
+            .map(|points| {
+                points
+                    .iter()
+                    .filter_map(|p| p.get(1).copied().flatten())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let round = |v: f64| (v * 100.0).round() / 100.0;
+        let mut summary = json!({
+            "metric": series.metric,
+            "latest": values.last().copied().map(round),
+            "avg": (!values.is_empty())
+                .then(|| round(values.iter().sum::<f64>() / values.len() as f64)),
+            "max": values.iter().copied().reduce(f64::max).map(round),
+        });
+
+        // Keep the group-by tags (e.g. device) that distinguish split series
+        if let Some(tags) = series.tag_set.as_ref().filter(|t| !t.is_empty()) {
+            summary["tags"] = json!(tags);
+        }
+        if let Some(unit) = series
+            .unit
+            .as_ref()
+            .and_then(|units| units.first())
+            .and_then(|u| u.as_ref())
+        {
+            summary["unit"] = json!(unit.name);
+        }
+
+        summary
+    }
+
+    /// Group series by bundle category using each series' query index
+    fn group_snapshot(series: &[MetricSeries]) -> Value {
+        let mut snapshot = json!({});
+        for (category, _) in HOST_METRIC_BUNDLE {
+            snapshot[*category] = json!([]);
+        }
+
+        for s in series {
+            let category = s
+                .query_index
+                .and_then(|i| HOST_METRIC_BUNDLE.get(i as usize))
+                .map(|(category, _)| *category)
+                .unwrap_or("other");
+            if snapshot.get(category).is_none() {
+                snapshot[category] = json!([]);
+            }
+            if let Some(list) = snapshot[category].as_array_mut() {
+                list.push(Self::series_summary(s));
+            }
+        }
+
+        snapshot
+    }
+
+    pub async fn metrics(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = HostsHandler;
+
+        let host = Self::required_host(params)?;
+
+        let time = handler.parse_time(params, 1)?;
+        let TimeParams::Timestamp { from, to } = time;
+
+        // One request: the v1 query API evaluates comma-separated queries together
+        let query = HOST_METRIC_BUNDLE
+            .iter()
+            .map(|(_, template)| template.replace("HOST", host))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let response = client.query_metrics(&query, from, to).await?;
+
+        let meta = json!({
+            "host": host,
+            "from": crate::utils::format_timestamp(from),
+            "to": crate::utils::format_timestamp(to),
+            "series_count": response.series.len(),
+        });
+
+        Ok(handler.format_list(Self::group_snapshot(&response.series), None, Some(meta)))
+    }
+
+    /// The `host` argument; it becomes a path segment, so separators and `..` are refused
+    fn required_host(params: &Value) -> Result<&str> {
+        let host = params["host"]
+            .as_str()
+            .filter(|h| !h.is_empty())
+            .ok_or_else(|| DatadogError::InvalidInput("Missing 'host' parameter".to_string()))?;
+        if host.contains(['/', '?', '#']) || host.contains("..") {
+            return Err(DatadogError::InvalidInput(format!(
+                "Invalid host name '{}'",
+                host
+            )));
+        }
+        Ok(host)
+    }
+
+    /// Resolve the mute end from 'duration' (e.g., '2h') or 'end' (time expression)
+    fn mute_end(params: &Value, now: i64) -> Result<Option<i64>> {
+        if let Some(duration) = params["duration"].as_str().filter(|d| !d.is_empty()) {
+            let secs = crate::utils::parse_duration(duration).ok_or_else(|| {
+                DatadogError::InvalidInput(format!(
+                    "Invalid 'duration' value '{}' (expected e.g. '30m', '2h', '1d')",
+                    duration
+                ))
+            })?;
+            return Ok(Some(now + secs));
+        }
+
+        match params["end"].as_str().filter(|e| !e.is_empty()) {
+            Some(end) => {
+                let end = crate::utils::parse_time(end)?;
+                if end <= now {
+                    return Err(DatadogError::InvalidInput(
+                        "'end' must be in the future".to_string(),
+                    ));
+                }
+                Ok(Some(end))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn mute_json(response: &crate::datadog::models::HostMuteResponse) -> Value {
+        json!({
+            "action": response.action,
+            "host": response.hostname,
+            "message": response.message,
+            "end": response.end.map(crate::utils::format_timestamp),
+        })
+    }
+
+    pub async fn mute(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = HostsHandler;
+
+        let host = Self::required_host(params)?;
+        let end = Self::mute_end(params, chrono::Utc::now().timestamp())?;
+        let message = params["message"].as_str().map(String::from);
+        let override_existing = params["override"].as_bool().unwrap_or(false);
+
+        let response = client
+            .mute_host(host, end, message, override_existing)
+            .await?;
+
+        Ok(handler.format_detail(Self::mute_json(&response)))
+    }
+
+    pub async fn unmute(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = HostsHandler;
+
+        let host = Self::required_host(params)?;
+
+        let response = client.unmute_host(host).await?;
+
+        Ok(handler.format_detail(Self::mute_json(&response)))
+    }
+
+    /// Host tags sorted, with the keys that carry more than one value (often a tagging
+    /// mistake such as `env:prod` next to `env:production`)
+    fn tags_json(host: &str, source: Option<&str>, response: &HostTagsResponse) -> Value {
+        let mut tags = response.tags.clone();
+        tags.sort();
+        tags.dedup();
+
+        let mut values_by_key: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for tag in &tags {
+            if let Some((key, value)) = tag.split_once(':') {
+                values_by_key.entry(key).or_default().push(value);
+            }
+        }
+        values_by_key.retain(|_, values| values.len() > 1);
+
+        json!({
+            "host": response.host.as_deref().unwrap_or(host),
+            "source": source,
+            "count": tags.len(),
+            "tags": tags,
+            "multi_valued_keys": values_by_key,
+        })
+    }
+
+    pub async fn tags(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = HostsHandler;
+
+        let host = Self::required_host(params)?;
+        let source = params["source"].as_str().filter(|s| !s.is_empty());
+
+        let response = client.get_host_tags(host, source.map(String::from)).await?;
+
+        Ok(handler.format_detail(Self::tags_json(host, source, &response)))
+    }
+
+    /// Add tags, replace the tags of a source, or remove some tags (the rest of the source's
+    /// tags are written back)
+    pub async fn update_tags(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = HostsHandler;
+
+        let host = Self::required_host(params)?;
+        let source = params["source"].as_str().filter(|s| !s.is_empty());
+        let mode = params["mode"].as_str().unwrap_or("add");
+        if !TAG_UPDATE_MODES.contains(&mode) {
+            return Err(DatadogError::InvalidInput(format!(
+                "Invalid mode '{}' (expected one of: {})",
+                mode,
+                TAG_UPDATE_MODES.join(", ")
+            )));
+        }
+        let tags = EventsHandler::parse_tags(&params["tags"])?;
+        if tags.is_empty() && mode != "replace" {
+            return Err(DatadogError::InvalidInput(
+                "Missing 'tags' parameter (an array or comma-separated string)".to_string(),
+            ));
+        }
+        client.ensure_writes_allowed()?;
+
+        let response = match mode {
+            "add" => {
+                client
+                    .update_host_tags(host, &tags, source.map(String::from), false)
+                    .await?
+            }
+            "replace" => {
+                client
+                    .update_host_tags(host, &tags, source.map(String::from), true)
+                    .await?
+            }
+            _ => {
+                let current = client.get_host_tags(host, source.map(String::from)).await?;
+                let remaining: Vec<String> = current
+                    .tags
+                    .into_iter()
+                    .filter(|tag| !tags.contains(tag))
+                    .collect();
+                client
+                    .update_host_tags(host, &remaining, source.map(String::from), true)
+                    .await?
+            }
+        };
+
+        let mut result = Self::tags_json(host, source, &response);
+        result["mode"] = json!(mode);
+        Ok(handler.format_detail(result))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::datadog::DatadogClient;
     use serde_json::json;
 
     #[test]
@@ -159,4 +577,316 @@ mod tests {
         assert!(response.get("data").is_some());
         assert!(response.get("meta").is_some());
     }
+
+    fn series(query_index: i64, metric: &str, tag_set: &[&str], values: &[f64]) -> MetricSeries {
+        serde_json::from_value(json!({
+            "metric": metric,
+            "scope": "host:web-1",
+            "expression": metric,
+            "tag_set": tag_set,
+            "query_index": query_index,
+            "pointlist": values.iter().enumerate().map(|(i, v)| json!([i as f64, v])).collect::<Vec<_>>()
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_series_summary() {
+        let summary =
+            HostsHandler::series_summary(&series(0, "system.cpu.user", &[], &[10.0, 30.0, 20.0]));
+        assert_eq!(summary["latest"], 20.0);
+        assert_eq!(summary["avg"], 20.0);
+        assert_eq!(summary["max"], 30.0);
+        assert!(summary.get("tags").is_none());
+    }
+
+    #[test]
+    fn test_group_snapshot_by_category() {
+        let snapshot = HostsHandler::group_snapshot(&[
+            series(0, "system.cpu.user", &[], &[1.0]),
+            series(7, "system.disk.in_use", &["device:/dev/sda1"], &[0.5]),
+            series(7, "system.disk.in_use", &["device:/dev/sdb1"], &[0.9]),
+            series(10, "system.net.bytes_sent", &[], &[]),
+        ]);
+
+        assert_eq!(snapshot["cpu"].as_array().unwrap().len(), 1);
+        assert_eq!(snapshot["memory"].as_array().unwrap().len(), 0);
+        assert_eq!(snapshot["disk"][1]["tags"][0], "device:/dev/sdb1");
+        assert!(snapshot["network"][0]["latest"].is_null());
+    }
+
+    #[test]
+    fn test_mute_end() {
+        let now = 1_700_000_000;
+
+        assert_eq!(HostsHandler::mute_end(&json!({}), now).unwrap(), None);
+        assert_eq!(
+            HostsHandler::mute_end(&json!({"duration": "2h"}), now).unwrap(),
+            Some(now + 7200)
+        );
+        assert_eq!(
+            HostsHandler::mute_end(&json!({"end": "1700003600"}), now).unwrap(),
+            Some(now + 3600)
+        );
+        assert!(HostsHandler::mute_end(&json!({"end": "1600000000"}), now).is_err());
+        assert!(HostsHandler::mute_end(&json!({"duration": "soon"}), now).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mute_requires_writes() {
+        let client = Arc::new(
+            DatadogClient::with_tag_filter("key".to_string(), "app".to_string(), None, None)
+                .unwrap(),
+        );
+
+        let result = HostsHandler::mute(client.clone(), &json!({"host": "web-1"})).await;
+        assert!(result.unwrap_err().to_string().contains("DD_ALLOW_WRITES"));
+
+        let result = HostsHandler::unmute(client, &json!({"host": "web-1"})).await;
+        assert!(result.unwrap_err().to_string().contains("DD_ALLOW_WRITES"));
+    }
+
+    #[tokio::test]
+    async fn test_unmute_encodes_host() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/host/web%201/unmute"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"action": "Unmuted", "hostname": "web 1"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = DatadogClient::new("key".to_string(), "app".to_string(), None)
+            .unwrap()
+            .with_api_url(&mock_server.uri())
+            .with_writes(true);
+        let client: Arc<dyn DatadogApi> = Arc::new(client);
+
+        assert!(client.as_ref().unmute_host("web 1").await.is_ok());
+        assert!(client.as_ref().unmute_host("..").await.is_err());
+    }
+
+    #[test]
+    fn test_metrics_missing_host() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let client = Arc::new(
+                DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None)
+                    .unwrap(),
+            );
+
+            let result = HostsHandler::metrics(client, &json!({})).await;
+            assert!(result.is_err());
+        });
+    }
+
+    #[test]
+    fn test_group_hosts() {
+        let host = |name: &str, up: bool, tags: Value| -> Host {
+            serde_json::from_value(json!({
+                "name": name,
+                "host_name": name,
+                "up": up,
+                "is_muted": false,
+                "tags_by_source": tags
+            }))
+            .unwrap()
+        };
+        let hosts = vec![
+            host(
+                "a",
+                true,
+                json!({"Amazon Web Services": ["availability-zone:us-east-1a"]}),
+            ),
+            host(
+                "b",
+                false,
+                json!({"Amazon Web Services": ["availability-zone:us-east-1a"]}),
+            ),
+            host(
+                "c",
+                true,
+                json!({"Amazon Web Services": ["availability-zone:us-east-1b"], "Datadog": ["availability-zone:us-east-1b"]}),
+            ),
+            host("d", true, json!({"Datadog": ["env:prod"]})),
+        ];
+
+        let groups = HostsHandler::group_hosts(&hosts, "availability-zone");
+        assert_eq!(
+            groups[0],
+            json!({"value": "us-east-1a", "count": 2, "up": 1, "down": 1, "muted": 0})
+        );
+        // The same value from two sources counts once
+        assert_eq!(groups[1]["value"], "(none)");
+        assert_eq!(groups[2]["value"], "us-east-1b");
+        assert_eq!(groups[2]["count"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_fetches_pages_past_the_api_maximum() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        for (start, count) in [("0", "1000"), ("1000", "1000"), ("2000", "500")] {
+            let hosts: Vec<Value> = (0..count.parse::<usize>().unwrap())
+                .map(|i| {
+                    json!({
+                        "name": format!("host-{}-{}", start, i),
+                        "host_name": format!("host-{}-{}", start, i),
+                        "up": true,
+                        "is_muted": false,
+                        "tags_by_source": {"Datadog": ["env:prod"]}
+                    })
+                })
+                .collect();
+            Mock::given(method("GET"))
+                .and(path("/api/v1/hosts"))
+                .and(query_param("start", start))
+                .and(query_param("count", count))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "total_matching": 2500,
+                    "total_returned": hosts.len(),
+                    "host_list": hosts
+                })))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+
+        let client = DatadogClient::new("key".to_string(), "app".to_string(), None)
+            .unwrap()
+            .with_api_url(&mock_server.uri())
+            .with_max_concurrency(2);
+        let result = HostsHandler::list(Arc::new(client), &json!({"group_by": "env"}))
+            .await
+            .unwrap();
+
+        assert_eq!(result["meta"]["hosts_grouped"], 2500);
+        assert_eq!(result["data"][0]["value"], "prod");
+        assert_eq!(result["data"][0]["count"], 2500);
+        assert!(result["meta"].get("note").is_none());
+    }
+
+    #[test]
+    fn test_tags_json() {
+        let response = HostTagsResponse {
+            host: None,
+            tags: [
+                "role:db",
+                "env:prod",
+                "env:production",
+                "env:prod",
+                "legacy",
+            ]
+            .iter()
+            .map(|t| t.to_string())
+            .collect(),
+        };
+
+        let value = HostsHandler::tags_json("db-1", Some("users"), &response);
+        assert_eq!(value["host"], "db-1");
+        assert_eq!(value["count"], 4);
+        assert_eq!(value["tags"][0], "env:prod");
+        assert_eq!(
+            value["multi_valued_keys"],
+            json!({"env": ["prod", "production"]})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remove_tags_writes_back_the_rest() {
+        use wiremock::matchers::{body_json, method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/tags/hosts/web-1"))
+            .and(query_param("source", "users"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"tags": ["env:prod", "env:staging", "team:web"]})),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/api/v1/tags/hosts/web-1"))
+            .and(query_param("source", "users"))
+            .and(body_json(
+                json!({"host": "web-1", "tags": ["env:prod", "team:web"]}),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"host": "web-1", "tags": ["env:prod", "team:web"]})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = DatadogClient::new("key".to_string(), "app".to_string(), None)
+            .unwrap()
+            .with_api_url(&mock_server.uri())
+            .with_writes(true);
+        let result = HostsHandler::update_tags(
+            Arc::new(client),
+            &json!({"host": "web-1", "source": "users", "mode": "remove", "tags": "env:staging"}),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result["data"]["tags"], json!(["env:prod", "team:web"]));
+        assert_eq!(result["data"]["mode"], "remove");
+    }
+
+    #[test]
+    fn test_required_host_rejects_path_segments() {
+        assert_eq!(
+            HostsHandler::required_host(&json!({"host": "web-1.example.com"})).unwrap(),
+            "web-1.example.com"
+        );
+        for host in [
+            "../../../v1/dashboard/abc",
+            "web-1/mute",
+            "web-1?x=1",
+            "web#1",
+            "..",
+        ] {
+            assert!(
+                HostsHandler::required_host(&json!({ "host": host })).is_err(),
+                "{}",
+                host
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_tags_validation() {
+        let client =
+            Arc::new(DatadogClient::new("key".to_string(), "app".to_string(), None).unwrap());
+
+        let error = HostsHandler::update_tags(client.clone(), &json!({"host": "web-1"}))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("tags"));
+
+        let error = HostsHandler::update_tags(
+            client.clone(),
+            &json!({"host": "web-1", "tags": ["env:prod"], "mode": "merge"}),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("mode"));
+
+        let error =
+            HostsHandler::update_tags(client, &json!({"host": "web-1", "tags": ["env:prod"]}))
+                .await
+                .unwrap_err();
+        assert!(error.to_string().contains("DD_ALLOW_WRITES"));
+    }
 }
@@ -0,0 +1,278 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::{ResponseFormatter, TimeHandler, TimeParams};
+use crate::handlers::events::EventsHandler;
+
+/// Insights returned unless the caller asks for more, and at most
+const DEFAULT_INSIGHT_LIMIT: usize = 25;
+const MAX_INSIGHT_LIMIT: usize = 1000;
+
+/// Characters of the event text kept as an insight's summary
+const SUMMARY_LENGTH: usize = 200;
+
+/// Kinds of anomaly, each recognized by words in a Watchdog story's title or tags
+const CATEGORIES: &[(&str, &[&str])] = &[
+    ("latency", &["latency"]),
+    ("error_rate", &["error"]),
+    ("throughput", &["hits", "throughput", "traffic", "requests"]),
+    ("log_anomaly", &["log"]),
+    (
+        "infrastructure",
+        &["cpu", "memory", "disk", "network", "host"],
+    ),
+];
+
+pub struct InsightsHandler;
+
+impl TimeHandler for InsightsHandler {}
+impl ResponseFormatter for InsightsHandler {}
+
+impl InsightsHandler {
+    /// Events query for Watchdog stories, narrowed by the optional service/env filters
+    fn build_query(params: &Value) -> String {
+        let mut query = "source:watchdog".to_string();
+        for key in ["service", "env"] {
+            if let Some(value) = params[key].as_str().filter(|v| !v.is_empty()) {
+                query.push_str(&format!(" {}:{}", key, value));
+            }
+        }
+        if let Some(extra) = params["query"].as_str().filter(|q| !q.trim().is_empty()) {
+            query.push(' ');
+            query.push_str(extra.trim());
+        }
+        query
+    }
+
+    /// Value of the first `key:value` tag
+    fn tag_value<'a>(tags: &'a [String], key: &str) -> Option<&'a str> {
+        tags.iter()
+            .find_map(|tag| tag.strip_prefix(key)?.strip_prefix(':'))
+    }
+
+    /// Kind of anomaly, from the words of the title and the `story_*` tags Watchdog sets
+    fn category(title: &str, tags: &[String]) -> &'static str {
+        let words = tags
+            .iter()
+            .filter(|tag| tag.starts_with("story_"))
+            .fold(title.to_lowercase(), |words, tag| {
+                words + " " + &tag.to_lowercase()
+            });
+        CATEGORIES
+            .iter()
+            .find(|(_, keywords)| keywords.iter().any(|keyword| words.contains(keyword)))
+            .map(|(category, _)| *category)
+            .unwrap_or("other")
+    }
+
+    /// First line of prose in an event text, without the `%%%` markdown fences
+    fn summary(text: &str) -> Option<String> {
+        text.lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && *line != "%%%")
+            .map(|line| line.chars().take(SUMMARY_LENGTH).collect())
+    }
+
+    /// An event flattened by the events tool, reshaped as an insight
+    fn insight(event: &Value) -> Value {
+        let tags: Vec<String> = event["tags"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|tag| tag.as_str().map(String::from))
+            .collect();
+        let title = event["title"].as_str().unwrap_or_default();
+
+        json!({
+            "id": event["id"],
+            "date": event["date"],
+            "title": title,
+            "category": Self::category(title, &tags),
+            "service": Self::tag_value(&tags, "service"),
+            "env": Self::tag_value(&tags, "env"),
+            "status": event["alert_type"],
+            "summary": event["text"].as_str().and_then(Self::summary),
+        })
+    }
+
+    /// Count of insights per value of `key` ("unknown" when missing)
+    fn count_by(insights: &[Value], key: &str) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for insight in insights {
+            let value = insight[key].as_str().unwrap_or("unknown").to_string();
+            *counts.entry(value).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub async fn list(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = InsightsHandler;
+
+        let mut window = params.clone();
+        if window["from"].is_null() {
+            window["from"] = json!("24 hours ago");
+        }
+        let TimeParams::Timestamp { from, to } = handler.parse_time(&window, 2)?;
+        let from_iso = handler.timestamp_to_iso8601(from)?;
+        let to_iso = handler.timestamp_to_iso8601(to)?;
+
+        let limit = params["limit"]
+            .as_u64()
+            .map(|l| l as usize)
+            .unwrap_or(DEFAULT_INSIGHT_LIMIT)
+            .clamp(1, MAX_INSIGHT_LIMIT);
+        let category = params["category"].as_str().filter(|c| !c.is_empty());
+        if let Some(category) = category
+            && category != "other"
+            && !CATEGORIES.iter().any(|(name, _)| *name == category)
+        {
+            return Err(DatadogError::InvalidInput(format!(
+                "Invalid category '{}' (expected one of: {}, other)",
+                category,
+                CATEGORIES
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+
+        let query = Self::build_query(params);
+        let response = client
+            .search_events(&query, &from_iso, &to_iso, limit, None)
+            .await?;
+
+        let insights: Vec<Value> = response
+            .data
+            .iter()
+            .flatten()
+            .map(|event| Self::insight(&EventsHandler::v2_event_json(event)))
+            .filter(|insight| category.is_none_or(|c| insight["category"] == c))
+            .collect();
+
+        let has_more = response
+            .meta
+            .and_then(|m| m.page)
+            .and_then(|p| p.after)
+            .is_some();
+        let mut meta = json!({
+            "query": query,
+            "from": crate::utils::format_timestamp(from),
+            "to": crate::utils::format_timestamp(to),
+            "count": insights.len(),
+            "by_category": Self::count_by(&insights, "category"),
+            "by_service": Self::count_by(&insights, "service"),
+        });
+        if has_more {
+            meta["truncated"] = json!(true);
+        }
+        if insights.is_empty() {
+            meta["note"] =
+                json!("Watchdog detected no anomalies matching these filters in this window");
+        }
+
+        Ok(handler.format_list(json!(insights), None, Some(meta)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_build_query() {
+        assert_eq!(InsightsHandler::build_query(&json!({})), "source:watchdog");
+        assert_eq!(
+            InsightsHandler::build_query(
+                &json!({"service": "checkout", "env": "prod", "query": "priority:normal"})
+            ),
+            "source:watchdog service:checkout env:prod priority:normal"
+        );
+    }
+
+    #[test]
+    fn test_category() {
+        let tags = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            InsightsHandler::category("Anomaly on checkout", &tags(&["story_type:latency"])),
+            "latency"
+        );
+        assert_eq!(
+            InsightsHandler::category("[Watchdog] Latency increase on checkout", &[]),
+            "latency"
+        );
+        assert_eq!(
+            InsightsHandler::category("Error rate spike on web-api", &[]),
+            "error_rate"
+        );
+        assert_eq!(InsightsHandler::category("Something odd", &[]), "other");
+    }
+
+    #[test]
+    fn test_summary() {
+        assert_eq!(
+            InsightsHandler::summary("%%%\n\nLatency rose from 80ms to 450ms\nmore").as_deref(),
+            Some("Latency rose from 80ms to 450ms")
+        );
+        assert_eq!(InsightsHandler::summary("%%%\n%%%"), None);
+    }
+
+    #[tokio::test]
+    async fn test_list_filters_by_category() {
+        let mock_server = MockServer::start().await;
+        let event = |id: &str, title: &str, tags: Value| {
+            json!({
+                "id": id,
+                "type": "event",
+                "attributes": {
+                    "timestamp": "2024-01-01T00:00:00Z",
+                    "message": "%%%\nDetails\n%%%",
+                    "tags": tags,
+                    "attributes": {"title": title, "status": "warning"}
+                }
+            })
+        };
+        Mock::given(method("POST"))
+            .and(path("/api/v2/events/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    event("1", "[Watchdog] Latency increase on checkout", json!(["service:checkout", "env:prod"])),
+                    event("2", "[Watchdog] Error rate increase on web", json!(["service:web"]))
+                ]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = DatadogClient::new("key".to_string(), "app".to_string(), None)
+            .unwrap()
+            .with_api_url(&mock_server.uri());
+        let client: Arc<dyn DatadogApi> = Arc::new(client);
+
+        let all = InsightsHandler::list(client.clone(), &json!({}))
+            .await
+            .unwrap();
+        assert_eq!(all["meta"]["count"], 2);
+        assert_eq!(all["meta"]["by_category"]["latency"], 1);
+        assert_eq!(all["data"][0]["service"], "checkout");
+        assert_eq!(all["data"][0]["env"], "prod");
+        assert_eq!(all["data"][0]["summary"], "Details");
+
+        let latency = InsightsHandler::list(client.clone(), &json!({"category": "latency"}))
+            .await
+            .unwrap();
+        assert_eq!(latency["meta"]["count"], 1);
+        assert_eq!(latency["data"][0]["id"], "1");
+
+        let error = InsightsHandler::list(client, &json!({"category": "weather"}))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("category"));
+    }
+}
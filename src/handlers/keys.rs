@@ -0,0 +1,241 @@
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::{KeyRelationship, KeyUser, KeysMeta};
+use crate::error::Result;
+use crate::handlers::common::{Paginator, ResponseFormatter};
+
+/// Largest page the key APIs accept
+const MAX_KEYS_PAGE_SIZE: usize = 100;
+
+pub struct KeysHandler;
+
+impl Paginator for KeysHandler {}
+impl ResponseFormatter for KeysHandler {}
+
+impl KeysHandler {
+    /// Masked form of a key: only the last four characters Datadog returns are shown
+    fn masked(last4: Option<&str>) -> Option<String> {
+        last4.map(|last4| format!("****{}", last4))
+    }
+
+    /// Whole days since an RFC 3339 timestamp
+    fn age_days(created_at: Option<&str>, now: DateTime<Utc>) -> Option<i64> {
+        let created = DateTime::parse_from_rfc3339(created_at?).ok()?;
+        Some((now - created.with_timezone(&Utc)).num_days())
+    }
+
+    /// Handles (or emails) of the included users by ID
+    fn users(included: &[KeyUser]) -> HashMap<&str, &str> {
+        included
+            .iter()
+            .filter_map(|user| {
+                let attributes = user.attributes.as_ref()?;
+                let name = attributes
+                    .handle
+                    .as_deref()
+                    .or(attributes.email.as_deref())?;
+                Some((user.id.as_str(), name))
+            })
+            .collect()
+    }
+
+    fn user_of<'a>(
+        relationship: Option<&'a KeyRelationship>,
+        users: &HashMap<&str, &'a str>,
+    ) -> Option<&'a str> {
+        let id = relationship?.data.as_ref()?.id.as_str();
+        Some(users.get(id).copied().unwrap_or(id))
+    }
+
+    fn page_args(params: &Value) -> (usize, usize, Option<String>) {
+        let (page, page_size) = KeysHandler.parse_pagination(params);
+        let filter = params["filter"]
+            .as_str()
+            .filter(|f| !f.is_empty())
+            .map(String::from);
+        (page, page_size.clamp(1, MAX_KEYS_PAGE_SIZE), filter)
+    }
+
+    fn pagination(
+        page: usize,
+        page_size: usize,
+        returned: usize,
+        meta: Option<&KeysMeta>,
+    ) -> Value {
+        let total = meta
+            .and_then(|m| m.page.as_ref())
+            .and_then(|p| p.total_filtered_count)
+            .map(|t| t as usize)
+            .unwrap_or(page * page_size + returned);
+        KeysHandler.format_pagination(page, page_size, total)
+    }
+
+    /// API keys of the organization with their last four characters only
+    pub async fn api_keys(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = KeysHandler;
+        let (page, page_size, filter) = Self::page_args(params);
+
+        let response = client.list_api_keys(page, page_size, filter).await?;
+
+        let now = Utc::now();
+        let users = Self::users(&response.included);
+        let keys: Vec<Value> = response
+            .data
+            .iter()
+            .map(|key| {
+                let attributes = key.attributes.as_ref();
+                let created_at = attributes.and_then(|a| a.created_at.as_deref());
+                json!({
+                    "id": key.id,
+                    "name": attributes.and_then(|a| a.name.as_deref()),
+                    "key": Self::masked(attributes.and_then(|a| a.last4.as_deref())),
+                    "created_at": created_at,
+                    "modified_at": attributes.and_then(|a| a.modified_at.as_deref()),
+                    "age_days": Self::age_days(created_at, now),
+                    "created_by": Self::user_of(
+                        key.relationships.as_ref().and_then(|r| r.created_by.as_ref()),
+                        &users,
+                    ),
+                })
+            })
+            .collect();
+
+        let pagination = Self::pagination(page, page_size, keys.len(), response.meta.as_ref());
+        Ok(handler.format_list(json!(keys), Some(pagination), None))
+    }
+
+    /// Application keys of every user, with owner, scopes, and which one this server uses
+    pub async fn application_keys(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = KeysHandler;
+        let (page, page_size, filter) = Self::page_args(params);
+
+        let response = client
+            .list_application_keys(page, page_size, filter)
+            .await?;
+
+        let now = Utc::now();
+        let users = Self::users(&response.included);
+        let in_use = client.app_key_last4();
+        let keys: Vec<Value> = response
+            .data
+            .iter()
+            .map(|key| {
+                let attributes = key.attributes.as_ref();
+                let last4 = attributes.and_then(|a| a.last4.as_deref());
+                let created_at = attributes.and_then(|a| a.created_at.as_deref());
+                let scopes = attributes.and_then(|a| a.scopes.as_ref());
+                json!({
+                    "id": key.id,
+                    "name": attributes.and_then(|a| a.name.as_deref()),
+                    "key": Self::masked(last4),
+                    "created_at": created_at,
+                    "age_days": Self::age_days(created_at, now),
+                    "owner": Self::user_of(
+                        key.relationships.as_ref().and_then(|r| r.owned_by.as_ref()),
+                        &users,
+                    ),
+                    // Unscoped keys carry every permission of their owner
+                    "scopes": scopes,
+                    "unscoped": scopes.is_none(),
+                    "used_by_this_server": last4 == Some(in_use),
+                })
+            })
+            .collect();
+
+        let pagination = Self::pagination(page, page_size, keys.len(), response.meta.as_ref());
+        Ok(handler.format_list(json!(keys), Some(pagination), None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_age_days() {
+        let now = DateTime::parse_from_rfc3339("2024-03-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            KeysHandler::age_days(Some("2024-02-01T12:00:00.000000+00:00"), now),
+            Some(28)
+        );
+        assert_eq!(KeysHandler::age_days(Some("yesterday"), now), None);
+        assert_eq!(KeysHandler::age_days(None, now), None);
+    }
+
+    #[tokio::test]
+    async fn test_api_keys_never_return_the_key() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/api_keys"))
+            .and(query_param("include", "created_by"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{
+                    "id": "k-1",
+                    "type": "api_keys",
+                    "attributes": {
+                        "name": "ci",
+                        "key": "0123456789abcdef0123456789abcdef",
+                        "last4": "cdef",
+                        "created_at": "2024-01-01T00:00:00Z"
+                    },
+                    "relationships": {"created_by": {"data": {"id": "u-1", "type": "users"}}}
+                }],
+                "included": [{"id": "u-1", "type": "users", "attributes": {"handle": "jane@example.com"}}],
+                "meta": {"page": {"total_filtered_count": 1}}
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = DatadogClient::new("key".to_string(), "app".to_string(), None)
+            .unwrap()
+            .with_api_url(&mock_server.uri());
+        let result = KeysHandler::api_keys(Arc::new(client), &json!({}))
+            .await
+            .unwrap();
+
+        assert_eq!(result["data"][0]["key"], "****cdef");
+        assert_eq!(result["data"][0]["created_by"], "jane@example.com");
+        assert_eq!(result["pagination"]["total"], 1);
+        assert!(!result.to_string().contains("0123456789abcdef"));
+    }
+
+    #[tokio::test]
+    async fn test_application_keys_flag_the_key_in_use() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/application_keys"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"id": "a-1", "attributes": {"name": "mcp", "last4": "5678", "scopes": ["metrics_read"]}},
+                    {"id": "a-2", "attributes": {"name": "old", "last4": "0000", "scopes": null},
+                     "relationships": {"owned_by": {"data": {"id": "u-9"}}}}
+                ]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = DatadogClient::new("key".to_string(), "app-key-5678".to_string(), None)
+            .unwrap()
+            .with_api_url(&mock_server.uri());
+        let result = KeysHandler::application_keys(Arc::new(client), &json!({}))
+            .await
+            .unwrap();
+
+        assert_eq!(result["data"][0]["used_by_this_server"], true);
+        assert_eq!(result["data"][0]["unscoped"], false);
+        assert_eq!(result["data"][1]["used_by_this_server"], false);
+        assert_eq!(result["data"][1]["unscoped"], true);
+        // Owners missing from `included` fall back to their ID
+        assert_eq!(result["data"][1]["owner"], "u-9");
+    }
+}
@@ -1,21 +1,96 @@
 use serde_json::{Value, json};
 use std::sync::Arc;
 
-use crate::datadog::DatadogClient;
+use crate::datadog::DatadogApi;
 use crate::error::Result;
 use crate::handlers::common::{
-    PaginationInfo, ResponseFilter, ResponseFormatter, TagFilter, TimeHandler, TimeParams,
+    LOGS_RETENTION_DAYS, PaginationInfo, ResponseFilter, ResponseFormatter, TagFilter, TagSelector,
+    TimeHandler, TimeParams,
 };
+use crate::handlers::logs_indexes::LogsIndexesHandler;
 
 pub struct LogsHandler;
 
-impl TimeHandler for LogsHandler {}
+/// A path of the `attributes` argument or deny list: `@http.status_code` and
+/// `http.status_code` name the same attribute
+fn attribute_path(path: &str) -> &str {
+    path.trim().trim_start_matches('@')
+}
+
+/// Attribute paths never returned, from DD_LOGS_ATTRIBUTE_DENYLIST (comma-separated, e.g.
+/// `usr.email,network.client.ip`); denying a path also denies everything below it
+fn denied_attributes(spec: Option<&str>) -> Vec<String> {
+    spec.unwrap_or_default()
+        .split(',')
+        .map(attribute_path)
+        .filter(|p| !p.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Whether `path` is `ancestor` or lies below it
+fn is_within(path: &str, ancestor: &str) -> bool {
+    path.strip_prefix(ancestor)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+/// Value at a dotted path; keys that themselves contain dots are matched too
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let fields = value.as_object()?;
+    if let Some(found) = fields.get(path) {
+        return Some(found);
+    }
+    path.match_indices('.').find_map(|(i, _)| {
+        fields
+            .get(&path[..i])
+            .and_then(|nested| lookup(nested, &path[i + 1..]))
+    })
+}
+
+/// Remove the value at a dotted path, as `lookup` finds it
+fn remove_path(value: &mut Value, path: &str) {
+    let Some(fields) = value.as_object_mut() else {
+        return;
+    };
+    if fields.remove(path).is_some() {
+        return;
+    }
+    for (i, _) in path.match_indices('.') {
+        if let Some(nested) = fields.get_mut(&path[..i]) {
+            remove_path(nested, &path[i + 1..]);
+        }
+    }
+}
+
+/// The requested attributes of one log, keyed by path, minus denied paths inside them
+fn pick_attributes(attributes: &Value, requested: &[&str], denied: &[String]) -> Value {
+    let mut picked = serde_json::Map::new();
+    for path in requested {
+        let Some(value) = lookup(attributes, path) else {
+            continue;
+        };
+        let mut value = value.clone();
+        for denied in denied {
+            if let Some(inner) = denied.strip_prefix(*path).and_then(|r| r.strip_prefix('.')) {
+                remove_path(&mut value, inner);
+            }
+        }
+        picked.insert(path.to_string(), value);
+    }
+    Value::Object(picked)
+}
+
+impl TimeHandler for LogsHandler {
+    fn retention_days(&self) -> Option<i64> {
+        Some(LOGS_RETENTION_DAYS)
+    }
+}
 impl TagFilter for LogsHandler {}
 impl ResponseFilter for LogsHandler {}
 impl ResponseFormatter for LogsHandler {}
 
 impl LogsHandler {
-    pub async fn search(client: Arc<DatadogClient>, params: &Value) -> Result<Value> {
+    pub async fn search(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
         let handler = LogsHandler;
 
         let query = params["query"].as_str().ok_or_else(|| {
@@ -35,14 +110,26 @@ impl LogsHandler {
             .await?;
 
         if let Some(errors) = response.errors {
-            return Err(crate::error::DatadogError::ApiError(errors.join(", ")));
+            return Err(crate::error::DatadogError::QueryError(errors.join(", ")));
         }
 
         // Determine tag filter: parameter > env var > "*" (all tags)
         let tag_filter = params["tag_filter"]
             .as_str()
-            .or_else(|| client.get_tag_filter())
+            .or_else(|| client.get_tag_filter_for("logs"))
             .unwrap_or("*");
+        let tag_filter = TagSelector::parse(tag_filter)?;
+
+        // Custom attributes are only returned when asked for, and never when denied
+        let denied = denied_attributes(std::env::var("DD_LOGS_ATTRIBUTE_DENYLIST").ok().as_deref());
+        let (withheld, requested): (Vec<&str>, Vec<&str>) = params["attributes"]
+            .as_array()
+            .map(|paths| paths.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter()
+            .map(attribute_path)
+            .filter(|p| !p.is_empty())
+            .partition(|path| denied.iter().any(|d| is_within(path, d)));
 
         let logs = response
             .data
@@ -52,7 +139,7 @@ impl LogsHandler {
                 let attrs = log.attributes.as_ref();
                 let tags = attrs
                     .and_then(|a| a.tags.as_ref())
-                    .map(|t| handler.filter_tags(t, tag_filter));
+                    .map(|t| handler.filter_tags(t, &tag_filter));
 
                 // Build log entry, excluding null/empty fields
                 let mut log_entry = json!({
@@ -83,25 +170,57 @@ impl LogsHandler {
                     log_entry["tags"] = json!(tags_vec);
                 }
 
+                if !requested.is_empty()
+                    && let Some(attributes) = attrs.and_then(|a| a.attributes.as_ref())
+                {
+                    let picked = pick_attributes(&json!(attributes), &requested, &denied);
+                    if picked.as_object().is_some_and(|p| !p.is_empty()) {
+                        log_entry["attributes"] = picked;
+                    }
+                }
+
                 log_entry
             })
             .collect::<Vec<_>>();
+        let logs = handler.project_fields(logs, params);
 
         let result_count = logs.len();
 
         // Use PaginationInfo for single-page API with heuristic
         let pagination = PaginationInfo::single_page(result_count, limit);
 
-        Ok(json!({
+        let mut result = json!({
             "data": logs,
             "pagination": pagination
-        }))
+        });
+        if !withheld.is_empty() {
+            result["meta"]["withheld_attributes"] = json!(withheld);
+        }
+
+        // Optionally surface index exclusion filters that may have dropped matching logs
+        if params["check_exclusions"].as_bool().unwrap_or(false) {
+            match client.list_logs_indexes().await {
+                Ok(response) => {
+                    let active = LogsIndexesHandler::active_exclusions(&response.indexes);
+                    if !active.is_empty() {
+                        result["meta"]["active_exclusion_filters"] = json!(active);
+                        result["meta"]["note"] = json!(
+                            "Logs matching an enabled exclusion filter are not indexed and cannot be found by search"
+                        );
+                    }
+                }
+                Err(e) => log::warn!("Failed to check log exclusion filters: {}", e),
+            }
+        }
+
+        Ok(result)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::datadog::DatadogClient;
     use serde_json::json;
 
     #[test]
@@ -124,6 +243,37 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_pick_attributes() {
+        let attributes = json!({
+            "http": {"status_code": 502, "method": "GET"},
+            "usr": {"id": "u-1", "email": "a@example.com"},
+            "duration.ms": 1200,
+            "network": {"client": {"ip": "10.0.0.1"}}
+        });
+        let denied = denied_attributes(Some("@usr.email, network.client.ip"));
+        assert_eq!(denied, ["usr.email", "network.client.ip"]);
+
+        let picked = pick_attributes(
+            &attributes,
+            &["http.status_code", "usr", "duration.ms", "missing"],
+            &denied,
+        );
+        assert_eq!(
+            picked,
+            json!({
+                "http.status_code": 502,
+                "usr": {"id": "u-1"},
+                "duration.ms": 1200
+            })
+        );
+
+        assert!(is_within("usr.email", "usr.email"));
+        assert!(is_within("network.client.ip.v4", "network.client"));
+        assert!(!is_within("usr.emails", "usr.email"));
+        assert_eq!(attribute_path(" @http.url "), "http.url");
+    }
+
     #[test]
     fn test_valid_input_parameters() {
         let params = json!({
@@ -167,8 +317,8 @@ mod tests {
     fn test_time_handler_available() {
         let handler = LogsHandler;
         let params = json!({
-            "from": "1609459200",
-            "to": "1609462800"
+            "from": "2 hours ago",
+            "to": "1 hour ago"
         });
 
         let result = handler.parse_time(&params, 1);
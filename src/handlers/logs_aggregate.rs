@@ -2,19 +2,23 @@ use serde_json::{Value, json};
 use std::sync::Arc;
 
 use crate::datadog::{
-    DatadogClient,
+    DatadogApi,
     models::{LogsCompute, LogsGroupBy, LogsGroupBySort},
 };
 use crate::error::Result;
-use crate::handlers::common::{ResponseFormatter, TimeHandler, TimeParams};
+use crate::handlers::common::{LOGS_RETENTION_DAYS, ResponseFormatter, TimeHandler, TimeParams};
 
 pub struct LogsAggregateHandler;
 
-impl TimeHandler for LogsAggregateHandler {}
+impl TimeHandler for LogsAggregateHandler {
+    fn retention_days(&self) -> Option<i64> {
+        Some(LOGS_RETENTION_DAYS)
+    }
+}
 impl ResponseFormatter for LogsAggregateHandler {}
 
 impl LogsAggregateHandler {
-    pub async fn aggregate(client: Arc<DatadogClient>, params: &Value) -> Result<Value> {
+    pub async fn aggregate(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
         let handler = LogsAggregateHandler;
 
         // Use v1 API time parsing to get timestamps, then convert to milliseconds strings
@@ -247,8 +251,8 @@ mod tests {
     fn test_time_handler_available() {
         let handler = LogsAggregateHandler;
         let params = json!({
-            "from": "1609459200",
-            "to": "1609462800"
+            "from": "2 hours ago",
+            "to": "1 hour ago"
         });
 
         let result = handler.parse_time(&params, 1);
@@ -0,0 +1,253 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::error::Result;
+use crate::handlers::common::{LOGS_RETENTION_DAYS, ResponseFormatter, TimeHandler, TimeParams};
+
+/// Logs sampled to discover attributes; the search API returns at most 1000 per page
+const DEFAULT_SAMPLE_SIZE: usize = 200;
+const MAX_SAMPLE_SIZE: usize = 1000;
+
+/// Distinct example values kept per facet
+const MAX_EXAMPLES: usize = 3;
+
+/// Example values longer than this are cut
+const MAX_EXAMPLE_LENGTH: usize = 80;
+
+/// Nesting below this depth is reported as an object facet
+const MAX_DEPTH: usize = 6;
+
+pub struct LogsFacetsHandler;
+
+impl TimeHandler for LogsFacetsHandler {
+    fn retention_days(&self) -> Option<i64> {
+        Some(LOGS_RETENTION_DAYS)
+    }
+}
+impl ResponseFormatter for LogsFacetsHandler {}
+
+/// What was seen of one attribute or tag key across the sample
+#[derive(Default)]
+struct FacetStats {
+    count: usize,
+    types: BTreeMap<&'static str, usize>,
+    examples: Vec<String>,
+}
+
+impl FacetStats {
+    fn observe(&mut self, value: &Value) {
+        self.count += 1;
+        *self.types.entry(Self::type_name(value)).or_default() += 1;
+
+        let example = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let example: String = example.chars().take(MAX_EXAMPLE_LENGTH).collect();
+        if self.examples.len() < MAX_EXAMPLES && !self.examples.contains(&example) {
+            self.examples.push(example);
+        }
+    }
+
+    fn type_name(value: &Value) -> &'static str {
+        match value {
+            Value::String(_) => "string",
+            Value::Number(n) if n.is_f64() => "double",
+            Value::Number(_) => "integer",
+            Value::Bool(_) => "boolean",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+            Value::Null => "null",
+        }
+    }
+
+    /// Most common type; a facet's type in Datadog is fixed, so mixed types are worth noting
+    fn dominant_type(&self) -> &'static str {
+        self.types
+            .iter()
+            .max_by_key(|(_, count)| **count)
+            .map(|(name, _)| *name)
+            .unwrap_or("null")
+    }
+}
+
+impl LogsFacetsHandler {
+    /// Record every leaf of a log's custom attributes under its dotted path
+    fn collect_attributes<'a>(
+        prefix: &str,
+        attributes: impl IntoIterator<Item = (&'a String, &'a Value)>,
+        depth: usize,
+        facets: &mut BTreeMap<String, FacetStats>,
+    ) {
+        for (key, value) in attributes {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                Value::Object(nested) if depth < MAX_DEPTH && !nested.is_empty() => {
+                    Self::collect_attributes(&path, nested, depth + 1, facets)
+                }
+                _ => facets.entry(path).or_default().observe(value),
+            }
+        }
+    }
+
+    /// Tag keys and their values (`env:prod` -> env = "prod")
+    fn collect_tags(tags: &[String], facets: &mut BTreeMap<String, FacetStats>) {
+        for tag in tags {
+            if let Some((key, value)) = tag.split_once(':') {
+                facets
+                    .entry(key.to_string())
+                    .or_default()
+                    .observe(&json!(value));
+            }
+        }
+    }
+
+    fn facet_entries(
+        facets: BTreeMap<String, FacetStats>,
+        source: &str,
+        query_prefix: &str,
+        sampled: usize,
+    ) -> Vec<Value> {
+        facets
+            .into_iter()
+            .map(|(path, stats)| {
+                let mut entry = json!({
+                    "facet": format!("{}{}", query_prefix, path),
+                    "source": source,
+                    "type": stats.dominant_type(),
+                    "count": stats.count,
+                    "coverage_pct": stats.count as f64 * 100.0 / sampled.max(1) as f64,
+                    "examples": stats.examples,
+                });
+                if stats.types.len() > 1 {
+                    entry["mixed_types"] = json!(stats.types);
+                }
+                entry
+            })
+            .collect()
+    }
+
+    pub async fn facets(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = LogsFacetsHandler;
+
+        let query = params["query"].as_str().unwrap_or("*");
+        let sample_size = params["sample_size"]
+            .as_u64()
+            .map(|s| (s as usize).clamp(1, MAX_SAMPLE_SIZE))
+            .unwrap_or(DEFAULT_SAMPLE_SIZE);
+        let include_tags = params["include_tags"].as_bool().unwrap_or(true);
+        let search = params["search"].as_str().map(|s| s.to_lowercase());
+
+        let TimeParams::Timestamp { from, to } = handler.parse_time(params, 1)?;
+        let from_iso = handler.timestamp_to_iso8601(from)?;
+        let to_iso = handler.timestamp_to_iso8601(to)?;
+
+        let response = client
+            .search_logs(query, &from_iso, &to_iso, Some(sample_size as i32))
+            .await?;
+
+        if let Some(errors) = response.errors {
+            return Err(crate::error::DatadogError::QueryError(errors.join(", ")));
+        }
+
+        let logs = response.data.unwrap_or_default();
+        let mut attributes = BTreeMap::new();
+        let mut tags = BTreeMap::new();
+        for attrs in logs.iter().filter_map(|log| log.attributes.as_ref()) {
+            if let Some(custom) = &attrs.attributes {
+                Self::collect_attributes("", custom, 0, &mut attributes);
+            }
+            if include_tags && let Some(log_tags) = &attrs.tags {
+                Self::collect_tags(log_tags, &mut tags);
+            }
+        }
+
+        let sampled = logs.len();
+        let mut data = Self::facet_entries(attributes, "attribute", "@", sampled);
+        data.extend(Self::facet_entries(tags, "tag", "", sampled));
+        if let Some(search) = &search {
+            data.retain(|entry| {
+                entry["facet"]
+                    .as_str()
+                    .is_some_and(|facet| facet.to_lowercase().contains(search.as_str()))
+            });
+        }
+        data.sort_by(|a, b| {
+            b["count"]
+                .as_u64()
+                .cmp(&a["count"].as_u64())
+                .then_with(|| a["facet"].as_str().cmp(&b["facet"].as_str()))
+        });
+
+        let meta = json!({
+            "query": query,
+            "from": crate::utils::format_timestamp(from),
+            "to": crate::utils::format_timestamp(to),
+            "sampled_logs": sampled,
+            "reserved_attributes": ["host", "service", "status", "source", "message", "trace_id"],
+            "note": "Discovered from sampled logs; query attributes as @facet:value and tags as key:value. Attributes not yet declared as facets can be searched but not aggregated"
+        });
+
+        Ok(handler.format_list(json!(data), None, Some(meta)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_attributes_flattens_paths() {
+        let mut facets = BTreeMap::new();
+        let attributes = json!({
+            "http": {"status_code": 500, "method": "GET"},
+            "duration": 1.5,
+            "retry": true
+        });
+        LogsFacetsHandler::collect_attributes("", attributes.as_object().unwrap(), 0, &mut facets);
+        let attributes = json!({"http": {"status_code": "timeout", "method": "GET"}});
+        LogsFacetsHandler::collect_attributes("", attributes.as_object().unwrap(), 0, &mut facets);
+
+        let entries = LogsFacetsHandler::facet_entries(facets, "attribute", "@", 2);
+        let names: Vec<&str> = entries
+            .iter()
+            .map(|e| e["facet"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["@duration", "@http.method", "@http.status_code", "@retry"]
+        );
+
+        assert_eq!(entries[0]["type"], "double");
+        assert_eq!(entries[0]["coverage_pct"], 50.0);
+        assert_eq!(entries[1]["examples"], json!(["GET"]));
+        assert_eq!(entries[1]["count"], 2);
+        assert_eq!(entries[2]["examples"], json!(["500", "timeout"]));
+        assert_eq!(
+            entries[2]["mixed_types"],
+            json!({"integer": 1, "string": 1})
+        );
+        assert_eq!(entries[3]["type"], "boolean");
+    }
+
+    #[test]
+    fn test_collect_tags() {
+        let mut facets = BTreeMap::new();
+        let tags = vec![
+            "env:prod".to_string(),
+            "version:1.2:3".to_string(),
+            "standalone".to_string(),
+        ];
+        LogsFacetsHandler::collect_tags(&tags, &mut facets);
+
+        assert_eq!(facets.len(), 2);
+        assert_eq!(facets["env"].examples, vec!["prod"]);
+        assert_eq!(facets["version"].examples, vec!["1.2:3"]);
+    }
+}
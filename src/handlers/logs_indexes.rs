@@ -0,0 +1,152 @@
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::{LogsExclusionFilter, LogsIndex};
+use crate::error::Result;
+use crate::handlers::common::ResponseFormatter;
+
+pub struct LogsIndexesHandler;
+
+impl ResponseFormatter for LogsIndexesHandler {}
+
+impl LogsIndexesHandler {
+    fn exclusion_json(index: &LogsIndex, filter: &LogsExclusionFilter) -> Value {
+        let rule = filter.filter.as_ref();
+        json!({
+            "index": index.name,
+            "name": filter.name,
+            "enabled": filter.is_enabled.unwrap_or(false),
+            "query": rule.and_then(|r| r.query.as_deref()).unwrap_or("*"),
+            "sample_rate": rule.and_then(|r| r.sample_rate),
+        })
+    }
+
+    /// Enabled exclusion filters across the given indexes
+    pub fn active_exclusions(indexes: &[LogsIndex]) -> Vec<Value> {
+        indexes
+            .iter()
+            .flat_map(|index| {
+                index
+                    .exclusion_filters
+                    .iter()
+                    .flatten()
+                    .filter(|f| f.is_enabled.unwrap_or(false))
+                    .map(move |f| Self::exclusion_json(index, f))
+            })
+            .collect()
+    }
+
+    fn index_summary(index: &LogsIndex, include_disabled: bool) -> Value {
+        let exclusion_filters: Vec<Value> = index
+            .exclusion_filters
+            .iter()
+            .flatten()
+            .filter(|f| include_disabled || f.is_enabled.unwrap_or(false))
+            .map(|f| {
+                let mut entry = Self::exclusion_json(index, f);
+                if let Some(obj) = entry.as_object_mut() {
+                    obj.remove("index");
+                }
+                entry
+            })
+            .collect();
+
+        json!({
+            "index": index.name,
+            "filter": index.filter.as_ref().and_then(|f| f.query.as_deref()).unwrap_or("*"),
+            "retention_days": index.num_retention_days,
+            "daily_limit": index.daily_limit,
+            "is_rate_limited": index.is_rate_limited,
+            "exclusion_filters": exclusion_filters,
+        })
+    }
+
+    pub async fn exclusion_filters(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = LogsIndexesHandler;
+
+        let index_name = params["index"].as_str().filter(|s| !s.is_empty());
+        let include_disabled = params["include_disabled"].as_bool().unwrap_or(false);
+
+        let response = client.list_logs_indexes().await?;
+
+        let indexes: Vec<&LogsIndex> = response
+            .indexes
+            .iter()
+            .filter(|index| index_name.is_none_or(|name| index.name == name))
+            .collect();
+
+        let active_count = indexes
+            .iter()
+            .flat_map(|index| index.exclusion_filters.iter().flatten())
+            .filter(|f| f.is_enabled.unwrap_or(false))
+            .count();
+
+        let data = json!(
+            indexes
+                .iter()
+                .map(|index| Self::index_summary(index, include_disabled))
+                .collect::<Vec<_>>()
+        );
+
+        let meta = json!({
+            "index_count": indexes.len(),
+            "active_exclusion_filters": active_count,
+        });
+
+        Ok(handler.format_list(data, None, Some(meta)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indexes() -> Vec<LogsIndex> {
+        serde_json::from_value(json!([
+            {
+                "name": "main",
+                "filter": {"query": "*"},
+                "num_retention_days": 15,
+                "exclusion_filters": [
+                    {"name": "drop debug", "is_enabled": true, "filter": {"query": "status:debug", "sample_rate": 1.0}},
+                    {"name": "old rule", "is_enabled": false, "filter": {"query": "service:legacy", "sample_rate": 0.5}}
+                ]
+            },
+            {
+                "name": "audit",
+                "filter": {"query": "source:audit"}
+            }
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_active_exclusions() {
+        let active = LogsIndexesHandler::active_exclusions(&indexes());
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0]["index"], "main");
+        assert_eq!(active[0]["name"], "drop debug");
+        assert_eq!(active[0]["sample_rate"], 1.0);
+    }
+
+    #[test]
+    fn test_index_summary_hides_disabled_by_default() {
+        let indexes = indexes();
+        let summary = LogsIndexesHandler::index_summary(&indexes[0], false);
+        assert_eq!(summary["exclusion_filters"].as_array().unwrap().len(), 1);
+        assert!(summary["exclusion_filters"][0].get("index").is_none());
+
+        let summary = LogsIndexesHandler::index_summary(&indexes[0], true);
+        assert_eq!(summary["exclusion_filters"].as_array().unwrap().len(), 2);
+        assert_eq!(summary["exclusion_filters"][1]["enabled"], false);
+    }
+
+    #[test]
+    fn test_index_summary_without_exclusions() {
+        let indexes = indexes();
+        let summary = LogsIndexesHandler::index_summary(&indexes[1], false);
+        assert_eq!(summary["filter"], "source:audit");
+        assert_eq!(summary["exclusion_filters"], json!([]));
+    }
+}
@@ -0,0 +1,448 @@
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::LogsMetric;
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::{MAX_METRIC_NAME_LEN, ResponseFormatter, validate_metric_name};
+
+/// Facets that usually explode metric cardinality when used as tags
+const HIGH_CARDINALITY_FACETS: &[&str] = &[
+    "@http.url",
+    "@http.url_details.path",
+    "@usr.id",
+    "@usr.email",
+    "@network.client.ip",
+    "trace_id",
+    "@trace_id",
+    "@request_id",
+    "host",
+];
+
+pub struct LogsMetricsHandler;
+
+impl ResponseFormatter for LogsMetricsHandler {}
+
+impl LogsMetricsHandler {
+    /// Lowercase a free-form string into a metric-name-safe segment
+    fn sanitize(input: &str) -> String {
+        let mut out = String::new();
+        for c in input.chars() {
+            if c.is_ascii_alphanumeric() {
+                out.push(c.to_ascii_lowercase());
+            } else if !out.ends_with('_') {
+                out.push('_');
+            }
+        }
+        out.trim_matches('_').to_string()
+    }
+
+    fn default_metric_name(query: &str, aggregation: &str, measure: Option<&str>) -> String {
+        let scope = match Self::sanitize(query) {
+            s if s.is_empty() => "all".to_string(),
+            s => s,
+        };
+        let suffix = match measure {
+            Some(measure) => Self::sanitize(measure),
+            None => aggregation.to_string(),
+        };
+
+        let mut name = format!("logs.{}.{}", scope, suffix);
+        name.truncate(MAX_METRIC_NAME_LEN);
+        name
+    }
+
+    /// Tag name for a group-by facet: '@http.status_code' -> 'http_status_code'
+    fn tag_name(facet: &str) -> String {
+        Self::sanitize(facet.trim_start_matches('@'))
+    }
+
+    /// Space aggregator for querying the created metric with the same semantics
+    fn metric_aggregator(aggregation: &str) -> Option<&'static str> {
+        match aggregation {
+            "count" | "sum" => Some("sum"),
+            "avg" => Some("avg"),
+            "min" => Some("min"),
+            "max" => Some("max"),
+            "median" => Some("p50"),
+            "pc75" => Some("p75"),
+            "pc90" => Some("p90"),
+            "pc95" => Some("p95"),
+            "pc98" => Some("p98"),
+            "pc99" => Some("p99"),
+            _ => None,
+        }
+    }
+
+    /// Metrics query reading a logs-based metric: summed as a count without `aggregator`,
+    /// otherwise with that space aggregator
+    fn metric_query(metric_name: &str, aggregator: Option<&str>, tags: &[String]) -> String {
+        let by_clause = if tags.is_empty() {
+            String::new()
+        } else {
+            format!(" by {{{}}}", tags.join(","))
+        };
+        match aggregator {
+            None => format!("sum:{}{{*}}{}.as_count()", metric_name, by_clause),
+            Some(aggregator) => format!("{}:{}{{*}}{}", aggregator, metric_name, by_clause),
+        }
+    }
+
+    /// Build the logs-based metric payload and the query to run against it afterwards
+    fn build_suggestion(params: &Value) -> Result<Value> {
+        let query = params["query"].as_str().unwrap_or("*").trim();
+        let query = if query.is_empty() { "*" } else { query };
+
+        let compute = params["compute"]
+            .as_array()
+            .and_then(|c| c.first())
+            .cloned()
+            .unwrap_or_else(|| json!({"aggregation": "count"}));
+        let aggregation = compute["aggregation"].as_str().unwrap_or("count");
+        let measure = compute["metric"].as_str().filter(|m| !m.is_empty());
+
+        let aggregator = Self::metric_aggregator(aggregation).ok_or_else(|| {
+            DatadogError::InvalidInput(format!(
+                "Aggregation '{}' cannot be expressed as a logs-based metric (use count, sum, avg, min, max, median or pcXX)",
+                aggregation
+            ))
+        })?;
+
+        let metric_compute = match (aggregation, measure) {
+            ("count", _) => json!({ "aggregation_type": "count" }),
+            (_, Some(measure)) => json!({
+                "aggregation_type": "distribution",
+                "path": measure,
+                "include_percentiles": aggregator.starts_with('p'),
+            }),
+            (_, None) => {
+                return Err(DatadogError::InvalidInput(format!(
+                    "Aggregation '{}' requires a 'metric' measure (e.g., '@duration')",
+                    aggregation
+                )));
+            }
+        };
+
+        let facets: Vec<&str> = params["group_by"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|g| g["facet"].as_str().or_else(|| g.as_str()))
+            .collect();
+        let group_by: Vec<Value> = facets
+            .iter()
+            .map(|facet| json!({ "path": facet, "tag_name": Self::tag_name(facet) }))
+            .collect();
+
+        let metric_name = match params["metric_name"].as_str().filter(|n| !n.is_empty()) {
+            Some(name) => {
+                validate_metric_name(name)?;
+                name.to_string()
+            }
+            None => Self::default_metric_name(query, aggregation, measure),
+        };
+
+        let tags: Vec<String> = group_by
+            .iter()
+            .filter_map(|g| g["tag_name"].as_str().map(String::from))
+            .collect();
+        let metric_query = Self::metric_query(
+            &metric_name,
+            (aggregation != "count").then_some(aggregator),
+            &tags,
+        );
+
+        let mut notes = vec![
+            "Logs-based metrics only include logs ingested after creation; they are retained for 15 months".to_string(),
+        ];
+        for facet in &facets {
+            if HIGH_CARDINALITY_FACETS.contains(facet) {
+                notes.push(format!(
+                    "Grouping by '{}' is likely high-cardinality and may create many custom metrics",
+                    facet
+                ));
+            }
+        }
+
+        let mut attributes = json!({
+            "compute": metric_compute,
+            "filter": { "query": query },
+        });
+        if !group_by.is_empty() {
+            attributes["group_by"] = json!(group_by);
+        }
+
+        Ok(json!({
+            "metric_name": metric_name,
+            "payload": {
+                "data": {
+                    "id": metric_name,
+                    "type": "logs_metrics",
+                    "attributes": attributes,
+                }
+            },
+            "metric_query": metric_query,
+            "notes": notes,
+        }))
+    }
+
+    /// An existing logs-based metric, with the metrics query that reads it
+    fn metric_json(metric: &LogsMetric) -> Value {
+        let attributes = metric.attributes.as_ref();
+        let compute = attributes.and_then(|a| a.compute.as_ref());
+        let aggregation_type = compute.and_then(|c| c.aggregation_type.as_deref());
+        let include_percentiles = compute.and_then(|c| c.include_percentiles);
+        // Tags default to the attribute path when the metric doesn't rename them
+        let group_by: Vec<(&str, &str)> = attributes
+            .map(|a| a.group_by.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|g| (g.path.as_str(), g.tag_name.as_deref().unwrap_or(&g.path)))
+            .collect();
+        let tags: Vec<String> = group_by.iter().map(|(_, tag)| tag.to_string()).collect();
+
+        let aggregator = match (aggregation_type, include_percentiles) {
+            (Some("distribution"), Some(true)) => Some("p95"),
+            (Some("distribution"), _) => Some("avg"),
+            _ => None,
+        };
+
+        json!({
+            "name": metric.id,
+            "query": attributes.and_then(|a| a.filter.as_ref()).and_then(|f| f.query.as_deref()),
+            "aggregation_type": aggregation_type,
+            "measure": compute.and_then(|c| c.path.as_deref()),
+            "include_percentiles": include_percentiles,
+            "group_by": group_by
+                .iter()
+                .map(|(path, tag)| json!({ "path": path, "tag_name": tag }))
+                .collect::<Vec<_>>(),
+            "metric_query": Self::metric_query(&metric.id, aggregator, &tags),
+        })
+    }
+
+    /// Logs-based metrics, optionally those whose name or filter query contains `query`
+    pub async fn list(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = LogsMetricsHandler;
+
+        let needle = params["query"]
+            .as_str()
+            .map(|q| q.trim().to_lowercase())
+            .filter(|q| !q.is_empty());
+
+        let response = client.list_logs_metrics().await?;
+        let total = response.data.len();
+
+        let metrics: Vec<Value> = response
+            .data
+            .iter()
+            .map(Self::metric_json)
+            .filter(|metric| {
+                needle.as_ref().is_none_or(|needle| {
+                    ["name", "query"].iter().any(|field| {
+                        metric[field]
+                            .as_str()
+                            .is_some_and(|v| v.to_lowercase().contains(needle.as_str()))
+                    })
+                })
+            })
+            .collect();
+
+        let meta = json!({
+            "count": metrics.len(),
+            "total": total,
+        });
+
+        Ok(handler.format_list(json!(metrics), None, Some(meta)))
+    }
+
+    pub async fn get(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = LogsMetricsHandler;
+
+        let metric_id = params["metric_id"]
+            .as_str()
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| {
+                DatadogError::InvalidInput("Missing 'metric_id' parameter".to_string())
+            })?;
+
+        let response = client.get_logs_metric(metric_id).await?;
+
+        Ok(handler.format_detail(Self::metric_json(&response.data)))
+    }
+
+    /// Metric definition only; nothing is written to Datadog
+    pub async fn suggest(_client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = LogsMetricsHandler;
+
+        let mut data = Self::build_suggestion(params)?;
+        data["created"] = json!(false);
+
+        Ok(handler.format_detail(data))
+    }
+
+    /// Create the suggested metric (write operation)
+    pub async fn create(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = LogsMetricsHandler;
+
+        let mut data = Self::build_suggestion(params)?;
+        let response = client.create_logs_metric(&data["payload"]).await?;
+        data["created"] = json!(true);
+        data["response"] = response["data"].clone();
+
+        Ok(handler.format_detail(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+
+    #[test]
+    fn test_count_suggestion() {
+        let suggestion = LogsMetricsHandler::build_suggestion(&json!({
+            "query": "service:web status:error",
+            "group_by": [{"facet": "@http.status_code"}]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            suggestion["metric_name"],
+            "logs.service_web_status_error.count"
+        );
+        let attributes = &suggestion["payload"]["data"]["attributes"];
+        assert_eq!(attributes["compute"]["aggregation_type"], "count");
+        assert_eq!(attributes["filter"]["query"], "service:web status:error");
+        assert_eq!(attributes["group_by"][0]["tag_name"], "http_status_code");
+        assert_eq!(
+            suggestion["metric_query"],
+            "sum:logs.service_web_status_error.count{*} by {http_status_code}.as_count()"
+        );
+    }
+
+    #[test]
+    fn test_distribution_suggestion() {
+        let suggestion = LogsMetricsHandler::build_suggestion(&json!({
+            "query": "source:nginx",
+            "compute": [{"aggregation": "pc99", "metric": "@duration"}],
+            "group_by": [{"facet": "@http.url"}],
+            "metric_name": "nginx.request.duration"
+        }))
+        .unwrap();
+
+        let compute = &suggestion["payload"]["data"]["attributes"]["compute"];
+        assert_eq!(compute["aggregation_type"], "distribution");
+        assert_eq!(compute["path"], "@duration");
+        assert_eq!(compute["include_percentiles"], true);
+        assert_eq!(
+            suggestion["metric_query"],
+            "p99:nginx.request.duration{*} by {http_url}"
+        );
+        assert!(
+            suggestion["notes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|n| n.as_str().unwrap().contains("@http.url"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_suggestions() {
+        assert!(
+            LogsMetricsHandler::build_suggestion(&json!({
+                "compute": [{"aggregation": "avg"}]
+            }))
+            .is_err()
+        );
+        assert!(
+            LogsMetricsHandler::build_suggestion(&json!({
+                "compute": [{"aggregation": "cardinality", "metric": "@usr.id"}]
+            }))
+            .is_err()
+        );
+        assert!(
+            LogsMetricsHandler::build_suggestion(&json!({
+                "metric_name": "1bad-name"
+            }))
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_existing_metrics() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/logs/config/metrics"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {
+                        "id": "logs.checkout.errors",
+                        "type": "logs_metrics",
+                        "attributes": {
+                            "compute": {"aggregation_type": "count"},
+                            "filter": {"query": "service:checkout status:error"},
+                            "group_by": [{"path": "@http.status_code", "tag_name": "status_code"}]
+                        }
+                    },
+                    {
+                        "id": "nginx.request.duration",
+                        "type": "logs_metrics",
+                        "attributes": {
+                            "compute": {"aggregation_type": "distribution", "path": "@duration", "include_percentiles": true},
+                            "filter": {"query": "source:nginx"},
+                            "group_by": [{"path": "env"}]
+                        }
+                    }
+                ]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = DatadogClient::new("key".to_string(), "app".to_string(), None)
+            .unwrap()
+            .with_api_url(&mock_server.uri());
+        let client: Arc<dyn DatadogApi> = Arc::new(client);
+
+        let all = LogsMetricsHandler::list(client.clone(), &json!({}))
+            .await
+            .unwrap();
+        assert_eq!(all["meta"]["count"], 2);
+        assert_eq!(
+            all["data"][0]["metric_query"],
+            "sum:logs.checkout.errors{*} by {status_code}.as_count()"
+        );
+        assert_eq!(
+            all["data"][1]["metric_query"],
+            "p95:nginx.request.duration{*} by {env}"
+        );
+
+        let nginx = LogsMetricsHandler::list(client, &json!({"query": "NGINX"}))
+            .await
+            .unwrap();
+        assert_eq!(nginx["meta"]["count"], 1);
+        assert_eq!(nginx["meta"]["total"], 2);
+        assert_eq!(nginx["data"][0]["measure"], "@duration");
+    }
+
+    #[tokio::test]
+    async fn test_create_requires_writes() {
+        let client = Arc::new(
+            DatadogClient::with_tag_filter("key".to_string(), "app".to_string(), None, None)
+                .unwrap(),
+        );
+
+        let result = LogsMetricsHandler::create(client.clone(), &json!({})).await;
+        assert!(matches!(result, Err(DatadogError::WritesDisabled)));
+
+        // Suggesting never writes, so it works in read-only mode
+        let result = LogsMetricsHandler::suggest(client, &json!({}))
+            .await
+            .unwrap();
+        assert_eq!(result["data"]["created"], false);
+    }
+}
@@ -0,0 +1,263 @@
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::error::Result;
+use crate::handlers::common::{LOGS_RETENTION_DAYS, ResponseFormatter, TimeHandler, TimeParams};
+
+/// Logs sampled per call; the search API returns at most 1000 per page
+const DEFAULT_SAMPLE_SIZE: usize = 500;
+const MAX_SAMPLE_SIZE: usize = 1000;
+
+const DEFAULT_PATTERN_LIMIT: usize = 20;
+
+/// Share of positions two messages must have in common to join the same pattern
+const DEFAULT_SIMILARITY: f64 = 0.5;
+
+/// Tokens of a message beyond this are ignored when clustering
+const MAX_TOKENS: usize = 64;
+
+/// Services listed per pattern
+const MAX_PATTERN_SERVICES: usize = 5;
+
+const WILDCARD: &str = "<*>";
+
+pub struct LogsPatternsHandler;
+
+impl TimeHandler for LogsPatternsHandler {
+    fn retention_days(&self) -> Option<i64> {
+        Some(LOGS_RETENTION_DAYS)
+    }
+}
+impl ResponseFormatter for LogsPatternsHandler {}
+
+/// Messages sharing a template, where varying positions are wildcards
+struct Cluster {
+    template: Vec<String>,
+    count: usize,
+    example: String,
+    services: HashMap<String, usize>,
+    statuses: BTreeMap<String, usize>,
+}
+
+impl LogsPatternsHandler {
+    /// First line of a message split on whitespace; tokens with digits (ids, durations,
+    /// addresses) are variables and masked up front
+    fn tokenize(message: &str) -> Vec<String> {
+        message
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .split_whitespace()
+            .take(MAX_TOKENS)
+            .map(|token| {
+                if token.chars().any(|c| c.is_ascii_digit()) {
+                    WILDCARD.to_string()
+                } else {
+                    token.to_string()
+                }
+            })
+            .collect()
+    }
+
+    /// Share of positions where the template matches (wildcards match anything)
+    fn similarity(template: &[String], tokens: &[String]) -> f64 {
+        if template.is_empty() {
+            return 1.0;
+        }
+        let same = template
+            .iter()
+            .zip(tokens)
+            .filter(|(t, token)| *t == WILDCARD || t == token)
+            .count();
+        same as f64 / template.len() as f64
+    }
+
+    /// Drain-style clustering: messages are bucketed by token count and first token,
+    /// then joined to the most similar pattern in the bucket or start a new one
+    fn cluster<'a>(
+        logs: impl IntoIterator<Item = (&'a str, Option<&'a str>, Option<&'a str>)>,
+        threshold: f64,
+    ) -> Vec<Cluster> {
+        let mut buckets: HashMap<(usize, String), Vec<Cluster>> = HashMap::new();
+
+        for (message, service, status) in logs {
+            let tokens = Self::tokenize(message);
+            let key = (tokens.len(), tokens.first().cloned().unwrap_or_default());
+            let clusters = buckets.entry(key).or_default();
+
+            let best = clusters
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (i, Self::similarity(&c.template, &tokens)))
+                .filter(|(_, score)| *score >= threshold)
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i);
+
+            let index = match best {
+                Some(index) => {
+                    for (slot, token) in clusters[index].template.iter_mut().zip(&tokens) {
+                        if slot != token {
+                            *slot = WILDCARD.to_string();
+                        }
+                    }
+                    index
+                }
+                None => {
+                    clusters.push(Cluster {
+                        template: tokens,
+                        count: 0,
+                        example: message.to_string(),
+                        services: HashMap::new(),
+                        statuses: BTreeMap::new(),
+                    });
+                    clusters.len() - 1
+                }
+            };
+
+            let cluster = &mut clusters[index];
+            cluster.count += 1;
+            if let Some(service) = service {
+                *cluster.services.entry(service.to_string()).or_default() += 1;
+            }
+            if let Some(status) = status {
+                *cluster.statuses.entry(status.to_string()).or_default() += 1;
+            }
+        }
+
+        let mut clusters: Vec<Cluster> = buckets.into_values().flatten().collect();
+        clusters.sort_by(|a, b| b.count.cmp(&a.count).then(a.example.cmp(&b.example)));
+        clusters
+    }
+
+    fn pattern_entry(cluster: Cluster, sampled: usize) -> Value {
+        let mut services: Vec<(String, usize)> = cluster.services.into_iter().collect();
+        services.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let services: Vec<String> = services
+            .into_iter()
+            .take(MAX_PATTERN_SERVICES)
+            .map(|(service, _)| service)
+            .collect();
+
+        json!({
+            "pattern": cluster.template.join(" "),
+            "count": cluster.count,
+            "percent": cluster.count as f64 * 100.0 / sampled.max(1) as f64,
+            "example": cluster.example,
+            "services": services,
+            "statuses": cluster.statuses,
+        })
+    }
+
+    pub async fn patterns(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = LogsPatternsHandler;
+
+        let query = params["query"].as_str().unwrap_or("*");
+        let sample_size = params["sample_size"]
+            .as_u64()
+            .map(|s| (s as usize).clamp(1, MAX_SAMPLE_SIZE))
+            .unwrap_or(DEFAULT_SAMPLE_SIZE);
+        let limit = params["limit"]
+            .as_u64()
+            .map(|l| l as usize)
+            .unwrap_or(DEFAULT_PATTERN_LIMIT);
+        let similarity = params["similarity"]
+            .as_f64()
+            .map(|s| s.clamp(0.0, 1.0))
+            .unwrap_or(DEFAULT_SIMILARITY);
+
+        let TimeParams::Timestamp { from, to } = handler.parse_time(params, 1)?;
+        let from_iso = handler.timestamp_to_iso8601(from)?;
+        let to_iso = handler.timestamp_to_iso8601(to)?;
+
+        let response = client
+            .search_logs(query, &from_iso, &to_iso, Some(sample_size as i32))
+            .await?;
+
+        if let Some(errors) = response.errors {
+            return Err(crate::error::DatadogError::QueryError(errors.join(", ")));
+        }
+
+        let logs = response.data.unwrap_or_default();
+        let with_message: Vec<_> = logs
+            .iter()
+            .filter_map(|log| {
+                let attrs = log.attributes.as_ref()?;
+                let message = attrs.message.as_deref().filter(|m| !m.trim().is_empty())?;
+                Some((message, attrs.service.as_deref(), attrs.status.as_deref()))
+            })
+            .collect();
+        let sampled = with_message.len();
+
+        let clusters = Self::cluster(with_message, similarity);
+        let total_patterns = clusters.len();
+        let data: Vec<Value> = clusters
+            .into_iter()
+            .take(limit)
+            .map(|cluster| Self::pattern_entry(cluster, sampled))
+            .collect();
+
+        let meta = json!({
+            "query": query,
+            "from": crate::utils::format_timestamp(from),
+            "to": crate::utils::format_timestamp(to),
+            "sampled_logs": sampled,
+            "without_message": logs.len() - sampled,
+            "total_patterns": total_patterns,
+            "similarity": similarity,
+            "note": "Counts are within the sampled logs (oldest first), not the whole time range; use datadog_logs_aggregate for exact totals"
+        });
+
+        Ok(handler.format_list(json!(data), None, Some(meta)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_masks_variables() {
+        let tokens =
+            LogsPatternsHandler::tokenize("Timeout after 30s calling 10.0.0.1:8080\n  at frame");
+        assert_eq!(tokens, vec!["Timeout", "after", "<*>", "calling", "<*>"]);
+    }
+
+    #[test]
+    fn test_cluster_merges_similar_messages() {
+        let logs = vec![
+            ("User alice logged in", Some("auth"), Some("info")),
+            ("User bob logged in", Some("auth"), Some("info")),
+            ("User carol logged in", Some("web"), Some("info")),
+            (
+                "Payment failed for order 42",
+                Some("billing"),
+                Some("error"),
+            ),
+            ("Cache miss", None, None),
+        ];
+
+        let clusters = LogsPatternsHandler::cluster(logs, DEFAULT_SIMILARITY);
+        assert_eq!(clusters.len(), 3);
+
+        let entry = LogsPatternsHandler::pattern_entry(clusters.into_iter().next().unwrap(), 5);
+        assert_eq!(entry["pattern"], "User <*> logged in");
+        assert_eq!(entry["count"], 3);
+        assert_eq!(entry["percent"], 60.0);
+        assert_eq!(entry["example"], "User alice logged in");
+        assert_eq!(entry["services"], json!(["auth", "web"]));
+        assert_eq!(entry["statuses"], json!({"info": 3}));
+    }
+
+    #[test]
+    fn test_cluster_threshold() {
+        let logs = vec![
+            ("GET /health ok", None, None),
+            ("GET /orders failed", None, None),
+        ];
+
+        assert_eq!(LogsPatternsHandler::cluster(logs.clone(), 0.3).len(), 1);
+        assert_eq!(LogsPatternsHandler::cluster(logs, 0.5).len(), 2);
+    }
+}
@@ -2,19 +2,23 @@ use serde_json::{Value, json};
 use std::sync::Arc;
 
 use crate::datadog::{
-    DatadogClient,
+    DatadogApi,
     models::{LogsCompute, LogsGroupBy},
 };
 use crate::error::Result;
-use crate::handlers::common::{ResponseFormatter, TimeHandler, TimeParams};
+use crate::handlers::common::{LOGS_RETENTION_DAYS, ResponseFormatter, TimeHandler, TimeParams};
 
 pub struct LogsTimeseriesHandler;
 
-impl TimeHandler for LogsTimeseriesHandler {}
+impl TimeHandler for LogsTimeseriesHandler {
+    fn retention_days(&self) -> Option<i64> {
+        Some(LOGS_RETENTION_DAYS)
+    }
+}
 impl ResponseFormatter for LogsTimeseriesHandler {}
 
 impl LogsTimeseriesHandler {
-    pub async fn timeseries(client: Arc<DatadogClient>, params: &Value) -> Result<Value> {
+    pub async fn timeseries(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
         let handler = LogsTimeseriesHandler;
 
         // Use v1 API time parsing to get timestamps, then convert to milliseconds strings
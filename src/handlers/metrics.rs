@@ -1,16 +1,66 @@
+use futures::FutureExt;
 use serde_json::{Value, json};
 use std::sync::Arc;
 
-use crate::datadog::DatadogClient;
-use crate::error::Result;
-use crate::handlers::common::{ResponseFormatter, TimeHandler, TimeParams};
+use crate::datadog::batch::{ApiCall, execute_batch};
+use crate::datadog::models::MetricSeries;
+use crate::datadog::{DatadogApi, query};
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::{
+    METRICS_RETENTION_DAYS, ResponseFormatter, TimeHandler, TimeParams, percentile,
+};
+
+/// Upper bound on queries per multi-query call to stay within API rate limits
+const MAX_MULTI_QUERIES: usize = 10;
+
+/// Sparkline glyphs from lowest to highest value
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Sparklines longer than this average neighbouring points into one glyph
+const SPARKLINE_WIDTH: usize = 40;
+
+/// Anomalous points listed per series; `count` still reports all of them
+const MAX_ANOMALIES_PER_SERIES: usize = 20;
+
+/// Series shorter than this are too short to tell an outlier from normal variation
+const MIN_ANOMALY_POINTS: usize = 8;
+
+/// Tukey fence multiplier for the IQR detector
+const IQR_FENCE: f64 = 1.5;
+
+/// |z| above which the z-score detector flags a point
+const ZSCORE_THRESHOLD: f64 = 3.0;
+
+/// Change between the first and last third of a series below which it counts as flat
+const TREND_FLAT_PCT: f64 = 5.0;
 
 pub struct MetricsHandler;
 
-impl TimeHandler for MetricsHandler {}
+impl TimeHandler for MetricsHandler {
+    fn retention_days(&self) -> Option<i64> {
+        Some(METRICS_RETENTION_DAYS)
+    }
+}
 impl ResponseFormatter for MetricsHandler {}
 
 impl MetricsHandler {
+    /// Optional `max_points` argument; anything below 1 would divide the range by zero
+    fn parse_max_points(params: &Value) -> Result<Option<usize>> {
+        match &params["max_points"] {
+            Value::Null => Ok(None),
+            value => value
+                .as_u64()
+                .filter(|max| *max >= 1)
+                .map(|max| Some(max as usize))
+                .ok_or_else(|| {
+                    DatadogError::InvalidInput(format!(
+                        "'max_points' must be a positive integer, got {}",
+                        value
+                    ))
+                }),
+        }
+    }
+
     // Calculate rollup interval based on time range and desired max_points
     fn calculate_rollup_interval(from_ts: i64, to_ts: i64, max_points: usize) -> i64 {
         let time_range = to_ts - from_ts;
@@ -61,36 +111,297 @@ impl MetricsHandler {
         format!("{}.rollup({}, {})", query, agg, interval)
     }
 
-    pub async fn query(client: Arc<DatadogClient>, params: &Value) -> Result<Value> {
-        let handler = MetricsHandler;
+    /// Average consecutive points into at most `max_points` buckets, each stamped with its
+    /// first timestamp; nulls are skipped and an all-null bucket stays null. Backs up the
+    /// `.rollup()` hint, which arithmetic and already rolled-up queries ignore.
+    fn downsample(pointlist: &[Vec<Option<f64>>], max_points: usize) -> Vec<Vec<Option<f64>>> {
+        let bucket = pointlist.len().div_ceil(max_points.max(1)).max(1);
+        pointlist
+            .chunks(bucket)
+            .map(|chunk| {
+                let timestamp = chunk.first().and_then(|p| p.first().copied().flatten());
+                let value =
+                    Self::series_avg(chunk.iter().filter_map(|p| p.get(1).copied().flatten()));
+                vec![timestamp, value]
+            })
+            .collect()
+    }
 
-        let mut query = params["query"]
-            .as_str()
+    /// Downsample every series longer than `max_points`; returns the longest original length
+    /// when any was reduced
+    fn enforce_max_points(series: &mut [MetricSeries], max_points: usize) -> Option<usize> {
+        let mut reduced_from = None;
+        for pointlist in series.iter_mut().filter_map(|s| s.pointlist.as_mut()) {
+            if pointlist.len() > max_points {
+                reduced_from = reduced_from.max(Some(pointlist.len()));
+                *pointlist = Self::downsample(pointlist, max_points);
+            }
+        }
+        reduced_from
+    }
+
+    // Parse a compare_to offset such as '1h', '1d', '1w' into seconds
+    // or a relative time such as '1 week ago'
+    fn parse_shift(compare_to: &str) -> Result<i64> {
+        let relative = || {
+            compare_to
+                .trim()
+                .ends_with("ago")
+                .then(|| crate::utils::parse_time(compare_to).ok())
+                .flatten()
+                .map(|ts| chrono::Utc::now().timestamp() - ts)
+                .filter(|shift| *shift > 0)
+        };
+        crate::utils::parse_duration(compare_to)
+            .or_else(relative)
             .ok_or_else(|| {
-                crate::error::DatadogError::InvalidInput("Missing 'query' parameter".to_string())
-            })?
-            .to_string();
+                crate::error::DatadogError::InvalidInput(format!(
+                    "Invalid 'compare_to' value '{}' (expected e.g. '1h', '1d', '1w' or '1 week ago')",
+                    compare_to
+                ))
+            })
+    }
 
-        let time = handler.parse_time(params, 1)?; // v1 API
+    fn percent_change(current: f64, previous: f64) -> Option<f64> {
+        (previous != 0.0).then(|| ((current - previous) / previous.abs() * 10000.0).round() / 100.0)
+    }
 
-        let TimeParams::Timestamp {
-            from: from_ts,
-            to: to_ts,
-        } = time;
+    fn series_avg(points: impl Iterator<Item = f64>) -> Option<f64> {
+        let (sum, count) = points.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+        (count > 0).then(|| sum / count as f64)
+    }
 
-        // Get max_points parameter and apply rollup at API level
-        let max_points = params["max_points"].as_i64().map(|p| p as usize);
-        let mut applied_rollup = false;
+    /// Points outside the Tukey fences (`iqr`, default) or beyond 3 standard deviations
+    /// (`zscore`), each with a score: distance past the fence in IQRs, or |z|
+    fn detect_anomalies(series: &MetricSeries, method: &str) -> Option<Value> {
+        let points: Vec<(f64, f64)> = series
+            .pointlist
+            .iter()
+            .flatten()
+            .filter_map(|p| Some((p.first().copied()??, p.get(1).copied()??)))
+            .collect();
+        if points.len() < MIN_ANOMALY_POINTS {
+            return None;
+        }
+        let values: Vec<f64> = points.iter().map(|(_, v)| *v).collect();
 
-        if let Some(max) = max_points {
-            let interval = Self::calculate_rollup_interval(from_ts, to_ts, max);
-            query = Self::add_rollup_to_query(&query, interval);
-            applied_rollup = true;
+        let score: Box<dyn Fn(f64) -> Option<f64>> = if method == "zscore" {
+            let mean = Self::series_avg(values.iter().copied())?;
+            let variance =
+                values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+            let std_dev = variance.sqrt();
+            Box::new(move |v| {
+                let z = if std_dev > 0.0 {
+                    (v - mean).abs() / std_dev
+                } else {
+                    0.0
+                };
+                (z > ZSCORE_THRESHOLD).then_some(z)
+            })
+        } else {
+            let mut sorted = values.clone();
+            sorted.sort_by(f64::total_cmp);
+            let q1 = percentile(&sorted, 25.0)?;
+            let q3 = percentile(&sorted, 75.0)?;
+            let iqr = q3 - q1;
+            let (low, high) = (q1 - IQR_FENCE * iqr, q3 + IQR_FENCE * iqr);
+            Box::new(move |v| {
+                let distance = (low - v).max(v - high);
+                (distance > 0.0).then(|| if iqr > 0.0 { distance / iqr } else { distance })
+            })
+        };
+
+        let anomalies: Vec<Value> = points
+            .iter()
+            .filter_map(|(ts, v)| {
+                score(*v).map(|score| {
+                    json!({
+                        "timestamp": crate::utils::format_timestamp(*ts as i64 / 1000),
+                        "value": v,
+                        "score": (score * 100.0).round() / 100.0
+                    })
+                })
+            })
+            .collect();
+        if anomalies.is_empty() {
+            return None;
+        }
+
+        Some(json!({
+            "metric": series.metric,
+            "scope": series.scope,
+            "count": anomalies.len(),
+            "points": anomalies.into_iter().take(MAX_ANOMALIES_PER_SERIES).collect::<Vec<_>>()
+        }))
+    }
+
+    /// Statistics and shape of a series in place of its datapoints
+    fn summarize_points(values: &[f64], sparkline: bool) -> Value {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        // Compare the first and last thirds so a single spike doesn't decide the trend
+        let third = values.len().div_ceil(3);
+        let head = Self::series_avg(values.iter().take(third).copied());
+        let tail = Self::series_avg(values.iter().rev().take(third).copied());
+        let trend = match head.zip(tail) {
+            Some((head, tail)) if values.len() >= 3 => {
+                let change = if head == 0.0 {
+                    if tail == 0.0 {
+                        0.0
+                    } else {
+                        100.0 * tail.signum()
+                    }
+                } else {
+                    (tail - head) / head.abs() * 100.0
+                };
+                if change > TREND_FLAT_PCT {
+                    "rising"
+                } else if change < -TREND_FLAT_PCT {
+                    "falling"
+                } else {
+                    "flat"
+                }
+            }
+            _ => "flat",
+        };
+
+        let mut summary = json!({
+            "count": values.len(),
+            "min": sorted.first(),
+            "max": sorted.last(),
+            "avg": Self::series_avg(values.iter().copied()),
+            "p95": percentile(&sorted, 95.0),
+            "last": values.last(),
+            "trend": trend,
+        });
+        if sparkline && !values.is_empty() {
+            summary["sparkline"] = json!(Self::sparkline(values));
+        }
+        summary
+    }
+
+    /// One glyph per point (or per bucket of points, past SPARKLINE_WIDTH) scaled min to max
+    fn sparkline(values: &[f64]) -> String {
+        let bucket = values.len().div_ceil(SPARKLINE_WIDTH).max(1);
+        let points: Vec<f64> = values
+            .chunks(bucket)
+            .map(|chunk| chunk.iter().sum::<f64>() / chunk.len() as f64)
+            .collect();
+
+        let min = points.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = points.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let top = (SPARK_LEVELS.len() - 1) as f64;
+        points
+            .iter()
+            .map(|v| {
+                let level = if max > min {
+                    ((v - min) / (max - min) * top).round() as usize
+                } else {
+                    0
+                };
+                SPARK_LEVELS[level.min(SPARK_LEVELS.len() - 1)]
+            })
+            .collect()
+    }
+
+    /// Join width in ms: the coarser rollup interval of the two series, else the spacing of
+    /// the current points; both windows' points within one such bucket are paired
+    fn bucket_width(current: &MetricSeries, previous: Option<&MetricSeries>) -> i64 {
+        let interval = current
+            .interval
+            .into_iter()
+            .chain(previous.and_then(|p| p.interval))
+            .max();
+        let spacing = || {
+            let timestamps: Vec<f64> = current
+                .pointlist
+                .iter()
+                .flatten()
+                .filter_map(|p| p.first().copied().flatten())
+                .take(2)
+                .collect();
+            match timestamps[..] {
+                [first, second] => Some((second - first) as i64 / 1000),
+                _ => None,
+            }
+        };
+        interval
+            .or_else(spacing)
+            .filter(|secs| *secs > 0)
+            .map_or(1, |secs| secs * 1000)
+    }
+
+    /// Current points with the value `shift` seconds earlier in the same rollup bucket:
+    /// (timestamp ms, current, previous). Points of the two windows rarely share exact
+    /// timestamps, so both are bucketed by `bucket_width` and previous values averaged.
+    fn align(
+        current: &MetricSeries,
+        previous: Option<&MetricSeries>,
+        shift: i64,
+    ) -> Vec<(i64, Option<f64>, Option<f64>)> {
+        let shift_ms = (shift * 1000) as f64;
+        let width = Self::bucket_width(current, previous);
+
+        let mut buckets: std::collections::HashMap<i64, (f64, usize)> =
+            std::collections::HashMap::new();
+        for point in previous
+            .and_then(|p| p.pointlist.as_ref())
+            .into_iter()
+            .flatten()
+        {
+            if let (Some(Some(ts)), Some(Some(value))) = (point.first(), point.get(1)) {
+                let bucket = buckets
+                    .entry(((ts + shift_ms) as i64).div_euclid(width))
+                    .or_default();
+                bucket.0 += value;
+                bucket.1 += 1;
+            }
         }
 
-        let response = client.query_metrics(&query, from_ts, to_ts).await?;
+        current
+            .pointlist
+            .iter()
+            .flatten()
+            .filter_map(|p| {
+                let ts = p.first().copied()?? as i64;
+                let previous = buckets
+                    .get(&ts.div_euclid(width))
+                    .map(|(sum, count)| sum / *count as f64);
+                Some((ts, p.get(1).copied().flatten(), previous))
+            })
+            .collect()
+    }
+
+    // Align the shifted series onto the current timestamps with per-point percent deltas
+    fn overlay(current: &MetricSeries, previous: Option<&MetricSeries>, shift: i64) -> Value {
+        let aligned = Self::align(current, previous, shift);
+        let previous_avg = Self::series_avg(
+            previous
+                .and_then(|p| p.pointlist.as_ref())
+                .into_iter()
+                .flatten()
+                .filter_map(|p| p.get(1).copied().flatten()),
+        );
+
+        let current_avg = Self::series_avg(aligned.iter().filter_map(|(_, v, _)| *v));
+
+        json!({
+            "matched": previous.is_some(),
+            "current_avg": current_avg,
+            "previous_avg": previous_avg,
+            "change_pct": current_avg.zip(previous_avg).and_then(|(c, p)| Self::percent_change(c, p)),
+            "data": aligned.iter().map(|(ts, value, previous)| json!({
+                "timestamp": crate::utils::format_timestamp(ts / 1000),
+                "previous": previous,
+                "change_pct": value.zip(*previous).and_then(|(c, p)| Self::percent_change(c, p))
+            })).collect::<Vec<_>>()
+        })
+    }
 
-        let series = response.series.iter().map(|s| {
+    // Convert API series into compact JSON (timestamped points, first non-null unit)
+    fn format_series(series: &[MetricSeries]) -> Vec<Value> {
+        series.iter().map(|s| {
             let points_data = if let Some(ref pointlist) = s.pointlist {
                 json!({
                     "count": pointlist.len(),
@@ -145,7 +456,124 @@ impl MetricsHandler {
             }
 
             json!(series_obj)
-        }).collect::<Vec<_>>();
+        }).collect()
+    }
+
+    pub async fn query(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = MetricsHandler;
+
+        let mut query = params["query"]
+            .as_str()
+            .ok_or_else(|| {
+                crate::error::DatadogError::InvalidInput("Missing 'query' parameter".to_string())
+            })?
+            .to_string();
+        query::validate(&query)?;
+
+        let time = handler.parse_time(params, 1)?; // v1 API
+
+        let TimeParams::Timestamp {
+            from: from_ts,
+            to: to_ts,
+        } = time;
+
+        // Get max_points parameter and apply rollup at API level
+        let max_points = Self::parse_max_points(params)?;
+        let mut applied_rollup = false;
+
+        if let Some(max) = max_points {
+            let interval = Self::calculate_rollup_interval(from_ts, to_ts, max);
+            query = Self::add_rollup_to_query(&query, interval);
+            applied_rollup = true;
+        }
+
+        // Optional time-shifted overlay fetched alongside the main query
+        let compare_to = params["compare_to"].as_str().filter(|s| !s.is_empty());
+        let shift = compare_to.map(Self::parse_shift).transpose()?;
+
+        let (mut response, mut previous) = match shift {
+            Some(shift) => {
+                let (current, previous) = tokio::try_join!(
+                    client.query_metrics(&query, from_ts, to_ts),
+                    client.query_metrics(&query, from_ts - shift, to_ts - shift),
+                )
+                .map_err(|e| query::explain(&query, e))?;
+                (current, Some(previous))
+            }
+            None => (
+                client
+                    .query_metrics(&query, from_ts, to_ts)
+                    .await
+                    .map_err(|e| query::explain(&query, e))?,
+                None,
+            ),
+        };
+        if let Some(error) = query::response_error(&query, response.error.as_deref()) {
+            return Err(error);
+        }
+
+        // Same bucketing for both, so the shifted points still line up
+        let mut downsampled_from = None;
+        if let Some(max) = max_points {
+            downsampled_from = Self::enforce_max_points(&mut response.series, max);
+            if let Some(previous) = previous.as_mut() {
+                Self::enforce_max_points(&mut previous.series, max);
+            }
+        }
+
+        let mut series = Self::format_series(&response.series);
+
+        if let (Some(previous), Some(shift)) = (&previous, shift) {
+            for (formatted, current) in series.iter_mut().zip(&response.series) {
+                let matching = previous
+                    .series
+                    .iter()
+                    .find(|p| p.metric == current.metric && p.scope == current.scope);
+                formatted["comparison"] = Self::overlay(current, matching, shift);
+            }
+        }
+
+        // Computed before `summarize` drops the points; reported in meta
+        let anomalies = params["detect_anomalies"]
+            .as_bool()
+            .unwrap_or(false)
+            .then(|| {
+                let method = params["anomaly_method"].as_str().unwrap_or("iqr");
+                let found: Vec<Value> = response
+                    .series
+                    .iter()
+                    .filter_map(|s| Self::detect_anomalies(s, method))
+                    .collect();
+                json!({ "method": method, "series": found })
+            });
+
+        // Shape instead of every datapoint: the summary replaces `points` and the
+        // comparison keeps only its averages
+        let summarize = params["summarize"].as_bool().unwrap_or(false);
+        if summarize {
+            let sparkline = params["sparkline"].as_bool().unwrap_or(true);
+            for (formatted, current) in series.iter_mut().zip(&response.series) {
+                let values: Vec<f64> = current
+                    .pointlist
+                    .iter()
+                    .flatten()
+                    .filter_map(|p| p.get(1).copied().flatten())
+                    .collect();
+                if let Some(obj) = formatted.as_object_mut() {
+                    obj.remove("points");
+                    obj.insert(
+                        "summary".to_string(),
+                        Self::summarize_points(&values, sparkline),
+                    );
+                }
+                if let Some(comparison) = formatted
+                    .get_mut("comparison")
+                    .and_then(Value::as_object_mut)
+                {
+                    comparison.remove("data");
+                }
+            }
+        }
 
         // Build optimized meta - only include meaningful fields
         let mut meta = serde_json::Map::new();
@@ -181,20 +609,270 @@ impl MetricsHandler {
             meta.insert("group_by".to_string(), json!(group_by));
         }
 
+        if let (Some(compare_to), Some(shift)) = (compare_to, shift) {
+            meta.insert(
+                "comparison".to_string(),
+                json!({
+                    "compare_to": compare_to,
+                    "from": crate::utils::format_timestamp(from_ts - shift),
+                    "to": crate::utils::format_timestamp(to_ts - shift)
+                }),
+            );
+        }
+
         if applied_rollup {
             meta.insert("rollup_applied".to_string(), json!(true));
             if let Some(max) = max_points {
                 meta.insert("requested_max_points".to_string(), json!(max));
             }
         }
+        if let Some(anomalies) = anomalies {
+            meta.insert("anomalies".to_string(), anomalies);
+        }
+        if let Some(original) = downsampled_from {
+            meta.insert(
+                "downsampled".to_string(),
+                json!({ "method": "bucket_avg", "original_points": original }),
+            );
+        }
 
         Ok(handler.format_list(json!(series), None, Some(json!(meta))))
     }
+
+    /// avg/min/max/last of a window's values
+    fn window_stats(values: &[f64]) -> Value {
+        json!({
+            "avg": Self::series_avg(values.iter().copied()),
+            "min": values.iter().copied().reduce(f64::min),
+            "max": values.iter().copied().reduce(f64::max),
+            "last": values.last(),
+        })
+    }
+
+    /// Query a metric over the current window and a baseline window shifted back by
+    /// `compare_to`, and report per-series statistics of both with their deltas
+    pub async fn compare(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = MetricsHandler;
+
+        let query = params["query"].as_str().ok_or_else(|| {
+            crate::error::DatadogError::InvalidInput("Missing 'query' parameter".to_string())
+        })?;
+        query::validate(query)?;
+        let compare_to = params["compare_to"].as_str().unwrap_or("1w");
+        let shift = Self::parse_shift(compare_to)?;
+
+        let TimeParams::Timestamp {
+            from: from_ts,
+            to: to_ts,
+        } = handler.parse_time(params, 1)?;
+
+        let max_points = Self::parse_max_points(params)?;
+        let query = match max_points {
+            Some(max) => Self::add_rollup_to_query(
+                query,
+                Self::calculate_rollup_interval(from_ts, to_ts, max),
+            ),
+            None => query.to_string(),
+        };
+
+        let (mut current, mut baseline) = tokio::try_join!(
+            client.query_metrics(&query, from_ts, to_ts),
+            client.query_metrics(&query, from_ts - shift, to_ts - shift),
+        )
+        .map_err(|e| query::explain(&query, e))?;
+        if let Some(error) = query::response_error(&query, current.error.as_deref()) {
+            return Err(error);
+        }
+        if let Some(max) = max_points {
+            Self::enforce_max_points(&mut current.series, max);
+            Self::enforce_max_points(&mut baseline.series, max);
+        }
+
+        let include_points = params["include_points"].as_bool().unwrap_or(false);
+        let values = |series: &MetricSeries| -> Vec<f64> {
+            series
+                .pointlist
+                .iter()
+                .flatten()
+                .filter_map(|p| p.get(1).copied().flatten())
+                .collect()
+        };
+
+        let data: Vec<Value> = current
+            .series
+            .iter()
+            .map(|series| {
+                let matching = baseline
+                    .series
+                    .iter()
+                    .find(|b| b.metric == series.metric && b.scope == series.scope);
+                let current_stats = Self::window_stats(&values(series));
+                let baseline_stats = Self::window_stats(&matching.map(values).unwrap_or_default());
+                let delta = |field: &str| {
+                    current_stats[field]
+                        .as_f64()
+                        .zip(baseline_stats[field].as_f64())
+                };
+
+                let mut entry = json!({
+                    "metric": series.metric,
+                    "scope": series.scope,
+                    "matched": matching.is_some(),
+                    "current": current_stats,
+                    "baseline": baseline_stats,
+                    "delta_avg": delta("avg").map(|(c, b)| c - b),
+                    "change_pct": delta("avg").and_then(|(c, b)| Self::percent_change(c, b)),
+                    "max_change_pct": delta("max").and_then(|(c, b)| Self::percent_change(c, b)),
+                });
+                if include_points {
+                    entry["points"] = json!(
+                        Self::align(series, matching, shift)
+                            .iter()
+                            .map(|(ts, value, previous)| json!({
+                                "timestamp": crate::utils::format_timestamp(ts / 1000),
+                                "current": value,
+                                "baseline": previous,
+                                "change_pct": value
+                                    .zip(*previous)
+                                    .and_then(|(c, p)| Self::percent_change(c, p))
+                            }))
+                            .collect::<Vec<_>>()
+                    );
+                }
+                entry
+            })
+            .collect();
+
+        let meta = json!({
+            "query": query,
+            "compare_to": compare_to,
+            "current": {
+                "from": crate::utils::format_timestamp(from_ts),
+                "to": crate::utils::format_timestamp(to_ts)
+            },
+            "baseline": {
+                "from": crate::utils::format_timestamp(from_ts - shift),
+                "to": crate::utils::format_timestamp(to_ts - shift)
+            },
+        });
+
+        Ok(handler.format_list(json!(data), None, Some(meta)))
+    }
+
+    // Extract non-empty query strings from the 'queries' array parameter
+    fn parse_queries(params: &Value) -> Result<Vec<String>> {
+        let queries: Vec<String> = params["queries"]
+            .as_array()
+            .ok_or_else(|| {
+                crate::error::DatadogError::InvalidInput(
+                    "Missing 'queries' parameter (array of metric queries)".to_string(),
+                )
+            })?
+            .iter()
+            .filter_map(|q| q.as_str())
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .map(String::from)
+            .collect();
+
+        if queries.is_empty() {
+            return Err(crate::error::DatadogError::InvalidInput(
+                "'queries' must contain at least one metric query".to_string(),
+            ));
+        }
+        if queries.len() > MAX_MULTI_QUERIES {
+            return Err(crate::error::DatadogError::InvalidInput(format!(
+                "Too many queries: {} (max {})",
+                queries.len(),
+                MAX_MULTI_QUERIES
+            )));
+        }
+
+        Ok(queries)
+    }
+
+    pub async fn multi_query(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = MetricsHandler;
+
+        let queries = Self::parse_queries(params)?;
+
+        let TimeParams::Timestamp {
+            from: from_ts,
+            to: to_ts,
+        } = handler.parse_time(params, 1)?;
+
+        let max_points = Self::parse_max_points(params)?;
+        let interval = max_points.map(|max| Self::calculate_rollup_interval(from_ts, to_ts, max));
+
+        let calls: Vec<ApiCall<'_, _>> = queries
+            .iter()
+            .map(|query| {
+                let client = client.clone();
+                let api_query = match interval {
+                    Some(interval) => Self::add_rollup_to_query(query, interval),
+                    None => query.clone(),
+                };
+                async move {
+                    query::validate(&api_query)?;
+                    client
+                        .query_metrics(&api_query, from_ts, to_ts)
+                        .await
+                        .map_err(|e| query::explain(&api_query, e))
+                }
+                .boxed()
+            })
+            .collect();
+        let responses = execute_batch(calls, client.max_concurrency()).await;
+
+        // Keep partial results: a failing query is reported under its key instead of failing the call
+        let mut results = serde_json::Map::new();
+        let mut failed = 0;
+        for (query, response) in queries.iter().zip(responses) {
+            let entry = match response {
+                Ok(mut response) => match response.error.as_deref().filter(|e| !e.is_empty()) {
+                    Some(error) => {
+                        failed += 1;
+                        let error = query::response_error(query, Some(error))
+                            .map_or_else(|| error.to_string(), |e| e.to_string());
+                        json!({ "status": response.status, "error": error })
+                    }
+                    None => {
+                        if let Some(max) = max_points {
+                            Self::enforce_max_points(&mut response.series, max);
+                        }
+                        json!({
+                            "status": response.status,
+                            "series": Self::format_series(&response.series)
+                        })
+                    }
+                },
+                Err(e) => {
+                    failed += 1;
+                    json!({ "status": "error", "error": e.to_string() })
+                }
+            };
+            results.insert(query.clone(), entry);
+        }
+
+        let mut meta = json!({
+            "query_count": queries.len(),
+            "failed": failed,
+            "from": crate::utils::format_timestamp(from_ts),
+            "to": crate::utils::format_timestamp(to_ts)
+        });
+        if let (Some(max), Some(interval)) = (max_points, interval) {
+            meta["rollup_interval"] = json!(interval);
+            meta["requested_max_points"] = json!(max);
+        }
+
+        Ok(handler.format_list(json!(results), None, Some(meta)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::datadog::DatadogClient;
     use serde_json::json;
 
     #[test]
@@ -275,6 +953,32 @@ mod tests {
         assert!(params["to"].as_str().is_some());
     }
 
+    #[test]
+    fn test_parse_max_points() {
+        assert_eq!(
+            MetricsHandler::parse_max_points(&json!({"max_points": 100})).unwrap(),
+            Some(100)
+        );
+        assert_eq!(MetricsHandler::parse_max_points(&json!({})).unwrap(), None);
+
+        for invalid in [json!(0), json!(-1), json!("10")] {
+            let error =
+                MetricsHandler::parse_max_points(&json!({"max_points": invalid})).unwrap_err();
+            assert!(error.to_string().contains("positive integer"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_rejects_zero_max_points() {
+        let client = Arc::new(
+            DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None).unwrap(),
+        );
+        let params = json!({"query": "avg:cpu{*}", "from": "1 hour ago", "max_points": 0});
+
+        let result = MetricsHandler::query(client, &params).await;
+        assert!(matches!(result, Err(DatadogError::InvalidInput(_))));
+    }
+
     #[test]
     fn test_optional_max_points_parameter() {
         let params_with = json!({
@@ -299,8 +1003,8 @@ mod tests {
         // Verify MetricsHandler implements TimeHandler
         let handler = MetricsHandler;
         let params = json!({
-            "from": "1609459200",
-            "to": "1609462800"
+            "from": "2 hours ago",
+            "to": "1 hour ago"
         });
 
         // This should not panic
@@ -381,6 +1085,212 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_queries() {
+        let params = json!({
+            "queries": ["avg:system.cpu.user{*}", "  ", "avg:system.mem.used{*} ", 42]
+        });
+        let queries = MetricsHandler::parse_queries(&params).unwrap();
+        assert_eq!(
+            queries,
+            vec!["avg:system.cpu.user{*}", "avg:system.mem.used{*}"]
+        );
+
+        assert!(MetricsHandler::parse_queries(&json!({})).is_err());
+        assert!(MetricsHandler::parse_queries(&json!({"queries": []})).is_err());
+
+        let too_many: Vec<String> = (0..=MAX_MULTI_QUERIES)
+            .map(|i| format!("avg:metric.{}{{*}}", i))
+            .collect();
+        assert!(MetricsHandler::parse_queries(&json!({ "queries": too_many })).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_multi_query_missing_queries() {
+        let client = Arc::new(
+            DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None).unwrap(),
+        );
+
+        let result = MetricsHandler::multi_query(client, &json!({"from": "1 hour ago"})).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_shift() {
+        assert_eq!(MetricsHandler::parse_shift("1h").unwrap(), 3600);
+        assert_eq!(MetricsHandler::parse_shift("2d").unwrap(), 172800);
+        assert_eq!(MetricsHandler::parse_shift("1w").unwrap(), 604800);
+        assert!(MetricsHandler::parse_shift("w").is_err());
+        assert!(MetricsHandler::parse_shift("0d").is_err());
+        assert!(MetricsHandler::parse_shift("1y").is_err());
+        assert!(MetricsHandler::parse_shift("7").is_err());
+        let week = MetricsHandler::parse_shift("1 week ago").unwrap();
+        assert!((604799..=604801).contains(&week));
+    }
+
+    #[test]
+    fn test_downsample() {
+        let pointlist: Vec<Vec<Option<f64>>> = (0..10)
+            .map(|i| vec![Some(1000.0 * i as f64), (i != 3).then_some(i as f64)])
+            .collect();
+
+        let downsampled = MetricsHandler::downsample(&pointlist, 4);
+        assert_eq!(
+            downsampled,
+            vec![
+                vec![Some(0.0), Some(1.0)],
+                vec![Some(3000.0), Some(4.5)],
+                vec![Some(6000.0), Some(7.0)],
+                vec![Some(9000.0), Some(9.0)],
+            ]
+        );
+
+        let all_null = vec![vec![Some(0.0), None], vec![Some(1000.0), None]];
+        assert_eq!(
+            MetricsHandler::downsample(&all_null, 1),
+            vec![vec![Some(0.0), None]]
+        );
+    }
+
+    #[test]
+    fn test_detect_anomalies() {
+        // Enough points that one spike can exceed 3 standard deviations
+        let mut values = [10.0, 11.0, 10.5, 9.5, 10.0, 10.2, 9.8, 10.1, 10.4, 9.9].repeat(2);
+        values[5] = 95.0;
+        let series: MetricSeries = serde_json::from_value(json!({
+            "metric": "system.cpu.user",
+            "scope": "host:web-1",
+            "expression": "avg:system.cpu.user{host:web-1}",
+            "pointlist": values.iter().enumerate()
+                .map(|(i, v)| json!([1_700_000_000_000.0 + 60_000.0 * i as f64, v]))
+                .collect::<Vec<_>>(),
+        }))
+        .unwrap();
+
+        for method in ["iqr", "zscore"] {
+            let found = MetricsHandler::detect_anomalies(&series, method).unwrap();
+            assert_eq!(found["count"], 1, "{}", method);
+            assert_eq!(found["points"][0]["value"], 95.0);
+            assert_eq!(
+                found["points"][0]["timestamp"],
+                crate::utils::format_timestamp(1_700_000_300)
+            );
+        }
+
+        let short: MetricSeries = serde_json::from_value(json!({
+            "metric": "m", "scope": "*", "expression": "m",
+            "pointlist": [[0.0, 1.0], [60000.0, 100.0]],
+        }))
+        .unwrap();
+        assert!(MetricsHandler::detect_anomalies(&short, "iqr").is_none());
+    }
+
+    #[test]
+    fn test_window_stats() {
+        let stats = MetricsHandler::window_stats(&[4.0, 2.0, 6.0]);
+        assert_eq!(
+            stats,
+            json!({"avg": 4.0, "min": 2.0, "max": 6.0, "last": 6.0})
+        );
+
+        let empty = MetricsHandler::window_stats(&[]);
+        assert!(empty["avg"].is_null() && empty["last"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_multi_query_rejects_zero_max_points() {
+        let client = Arc::new(
+            DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None).unwrap(),
+        );
+        let params = json!({"queries": ["avg:cpu{*}"], "max_points": 0});
+
+        let result = MetricsHandler::multi_query(client, &params).await;
+        assert!(matches!(result, Err(DatadogError::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn test_compare_rejects_invalid_max_points() {
+        let client = Arc::new(
+            DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None).unwrap(),
+        );
+        for max_points in [0, -1] {
+            let params = json!({"query": "avg:cpu{*}", "max_points": max_points});
+            let result = MetricsHandler::compare(client.clone(), &params).await;
+            assert!(matches!(result, Err(DatadogError::InvalidInput(_))));
+        }
+    }
+
+    #[test]
+    fn test_summarize_points() {
+        let values: Vec<f64> = (1..=20).map(f64::from).collect();
+        let summary = MetricsHandler::summarize_points(&values, true);
+
+        assert_eq!(summary["count"], 20);
+        assert_eq!(summary["min"], 1.0);
+        assert_eq!(summary["max"], 20.0);
+        assert_eq!(summary["avg"], 10.5);
+        assert_eq!(summary["p95"], 19.0);
+        assert_eq!(summary["last"], 20.0);
+        assert_eq!(summary["trend"], "rising");
+        let sparkline = summary["sparkline"].as_str().unwrap();
+        assert_eq!(sparkline.chars().count(), 20);
+        assert!(sparkline.starts_with('▁') && sparkline.ends_with('█'));
+
+        let flat = MetricsHandler::summarize_points(&[5.0, 5.0, 5.0], false);
+        assert_eq!(flat["trend"], "flat");
+        assert!(flat.get("sparkline").is_none());
+
+        let falling: Vec<f64> = (1..=100).rev().map(f64::from).collect();
+        let summary = MetricsHandler::summarize_points(&falling, true);
+        assert_eq!(summary["trend"], "falling");
+        // 100 points in buckets of 3
+        let sparkline = summary["sparkline"].as_str().unwrap();
+        assert_eq!(sparkline.chars().count(), 34);
+    }
+
+    #[test]
+    fn test_overlay_aligns_shifted_points() {
+        let series = |points: Value| -> MetricSeries {
+            serde_json::from_value(json!({
+                "metric": "system.cpu.user",
+                "scope": "host:a",
+                "expression": "avg:system.cpu.user{host:a}",
+                "pointlist": points
+            }))
+            .unwrap()
+        };
+
+        // Previous window is one hour earlier
+        let current = series(json!([
+            [3_600_000.0, 20.0],
+            [3_660_000.0, 30.0],
+            [3_720_000.0, null]
+        ]));
+        let previous = series(json!([[0.0, 10.0], [60_000.0, 40.0]]));
+
+        let overlay = MetricsHandler::overlay(&current, Some(&previous), 3600);
+        assert_eq!(overlay["matched"], true);
+        assert_eq!(overlay["data"][0]["previous"], 10.0);
+        assert_eq!(overlay["data"][0]["change_pct"], 100.0);
+        assert_eq!(overlay["data"][1]["change_pct"], -25.0);
+        assert!(overlay["data"][2]["previous"].is_null());
+        assert_eq!(overlay["current_avg"], 25.0);
+        assert_eq!(overlay["previous_avg"], 25.0);
+        assert_eq!(overlay["change_pct"], 0.0);
+
+        // Rollup buckets of the previous window start 20s later than the current ones
+        let mut offset = series(json!([[20_000.0, 10.0], [80_000.0, 40.0]]));
+        offset.interval = Some(60);
+        let overlay = MetricsHandler::overlay(&current, Some(&offset), 3600);
+        assert_eq!(overlay["data"][0]["previous"], 10.0);
+        assert_eq!(overlay["data"][1]["previous"], 40.0);
+        assert!(overlay["data"][2]["previous"].is_null());
+
+        let unmatched = MetricsHandler::overlay(&current, None, 3600);
+        assert_eq!(unmatched["matched"], false);
+        assert!(unmatched["change_pct"].is_null());
+    }
+
     #[test]
     fn test_calculate_rollup_interval_large_ranges() {
         assert_eq!(
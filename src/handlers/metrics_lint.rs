@@ -0,0 +1,61 @@
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use crate::datadog::{DatadogApi, query};
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::ResponseFormatter;
+
+/// Window used when validating against the API - just enough to get a parse result
+const API_CHECK_WINDOW_SECS: i64 = 300;
+
+pub struct MetricsLintHandler;
+
+impl ResponseFormatter for MetricsLintHandler {}
+
+impl MetricsLintHandler {
+    pub async fn validate(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = MetricsLintHandler;
+
+        let query = params["query"]
+            .as_str()
+            .ok_or_else(|| DatadogError::InvalidInput("Missing 'query' parameter".to_string()))?;
+        let check_api = params["check_api"].as_bool().unwrap_or(false);
+
+        let report = query::check(query);
+        let mut data = json!({
+            "query": query,
+            "valid": report.errors.is_empty(),
+            "errors": report.errors,
+            "warnings": report.warnings,
+        });
+
+        if check_api {
+            data["api_check"] = if data["valid"] == json!(true) {
+                let to = chrono::Utc::now().timestamp();
+                match client
+                    .query_metrics(query, to - API_CHECK_WINDOW_SECS, to)
+                    .await
+                {
+                    Ok(response) => match response.error.filter(|e| !e.is_empty()) {
+                        Some(error) => {
+                            data["valid"] = json!(false);
+                            json!({ "ok": false, "error": error })
+                        }
+                        None => json!({
+                            "ok": true,
+                            "series": response.series.len(),
+                        }),
+                    },
+                    Err(e) => {
+                        data["valid"] = json!(false);
+                        json!({ "ok": false, "error": e.to_string() })
+                    }
+                }
+            } else {
+                json!({ "skipped": "Fix local syntax errors first" })
+            };
+        }
+
+        Ok(handler.format_detail(data))
+    }
+}
@@ -0,0 +1,248 @@
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::MetricMetadata;
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::{Paginator, ResponseFormatter};
+use crate::redact::redact;
+
+/// Default cap on values listed per tag key (hosts and pods can have thousands)
+const DEFAULT_MAX_TAG_VALUES: usize = 50;
+
+pub struct MetricsMetadataHandler;
+
+impl Paginator for MetricsMetadataHandler {}
+impl ResponseFormatter for MetricsMetadataHandler {}
+
+impl MetricsMetadataHandler {
+    fn required_str<'a>(params: &'a Value, name: &str) -> Result<&'a str> {
+        params[name]
+            .as_str()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| DatadogError::InvalidInput(format!("Missing '{}' parameter", name)))
+    }
+
+    // Only include fields Datadog actually populated
+    fn metadata_json(metric: &str, metadata: &MetricMetadata) -> Value {
+        let mut data = serde_json::Map::new();
+        data.insert("metric".to_string(), json!(metric));
+
+        let fields = [
+            ("type", &metadata.metric_type),
+            ("unit", &metadata.unit),
+            ("per_unit", &metadata.per_unit),
+            ("description", &metadata.description),
+            ("short_name", &metadata.short_name),
+            ("integration", &metadata.integration),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value.as_deref().filter(|v| !v.is_empty()) {
+                data.insert(key.to_string(), json!(value));
+            }
+        }
+
+        if let Some(interval) = metadata.statsd_interval {
+            data.insert("statsd_interval".to_string(), json!(interval));
+        }
+
+        json!(data)
+    }
+
+    /// Group "key:value" tags by key; bare tags become keys without values
+    fn group_tags(tags: &[String], tag_key: Option<&str>, max_values: usize) -> Vec<Value> {
+        let mut grouped: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for tag in tags {
+            let (key, value) = match tag.split_once(':') {
+                Some((key, value)) => (key, Some(value)),
+                None => (tag.as_str(), None),
+            };
+            if tag_key.is_some_and(|k| k != key) {
+                continue;
+            }
+            let values = grouped.entry(key).or_default();
+            if let Some(value) = value {
+                values.insert(value);
+            }
+        }
+
+        grouped
+            .into_iter()
+            .map(|(key, values)| {
+                json!({
+                    "key": key,
+                    "value_count": values.len(),
+                    "values": values.iter().take(max_values).collect::<Vec<_>>(),
+                    "truncated": values.len() > max_values,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn search(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = MetricsMetadataHandler;
+
+        let query = Self::required_str(params, "query")?;
+        let (page, page_size) = handler.parse_pagination(params);
+
+        let response = client.search_metrics(query).await?;
+
+        let mut metrics = response.results.metrics;
+        metrics.sort();
+        metrics.dedup();
+
+        let data = json!(handler.paginate(&metrics, page, page_size));
+        let pagination = handler.format_pagination(page, page_size, metrics.len());
+
+        Ok(handler.format_list(data, Some(pagination), Some(json!({ "query": query }))))
+    }
+
+    pub async fn metadata(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = MetricsMetadataHandler;
+
+        let metric = Self::required_str(params, "metric")?;
+
+        let metadata = client.get_metric_metadata(metric).await?;
+
+        Ok(handler.format_detail(Self::metadata_json(metric, &metadata)))
+    }
+
+    pub async fn tags(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = MetricsMetadataHandler;
+
+        let metric = Self::required_str(params, "metric")?;
+        let tag_key = params["tag_key"].as_str().filter(|k| !k.is_empty());
+        let max_values = params["max_values"]
+            .as_u64()
+            .map(|m| m as usize)
+            .unwrap_or(DEFAULT_MAX_TAG_VALUES);
+        let include_active = params["include_active"].as_bool().unwrap_or(false);
+
+        let tags = client
+            .get_metric_all_tags(metric)
+            .await?
+            .data
+            .attributes
+            .map(|a| a.tags)
+            .unwrap_or_default();
+        let grouped = Self::group_tags(&tags, tag_key, max_values);
+
+        let mut data = json!({
+            "metric": metric,
+            "tag_keys": grouped,
+        });
+
+        // Active configurations only exist for custom metrics; their absence is not an error
+        if include_active {
+            let window_seconds = params["window_seconds"].as_i64();
+            match client
+                .get_metric_active_configurations(metric, window_seconds)
+                .await
+            {
+                Ok(response) => {
+                    if let Some(attributes) = response.data.attributes {
+                        data["actively_queried"] = json!({
+                            "tag_keys": attributes.active_tags,
+                            "aggregations": attributes
+                                .active_aggregations
+                                .iter()
+                                .map(|a| json!({ "space": a.space, "time": a.time }))
+                                .collect::<Vec<_>>(),
+                        });
+                    }
+                }
+                Err(e) => log::debug!(
+                    "No active configurations for {}: {}",
+                    metric,
+                    redact(&e.to_string())
+                ),
+            }
+        }
+
+        let meta = json!({
+            "total_tags": tags.len(),
+            "max_values": max_values,
+        });
+
+        Ok(handler.format_list(data, None, Some(meta)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+
+    #[test]
+    fn test_required_str() {
+        let params = json!({"query": "  system.cpu ", "metric": ""});
+        assert_eq!(
+            MetricsMetadataHandler::required_str(&params, "query").unwrap(),
+            "system.cpu"
+        );
+        assert!(MetricsMetadataHandler::required_str(&params, "metric").is_err());
+        assert!(MetricsMetadataHandler::required_str(&params, "missing").is_err());
+    }
+
+    #[test]
+    fn test_metadata_json_skips_empty_fields() {
+        let metadata: MetricMetadata = serde_json::from_value(json!({
+            "type": "gauge",
+            "unit": "percent",
+            "per_unit": null,
+            "description": "",
+            "integration": "system",
+            "statsd_interval": null
+        }))
+        .unwrap();
+
+        let data = MetricsMetadataHandler::metadata_json("system.cpu.user", &metadata);
+        assert_eq!(data["metric"], "system.cpu.user");
+        assert_eq!(data["type"], "gauge");
+        assert_eq!(data["unit"], "percent");
+        assert_eq!(data["integration"], "system");
+        assert!(data.get("per_unit").is_none());
+        assert!(data.get("description").is_none());
+        assert!(data.get("statsd_interval").is_none());
+    }
+
+    #[test]
+    fn test_group_tags() {
+        let tags: Vec<String> = [
+            "env:prod",
+            "env:staging",
+            "host:a",
+            "host:b",
+            "host:c",
+            "canary",
+        ]
+        .iter()
+        .map(|t| t.to_string())
+        .collect();
+
+        let grouped = MetricsMetadataHandler::group_tags(&tags, None, 2);
+        assert_eq!(grouped.len(), 3);
+        assert_eq!(grouped[0]["key"], "canary");
+        assert_eq!(grouped[0]["value_count"], 0);
+        assert_eq!(grouped[1]["values"], json!(["prod", "staging"]));
+        assert_eq!(grouped[2]["value_count"], 3);
+        assert_eq!(grouped[2]["values"], json!(["a", "b"]));
+        assert_eq!(grouped[2]["truncated"], true);
+
+        let grouped = MetricsMetadataHandler::group_tags(&tags, Some("env"), 50);
+        assert_eq!(grouped.len(), 1);
+        assert_eq!(grouped[0]["truncated"], false);
+    }
+
+    #[tokio::test]
+    async fn test_search_missing_query() {
+        let client = Arc::new(
+            DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None).unwrap(),
+        );
+
+        let result = MetricsMetadataHandler::search(client, &json!({})).await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,243 @@
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::{ResponseFormatter, validate_metric_name};
+use crate::handlers::events::EventsHandler;
+
+/// Metric types of the v2 series intake, by the numbers it expects
+const METRIC_TYPES: &[(&str, u8)] = &[("count", 1), ("rate", 2), ("gauge", 3)];
+
+/// Points accepted in one call
+const MAX_POINTS: usize = 1000;
+
+/// The intake drops points older than an hour or more than ten minutes ahead
+const MAX_POINT_AGE_SECS: i64 = 3600;
+const MAX_POINT_LEAD_SECS: i64 = 600;
+
+pub struct MetricsSubmitHandler;
+
+impl ResponseFormatter for MetricsSubmitHandler {}
+
+impl MetricsSubmitHandler {
+    fn metric_name(params: &Value) -> Result<&str> {
+        let name = params["metric"]
+            .as_str()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .ok_or_else(|| DatadogError::InvalidInput("Missing 'metric' parameter".to_string()))?;
+        validate_metric_name(name)?;
+        Ok(name)
+    }
+
+    /// Timestamp of a point: Unix seconds or a time expression, now when omitted
+    fn point_timestamp(timestamp: &Value, now: i64) -> Result<i64> {
+        let timestamp = match timestamp {
+            Value::Null => return Ok(now),
+            Value::Number(n) => n.as_i64().ok_or_else(|| {
+                DatadogError::InvalidInput(format!("Invalid point timestamp {}", n))
+            })?,
+            Value::String(s) => crate::utils::parse_time(s)?,
+            other => {
+                return Err(DatadogError::InvalidInput(format!(
+                    "Invalid point timestamp {}",
+                    other
+                )));
+            }
+        };
+        if timestamp < now - MAX_POINT_AGE_SECS || timestamp > now + MAX_POINT_LEAD_SECS {
+            return Err(DatadogError::InvalidInput(format!(
+                "Point timestamp {} is outside what Datadog accepts (the last hour to 10 minutes ahead)",
+                crate::utils::format_timestamp(timestamp)
+            )));
+        }
+        Ok(timestamp)
+    }
+
+    /// Points from `points` ([{timestamp, value}] or bare numbers) or a single `value`
+    fn points(params: &Value, now: i64) -> Result<Vec<Value>> {
+        let raw: Vec<&Value> = match &params["points"] {
+            Value::Array(points) => points.iter().collect(),
+            Value::Null if !params["value"].is_null() => vec![params],
+            Value::Null => {
+                return Err(DatadogError::InvalidInput(
+                    "Missing 'value' or 'points' parameter".to_string(),
+                ));
+            }
+            _ => {
+                return Err(DatadogError::InvalidInput(
+                    "'points' must be an array".to_string(),
+                ));
+            }
+        };
+        if raw.is_empty() || raw.len() > MAX_POINTS {
+            return Err(DatadogError::InvalidInput(format!(
+                "Submit between 1 and {} points",
+                MAX_POINTS
+            )));
+        }
+
+        raw.into_iter()
+            .map(|point| {
+                let (timestamp, value) = match point {
+                    Value::Number(_) => (&Value::Null, point),
+                    _ => (&point["timestamp"], &point["value"]),
+                };
+                let value = value.as_f64().ok_or_else(|| {
+                    DatadogError::InvalidInput(format!("Point {} has no numeric 'value'", point))
+                })?;
+                Ok(json!({
+                    "timestamp": Self::point_timestamp(timestamp, now)?,
+                    "value": value,
+                }))
+            })
+            .collect()
+    }
+
+    /// v2 series payload for one metric
+    fn build_series(params: &Value, now: i64) -> Result<Value> {
+        let metric = Self::metric_name(params)?;
+        let kind = params["type"].as_str().unwrap_or("gauge");
+        let type_id = METRIC_TYPES
+            .iter()
+            .find(|(name, _)| *name == kind)
+            .map(|(_, id)| *id)
+            .ok_or_else(|| {
+                DatadogError::InvalidInput(format!(
+                    "Invalid type '{}' (expected one of: gauge, count, rate)",
+                    kind
+                ))
+            })?;
+
+        let mut series = json!({
+            "metric": metric,
+            "type": type_id,
+            "points": Self::points(params, now)?,
+            "tags": EventsHandler::parse_tags(&params["tags"])?,
+        });
+        if let Some(interval) = params["interval"].as_u64() {
+            series["interval"] = json!(interval);
+        } else if kind != "gauge" {
+            return Err(DatadogError::InvalidInput(format!(
+                "A {} needs 'interval': the seconds each point covers",
+                kind
+            )));
+        }
+        if let Some(unit) = params["unit"].as_str().filter(|u| !u.is_empty()) {
+            series["unit"] = json!(unit);
+        }
+        if let Some(host) = params["host"].as_str().filter(|h| !h.is_empty()) {
+            series["resources"] = json!([{ "type": "host", "name": host }]);
+        }
+
+        Ok(json!({ "series": [series] }))
+    }
+
+    /// Submit points of one custom metric (write operation)
+    pub async fn submit(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = MetricsSubmitHandler;
+
+        let body = Self::build_series(params, chrono::Utc::now().timestamp())?;
+        let response = client.submit_series(&body).await?;
+
+        let series = &body["series"][0];
+        Ok(handler.format_detail(json!({
+            "metric": series["metric"],
+            "type": params["type"].as_str().unwrap_or("gauge"),
+            "points": series["points"].as_array().map_or(0, Vec::len),
+            "tags": series["tags"],
+            "errors": response["errors"],
+            "note": "Submitted points take a minute or two to become queryable",
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[test]
+    fn test_build_series() {
+        let body = MetricsSubmitHandler::build_series(
+            &json!({
+                "metric": "experiment.score",
+                "value": 0.87,
+                "tags": "experiment:a, variant:b",
+                "host": "web-1"
+            }),
+            NOW,
+        )
+        .unwrap();
+
+        let series = &body["series"][0];
+        assert_eq!(series["type"], 3);
+        assert_eq!(series["points"], json!([{"timestamp": NOW, "value": 0.87}]));
+        assert_eq!(series["tags"], json!(["experiment:a", "variant:b"]));
+        assert_eq!(series["resources"][0]["name"], "web-1");
+        assert!(series.get("interval").is_none());
+
+        let body = MetricsSubmitHandler::build_series(
+            &json!({
+                "metric": "deploys",
+                "type": "count",
+                "interval": 60,
+                "points": [1, {"timestamp": NOW - 120, "value": 2}]
+            }),
+            NOW,
+        )
+        .unwrap();
+        let series = &body["series"][0];
+        assert_eq!(series["type"], 1);
+        assert_eq!(series["interval"], 60);
+        assert_eq!(series["points"][1]["timestamp"], NOW - 120);
+    }
+
+    #[test]
+    fn test_build_series_validation() {
+        let invalid = [
+            json!({"value": 1}),
+            json!({"metric": "1bad", "value": 1}),
+            json!({"metric": "ok"}),
+            json!({"metric": "ok", "value": "high"}),
+            json!({"metric": "ok", "value": 1, "type": "histogram"}),
+            json!({"metric": "ok", "value": 1, "type": "count"}),
+            json!({"metric": "ok", "points": []}),
+            json!({"metric": "ok", "points": [{"timestamp": NOW - 7200, "value": 1}]}),
+        ];
+        for params in invalid {
+            assert!(
+                MetricsSubmitHandler::build_series(&params, NOW).is_err(),
+                "{}",
+                params
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v2/series"))
+            .respond_with(ResponseTemplate::new(202).set_body_json(json!({"errors": []})))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = DatadogClient::new("key".to_string(), "app".to_string(), None)
+            .unwrap()
+            .with_api_url(&mock_server.uri());
+        let params = json!({"metric": "experiment.score", "value": 1});
+
+        let result = MetricsSubmitHandler::submit(Arc::new(client), &params)
+            .await
+            .unwrap();
+        assert_eq!(result["data"]["metric"], "experiment.score");
+        assert_eq!(result["data"]["points"], 1);
+    }
+}
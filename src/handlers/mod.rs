@@ -1,12 +1,37 @@
+pub mod apm_resources;
+pub mod apm_sampling;
 pub mod common;
+pub mod connection;
+pub mod dashboard_widgets;
 pub mod dashboards;
+pub mod error_tracking;
+pub mod event_templates;
 pub mod events;
 pub mod hosts;
+pub mod insights;
+pub mod keys;
 pub mod logs;
 pub mod logs_aggregate;
+pub mod logs_facets;
+pub mod logs_indexes;
+pub mod logs_metrics;
+pub mod logs_patterns;
 pub mod logs_timeseries;
 pub mod metrics;
+pub mod metrics_lint;
+pub mod metrics_metadata;
+pub mod metrics_submit;
+pub mod monitor_history;
+pub mod monitor_tuning;
 pub mod monitors;
+pub mod notebooks;
+pub mod orgs;
+pub mod powerpacks;
+pub mod processes;
 pub mod rum;
+pub mod security_rules;
+pub mod service_health;
 pub mod services;
 pub mod spans;
+pub mod traces;
+pub mod usage;
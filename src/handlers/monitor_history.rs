@@ -0,0 +1,286 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::EventV2;
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::{ResponseFormatter, TimeHandler, TimeParams};
+
+/// Monitor events fetched per call; the v2 search API returns at most 1000 per page
+const MAX_HISTORY_EVENTS: usize = 1000;
+
+const DEFAULT_TRANSITION_LIMIT: usize = 50;
+
+/// A group changing state this many times in the window is flagged as flapping
+const FLAP_THRESHOLD: usize = 4;
+
+pub struct MonitorHistoryHandler;
+
+impl TimeHandler for MonitorHistoryHandler {}
+impl ResponseFormatter for MonitorHistoryHandler {}
+
+/// One state change of a monitor, possibly for a single group
+#[derive(Debug, PartialEq)]
+struct Transition {
+    timestamp: String,
+    /// Triggered, Recovered, Warn, No Data, Re-Triggered, ...
+    transition: String,
+    groups: Vec<String>,
+    title: Option<String>,
+}
+
+impl MonitorHistoryHandler {
+    /// State change named in a monitor event title: "[Triggered on {host:a}] High CPU"
+    fn title_transition(title: &str) -> Option<(String, Vec<String>)> {
+        let (inner, rest) = title.strip_prefix('[')?.split_once(']')?;
+
+        // Priority prefixes such as "[P1] [Triggered]" come first in some titles
+        if inner
+            .strip_prefix('P')
+            .is_some_and(|n| n.parse::<u8>().is_ok())
+        {
+            return Self::title_transition(rest.trim_start());
+        }
+
+        let (state, groups) = match inner.split_once(" on {") {
+            Some((state, groups)) => (state, groups.trim_end_matches('}')),
+            None => (inner, ""),
+        };
+        Some((state.trim().to_string(), Self::split_groups(groups)))
+    }
+
+    /// "host:a,env:prod" -> ["host:a", "env:prod"]
+    fn split_groups(groups: &str) -> Vec<String> {
+        groups
+            .split(',')
+            .map(str::trim)
+            .filter(|g| !g.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    fn transition(event: &EventV2) -> Option<Transition> {
+        let attributes = event.attributes.as_ref()?;
+        let fields = attributes.attributes.as_ref();
+        let field = |name: &str| fields.and_then(|f| f.get(name));
+
+        let title = field("title").and_then(Value::as_str).map(String::from);
+        let from_title = title.as_deref().and_then(Self::title_transition);
+
+        let transition = field("monitor")
+            .and_then(|m| m["transition"]["transition_type"].as_str())
+            .map(String::from)
+            .or_else(|| from_title.as_ref().map(|(state, _)| state.clone()))
+            .or_else(|| field("status").and_then(Value::as_str).map(String::from))?;
+
+        let mut groups: Vec<String> = field("monitor_groups")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .collect();
+        if groups.is_empty() {
+            groups = from_title.map(|(_, groups)| groups).unwrap_or_default();
+        }
+
+        Some(Transition {
+            timestamp: attributes.timestamp.clone().unwrap_or_default(),
+            transition,
+            groups,
+            title,
+        })
+    }
+
+    /// Per-group transition counts and latest transition (transitions arrive newest first)
+    fn summarize_groups(transitions: &[Transition]) -> Vec<Value> {
+        let mut groups: BTreeMap<String, (usize, usize, &Transition)> = BTreeMap::new();
+        for transition in transitions {
+            let names = if transition.groups.is_empty() {
+                vec!["*".to_string()]
+            } else {
+                transition.groups.clone()
+            };
+            for name in names {
+                let entry = groups.entry(name).or_insert((0, 0, transition));
+                entry.0 += 1;
+                if transition.transition.to_lowercase().contains("trigger")
+                    || transition.transition.eq_ignore_ascii_case("alert")
+                {
+                    entry.1 += 1;
+                }
+            }
+        }
+
+        let mut summary: Vec<Value> = groups
+            .into_iter()
+            .map(|(group, (count, triggered, last))| {
+                json!({
+                    "group": group,
+                    "transitions": count,
+                    "triggered": triggered,
+                    "last_transition": last.transition,
+                    "last_transition_at": last.timestamp,
+                    "flapping": count >= FLAP_THRESHOLD,
+                })
+            })
+            .collect();
+        summary.sort_by(|a, b| b["transitions"].as_u64().cmp(&a["transitions"].as_u64()));
+        summary
+    }
+
+    pub async fn history(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = MonitorHistoryHandler;
+
+        let monitor_id = params["monitor_id"].as_i64().ok_or_else(|| {
+            DatadogError::InvalidInput("Missing 'monitor_id' parameter".to_string())
+        })?;
+        let limit = params["limit"]
+            .as_u64()
+            .map(|l| l as usize)
+            .unwrap_or(DEFAULT_TRANSITION_LIMIT);
+        let group = params["group"].as_str();
+
+        let mut window = params.clone();
+        if window["from"].is_null() && window["range"].is_null() {
+            window["from"] = json!("7 days ago");
+        }
+        let TimeParams::Timestamp { from, to } = handler.parse_time(&window, 2)?;
+        let from_iso = handler.timestamp_to_iso8601(from)?;
+        let to_iso = handler.timestamp_to_iso8601(to)?;
+
+        let query = format!("source:alert @monitor_id:{}", monitor_id);
+        let (monitor, events) = tokio::try_join!(
+            client.get_monitor(monitor_id),
+            client.search_events(&query, &from_iso, &to_iso, MAX_HISTORY_EVENTS, None),
+        )?;
+
+        let transitions: Vec<Transition> = events
+            .data
+            .iter()
+            .flatten()
+            .filter_map(Self::transition)
+            .filter(|t| group.is_none_or(|g| t.groups.iter().any(|name| name == g)))
+            .collect();
+        let groups = Self::summarize_groups(&transitions);
+        let flapping: Vec<&Value> = groups
+            .iter()
+            .filter(|g| g["flapping"] == true)
+            .map(|g| &g["group"])
+            .collect();
+
+        let data = json!({
+            "monitor": {
+                "id": monitor.id,
+                "name": monitor.name,
+                "overall_state": monitor.overall_state,
+                "overall_state_modified": monitor.overall_state_modified,
+            },
+            "groups": groups,
+            "transitions": transitions
+                .iter()
+                .take(limit)
+                .map(|t| json!({
+                    "timestamp": t.timestamp,
+                    "transition": t.transition,
+                    "groups": t.groups,
+                    "title": t.title,
+                }))
+                .collect::<Vec<_>>(),
+        });
+
+        let meta = json!({
+            "query": query,
+            "from": crate::utils::format_timestamp(from),
+            "to": crate::utils::format_timestamp(to),
+            "total_transitions": transitions.len(),
+            "returned_transitions": transitions.len().min(limit),
+            "flapping_groups": flapping,
+            "truncated": events.data.as_ref().map_or(0, Vec::len) >= MAX_HISTORY_EVENTS,
+        });
+
+        let mut response = handler.format_detail(data);
+        response["meta"] = meta;
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(title: &str, extra: Value) -> EventV2 {
+        let mut attributes = json!({"title": title});
+        if let (Some(target), Some(source)) = (attributes.as_object_mut(), extra.as_object()) {
+            target.extend(source.clone());
+        }
+        serde_json::from_value(json!({
+            "id": "1",
+            "attributes": {
+                "timestamp": "2024-01-01T00:00:00Z",
+                "attributes": attributes
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_title_transition() {
+        assert_eq!(
+            MonitorHistoryHandler::title_transition("[Triggered on {host:a,env:prod}] High CPU"),
+            Some((
+                "Triggered".to_string(),
+                vec!["host:a".to_string(), "env:prod".to_string()]
+            ))
+        );
+        assert_eq!(
+            MonitorHistoryHandler::title_transition("[P2] [Recovered] High CPU"),
+            Some(("Recovered".to_string(), vec![]))
+        );
+        assert_eq!(MonitorHistoryHandler::title_transition("High CPU"), None);
+    }
+
+    #[test]
+    fn test_transition_prefers_monitor_fields() {
+        let transition = MonitorHistoryHandler::transition(&event(
+            "[Triggered on {host:a}] High CPU",
+            json!({
+                "monitor": {"transition": {"transition_type": "alert"}},
+                "monitor_groups": ["host:b"]
+            }),
+        ))
+        .unwrap();
+        assert_eq!(transition.transition, "alert");
+        assert_eq!(transition.groups, vec!["host:b"]);
+
+        let transition =
+            MonitorHistoryHandler::transition(&event("[Warn on {host:c}] High CPU", json!({})))
+                .unwrap();
+        assert_eq!(transition.transition, "Warn");
+        assert_eq!(transition.groups, vec!["host:c"]);
+    }
+
+    #[test]
+    fn test_summarize_groups_flags_flapping() {
+        let transitions: Vec<Transition> = [
+            "[Recovered on {host:a}] x",
+            "[Triggered on {host:a}] x",
+            "[Recovered on {host:a}] x",
+            "[Triggered on {host:a}] x",
+            "[Triggered on {host:b}] x",
+        ]
+        .iter()
+        .filter_map(|title| MonitorHistoryHandler::transition(&event(title, json!({}))))
+        .collect();
+
+        let groups = MonitorHistoryHandler::summarize_groups(&transitions);
+        assert_eq!(groups[0]["group"], "host:a");
+        assert_eq!(groups[0]["transitions"], 4);
+        assert_eq!(groups[0]["triggered"], 2);
+        assert_eq!(groups[0]["last_transition"], "Recovered");
+        assert_eq!(groups[0]["flapping"], true);
+        assert_eq!(groups[1]["group"], "host:b");
+        assert_eq!(groups[1]["flapping"], false);
+    }
+}
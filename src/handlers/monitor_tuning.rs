@@ -0,0 +1,459 @@
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::MetricsResponse;
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::{ResponseFormatter, percentile};
+
+const DEFAULT_REPLAY_DAYS: i64 = 7;
+const MAX_REPLAY_DAYS: i64 = 30;
+
+/// Keep replayed series under the API's practical point limit
+const MAX_REPLAY_POINTS: i64 = 1500;
+
+/// Percentiles of the replayed values offered as candidate thresholds
+const CANDIDATE_PERCENTILES: &[f64] = &[50.0, 90.0, 95.0, 99.0, 99.9];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparator {
+    Above,
+    AboveOrEqual,
+    Below,
+    BelowOrEqual,
+}
+
+impl Comparator {
+    fn parse(op: &str) -> Option<Self> {
+        match op {
+            ">" => Some(Self::Above),
+            ">=" => Some(Self::AboveOrEqual),
+            "<" => Some(Self::Below),
+            "<=" => Some(Self::BelowOrEqual),
+            _ => None,
+        }
+    }
+
+    fn breaches(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Above => value > threshold,
+            Self::AboveOrEqual => value >= threshold,
+            Self::Below => value < threshold,
+            Self::BelowOrEqual => value <= threshold,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Above => ">",
+            Self::AboveOrEqual => ">=",
+            Self::Below => "<",
+            Self::BelowOrEqual => "<=",
+        }
+    }
+}
+
+/// A metric monitor query split into evaluation parts:
+/// `avg(last_5m):avg:system.cpu.user{env:prod} by {host} > 90`
+#[derive(Debug)]
+struct MonitorQuery {
+    time_aggregation: String,
+    window_secs: i64,
+    metric_query: String,
+    comparator: Comparator,
+    threshold: f64,
+}
+
+/// A contiguous run of breaching evaluations, in Unix seconds
+type Episode = (i64, i64);
+
+pub struct MonitorTuningHandler;
+
+impl ResponseFormatter for MonitorTuningHandler {}
+
+impl MonitorTuningHandler {
+    fn parse_monitor_query(query: &str) -> Result<MonitorQuery> {
+        let unsupported = |reason: &str| {
+            DatadogError::InvalidInput(format!(
+                "Cannot replay monitor query '{}': {}",
+                query, reason
+            ))
+        };
+
+        let (evaluation, rest) = query
+            .split_once("):")
+            .ok_or_else(|| unsupported("expected 'aggregation(last_N):metric query'"))?;
+        let (time_aggregation, window) = evaluation
+            .split_once('(')
+            .ok_or_else(|| unsupported("missing evaluation window"))?;
+        if !matches!(time_aggregation, "avg" | "max" | "min" | "sum") {
+            return Err(unsupported(
+                "only avg, max, min and sum evaluations can be replayed",
+            ));
+        }
+        let window_secs = window
+            .strip_prefix("last_")
+            .and_then(crate::utils::parse_duration)
+            .ok_or_else(|| unsupported("unrecognized evaluation window"))?;
+
+        let op_pos = rest
+            .rfind(['>', '<'])
+            .ok_or_else(|| unsupported("missing threshold comparison"))?;
+        let (op, threshold) = match rest[op_pos + 1..].strip_prefix('=') {
+            Some(threshold) => (&rest[op_pos..op_pos + 2], threshold),
+            None => (&rest[op_pos..op_pos + 1], &rest[op_pos + 1..]),
+        };
+        let comparator = Comparator::parse(op).ok_or_else(|| unsupported("bad comparator"))?;
+        let threshold: f64 = threshold
+            .trim()
+            .parse()
+            .map_err(|_| unsupported("threshold is not a number"))?;
+
+        Ok(MonitorQuery {
+            time_aggregation: time_aggregation.to_string(),
+            window_secs,
+            metric_query: rest[..op_pos].trim().to_string(),
+            comparator,
+            threshold,
+        })
+    }
+
+    /// Non-null (timestamp seconds, value) points for each replayed series
+    fn series_points(response: &MetricsResponse) -> Vec<Vec<(i64, f64)>> {
+        response
+            .series
+            .iter()
+            .map(|s| {
+                s.pointlist
+                    .iter()
+                    .flatten()
+                    .filter_map(|p| {
+                        Some(((p.first().copied()?? / 1000.0) as i64, p.get(1).copied()??))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn episodes(
+        points: &[(i64, f64)],
+        comparator: Comparator,
+        threshold: f64,
+        interval: i64,
+    ) -> Vec<Episode> {
+        let mut episodes = Vec::new();
+        let mut current: Option<Episode> = None;
+
+        for &(ts, value) in points {
+            if comparator.breaches(value, threshold) {
+                current = Some(match current {
+                    Some((start, _)) => (start, ts + interval),
+                    None => (ts, ts + interval),
+                });
+            } else if let Some(episode) = current.take() {
+                episodes.push(episode);
+            }
+        }
+        episodes.extend(current);
+        episodes
+    }
+
+    fn overlaps(a: &Episode, b: &Episode) -> bool {
+        a.0 < b.1 && b.0 < a.1
+    }
+
+    fn candidate_thresholds(
+        values: &[f64],
+        monitor: &MonitorQuery,
+        warning: Option<f64>,
+    ) -> Vec<f64> {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        let mut candidates: Vec<f64> = CANDIDATE_PERCENTILES
+            .iter()
+            .filter_map(|p| percentile(&sorted, *p))
+            .map(|v| (v * 100.0).round() / 100.0)
+            .chain(std::iter::once(monitor.threshold))
+            .chain(warning)
+            .collect();
+        candidates.sort_by(f64::total_cmp);
+        candidates.dedup();
+        candidates
+    }
+
+    /// Score each candidate against incidents when given, otherwise against the current threshold
+    fn evaluate(
+        series: &[Vec<(i64, f64)>],
+        monitor: &MonitorQuery,
+        candidates: &[f64],
+        interval: i64,
+        incidents: &[Episode],
+    ) -> Vec<Value> {
+        let total_points: usize = series.iter().map(Vec::len).sum();
+        let episodes_for = |threshold: f64| -> Vec<Episode> {
+            series
+                .iter()
+                .flat_map(|points| Self::episodes(points, monitor.comparator, threshold, interval))
+                .collect()
+        };
+
+        let reference = if incidents.is_empty() {
+            episodes_for(monitor.threshold)
+        } else {
+            incidents.to_vec()
+        };
+
+        candidates
+            .iter()
+            .map(|&threshold| {
+                let fired = episodes_for(threshold);
+                let breach_points = series
+                    .iter()
+                    .flatten()
+                    .filter(|(_, v)| monitor.comparator.breaches(*v, threshold))
+                    .count();
+
+                let false_positives = fired
+                    .iter()
+                    .filter(|e| !reference.iter().any(|r| Self::overlaps(e, r)))
+                    .count();
+                let false_negatives = reference
+                    .iter()
+                    .filter(|r| !fired.iter().any(|e| Self::overlaps(e, r)))
+                    .count();
+
+                json!({
+                    "threshold": threshold,
+                    "is_current": threshold == monitor.threshold,
+                    "alerts": fired.len(),
+                    "breach_pct": if total_points > 0 {
+                        (breach_points as f64 / total_points as f64 * 10000.0).round() / 100.0
+                    } else {
+                        0.0
+                    },
+                    "false_positives": false_positives,
+                    "false_negatives": false_negatives,
+                })
+            })
+            .collect()
+    }
+
+    /// Fewest missed incidents first, then fewest false alarms
+    fn recommend(table: &[Value]) -> Option<&Value> {
+        table.iter().min_by_key(|row| {
+            (
+                row["false_negatives"].as_u64().unwrap_or(u64::MAX),
+                row["false_positives"].as_u64().unwrap_or(u64::MAX),
+            )
+        })
+    }
+
+    fn parse_incidents(params: &Value) -> Result<Vec<Episode>> {
+        params["incidents"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|incident| {
+                let from = incident["from"].as_str().ok_or_else(|| {
+                    DatadogError::InvalidInput("Each incident needs 'from' and 'to'".to_string())
+                })?;
+                let to = incident["to"].as_str().ok_or_else(|| {
+                    DatadogError::InvalidInput("Each incident needs 'from' and 'to'".to_string())
+                })?;
+                Ok((
+                    crate::utils::parse_time(from)?,
+                    crate::utils::parse_time(to)?,
+                ))
+            })
+            .collect()
+    }
+
+    pub async fn tune(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = MonitorTuningHandler;
+
+        let monitor_id = params["monitor_id"].as_i64().ok_or_else(|| {
+            DatadogError::InvalidInput("Missing 'monitor_id' parameter".to_string())
+        })?;
+        let days = params["days"]
+            .as_i64()
+            .unwrap_or(DEFAULT_REPLAY_DAYS)
+            .clamp(1, MAX_REPLAY_DAYS);
+        let incidents = Self::parse_incidents(params)?;
+
+        let monitor = client.get_monitor(monitor_id).await?;
+        let parsed = Self::parse_monitor_query(&monitor.query)?;
+        let thresholds = monitor.options.as_ref().and_then(|o| o.thresholds.as_ref());
+        let warning = thresholds.and_then(|t| t.warning);
+
+        let to = chrono::Utc::now().timestamp();
+        let from = to - days * 86400;
+
+        // One point per evaluation window, coarsened only when the range would exceed the point limit
+        let interval = parsed.window_secs.max((to - from) / MAX_REPLAY_POINTS);
+        let replay_query = format!(
+            "{}.rollup({}, {})",
+            parsed.metric_query, parsed.time_aggregation, interval
+        );
+
+        let response = client.query_metrics(&replay_query, from, to).await?;
+        let series = Self::series_points(&response);
+        let values: Vec<f64> = series.iter().flatten().map(|(_, v)| *v).collect();
+
+        let candidates = match params["thresholds"].as_array() {
+            Some(custom) if !custom.is_empty() => {
+                let mut custom: Vec<f64> = custom.iter().filter_map(Value::as_f64).collect();
+                custom.push(parsed.threshold);
+                custom.sort_by(f64::total_cmp);
+                custom.dedup();
+                custom
+            }
+            _ => Self::candidate_thresholds(&values, &parsed, warning),
+        };
+
+        let table = Self::evaluate(&series, &parsed, &candidates, interval, &incidents);
+
+        let mut data = json!({
+            "monitor": {
+                "id": monitor.id,
+                "name": monitor.name,
+                "query": monitor.query,
+                "comparator": parsed.comparator.as_str(),
+                "critical": parsed.threshold,
+                "warning": warning,
+            },
+            "candidates": table,
+        });
+
+        if incidents.is_empty() {
+            data["note"] = json!(
+                "No incidents given: false positives/negatives are measured against the current threshold's alerts. Pass 'incidents' for a recommendation."
+            );
+        } else if let Some(best) = Self::recommend(&table) {
+            data["recommendation"] = best.clone();
+        }
+
+        let meta = json!({
+            "replay_query": replay_query,
+            "evaluation_window_secs": parsed.window_secs,
+            "replay_interval_secs": interval,
+            "series": series.len(),
+            "points": values.len(),
+            "ground_truth": if incidents.is_empty() { "current_threshold" } else { "incidents" },
+            "from": crate::utils::format_timestamp(from),
+            "to": crate::utils::format_timestamp(to),
+        });
+
+        Ok(handler.format_list(data, None, Some(meta)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(comparator: Comparator, threshold: f64) -> MonitorQuery {
+        MonitorQuery {
+            time_aggregation: "avg".to_string(),
+            window_secs: 60,
+            metric_query: "avg:system.cpu.user{*}".to_string(),
+            comparator,
+            threshold,
+        }
+    }
+
+    #[test]
+    fn test_parse_monitor_query() {
+        let parsed = MonitorTuningHandler::parse_monitor_query(
+            "avg(last_5m):avg:system.cpu.user{env:prod} by {host} >= 90.5",
+        )
+        .unwrap();
+        assert_eq!(parsed.time_aggregation, "avg");
+        assert_eq!(parsed.window_secs, 300);
+        assert_eq!(
+            parsed.metric_query,
+            "avg:system.cpu.user{env:prod} by {host}"
+        );
+        assert_eq!(parsed.comparator, Comparator::AboveOrEqual);
+        assert_eq!(parsed.threshold, 90.5);
+
+        let parsed =
+            MonitorTuningHandler::parse_monitor_query("min(last_1h):avg:system.disk.free{*} < 10")
+                .unwrap();
+        assert_eq!(parsed.comparator, Comparator::Below);
+        assert_eq!(parsed.window_secs, 3600);
+    }
+
+    #[test]
+    fn test_parse_monitor_query_unsupported() {
+        assert!(
+            MonitorTuningHandler::parse_monitor_query(
+                "pct_change(avg(last_5m),last_5m):avg:system.load.1{*} > 50"
+            )
+            .is_err()
+        );
+        assert!(
+            MonitorTuningHandler::parse_monitor_query(
+                "\"http.can_connect\".over(\"*\").last(2).count_by_status()"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_episodes() {
+        let points = vec![(0, 95.0), (60, 96.0), (120, 50.0), (180, 99.0)];
+        let episodes = MonitorTuningHandler::episodes(&points, Comparator::Above, 90.0, 60);
+        assert_eq!(episodes, vec![(0, 120), (180, 240)]);
+    }
+
+    #[test]
+    fn test_evaluate_against_current_threshold() {
+        let series = vec![vec![(0, 95.0), (60, 50.0), (120, 85.0), (180, 50.0)]];
+        let table = MonitorTuningHandler::evaluate(
+            &series,
+            &monitor(Comparator::Above, 90.0),
+            &[80.0, 90.0, 99.0],
+            60,
+            &[],
+        );
+
+        assert_eq!(table[0]["alerts"], 2);
+        assert_eq!(table[0]["false_positives"], 1);
+        assert_eq!(table[1]["is_current"], true);
+        assert_eq!(table[1]["false_positives"], 0);
+        assert_eq!(table[1]["false_negatives"], 0);
+        assert_eq!(table[2]["alerts"], 0);
+        assert_eq!(table[2]["false_negatives"], 1);
+        assert_eq!(table[0]["breach_pct"], 50.0);
+    }
+
+    #[test]
+    fn test_recommend_with_incidents() {
+        let series = vec![vec![(0, 95.0), (60, 50.0), (120, 85.0), (180, 50.0)]];
+        let incidents = vec![(100, 200)];
+        let table = MonitorTuningHandler::evaluate(
+            &series,
+            &monitor(Comparator::Above, 90.0),
+            &[80.0, 90.0],
+            60,
+            &incidents,
+        );
+
+        // 90 misses the incident; 80 catches it at the cost of one false alarm
+        assert_eq!(table[1]["false_negatives"], 1);
+        let best = MonitorTuningHandler::recommend(&table).unwrap();
+        assert_eq!(best["threshold"], 80.0);
+    }
+
+    #[test]
+    fn test_candidate_thresholds() {
+        let values: Vec<f64> = (1..=100).map(f64::from).collect();
+        let candidates = MonitorTuningHandler::candidate_thresholds(
+            &values,
+            &monitor(Comparator::Above, 90.0),
+            Some(80.0),
+        );
+        assert_eq!(candidates, vec![51.0, 80.0, 90.0, 95.0, 99.0, 100.0]);
+    }
+}
@@ -1,19 +1,203 @@
 use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use crate::cache::DataCache;
-use crate::datadog::DatadogClient;
+use crate::datadog::DatadogApi;
+use crate::datadog::models::{Monitor, MonitorGroupState, MonitorSearchCount};
 use crate::error::Result;
-use crate::handlers::common::{Paginator, ResponseFormatter};
+use crate::handlers::common::{Paginator, ResponseFilter, ResponseFormatter};
+
+/// Group statuses from most to least urgent
+const GROUP_STATUSES: &[&str] = &[
+    "Alert", "Warn", "No Data", "Unknown", "Skipped", "Ignored", "OK",
+];
+
+/// Groups listed when the caller does not choose statuses
+const DEFAULT_GROUP_STATES: &str = "alert,warn,no data";
+
+/// Monitors can have thousands of groups; list at most this many
+const MAX_GROUPS: usize = 100;
+
+/// The search endpoint caps `per_page` here
+const MAX_SEARCH_PAGE_SIZE: usize = 1000;
 
 pub struct MonitorsHandler;
 
 impl Paginator for MonitorsHandler {}
+impl ResponseFilter for MonitorsHandler {}
 impl ResponseFormatter for MonitorsHandler {}
 
 impl MonitorsHandler {
+    // Keep only the fields needed to understand why a monitor is silenced
+    fn summarize_downtimes(downtimes: &[Value]) -> Vec<Value> {
+        downtimes
+            .iter()
+            .map(|downtime| {
+                let mut summary = json!({ "id": downtime["id"] });
+
+                if let Some(scope) = downtime.get("scope").filter(|s| !s.is_null()) {
+                    summary["scope"] = scope.clone();
+                }
+                if let Some(start) = downtime["start"].as_i64() {
+                    summary["start"] = json!(crate::utils::format_timestamp(start));
+                }
+                if let Some(end) = downtime["end"].as_i64() {
+                    summary["end"] = json!(crate::utils::format_timestamp(end));
+                }
+                if let Some(active) = downtime["active"].as_bool() {
+                    summary["active"] = json!(active);
+                }
+
+                summary
+            })
+            .collect()
+    }
+
+    /// Monitor `modified` timestamps are RFC 3339 strings
+    fn parse_modified(modified: Option<&str>) -> Option<i64> {
+        chrono::DateTime::parse_from_rfc3339(modified?)
+            .ok()
+            .map(|dt| dt.timestamp())
+    }
+
+    fn status_rank(status: &str) -> usize {
+        GROUP_STATUSES
+            .iter()
+            .position(|s| s.eq_ignore_ascii_case(status))
+            .unwrap_or(GROUP_STATUSES.len())
+    }
+
+    /// Counts for every group plus the groups in the requested statuses, most urgent first
+    fn summarize_groups(groups: &HashMap<String, MonitorGroupState>, statuses: &str) -> Value {
+        let wanted: Vec<String> = statuses
+            .split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+        let include_all = wanted.iter().any(|s| s == "all");
+
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut listed: Vec<(&String, &MonitorGroupState)> = Vec::new();
+        for (name, group) in groups {
+            let status = group.status.as_deref().unwrap_or("Unknown");
+            *counts.entry(status).or_insert(0) += 1;
+            if include_all || wanted.contains(&status.to_lowercase()) {
+                listed.push((name, group));
+            }
+        }
+
+        listed.sort_by(|(a_name, a), (b_name, b)| {
+            let rank = |g: &MonitorGroupState| Self::status_rank(g.status.as_deref().unwrap_or(""));
+            rank(a)
+                .cmp(&rank(b))
+                .then(b.last_triggered_ts.cmp(&a.last_triggered_ts))
+                .then(a_name.cmp(b_name))
+        });
+        let total_listed = listed.len();
+
+        let format_ts = |ts: Option<i64>| ts.filter(|t| *t > 0).map(crate::utils::format_timestamp);
+        let listed: Vec<Value> = listed
+            .into_iter()
+            .take(MAX_GROUPS)
+            .map(|(name, group)| {
+                json!({
+                    "group": name,
+                    "status": group.status,
+                    "last_triggered": format_ts(group.last_triggered_ts),
+                    "last_resolved": format_ts(group.last_resolved_ts),
+                    "last_nodata": format_ts(group.last_nodata_ts),
+                })
+            })
+            .collect();
+
+        let mut summary = json!({
+            "total": groups.len(),
+            "counts": counts,
+            "groups": listed,
+        });
+        if total_listed > MAX_GROUPS {
+            summary["truncated"] = json!(total_listed - MAX_GROUPS);
+        }
+        summary
+    }
+
+    /// Facet counts as `{facet: {value: count}}`
+    fn summarize_counts(counts: &HashMap<String, Vec<MonitorSearchCount>>) -> Value {
+        let summary: BTreeMap<&String, BTreeMap<String, i64>> = counts
+            .iter()
+            .map(|(facet, values)| {
+                let values = values
+                    .iter()
+                    .map(|c| {
+                        let name = match &c.name {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        };
+                        (name, c.count)
+                    })
+                    .collect();
+                (facet, values)
+            })
+            .collect();
+        json!(summary)
+    }
+
+    pub async fn search(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = MonitorsHandler;
+        let (page, page_size) = handler.parse_pagination(params);
+        let per_page = page_size.min(MAX_SEARCH_PAGE_SIZE);
+
+        let query = params["query"]
+            .as_str()
+            .filter(|q| !q.is_empty())
+            .map(String::from);
+        let sort = params["sort"].as_str().map(String::from);
+
+        let response = client
+            .search_monitors(query.clone(), page, per_page, sort)
+            .await?;
+
+        let data = json!(
+            handler.project_fields(
+                response
+                    .monitors
+                    .iter()
+                    .map(|monitor| json!({
+                        "id": monitor.id,
+                        "name": monitor.name,
+                        "status": monitor.status,
+                        "type": monitor.monitor_type,
+                        "query": monitor.query,
+                        "tags": monitor.tags,
+                        "priority": monitor.priority,
+                        "last_triggered": monitor
+                            .last_triggered_ts
+                            .map(crate::utils::format_timestamp),
+                        "creator": monitor.creator.as_ref().and_then(|c| c.handle.clone()),
+                    }))
+                    .collect(),
+                params
+            )
+        );
+
+        let total = response
+            .metadata
+            .as_ref()
+            .and_then(|m| m.total_count)
+            .map(|t| t as usize)
+            .unwrap_or(page * per_page + response.monitors.len());
+        let pagination = handler.format_pagination(page, per_page, total);
+        let meta = json!({
+            "query": query,
+            "counts": response.counts.as_ref().map(Self::summarize_counts),
+        });
+
+        Ok(handler.format_list(data, Some(pagination), Some(meta)))
+    }
+
     pub async fn list(
-        client: Arc<DatadogClient>,
+        client: Arc<dyn DatadogApi>,
         cache: Arc<DataCache>,
         params: &Value,
     ) -> Result<Value> {
@@ -32,12 +216,26 @@ impl MonitorsHandler {
             }),
         );
 
+        let mut meta = None;
         let monitors = if page == 0 {
-            let fresh_monitors = client.list_monitors(tags, monitor_tags, None, None).await?;
-            cache.set_monitors(cache_key.clone(), fresh_monitors).await;
-            cache
-                .get_or_fetch_monitors(&cache_key, || async { unreachable!("Just inserted") })
-                .await?
+            match client.list_monitors(tags, monitor_tags, None, None).await {
+                Ok(fresh_monitors) => {
+                    cache.set_monitors(cache_key.clone(), fresh_monitors).await;
+                    cache
+                        .get_or_fetch_monitors(&cache_key, || async {
+                            unreachable!("Just inserted")
+                        })
+                        .await?
+                }
+                Err(e) if e.is_transient() => match cache.get_stale_monitors(&cache_key).await {
+                    Some((monitors, age)) => {
+                        meta = Some(DataCache::stale_meta(age, &e));
+                        monitors
+                    }
+                    None => return Err(e),
+                },
+                Err(e) => return Err(e),
+            }
         } else {
             cache
                 .get_or_fetch_monitors(&cache_key, || async {
@@ -46,40 +244,71 @@ impl MonitorsHandler {
                 .await?
         };
 
+        let modified_since = params["modified_since"]
+            .as_str()
+            .map(crate::utils::parse_time)
+            .transpose()?;
+        let monitors: Vec<&Monitor> = monitors
+            .iter()
+            .filter(|m| {
+                modified_since.is_none_or(|since| {
+                    Self::parse_modified(m.modified.as_deref()).is_some_and(|ts| ts >= since)
+                })
+            })
+            .collect();
+
         let monitors_slice = handler.paginate(&monitors, page, page_size);
 
         let data = json!(
-            monitors_slice
-                .iter()
-                .map(|monitor| {
-                    json!({
-                        "id": monitor.id,
-                        "name": monitor.name,
-                        "type": monitor.monitor_type,
-                        "query": monitor.query,
-                        "status": monitor.overall_state,
-                        "tags": monitor.tags,
-                        "priority": monitor.priority
+            handler.project_fields(
+                monitors_slice
+                    .iter()
+                    .map(|monitor| {
+                        let mut monitor_json = json!({
+                            "id": monitor.id,
+                            "name": monitor.name,
+                            "type": monitor.monitor_type,
+                            "query": monitor.query,
+                            "status": monitor.overall_state,
+                            "tags": monitor.tags,
+                            "priority": monitor.priority,
+                            "creator": monitor.creator.as_ref().and_then(|c| c.handle.clone()),
+                            "modified": monitor.modified,
+                            "state_modified": monitor.overall_state_modified
+                        });
+
+                        // Flag downtimed monitors with a compact summary of matching downtimes
+                        if let Some(downtimes) = &monitor.matching_downtimes
+                            && !downtimes.is_empty()
+                        {
+                            let summaries = Self::summarize_downtimes(downtimes);
+                            monitor_json["downtimed"] =
+                                json!(summaries.iter().any(|d| d["active"] != json!(false)));
+                            monitor_json["matching_downtimes"] = json!(summaries);
+                        }
+
+                        monitor_json
                     })
-                })
-                .collect::<Vec<_>>()
+                    .collect(),
+                params
+            )
         );
 
         let pagination = handler.format_pagination(page, page_size, monitors.len());
 
-        Ok(handler.format_list(data, Some(pagination), None))
+        Ok(handler.format_list(data, Some(pagination), meta))
     }
 
-    pub async fn get(client: Arc<DatadogClient>, params: &Value) -> Result<Value> {
+    pub async fn get(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
         let handler = MonitorsHandler;
 
         let monitor_id = params["monitor_id"].as_i64().ok_or_else(|| {
             crate::error::DatadogError::InvalidInput("Missing 'monitor_id' parameter".to_string())
         })?;
 
-        let response = client.get_monitor(monitor_id).await?;
+        let response = client.get_monitor_with_group_states(monitor_id).await?;
 
-        let data = json!({
+        let mut data = json!({
             "id": response.id,
             "name": response.name,
             "type": response.monitor_type,
@@ -110,6 +339,15 @@ impl MonitorsHandler {
             })
         });
 
+        if let Some(groups) = response.state.as_ref().and_then(|s| s.groups.as_ref())
+            && !groups.is_empty()
+        {
+            let statuses = params["group_states"]
+                .as_str()
+                .unwrap_or(DEFAULT_GROUP_STATES);
+            data["group_states"] = Self::summarize_groups(groups, statuses);
+        }
+
         Ok(handler.format_detail(data))
     }
 }
@@ -157,6 +395,87 @@ mod tests {
         assert_eq!(page_size, 25);
     }
 
+    #[test]
+    fn test_summarize_downtimes() {
+        let downtimes = vec![json!({
+            "id": 42,
+            "scope": ["env:prod"],
+            "start": 1704067200,
+            "end": null,
+            "active": true,
+            "monitor_id": 7
+        })];
+
+        let summaries = MonitorsHandler::summarize_downtimes(&downtimes);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0]["id"], 42);
+        assert_eq!(summaries[0]["scope"], json!(["env:prod"]));
+        assert!(
+            summaries[0]["start"]
+                .as_str()
+                .unwrap()
+                .contains("2024-01-01")
+        );
+        assert!(summaries[0].get("end").is_none());
+        assert_eq!(summaries[0]["active"], true);
+        assert!(summaries[0].get("monitor_id").is_none());
+    }
+
+    #[test]
+    fn test_parse_modified() {
+        assert_eq!(
+            MonitorsHandler::parse_modified(Some("2023-11-14T22:13:20.000000+00:00")),
+            Some(1_700_000_000)
+        );
+        assert_eq!(MonitorsHandler::parse_modified(Some("yesterday")), None);
+        assert_eq!(MonitorsHandler::parse_modified(None), None);
+    }
+
+    fn groups() -> HashMap<String, MonitorGroupState> {
+        serde_json::from_value(json!({
+            "host:a": {"name": "host:a", "status": "OK", "last_triggered_ts": 0},
+            "host:b": {"name": "host:b", "status": "Alert", "last_triggered_ts": 1_700_000_000},
+            "host:c": {"name": "host:c", "status": "Warn", "last_triggered_ts": 1_700_000_100},
+            "host:d": {"name": "host:d", "status": "Alert", "last_triggered_ts": 1_700_000_200}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_summarize_groups() {
+        let summary = MonitorsHandler::summarize_groups(&groups(), DEFAULT_GROUP_STATES);
+        assert_eq!(summary["total"], 4);
+        assert_eq!(summary["counts"]["Alert"], 2);
+        assert_eq!(summary["counts"]["OK"], 1);
+
+        // Most urgent first, most recently triggered first within a status; OK omitted
+        let listed: Vec<&str> = summary["groups"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|g| g["group"].as_str().unwrap())
+            .collect();
+        assert_eq!(listed, vec!["host:d", "host:b", "host:c"]);
+        assert!(summary["groups"][0]["last_resolved"].is_null());
+
+        let summary = MonitorsHandler::summarize_groups(&groups(), "all");
+        assert_eq!(summary["groups"].as_array().unwrap().len(), 4);
+        assert!(summary["groups"][3]["last_triggered"].is_null());
+    }
+
+    #[test]
+    fn test_summarize_counts() {
+        let counts: HashMap<String, Vec<MonitorSearchCount>> = serde_json::from_value(json!({
+            "status": [{"name": "Alert", "count": 3}, {"name": "OK", "count": 40}],
+            "muted": [{"name": false, "count": 42}, {"name": true, "count": 1}]
+        }))
+        .unwrap();
+
+        let summary = MonitorsHandler::summarize_counts(&counts);
+        assert_eq!(summary["status"]["Alert"], 3);
+        assert_eq!(summary["muted"]["true"], 1);
+    }
+
     #[test]
     fn test_get_missing_monitor_id() {
         let params = json!({});
@@ -0,0 +1,230 @@
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::{Notebook, NotebookCell};
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::{Paginator, ResponseFormatter};
+use crate::handlers::dashboard_widgets::DashboardWidgetsHandler;
+
+/// Markdown cells carry runbook text; keep enough to be useful without flooding context
+const DEFAULT_MAX_TEXT_LENGTH: usize = 2000;
+
+pub struct NotebooksHandler;
+
+impl Paginator for NotebooksHandler {}
+impl ResponseFormatter for NotebooksHandler {}
+
+impl NotebooksHandler {
+    fn summary_json(notebook: &Notebook) -> Value {
+        let attributes = &notebook.attributes;
+        json!({
+            "id": notebook.id,
+            "name": attributes.name,
+            "author": attributes.author.as_ref().and_then(|a| a.handle.clone()),
+            "status": attributes.status,
+            "created": attributes.created,
+            "modified": attributes.modified,
+        })
+    }
+
+    /// Markdown text for prose cells; runnable tool calls for graph cells
+    fn cell_json(&self, cell: &NotebookCell, from: &str, max_text_length: usize) -> Value {
+        let definition = cell
+            .attributes
+            .as_ref()
+            .map(|a| &a.definition)
+            .unwrap_or(&Value::Null);
+        let cell_type = definition["type"].as_str().unwrap_or("unknown");
+
+        let mut data = json!({
+            "id": cell.id,
+            "type": cell_type,
+        });
+
+        if cell_type == "markdown" {
+            let text = definition["text"].as_str().unwrap_or_default();
+            // Truncate on char boundaries; runbooks are often not ASCII
+            let truncated: String = text.chars().take(max_text_length).collect();
+            data["text"] = json!(if truncated.len() < text.len() {
+                format!("{}...", truncated)
+            } else {
+                truncated
+            });
+            return data;
+        }
+
+        if let Some(title) = definition["title"].as_str().filter(|t| !t.is_empty()) {
+            data["title"] = json!(title);
+        }
+
+        let mut translation = DashboardWidgetsHandler::translate(definition);
+        for call in &mut translation.tool_calls {
+            call["arguments"]["from"] = json!(from);
+            call["arguments"]["to"] = json!("now");
+        }
+        data["tool_calls"] = json!(translation.tool_calls);
+        if !translation.unsupported.is_empty() {
+            data["unsupported"] = json!(translation.unsupported);
+        }
+
+        data
+    }
+
+    pub async fn list(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = NotebooksHandler;
+        let (page, page_size) = handler.parse_pagination(params);
+
+        let query = params["query"].as_str().map(String::from);
+        let author = params["author_handle"].as_str().map(String::from);
+
+        let response = client
+            .list_notebooks(query, author, page * page_size, page_size)
+            .await?;
+
+        let total = response
+            .meta
+            .and_then(|m| m.page)
+            .and_then(|p| p.total_filtered_count.or(p.total_count))
+            .unwrap_or(page * page_size + response.data.len());
+
+        let data = json!(
+            response
+                .data
+                .iter()
+                .map(Self::summary_json)
+                .collect::<Vec<_>>()
+        );
+        let pagination = handler.format_pagination(page, page_size, total);
+
+        Ok(handler.format_list(data, Some(pagination), None))
+    }
+
+    pub async fn get(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = NotebooksHandler;
+
+        let notebook_id = params["notebook_id"].as_i64().ok_or_else(|| {
+            DatadogError::InvalidInput("Missing 'notebook_id' parameter".to_string())
+        })?;
+        let max_text_length = params["max_text_length"]
+            .as_u64()
+            .map(|m| m as usize)
+            .unwrap_or(DEFAULT_MAX_TEXT_LENGTH);
+
+        let notebook = client.get_notebook(notebook_id).await?.data;
+
+        let from = notebook
+            .attributes
+            .time
+            .as_ref()
+            .and_then(|t| t["live_span"].as_str())
+            .and_then(DashboardWidgetsHandler::live_span_to_from)
+            .unwrap_or_else(|| "1 hour ago".to_string());
+
+        let cells: Vec<Value> = notebook
+            .attributes
+            .cells
+            .iter()
+            .flatten()
+            .map(|cell| handler.cell_json(cell, &from, max_text_length))
+            .collect();
+
+        let mut data = Self::summary_json(&notebook);
+        data["time"] = json!(notebook.attributes.time);
+        data["cells_summary"] = json!({
+            "total_cells": cells.len(),
+            "cells": cells,
+        });
+
+        Ok(handler.format_detail(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+
+    fn cell(definition: Value) -> NotebookCell {
+        serde_json::from_value(json!({
+            "id": "cell-1",
+            "type": "notebook_cells",
+            "attributes": {"definition": definition}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_markdown_cell() {
+        let handler = NotebooksHandler;
+        let data = handler.cell_json(
+            &cell(json!({"type": "markdown", "text": "# Runbook\nRestart the pods"})),
+            "1 hour ago",
+            2000,
+        );
+        assert_eq!(data["type"], "markdown");
+        assert_eq!(data["text"], "# Runbook\nRestart the pods");
+
+        let data = handler.cell_json(
+            &cell(json!({"type": "markdown", "text": "abcdefghij"})),
+            "1 hour ago",
+            4,
+        );
+        assert_eq!(data["text"], "abcd...");
+
+        let data = handler.cell_json(
+            &cell(json!({"type": "markdown", "text": "재시작 절차"})),
+            "1 hour ago",
+            3,
+        );
+        assert_eq!(data["text"], "재시작...");
+    }
+
+    #[test]
+    fn test_graph_cell_tool_calls() {
+        let handler = NotebooksHandler;
+        let data = handler.cell_json(
+            &cell(json!({
+                "type": "timeseries",
+                "title": "CPU",
+                "requests": [{"q": "avg:system.cpu.user{env:prod}"}]
+            })),
+            "4 hours ago",
+            2000,
+        );
+
+        assert_eq!(data["title"], "CPU");
+        let call = &data["tool_calls"][0];
+        assert_eq!(call["tool"], "datadog_metrics_query");
+        assert_eq!(call["arguments"]["query"], "avg:system.cpu.user{env:prod}");
+        assert_eq!(call["arguments"]["from"], "4 hours ago");
+    }
+
+    #[test]
+    fn test_summary_json() {
+        let notebook: Notebook = serde_json::from_value(json!({
+            "id": 42,
+            "type": "notebooks",
+            "attributes": {
+                "name": "Checkout incident runbook",
+                "author": {"handle": "oncall@example.com"},
+                "status": "published"
+            }
+        }))
+        .unwrap();
+
+        let data = NotebooksHandler::summary_json(&notebook);
+        assert_eq!(data["id"], 42);
+        assert_eq!(data["author"], "oncall@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_get_missing_notebook_id() {
+        let client = Arc::new(
+            DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None).unwrap(),
+        );
+
+        let result = NotebooksHandler::get(client, &json!({})).await;
+        assert!(result.is_err());
+    }
+}
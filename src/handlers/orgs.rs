@@ -0,0 +1,110 @@
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::Org;
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::ResponseFormatter;
+
+pub struct OrgsHandler;
+
+impl ResponseFormatter for OrgsHandler {}
+
+impl OrgsHandler {
+    fn org_json(org: &Org) -> Value {
+        json!({
+            "public_id": org.public_id,
+            "name": org.name,
+            "description": org.description,
+            "created": org.created,
+            "subscription": org.subscription.as_ref().and_then(|s| s.plan_type.as_deref()),
+            "billing": org.billing.as_ref().and_then(|b| b.plan_type.as_deref()),
+        })
+    }
+
+    /// The org the keys belong to, plus its child orgs in a multi-org account
+    pub async fn list(client: Arc<dyn DatadogApi>, _params: &Value) -> Result<Value> {
+        let handler = OrgsHandler;
+
+        let response = client.list_orgs().await?;
+
+        let orgs: Vec<Value> = response.orgs.iter().map(Self::org_json).collect();
+        let meta = json!({
+            "site": client.site(),
+            "count": orgs.len(),
+        });
+
+        Ok(handler.format_list(json!(orgs), None, Some(meta)))
+    }
+
+    /// One org with its settings (SAML, widget sharing, ...)
+    pub async fn get(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = OrgsHandler;
+
+        let public_id = params["public_id"]
+            .as_str()
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| {
+                DatadogError::InvalidInput("Missing 'public_id' parameter".to_string())
+            })?;
+
+        let response = client.get_org(public_id).await?;
+
+        let mut org = Self::org_json(&response.org);
+        org["settings"] = response.org.settings.clone().unwrap_or(Value::Null);
+
+        Ok(handler.format_detail(org))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_list_and_get() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/org"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "orgs": [
+                    {"public_id": "abc123", "name": "Parent", "subscription": {"type": "pro"}},
+                    {"public_id": "def456", "name": "Child", "billing": {"type": "parent_billing"}}
+                ]
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/org/def456"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "org": {
+                    "public_id": "def456",
+                    "name": "Child",
+                    "settings": {"saml": {"enabled": true}}
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = DatadogClient::new("key".to_string(), "app".to_string(), None)
+            .unwrap()
+            .with_api_url(&mock_server.uri());
+        let client: Arc<dyn DatadogApi> = Arc::new(client);
+
+        let list = OrgsHandler::list(client.clone(), &json!({})).await.unwrap();
+        assert_eq!(list["meta"]["count"], 2);
+        assert_eq!(list["data"][0]["subscription"], "pro");
+        assert_eq!(list["data"][1]["billing"], "parent_billing");
+
+        let org = OrgsHandler::get(client.clone(), &json!({"public_id": "def456"}))
+            .await
+            .unwrap();
+        assert_eq!(org["data"]["name"], "Child");
+        assert_eq!(org["data"]["settings"]["saml"]["enabled"], true);
+
+        assert!(OrgsHandler::get(client, &json!({})).await.is_err());
+    }
+}
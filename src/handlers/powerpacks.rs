@@ -0,0 +1,106 @@
+use serde_json::{Value, json};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::Powerpack;
+use crate::error::Result;
+use crate::handlers::common::{Paginator, ResponseFormatter};
+
+/// Largest page the Powerpacks API serves
+const MAX_POWERPACK_PAGE_SIZE: usize = 1000;
+
+pub struct PowerpacksHandler;
+
+impl Paginator for PowerpacksHandler {}
+impl ResponseFormatter for PowerpacksHandler {}
+
+impl PowerpacksHandler {
+    fn powerpack_json(powerpack: &Powerpack) -> Value {
+        let attributes = &powerpack.attributes;
+        let group = attributes.group_widget.as_ref().map(|g| &g.definition);
+        let widget_types: BTreeSet<&str> = group
+            .into_iter()
+            .flat_map(|g| &g.widgets)
+            .map(|w| w.definition.widget_type.as_str())
+            .collect();
+
+        json!({
+            "id": powerpack.id,
+            "name": attributes.name,
+            "description": attributes.description,
+            "tags": attributes.tags.as_ref().unwrap_or(&Vec::new()),
+            "template_variables": attributes.template_variables.as_ref().map(|vars| {
+                vars.iter().map(|var| json!({
+                    "name": var.name,
+                    "defaults": var.defaults
+                })).collect::<Vec<_>>()
+            }).unwrap_or_default(),
+            "group_title": group.and_then(|g| g.title.clone()),
+            "total_widgets": group.map_or(0, |g| g.widgets.len()),
+            "widget_types": widget_types
+        })
+    }
+
+    pub async fn list(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = PowerpacksHandler;
+        let (page, page_size) = handler.parse_pagination(params);
+        let page_size = page_size.clamp(1, MAX_POWERPACK_PAGE_SIZE);
+
+        let response = client.list_powerpacks(page * page_size, page_size).await?;
+
+        let total = response
+            .meta
+            .and_then(|m| m.pagination)
+            .and_then(|p| p.total)
+            .unwrap_or(page * page_size + response.data.len());
+
+        let data = json!(
+            response
+                .data
+                .iter()
+                .map(Self::powerpack_json)
+                .collect::<Vec<_>>()
+        );
+        let pagination = handler.format_pagination(page, page_size, total);
+
+        Ok(handler.format_list(data, Some(pagination), None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_powerpack_json() {
+        let powerpack: Powerpack = serde_json::from_value(json!({
+            "id": "pp-1",
+            "type": "powerpack",
+            "attributes": {
+                "name": "Service health",
+                "tags": ["team:core"],
+                "template_variables": [{"name": "service", "defaults": ["*"]}],
+                "group_widget": {
+                    "definition": {
+                        "type": "group",
+                        "title": "Service health",
+                        "layout_type": "ordered",
+                        "widgets": [
+                            {"definition": {"type": "timeseries"}, "layout": {"x": 0, "y": 0, "width": 6, "height": 2}},
+                            {"definition": {"type": "query_value"}},
+                            {"definition": {"type": "timeseries"}}
+                        ]
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let data = PowerpacksHandler::powerpack_json(&powerpack);
+        assert_eq!(data["name"], "Service health");
+        assert_eq!(data["total_widgets"], 3);
+        assert_eq!(data["widget_types"], json!(["query_value", "timeseries"]));
+        assert_eq!(data["template_variables"][0]["defaults"], json!(["*"]));
+    }
+}
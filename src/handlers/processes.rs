@@ -0,0 +1,170 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::ProcessSummary;
+use crate::error::Result;
+use crate::handlers::common::{PaginationInfo, ResponseFormatter, TagFilter, TagSelector};
+
+const DEFAULT_PROCESS_LIMIT: usize = 100;
+const MAX_PROCESS_LIMIT: usize = 1000;
+
+/// Command lines of JVMs and the like run to kilobytes
+const MAX_CMDLINE_LENGTH: usize = 300;
+
+pub struct ProcessesHandler;
+
+impl TagFilter for ProcessesHandler {}
+impl ResponseFormatter for ProcessesHandler {}
+
+impl ProcessesHandler {
+    /// Comma-separated tag filter, with `host` folded in as a `host:` tag
+    fn tags_param(params: &Value) -> Option<String> {
+        let mut tags: Vec<String> = params["tags"]
+            .as_str()
+            .into_iter()
+            .flat_map(|t| t.split(','))
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .collect();
+
+        if let Some(host) = params["host"].as_str().filter(|h| !h.is_empty()) {
+            tags.push(format!("host:{}", host));
+        }
+
+        (!tags.is_empty()).then(|| tags.join(","))
+    }
+
+    fn truncate_cmdline(cmdline: &str) -> String {
+        if cmdline.chars().count() <= MAX_CMDLINE_LENGTH {
+            return cmdline.to_string();
+        }
+        let truncated: String = cmdline.chars().take(MAX_CMDLINE_LENGTH).collect();
+        format!("{}...", truncated)
+    }
+
+    fn process_json(&self, process: &ProcessSummary, tag_filter: &TagSelector) -> Value {
+        let attributes = process.attributes.as_ref();
+        let tags = attributes
+            .and_then(|a| a.tags.as_deref())
+            .unwrap_or_default();
+
+        let mut data = json!({
+            "pid": attributes.and_then(|a| a.pid),
+            "ppid": attributes.and_then(|a| a.ppid),
+            "host": attributes.and_then(|a| a.host.clone()),
+            "user": attributes.and_then(|a| a.user.clone()),
+            "cmdline": attributes
+                .and_then(|a| a.cmdline.as_deref())
+                .map(Self::truncate_cmdline),
+            "start": attributes.and_then(|a| a.start.clone()),
+        });
+
+        let tags = self.filter_tags(tags, tag_filter);
+        if !tags.is_empty() {
+            data["tags"] = json!(tags);
+        }
+
+        data
+    }
+
+    pub async fn list(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = ProcessesHandler;
+
+        let limit = params["limit"]
+            .as_u64()
+            .map(|l| l as usize)
+            .unwrap_or(DEFAULT_PROCESS_LIMIT)
+            .clamp(1, MAX_PROCESS_LIMIT);
+        let search = params["search"].as_str().map(String::from);
+        let tags = Self::tags_param(params);
+        let cursor = params["cursor"].as_str().map(String::from);
+
+        let response = client
+            .list_processes(search.clone(), tags.clone(), limit, cursor)
+            .await?;
+
+        let tag_filter = params["tag_filter"]
+            .as_str()
+            .or_else(|| client.get_tag_filter_for("processes"))
+            .unwrap_or("*");
+        let tag_filter = TagSelector::parse(tag_filter)?;
+
+        let processes: Vec<Value> = response
+            .data
+            .iter()
+            .map(|p| handler.process_json(p, &tag_filter))
+            .collect();
+
+        let mut by_host: BTreeMap<&str, usize> = BTreeMap::new();
+        for process in &processes {
+            *by_host
+                .entry(process["host"].as_str().unwrap_or("unknown"))
+                .or_insert(0) += 1;
+        }
+
+        let next_cursor = response.meta.and_then(|m| m.page).and_then(|p| p.after);
+        let mut pagination = json!(PaginationInfo::from_cursor(
+            processes.len(),
+            limit,
+            next_cursor.is_some()
+        ));
+        if let Some(cursor) = next_cursor {
+            pagination["next_cursor"] = json!(cursor);
+        }
+        let meta = json!({
+            "search": search,
+            "tags": tags,
+            "by_host": by_host,
+        });
+
+        Ok(handler.format_list(json!(processes), Some(pagination), Some(meta)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tags_param() {
+        assert_eq!(ProcessesHandler::tags_param(&json!({})), None);
+        assert_eq!(
+            ProcessesHandler::tags_param(
+                &json!({"tags": "env:prod, service:api", "host": "web-1"})
+            )
+            .as_deref(),
+            Some("env:prod,service:api,host:web-1")
+        );
+    }
+
+    #[test]
+    fn test_process_json() {
+        let process: ProcessSummary = serde_json::from_value(json!({
+            "id": "p1",
+            "type": "process",
+            "attributes": {
+                "cmdline": format!("java {}", "-Dx=y ".repeat(100)),
+                "host": "web-1",
+                "pid": 4242,
+                "ppid": 1,
+                "user": "app",
+                "start": "2024-01-01T00:00:00Z",
+                "tags": ["env:prod", "team:core"]
+            }
+        }))
+        .unwrap();
+
+        let handler = ProcessesHandler;
+        let data = handler.process_json(&process, &TagSelector::parse("env:").unwrap());
+        assert_eq!(data["pid"], 4242);
+        assert_eq!(data["host"], "web-1");
+        assert_eq!(data["tags"], json!(["env:prod"]));
+        assert!(data["cmdline"].as_str().unwrap().ends_with("..."));
+
+        let data = handler.process_json(&process, &TagSelector::parse("").unwrap());
+        assert!(data.get("tags").is_none());
+    }
+}
@@ -1,22 +1,26 @@
 use serde_json::{Value, json};
 use std::sync::Arc;
 
-use crate::datadog::DatadogClient;
+use crate::datadog::DatadogApi;
 use crate::error::Result;
 use crate::handlers::common::{
-    DEFAULT_STACK_TRACE_LINES, PaginationInfo, ResponseFilter, ResponseFormatter, TagFilter,
-    TimeHandler, TimeParams,
+    DEFAULT_STACK_TRACE_LINES, PaginationInfo, RUM_RETENTION_DAYS, ResponseFilter,
+    ResponseFormatter, TagFilter, TagSelector, TimeHandler, TimeParams,
 };
 
 pub struct RumHandler;
 
-impl TimeHandler for RumHandler {}
+impl TimeHandler for RumHandler {
+    fn retention_days(&self) -> Option<i64> {
+        Some(RUM_RETENTION_DAYS)
+    }
+}
 impl TagFilter for RumHandler {}
 impl ResponseFilter for RumHandler {}
 impl ResponseFormatter for RumHandler {}
 
 impl RumHandler {
-    pub async fn search_events(client: Arc<DatadogClient>, params: &Value) -> Result<Value> {
+    pub async fn search_events(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
         let handler = RumHandler;
 
         let query = params["query"].as_str().unwrap_or("*").to_string();
@@ -38,8 +42,9 @@ impl RumHandler {
         // Get tag filter (same pattern as logs/spans)
         let tag_filter = params["tag_filter"]
             .as_str()
-            .or_else(|| client.get_tag_filter())
+            .or_else(|| client.get_tag_filter_for("rum"))
             .unwrap_or("*");
+        let tag_filter = TagSelector::parse(tag_filter)?;
 
         // Process RUM events with aggressive optimization - only meaningful data
         let events = response
@@ -52,7 +57,7 @@ impl RumHandler {
                 // Apply tag filtering
                 let tags = attrs
                     .and_then(|a| a.tags.as_ref())
-                    .map(|t| handler.filter_tags(t, tag_filter));
+                    .map(|t| handler.filter_tags(t, &tag_filter));
 
                 // Build minimal event entry - only meaningful fields
                 let mut event_entry = json!({
@@ -224,6 +229,7 @@ impl RumHandler {
                 event_entry
             })
             .collect::<Vec<_>>();
+        let events = handler.project_fields(events, params);
 
         let events_count = events.len();
 
@@ -290,16 +296,16 @@ mod tests {
         let tags = vec!["env:prod".to_string(), "service:web".to_string()];
 
         // Test wildcard filter
-        let filtered = handler.filter_tags(&tags, "*");
+        let filtered = handler.filter_tags(&tags, &TagSelector::parse("*").unwrap());
         assert_eq!(filtered.len(), 2);
 
         // Test prefix filter
-        let filtered = handler.filter_tags(&tags, "env:");
+        let filtered = handler.filter_tags(&tags, &TagSelector::parse("env:").unwrap());
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0], "env:prod");
 
         // Test empty filter
-        let filtered = handler.filter_tags(&tags, "");
+        let filtered = handler.filter_tags(&tags, &TagSelector::parse("").unwrap());
         assert_eq!(filtered.len(), 0);
     }
 
@@ -0,0 +1,284 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::SecurityRule;
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::{Paginator, ResponseFormatter};
+
+/// Rules fetched per API page when collecting the full rule set
+const RULES_PAGE_SIZE: usize = 100;
+
+/// Hard stop for rule pagination (default rulesets alone run to several hundred)
+const MAX_RULE_PAGES: usize = 20;
+
+/// Case statuses from least to most severe
+const SEVERITIES: &[&str] = &["info", "low", "medium", "high", "critical"];
+
+pub struct SecurityRulesHandler;
+
+impl Paginator for SecurityRulesHandler {}
+impl ResponseFormatter for SecurityRulesHandler {}
+
+impl SecurityRulesHandler {
+    fn severity_rank(status: &str) -> Option<usize> {
+        SEVERITIES.iter().position(|s| *s == status)
+    }
+
+    /// Most severe status across the rule's cases
+    fn max_severity(rule: &SecurityRule) -> Option<&str> {
+        rule.cases
+            .iter()
+            .flatten()
+            .filter_map(|c| c.status.as_deref())
+            .max_by_key(|s| Self::severity_rank(s))
+    }
+
+    fn matches(rule: &SecurityRule, params: &Value) -> bool {
+        if let Some(enabled) = params["enabled"].as_bool()
+            && rule.is_enabled.unwrap_or(false) != enabled
+        {
+            return false;
+        }
+
+        if let Some(rule_type) = params["type"].as_str()
+            && rule.rule_type.as_deref() != Some(rule_type)
+        {
+            return false;
+        }
+
+        if let Some(severity) = params["severity"].as_str()
+            && !rule
+                .cases
+                .iter()
+                .flatten()
+                .any(|c| c.status.as_deref() == Some(severity))
+        {
+            return false;
+        }
+
+        if let Some(query) = params["query"].as_str().map(str::to_lowercase)
+            && !rule
+                .name
+                .as_deref()
+                .is_some_and(|n| n.to_lowercase().contains(&query))
+        {
+            return false;
+        }
+
+        true
+    }
+
+    fn summary_json(rule: &SecurityRule) -> Value {
+        json!({
+            "id": rule.id,
+            "name": rule.name,
+            "type": rule.rule_type,
+            "enabled": rule.is_enabled,
+            "default": rule.is_default,
+            "max_severity": Self::max_severity(rule),
+            "queries": rule
+                .queries
+                .iter()
+                .flatten()
+                .filter_map(|q| q.query.as_deref())
+                .collect::<Vec<_>>(),
+            "tags": rule.tags,
+        })
+    }
+
+    fn detail_json(rule: &SecurityRule) -> Value {
+        let mut data = Self::summary_json(rule);
+
+        data["queries"] = json!(
+            rule.queries
+                .iter()
+                .flatten()
+                .map(|q| json!({
+                    "name": q.name,
+                    "query": q.query,
+                    "aggregation": q.aggregation,
+                    "group_by": q.group_by_fields,
+                    "distinct_fields": q.distinct_fields,
+                }))
+                .collect::<Vec<_>>()
+        );
+        data["cases"] = json!(
+            rule.cases
+                .iter()
+                .flatten()
+                .map(|c| json!({
+                    "name": c.name,
+                    "severity": c.status,
+                    "condition": c.condition,
+                    "notifications": c.notifications,
+                }))
+                .collect::<Vec<_>>()
+        );
+        data["options"] = json!(rule.options);
+        data["message"] = json!(rule.message);
+        data["version"] = json!(rule.version);
+        data["created_at"] = json!(
+            rule.created_at
+                .map(|ms| crate::utils::format_timestamp(ms / 1000))
+        );
+
+        data
+    }
+
+    async fn fetch_all(client: &dyn DatadogApi) -> Result<Vec<SecurityRule>> {
+        let mut rules = Vec::new();
+
+        for page in 0..MAX_RULE_PAGES {
+            let response = client.list_security_rules(RULES_PAGE_SIZE, page).await?;
+            let fetched = response.data.len();
+            rules.extend(response.data);
+
+            let total = response
+                .meta
+                .and_then(|m| m.page)
+                .and_then(|p| p.total_filtered_count.or(p.total_count));
+            if fetched < RULES_PAGE_SIZE || total.is_some_and(|t| rules.len() >= t) {
+                break;
+            }
+        }
+
+        Ok(rules)
+    }
+
+    pub async fn list(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = SecurityRulesHandler;
+        let (page, page_size) = handler.parse_pagination(params);
+
+        let rules: Vec<SecurityRule> = Self::fetch_all(client.as_ref())
+            .await?
+            .into_iter()
+            .filter(|r| !r.is_deleted.unwrap_or(false))
+            .collect();
+
+        let mut by_type: BTreeMap<&str, usize> = BTreeMap::new();
+        for rule in &rules {
+            *by_type
+                .entry(rule.rule_type.as_deref().unwrap_or("unknown"))
+                .or_insert(0) += 1;
+        }
+        let enabled = rules
+            .iter()
+            .filter(|r| r.is_enabled.unwrap_or(false))
+            .count();
+
+        let filtered: Vec<&SecurityRule> =
+            rules.iter().filter(|r| Self::matches(r, params)).collect();
+
+        let data = json!(
+            handler
+                .paginate(&filtered, page, page_size)
+                .iter()
+                .map(|r| Self::summary_json(r))
+                .collect::<Vec<_>>()
+        );
+        let pagination = handler.format_pagination(page, page_size, filtered.len());
+        let meta = json!({
+            "total_rules": rules.len(),
+            "enabled": enabled,
+            "disabled": rules.len() - enabled,
+            "by_type": by_type,
+        });
+
+        Ok(handler.format_list(data, Some(pagination), Some(meta)))
+    }
+
+    pub async fn get(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = SecurityRulesHandler;
+
+        let rule_id = params["rule_id"]
+            .as_str()
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| DatadogError::InvalidInput("Missing 'rule_id' parameter".to_string()))?;
+
+        let rule = client.get_security_rule(rule_id).await?;
+
+        Ok(handler.format_detail(Self::detail_json(&rule)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+
+    fn rule(enabled: bool, rule_type: &str, severities: &[&str]) -> SecurityRule {
+        serde_json::from_value(json!({
+            "id": "abc-123",
+            "name": "AWS root account activity",
+            "type": rule_type,
+            "isEnabled": enabled,
+            "isDefault": true,
+            "queries": [{
+                "name": "a",
+                "query": "source:cloudtrail @userIdentity.type:Root",
+                "aggregation": "count",
+                "groupByFields": ["@usr.id"]
+            }],
+            "cases": severities
+                .iter()
+                .map(|s| json!({"name": "", "status": s, "condition": "a > 0"}))
+                .collect::<Vec<_>>(),
+            "createdAt": 1_700_000_000_000i64
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_max_severity() {
+        let r = rule(true, "log_detection", &["low", "critical", "medium"]);
+        assert_eq!(SecurityRulesHandler::max_severity(&r), Some("critical"));
+        assert_eq!(
+            SecurityRulesHandler::max_severity(&rule(true, "log_detection", &[])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_matches_filters() {
+        let r = rule(true, "log_detection", &["high"]);
+        assert!(SecurityRulesHandler::matches(&r, &json!({})));
+        assert!(SecurityRulesHandler::matches(
+            &r,
+            &json!({"enabled": true, "type": "log_detection", "severity": "high", "query": "ROOT"})
+        ));
+        assert!(!SecurityRulesHandler::matches(
+            &r,
+            &json!({"enabled": false})
+        ));
+        assert!(!SecurityRulesHandler::matches(
+            &r,
+            &json!({"type": "signal_correlation"})
+        ));
+        assert!(!SecurityRulesHandler::matches(
+            &r,
+            &json!({"severity": "low"})
+        ));
+        assert!(!SecurityRulesHandler::matches(&r, &json!({"query": "gcp"})));
+    }
+
+    #[test]
+    fn test_detail_json() {
+        let data = SecurityRulesHandler::detail_json(&rule(false, "log_detection", &["medium"]));
+        assert_eq!(data["enabled"], false);
+        assert_eq!(data["queries"][0]["group_by"], json!(["@usr.id"]));
+        assert_eq!(data["cases"][0]["severity"], "medium");
+        assert_eq!(data["max_severity"], "medium");
+    }
+
+    #[tokio::test]
+    async fn test_get_missing_rule_id() {
+        let client = Arc::new(
+            DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None).unwrap(),
+        );
+
+        let result = SecurityRulesHandler::get(client, &json!({})).await;
+        assert!(result.is_err());
+    }
+}
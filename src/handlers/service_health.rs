@@ -0,0 +1,374 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::{LogsCompute, MonitorSearchResponse};
+use crate::error::{DatadogError, Result};
+use crate::handlers::apm_resources::ApmResourcesHandler;
+use crate::handlers::common::{
+    ResponseFormatter, SPANS_RETENTION_DAYS, TimeHandler, TimeParams, push_warning,
+};
+use crate::handlers::events::EventsHandler;
+
+/// Monitors fetched for the service (one search page)
+const MONITOR_PAGE_SIZE: usize = 100;
+
+/// Recent events listed for the service
+const EVENT_LIMIT: usize = 10;
+
+/// APM error rate from which the service counts as degraded
+const ERROR_RATE_WARNING: f64 = 0.05;
+
+/// Monitor states that make the service critical, and those that make it degraded
+const CRITICAL_MONITOR_STATES: &[&str] = &["Alert"];
+const WARNING_MONITOR_STATES: &[&str] = &["Warn", "No Data"];
+
+pub struct ServiceHealthHandler;
+
+impl TimeHandler for ServiceHealthHandler {
+    fn retention_days(&self) -> Option<i64> {
+        Some(SPANS_RETENTION_DAYS)
+    }
+}
+impl ResponseFormatter for ServiceHealthHandler {}
+
+impl ServiceHealthHandler {
+    /// `service:<service>` plus `env:<env>` when given, as search terms
+    fn scope(service: &str, env: Option<&str>) -> String {
+        match env {
+            Some(env) => format!("service:{} env:{}", service, env),
+            None => format!("service:{}", service),
+        }
+    }
+
+    /// Monitor states with their counts, and every monitor that is not OK
+    fn summarize_monitors(response: &MonitorSearchResponse) -> Value {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for monitor in &response.monitors {
+            let status = monitor.status.clone().unwrap_or_else(|| "Unknown".into());
+            *counts.entry(status).or_insert(0) += 1;
+        }
+
+        let failing: Vec<Value> = response
+            .monitors
+            .iter()
+            .filter(|m| m.status.as_deref().is_some_and(|s| s != "OK"))
+            .map(|m| {
+                json!({
+                    "id": m.id,
+                    "name": m.name,
+                    "status": m.status,
+                    "last_triggered": m.last_triggered_ts.map(crate::utils::format_timestamp),
+                })
+            })
+            .collect();
+
+        json!({
+            "total": response
+                .metadata
+                .as_ref()
+                .and_then(|m| m.total_count)
+                .unwrap_or(response.monitors.len() as i64),
+            "by_status": counts,
+            "not_ok": failing,
+        })
+    }
+
+    /// Hits, errors, error rate and latency from the span aggregates (durations in ns)
+    fn summarize_apm(all: &Value, errors: &Value) -> Value {
+        let compute = |response: &Value, id: &str| {
+            ApmResourcesHandler::buckets(response)
+                .first()
+                .and_then(|bucket| ApmResourcesHandler::bucket_compute(bucket, id).as_f64())
+        };
+        let round = |v: f64| (v * 1000.0).round() / 1000.0;
+        let to_ms = |ns: f64| round(ns / 1_000_000.0);
+
+        let hits = compute(all, "c0").unwrap_or(0.0);
+        let error_count = compute(errors, "c0").unwrap_or(0.0);
+        json!({
+            "hits": hits as i64,
+            "errors": error_count as i64,
+            "error_rate": (hits > 0.0).then(|| round(error_count / hits)),
+            "avg_ms": compute(all, "c1").map(to_ms),
+            "p95_ms": compute(all, "c2").map(to_ms),
+            "p99_ms": compute(all, "c3").map(to_ms),
+        })
+    }
+
+    /// Overall status from the sections that answered, with the reasons for it
+    fn assess(monitors: &Value, apm: &Value, error_logs: Option<i64>) -> (String, Vec<String>) {
+        let mut signals = Vec::new();
+        let count_of = |states: &[&str]| -> u64 {
+            states
+                .iter()
+                .filter_map(|s| monitors["by_status"][*s].as_u64())
+                .sum()
+        };
+
+        let critical = count_of(CRITICAL_MONITOR_STATES);
+        let warning = count_of(WARNING_MONITOR_STATES);
+        if critical > 0 {
+            signals.push(format!("{} monitor(s) alerting", critical));
+        }
+        if warning > 0 {
+            signals.push(format!("{} monitor(s) warning or without data", warning));
+        }
+
+        let error_rate = apm["error_rate"].as_f64();
+        if let Some(rate) = error_rate.filter(|r| *r >= ERROR_RATE_WARNING) {
+            signals.push(format!("APM error rate {:.1}%", rate * 100.0));
+        }
+        if apm["hits"].as_i64() == Some(0) {
+            signals.push("No APM traffic in the window".to_string());
+        }
+        if let Some(count) = error_logs.filter(|c| *c > 0) {
+            signals.push(format!("{} error log(s)", count));
+        }
+
+        let status = if critical > 0 {
+            "critical"
+        } else if warning > 0 || error_rate.is_some_and(|r| r >= ERROR_RATE_WARNING) {
+            "degraded"
+        } else if monitors.is_null() && apm.is_null() {
+            "unknown"
+        } else {
+            "ok"
+        };
+        (status.to_string(), signals)
+    }
+
+    /// A section's value, or null with a warning naming the section when its call failed
+    fn section<T>(name: &str, result: Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                push_warning(format!("{} unavailable: {}", name, e));
+                None
+            }
+        }
+    }
+
+    /// Monitors, APM stats, error logs and recent events of one service, fetched concurrently
+    pub async fn health(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = ServiceHealthHandler;
+
+        let service = params["service"]
+            .as_str()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| DatadogError::InvalidInput("Missing 'service' parameter".to_string()))?;
+        let env = params["env"].as_str().filter(|e| !e.is_empty());
+
+        let TimeParams::Timestamp { from, to } = handler.parse_time(params, 2)?;
+        let from_iso = handler.timestamp_to_iso8601(from)?;
+        let to_iso = handler.timestamp_to_iso8601(to)?;
+
+        let scope = Self::scope(service, env);
+        let monitor_query = format!("tag:\"service:{}\"", service);
+        let error_scope = format!("{} status:error", scope);
+        let span_compute = json!([
+            {"aggregation": "count", "type": "total"},
+            {"aggregation": "avg", "metric": "@duration", "type": "total"},
+            {"aggregation": "pc95", "metric": "@duration", "type": "total"},
+            {"aggregation": "pc99", "metric": "@duration", "type": "total"}
+        ]);
+        let log_compute = vec![LogsCompute {
+            aggregation: "count".to_string(),
+            compute_type: Some("total".to_string()),
+            interval: None,
+            metric: None,
+        }];
+
+        let (monitors, spans, span_errors, error_logs, events) = tokio::join!(
+            client.search_monitors(Some(monitor_query), 0, MONITOR_PAGE_SIZE, None),
+            client.aggregate_spans(&scope, &from_iso, &to_iso, span_compute, json!([])),
+            client.aggregate_spans(
+                &error_scope,
+                &from_iso,
+                &to_iso,
+                json!([{"aggregation": "count", "type": "total"}]),
+                json!([]),
+            ),
+            client.aggregate_logs(
+                &error_scope,
+                &from_iso,
+                &to_iso,
+                Some(log_compute),
+                None,
+                None
+            ),
+            client.search_events(&scope, &from_iso, &to_iso, EVENT_LIMIT, None),
+        );
+
+        let monitors = Self::section("monitors", monitors)
+            .map(|m| Self::summarize_monitors(&m))
+            .unwrap_or(Value::Null);
+        let apm = match (
+            Self::section("apm", spans),
+            Self::section("apm errors", span_errors),
+        ) {
+            (Some(all), Some(errors)) => Self::summarize_apm(&all, &errors),
+            _ => Value::Null,
+        };
+        let error_logs = Self::section("error logs", error_logs).map(|response| {
+            ApmResourcesHandler::buckets(&response)
+                .first()
+                .and_then(|bucket| ApmResourcesHandler::bucket_compute(bucket, "c0").as_i64())
+                .unwrap_or(0)
+        });
+        let events: Value = Self::section("events", events)
+            .map(|response| {
+                response
+                    .data
+                    .iter()
+                    .flatten()
+                    .map(EventsHandler::v2_event_json)
+                    .map(|event| {
+                        json!({
+                            "date": event["date"],
+                            "title": event["title"],
+                            "source": event["source"],
+                            "alert_type": event["alert_type"],
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or(Value::Null);
+
+        let (status, signals) = Self::assess(&monitors, &apm, error_logs);
+
+        let data = json!({
+            "service": service,
+            "env": env,
+            "status": status,
+            "signals": signals,
+            "monitors": monitors,
+            "apm": apm,
+            "error_logs": error_logs,
+            "recent_events": events,
+        });
+        let meta = json!({
+            "scope": scope,
+            "from": crate::utils::format_timestamp(from),
+            "to": crate::utils::format_timestamp(to),
+        });
+
+        Ok(handler.format_list(data, None, Some(meta)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn monitors(statuses: &[&str]) -> MonitorSearchResponse {
+        serde_json::from_value(json!({
+            "monitors": statuses
+                .iter()
+                .enumerate()
+                .map(|(i, status)| json!({"id": i, "name": format!("m{}", i), "status": status}))
+                .collect::<Vec<_>>()
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_summarize_monitors() {
+        let summary = ServiceHealthHandler::summarize_monitors(&monitors(&["OK", "Alert", "OK"]));
+
+        assert_eq!(summary["total"], 3);
+        assert_eq!(summary["by_status"]["OK"], 2);
+        assert_eq!(summary["not_ok"].as_array().unwrap().len(), 1);
+        assert_eq!(summary["not_ok"][0]["name"], "m1");
+    }
+
+    #[test]
+    fn test_assess() {
+        let apm = |hits: i64, rate: f64| json!({"hits": hits, "error_rate": rate});
+        let summary = |statuses| ServiceHealthHandler::summarize_monitors(&monitors(statuses));
+
+        let (status, signals) =
+            ServiceHealthHandler::assess(&summary(&["OK", "Alert"]), &apm(100, 0.0), Some(0));
+        assert_eq!(status, "critical");
+        assert_eq!(signals, vec!["1 monitor(s) alerting"]);
+
+        let (status, signals) =
+            ServiceHealthHandler::assess(&summary(&["OK"]), &apm(100, 0.12), Some(7));
+        assert_eq!(status, "degraded");
+        assert_eq!(signals, vec!["APM error rate 12.0%", "7 error log(s)"]);
+
+        let (status, _) = ServiceHealthHandler::assess(&summary(&["OK"]), &apm(100, 0.01), None);
+        assert_eq!(status, "ok");
+
+        let (status, _) = ServiceHealthHandler::assess(&Value::Null, &Value::Null, None);
+        assert_eq!(status, "unknown");
+    }
+
+    #[tokio::test]
+    async fn test_failed_section_does_not_fail_the_call() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/monitor/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "monitors": [{"id": 1, "name": "High latency", "status": "Warn"}]
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v2/spans/analytics/aggregate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{"attributes": {"by": {}, "compute": {"c0": 200, "c1": 5_000_000.0}}}]
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v2/logs/analytics/aggregate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"buckets": [{"by": {}, "computes": {"c0": 3}}]}
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v2/events/search"))
+            .respond_with(
+                ResponseTemplate::new(403).set_body_json(json!({"errors": ["Forbidden"]})),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let client = DatadogClient::new("key".to_string(), "app".to_string(), None)
+            .unwrap()
+            .with_api_url(&mock_server.uri());
+        let result = ServiceHealthHandler::health(
+            Arc::new(client),
+            &json!({"service": "checkout", "env": "prod"}),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result["data"]["status"], "degraded");
+        assert_eq!(
+            result["data"]["monitors"]["not_ok"][0]["name"],
+            "High latency"
+        );
+        assert_eq!(result["data"]["apm"]["avg_ms"], 5.0);
+        assert_eq!(result["data"]["error_logs"], 3);
+        assert!(result["data"]["recent_events"].is_null());
+        assert_eq!(result["meta"]["scope"], "service:checkout env:prod");
+    }
+
+    #[tokio::test]
+    async fn test_missing_service() {
+        let client =
+            Arc::new(DatadogClient::new("key".to_string(), "app".to_string(), None).unwrap());
+        assert!(
+            ServiceHealthHandler::health(client, &json!({}))
+                .await
+                .is_err()
+        );
+    }
+}
@@ -1,20 +1,200 @@
 use serde_json::{Value, json};
 use std::sync::Arc;
 
-use crate::datadog::DatadogClient;
-use crate::error::Result;
+use crate::datadog::DatadogApi;
+use crate::datadog::models::{
+    CatalogEntitiesResponse, DatastoreSpec, EntityV3, QueueSpec, ServiceSpec, SystemSpec,
+};
+use crate::error::{DatadogError, Result};
 use crate::handlers::common::{Paginator, ResponseFormatter};
 
+/// Schema v3 entity kinds
+const ENTITY_KINDS: &[&str] = &["service", "datastore", "queue", "system"];
+
 pub struct ServicesHandler;
 
 impl Paginator for ServicesHandler {}
 impl ResponseFormatter for ServicesHandler {}
 
 impl ServicesHandler {
-    pub async fn list(client: Arc<DatadogClient>, params: &Value) -> Result<Value> {
+    /// Kind-specific spec fields, typed for the known v3 kinds
+    fn spec_json(kind: &str, spec: &Value) -> Value {
+        let typed = match kind {
+            "service" => serde_json::from_value::<ServiceSpec>(spec.clone()).map(|s| {
+                json!({
+                    "lifecycle": s.lifecycle,
+                    "tier": s.tier,
+                    "type": s.service_type,
+                    "languages": s.languages,
+                    "depends_on": s.depends_on,
+                    "component_of": s.component_of,
+                })
+            }),
+            "datastore" => serde_json::from_value::<DatastoreSpec>(spec.clone()).map(|s| {
+                json!({
+                    "lifecycle": s.lifecycle,
+                    "tier": s.tier,
+                    "type": s.datastore_type,
+                    "component_of": s.component_of,
+                })
+            }),
+            "queue" => serde_json::from_value::<QueueSpec>(spec.clone()).map(|s| {
+                json!({
+                    "lifecycle": s.lifecycle,
+                    "tier": s.tier,
+                    "type": s.queue_type,
+                    "component_of": s.component_of,
+                })
+            }),
+            "system" => serde_json::from_value::<SystemSpec>(spec.clone()).map(|s| {
+                json!({
+                    "lifecycle": s.lifecycle,
+                    "tier": s.tier,
+                    "components": s.components,
+                })
+            }),
+            _ => return spec.clone(),
+        };
+
+        typed.unwrap_or_else(|_| spec.clone())
+    }
+
+    /// Flatten a v3 entity definition into the same field names as v2 services
+    fn entity_json(id: Option<&str>, entity: &EntityV3) -> Value {
+        let kind = entity.kind.as_deref().unwrap_or("service");
+        let metadata = entity.metadata.as_ref();
+
+        let mut data = json!({
+            "id": id,
+            "kind": kind,
+            "schema_version": entity.api_version,
+            "name": metadata.and_then(|m| m.name.clone()),
+            "display_name": metadata.and_then(|m| m.display_name.clone()),
+            "namespace": metadata.and_then(|m| m.namespace.clone()),
+            "owner": metadata.and_then(|m| m.owner.clone()),
+            "description": metadata.and_then(|m| m.description.clone()),
+            "tags": metadata.and_then(|m| m.tags.clone()),
+        });
+
+        if let Some(spec) = &entity.spec
+            && let Value::Object(fields) = Self::spec_json(kind, spec)
+        {
+            for (key, value) in fields {
+                data[key] = value;
+            }
+        }
+
+        if let Some(contacts) = metadata.and_then(|m| m.contacts.as_ref()) {
+            data["contacts"] = json!(
+                contacts
+                    .iter()
+                    .map(|c| json!({"name": c.name, "email": c.email, "type": c.contact_type}))
+                    .collect::<Vec<_>>()
+            );
+        }
+        if let Some(links) = metadata.and_then(|m| m.links.as_ref()) {
+            data["links"] = json!(
+                links
+                    .iter()
+                    .map(|l| json!({"name": l.name, "url": l.url, "type": l.link_type}))
+                    .collect::<Vec<_>>()
+            );
+        }
+        if let Some(owners) = metadata.and_then(|m| m.additional_owners.as_ref()) {
+            data["additional_owners"] = json!(owners);
+        }
+        if let Some(integrations) = &entity.integrations {
+            data["integrations"] = integrations.clone();
+        }
+
+        data
+    }
+
+    /// Entities joined with their included schema; entities without one keep their summary fields
+    fn entities_json(response: &CatalogEntitiesResponse) -> Vec<Value> {
+        response
+            .data
+            .iter()
+            .map(|entity| {
+                let schema = entity
+                    .relationships
+                    .as_ref()
+                    .and_then(|r| r.schema.as_ref())
+                    .and_then(|s| s.data.as_ref())
+                    .and_then(|d| response.included.iter().flatten().find(|i| i.id == d.id))
+                    .and_then(|i| i.attributes.as_ref())
+                    .and_then(|a| a.schema.as_ref());
+
+                match (schema, &entity.attributes) {
+                    (Some(schema), _) => Self::entity_json(entity.id.as_deref(), schema),
+                    (None, attributes) => {
+                        let attributes = attributes.as_ref();
+                        json!({
+                            "id": entity.id,
+                            "kind": attributes.and_then(|a| a.kind.clone()),
+                            "schema_version": attributes.and_then(|a| a.api_version.clone()),
+                            "name": attributes.and_then(|a| a.name.clone()),
+                            "display_name": attributes.and_then(|a| a.display_name.clone()),
+                            "namespace": attributes.and_then(|a| a.namespace.clone()),
+                            "owner": attributes.and_then(|a| a.owner.clone()),
+                            "description": attributes.and_then(|a| a.description.clone()),
+                            "tags": attributes.and_then(|a| a.tags.clone()),
+                        })
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Software Catalog entities of one kind (or all kinds)
+    async fn list_entities(
+        client: Arc<dyn DatadogApi>,
+        params: &Value,
+        kind: &str,
+    ) -> Result<Value> {
         let handler = ServicesHandler;
         let (page, page_size) = handler.parse_pagination(params);
 
+        let filter = (kind != "all").then_some(kind);
+        if let Some(kind) = filter
+            && !ENTITY_KINDS.contains(&kind)
+        {
+            return Err(DatadogError::InvalidInput(format!(
+                "Invalid kind '{}' (expected one of: {}, all)",
+                kind,
+                ENTITY_KINDS.join(", ")
+            )));
+        }
+
+        let response = client
+            .list_catalog_entities(filter, page_size, page * page_size)
+            .await?;
+        let entities = Self::entities_json(&response);
+        let has_next = response
+            .links
+            .as_ref()
+            .and_then(|l| l.next.as_ref())
+            .is_some();
+
+        let pagination = json!({
+            "page": page,
+            "page_size": page_size,
+            "total": page * page_size + entities.len(),
+            "has_next": has_next,
+        });
+        let meta = json!({ "kind": kind });
+
+        Ok(handler.format_list(json!(entities), Some(pagination), Some(meta)))
+    }
+
+    pub async fn list(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = ServicesHandler;
+
+        if let Some(kind) = params["kind"].as_str() {
+            return Self::list_entities(client, params, kind).await;
+        }
+        let (page, page_size) = handler.parse_pagination(params);
+
         let page_size_param = Some(page_size as i32);
         let page_number_param = Some(page as i32);
         let filter_env = params["env"].as_str().map(|s| s.to_string());
@@ -30,6 +210,17 @@ impl ServicesHandler {
                 .data
                 .iter()
                 .map(|service| {
+                    // Definitions registered with schema v3 carry everything under `schema`
+                    if let Some(entity) = service
+                        .attributes
+                        .as_ref()
+                        .and_then(|a| a.extra.get("schema"))
+                        .filter(|schema| schema["apiVersion"] == "v3")
+                        .and_then(|schema| serde_json::from_value::<EntityV3>(schema.clone()).ok())
+                    {
+                        return Self::entity_json(service.id.as_deref(), &entity);
+                    }
+
                     let mut formatted_service = json!({
                         "id": service.id,
                         "type": service.service_type,
@@ -143,6 +334,7 @@ impl ServicesHandler {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::datadog::DatadogClient;
     use serde_json::json;
 
     #[test]
@@ -151,6 +343,104 @@ mod tests {
         assert_eq!(params["env"].as_str(), Some("production"));
     }
 
+    fn entity(kind: &str, spec: Value) -> EntityV3 {
+        serde_json::from_value(json!({
+            "apiVersion": "v3",
+            "kind": kind,
+            "metadata": {
+                "name": "orders-db",
+                "owner": "team-storage",
+                "tags": ["env:prod"],
+                "contacts": [{"name": "oncall", "type": "slack", "contact": "#storage"}]
+            },
+            "spec": spec,
+            "integrations": {"pagerduty": {"serviceURL": "https://pd.example.com/x"}}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_entity_json_typed_specs() {
+        let data = ServicesHandler::entity_json(
+            Some("e1"),
+            &entity(
+                "service",
+                json!({"lifecycle": "production", "tier": "1", "type": "web", "languages": ["go"], "dependsOn": ["datastore:orders-db"]}),
+            ),
+        );
+        assert_eq!(data["kind"], "service");
+        assert_eq!(data["schema_version"], "v3");
+        assert_eq!(data["owner"], "team-storage");
+        assert_eq!(data["type"], "web");
+        assert_eq!(data["depends_on"], json!(["datastore:orders-db"]));
+        assert_eq!(data["contacts"][0]["type"], "slack");
+        assert!(data["integrations"]["pagerduty"].is_object());
+
+        let data = ServicesHandler::entity_json(
+            None,
+            &entity(
+                "datastore",
+                json!({"type": "postgres", "componentOf": ["system:orders"]}),
+            ),
+        );
+        assert_eq!(data["type"], "postgres");
+        assert_eq!(data["component_of"], json!(["system:orders"]));
+        assert!(data.get("languages").is_none());
+
+        let data = ServicesHandler::entity_json(
+            None,
+            &entity(
+                "system",
+                json!({"components": ["service:orders", "queue:orders-events"]}),
+            ),
+        );
+        assert_eq!(data["components"][1], "queue:orders-events");
+    }
+
+    #[test]
+    fn test_entities_json_joins_included_schema() {
+        let response: CatalogEntitiesResponse = serde_json::from_value(json!({
+            "data": [
+                {
+                    "id": "e1",
+                    "attributes": {"apiVersion": "v3", "kind": "queue", "name": "orders-events"},
+                    "relationships": {"schema": {"data": {"id": "s1", "type": "schema"}}}
+                },
+                {
+                    "id": "e2",
+                    "attributes": {"apiVersion": "v3", "kind": "service", "name": "legacy", "owner": "team-a"}
+                }
+            ],
+            "included": [{
+                "id": "s1",
+                "type": "schema",
+                "attributes": {"schema": {
+                    "apiVersion": "v3",
+                    "kind": "queue",
+                    "metadata": {"name": "orders-events"},
+                    "spec": {"type": "kafka", "lifecycle": "production"}
+                }}
+            }]
+        }))
+        .unwrap();
+
+        let entities = ServicesHandler::entities_json(&response);
+        assert_eq!(entities[0]["type"], "kafka");
+        assert_eq!(entities[0]["lifecycle"], "production");
+        assert_eq!(entities[1]["name"], "legacy");
+        assert_eq!(entities[1]["owner"], "team-a");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_kind() {
+        let client = Arc::new(
+            DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None).unwrap(),
+        );
+
+        let result = ServicesHandler::list(client, &json!({"kind": "lambda"})).await;
+        assert!(result.unwrap_err().to_string().contains("kind"));
+    }
+
     #[test]
     fn test_pagination_parameters() {
         let handler = ServicesHandler;
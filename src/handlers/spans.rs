@@ -1,23 +1,131 @@
 use serde_json::{Value, json};
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::datadog::DatadogClient;
-use crate::error::Result;
+use crate::datadog::DatadogApi;
+use crate::datadog::models::SpanEvent;
+use crate::error::{DatadogError, Result};
 use crate::handlers::common::{
     DEFAULT_STACK_TRACE_LINES, MAX_STRING_LENGTH, PaginationInfo, Paginator, ResponseFilter,
-    ResponseFormatter, TagFilter, TimeHandler, TimeParams,
+    ResponseFormatter, SPANS_RETENTION_DAYS, TagFilter, TagSelector, TimeHandler, TimeParams,
+    percentile,
 };
+use crate::handlers::traces::TracesHandler;
+
+/// Spans fetched per page for a summary
+const SUMMARY_PAGE_LIMIT: usize = 1000;
+
+/// Spans a summary aggregates by default, and at most
+const DEFAULT_SUMMARY_SPANS: usize = 1000;
+const MAX_SUMMARY_SPANS: usize = 5000;
+
+/// Groups a summary returns by default
+const DEFAULT_SUMMARY_GROUPS: usize = 20;
+
+/// Values accepted by the summary's `group_by`
+const SUMMARY_GROUP_BY: &[&str] = &["service", "resource"];
 
 pub struct SpansHandler;
 
-impl TimeHandler for SpansHandler {}
+/// Latency and errors of the spans sharing a service (and resource)
+#[derive(Debug, Default, PartialEq)]
+struct LatencyGroup {
+    service: String,
+    resource: Option<String>,
+    count: usize,
+    errors: usize,
+    durations_ms: Vec<f64>,
+}
+
+impl LatencyGroup {
+    fn total_ms(&self) -> f64 {
+        self.durations_ms.iter().sum()
+    }
+
+    fn to_json(&self, all_ms: f64) -> Value {
+        let round = |v: f64| (v * 1000.0).round() / 1000.0;
+        let mut sorted = self.durations_ms.clone();
+        sorted.sort_by(f64::total_cmp);
+        let total_ms = self.total_ms();
+
+        let mut group = json!({
+            "service": self.service,
+            "count": self.count,
+            "errors": self.errors,
+            "error_rate": round(self.errors as f64 / self.count.max(1) as f64),
+            "avg_ms": (!sorted.is_empty()).then(|| round(total_ms / sorted.len() as f64)),
+            "p95_ms": percentile(&sorted, 95.0).map(round),
+            "max_ms": sorted.last().copied().map(round),
+            "total_ms": round(total_ms),
+            "time_share": round(if all_ms > 0.0 { total_ms / all_ms } else { 0.0 }),
+        });
+        if let Some(resource) = &self.resource {
+            group["resource"] = json!(resource);
+        }
+        group
+    }
+}
+
+impl TimeHandler for SpansHandler {
+    fn retention_days(&self) -> Option<i64> {
+        Some(SPANS_RETENTION_DAYS)
+    }
+}
 impl Paginator for SpansHandler {}
 impl TagFilter for SpansHandler {}
 impl ResponseFilter for SpansHandler {}
 impl ResponseFormatter for SpansHandler {}
 
 impl SpansHandler {
-    pub async fn list(client: Arc<DatadogClient>, params: &Value) -> Result<Value> {
+    /// Default projection of a span: what it was, how long it took and whether it failed.
+    /// Tags are kept only when a tag filter asks for them; `verbose: true` returns everything.
+    fn compact(
+        span: &SpanEvent,
+        handler: &SpansHandler,
+        tag_filter: Option<&TagSelector>,
+    ) -> Value {
+        let Some(attrs) = &span.attributes else {
+            return json!({"id": span.id});
+        };
+
+        let mut compact = json!({
+            "span_id": attrs.span_id,
+            "trace_id": attrs.trace_id,
+            "service": attrs.service,
+            "resource": attrs.resource_name,
+            "start": attrs.start_timestamp,
+            "duration_ms": TracesHandler::duration_ms(attrs),
+            "status": attrs.status,
+        });
+
+        if let Some(error) = attrs.custom.as_ref().map(|c| &c["error"])
+            && error.is_object()
+        {
+            compact["error"] = ["type", "message"]
+                .iter()
+                .filter(|key| !error[**key].is_null())
+                .map(|key| (key.to_string(), error[*key].clone()))
+                .collect::<serde_json::Map<_, _>>()
+                .into();
+        } else if TracesHandler::is_error(attrs) {
+            compact["error"] = json!(true);
+        }
+
+        if let (Some(tag_filter), Some(tags)) = (tag_filter, &attrs.tags) {
+            let tags = handler.filter_tags(tags, tag_filter);
+            if !tags.is_empty() {
+                compact["tags"] = json!(tags);
+            }
+        }
+
+        // Nulls carry nothing
+        if let Some(fields) = compact.as_object_mut() {
+            fields.retain(|_, v| !v.is_null());
+        }
+        compact
+    }
+
+    pub async fn list(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
         let handler = SpansHandler;
 
         let query = params["query"].as_str().unwrap_or("*").to_string();
@@ -44,103 +152,119 @@ impl SpansHandler {
             .await?;
 
         // Get tag filter (same pattern as logs)
-        let tag_filter = params["tag_filter"]
+        let explicit_filter = params["tag_filter"]
             .as_str()
-            .or_else(|| client.get_tag_filter())
-            .unwrap_or("*");
-
-        // Process spans with filtering and optimization
-        let data = response["data"]
-            .as_array()
-            .unwrap_or(&vec![])
-            .iter()
-            .map(|span| {
-                let mut span_obj = span.as_object().unwrap().clone();
-
-                // Apply tag filtering and response optimization to attributes
-                if let Some(attrs) = span_obj.get_mut("attributes")
-                    && let Some(attrs_obj) = attrs.as_object_mut()
-                {
-                    // Apply tag filtering
-                    if let Some(tags) = attrs_obj.get("tags")
-                        && let Some(tags_arr) = tags.as_array()
+            .or_else(|| client.get_tag_filter_for("spans"));
+        let tag_filter = explicit_filter.map(TagSelector::parse).transpose()?;
+        let verbose = params["verbose"].as_bool().unwrap_or(false);
+
+        // Full payload, with filtering and truncation of the noisiest fields
+        let data = if verbose {
+            response
+                .data
+                .iter()
+                .map(|span| {
+                    let mut span_obj = match serde_json::to_value(span) {
+                        Ok(Value::Object(obj)) => obj,
+                        _ => serde_json::Map::new(),
+                    };
+
+                    // Apply tag filtering and response optimization to attributes
+                    if let Some(attrs) = span_obj.get_mut("attributes")
+                        && let Some(attrs_obj) = attrs.as_object_mut()
                     {
-                        let tag_strings: Vec<String> = tags_arr
-                            .iter()
-                            .filter_map(|t| t.as_str().map(String::from))
-                            .collect();
-
-                        let filtered_tags = handler.filter_tags(&tag_strings, tag_filter);
-
-                        // Remove empty tags arrays
-                        if filtered_tags.is_empty() {
-                            attrs_obj.remove("tags");
-                        } else {
-                            attrs_obj.insert(
-                                "tags".to_string(),
-                                Value::Array(
-                                    filtered_tags.into_iter().map(Value::String).collect(),
-                                ),
+                        // Apply tag filtering
+                        if let Some(tags) = attrs_obj.get("tags")
+                            && let Some(tags_arr) = tags.as_array()
+                        {
+                            let tag_strings: Vec<String> = tags_arr
+                                .iter()
+                                .filter_map(|t| t.as_str().map(String::from))
+                                .collect();
+
+                            let filtered_tags = handler.filter_tags(
+                                &tag_strings,
+                                tag_filter.as_ref().unwrap_or(&TagSelector::All),
                             );
-                        }
-                    }
 
-                    // Remove empty ingestion_reason
-                    if let Some(ingestion_reason) = attrs_obj.get("ingestion_reason")
-                        && ingestion_reason.as_str().unwrap_or("").is_empty()
-                    {
-                        attrs_obj.remove("ingestion_reason");
-                    }
-
-                    // Process custom object for filtering and truncation
-                    if let Some(custom) = attrs_obj.get_mut("custom")
-                        && let Some(custom_obj) = custom.as_object_mut()
-                    {
-                        // Remove http.useragent_details
-                        if let Some(http) = custom_obj.get_mut("http") {
-                            handler.filter_http_verbose_fields(http);
+                            // Remove empty tags arrays
+                            if filtered_tags.is_empty() {
+                                attrs_obj.remove("tags");
+                            } else {
+                                attrs_obj.insert(
+                                    "tags".to_string(),
+                                    Value::Array(
+                                        filtered_tags.into_iter().map(Value::String).collect(),
+                                    ),
+                                );
+                            }
                         }
 
-                        // Truncate stack traces in error objects
-                        if let Some(error) = custom_obj.get_mut("error")
-                            && let Some(error_obj) = error.as_object_mut()
-                            && let Some(stack) = error_obj.get_mut("stack")
-                            && let Some(stack_str) = stack.as_str()
-                            && handler.should_truncate_stack_trace(params)
+                        // Remove empty ingestion_reason
+                        if let Some(ingestion_reason) = attrs_obj.get("ingestion_reason")
+                            && ingestion_reason.as_str().unwrap_or("").is_empty()
                         {
-                            let truncated =
-                                handler.truncate_stack_trace(stack_str, DEFAULT_STACK_TRACE_LINES);
-                            *stack = Value::String(truncated);
+                            attrs_obj.remove("ingestion_reason");
                         }
 
-                        // Truncate long strings in kafka bootstrap servers
-                        if let Some(messaging) = custom_obj.get_mut("messaging")
-                            && let Some(messaging_obj) = messaging.as_object_mut()
-                            && let Some(kafka) = messaging_obj.get_mut("kafka")
-                            && let Some(kafka_obj) = kafka.as_object_mut()
-                            && let Some(bootstrap) = kafka_obj.get_mut("bootstrap")
-                            && let Some(bootstrap_obj) = bootstrap.as_object_mut()
-                            && let Some(servers) = bootstrap_obj.get_mut("servers")
-                            && let Some(servers_str) = servers.as_str()
+                        // Process custom object for filtering and truncation
+                        if let Some(custom) = attrs_obj.get_mut("custom")
+                            && let Some(custom_obj) = custom.as_object_mut()
                         {
-                            let truncated =
-                                handler.truncate_long_string(servers_str, MAX_STRING_LENGTH);
-                            *servers = Value::String(truncated);
+                            // Remove http.useragent_details
+                            if let Some(http) = custom_obj.get_mut("http") {
+                                handler.filter_http_verbose_fields(http);
+                            }
+
+                            // Truncate stack traces in error objects
+                            if let Some(error) = custom_obj.get_mut("error")
+                                && let Some(error_obj) = error.as_object_mut()
+                                && let Some(stack) = error_obj.get_mut("stack")
+                                && let Some(stack_str) = stack.as_str()
+                                && handler.should_truncate_stack_trace(params)
+                            {
+                                let truncated = handler
+                                    .truncate_stack_trace(stack_str, DEFAULT_STACK_TRACE_LINES);
+                                *stack = Value::String(truncated);
+                            }
+
+                            // Truncate long strings in kafka bootstrap servers
+                            if let Some(messaging) = custom_obj.get_mut("messaging")
+                                && let Some(messaging_obj) = messaging.as_object_mut()
+                                && let Some(kafka) = messaging_obj.get_mut("kafka")
+                                && let Some(kafka_obj) = kafka.as_object_mut()
+                                && let Some(bootstrap) = kafka_obj.get_mut("bootstrap")
+                                && let Some(bootstrap_obj) = bootstrap.as_object_mut()
+                                && let Some(servers) = bootstrap_obj.get_mut("servers")
+                                && let Some(servers_str) = servers.as_str()
+                            {
+                                let truncated =
+                                    handler.truncate_long_string(servers_str, MAX_STRING_LENGTH);
+                                *servers = Value::String(truncated);
+                            }
                         }
                     }
-                }
 
-                Value::Object(span_obj)
-            })
-            .collect::<Vec<_>>();
+                    Value::Object(span_obj)
+                })
+                .collect::<Vec<_>>()
+        } else {
+            response
+                .data
+                .iter()
+                .map(|span| Self::compact(span, &handler, tag_filter.as_ref()))
+                .collect()
+        };
+        let data = handler.project_fields(data, params);
 
         let spans_count = data.len();
 
         // Use PaginationInfo for consistent pagination structure
         let has_cursor = response
-            .get("meta")
-            .and_then(|m| m.get("page"))
-            .and_then(|p| p.get("after"))
+            .meta
+            .as_ref()
+            .and_then(|m| m.page.as_ref())
+            .and_then(|p| p.after.as_ref())
             .is_some();
 
         let pagination = PaginationInfo::from_cursor(spans_count, page_size, has_cursor);
@@ -150,6 +274,127 @@ impl SpansHandler {
             "pagination": pagination
         }))
     }
+
+    /// Group spans by service, or by service and resource, largest total time first
+    fn summarize(spans: &[SpanEvent], by_resource: bool) -> Vec<LatencyGroup> {
+        let mut groups: HashMap<(String, Option<String>), LatencyGroup> = HashMap::new();
+
+        for attrs in spans.iter().filter_map(|span| span.attributes.as_ref()) {
+            let service = attrs
+                .service
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string());
+            let resource = by_resource.then(|| attrs.resource_name.clone().unwrap_or_default());
+
+            let group = groups
+                .entry((service.clone(), resource.clone()))
+                .or_insert_with(|| LatencyGroup {
+                    service,
+                    resource,
+                    ..Default::default()
+                });
+            group.count += 1;
+            if TracesHandler::is_error(attrs) {
+                group.errors += 1;
+            }
+            if let Some(duration) = TracesHandler::duration_ms(attrs) {
+                group.durations_ms.push(duration);
+            }
+        }
+
+        let mut groups: Vec<LatencyGroup> = groups.into_values().collect();
+        groups.sort_by(|a, b| {
+            b.total_ms()
+                .total_cmp(&a.total_ms())
+                .then_with(|| b.count.cmp(&a.count))
+        });
+        groups
+    }
+
+    /// Latency breakdown of the spans matching a query: count, avg, p95, max and error rate
+    /// per service or resource, computed from the spans themselves (up to `max_spans`)
+    pub async fn summary(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = SpansHandler;
+
+        let query = params["query"].as_str().unwrap_or("*").to_string();
+        let group_by = params["group_by"].as_str().unwrap_or("resource");
+        if !SUMMARY_GROUP_BY.contains(&group_by) {
+            return Err(DatadogError::InvalidInput(format!(
+                "Invalid 'group_by' value '{}' (expected one of: {})",
+                group_by,
+                SUMMARY_GROUP_BY.join(", ")
+            )));
+        }
+        let max_spans = params["max_spans"]
+            .as_u64()
+            .map(|m| m as usize)
+            .unwrap_or(DEFAULT_SUMMARY_SPANS)
+            .clamp(1, MAX_SUMMARY_SPANS);
+        let limit = params["limit"]
+            .as_u64()
+            .map(|l| l as usize)
+            .unwrap_or(DEFAULT_SUMMARY_GROUPS)
+            .max(1);
+
+        let TimeParams::Timestamp { from, to } = handler.parse_time(params, 1)?;
+        let from = handler.timestamp_to_iso8601(from)?;
+        let to = handler.timestamp_to_iso8601(to)?;
+
+        let mut spans: Vec<SpanEvent> = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page_limit = (max_spans - spans.len()).min(SUMMARY_PAGE_LIMIT);
+            let response = client
+                .list_spans(
+                    &query,
+                    &from,
+                    &to,
+                    Some(page_limit as i32),
+                    cursor.take(),
+                    Some("-timestamp".to_string()),
+                )
+                .await?;
+
+            let received = response.data.len();
+            spans.extend(response.data);
+            cursor = response.meta.and_then(|m| m.page).and_then(|p| p.after);
+            if cursor.is_none() || received == 0 || spans.len() >= max_spans {
+                break;
+            }
+        }
+
+        let groups = Self::summarize(&spans, group_by == "resource");
+        let all_ms: f64 = groups.iter().map(LatencyGroup::total_ms).sum();
+        let total_groups = groups.len();
+
+        let mut meta = json!({
+            "query": query,
+            "from": from,
+            "to": to,
+            "group_by": group_by,
+            "spans_analyzed": spans.len(),
+            "total_groups": total_groups,
+        });
+        if cursor.is_some() {
+            meta["truncated"] = json!(true);
+            meta["note"] = json!(format!(
+                "Only the latest {} matching spans were analyzed; narrow the query or time range, raise max_spans, or use datadog_apm_resources_stats for exact per-resource figures",
+                spans.len()
+            ));
+        }
+
+        Ok(handler.format_list(
+            json!(
+                groups
+                    .iter()
+                    .take(limit)
+                    .map(|group| group.to_json(all_ms))
+                    .collect::<Vec<_>>()
+            ),
+            None,
+            Some(meta),
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -208,4 +453,147 @@ mod tests {
         let response = handler.format_list(data, Some(pagination), Some(meta));
         assert!(response.get("data").is_some());
     }
+
+    fn span() -> SpanEvent {
+        serde_json::from_value(json!({
+            "id": "AAAA",
+            "type": "spans",
+            "attributes": {
+                "span_id": 42,
+                "trace_id": "123",
+                "service": "checkout",
+                "resource_name": "POST /pay",
+                "start_timestamp": "2024-01-01T00:00:00.000Z",
+                "end_timestamp": "2024-01-01T00:00:00.120Z",
+                "status": "error",
+                "tags": ["env:prod", "team:payments", "version:1.2"],
+                "ingestion_reason": "rule",
+                "custom": {
+                    "duration": 120000000,
+                    "error": {"type": "Timeout", "message": "upstream timed out", "stack": "at pay()"},
+                    "http": {"useragent_details": {"browser": {"family": "curl"}}}
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_compact_projection() {
+        let compact = SpansHandler::compact(&span(), &SpansHandler, None);
+
+        assert_eq!(
+            compact,
+            json!({
+                "span_id": "42",
+                "trace_id": "123",
+                "service": "checkout",
+                "resource": "POST /pay",
+                "start": "2024-01-01T00:00:00.000Z",
+                "duration_ms": 120.0,
+                "status": "error",
+                "error": {"type": "Timeout", "message": "upstream timed out"}
+            })
+        );
+
+        let tag_filter = TagSelector::parse("env:").unwrap();
+        let compact = SpansHandler::compact(&span(), &SpansHandler, Some(&tag_filter));
+        assert_eq!(compact["tags"], json!(["env:prod"]));
+    }
+
+    #[test]
+    fn test_span_round_trips_for_verbose() {
+        let value = serde_json::to_value(span()).unwrap();
+
+        assert_eq!(value["type"], "spans");
+        assert_eq!(value["attributes"]["span_id"], "42");
+        // Fields without a typed counterpart are kept
+        assert_eq!(value["attributes"]["ingestion_reason"], "rule");
+        assert_eq!(value["attributes"]["custom"]["error"]["stack"], "at pay()");
+        assert!(value["attributes"].get("parent_id").is_none());
+    }
+
+    #[test]
+    fn test_summarize_groups_by_total_time() {
+        let span = |service: &str, resource: &str, duration_ns: u64, error: bool| {
+            let mut custom = json!({"duration": duration_ns});
+            if error {
+                custom["error"] = json!({"type": "Timeout"});
+            }
+            serde_json::from_value::<SpanEvent>(json!({
+                "attributes": {"service": service, "resource_name": resource, "custom": custom}
+            }))
+            .unwrap()
+        };
+        let spans = vec![
+            span("web", "GET /", 10_000_000, false),
+            span("web", "GET /", 30_000_000, true),
+            span("db", "SELECT", 100_000_000, false),
+            span("web", "POST /pay", 20_000_000, false),
+        ];
+
+        let groups = SpansHandler::summarize(&spans, true);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].service, "db");
+        assert_eq!(groups[1].resource.as_deref(), Some("GET /"));
+        assert_eq!(groups[1].count, 2);
+        assert_eq!(groups[1].errors, 1);
+
+        let all_ms = groups.iter().map(LatencyGroup::total_ms).sum();
+        let value = groups[1].to_json(all_ms);
+        assert_eq!(value["avg_ms"], 20.0);
+        assert_eq!(value["p95_ms"], 30.0);
+        assert_eq!(value["error_rate"], 0.5);
+        assert_eq!(value["time_share"], 0.25);
+
+        let by_service = SpansHandler::summarize(&spans, false);
+        assert_eq!(by_service.len(), 2);
+        assert_eq!(by_service[1].service, "web");
+        assert_eq!(by_service[1].count, 3);
+        assert!(by_service[1].resource.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_summary_pages_until_max_spans() {
+        use crate::datadog::DatadogClient;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/spans/events"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"attributes": {"service": "web", "resource_name": "GET /", "custom": {"duration": 2_000_000}}},
+                    {"attributes": {"service": "web", "resource_name": "GET /", "status": "error", "custom": {"duration": 4_000_000}}}
+                ],
+                "meta": {"page": {"after": "next"}}
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let client = DatadogClient::new("key".to_string(), "app".to_string(), None)
+            .unwrap()
+            .with_api_url(&mock_server.uri());
+        let result = SpansHandler::summary(
+            Arc::new(client),
+            &json!({"from": "1 hour ago", "to": "now", "max_spans": 4}),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result["data"][0]["count"], 4);
+        assert_eq!(result["data"][0]["errors"], 2);
+        assert_eq!(result["meta"]["spans_analyzed"], 4);
+        assert_eq!(result["meta"]["truncated"], true);
+
+        let error = SpansHandler::summary(
+            Arc::new(DatadogClient::new("key".to_string(), "app".to_string(), None).unwrap()),
+            &json!({"from": "1 hour ago", "to": "now", "group_by": "host"}),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("group_by"));
+    }
 }
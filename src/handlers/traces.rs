@@ -0,0 +1,390 @@
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::models::{SpanAttributes, SpanEvent};
+use crate::error::Result;
+use crate::handlers::common::{ResponseFormatter, SPANS_RETENTION_DAYS, TimeHandler, TimeParams};
+
+/// Spans fetched per page when reassembling a trace
+const TRACE_PAGE_LIMIT: i32 = 1000;
+
+/// Upper bound on pages fetched for a single trace
+const MAX_TRACE_PAGES: usize = 10;
+
+pub struct TracesHandler;
+
+impl TimeHandler for TracesHandler {
+    fn retention_days(&self) -> Option<i64> {
+        Some(SPANS_RETENTION_DAYS)
+    }
+}
+impl ResponseFormatter for TracesHandler {}
+
+/// Flattened view of a span used to rebuild the trace tree
+#[derive(Debug, Clone, PartialEq)]
+struct TraceSpan {
+    span_id: String,
+    parent_id: Option<String>,
+    service: Option<String>,
+    name: Option<String>,
+    resource: Option<String>,
+    start: Option<String>,
+    start_ns: i64,
+    duration_ms: Option<f64>,
+    error: bool,
+}
+
+impl TracesHandler {
+    fn id_string(value: &Value) -> Option<String> {
+        match value {
+            Value::String(s) if !s.is_empty() => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }
+    }
+
+    fn parse_nanos(timestamp: &str) -> Option<i64> {
+        chrono::DateTime::parse_from_rfc3339(timestamp)
+            .ok()
+            .and_then(|dt| dt.timestamp_nanos_opt())
+    }
+
+    /// Span duration in milliseconds: from start/end timestamps when both are present, else
+    /// from `custom.duration` (nanoseconds)
+    pub(crate) fn duration_ms(attrs: &SpanAttributes) -> Option<f64> {
+        let start_ns = attrs.start_timestamp.as_deref().and_then(Self::parse_nanos);
+        let end_ns = attrs.end_timestamp.as_deref().and_then(Self::parse_nanos);
+        match (start_ns, end_ns) {
+            (Some(s), Some(e)) if e >= s => Some((e - s) as f64 / 1_000_000.0),
+            _ => attrs
+                .custom
+                .as_ref()
+                .and_then(|c| c["duration"].as_f64())
+                .map(|ns| ns / 1_000_000.0),
+        }
+    }
+
+    /// Whether the span carries an error object or an error status
+    pub(crate) fn is_error(attrs: &SpanAttributes) -> bool {
+        attrs
+            .custom
+            .as_ref()
+            .and_then(|c| c.get("error"))
+            .is_some_and(|e| !e.is_null())
+            || attrs.status.as_deref() == Some("error")
+    }
+
+    // Extract the fields needed for the tree from a v2 span event
+    fn parse_span(span: &SpanEvent) -> Option<TraceSpan> {
+        let attrs = span.attributes.as_ref()?;
+
+        let span_id = attrs.span_id.clone()?;
+        let parent_id = attrs.parent_id.clone().filter(|id| id != "0");
+
+        let start = attrs.start_timestamp.clone();
+        let start_ns = start.as_deref().and_then(Self::parse_nanos);
+
+        let name = attrs.operation_name.clone().or_else(|| {
+            attrs
+                .custom
+                .as_ref()
+                .and_then(|c| c["operation_name"].as_str())
+                .map(String::from)
+        });
+
+        Some(TraceSpan {
+            span_id,
+            parent_id,
+            service: attrs.service.clone(),
+            name,
+            resource: attrs.resource_name.clone(),
+            start,
+            start_ns: start_ns.unwrap_or(0),
+            duration_ms: Self::duration_ms(attrs),
+            error: Self::is_error(attrs),
+        })
+    }
+
+    fn span_node(
+        span: &TraceSpan,
+        children_of: &HashMap<&str, Vec<&TraceSpan>>,
+        depth: usize,
+    ) -> Value {
+        let mut node = json!({
+            "span_id": span.span_id,
+            "depth": depth,
+        });
+
+        if let Some(service) = &span.service {
+            node["service"] = json!(service);
+        }
+        if let Some(name) = &span.name {
+            node["name"] = json!(name);
+        }
+        if let Some(resource) = &span.resource {
+            node["resource"] = json!(resource);
+        }
+        if let Some(start) = &span.start {
+            node["start"] = json!(start);
+        }
+        if let Some(duration) = span.duration_ms {
+            node["duration_ms"] = json!(duration);
+        }
+        if span.error {
+            node["error"] = json!(true);
+        }
+
+        if let Some(children) = children_of.get(span.span_id.as_str()) {
+            node["children"] = json!(
+                children
+                    .iter()
+                    .map(|child| Self::span_node(child, children_of, depth + 1))
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        node
+    }
+
+    /// Rebuild the parent/child hierarchy; spans whose parent is missing become roots
+    fn build_span_tree(spans: &[TraceSpan]) -> Vec<Value> {
+        let known: std::collections::HashSet<&str> =
+            spans.iter().map(|s| s.span_id.as_str()).collect();
+
+        let mut ordered: Vec<&TraceSpan> = spans.iter().collect();
+        ordered.sort_by_key(|s| s.start_ns);
+
+        let mut roots = Vec::new();
+        let mut children_of: HashMap<&str, Vec<&TraceSpan>> = HashMap::new();
+
+        for span in ordered {
+            match span.parent_id.as_deref() {
+                Some(parent) if known.contains(parent) && parent != span.span_id => {
+                    children_of.entry(parent).or_default().push(span)
+                }
+                _ => roots.push(span),
+            }
+        }
+
+        roots
+            .iter()
+            .map(|root| Self::span_node(root, &children_of, 0))
+            .collect()
+    }
+
+    pub async fn get(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = TracesHandler;
+
+        let trace_id = Self::id_string(&params["trace_id"]).ok_or_else(|| {
+            crate::error::DatadogError::InvalidInput("Missing 'trace_id' parameter".to_string())
+        })?;
+
+        let time = handler.parse_time(params, 2)?;
+        let TimeParams::Timestamp { from, to } = time;
+        let from = handler.timestamp_to_iso8601(from)?;
+        let to = handler.timestamp_to_iso8601(to)?;
+
+        let query = format!("trace_id:{}", trace_id);
+
+        let mut spans = Vec::new();
+        let mut cursor: Option<String> = None;
+        let mut truncated = false;
+
+        for page in 0..MAX_TRACE_PAGES {
+            let response = client
+                .list_spans(
+                    &query,
+                    &from,
+                    &to,
+                    Some(TRACE_PAGE_LIMIT),
+                    cursor.take(),
+                    Some("timestamp".to_string()),
+                )
+                .await?;
+
+            spans.extend(response.data.iter().filter_map(Self::parse_span));
+
+            cursor = response.meta.and_then(|m| m.page).and_then(|p| p.after);
+            if cursor.is_none() {
+                break;
+            }
+            truncated = page + 1 == MAX_TRACE_PAGES;
+        }
+
+        let roots = Self::build_span_tree(&spans);
+
+        let trace_start = spans
+            .iter()
+            .filter(|s| s.start.is_some())
+            .map(|s| s.start_ns)
+            .min();
+        let trace_end = spans
+            .iter()
+            .filter(|s| s.start.is_some())
+            .filter_map(|s| s.duration_ms.map(|d| s.start_ns + (d * 1_000_000.0) as i64))
+            .max();
+
+        let mut services: Vec<&str> = spans.iter().filter_map(|s| s.service.as_deref()).collect();
+        services.sort_unstable();
+        services.dedup();
+
+        let mut meta = json!({
+            "trace_id": trace_id,
+            "span_count": spans.len(),
+            "error_count": spans.iter().filter(|s| s.error).count(),
+            "services": services,
+        });
+
+        if let (Some(start), Some(end)) = (trace_start, trace_end) {
+            meta["duration_ms"] = json!((end - start) as f64 / 1_000_000.0);
+        }
+        if truncated {
+            meta["truncated"] = json!(true);
+        }
+
+        Ok(handler.format_list(json!(roots), None, Some(meta)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+    use serde_json::json;
+
+    fn event(raw: Value) -> SpanEvent {
+        serde_json::from_value(raw).unwrap()
+    }
+
+    fn raw_span(span_id: &str, parent_id: &str, start: &str, end: &str) -> SpanEvent {
+        event(json!({
+            "id": span_id,
+            "attributes": {
+                "span_id": span_id,
+                "parent_id": parent_id,
+                "trace_id": "123",
+                "service": "web",
+                "resource_name": "GET /",
+                "start_timestamp": start,
+                "end_timestamp": end,
+                "custom": {}
+            }
+        }))
+    }
+
+    #[test]
+    fn test_parse_span_duration_from_timestamps() {
+        let span = TracesHandler::parse_span(&raw_span(
+            "1",
+            "0",
+            "2024-01-01T00:00:00.000Z",
+            "2024-01-01T00:00:00.250Z",
+        ))
+        .unwrap();
+
+        assert_eq!(span.span_id, "1");
+        assert_eq!(span.parent_id, None);
+        assert_eq!(span.duration_ms, Some(250.0));
+        assert!(!span.error);
+    }
+
+    #[test]
+    fn test_parse_span_duration_from_custom() {
+        // Numeric IDs are read as strings
+        let raw = event(json!({
+            "attributes": {
+                "span_id": 7,
+                "custom": {"duration": 5_000_000.0, "error": {"message": "boom"}}
+            }
+        }));
+
+        let span = TracesHandler::parse_span(&raw).unwrap();
+        assert_eq!(span.span_id, "7");
+        assert_eq!(span.duration_ms, Some(5.0));
+        assert!(span.error);
+    }
+
+    #[test]
+    fn test_parse_span_without_id() {
+        let raw = event(json!({"attributes": {"service": "web"}}));
+        assert!(TracesHandler::parse_span(&raw).is_none());
+    }
+
+    #[test]
+    fn test_build_span_tree_hierarchy() {
+        let spans: Vec<TraceSpan> = [
+            raw_span("1", "0", "2024-01-01T00:00:00Z", "2024-01-01T00:00:01Z"),
+            raw_span(
+                "3",
+                "1",
+                "2024-01-01T00:00:00.500Z",
+                "2024-01-01T00:00:00.600Z",
+            ),
+            raw_span(
+                "2",
+                "1",
+                "2024-01-01T00:00:00.100Z",
+                "2024-01-01T00:00:00.200Z",
+            ),
+            raw_span(
+                "4",
+                "2",
+                "2024-01-01T00:00:00.110Z",
+                "2024-01-01T00:00:00.120Z",
+            ),
+        ]
+        .iter()
+        .filter_map(TracesHandler::parse_span)
+        .collect();
+
+        let roots = TracesHandler::build_span_tree(&spans);
+        assert_eq!(roots.len(), 1);
+
+        let root = &roots[0];
+        assert_eq!(root["span_id"], "1");
+        assert_eq!(root["depth"], 0);
+
+        // Children are ordered by start time
+        let children = root["children"].as_array().unwrap();
+        assert_eq!(children[0]["span_id"], "2");
+        assert_eq!(children[1]["span_id"], "3");
+        assert_eq!(children[0]["children"][0]["span_id"], "4");
+        assert_eq!(children[0]["children"][0]["depth"], 2);
+    }
+
+    #[test]
+    fn test_build_span_tree_orphans_become_roots() {
+        let spans: Vec<TraceSpan> = [
+            raw_span("1", "0", "2024-01-01T00:00:00Z", "2024-01-01T00:00:01Z"),
+            raw_span(
+                "5",
+                "missing",
+                "2024-01-01T00:00:02Z",
+                "2024-01-01T00:00:03Z",
+            ),
+        ]
+        .iter()
+        .filter_map(TracesHandler::parse_span)
+        .collect();
+
+        let roots = TracesHandler::build_span_tree(&spans);
+        assert_eq!(roots.len(), 2);
+        assert!(roots[1].get("children").is_none());
+    }
+
+    #[test]
+    fn test_missing_trace_id_parameter() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let client = Arc::new(
+                DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None)
+                    .unwrap(),
+            );
+
+            let result = TracesHandler::get(client, &json!({})).await;
+            assert!(result.is_err());
+        });
+    }
+}
@@ -0,0 +1,691 @@
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use futures::FutureExt;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+
+use crate::datadog::DatadogApi;
+use crate::datadog::batch::{ApiCall, execute_batch};
+use crate::datadog::models::{CostByOrg, HourlyUsageAttribution, UsageSummaryDate};
+use crate::error::{DatadogError, Result};
+use crate::handlers::common::ResponseFormatter;
+
+/// Products checked by the spike detector and their hourly attribution usage types
+const SPIKE_PRODUCTS: &[(&str, &str)] = &[
+    ("logs", "estimated_indexed_logs_usage"),
+    ("custom_metrics", "custom_timeseries_usage"),
+    ("spans", "estimated_indexed_spans_usage"),
+];
+
+/// Hard stop for next_record_id pagination of a single usage type
+const MAX_ATTRIBUTION_PAGES: usize = 20;
+
+const DEFAULT_BASELINE_DAYS: i64 = 7;
+const MAX_BASELINE_DAYS: i64 = 30;
+const DEFAULT_SPIKE_THRESHOLD: f64 = 1.5;
+const DEFAULT_TOP_CONTRIBUTORS: usize = 5;
+
+/// Months covered when no start_month is given (current month plus the two before it)
+const DEFAULT_SUMMARY_MONTHS: u32 = 3;
+
+/// Usage summary field prefixes and the product family they bill under; first match wins
+const PRODUCT_FAMILIES: &[(&str, &str)] = &[
+    ("infra_host", "infrastructure"),
+    ("agent_host", "infrastructure"),
+    ("aws_host", "infrastructure"),
+    ("azure_", "infrastructure"),
+    ("gcp_host", "infrastructure"),
+    ("container", "containers"),
+    ("fargate", "containers"),
+    ("apm", "apm"),
+    ("trace_search", "apm"),
+    ("ingested_events", "apm"),
+    ("indexed_events", "apm"),
+    ("profiling", "profiling"),
+    ("logs", "logs"),
+    ("online_archive", "logs"),
+    ("custom_", "custom_metrics"),
+    ("synthetics", "synthetics"),
+    ("browser_rum", "rum"),
+    ("rum", "rum"),
+    ("mobile_rum", "rum"),
+    ("npm", "network"),
+    ("dbm", "database_monitoring"),
+    ("aws_lambda", "serverless"),
+    ("serverless", "serverless"),
+    ("cspm", "security"),
+    ("cws", "security"),
+    ("siem", "security"),
+    ("sds", "security"),
+    ("ci_", "ci_visibility"),
+    ("incident_management", "incident_management"),
+    ("iot", "iot"),
+];
+
+const HOUR_SECS: i64 = 3600;
+const DAY_SECS: i64 = 86400;
+
+pub struct UsageHandler;
+
+impl ResponseFormatter for UsageHandler {}
+
+/// Accumulated usage for one comparison window
+#[derive(Default)]
+struct UsageWindow {
+    total: f64,
+    hours: BTreeSet<i64>,
+    by_tags: HashMap<String, f64>,
+}
+
+impl UsageWindow {
+    fn add(&mut self, hour: i64, tags: String, value: f64) {
+        self.total += value;
+        self.hours.insert(hour);
+        *self.by_tags.entry(tags).or_insert(0.0) += value;
+    }
+
+    /// Hourly average over the hours that reported data (attribution lags by up to a day)
+    fn hourly_avg(&self, value: f64) -> f64 {
+        value / self.hours.len().max(1) as f64
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+impl UsageHandler {
+    fn format_hour(ts: i64) -> String {
+        DateTime::from_timestamp(ts, 0)
+            .map(|dt| dt.format("%Y-%m-%dT%H").to_string())
+            .unwrap_or_default()
+    }
+
+    fn parse_hour(hour: &str) -> Option<i64> {
+        DateTime::parse_from_rfc3339(hour)
+            .map(|dt| dt.timestamp())
+            .ok()
+    }
+
+    /// Stable "key:value" label for an attribution tag set
+    fn tags_label(record: &HourlyUsageAttribution) -> String {
+        let mut pairs: Vec<String> = record
+            .tags
+            .iter()
+            .flatten()
+            .flat_map(|(key, values)| {
+                values
+                    .iter()
+                    .flatten()
+                    .map(move |value| format!("{}:{}", key, value))
+            })
+            .collect();
+
+        if pairs.is_empty() {
+            return "untagged".to_string();
+        }
+
+        pairs.sort();
+        pairs.join(",")
+    }
+
+    fn selected_products(params: &Value) -> Result<Vec<(&'static str, &'static str)>> {
+        let Some(requested) = params["products"].as_array() else {
+            return Ok(SPIKE_PRODUCTS.to_vec());
+        };
+
+        requested
+            .iter()
+            .filter_map(|p| p.as_str())
+            .map(|name| {
+                SPIKE_PRODUCTS
+                    .iter()
+                    .find(|(product, _)| *product == name)
+                    .copied()
+                    .ok_or_else(|| {
+                        DatadogError::InvalidInput(format!(
+                            "Unknown product '{}' (expected one of: logs, custom_metrics, spans)",
+                            name
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    /// Validate a YYYY-MM month parameter
+    fn parse_month(params: &Value, name: &str) -> Result<Option<String>> {
+        let Some(month) = params[name].as_str().filter(|m| !m.is_empty()) else {
+            return Ok(None);
+        };
+
+        NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").map_err(|_| {
+            DatadogError::InvalidInput(format!("Invalid '{}' '{}': expected YYYY-MM", name, month))
+        })?;
+
+        Ok(Some(month.to_string()))
+    }
+
+    /// YYYY-MM for the month `months_back` months before `today`
+    fn month_before(today: NaiveDate, months_back: u32) -> String {
+        let index = today.year() * 12 + today.month0() as i32 - months_back as i32;
+        format!(
+            "{:04}-{:02}",
+            index.div_euclid(12),
+            index.rem_euclid(12) + 1
+        )
+    }
+
+    fn month_range(params: &Value) -> Result<(String, Option<String>)> {
+        let start = Self::parse_month(params, "start_month")?.unwrap_or_else(|| {
+            Self::month_before(Utc::now().date_naive(), DEFAULT_SUMMARY_MONTHS - 1)
+        });
+        let end = Self::parse_month(params, "end_month")?;
+
+        if end.as_ref().is_some_and(|end| *end < start) {
+            return Err(DatadogError::InvalidInput(
+                "'end_month' must not be before 'start_month'".to_string(),
+            ));
+        }
+
+        Ok((start, end))
+    }
+
+    fn product_family(field: &str) -> &'static str {
+        PRODUCT_FAMILIES
+            .iter()
+            .find(|(prefix, _)| field.starts_with(prefix))
+            .map(|(_, family)| *family)
+            .unwrap_or("other")
+    }
+
+    /// Group one month's non-zero usage fields by product family
+    fn summarize_month(usage: &UsageSummaryDate, family: Option<&str>) -> Value {
+        let mut families: BTreeMap<&str, BTreeMap<&str, &Value>> = BTreeMap::new();
+        for (field, value) in &usage.fields {
+            if value.as_f64().is_none_or(|v| v == 0.0) {
+                continue;
+            }
+            let product = Self::product_family(field);
+            if family.is_some_and(|f| f != product) {
+                continue;
+            }
+            families.entry(product).or_default().insert(field, value);
+        }
+
+        json!({
+            "month": usage.date.as_deref().map(|d| d.chars().take(7).collect::<String>()),
+            "families": families,
+        })
+    }
+
+    /// Per-month product costs, preferring Datadog's own "total" charge over summing parts
+    fn summarize_costs(records: &[CostByOrg], product: Option<&str>) -> (Vec<Value>, Vec<Value>) {
+        let mut months: BTreeMap<String, BTreeMap<String, BTreeMap<String, f64>>> = BTreeMap::new();
+
+        for attributes in records.iter().filter_map(|r| r.attributes.as_ref()) {
+            let month: String = attributes
+                .date
+                .as_deref()
+                .unwrap_or("")
+                .chars()
+                .take(7)
+                .collect();
+            let products = months.entry(month).or_default();
+            for charge in attributes.charges.iter().flatten() {
+                let (Some(name), Some(cost)) = (charge.product_name.as_deref(), charge.cost) else {
+                    continue;
+                };
+                if product.is_some_and(|p| !name.contains(p)) {
+                    continue;
+                }
+                let charge_type = charge
+                    .charge_type
+                    .clone()
+                    .unwrap_or_else(|| "total".to_string());
+                *products
+                    .entry(name.to_string())
+                    .or_default()
+                    .entry(charge_type)
+                    .or_insert(0.0) += cost;
+            }
+        }
+
+        let mut totals: HashMap<String, f64> = HashMap::new();
+        let monthly = months
+            .into_iter()
+            .map(|(month, products)| {
+                let mut rows: Vec<(String, f64, BTreeMap<String, f64>)> = products
+                    .into_iter()
+                    .map(|(name, mut charges)| {
+                        let cost = charges
+                            .remove("total")
+                            .unwrap_or_else(|| charges.values().sum());
+                        (name, cost, charges)
+                    })
+                    .collect();
+                rows.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+                let month_total: f64 = rows.iter().map(|(_, cost, _)| cost).sum();
+                for (name, cost, _) in &rows {
+                    *totals.entry(name.clone()).or_insert(0.0) += cost;
+                }
+
+                json!({
+                    "month": month,
+                    "total_cost": round2(month_total),
+                    "products": rows
+                        .into_iter()
+                        .map(|(name, cost, charges)| json!({
+                            "product": name,
+                            "cost": round2(cost),
+                            "charges": charges
+                                .into_iter()
+                                .map(|(k, v)| (k, json!(round2(v))))
+                                .collect::<serde_json::Map<_, _>>(),
+                        }))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+
+        let mut totals: Vec<(String, f64)> = totals.into_iter().collect();
+        totals.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let totals = totals
+            .into_iter()
+            .map(|(name, cost)| json!({ "product": name, "cost": round2(cost) }))
+            .collect();
+
+        (monthly, totals)
+    }
+
+    /// Compare the recent window against the trailing baseline for one usage type
+    fn detect_spike(
+        records: &[HourlyUsageAttribution],
+        recent_start: i64,
+        threshold: f64,
+        top_n: usize,
+    ) -> Value {
+        let mut recent = UsageWindow::default();
+        let mut baseline = UsageWindow::default();
+
+        for record in records {
+            let (Some(hour), Some(value)) = (
+                record.hour.as_deref().and_then(Self::parse_hour),
+                record.total_usage_sum,
+            ) else {
+                continue;
+            };
+
+            let window = if hour >= recent_start {
+                &mut recent
+            } else {
+                &mut baseline
+            };
+            window.add(hour, Self::tags_label(record), value);
+        }
+
+        let recent_avg = recent.hourly_avg(recent.total);
+        let baseline_avg = baseline.hourly_avg(baseline.total);
+        let ratio = (baseline_avg > 0.0).then(|| recent_avg / baseline_avg);
+        let spike = match ratio {
+            Some(ratio) => ratio >= threshold,
+            None => recent_avg > 0.0,
+        };
+
+        let mut result = json!({
+            "recent_hourly_avg": round2(recent_avg),
+            "baseline_hourly_avg": round2(baseline_avg),
+            "ratio": ratio.map(round2),
+            "spike": spike,
+            "hours_with_data": {
+                "recent": recent.hours.len(),
+                "baseline": baseline.hours.len()
+            }
+        });
+
+        if spike {
+            let mut contributors: Vec<(String, f64, f64)> = recent
+                .by_tags
+                .iter()
+                .map(|(tags, total)| {
+                    let before =
+                        baseline.hourly_avg(baseline.by_tags.get(tags).copied().unwrap_or(0.0));
+                    (tags.clone(), recent.hourly_avg(*total), before)
+                })
+                .filter(|(_, now, before)| now > before)
+                .collect();
+
+            contributors.sort_by(|a, b| (b.1 - b.2).total_cmp(&(a.1 - a.2)));
+            contributors.truncate(top_n);
+
+            result["top_contributors"] = json!(
+                contributors
+                    .into_iter()
+                    .map(|(tags, now, before)| json!({
+                        "tags": tags,
+                        "recent_hourly_avg": round2(now),
+                        "baseline_hourly_avg": round2(before),
+                        "increase": round2(now - before)
+                    }))
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        result
+    }
+
+    async fn fetch_attribution(
+        client: &dyn DatadogApi,
+        start_hr: &str,
+        end_hr: &str,
+        usage_type: &str,
+    ) -> Result<Vec<HourlyUsageAttribution>> {
+        let mut records = Vec::new();
+        let mut next_record_id = None;
+
+        for _ in 0..MAX_ATTRIBUTION_PAGES {
+            let response = client
+                .get_hourly_usage_attribution(start_hr, end_hr, usage_type, next_record_id)
+                .await?;
+
+            records.extend(response.usage.unwrap_or_default());
+
+            next_record_id = response
+                .metadata
+                .and_then(|m| m.pagination)
+                .and_then(|p| p.next_record_id)
+                .filter(|id| !id.is_empty());
+
+            if next_record_id.is_none() {
+                break;
+            }
+        }
+
+        Ok(records)
+    }
+
+    pub async fn spike_check(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = UsageHandler;
+
+        let products = Self::selected_products(params)?;
+        let baseline_days = params["baseline_days"]
+            .as_i64()
+            .unwrap_or(DEFAULT_BASELINE_DAYS)
+            .clamp(1, MAX_BASELINE_DAYS);
+        let threshold = params["threshold"]
+            .as_f64()
+            .unwrap_or(DEFAULT_SPIKE_THRESHOLD);
+        if threshold <= 1.0 {
+            return Err(DatadogError::InvalidInput(
+                "'threshold' must be greater than 1.0".to_string(),
+            ));
+        }
+        let top_n = params["top_n"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_TOP_CONTRIBUTORS);
+
+        let now = Utc::now().timestamp();
+        let end = now - now % HOUR_SECS;
+        let recent_start = end - DAY_SECS;
+        let baseline_start = recent_start - baseline_days * DAY_SECS;
+
+        let start_hr = Self::format_hour(baseline_start);
+        let end_hr = Self::format_hour(end);
+
+        let calls: Vec<ApiCall<'_, _>> = products
+            .iter()
+            .map(|(_, usage_type)| {
+                Self::fetch_attribution(client.as_ref(), &start_hr, &end_hr, usage_type).boxed()
+            })
+            .collect();
+        let responses = execute_batch(calls, client.max_concurrency()).await;
+
+        let mut data = serde_json::Map::new();
+        let mut spikes = Vec::new();
+        for ((product, usage_type), records) in products.iter().zip(responses) {
+            let mut entry = match records {
+                Ok(records) => Self::detect_spike(&records, recent_start, threshold, top_n),
+                Err(e) => json!({ "error": e.to_string() }),
+            };
+            if entry["spike"] == json!(true) {
+                spikes.push(*product);
+            }
+            entry["usage_type"] = json!(usage_type);
+            data.insert(product.to_string(), entry);
+        }
+
+        let meta = json!({
+            "spikes": spikes,
+            "threshold": threshold,
+            "recent_window": {
+                "from": crate::utils::format_timestamp(recent_start),
+                "to": crate::utils::format_timestamp(end)
+            },
+            "baseline_window": {
+                "from": crate::utils::format_timestamp(baseline_start),
+                "to": crate::utils::format_timestamp(recent_start)
+            },
+            "note": "Usage attribution can lag by up to 24-72 hours; averages only count hours that reported data"
+        });
+
+        Ok(handler.format_list(json!(data), None, Some(meta)))
+    }
+
+    pub async fn summary(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = UsageHandler;
+
+        let (start_month, end_month) = Self::month_range(params)?;
+        let family = params["family"].as_str().filter(|f| !f.is_empty());
+
+        let response = client
+            .get_usage_summary(&start_month, end_month.as_deref())
+            .await?;
+
+        let months: Vec<Value> = response
+            .usage
+            .iter()
+            .flatten()
+            .map(|usage| Self::summarize_month(usage, family))
+            .collect();
+
+        let meta = json!({
+            "start_month": start_month,
+            "end_month": end_month,
+            "family": family,
+            "note": "Values are billable usage quantities (hosts, events, bytes), not costs; use datadog_usage_by_product for cost"
+        });
+
+        Ok(handler.format_list(json!(months), None, Some(meta)))
+    }
+
+    pub async fn by_product(client: Arc<dyn DatadogApi>, params: &Value) -> Result<Value> {
+        let handler = UsageHandler;
+
+        let (start_month, end_month) = Self::month_range(params)?;
+        let product = params["product"].as_str().filter(|p| !p.is_empty());
+        let cost_kind = match params["cost_type"].as_str().unwrap_or("estimated") {
+            "estimated" => "estimated_cost",
+            "historical" => "historical_cost",
+            other => {
+                return Err(DatadogError::InvalidInput(format!(
+                    "Invalid cost_type '{}' (expected estimated or historical)",
+                    other
+                )));
+            }
+        };
+
+        let response = client
+            .get_usage_cost(cost_kind, &start_month, end_month.as_deref())
+            .await?;
+
+        let (months, totals) = Self::summarize_costs(&response.data.unwrap_or_default(), product);
+
+        let meta = json!({
+            "cost_type": cost_kind,
+            "start_month": start_month,
+            "end_month": end_month,
+            "product": product,
+            "currency": "USD",
+            "note": "Estimated cost covers the current and previous month; historical cost covers closed months"
+        });
+
+        Ok(handler.format_list(
+            json!({ "months": months, "totals": totals }),
+            None,
+            Some(meta),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECENT_START: i64 = 1_700_006_400; // 2023-11-15T00:00:00Z
+
+    fn record(hour_offset: i64, service: Option<&str>, value: f64) -> HourlyUsageAttribution {
+        let hour = DateTime::from_timestamp(RECENT_START + hour_offset * HOUR_SECS, 0)
+            .unwrap()
+            .to_rfc3339();
+        serde_json::from_value(json!({
+            "hour": hour,
+            "tags": service.map(|s| json!({"service": [s]})),
+            "total_usage_sum": value,
+            "usage_type": "estimated_indexed_logs_usage"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_format_hour() {
+        assert_eq!(
+            UsageHandler::format_hour(RECENT_START + 5400),
+            "2023-11-15T01"
+        );
+    }
+
+    #[test]
+    fn test_tags_label() {
+        assert_eq!(
+            UsageHandler::tags_label(&record(0, Some("web"), 1.0)),
+            "service:web"
+        );
+        assert_eq!(UsageHandler::tags_label(&record(0, None, 1.0)), "untagged");
+    }
+
+    #[test]
+    fn test_selected_products() {
+        assert_eq!(
+            UsageHandler::selected_products(&json!({})).unwrap().len(),
+            3
+        );
+
+        let products = UsageHandler::selected_products(&json!({"products": ["spans"]})).unwrap();
+        assert_eq!(products, vec![("spans", "estimated_indexed_spans_usage")]);
+
+        assert!(UsageHandler::selected_products(&json!({"products": ["apm_hosts"]})).is_err());
+    }
+
+    #[test]
+    fn test_detect_spike_with_contributors() {
+        let records = vec![
+            record(-48, Some("web"), 100.0),
+            record(-47, Some("api"), 100.0),
+            record(1, Some("web"), 100.0),
+            record(1, Some("api"), 400.0),
+        ];
+
+        let result = UsageHandler::detect_spike(&records, RECENT_START, 1.5, 5);
+        assert_eq!(result["baseline_hourly_avg"], 100.0);
+        assert_eq!(result["recent_hourly_avg"], 500.0);
+        assert_eq!(result["ratio"], 5.0);
+        assert_eq!(result["spike"], true);
+        assert_eq!(result["top_contributors"][0]["tags"], "service:api");
+        assert_eq!(result["top_contributors"][0]["increase"], 350.0);
+    }
+
+    #[test]
+    fn test_detect_spike_steady_usage() {
+        let records = vec![
+            record(-30, Some("web"), 100.0),
+            record(2, Some("web"), 110.0),
+        ];
+
+        let result = UsageHandler::detect_spike(&records, RECENT_START, 1.5, 5);
+        assert_eq!(result["spike"], false);
+        assert!(result.get("top_contributors").is_none());
+    }
+
+    #[test]
+    fn test_month_range() {
+        let today = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
+        assert_eq!(UsageHandler::month_before(today, 0), "2026-02");
+        assert_eq!(UsageHandler::month_before(today, 2), "2025-12");
+
+        let (start, end) =
+            UsageHandler::month_range(&json!({"start_month": "2026-01", "end_month": "2026-03"}))
+                .unwrap();
+        assert_eq!(start, "2026-01");
+        assert_eq!(end.as_deref(), Some("2026-03"));
+
+        assert!(UsageHandler::month_range(&json!({"start_month": "2026-13"})).is_err());
+        assert!(
+            UsageHandler::month_range(&json!({"start_month": "2026-03", "end_month": "2026-01"}))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_summarize_month() {
+        let usage: UsageSummaryDate = serde_json::from_value(json!({
+            "date": "2026-09-01T00:00:00+00:00",
+            "infra_host_top99p": 120,
+            "apm_host_top99p": 40,
+            "logs_indexed_logs_usage_sum": 5_000_000,
+            "custom_ts_avg": 0,
+            "orgs": []
+        }))
+        .unwrap();
+
+        let month = UsageHandler::summarize_month(&usage, None);
+        assert_eq!(month["month"], "2026-09");
+        assert_eq!(
+            month["families"]["infrastructure"]["infra_host_top99p"],
+            120
+        );
+        assert_eq!(month["families"]["apm"]["apm_host_top99p"], 40);
+        assert!(month["families"].get("custom_metrics").is_none());
+
+        let month = UsageHandler::summarize_month(&usage, Some("logs"));
+        assert_eq!(month["families"].as_object().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_summarize_costs() {
+        let records: Vec<CostByOrg> = serde_json::from_value(json!([
+            {"attributes": {"date": "2026-08-01T00:00:00+00:00", "charges": [
+                {"product_name": "logs_indexed_15day", "charge_type": "committed", "cost": 100.0},
+                {"product_name": "logs_indexed_15day", "charge_type": "on_demand", "cost": 50.0},
+                {"product_name": "logs_indexed_15day", "charge_type": "total", "cost": 150.0},
+                {"product_name": "infra_host", "charge_type": "committed", "cost": 300.0}
+            ]}},
+            {"attributes": {"date": "2026-09-01T00:00:00+00:00", "charges": [
+                {"product_name": "logs_indexed_15day", "charge_type": "total", "cost": 250.0}
+            ]}}
+        ]))
+        .unwrap();
+
+        let (months, totals) = UsageHandler::summarize_costs(&records, None);
+        assert_eq!(months[0]["month"], "2026-08");
+        assert_eq!(months[0]["total_cost"], 450.0);
+        assert_eq!(months[0]["products"][0]["product"], "infra_host");
+        assert_eq!(months[0]["products"][1]["cost"], 150.0);
+        assert_eq!(months[0]["products"][1]["charges"]["on_demand"], 50.0);
+        assert_eq!(totals[0]["product"], "logs_indexed_15day");
+        assert_eq!(totals[0]["cost"], 400.0);
+
+        let (_, totals) = UsageHandler::summarize_costs(&records, Some("infra"));
+        assert_eq!(totals.len(), 1);
+    }
+}
@@ -1,13 +1,19 @@
 // Library interface for MCP Datadog Server
 // This exposes modules for testing and potential library usage
 
+pub mod api;
 pub mod cache;
 pub mod datadog;
 pub mod error;
 pub mod handlers;
+pub mod redact;
+pub mod scrub;
+pub mod secrets;
 pub mod server;
+pub mod telemetry;
 pub mod utils;
 
 // Re-export commonly used types
-pub use datadog::DatadogClient;
+pub use api::Datadog;
+pub use datadog::{DatadogApi, DatadogClient};
 pub use error::{DatadogError, Result};
@@ -2,7 +2,11 @@ mod cache;
 mod datadog;
 mod error;
 mod handlers;
+mod redact;
+mod scrub;
+mod secrets;
 mod server;
+mod telemetry;
 mod utils;
 
 use dotenvy::dotenv;
@@ -21,16 +25,41 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ))
     .init();
 
-    // Get API credentials from environment
-    let api_key = env::var("DD_API_KEY").unwrap_or_else(|_| "DEMO_API_KEY".to_string());
+    let args: Vec<String> = env::args().collect();
 
-    let app_key = env::var("DD_APP_KEY").unwrap_or_else(|_| "DEMO_APP_KEY".to_string());
+    // Spawn this binary over stdio and check it like an MCP client would
+    if args.iter().any(|a| a == "--self-test") {
+        let passed = server::self_test::self_test().await?;
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+
+    // Get API credentials from the environment, a *_CMD command or the OS keyring;
+    // placeholders keep tools/list (and replay mode) usable without them
+    let replay = datadog::replay::replay_mode();
+    let credential =
+        |name: &str, placeholder: &str| -> Result<String, Box<dyn std::error::Error>> {
+            Ok(secrets::resolve(name)?.unwrap_or_else(|| {
+                if !replay {
+                    log::warn!(
+                        "{} is not set; API calls will fail with 403 until it is configured",
+                        name
+                    );
+                }
+                placeholder.to_string()
+            }))
+        };
+    let api_key = credential("DD_API_KEY", "DEMO_API_KEY")?;
+    let app_key = credential("DD_APP_KEY", "DEMO_APP_KEY")?;
 
     let site = env::var("DD_SITE").ok();
 
+    // Select transport: stdio (default) or Streamable HTTP
+    let transport = server::Transport::from_args_and_env(&args)?;
+
     // Create and run the server
     let server = server::Server::new(api_key, app_key, site)?;
-    server.run().await?;
+    server.serve(transport).await?;
 
-    Ok(())
+    // A pending blocking stdin read would otherwise keep the runtime from shutting down
+    std::process::exit(0);
 }
@@ -0,0 +1,93 @@
+use serde_json::Value;
+use std::sync::{OnceLock, RwLock};
+
+/// Shorter values are never treated as secrets; masking them would mangle ordinary text
+/// (Datadog API keys are 32 characters, application keys 40)
+const MIN_SECRET_LEN: usize = 16;
+
+/// Characters of a secret left visible so the key can still be told apart
+const VISIBLE_SUFFIX: usize = 4;
+
+fn secrets() -> &'static RwLock<Vec<String>> {
+    static SECRETS: OnceLock<RwLock<Vec<String>>> = OnceLock::new();
+    SECRETS.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+/// Remember a configured key so it is masked wherever it shows up in text leaving the server
+pub fn register_secret(secret: &str) {
+    let secret = secret.trim();
+    if secret.len() < MIN_SECRET_LEN {
+        return;
+    }
+    let mut secrets = secrets().write().unwrap_or_else(|e| e.into_inner());
+    if !secrets.iter().any(|s| s == secret) {
+        secrets.push(secret.to_string());
+    }
+}
+
+/// `text` with every registered key replaced by `****` and its last four characters
+pub fn redact(text: &str) -> String {
+    let secrets = secrets().read().unwrap_or_else(|e| e.into_inner());
+    mask(text, &secrets)
+}
+
+/// Redact every string in a JSON value in place
+pub fn redact_value(value: &mut Value) {
+    match value {
+        Value::String(text) => {
+            let redacted = redact(text);
+            if redacted != *text {
+                *text = redacted;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        Value::Object(fields) => fields.values_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+fn mask(text: &str, secrets: &[String]) -> String {
+    secrets.iter().fold(text.to_string(), |text, secret| {
+        if !text.contains(secret.as_str()) {
+            return text;
+        }
+        let suffix = &secret[secret.len() - VISIBLE_SUFFIX..];
+        text.replace(secret.as_str(), &format!("****{}", suffix))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_mask() {
+        let secrets = vec!["0123456789abcdef0123456789abcdef".to_string()];
+        assert_eq!(
+            mask(
+                "GET /api/v1/validate?api_key=0123456789abcdef0123456789abcdef failed",
+                &secrets
+            ),
+            "GET /api/v1/validate?api_key=****cdef failed"
+        );
+        assert_eq!(mask("nothing here", &secrets), "nothing here");
+    }
+
+    #[test]
+    fn test_register_and_redact_value() {
+        register_secret("short");
+        register_secret("fedcba9876543210fedcba9876543210aaaa");
+
+        assert_eq!(redact("key short"), "key short");
+        let mut value = json!({
+            "message": "Forbidden for fedcba9876543210fedcba9876543210aaaa",
+            "errors": ["fedcba9876543210fedcba9876543210aaaa"],
+            "status": 403
+        });
+        redact_value(&mut value);
+        assert_eq!(value["message"], "Forbidden for ****aaaa");
+        assert_eq!(value["errors"][0], "****aaaa");
+        assert_eq!(value["status"], 403);
+    }
+}
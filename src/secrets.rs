@@ -0,0 +1,144 @@
+use std::env;
+use std::process::Command;
+
+use crate::error::{DatadogError, Result};
+
+/// Value of a secret such as `DD_API_KEY`, looked up in order:
+/// 1. the variable itself
+/// 2. the output of the shell command in `<NAME>_CMD` (e.g. `aws secretsmanager ...`)
+/// 3. the OS keyring, when `DD_KEYRING_SERVICE` names the service the key is stored under
+///    (account = variable name)
+///
+/// `None` when no source provides it. A command or keyring that is configured but fails is an
+/// error rather than a silent fallback, so a broken setup is not mistaken for a missing key.
+pub fn resolve(name: &str) -> Result<Option<String>> {
+    if let Ok(value) = env::var(name) {
+        return Ok(Some(value));
+    }
+
+    let command_var = format!("{}_CMD", name);
+    if let Ok(command) = env::var(&command_var) {
+        return from_command(&command_var, &command).map(Some);
+    }
+
+    if let Ok(service) = env::var("DD_KEYRING_SERVICE") {
+        return from_keyring(&service, name);
+    }
+
+    Ok(None)
+}
+
+/// Trimmed stdout of `command` run through the platform shell
+fn from_command(source: &str, command: &str) -> Result<String> {
+    let output = shell(command).output().map_err(|e| {
+        DatadogError::InvalidInput(format!("{}: failed to run command: {}", source, e))
+    })?;
+
+    if !output.status.success() {
+        // stderr only: stdout may hold a partial secret
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(DatadogError::InvalidInput(format!(
+            "{}: command exited with {}: {}",
+            source,
+            output.status,
+            stderr.trim()
+        )));
+    }
+
+    let secret = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if secret.is_empty() {
+        return Err(DatadogError::InvalidInput(format!(
+            "{}: command printed nothing",
+            source
+        )));
+    }
+    Ok(secret)
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", command]);
+    cmd
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]);
+    cmd
+}
+
+/// Keychain item (`security add-generic-password -s <service> -a DD_API_KEY -w`)
+#[cfg(target_os = "macos")]
+fn keyring_command(service: &str, account: &str) -> Option<Command> {
+    let mut cmd = Command::new("security");
+    cmd.args(["find-generic-password", "-s", service, "-a", account, "-w"]);
+    Some(cmd)
+}
+
+/// Secret Service item (`secret-tool store --label=... service <service> account DD_API_KEY`)
+#[cfg(all(unix, not(target_os = "macos")))]
+fn keyring_command(service: &str, account: &str) -> Option<Command> {
+    let mut cmd = Command::new("secret-tool");
+    cmd.args(["lookup", "service", service, "account", account]);
+    Some(cmd)
+}
+
+#[cfg(not(unix))]
+fn keyring_command(_service: &str, _account: &str) -> Option<Command> {
+    None
+}
+
+/// Secret stored in the OS keyring, `None` when the item does not exist
+fn from_keyring(service: &str, account: &str) -> Result<Option<String>> {
+    let Some(mut cmd) = keyring_command(service, account) else {
+        return Err(DatadogError::InvalidInput(format!(
+            "DD_KEYRING_SERVICE is not supported on this platform; use {}_CMD instead",
+            account
+        )));
+    };
+
+    let output = cmd.output().map_err(|e| {
+        DatadogError::InvalidInput(format!(
+            "DD_KEYRING_SERVICE: failed to run keyring tool for {}: {}",
+            account, e
+        ))
+    })?;
+
+    let secret = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || secret.is_empty() {
+        log::debug!("{} not found in keyring service '{}'", account, service);
+        return Ok(None);
+    }
+    Ok(Some(secret))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_command_trims_output() {
+        let secret = from_command("DD_API_KEY_CMD", "printf '  abc123\\n'").unwrap();
+        assert_eq!(secret, "abc123");
+    }
+
+    #[test]
+    fn test_from_command_failure() {
+        let error = from_command("DD_API_KEY_CMD", "echo partial; echo denied >&2; exit 3")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("DD_API_KEY_CMD: command exited with"));
+        assert!(error.contains("denied"));
+        assert!(!error.contains("partial"));
+    }
+
+    #[test]
+    fn test_from_command_empty_output() {
+        let error = from_command("DD_APP_KEY_CMD", "true")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("DD_APP_KEY_CMD: command printed nothing"));
+    }
+}
@@ -0,0 +1,134 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::protocol::Server;
+use crate::datadog::DatadogApi;
+
+/// Set by build.rs from `git rev-parse`, or by the builder through GIT_COMMIT
+const GIT_COMMIT: Option<&str> = option_env!("GIT_COMMIT");
+
+impl Server {
+    /// Tool counts per group, the group being the middle of "datadog_<group>_<action>"
+    fn tool_groups(tools: &Value) -> BTreeMap<String, usize> {
+        let mut groups = BTreeMap::new();
+
+        for name in tools["tools"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|t| t["name"].as_str())
+        {
+            let group = name
+                .trim_start_matches("datadog_")
+                .split('_')
+                .next()
+                .unwrap_or(name);
+            *groups.entry(group.to_string()).or_insert(0) += 1;
+        }
+
+        groups
+    }
+
+    /// `serverInfo` for initialize, extended with how this instance is configured
+    pub(super) fn server_info(&self) -> Value {
+        let orgs: serde_json::Map<String, Value> = self
+            .orgs
+            .iter()
+            .map(|(name, org)| (name.clone(), json!(org.client.site())))
+            .collect();
+        json!({
+            "name": "datadog-mcp-server",
+            "version": env!("CARGO_PKG_VERSION"),
+            "commit": GIT_COMMIT,
+            "configuration": {
+                "site": self.client.site(),
+                "api_url": self.client.base_url(),
+                "orgs": orgs,
+                "mode": if self.client.writes_allowed() { "read_write" } else { "read_only" },
+                "read_only": !self.client.writes_allowed(),
+                "tool_groups": Self::tool_groups(&self.tool_definitions()),
+                "cache": {
+                    "ttl_secs": self.cache.ttl().as_secs(),
+                    "stale_ttl_secs": self.cache.stale_ttl().as_secs(),
+                    "results_ttl_secs": self.results.ttl().as_secs(),
+                },
+                "precision": self.precision,
+                "max_response_bytes": self.max_response_bytes,
+            }
+        })
+    }
+
+    /// One-line summary of the configuration, logged when the server starts
+    pub(super) fn log_startup_banner(&self) {
+        let groups = Self::tool_groups(&self.tool_definitions());
+        log::info!(
+            "datadog-mcp-server {}{} | site {} | {} | {} tools in {} groups ({}) | cache TTL {}s",
+            env!("CARGO_PKG_VERSION"),
+            GIT_COMMIT.map(|c| format!(" ({})", c)).unwrap_or_default(),
+            self.client.site(),
+            if self.client.writes_allowed() {
+                "writes enabled"
+            } else {
+                "read-only"
+            },
+            groups.values().sum::<usize>(),
+            groups.len(),
+            groups.keys().cloned().collect::<Vec<_>>().join(", "),
+            self.cache.ttl().as_secs()
+        );
+    }
+
+    /// Validate the API key of the default org and each DD_ORGS org in the background,
+    /// logging which site each is connected to, so bad credentials show up at startup
+    /// rather than as 403s on the first tool call
+    pub(super) fn spawn_credential_check(&self) {
+        let clients: Vec<(String, Arc<dyn DatadogApi>)> =
+            std::iter::once(("default".to_string(), self.client.clone()))
+                .chain(
+                    self.orgs
+                        .iter()
+                        .map(|(name, org)| (name.clone(), org.client.clone())),
+                )
+                .collect();
+
+        tokio::spawn(async move {
+            for (org, client) in clients {
+                match client.validate().await {
+                    Ok(_) => log::info!(
+                        "Connected to Datadog {} as org '{}' ({})",
+                        client.site(),
+                        org,
+                        client.base_url()
+                    ),
+                    Err(e) => log::error!(
+                        "Datadog credentials for org '{}' ({}) failed validation: {}. {}",
+                        org,
+                        client.site(),
+                        e,
+                        e.suggestion()
+                    ),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_groups() {
+        let tools = json!({"tools": [
+            {"name": "datadog_logs_search"},
+            {"name": "datadog_logs_aggregate"},
+            {"name": "datadog_monitors_list"},
+        ]});
+
+        let groups = Server::tool_groups(&tools);
+        assert_eq!(groups["logs"], 2);
+        assert_eq!(groups["monitors"], 1);
+        assert_eq!(groups.len(), 2);
+    }
+}
@@ -0,0 +1,348 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use interim::{Dialect, parse_date_string};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use super::protocol::Server;
+use crate::error::{DatadogError, Result};
+
+/// Tools whose `query` uses search syntax, so scope defaults become `key:value` terms
+const QUERY_TOOLS: &[&str] = &[
+    "datadog_logs_search",
+    "datadog_logs_aggregate",
+    "datadog_logs_timeseries",
+    "datadog_logs_patterns",
+    "datadog_logs_facets",
+    "datadog_spans_search",
+    "datadog_spans_summary",
+    "datadog_rum_events_search",
+    "datadog_error_tracking_issues",
+];
+
+/// Tools taking scope as separate `env`/`service` arguments
+const SCOPED_TOOLS: &[&str] = &[
+    "datadog_apm_resources_stats",
+    "datadog_apm_sampling_diagnostics",
+    "datadog_services_list",
+    "datadog_insights_list",
+    "datadog_service_health",
+];
+
+/// Tools taking a `from`/`to` time window
+const TIME_TOOLS: &[&str] = &[
+    "datadog_metrics_query",
+    "datadog_metrics_compare",
+    "datadog_metrics_multi_query",
+    "datadog_logs_search",
+    "datadog_logs_aggregate",
+    "datadog_logs_timeseries",
+    "datadog_logs_patterns",
+    "datadog_logs_facets",
+    "datadog_events_query",
+    "datadog_events_sources",
+    "datadog_insights_list",
+    "datadog_hosts_metrics",
+    "datadog_spans_search",
+    "datadog_spans_summary",
+    "datadog_traces_get",
+    "datadog_apm_resources_stats",
+    "datadog_apm_sampling_diagnostics",
+    "datadog_service_health",
+    "datadog_rum_events_search",
+    "datadog_error_tracking_issues",
+    "datadog_dashboards_widget_query",
+    "datadog_dashboards_widget_value",
+    "datadog_dashboards_run_widget",
+];
+
+/// Defaults set with `datadog_set_context` and filled into later tool calls
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct SessionContext {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+}
+
+impl SessionContext {
+    /// Parse "UTC", "+09:00", "-0530" or "+9" into a fixed offset
+    fn parse_offset(timezone: &str) -> Option<FixedOffset> {
+        let tz = timezone.trim();
+        if matches!(tz.to_uppercase().as_str(), "UTC" | "Z" | "GMT") {
+            return FixedOffset::east_opt(0);
+        }
+
+        let sign = match tz.chars().next()? {
+            '+' => 1,
+            '-' => -1,
+            _ => return None,
+        };
+        let digits: String = tz[1..].chars().filter(|c| *c != ':').collect();
+        let (hours, minutes) = match digits.len() {
+            1 | 2 => (digits.parse::<i32>().ok()?, 0),
+            4 => (
+                digits[..2].parse::<i32>().ok()?,
+                digits[2..].parse::<i32>().ok()?,
+            ),
+            _ => return None,
+        };
+        if hours > 14 || minutes >= 60 {
+            return None;
+        }
+
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+    }
+
+    /// Set each given field; an empty string clears it, `clear: true` resets everything first
+    pub fn update(&mut self, params: &Value) -> Result<()> {
+        if params["clear"].as_bool().unwrap_or(false) {
+            *self = Self::default();
+        }
+
+        if let Some(timezone) = params["timezone"].as_str().filter(|t| !t.is_empty())
+            && Self::parse_offset(timezone).is_none()
+        {
+            return Err(DatadogError::InvalidInput(format!(
+                "Invalid timezone '{}': use a UTC offset such as '+09:00', '-05:00' or 'UTC'",
+                timezone
+            )));
+        }
+
+        let fields = [
+            ("env", &mut self.env),
+            ("service", &mut self.service),
+            ("team", &mut self.team),
+            ("timezone", &mut self.timezone),
+            ("from", &mut self.from),
+            ("to", &mut self.to),
+        ];
+        for (name, field) in fields {
+            if let Some(value) = params[name].as_str() {
+                let value = value.trim();
+                *field = (!value.is_empty()).then(|| value.to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Append `key:value` terms the query does not already constrain
+    fn scope_query(&self, query: Option<&str>) -> Option<String> {
+        let query = query.map(str::trim).filter(|q| !q.is_empty() && *q != "*");
+        let terms: Vec<String> = [
+            ("env", &self.env),
+            ("service", &self.service),
+            ("team", &self.team),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value.as_deref()?)))
+        .filter(|(key, _)| !query.is_some_and(|q| q.contains(&format!("{}:", key))))
+        .map(|(key, value)| format!("{}:{}", key, value))
+        .collect();
+
+        if terms.is_empty() {
+            return None;
+        }
+
+        Some(match query {
+            Some(query) => format!("{} {}", query, terms.join(" ")),
+            None => terms.join(" "),
+        })
+    }
+
+    /// Resolve wall-clock expressions ("today 9am") in the session timezone to a Unix timestamp
+    fn localize_time(&self, expression: &str) -> Option<String> {
+        let offset = Self::parse_offset(self.timezone.as_deref()?)?;
+        let trimmed = expression.trim();
+        if trimmed.eq_ignore_ascii_case("now")
+            || trimmed.parse::<i64>().is_ok()
+            || DateTime::parse_from_rfc3339(trimmed).is_ok()
+        {
+            return None;
+        }
+
+        let now = Utc::now().with_timezone(&offset);
+        parse_date_string(trimmed, now, Dialect::Us)
+            .ok()
+            .map(|dt| dt.timestamp().to_string())
+    }
+
+    /// Fill omitted arguments from the context; explicit arguments always win
+    pub fn apply(&self, tool: &str, arguments: &Value) -> Value {
+        let mut arguments = match arguments {
+            Value::Object(_) => arguments.clone(),
+            _ => json!({}),
+        };
+
+        if QUERY_TOOLS.contains(&tool)
+            && let Some(query) = self.scope_query(arguments["query"].as_str())
+        {
+            arguments["query"] = json!(query);
+        }
+
+        if SCOPED_TOOLS.contains(&tool) {
+            let fields = [("env", &self.env), ("service", &self.service)];
+            for (name, value) in fields {
+                // The service catalog filters by env only
+                if tool == "datadog_services_list" && name == "service" {
+                    continue;
+                }
+                if let Some(value) = value
+                    && arguments[name].is_null()
+                {
+                    arguments[name] = json!(value);
+                }
+            }
+        }
+
+        // A `range` argument replaces the from/to window altogether
+        if TIME_TOOLS.contains(&tool) && arguments["range"].is_null() {
+            for (name, default) in [("from", &self.from), ("to", &self.to)] {
+                if arguments[name].is_null()
+                    && let Some(default) = default
+                {
+                    arguments[name] = json!(default);
+                }
+                if let Some(localized) =
+                    arguments[name].as_str().and_then(|t| self.localize_time(t))
+                {
+                    arguments[name] = json!(localized);
+                }
+            }
+        }
+
+        arguments
+    }
+}
+
+impl Server {
+    pub(super) async fn set_context(&self, params: &Value) -> Result<Value> {
+        let mut context = self.context.write().await;
+        context.update(params)?;
+
+        Ok(json!({
+            "data": &*context,
+            "meta": {
+                "note": "Defaults apply to later tool calls in this session when the argument is omitted"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> SessionContext {
+        let mut context = SessionContext::default();
+        context
+            .update(&json!({"env": "prod", "service": "checkout", "from": "4 hours ago"}))
+            .unwrap();
+        context
+    }
+
+    #[test]
+    fn test_update_and_clear() {
+        let mut context = context();
+        context.update(&json!({"service": ""})).unwrap();
+        assert_eq!(context.env.as_deref(), Some("prod"));
+        assert!(context.service.is_none());
+
+        context
+            .update(&json!({"clear": true, "team": "payments"}))
+            .unwrap();
+        assert!(context.env.is_none());
+        assert_eq!(context.team.as_deref(), Some("payments"));
+
+        assert!(context.update(&json!({"timezone": "Asia/Seoul"})).is_err());
+        assert!(context.update(&json!({"timezone": "+09:00"})).is_ok());
+    }
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(
+            SessionContext::parse_offset("+09:00")
+                .unwrap()
+                .local_minus_utc(),
+            9 * 3600
+        );
+        assert_eq!(
+            SessionContext::parse_offset("-0530")
+                .unwrap()
+                .local_minus_utc(),
+            -(5 * 3600 + 30 * 60)
+        );
+        assert_eq!(
+            SessionContext::parse_offset("utc")
+                .unwrap()
+                .local_minus_utc(),
+            0
+        );
+        assert!(SessionContext::parse_offset("+25").is_none());
+        assert!(SessionContext::parse_offset("KST").is_none());
+    }
+
+    #[test]
+    fn test_apply_query_tool() {
+        let context = context();
+
+        let args = context.apply("datadog_logs_search", &json!({"query": "status:error"}));
+        assert_eq!(args["query"], "status:error env:prod service:checkout");
+        assert_eq!(args["from"], "4 hours ago");
+
+        // Explicit scope and time win over the context
+        let args = context.apply(
+            "datadog_logs_search",
+            &json!({"query": "service:cart", "from": "1 hour ago"}),
+        );
+        assert_eq!(args["query"], "service:cart env:prod");
+        assert_eq!(args["from"], "1 hour ago");
+
+        let args = context.apply("datadog_spans_search", &Value::Null);
+        assert_eq!(args["query"], "env:prod service:checkout");
+    }
+
+    #[test]
+    fn test_apply_scoped_and_unrelated_tools() {
+        let context = context();
+
+        let args = context.apply("datadog_apm_resources_stats", &json!({}));
+        assert_eq!(args["env"], "prod");
+        assert_eq!(args["service"], "checkout");
+
+        let args = context.apply("datadog_services_list", &json!({}));
+        assert_eq!(args["env"], "prod");
+        assert!(args.get("service").is_none());
+
+        let args = context.apply("datadog_monitors_get", &json!({"monitor_id": 1}));
+        assert_eq!(args, json!({"monitor_id": 1}));
+    }
+
+    #[test]
+    fn test_apply_localizes_wall_clock_times() {
+        let mut context = SessionContext::default();
+        context.update(&json!({"timezone": "+09:00"})).unwrap();
+
+        let args = context.apply(
+            "datadog_metrics_query",
+            &json!({"from": "2024-01-01 09:00", "to": "now"}),
+        );
+        assert_eq!(args["from"], "1704067200");
+        assert_eq!(args["to"], "now");
+
+        // Relative and zoned expressions are left to the handler
+        let args = context.apply(
+            "datadog_metrics_query",
+            &json!({"from": "2024-01-01T00:00:00Z"}),
+        );
+        assert_eq!(args["from"], "2024-01-01T00:00:00Z");
+    }
+}
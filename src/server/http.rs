@@ -0,0 +1,805 @@
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Body, Bytes, Incoming};
+use hyper::header::{self, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio::sync::RwLock;
+
+use super::protocol::{JsonRpcRequest, Server};
+use super::shutdown::{SHUTDOWN_GRACE, Shutdown};
+use crate::error::{DatadogError, Result};
+use crate::redact::redact;
+use crate::telemetry::telemetry;
+
+/// Single endpoint serving the Streamable HTTP transport
+pub const MCP_ENDPOINT: &str = "/mcp";
+
+/// Prometheus scrape endpoint for the server's own telemetry
+pub const METRICS_ENDPOINT: &str = "/metrics";
+
+/// Header carrying the session assigned at initialization
+pub const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Maximum accepted request body size (4 MiB)
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// Default idle time after which a session is dropped, overridable with
+/// MCP_HTTP_SESSION_TTL (seconds); clients don't always send DELETE
+const DEFAULT_SESSION_TTL_SECS: u64 = 1800;
+
+/// Default cap on open sessions, overridable with MCP_HTTP_MAX_SESSIONS
+const DEFAULT_MAX_SESSIONS: usize = 1000;
+
+/// Outcome of handling one HTTP request, independent of hyper types
+#[derive(Debug)]
+struct HttpReply {
+    status: StatusCode,
+    body: Option<Value>,
+    session_id: Option<String>,
+}
+
+impl HttpReply {
+    fn empty(status: StatusCode) -> Self {
+        Self {
+            status,
+            body: None,
+            session_id: None,
+        }
+    }
+
+    fn error(status: StatusCode, code: i32, message: &str) -> Self {
+        let response = Server::create_error_response(code, message.to_string(), None);
+        Self {
+            status,
+            body: serde_json::to_value(response).ok(),
+            session_id: None,
+        }
+    }
+
+    fn into_response(self) -> Response<Full<Bytes>> {
+        let has_body = self.body.is_some();
+        let body = self
+            .body
+            .map(|b| Bytes::from(serde_json::to_vec(&b).unwrap_or_default()))
+            .unwrap_or_default();
+
+        let mut response = Response::new(Full::new(body));
+        *response.status_mut() = self.status;
+
+        if has_body {
+            response.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            );
+        }
+        if let Some(session_id) = self.session_id
+            && let Ok(value) = HeaderValue::from_str(&session_id)
+        {
+            response.headers_mut().insert(SESSION_HEADER, value);
+        }
+        if self.status == StatusCode::METHOD_NOT_ALLOWED {
+            response
+                .headers_mut()
+                .insert(header::ALLOW, HeaderValue::from_static("POST, DELETE"));
+        }
+
+        response
+    }
+}
+
+/// Extra browser origins allowed to call HTTP transports (`MCP_HTTP_ALLOWED_ORIGINS`)
+pub(super) fn allowed_origins_from_env() -> Vec<String> {
+    std::env::var("MCP_HTTP_ALLOWED_ORIGINS")
+        .map(|v| {
+            v.split(',')
+                .map(|o| o.trim().to_string())
+                .filter(|o| !o.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Bearer token HTTP transports require on every request: `MCP_HTTP_TOKEN` when set, and
+/// mandatory when listening on anything but loopback
+pub(super) fn auth_token_from_env(addr: SocketAddr) -> Result<Option<String>> {
+    required_token(addr, std::env::var("MCP_HTTP_TOKEN").ok())
+}
+
+fn required_token(addr: SocketAddr, token: Option<String>) -> Result<Option<String>> {
+    let token = token
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    if token.is_none() && !addr.ip().is_loopback() {
+        return Err(DatadogError::InvalidInput(format!(
+            "Listening on {} exposes the server beyond this machine; set MCP_HTTP_TOKEN \
+             to require a bearer token, or bind to 127.0.0.1",
+            addr
+        )));
+    }
+    Ok(token)
+}
+
+/// Whether an `Authorization` header carries the expected bearer token (any request passes
+/// when no token is configured)
+pub(super) fn authorized(authorization: Option<&str>, token: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    let Some(given) = authorization.and_then(|v| v.strip_prefix("Bearer ")) else {
+        return false;
+    };
+
+    // Compare in constant time so the token can't be guessed byte by byte
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Reject cross-origin browser requests (DNS rebinding protection) unless allow-listed
+pub(super) fn origin_allowed(origin: Option<&str>, allowed_origins: &[String]) -> bool {
+    let Some(origin) = origin else {
+        return true;
+    };
+
+    if allowed_origins
+        .iter()
+        .any(|allowed| allowed == "*" || allowed == origin)
+    {
+        return true;
+    }
+
+    let host = origin
+        .split("://")
+        .nth(1)
+        .unwrap_or(origin)
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    let host = host
+        .rsplit_once(':')
+        .filter(|(_, port)| port.chars().all(|c| c.is_ascii_digit()))
+        .map(|(h, _)| h)
+        .unwrap_or(host);
+
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+/// 401 asking the client for its bearer token
+pub(super) fn unauthorized<B>(body: B) -> Response<B> {
+    let mut response = Response::new(body);
+    *response.status_mut() = StatusCode::UNAUTHORIZED;
+    response
+        .headers_mut()
+        .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    response
+}
+
+/// Serve HTTP/1.1 connections from `listener`, routing every request through `handler`,
+/// until shutdown; requests still running then get `SHUTDOWN_GRACE` to finish
+pub(super) async fn accept_loop<F, Fut, B>(
+    listener: TcpListener,
+    shutdown: Arc<Shutdown>,
+    handler: F,
+) -> Result<()>
+where
+    F: Fn(Request<Incoming>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Response<B>> + Send,
+    B: Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    loop {
+        let accepted = tokio::select! {
+            accepted = listener.accept() => accepted,
+            _ = shutdown.requested() => break,
+        };
+        let (stream, peer) = match accepted {
+            Ok(conn) => conn,
+            Err(e) => {
+                log::warn!("Failed to accept connection: {}", e);
+                continue;
+            }
+        };
+
+        let handler = handler.clone();
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |req| {
+                let in_flight = shutdown.track();
+                let response = handler(req);
+                async move {
+                    let response = response.await;
+                    drop(in_flight);
+                    Ok::<_, Infallible>(response)
+                }
+            });
+
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                log::debug!(
+                    "Connection from {} closed with error: {}",
+                    peer,
+                    redact(&e.to_string())
+                );
+            }
+        });
+    }
+
+    if !shutdown.drain(SHUTDOWN_GRACE).await {
+        log::warn!("Shutdown cut off in-flight requests");
+    }
+    Ok(())
+}
+
+/// One client's view of the server and when it was last used
+struct Session {
+    server: Server,
+    last_seen: Instant,
+}
+
+/// Streamable HTTP transport (MCP 2025-03-26): JSON-RPC over POST with session tracking
+pub struct HttpTransport {
+    server: Arc<Server>,
+    /// Each session's view of the server, keyed by `Mcp-Session-Id`
+    sessions: RwLock<HashMap<String, Session>>,
+    /// Idle time after which a session is dropped
+    session_ttl: Duration,
+    /// Open sessions beyond which `initialize` is refused
+    max_sessions: usize,
+    allowed_origins: Vec<String>,
+    auth_token: Option<String>,
+}
+
+impl HttpTransport {
+    pub fn new(server: Server) -> Self {
+        let env = |name: &str| std::env::var(name).ok().and_then(|v| v.parse().ok());
+        Self {
+            server: Arc::new(server),
+            sessions: RwLock::new(HashMap::new()),
+            session_ttl: Duration::from_secs(
+                env("MCP_HTTP_SESSION_TTL").unwrap_or(DEFAULT_SESSION_TTL_SECS),
+            ),
+            max_sessions: env("MCP_HTTP_MAX_SESSIONS")
+                .map(|max: u64| max as usize)
+                .unwrap_or(DEFAULT_MAX_SESSIONS),
+            allowed_origins: allowed_origins_from_env(),
+            auth_token: None,
+        }
+    }
+
+    /// Drop sessions idle for longer than the TTL
+    async fn evict_idle_sessions(&self) {
+        let ttl = self.session_ttl;
+        let mut sessions = self.sessions.write().await;
+        let before = sessions.len();
+        sessions.retain(|_, session| session.last_seen.elapsed() <= ttl);
+        let evicted = before - sessions.len();
+        if evicted > 0 {
+            log::debug!("Evicted {} idle HTTP sessions", evicted);
+        }
+    }
+
+    pub async fn serve(mut self, addr: SocketAddr) -> Result<()> {
+        self.auth_token = auth_token_from_env(addr)?;
+        let listener = TcpListener::bind(addr).await?;
+        log::info!(
+            "Streamable HTTP transport listening on http://{}{}",
+            listener.local_addr()?,
+            MCP_ENDPOINT
+        );
+
+        self.server.spawn_cache_cleanup();
+        let transport = Arc::new(self);
+
+        let shutdown = transport.server.shutdown.clone();
+        accept_loop(listener, shutdown, move |req| {
+            let transport = transport.clone();
+            async move { transport.handle(req).await }
+        })
+        .await
+    }
+
+    async fn handle(&self, req: Request<Incoming>) -> Response<Full<Bytes>> {
+        let path = req.uri().path();
+        if path != MCP_ENDPOINT && path != METRICS_ENDPOINT {
+            return HttpReply::empty(StatusCode::NOT_FOUND).into_response();
+        }
+
+        let origin = req
+            .headers()
+            .get(header::ORIGIN)
+            .and_then(|v| v.to_str().ok());
+        if !origin_allowed(origin, &self.allowed_origins) {
+            return HttpReply::empty(StatusCode::FORBIDDEN).into_response();
+        }
+
+        let authorization = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok());
+        if !authorized(authorization, self.auth_token.as_deref()) {
+            return unauthorized(Full::default());
+        }
+
+        if req.uri().path() == METRICS_ENDPOINT {
+            return match *req.method() {
+                Method::GET => self.metrics().await,
+                _ => {
+                    let mut response = Response::new(Full::default());
+                    *response.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+                    response
+                        .headers_mut()
+                        .insert(header::ALLOW, HeaderValue::from_static("GET"));
+                    response
+                }
+            };
+        }
+
+        let session_id = req
+            .headers()
+            .get(SESSION_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+
+        let reply = match *req.method() {
+            Method::POST => match Limited::new(req.into_body(), MAX_BODY_BYTES)
+                .collect()
+                .await
+            {
+                Ok(collected) => {
+                    self.handle_post(session_id.as_deref(), &collected.to_bytes())
+                        .await
+                }
+                Err(_) => HttpReply::empty(StatusCode::PAYLOAD_TOO_LARGE),
+            },
+            Method::DELETE => self.handle_delete(session_id.as_deref()).await,
+            _ => HttpReply::empty(StatusCode::METHOD_NOT_ALLOWED),
+        };
+
+        reply.into_response()
+    }
+
+    /// Telemetry and cache counters in the Prometheus text format
+    async fn metrics(&self) -> Response<Full<Bytes>> {
+        let caches = self.server.cache.named_stats().await;
+        let body = telemetry().render_prometheus(&caches);
+
+        let mut response = Response::new(Full::new(Bytes::from(body)));
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/plain; version=0.0.4"),
+        );
+        response
+    }
+
+    async fn handle_delete(&self, session_id: Option<&str>) -> HttpReply {
+        match session_id {
+            Some(id) if self.sessions.write().await.remove(id).is_some() => {
+                HttpReply::empty(StatusCode::OK)
+            }
+            Some(_) => HttpReply::empty(StatusCode::NOT_FOUND),
+            None => HttpReply::error(
+                StatusCode::BAD_REQUEST,
+                -32600,
+                "Missing Mcp-Session-Id header",
+            ),
+        }
+    }
+
+    async fn handle_post(&self, session_id: Option<&str>, body: &[u8]) -> HttpReply {
+        let payload: Value = match serde_json::from_slice(body) {
+            Ok(v) => v,
+            Err(_) => return HttpReply::error(StatusCode::BAD_REQUEST, -32700, "Parse error"),
+        };
+
+        let (messages, is_batch) = match payload {
+            Value::Array(items) if !items.is_empty() => (items, true),
+            Value::Array(_) => {
+                return HttpReply::error(StatusCode::BAD_REQUEST, -32600, "Empty batch");
+            }
+            single => (vec![single], false),
+        };
+
+        let initializing = messages.iter().any(|m| m["method"] == "initialize");
+
+        self.evict_idle_sessions().await;
+
+        let server = if initializing {
+            if self.sessions.read().await.len() >= self.max_sessions {
+                return HttpReply::error(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    -32000,
+                    "Too many open sessions",
+                );
+            }
+            self.server.for_session()
+        } else {
+            let Some(id) = session_id else {
+                return HttpReply::error(
+                    StatusCode::BAD_REQUEST,
+                    -32600,
+                    "Missing Mcp-Session-Id header",
+                );
+            };
+            match self.sessions.write().await.get_mut(id) {
+                Some(session) => {
+                    session.last_seen = Instant::now();
+                    session.server.clone()
+                }
+                None => {
+                    return HttpReply::error(StatusCode::NOT_FOUND, -32001, "Session not found");
+                }
+            }
+        };
+
+        let mut responses = Vec::new();
+        let mut initialized_ok = false;
+
+        for message in messages {
+            // Client responses to server requests carry no method and need no reply
+            if message.get("method").is_none() {
+                continue;
+            }
+
+            let id = message.get("id").cloned();
+            let response = match serde_json::from_value::<JsonRpcRequest>(message) {
+                Ok(request) => {
+                    let is_initialize = request.method == "initialize";
+                    let response = server.process_request_or_error(request).await;
+                    if is_initialize && let Some(ref r) = response {
+                        initialized_ok |= r.error.is_none();
+                    }
+                    response
+                }
+                Err(e) => Some(Server::create_error_response(
+                    -32600,
+                    format!("Invalid Request: {}", e),
+                    id,
+                )),
+            };
+
+            if let Some(r) = response {
+                responses.push(r);
+            }
+        }
+
+        let new_session = if initialized_ok {
+            let id = uuid::Uuid::new_v4().to_string();
+            self.sessions.write().await.insert(
+                id.clone(),
+                Session {
+                    server,
+                    last_seen: Instant::now(),
+                },
+            );
+            Some(id)
+        } else {
+            None
+        };
+
+        if responses.is_empty() {
+            return HttpReply {
+                status: StatusCode::ACCEPTED,
+                body: None,
+                session_id: new_session,
+            };
+        }
+
+        let body = if is_batch {
+            serde_json::to_value(&responses).ok()
+        } else {
+            responses
+                .into_iter()
+                .next()
+                .and_then(|r| serde_json::to_value(r).ok())
+        };
+
+        HttpReply {
+            status: StatusCode::OK,
+            body,
+            session_id: new_session,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_transport() -> HttpTransport {
+        let server = Server::new("test_key".to_string(), "test_app_key".to_string(), None).unwrap();
+        HttpTransport::new(server)
+    }
+
+    fn body(value: Value) -> Vec<u8> {
+        serde_json::to_vec(&value).unwrap()
+    }
+
+    async fn initialize(transport: &HttpTransport) -> String {
+        let reply = transport
+            .handle_post(
+                None,
+                &body(json!({
+                    "jsonrpc": "2.0",
+                    "method": "initialize",
+                    "params": {"protocolVersion": "2025-03-26"},
+                    "id": 1
+                })),
+            )
+            .await;
+
+        assert_eq!(reply.status, StatusCode::OK);
+        reply
+            .session_id
+            .expect("initialize should assign a session")
+    }
+
+    #[tokio::test]
+    async fn test_initialize_assigns_session() {
+        let transport = create_transport();
+        let session = initialize(&transport).await;
+        assert!(transport.sessions.read().await.contains_key(&session));
+    }
+
+    #[tokio::test]
+    async fn test_request_without_session_rejected() {
+        let transport = create_transport();
+        let reply = transport
+            .handle_post(
+                None,
+                &body(json!({"jsonrpc": "2.0", "method": "tools/list", "id": 2})),
+            )
+            .await;
+
+        assert_eq!(reply.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_session_not_found() {
+        let transport = create_transport();
+        let reply = transport
+            .handle_post(
+                Some("missing"),
+                &body(json!({"jsonrpc": "2.0", "method": "tools/list", "id": 2})),
+            )
+            .await;
+
+        assert_eq!(reply.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_notification_accepted_without_body() {
+        let transport = create_transport();
+        let session = initialize(&transport).await;
+
+        let reply = transport
+            .handle_post(
+                Some(&session),
+                &body(json!({"jsonrpc": "2.0", "method": "notifications/initialized"})),
+            )
+            .await;
+
+        assert_eq!(reply.status, StatusCode::ACCEPTED);
+        assert!(reply.body.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_batch_returns_array() {
+        let transport = create_transport();
+        let session = initialize(&transport).await;
+
+        let reply = transport
+            .handle_post(
+                Some(&session),
+                &body(json!([
+                    {"jsonrpc": "2.0", "method": "notifications/initialized"},
+                    {"jsonrpc": "2.0", "method": "tools/list", "id": 3},
+                    {"jsonrpc": "2.0", "method": "prompts/list", "id": 4}
+                ])),
+            )
+            .await;
+
+        assert_eq!(reply.status, StatusCode::OK);
+        let responses = reply.body.unwrap();
+        assert_eq!(responses.as_array().unwrap().len(), 2);
+        assert_eq!(responses[0]["id"], 3);
+        assert!(responses[0]["result"]["tools"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_parse_error() {
+        let transport = create_transport();
+        let reply = transport.handle_post(None, b"{not json").await;
+
+        assert_eq!(reply.status, StatusCode::BAD_REQUEST);
+        assert_eq!(reply.body.unwrap()["error"]["code"], -32700);
+    }
+
+    #[tokio::test]
+    async fn test_delete_terminates_session() {
+        let transport = create_transport();
+        let session = initialize(&transport).await;
+
+        let reply = transport.handle_delete(Some(&session)).await;
+        assert_eq!(reply.status, StatusCode::OK);
+
+        let reply = transport.handle_delete(Some(&session)).await;
+        assert_eq!(reply.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_idle_session_evicted() {
+        let mut transport = create_transport();
+        transport.session_ttl = Duration::from_secs(60);
+        let idle = initialize(&transport).await;
+        let active = initialize(&transport).await;
+
+        transport
+            .sessions
+            .write()
+            .await
+            .get_mut(&idle)
+            .unwrap()
+            .last_seen -= Duration::from_secs(61);
+
+        let call = body(json!({"jsonrpc": "2.0", "method": "tools/list", "id": 2}));
+        let reply = transport.handle_post(Some(&idle), &call).await;
+        assert_eq!(reply.status, StatusCode::NOT_FOUND);
+        let reply = transport.handle_post(Some(&active), &call).await;
+        assert_eq!(reply.status, StatusCode::OK);
+
+        let sessions = transport.sessions.read().await;
+        assert!(!sessions.contains_key(&idle));
+        assert!(sessions.contains_key(&active));
+    }
+
+    #[tokio::test]
+    async fn test_session_cap_refuses_initialize() {
+        let mut transport = create_transport();
+        transport.max_sessions = 1;
+        initialize(&transport).await;
+
+        let reply = transport
+            .handle_post(
+                None,
+                &body(json!({
+                    "jsonrpc": "2.0",
+                    "method": "initialize",
+                    "params": {"protocolVersion": "2025-03-26"},
+                    "id": 1
+                })),
+            )
+            .await;
+        assert_eq!(reply.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(reply.session_id.is_none());
+        assert_eq!(transport.sessions.read().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_context_and_results_are_per_session() {
+        let transport = create_transport();
+        let first = initialize(&transport).await;
+        let second = initialize(&transport).await;
+
+        let reply = transport
+            .handle_post(
+                Some(&first),
+                &body(json!([
+                    {"jsonrpc": "2.0", "method": "notifications/initialized"},
+                    {
+                        "jsonrpc": "2.0",
+                        "method": "tools/call",
+                        "params": {"name": "datadog_set_context", "arguments": {"env": "prod"}},
+                        "id": 2
+                    }
+                ])),
+            )
+            .await;
+        assert!(reply.body.unwrap()[0]["error"].is_null());
+
+        let env = |session: String| {
+            let transport = &transport;
+            async move {
+                let sessions = transport.sessions.read().await;
+                sessions[&session].server.context.read().await.env.clone()
+            }
+        };
+        assert_eq!(env(first.clone()).await.as_deref(), Some("prod"));
+        assert_eq!(env(second.clone()).await, None);
+        assert_eq!(transport.server.context.read().await.env, None);
+
+        // A result recorded in one session can't be saved or read from another
+        let sessions = transport.sessions.read().await;
+        let first_results = &sessions[&first].server.results;
+        first_results
+            .record("datadog_monitors_list", &json!({}), &json!({"data": []}))
+            .await;
+        assert!(first_results.save(None).await.is_ok());
+        assert!(sessions[&second].server.results.save(None).await.is_err());
+        assert!(
+            sessions[&second]
+                .server
+                .results
+                .get("r1", None)
+                .await
+                .is_err()
+        );
+        drop(sessions);
+
+        transport.handle_delete(Some(&first)).await;
+        assert!(!transport.sessions.read().await.contains_key(&first));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        let transport = create_transport();
+        let response = transport.metrics().await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/plain; version=0.0.4"
+        );
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("# TYPE mcp_datadog_uptime_seconds gauge"));
+        assert!(text.contains("mcp_datadog_cache_entries{cache=\"dashboards\"} 0"));
+    }
+
+    #[test]
+    fn test_required_token() {
+        let local: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        let public: SocketAddr = "0.0.0.0:8080".parse().unwrap();
+
+        assert_eq!(required_token(local, None).unwrap(), None);
+        assert!(required_token(public, None).is_err());
+        assert!(required_token(public, Some("  ".to_string())).is_err());
+        assert_eq!(
+            required_token(public, Some("secret".to_string())).unwrap(),
+            Some("secret".to_string())
+        );
+    }
+
+    #[test]
+    fn test_authorized() {
+        assert!(authorized(None, None));
+        assert!(authorized(Some("Bearer secret"), Some("secret")));
+        assert!(!authorized(None, Some("secret")));
+        assert!(!authorized(Some("Bearer secreT"), Some("secret")));
+        assert!(!authorized(Some("Bearer secret2"), Some("secret")));
+        assert!(!authorized(Some("Basic secret"), Some("secret")));
+    }
+
+    #[tokio::test]
+    async fn test_serve_refuses_public_address_without_token() {
+        let result = create_transport().serve("0.0.0.0:0".parse().unwrap()).await;
+        assert!(result.unwrap_err().to_string().contains("MCP_HTTP_TOKEN"));
+    }
+
+    #[test]
+    fn test_origin_validation() {
+        assert!(origin_allowed(None, &[]));
+        assert!(origin_allowed(Some("http://localhost:3000"), &[]));
+        assert!(origin_allowed(Some("http://127.0.0.1"), &[]));
+        assert!(!origin_allowed(Some("https://evil.example.com"), &[]));
+
+        let allowed = vec!["https://app.example.com".to_string()];
+        assert!(origin_allowed(Some("https://app.example.com"), &allowed));
+    }
+}
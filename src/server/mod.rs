@@ -1,5 +1,21 @@
+mod capabilities;
+mod context;
+mod http;
+mod orgs;
 mod protocol;
+mod registry;
+mod resources;
+mod results;
 mod router;
+mod saved_queries;
 mod schema;
+mod self_report;
+pub mod self_test;
+mod shutdown;
+mod sse;
+mod tools;
+mod transport;
+mod validation;
 
 pub use protocol::Server;
+pub use transport::Transport;
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::protocol::Server;
+use crate::cache::DataCache;
+use crate::datadog::{DatadogApi, DatadogClient, replay};
+use crate::error::{DatadogError, Result};
+use crate::secrets;
+
+/// Client and cache of one additional Datadog organization
+#[derive(Clone)]
+pub struct Org {
+    pub client: Arc<dyn DatadogApi>,
+    pub cache: Arc<DataCache>,
+}
+
+/// Additional orgs by name, selected per call with the `org` argument
+pub type OrgPool = BTreeMap<String, Org>;
+
+/// Prefix of an org's variables: `prod-eu` → `DD_ORG_PROD_EU`
+fn env_prefix(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("DD_ORG_{}", name)
+}
+
+/// Orgs named in DD_ORGS ("prod,staging"), each configured with DD_ORG_<NAME>_API_KEY,
+/// DD_ORG_<NAME>_APP_KEY (or their `_CMD`/keyring sources, see `secrets::resolve`) and
+/// optionally DD_ORG_<NAME>_SITE, DD_ORG_<NAME>_API_URL and DD_ORG_<NAME>_EXTRA_HEADERS.
+/// Other settings (timeouts, tag filters, mode) are shared with the default org; its
+/// DD_API_URL and DD_EXTRA_HEADERS are not.
+pub(super) fn load_orgs(cache_ttl: u64) -> Result<OrgPool> {
+    let names = std::env::var("DD_ORGS").unwrap_or_default();
+    let mut orgs = OrgPool::new();
+
+    for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let org = load_org(
+            name,
+            cache_ttl,
+            |var| std::env::var(var).ok(),
+            secrets::resolve,
+        )?;
+        orgs.insert(name.to_string(), org);
+    }
+
+    Ok(orgs)
+}
+
+/// One org from its DD_ORG_<NAME>_* variables, read through `var` and `secret`
+fn load_org(
+    name: &str,
+    cache_ttl: u64,
+    var: impl Fn(&str) -> Option<String>,
+    secret: impl Fn(&str) -> Result<Option<String>>,
+) -> Result<Org> {
+    let prefix = env_prefix(name);
+    let var = |suffix: &str| var(&format!("{}_{}", prefix, suffix));
+    let secret = |suffix: &str| secret(&format!("{}_{}", prefix, suffix));
+    let missing = |suffix: &str| {
+        DatadogError::InvalidInput(format!(
+            "Org '{}' is listed in DD_ORGS but {}_{} is not set",
+            name, prefix, suffix
+        ))
+    };
+
+    let api_key = secret("API_KEY")?.ok_or_else(|| missing("API_KEY"))?;
+    let app_key = secret("APP_KEY")?.ok_or_else(|| missing("APP_KEY"))?;
+    let mut client = DatadogClient::with_shared_settings(api_key, app_key, var("SITE"))?;
+    if let Some(spec) = var("EXTRA_HEADERS") {
+        client = client.with_headers(DatadogClient::parse_headers(&spec)?);
+    }
+    if let Some(url) = var("API_URL") {
+        client = client.with_api_url(&url);
+    }
+
+    Ok(Org {
+        client: replay::from_env(client, Some(name)),
+        cache: Arc::new(DataCache::new(cache_ttl)),
+    })
+}
+
+impl Server {
+    /// The server as seen by a call for `org`: that org's client and cache, shared session
+    /// state. No org means the default one (DD_API_KEY/DD_APP_KEY/DD_SITE).
+    pub(super) fn for_org(&self, org: Option<&str>) -> Result<Server> {
+        let Some(name) = org else {
+            return Ok(self.clone());
+        };
+        let org = self.orgs.get(name).ok_or_else(|| {
+            DatadogError::InvalidInput(format!(
+                "Unknown org '{}'. Configured orgs: {}",
+                name,
+                self.org_names().join(", ")
+            ))
+        })?;
+
+        Ok(Server {
+            client: org.client.clone(),
+            cache: org.cache.clone(),
+            ..self.clone()
+        })
+    }
+
+    /// Names the `org` argument accepts, empty when only the default org is configured
+    pub(super) fn org_names(&self) -> Vec<&str> {
+        self.orgs.keys().map(String::as_str).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn org(site: &str) -> Org {
+        let client =
+            DatadogClient::new("key".to_string(), "app".to_string(), Some(site.to_string()))
+                .unwrap();
+        Org {
+            client: Arc::new(client),
+            cache: Arc::new(DataCache::new(300)),
+        }
+    }
+
+    #[test]
+    fn test_env_prefix() {
+        assert_eq!(env_prefix("prod"), "DD_ORG_PROD");
+        assert_eq!(env_prefix("prod-eu"), "DD_ORG_PROD_EU");
+    }
+
+    #[test]
+    fn test_load_org_uses_own_site_and_overrides() {
+        let env = std::collections::HashMap::from([
+            ("DD_ORG_EU_API_KEY", "eu-key"),
+            ("DD_ORG_EU_APP_KEY", "eu-app"),
+            ("DD_ORG_EU_SITE", "datadoghq.eu"),
+            ("DD_ORG_US5_API_KEY", "us5-key"),
+            ("DD_ORG_US5_APP_KEY", "us5-app"),
+            ("DD_ORG_US5_SITE", "us5.datadoghq.com"),
+            ("DD_ORG_US5_API_URL", "https://preview.example.com/"),
+        ]);
+        let var = |name: &str| env.get(name).map(|v| v.to_string());
+        let secret = |name: &str| Ok(var(name));
+
+        let eu = load_org("eu", 300, var, secret).unwrap();
+        assert_eq!(eu.client.site(), "datadoghq.eu");
+        assert_eq!(eu.client.base_url(), "https://api.datadoghq.eu");
+
+        let us5 = load_org("us5", 300, var, secret).unwrap();
+        assert_eq!(us5.client.site(), "us5.datadoghq.com");
+        assert_eq!(us5.client.base_url(), "https://preview.example.com");
+
+        let Err(error) = load_org("ap1", 300, var, secret) else {
+            panic!("org without keys accepted");
+        };
+        assert!(error.to_string().contains("DD_ORG_AP1_API_KEY is not set"));
+    }
+
+    #[test]
+    fn test_for_org() {
+        let mut server = Server::new("key".to_string(), "app".to_string(), None).unwrap();
+        server.orgs = Arc::new(OrgPool::from([("eu".to_string(), org("datadoghq.eu"))]));
+
+        assert_eq!(server.for_org(None).unwrap().client.site(), "datadoghq.com");
+        let eu = server.for_org(Some("eu")).unwrap();
+        assert_eq!(eu.client.site(), "datadoghq.eu");
+        assert!(Arc::ptr_eq(&eu.results, &server.results));
+
+        let tools = server.tool_definitions();
+        assert_eq!(
+            tools["tools"][0]["inputSchema"]["properties"]["org"]["enum"],
+            serde_json::json!(["eu"])
+        );
+
+        let Err(error) = server.for_org(Some("us3")) else {
+            panic!("unknown org accepted");
+        };
+        let error = error.to_string();
+        assert!(error.contains("Unknown org 'us3'. Configured orgs: eu"));
+    }
+}
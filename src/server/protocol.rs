@@ -5,9 +5,15 @@ use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::sync::RwLock;
 
+use super::context::SessionContext;
+use super::orgs::{OrgPool, load_orgs};
+use super::results::ResultStore;
+use super::saved_queries::{SavedQueries, load_saved_queries};
+use super::shutdown::Shutdown;
 use crate::cache::DataCache;
-use crate::datadog::DatadogClient;
+use crate::datadog::{DatadogApi, DatadogClient, replay};
 use crate::error::Result;
+use crate::scrub::Scrubber;
 
 #[derive(Debug, Deserialize)]
 pub struct JsonRpcRequest {
@@ -41,10 +47,25 @@ pub struct JsonRpcError {
     pub data: Option<Value>,
 }
 
+#[derive(Clone)]
 pub struct Server {
-    pub client: Arc<DatadogClient>,
+    pub client: Arc<dyn DatadogApi>,
     pub cache: Arc<DataCache>,
+    /// Orgs besides the default one, from DD_ORGS
+    pub orgs: Arc<OrgPool>,
     pub initialized: Arc<RwLock<bool>>,
+    pub context: Arc<RwLock<SessionContext>>,
+    pub results: Arc<ResultStore>,
+    /// Named queries from DD_SAVED_QUERIES_FILE
+    pub saved_queries: Arc<SavedQueries>,
+    /// Masks PII in telemetry tool results, when DD_SCRUB_PII or DD_SCRUB_PATTERN is set
+    pub scrubber: Option<Arc<Scrubber>>,
+    /// Default significant digits for floats in tool results (DD_PRECISION)
+    pub precision: Option<u32>,
+    /// Default size cap in bytes of a tool result (DD_MAX_RESPONSE_BYTES)
+    pub max_response_bytes: Option<usize>,
+    /// Set on SIGINT/SIGTERM or stdio exit; stops the transports and the cache cleanup task
+    pub shutdown: Arc<Shutdown>,
 }
 
 impl Server {
@@ -73,46 +94,104 @@ impl Server {
     }
 
     pub fn new(api_key: String, app_key: String, site: Option<String>) -> Result<Self> {
-        let client = match DatadogClient::new(api_key, app_key, site) {
-            Ok(c) => Arc::new(c),
-            Err(e) => return Err(e),
-        };
+        let client = replay::from_env(DatadogClient::new(api_key, app_key, site)?, None);
         let cache = Arc::new(DataCache::new(300)); // 5 minutes TTL
         Ok(Self {
             client,
             cache,
+            orgs: Arc::new(load_orgs(300)?),
             initialized: Arc::new(RwLock::new(false)),
+            context: Arc::new(RwLock::new(SessionContext::default())),
+            results: Arc::new(ResultStore::default()),
+            saved_queries: Arc::new(load_saved_queries()?),
+            scrubber: Scrubber::from_env()?.map(Arc::new),
+            precision: std::env::var("DD_PRECISION")
+                .ok()
+                .and_then(|p| p.parse().ok()),
+            max_response_bytes: std::env::var("DD_MAX_RESPONSE_BYTES")
+                .ok()
+                .and_then(|b| b.trim().parse().ok())
+                .filter(|b| *b > 0),
+            shutdown: Arc::new(Shutdown::default()),
         })
     }
 
-    pub async fn run(self) -> Result<()> {
-        // Use async I/O for better compatibility
-        let stdin = tokio::io::stdin();
-        let stdout = tokio::io::stdout();
-        let mut reader = BufReader::new(stdin);
-        let mut stdout = stdout;
+    /// The server as seen by one HTTP or SSE session: same clients and caches, but its own
+    /// initialization state, `datadog_set_context` defaults and saved results, dropped with
+    /// the session
+    pub fn for_session(&self) -> Server {
+        Server {
+            initialized: Arc::new(RwLock::new(false)),
+            context: Arc::new(RwLock::new(SessionContext::default())),
+            results: Arc::new(ResultStore::new(self.results.ttl())),
+            ..self.clone()
+        }
+    }
 
-        // Spawn background cache cleanup task
-        let cache_clone = self.cache.clone();
+    /// Spawn background cache cleanup task, stopped by shutdown
+    pub fn spawn_cache_cleanup(&self) {
+        let caches: Vec<Arc<DataCache>> = std::iter::once(self.cache.clone())
+            .chain(self.orgs.values().map(|org| org.cache.clone()))
+            .collect();
+        let shutdown = self.shutdown.clone();
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
             loop {
-                interval.tick().await;
-                let removed = cache_clone.cleanup_all_expired().await;
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = shutdown.requested() => break,
+                }
+                let mut removed = 0;
+                for cache in &caches {
+                    removed += cache.cleanup_all_expired().await;
+                }
                 if removed > 0 {
                     log::info!("Cache cleanup: removed {} expired entries", removed);
                 }
             }
         });
+    }
+
+    pub(super) async fn cache_stats(&self) -> Result<Value> {
+        Ok(json!({ "data": self.cache.stats().await }))
+    }
+
+    /// Tool and Datadog API counters since startup, with the cache counters
+    pub(super) async fn server_stats(&self) -> Result<Value> {
+        let mut data = crate::telemetry::telemetry().snapshot();
+        data["cache"] = self.cache.stats().await;
+        Ok(json!({ "data": data }))
+    }
+
+    pub(super) async fn clear_cache(&self, params: &Value) -> Result<Value> {
+        let name = params["cache"].as_str().filter(|c| !c.is_empty());
+        let removed = self.cache.clear(name).await?;
+        Ok(json!({ "data": { "cache": name.unwrap_or("all"), "removed_entries": removed } }))
+    }
+
+    pub async fn run(self) -> Result<()> {
+        // Use async I/O for better compatibility
+        let stdin = tokio::io::stdin();
+        let stdout = tokio::io::stdout();
+        let mut reader = BufReader::new(stdin);
+        let mut stdout = stdout;
+
+        self.spawn_cache_cleanup();
 
         let mut buffer = String::new();
         let mut empty_reads = 0;
+        // Set by the `shutdown` request: only `exit` is served from then on
+        let mut shutting_down = false;
 
         loop {
             buffer.clear();
 
-            // Read a line from stdin
-            let line = match reader.read_line(&mut buffer).await {
+            // Read a line from stdin, unless a signal started shutdown
+            let read = tokio::select! {
+                read = reader.read_line(&mut buffer) => read,
+                _ = self.shutdown.requested() => break,
+            };
+            let line = match read {
                 Ok(0) => {
                     empty_reads += 1;
                     if empty_reads > 3 {
@@ -159,8 +238,28 @@ impl Server {
                 }
             };
 
+            let method = request.method.clone();
+            let result = if shutting_down && method != "exit" {
+                Ok(request.id.map(|id| {
+                    Self::create_error_response(
+                        -32600,
+                        "Server is shutting down".to_string(),
+                        Some(id),
+                    )
+                }))
+            } else {
+                // A signal during a long tool call leaves it SHUTDOWN_GRACE to finish
+                tokio::select! {
+                    result = self.process_request(request) => result,
+                    _ = self.shutdown.grace_expired() => {
+                        log::warn!("Shutdown cut off an in-flight {} request", method);
+                        break;
+                    }
+                }
+            };
+
             // Process the request
-            match self.process_request(request).await {
+            match result {
                 Ok(Some(response)) => {
                     let response_str = match serde_json::to_string(&response) {
                         Ok(s) => s,
@@ -190,8 +289,17 @@ impl Server {
                     }
                 }
             }
+
+            match method.as_str() {
+                "shutdown" => shutting_down = true,
+                "exit" => break,
+                _ => {}
+            }
         }
 
+        let _ = stdout.flush().await;
+        // Stops the cache cleanup task
+        self.shutdown.trigger();
         Ok(())
     }
 
@@ -215,17 +323,8 @@ impl Server {
                 };
                 Ok(Some(response))
             }
-            "resources/list" => {
-                let response = JsonRpcResponse {
-                    jsonrpc: "2.0".to_string(),
-                    result: Some(json!({
-                        "resources": []
-                    })),
-                    error: None,
-                    id: request.id,
-                };
-                Ok(Some(response))
-            }
+            "resources/list" => self.handle_resources_list(&request).await,
+            "resources/read" => self.handle_resources_read(&request).await,
             "shutdown" => {
                 let response = JsonRpcResponse {
                     jsonrpc: "2.0".to_string(),
@@ -260,6 +359,21 @@ impl Server {
         }
     }
 
+    /// Process a request, converting processing failures into JSON-RPC internal errors
+    pub async fn process_request_or_error(
+        &self,
+        request: JsonRpcRequest,
+    ) -> Option<JsonRpcResponse> {
+        let id = request.id.clone();
+        match self.process_request(request).await {
+            Ok(response) => response,
+            Err(e) => {
+                error!("Request processing error: {}", e);
+                Some(Self::create_error_response(-32603, e.to_string(), id))
+            }
+        }
+    }
+
     pub async fn handle_initialize(
         &self,
         request: &JsonRpcRequest,
@@ -304,12 +418,10 @@ impl Server {
             jsonrpc: "2.0".to_string(),
             result: Some(json!({
                 "protocolVersion": protocol_version,
-                "serverInfo": {
-                    "name": "datadog-mcp-server",
-                    "version": "0.1.0"
-                },
+                "serverInfo": self.server_info(),
                 "capabilities": {
-                    "tools": {}
+                    "tools": {},
+                    "resources": {}
                 }
             })),
             error: None,
@@ -392,6 +504,11 @@ mod tests {
         let result = resp.result.unwrap();
         assert_eq!(result["protocolVersion"], "2024-11-05");
         assert_eq!(result["serverInfo"]["name"], "datadog-mcp-server");
+        assert_eq!(result["serverInfo"]["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            result["serverInfo"]["configuration"]["site"],
+            "datadoghq.com"
+        );
         assert!(result["capabilities"]["tools"].is_object());
     }
 
@@ -522,13 +639,10 @@ mod tests {
         let response = server.process_request(request).await.unwrap();
         assert!(response.is_some());
 
+        // Resources require initialization, like tools
         let resp = response.unwrap();
-        assert!(resp.error.is_none());
-        assert!(resp.result.is_some());
-
-        let result = resp.result.unwrap();
-        assert!(result["resources"].is_array());
-        assert_eq!(result["resources"].as_array().unwrap().len(), 0);
+        assert!(resp.result.is_none());
+        assert_eq!(resp.error.unwrap().code, -32002);
     }
 
     #[tokio::test]
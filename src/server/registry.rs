@@ -0,0 +1,204 @@
+use futures::future::BoxFuture;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use super::protocol::Server;
+use super::tools::register_tools;
+use crate::datadog::DatadogApi;
+use crate::error::Result;
+
+pub(super) type ToolFuture<'a> = BoxFuture<'a, Result<Value>>;
+
+/// Builds a tool's description and inputSchema; descriptions may show client settings
+pub(super) type DefinitionFn = fn(&dyn DatadogApi) -> Value;
+
+/// Runs a tool call against the server's client, cache and session state
+pub(super) type ExecuteFn = for<'a> fn(&'a Server, &'a Value) -> ToolFuture<'a>;
+
+/// An MCP tool: what tools/list advertises and what tools/call runs
+pub(super) trait Tool: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// `{"name", "description", "inputSchema"}` as listed by tools/list
+    fn definition(&self, client: &dyn DatadogApi) -> Value;
+
+    fn execute<'a>(&'a self, server: &'a Server, arguments: &'a Value) -> ToolFuture<'a>;
+
+    /// Creates or modifies Datadog resources: hidden and rejected in read-only mode
+    fn is_mutating(&self) -> bool {
+        false
+    }
+
+    /// Returns free-form user data (log messages and group values, spans, RUM and event text,
+    /// process command lines) that the PII scrubber masks
+    fn is_scrubbed(&self) -> bool {
+        false
+    }
+}
+
+/// A tool whose definition and execution are plain functions, usually a handler call
+pub(super) struct HandlerTool {
+    name: &'static str,
+    definition: DefinitionFn,
+    execute: ExecuteFn,
+    mutating: bool,
+    scrubbed: bool,
+}
+
+impl HandlerTool {
+    /// `definition` returns the description and inputSchema; the name is added from `name`
+    pub(super) fn new(name: &'static str, definition: DefinitionFn, execute: ExecuteFn) -> Self {
+        Self {
+            name,
+            definition,
+            execute,
+            mutating: false,
+            scrubbed: false,
+        }
+    }
+
+    /// Mark the tool as one that writes to Datadog
+    pub(super) fn mutating(mut self) -> Self {
+        self.mutating = true;
+        self
+    }
+
+    /// Mark the tool's results for PII scrubbing (DD_SCRUB_PII / DD_SCRUB_PATTERN)
+    pub(super) fn scrubbed(mut self) -> Self {
+        self.scrubbed = true;
+        self
+    }
+}
+
+impl Tool for HandlerTool {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn definition(&self, client: &dyn DatadogApi) -> Value {
+        let mut definition = json!({"name": self.name});
+        if let Value::Object(fields) = (self.definition)(client) {
+            definition.as_object_mut().unwrap().extend(fields);
+        }
+        definition
+    }
+
+    fn execute<'a>(&'a self, server: &'a Server, arguments: &'a Value) -> ToolFuture<'a> {
+        (self.execute)(server, arguments)
+    }
+
+    fn is_mutating(&self) -> bool {
+        self.mutating
+    }
+
+    fn is_scrubbed(&self) -> bool {
+        self.scrubbed
+    }
+}
+
+/// All tools in registration order, which is the tools/list order
+#[derive(Default)]
+pub(super) struct ToolRegistry {
+    tools: Vec<Box<dyn Tool>>,
+    by_name: HashMap<&'static str, usize>,
+}
+
+impl ToolRegistry {
+    /// Panics on a duplicate name, which is a programming error caught by any test run
+    pub(super) fn register(&mut self, tool: impl Tool + 'static) {
+        let name = tool.name();
+        assert!(
+            self.by_name.insert(name, self.tools.len()).is_none(),
+            "tool '{}' registered twice",
+            name
+        );
+        self.tools.push(Box::new(tool));
+    }
+
+    pub(super) fn get(&self, name: &str) -> Option<&dyn Tool> {
+        self.by_name.get(name).map(|&i| self.tools[i].as_ref())
+    }
+
+    pub(super) fn iter(&self) -> impl Iterator<Item = &dyn Tool> {
+        self.tools.iter().map(|tool| tool.as_ref())
+    }
+}
+
+/// The server's tools, registered on first use
+pub(super) fn registry() -> &'static ToolRegistry {
+    static REGISTRY: OnceLock<ToolRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut registry = ToolRegistry::default();
+        register_tools(&mut registry);
+        registry
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datadog::DatadogClient;
+    use futures::FutureExt;
+
+    fn client() -> DatadogClient {
+        DatadogClient::new("key".to_string(), "app".to_string(), None).unwrap()
+    }
+
+    #[test]
+    fn test_handler_tool_definition() {
+        let tool = HandlerTool::new(
+            "datadog_example",
+            |_| json!({"description": "Example", "inputSchema": {"type": "object", "properties": {}}}),
+            |_, arguments| async move { Ok(arguments.clone()) }.boxed(),
+        );
+
+        let definition = tool.definition(&client());
+        assert_eq!(definition["name"], "datadog_example");
+        assert_eq!(definition["description"], "Example");
+        assert_eq!(definition["inputSchema"]["type"], "object");
+    }
+
+    #[test]
+    #[should_panic(expected = "registered twice")]
+    fn test_duplicate_registration() {
+        let mut registry = ToolRegistry::default();
+        for _ in 0..2 {
+            registry.register(HandlerTool::new(
+                "datadog_example",
+                |_| json!({}),
+                |_, _| async { Ok(json!({})) }.boxed(),
+            ));
+        }
+    }
+
+    #[test]
+    fn test_registry_definitions() {
+        let client = client();
+        for tool in registry().iter() {
+            let definition = tool.definition(&client);
+            assert_eq!(definition["name"], tool.name());
+            assert!(
+                definition["description"].is_string(),
+                "{} has no description",
+                tool.name()
+            );
+            assert_eq!(
+                definition["inputSchema"]["type"],
+                "object",
+                "{} has no object inputSchema",
+                tool.name()
+            );
+        }
+        assert!(registry().get("datadog_metrics_query").is_some());
+        assert!(registry().get("datadog_unknown").is_none());
+
+        let scrubbed = |name| registry().get(name).unwrap().is_scrubbed();
+        assert!(scrubbed("datadog_logs_search"));
+        assert!(scrubbed("datadog_spans_search"));
+        assert!(scrubbed("datadog_rum_events_search"));
+        assert!(scrubbed("datadog_logs_aggregate"));
+        assert!(scrubbed("datadog_processes_list"));
+        assert!(!scrubbed("datadog_metrics_query"));
+    }
+}
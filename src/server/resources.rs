@@ -0,0 +1,375 @@
+use serde_json::{Value, json};
+
+use super::protocol::{JsonRpcRequest, JsonRpcResponse, Server};
+use crate::cache::create_cache_key;
+use crate::error::{DatadogError, Result};
+
+/// URI scheme for Datadog resources
+pub const RESOURCE_SCHEME: &str = "datadog://";
+
+/// Resources returned per `resources/list` page
+const RESOURCES_PAGE_SIZE: usize = 100;
+
+const RESOURCE_MIME_TYPE: &str = "application/json";
+
+/// Datadog object types exposed as MCP resources
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResourceKind {
+    Dashboard,
+    Monitor,
+    Slo,
+}
+
+impl ResourceKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ResourceKind::Dashboard => "dashboard",
+            ResourceKind::Monitor => "monitor",
+            ResourceKind::Slo => "slo",
+        }
+    }
+
+    fn uri(self, id: &str) -> String {
+        format!("{}{}/{}", RESOURCE_SCHEME, self.as_str(), id)
+    }
+}
+
+/// Split `datadog://{kind}/{id}` into its kind and ID
+pub fn parse_resource_uri(uri: &str) -> Option<(ResourceKind, &str)> {
+    let (kind, id) = uri.strip_prefix(RESOURCE_SCHEME)?.split_once('/')?;
+    if id.is_empty() || id.contains('/') {
+        return None;
+    }
+
+    let kind = match kind {
+        "dashboard" => ResourceKind::Dashboard,
+        "monitor" => ResourceKind::Monitor,
+        "slo" => ResourceKind::Slo,
+        _ => return None,
+    };
+    Some((kind, id))
+}
+
+fn resource_entry(kind: ResourceKind, id: &str, name: &str, description: Option<&str>) -> Value {
+    let mut entry = json!({
+        "uri": kind.uri(id),
+        "name": name,
+        "mimeType": RESOURCE_MIME_TYPE,
+    });
+    if let Some(description) = description.filter(|d| !d.is_empty()) {
+        entry["description"] = json!(description);
+    }
+    entry
+}
+
+impl Server {
+    async fn dashboard_resources(&self) -> Result<Vec<Value>> {
+        let cache_key = create_cache_key("dashboards", &json!({}));
+        let dashboards = self
+            .cache
+            .get_or_fetch_dashboards(&cache_key, || async {
+                Ok(self.client.list_dashboards().await?.dashboards)
+            })
+            .await?;
+
+        Ok(dashboards
+            .iter()
+            .map(|d| {
+                resource_entry(
+                    ResourceKind::Dashboard,
+                    &d.id,
+                    &d.title,
+                    d.description.as_deref(),
+                )
+            })
+            .collect())
+    }
+
+    async fn monitor_resources(&self) -> Result<Vec<Value>> {
+        let cache_key = create_cache_key(
+            "monitors",
+            &json!({
+                "tags": None::<String>,
+                "monitor_tags": None::<String>
+            }),
+        );
+        let monitors = self
+            .cache
+            .get_or_fetch_monitors(&cache_key, || async {
+                self.client.list_monitors(None, None, None, None).await
+            })
+            .await?;
+
+        Ok(monitors
+            .iter()
+            .map(|m| {
+                let description = m.overall_state.as_deref().map(|s| format!("State: {}", s));
+                resource_entry(
+                    ResourceKind::Monitor,
+                    &m.id.to_string(),
+                    &m.name,
+                    description.as_deref(),
+                )
+            })
+            .collect())
+    }
+
+    async fn slo_resources(&self) -> Result<Vec<Value>> {
+        let response = self.client.list_slos().await?;
+
+        Ok(response["data"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(|slo| {
+                Some(resource_entry(
+                    ResourceKind::Slo,
+                    slo["id"].as_str()?,
+                    slo["name"].as_str().unwrap_or_default(),
+                    slo["description"].as_str(),
+                ))
+            })
+            .collect())
+    }
+
+    pub async fn handle_resources_list(
+        &self,
+        request: &JsonRpcRequest,
+    ) -> Result<Option<JsonRpcResponse>> {
+        // Check if initialized
+        {
+            let initialized = self.initialized.read().await;
+            if !*initialized {
+                let error_response = Self::create_error_response(
+                    -32002,
+                    "Server not initialized".to_string(),
+                    request.id.clone(),
+                );
+                return Ok(Some(error_response));
+            }
+        }
+
+        let offset = match request
+            .params
+            .as_ref()
+            .and_then(|p| p["cursor"].as_str())
+            .map(str::parse::<usize>)
+        {
+            None => 0,
+            Some(Ok(offset)) => offset,
+            Some(Err(_)) => {
+                let error_response = Self::create_error_response(
+                    -32602,
+                    "Invalid cursor".to_string(),
+                    request.id.clone(),
+                );
+                return Ok(Some(error_response));
+            }
+        };
+
+        let (dashboards, monitors, slos) = tokio::join!(
+            self.dashboard_resources(),
+            self.monitor_resources(),
+            self.slo_resources()
+        );
+
+        // A failing resource type (e.g. missing SLO permissions) should not hide the others
+        let mut resources = Vec::new();
+        for (kind, result) in [
+            ("dashboards", dashboards),
+            ("monitors", monitors),
+            ("slos", slos),
+        ] {
+            match result {
+                Ok(entries) => resources.extend(entries),
+                Err(e) => log::warn!("Failed to list {} resources: {}", kind, e),
+            }
+        }
+
+        let end = (offset + RESOURCES_PAGE_SIZE).min(resources.len());
+        let page = resources.get(offset..end).unwrap_or_default().to_vec();
+
+        let mut result = json!({ "resources": page });
+        if end < resources.len() {
+            result["nextCursor"] = json!(end.to_string());
+        }
+
+        Ok(Some(Self::create_success_response(
+            result,
+            request.id.clone(),
+        )))
+    }
+
+    async fn read_resource(&self, kind: ResourceKind, id: &str) -> Result<Value> {
+        match kind {
+            ResourceKind::Dashboard => {
+                Ok(serde_json::to_value(self.client.get_dashboard(id).await?)?)
+            }
+            ResourceKind::Monitor => {
+                let monitor_id = id.parse::<i64>().map_err(|_| {
+                    DatadogError::InvalidInput(format!("Invalid monitor ID: '{}'", id))
+                })?;
+                Ok(serde_json::to_value(
+                    self.client.get_monitor(monitor_id).await?,
+                )?)
+            }
+            ResourceKind::Slo => {
+                let response = self.client.get_slo(id).await?;
+                Ok(response.get("data").cloned().unwrap_or(response))
+            }
+        }
+    }
+
+    pub async fn handle_resources_read(
+        &self,
+        request: &JsonRpcRequest,
+    ) -> Result<Option<JsonRpcResponse>> {
+        // Check if initialized
+        {
+            let initialized = self.initialized.read().await;
+            if !*initialized {
+                let error_response = Self::create_error_response(
+                    -32002,
+                    "Server not initialized".to_string(),
+                    request.id.clone(),
+                );
+                return Ok(Some(error_response));
+            }
+        }
+
+        let Some(uri) = request.params.as_ref().and_then(|p| p["uri"].as_str()) else {
+            let error_response = Self::create_error_response(
+                -32602,
+                "Missing 'uri' parameter".to_string(),
+                request.id.clone(),
+            );
+            return Ok(Some(error_response));
+        };
+
+        let Some((kind, id)) = parse_resource_uri(uri) else {
+            let error_response = Self::create_error_response(
+                -32602,
+                format!("Unknown resource URI: {}", uri),
+                request.id.clone(),
+            );
+            return Ok(Some(error_response));
+        };
+
+        let response = match self.read_resource(kind, id).await {
+            Ok(data) => Self::create_success_response(
+                json!({
+                    "contents": [{
+                        "uri": uri,
+                        "mimeType": RESOURCE_MIME_TYPE,
+                        "text": serde_json::to_string_pretty(&data)?
+                    }]
+                }),
+                request.id.clone(),
+            ),
+            Err(e) => Self::create_error_response(
+                -32603,
+                format!("Failed to read {}: {}", uri, e),
+                request.id.clone(),
+            ),
+        };
+
+        Ok(Some(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_initialized_server() -> Server {
+        let server = Server::new("test_key".to_string(), "test_app_key".to_string(), None).unwrap();
+        *server.initialized.try_write().unwrap() = true;
+        server
+    }
+
+    fn request(method: &str, params: Option<Value>) -> JsonRpcRequest {
+        JsonRpcRequest {
+            method: method.to_string(),
+            params,
+            id: Some(json!(1)),
+        }
+    }
+
+    #[test]
+    fn test_parse_resource_uri() {
+        assert_eq!(
+            parse_resource_uri("datadog://dashboard/abc-def-ghi"),
+            Some((ResourceKind::Dashboard, "abc-def-ghi"))
+        );
+        assert_eq!(
+            parse_resource_uri("datadog://monitor/123"),
+            Some((ResourceKind::Monitor, "123"))
+        );
+        assert_eq!(
+            parse_resource_uri("datadog://slo/abc123"),
+            Some((ResourceKind::Slo, "abc123"))
+        );
+    }
+
+    #[test]
+    fn test_parse_resource_uri_rejects_invalid() {
+        assert_eq!(parse_resource_uri("datadog://dashboard/"), None);
+        assert_eq!(parse_resource_uri("datadog://notebook/1"), None);
+        assert_eq!(parse_resource_uri("datadog://monitor/1/extra"), None);
+        assert_eq!(parse_resource_uri("https://monitor/1"), None);
+    }
+
+    #[test]
+    fn test_resource_entry() {
+        let entry = resource_entry(ResourceKind::Monitor, "42", "CPU high", Some(""));
+        assert_eq!(entry["uri"], "datadog://monitor/42");
+        assert_eq!(entry["name"], "CPU high");
+        assert_eq!(entry["mimeType"], "application/json");
+        assert!(entry.get("description").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resources_read_missing_uri() {
+        let server = create_initialized_server();
+        let response = server
+            .handle_resources_read(&request("resources/read", Some(json!({}))))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(response.error.unwrap().code, -32602);
+    }
+
+    #[tokio::test]
+    async fn test_resources_read_unknown_uri() {
+        let server = create_initialized_server();
+        let response = server
+            .handle_resources_read(&request(
+                "resources/read",
+                Some(json!({"uri": "datadog://widget/1"})),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let error = response.error.unwrap();
+        assert_eq!(error.code, -32602);
+        assert!(error.message.contains("datadog://widget/1"));
+    }
+
+    #[tokio::test]
+    async fn test_resources_list_invalid_cursor() {
+        let server = create_initialized_server();
+        let response = server
+            .handle_resources_list(&request(
+                "resources/list",
+                Some(json!({"cursor": "not-a-number"})),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(response.error.unwrap().code, -32602);
+    }
+}
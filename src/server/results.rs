@@ -0,0 +1,295 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use super::protocol::Server;
+use crate::error::{DatadogError, Result};
+
+/// Default lifetime of a saved result, overridable with DD_RESULTS_TTL (seconds)
+const DEFAULT_RESULTS_TTL_SECS: u64 = 3600;
+
+/// Oldest saved results are dropped beyond this
+const MAX_SAVED_RESULTS: usize = 50;
+
+/// Tools that read or write the store itself and are never recorded as "last result"
+const RESULTS_TOOLS: &[&str] = &["datadog_results_save", "datadog_results_get"];
+
+struct ToolResult {
+    tool: String,
+    arguments: Value,
+    data: Value,
+}
+
+struct SavedResult {
+    result: ToolResult,
+    label: Option<String>,
+    saved_at: Instant,
+}
+
+#[derive(Default)]
+struct Inner {
+    last: Option<ToolResult>,
+    saved: BTreeMap<u64, SavedResult>,
+    next_id: u64,
+}
+
+/// Tool results stashed under short handles ("r1", "r2", ...) so they can be referenced later
+pub struct ResultStore {
+    inner: Mutex<Inner>,
+    ttl: Duration,
+}
+
+impl Default for ResultStore {
+    fn default() -> Self {
+        let ttl = std::env::var("DD_RESULTS_TTL")
+            .ok()
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(DEFAULT_RESULTS_TTL_SECS);
+        Self::new(Duration::from_secs(ttl))
+    }
+}
+
+impl ResultStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            inner: Mutex::new(Inner::default()),
+            ttl,
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    fn handle(id: u64) -> String {
+        format!("r{}", id)
+    }
+
+    /// Accept "r3", "3" or "#3"
+    fn parse_handle(handle: &str) -> Option<u64> {
+        handle
+            .trim()
+            .trim_start_matches('#')
+            .trim_start_matches('r')
+            .parse()
+            .ok()
+    }
+
+    /// Handle, originating call and size of a saved result
+    fn describe(id: u64, saved: &SavedResult, ttl: Duration) -> Value {
+        let result = &saved.result;
+        let items = result.data["data"].as_array().map(Vec::len);
+        let size = serde_json::to_string(&result.data)
+            .map(|s| s.len())
+            .unwrap_or(0);
+
+        json!({
+            "handle": Self::handle(id),
+            "label": saved.label,
+            "tool": result.tool,
+            "arguments": result.arguments,
+            "items": items,
+            "size_bytes": size,
+            "expires_in_secs": ttl.saturating_sub(saved.saved_at.elapsed()).as_secs(),
+        })
+    }
+
+    fn purge_expired(&self, inner: &mut Inner) {
+        inner
+            .saved
+            .retain(|_, saved| saved.saved_at.elapsed() < self.ttl);
+    }
+
+    /// Remember the latest successful tool result so it can be saved afterwards
+    pub async fn record(&self, tool: &str, arguments: &Value, data: &Value) {
+        if RESULTS_TOOLS.contains(&tool) {
+            return;
+        }
+
+        self.inner.lock().await.last = Some(ToolResult {
+            tool: tool.to_string(),
+            arguments: arguments.clone(),
+            data: data.clone(),
+        });
+    }
+
+    /// Save the most recent tool result under a new handle
+    pub async fn save(&self, label: Option<String>) -> Result<Value> {
+        let mut inner = self.inner.lock().await;
+        self.purge_expired(&mut inner);
+
+        let result = inner.last.take().ok_or_else(|| {
+            DatadogError::InvalidInput(
+                "No tool result to save: call a tool first, then save its result".to_string(),
+            )
+        })?;
+
+        while inner.saved.len() >= MAX_SAVED_RESULTS {
+            inner.saved.pop_first();
+        }
+
+        inner.next_id += 1;
+        let id = inner.next_id;
+        let saved = SavedResult {
+            result,
+            label,
+            saved_at: Instant::now(),
+        };
+        let description = Self::describe(id, &saved, self.ttl);
+        inner.saved.insert(id, saved);
+
+        Ok(description)
+    }
+
+    /// Saved result by handle or label, optionally narrowed to a JSON pointer
+    pub async fn get(&self, handle: &str, path: Option<&str>) -> Result<Value> {
+        let mut inner = self.inner.lock().await;
+        self.purge_expired(&mut inner);
+
+        let found = Self::parse_handle(handle)
+            .and_then(|id| Some((id, inner.saved.get(&id)?)))
+            .or_else(|| {
+                inner
+                    .saved
+                    .iter()
+                    .rev()
+                    .find(|(_, s)| s.label.as_deref() == Some(handle))
+                    .map(|(id, s)| (*id, s))
+            });
+        let (id, saved) = found.ok_or_else(|| {
+            DatadogError::InvalidInput(format!(
+                "No saved result '{}' (it may have expired)",
+                handle
+            ))
+        })?;
+
+        let data = match path.filter(|p| !p.is_empty()) {
+            Some(pointer) => saved.result.data.pointer(pointer).ok_or_else(|| {
+                DatadogError::InvalidInput(format!(
+                    "Path '{}' not found in result {}",
+                    pointer,
+                    Self::handle(id)
+                ))
+            })?,
+            None => &saved.result.data,
+        };
+
+        Ok(json!({
+            "data": data,
+            "meta": Self::describe(id, saved, self.ttl),
+        }))
+    }
+
+    /// Descriptions of all live saved results, oldest first
+    pub async fn list(&self) -> Value {
+        let mut inner = self.inner.lock().await;
+        self.purge_expired(&mut inner);
+
+        let saved: Vec<Value> = inner
+            .saved
+            .iter()
+            .map(|(id, s)| Self::describe(*id, s, self.ttl))
+            .collect();
+
+        json!({
+            "data": saved,
+            "meta": {"total": saved.len()}
+        })
+    }
+}
+
+impl Server {
+    pub(super) async fn save_result(&self, params: &Value) -> Result<Value> {
+        let label = params["label"]
+            .as_str()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(String::from);
+
+        Ok(json!({ "data": self.results.save(label).await? }))
+    }
+
+    pub(super) async fn get_result(&self, params: &Value) -> Result<Value> {
+        match params["handle"].as_str().filter(|h| !h.is_empty()) {
+            Some(handle) => self.results.get(handle, params["path"].as_str()).await,
+            None => Ok(self.results.list().await),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn store_with_result() -> ResultStore {
+        let store = ResultStore::new(Duration::from_secs(60));
+        store
+            .record(
+                "datadog_logs_search",
+                &json!({"query": "status:error"}),
+                &json!({"data": [{"message": "boom"}, {"message": "bang"}]}),
+            )
+            .await;
+        store
+    }
+
+    #[tokio::test]
+    async fn test_save_and_get() {
+        let store = store_with_result().await;
+
+        let saved = store.save(Some("errors".to_string())).await.unwrap();
+        assert_eq!(saved["handle"], "r1");
+        assert_eq!(saved["tool"], "datadog_logs_search");
+        assert_eq!(saved["items"], 2);
+
+        let result = store.get("r1", None).await.unwrap();
+        assert_eq!(result["data"]["data"][1]["message"], "bang");
+
+        // Handles accept "#1", and labels resolve too
+        let result = store.get("#1", Some("/data/0/message")).await.unwrap();
+        assert_eq!(result["data"], "boom");
+        assert_eq!(
+            store.get("errors", None).await.unwrap()["meta"]["handle"],
+            "r1"
+        );
+
+        assert!(store.get("r1", Some("/missing")).await.is_err());
+        assert!(store.get("r2", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_save_requires_result() {
+        let store = store_with_result().await;
+        store.save(None).await.unwrap();
+
+        // The last result is consumed by saving it
+        assert!(store.save(None).await.is_err());
+
+        store
+            .record("datadog_results_get", &json!({}), &json!({"data": []}))
+            .await;
+        assert!(store.save(None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expiry_and_eviction() {
+        let store = ResultStore::new(Duration::ZERO);
+        store
+            .record("datadog_hosts_list", &json!({}), &json!({"data": []}))
+            .await;
+        store.save(None).await.unwrap();
+        assert!(store.get("r1", None).await.is_err());
+
+        let store = ResultStore::new(Duration::from_secs(60));
+        for _ in 0..=MAX_SAVED_RESULTS {
+            store
+                .record("datadog_hosts_list", &json!({}), &json!({"data": []}))
+                .await;
+            store.save(None).await.unwrap();
+        }
+        let list = store.list().await;
+        assert_eq!(list["meta"]["total"], MAX_SAVED_RESULTS);
+        assert_eq!(list["data"][0]["handle"], "r2");
+    }
+}
@@ -1,7 +1,21 @@
 use super::protocol::{JsonRpcRequest, JsonRpcResponse, Server};
+use super::registry::registry;
+use super::saved_queries::apply_saved_query;
+use super::validation::validate_arguments;
+use crate::datadog::DatadogClient;
+use crate::datadog::dry_run::DryRun;
 use crate::error::Result;
 use crate::handlers;
+use crate::handlers::common::OutputFormat;
+use crate::redact::{redact, redact_value};
+use crate::telemetry::telemetry;
 use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+
+/// Upper bound for the per-call `timeout_secs` argument
+pub(super) const MAX_TIMEOUT_SECS: u64 = 600;
 
 impl Server {
     pub async fn handle_tool_call(
@@ -45,96 +59,137 @@ impl Server {
             }
         };
 
-        let arguments = &params["arguments"];
+        let Some(tool) = registry().get(tool_name) else {
+            let error_response = Self::create_error_response(
+                -32602,
+                format!("Unknown tool: {}", tool_name),
+                request.id.clone(),
+            );
+            return Ok(Some(error_response));
+        };
 
-        let result = match tool_name {
-            "datadog_metrics_query" => {
-                handlers::metrics::MetricsHandler::query(self.client.clone(), arguments).await
-            }
-            "datadog_logs_search" => {
-                handlers::logs::LogsHandler::search(self.client.clone(), arguments).await
-            }
-            "datadog_monitors_list" => {
-                handlers::monitors::MonitorsHandler::list(
-                    self.client.clone(),
-                    self.cache.clone(),
-                    arguments,
-                )
-                .await
-            }
-            "datadog_monitors_get" => {
-                handlers::monitors::MonitorsHandler::get(self.client.clone(), arguments).await
-            }
-            "datadog_events_query" => {
-                handlers::events::EventsHandler::query(
-                    self.client.clone(),
-                    self.cache.clone(),
-                    arguments,
-                )
-                .await
-            }
-            "datadog_hosts_list" => {
-                handlers::hosts::HostsHandler::list(self.client.clone(), arguments).await
-            }
-            "datadog_dashboards_list" => {
-                handlers::dashboards::DashboardsHandler::list(
-                    self.client.clone(),
-                    self.cache.clone(),
-                    arguments,
-                )
-                .await
-            }
-            "datadog_dashboards_get" => {
-                handlers::dashboards::DashboardsHandler::get(self.client.clone(), arguments).await
-            }
-            "datadog_spans_search" => {
-                handlers::spans::SpansHandler::list(self.client.clone(), arguments).await
+        // Typos and wrong types would otherwise be silently replaced by defaults
+        if let Err(problems) =
+            validate_arguments(&self.tool_input_schema(tool), &params["arguments"])
+        {
+            let error_response = Self::create_error_response(
+                -32602,
+                format!("Invalid arguments for {}: {}", tool_name, problems),
+                request.id.clone(),
+            );
+            return Ok(Some(error_response));
+        }
+
+        // `saved_query` names a DD_SAVED_QUERIES_FILE entry that supplies the query
+        let arguments =
+            match apply_saved_query(&self.saved_queries, tool_name, &params["arguments"]) {
+                Ok(arguments) => arguments,
+                Err(e) => {
+                    let error_response =
+                        Self::create_error_response(-32602, e.to_string(), request.id.clone());
+                    return Ok(Some(error_response));
+                }
+            };
+
+        // Session defaults from datadog_set_context fill in omitted arguments
+        let arguments = &self.context.read().await.apply(tool_name, &arguments);
+
+        // Bounds every Datadog request the tool makes, retries included
+        let timeout = arguments["timeout_secs"]
+            .as_u64()
+            .map(|secs| Duration::from_secs(secs.clamp(1, MAX_TIMEOUT_SECS)));
+
+        // Write tools can be asked to stop short of the write and return it instead
+        let dry_run = tool.is_mutating() && arguments["dry_run"].as_bool().unwrap_or(false);
+
+        let started = Instant::now();
+        let (mut result, warnings) = handlers::common::collect_warnings(async {
+            // `org` picks one of the DD_ORGS clients; omitted, the default client is used
+            let mut server = self.for_org(arguments["org"].as_str())?;
+            // Write tools aren't listed in read-only mode; calling them by name is refused too
+            if tool.is_mutating() {
+                server.client.ensure_writes_allowed()?;
             }
-            "datadog_services_list" => {
-                handlers::services::ServicesHandler::list(self.client.clone(), arguments).await
+            if dry_run {
+                server.client = Arc::new(DryRun::new(server.client.clone()));
             }
-            "datadog_logs_aggregate" => {
-                handlers::logs_aggregate::LogsAggregateHandler::aggregate(
-                    self.client.clone(),
-                    arguments,
-                )
-                .await
+            let result =
+                DatadogClient::with_call_timeout(timeout, tool.execute(&server, arguments)).await;
+            if dry_run {
+                DryRun::outcome(result)
+            } else {
+                result
             }
-            "datadog_logs_timeseries" => {
-                handlers::logs_timeseries::LogsTimeseriesHandler::timeseries(
-                    self.client.clone(),
-                    arguments,
-                )
-                .await
+        })
+        .instrument(tracing::info_span!("tool_call", tool = tool_name))
+        .await;
+
+        let elapsed = started.elapsed();
+        telemetry().record_tool(tool_name, elapsed, result.is_ok());
+        if let Err(e) = &result {
+            telemetry().record_tool_error(tool_name, &redact(&e.to_string()));
+        }
+        tracing::debug!(
+            tool = tool_name,
+            elapsed_ms = elapsed.as_millis() as u64,
+            ok = result.is_ok(),
+            "Tool call finished"
+        );
+
+        if let Ok(data) = &mut result {
+            // Before anything else sees the result, saved results included
+            if tool.is_scrubbed()
+                && let Some(scrubber) = &self.scrubber
+            {
+                scrubber.scrub_value(data);
             }
-            "datadog_rum_events_search" => {
-                handlers::rum::RumHandler::search_events(self.client.clone(), arguments).await
+            // e.g. a reversed from/to that was swapped
+            handlers::common::attach_warnings(data, warnings);
+            let precision = arguments["precision"]
+                .as_u64()
+                .map(|p| p as u32)
+                .or(self.precision);
+            if let Some(precision) = precision {
+                handlers::common::apply_precision(data, precision);
             }
-            _ => {
-                let error_response = Self::create_error_response(
-                    -32602,
-                    format!("Unknown tool: {}", tool_name),
-                    request.id.clone(),
-                );
-                return Ok(Some(error_response));
+            // Keeps large log/span lists from overflowing the caller's context
+            let max_bytes = arguments["max_response_bytes"]
+                .as_u64()
+                .map(|b| b.max(handlers::common::MIN_RESPONSE_BYTES) as usize)
+                .or(self.max_response_bytes);
+            if let Some(max_bytes) = max_bytes {
+                handlers::common::apply_size_budget(data, max_bytes);
             }
-        };
+            self.results.record(tool_name, arguments, data).await;
+        }
+
+        let format = arguments["format"]
+            .as_str()
+            .and_then(OutputFormat::parse)
+            .unwrap_or_default();
 
         let result_content = match result {
             Ok(data) => json!({
                 "content": [{
                     "type": "text",
-                    "text": serde_json::to_string_pretty(&data)
-                        .unwrap_or_else(|_| "Error formatting response".to_string())
+                    "text": handlers::common::render_response(&data, format)
                 }]
             }),
-            Err(e) => json!({
-                "content": [{
-                    "type": "text",
-                    "text": format!("Error: {}", e)
-                }],
-                "isError": true
-            }),
+            Err(e) => {
+                // Errors can quote requests and responses that carry the configured keys
+                let mut details = e.details();
+                redact_value(&mut details);
+                json!({
+                    "content": [{
+                        "type": "text",
+                        "text": redact(&format!("Error [{}]: {}\n{}", e.category(), e, e.suggestion()))
+                    }],
+                    "structuredContent": {
+                        "error": details
+                    },
+                    "isError": true
+                })
+            }
         };
 
         let response = Self::create_success_response(result_content, request.id.clone());
@@ -158,7 +213,15 @@ mod tests {
         Server {
             client: Arc::new(client),
             cache,
+            orgs: Arc::new(Default::default()),
             initialized: Arc::new(RwLock::new(true)),
+            context: Arc::new(RwLock::new(Default::default())),
+            results: Arc::new(Default::default()),
+            saved_queries: Arc::new(Default::default()),
+            scrubber: None,
+            shutdown: Arc::new(Default::default()),
+            precision: None,
+            max_response_bytes: None,
         }
     }
 
@@ -283,6 +346,222 @@ mod tests {
         let content = &result["content"][0]["text"];
         let text = content.as_str().unwrap();
         assert!(text.contains("Error") || text.contains("query"));
+
+        assert_eq!(result["isError"], true);
+        let error = &result["structuredContent"]["error"];
+        assert_eq!(error["category"], "user_fixable");
+        assert_eq!(error["retryable"], false);
+        assert_eq!(error["code"], "invalid_input");
+        assert!(text.starts_with("Error [user_fixable]"));
+    }
+
+    #[tokio::test]
+    async fn test_tool_error_redacts_configured_keys() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let api_key = "00112233445566778899aabbccddeeff";
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(400).set_body_string(format!(
+                r#"{{"errors":["Invalid request with api_key={}"]}}"#,
+                api_key
+            )))
+            .mount(&mock_server)
+            .await;
+
+        let mut server = create_test_server();
+        let client = DatadogClient::new(api_key.to_string(), "app".to_string(), None).unwrap();
+        server.client = Arc::new(client.with_api_url(&mock_server.uri()));
+
+        let request = JsonRpcRequest {
+            method: "tools/call".to_string(),
+            params: Some(json!({"name": "datadog_monitors_list", "arguments": {}})),
+            id: Some(json!(1)),
+        };
+        let response = server.handle_tool_call(&request).await.unwrap().unwrap();
+        let result = response.result.unwrap();
+
+        assert_eq!(result["isError"], true);
+        let rendered = result.to_string();
+        assert!(!rendered.contains(api_key));
+        assert!(rendered.contains("api_key=****eeff"));
+    }
+
+    #[tokio::test]
+    async fn test_route_invalid_arguments() {
+        let server = create_test_server();
+
+        let request = JsonRpcRequest {
+            method: "tools/call".to_string(),
+            params: Some(json!({
+                "name": "datadog_metrics_query",
+                "arguments": {"qurey": "avg:cpu{*}", "form": "1 hour ago"}
+            })),
+            id: Some(json!(1)),
+        };
+
+        let response = server.handle_tool_call(&request).await.unwrap().unwrap();
+        let error = response.error.unwrap();
+        assert_eq!(error.code, -32602);
+        assert!(
+            error
+                .message
+                .starts_with("Invalid arguments for datadog_metrics_query")
+        );
+        assert!(error.message.contains("did you mean 'query'?"));
+        assert!(error.message.contains("Valid arguments:"));
+    }
+
+    #[tokio::test]
+    async fn test_route_write_tool_in_read_only_mode() {
+        let server = create_test_server();
+
+        let listed = server.tool_definitions();
+        let names: Vec<&str> = listed["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|t| t["name"].as_str())
+            .collect();
+        assert!(names.contains(&"datadog_hosts_list"));
+        assert!(!names.contains(&"datadog_hosts_mute"));
+
+        let request = JsonRpcRequest {
+            method: "tools/call".to_string(),
+            params: Some(json!({
+                "name": "datadog_hosts_mute",
+                "arguments": {"host": "web-1"}
+            })),
+            id: Some(json!(1)),
+        };
+
+        let result = server
+            .handle_tool_call(&request)
+            .await
+            .unwrap()
+            .unwrap()
+            .result
+            .unwrap();
+        assert_eq!(result["isError"], true);
+        assert_eq!(
+            result["structuredContent"]["error"]["code"],
+            "writes_disabled"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_route_write_tool_dry_run() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"status": "ok"})))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let mut server = create_test_server();
+        let client = DatadogClient::new("key".to_string(), "app".to_string(), None).unwrap();
+        server.client = Arc::new(client.with_writes(true).with_api_url(&mock_server.uri()));
+
+        let schema = server.tool_input_schema(registry().get("datadog_events_post").unwrap());
+        assert_eq!(schema["properties"]["dry_run"]["type"], "boolean");
+        let tools = server.tool_definitions();
+        let events_post = tools["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|t| t["name"] == "datadog_events_post")
+            .unwrap();
+        assert!(
+            events_post["description"]
+                .as_str()
+                .unwrap()
+                .ends_with(crate::server::schema::WRITE_REQUIREMENT)
+        );
+
+        let request = JsonRpcRequest {
+            method: "tools/call".to_string(),
+            params: Some(json!({
+                "name": "datadog_events_post",
+                "arguments": {"title": "Deploy v42", "text": "Rolled out", "dry_run": true}
+            })),
+            id: Some(json!(1)),
+        };
+        let result = server
+            .handle_tool_call(&request)
+            .await
+            .unwrap()
+            .unwrap()
+            .result
+            .unwrap();
+        assert_ne!(result["isError"], true);
+
+        let data: serde_json::Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(data["data"]["dry_run"], true);
+        assert_eq!(data["data"]["request"]["method"], "POST");
+        assert_eq!(data["data"]["request"]["endpoint"], "/api/v1/events");
+        assert_eq!(data["data"]["request"]["body"]["title"], "Deploy v42");
+    }
+
+    #[tokio::test]
+    async fn test_route_metrics_submit_point_forms() {
+        let mut server = create_test_server();
+
+        // The handler leaves write gating to the router
+        let read_only = JsonRpcRequest {
+            method: "tools/call".to_string(),
+            params: Some(json!({
+                "name": "datadog_metrics_submit",
+                "arguments": {"metric": "experiment.score", "value": 1}
+            })),
+            id: Some(json!(1)),
+        };
+        let result = server
+            .handle_tool_call(&read_only)
+            .await
+            .unwrap()
+            .unwrap()
+            .result
+            .unwrap();
+        assert_eq!(result["isError"], true);
+
+        let client = DatadogClient::new("key".to_string(), "app".to_string(), None).unwrap();
+        server.client = Arc::new(client.with_writes(true));
+
+        // Both point forms the handler accepts must get past schema validation
+        let now = chrono::Utc::now().timestamp();
+        let request = JsonRpcRequest {
+            method: "tools/call".to_string(),
+            params: Some(json!({
+                "name": "datadog_metrics_submit",
+                "arguments": {
+                    "metric": "experiment.score",
+                    "timestamp": now - 60,
+                    "value": 1.5,
+                    "points": [2, {"timestamp": now - 30, "value": 3}],
+                    "dry_run": true
+                }
+            })),
+            id: Some(json!(1)),
+        };
+        let result = server
+            .handle_tool_call(&request)
+            .await
+            .unwrap()
+            .unwrap()
+            .result
+            .unwrap();
+        assert_ne!(result["isError"], true, "{}", result);
+
+        let data: serde_json::Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        let points = &data["data"]["request"]["body"]["series"][0]["points"];
+        assert_eq!(points[0]["value"], 2.0);
+        assert_eq!(points[1]["timestamp"], now - 30);
     }
 
     #[tokio::test]
@@ -0,0 +1,257 @@
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+
+use super::protocol::Server;
+use crate::error::{DatadogError, Result};
+
+/// A named query from DD_SAVED_QUERIES_FILE, used as `saved_query: "<name>"`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedQuery {
+    pub query: String,
+    pub description: Option<String>,
+    /// Tool the query is written for (log search syntax differs from metrics queries)
+    pub tool: Option<String>,
+    /// Further default arguments, e.g. `from`
+    pub arguments: Map<String, Value>,
+}
+
+/// Saved queries by name
+pub type SavedQueries = BTreeMap<String, SavedQuery>;
+
+/// Queries from the JSON file named by DD_SAVED_QUERIES_FILE. Each entry is a query string
+/// or an object with `query`, optional `description` and `tool`, and default arguments:
+///
+/// ```json
+/// {
+///   "checkout-errors": {
+///     "query": "service:checkout status:error",
+///     "tool": "datadog_logs_search",
+///     "from": "4 hours ago"
+///   },
+///   "web-latency": "avg:trace.http.request.duration{service:web}"
+/// }
+/// ```
+pub(super) fn load_saved_queries() -> Result<SavedQueries> {
+    let Ok(path) = std::env::var("DD_SAVED_QUERIES_FILE") else {
+        return Ok(SavedQueries::new());
+    };
+    let invalid = |reason: String| {
+        DatadogError::InvalidInput(format!(
+            "Invalid DD_SAVED_QUERIES_FILE '{}': {}",
+            path, reason
+        ))
+    };
+
+    let content = std::fs::read_to_string(&path).map_err(|e| invalid(e.to_string()))?;
+    let entries: Map<String, Value> =
+        serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+    entries
+        .into_iter()
+        .map(|(name, entry)| {
+            let query =
+                parse_entry(entry).map_err(|reason| invalid(format!("'{}' {}", name, reason)))?;
+            Ok((name, query))
+        })
+        .collect()
+}
+
+fn parse_entry(entry: Value) -> std::result::Result<SavedQuery, &'static str> {
+    let mut arguments = match entry {
+        Value::String(query) => {
+            return Ok(SavedQuery {
+                query,
+                description: None,
+                tool: None,
+                arguments: Map::new(),
+            });
+        }
+        Value::Object(fields) => fields,
+        _ => return Err("must be a query string or an object"),
+    };
+
+    let mut take_string = |key: &str| match arguments.remove(key) {
+        Some(Value::String(value)) => Ok(Some(value)),
+        Some(_) => Err("has a non-string query, description or tool"),
+        None => Ok(None),
+    };
+    let query = take_string("query")?.ok_or("has no 'query'")?;
+    let description = take_string("description")?;
+    let tool = take_string("tool")?;
+
+    Ok(SavedQuery {
+        query,
+        description,
+        tool,
+        arguments,
+    })
+}
+
+/// Expand a `saved_query` argument into `query` plus the saved default arguments;
+/// explicit arguments always win
+pub(super) fn apply_saved_query(
+    queries: &SavedQueries,
+    tool: &str,
+    arguments: &Value,
+) -> Result<Value> {
+    let Some(name) = arguments["saved_query"].as_str() else {
+        return Ok(arguments.clone());
+    };
+    let saved = queries.get(name).ok_or_else(|| {
+        DatadogError::InvalidInput(format!(
+            "Unknown saved query '{}'. Saved queries: {}",
+            name,
+            queries.keys().cloned().collect::<Vec<_>>().join(", ")
+        ))
+    })?;
+    if let Some(expected) = saved.tool.as_deref().filter(|expected| *expected != tool) {
+        return Err(DatadogError::InvalidInput(format!(
+            "Saved query '{}' is written for {}, not {}",
+            name, expected, tool
+        )));
+    }
+
+    let mut arguments = arguments.clone();
+    if arguments["query"].is_null() {
+        arguments["query"] = json!(saved.query);
+    }
+    for (key, value) in &saved.arguments {
+        if arguments[key.as_str()].is_null() {
+            arguments[key.as_str()] = value.clone();
+        }
+    }
+    Ok(arguments)
+}
+
+impl Server {
+    /// Saved queries from DD_SAVED_QUERIES_FILE; Datadog's API has no documented listing of
+    /// saved Log Explorer views, so those are not included
+    pub(super) async fn saved_views(&self, params: &Value) -> Result<Value> {
+        let tool = params["tool"].as_str();
+
+        let data: Vec<Value> = self
+            .saved_queries
+            .iter()
+            .filter(|(_, saved)| {
+                tool.is_none() || saved.tool.is_none() || saved.tool.as_deref() == tool
+            })
+            .map(|(name, saved)| {
+                json!({
+                    "name": name,
+                    "query": saved.query,
+                    "description": saved.description,
+                    "tool": saved.tool,
+                    "arguments": saved.arguments,
+                })
+            })
+            .collect();
+
+        Ok(json!({
+            "data": data,
+            "meta": {"usage": "Pass a name as saved_query to the tool"}
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queries() -> SavedQueries {
+        let entries = json!({
+            "checkout-errors": {
+                "query": "service:checkout status:error",
+                "tool": "datadog_logs_search",
+                "from": "4 hours ago"
+            },
+            "web-latency": "avg:trace.http.request.duration{service:web}"
+        });
+        entries
+            .as_object()
+            .unwrap()
+            .clone()
+            .into_iter()
+            .map(|(name, entry)| (name, parse_entry(entry).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_entry() {
+        let queries = queries();
+        let checkout = &queries["checkout-errors"];
+        assert_eq!(checkout.query, "service:checkout status:error");
+        assert_eq!(checkout.tool.as_deref(), Some("datadog_logs_search"));
+        assert_eq!(checkout.arguments["from"], "4 hours ago");
+        assert!(queries["web-latency"].arguments.is_empty());
+
+        assert!(parse_entry(json!({"from": "1h"})).is_err());
+        assert!(parse_entry(json!({"query": 1})).is_err());
+        assert!(parse_entry(json!(3)).is_err());
+    }
+
+    #[test]
+    fn test_apply_saved_query() {
+        let queries = queries();
+
+        let args = apply_saved_query(
+            &queries,
+            "datadog_logs_search",
+            &json!({"saved_query": "checkout-errors", "limit": 5}),
+        )
+        .unwrap();
+        assert_eq!(args["query"], "service:checkout status:error");
+        assert_eq!(args["from"], "4 hours ago");
+        assert_eq!(args["limit"], 5);
+
+        let args = apply_saved_query(
+            &queries,
+            "datadog_logs_search",
+            &json!({"saved_query": "checkout-errors", "from": "1 hour ago"}),
+        )
+        .unwrap();
+        assert_eq!(args["from"], "1 hour ago");
+
+        let error = apply_saved_query(
+            &queries,
+            "datadog_metrics_query",
+            &json!({"saved_query": "checkout-errors"}),
+        )
+        .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("written for datadog_logs_search")
+        );
+
+        let error = apply_saved_query(
+            &queries,
+            "datadog_logs_search",
+            &json!({"saved_query": "nope"}),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("checkout-errors, web-latency"));
+    }
+
+    #[tokio::test]
+    async fn test_saved_views_filters_by_tool() {
+        let mut server = Server::new("key".to_string(), "app".to_string(), None).unwrap();
+        server.saved_queries = std::sync::Arc::new(queries());
+
+        let all = server.saved_views(&json!({})).await.unwrap();
+        let names: Vec<_> = all["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["checkout-errors", "web-latency"]);
+        assert_eq!(all["data"][0]["arguments"]["from"], "4 hours ago");
+
+        // Queries without a tool are usable with any
+        let metrics = server
+            .saved_views(&json!({"tool": "datadog_metrics_query"}))
+            .await
+            .unwrap();
+        assert_eq!(metrics["data"].as_array().unwrap().len(), 1);
+        assert_eq!(metrics["data"][0]["name"], "web-latency");
+    }
+}
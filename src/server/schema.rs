@@ -1,6 +1,13 @@
 use super::protocol::{JsonRpcRequest, JsonRpcResponse, Server};
+use super::registry::{Tool, registry};
+use super::router::MAX_TIMEOUT_SECS;
 use crate::error::Result;
-use serde_json::json;
+use crate::handlers::common::{MAX_PRECISION, MIN_RESPONSE_BYTES, OutputFormat};
+use serde_json::{Value, json};
+
+/// Appended to the description of every write tool
+pub(super) const WRITE_REQUIREMENT: &str =
+    "Requires DD_MCP_MODE=read_write (DD_ALLOW_WRITES=true is the legacy alias).";
 
 impl Server {
     pub async fn handle_tools_list(
@@ -20,463 +27,124 @@ impl Server {
             }
         }
 
-        // Get tag filter default from environment variable
-        let tag_filter_default = self.client.get_tag_filter().unwrap_or("*");
-        let tag_filter_desc = format!(
-            "Comma-separated tag prefixes to include (e.g., 'env:,service:,version:'). Use '*' for all tags (default), '' (empty) to exclude all tags. Current default: '{}'",
-            tag_filter_default
-        );
+        let response = Self::create_success_response(self.tool_definitions(), request.id.clone());
+        Ok(Some(response))
+    }
 
-        let tools_result = json!({
-            "tools": [
-                {
-                    "name": "datadog_metrics_query",
-                    "description": "Query time series metrics from Datadog. Returns metric data points with timestamps and values. Supports natural language time expressions ('1 hour ago'), ISO8601, and Unix timestamps.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "query": {
-                                "type": "string",
-                                "description": "Metrics query (e.g., 'avg:system.cpu.user{*}')"
-                            },
-                            "from": {
-                                "type": "string",
-                                "description": "Start time (supports natural language like '1 hour ago', ISO8601 timestamps, or Unix timestamps)",
-                                "default": "1 hour ago"
-                            },
-                            "to": {
-                                "type": "string",
-                                "description": "End time (supports natural language like 'now', ISO8601 timestamps, or Unix timestamps)",
-                                "default": "now"
-                            },
-                            "max_points": {
-                                "type": "integer",
-                                "description": "Maximum number of data points to return (downsample if exceeded). Useful for large time ranges to reduce response size. If not specified, returns all points from API."
-                            }
-                        },
-                        "required": ["query"]
-                    }
-                },
-                {
-                    "name": "datadog_logs_search",
-                    "description": "Search log events in Datadog. Returns log entries with timestamps, messages, and metadata. Supports Datadog query syntax and natural language time expressions.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "query": {
-                                "type": "string",
-                                "description": "Log search query"
-                            },
-                            "from": {
-                                "type": "string",
-                                "description": "Start time (supports natural language like '1 hour ago', ISO8601, or Unix timestamps)",
-                                "default": "1 hour ago"
-                            },
-                            "to": {
-                                "type": "string",
-                                "description": "End time (supports natural language like 'now', ISO8601, or Unix timestamps)",
-                                "default": "now"
-                            },
-                            "limit": {
-                                "type": "integer",
-                                "description": "Maximum number of logs to return",
-                                "default": 10
-                            },
-                            "tag_filter": {
-                                "type": "string",
-                                "description": &tag_filter_desc
-                            }
-                        },
-                        "required": ["query"]
-                    }
-                },
-                {
-                    "name": "datadog_monitors_list",
-                    "description": "List all monitors from Datadog. Returns monitor names, types, queries, and states. Supports filtering by tags. Page 0 always fetches fresh data, subsequent pages use cache.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "tags": {
-                                "type": "string",
-                                "description": "Filter by tags (comma-separated)"
-                            },
-                            "monitor_tags": {
-                                "type": "string",
-                                "description": "Filter by monitor tags"
-                            },
-                            "page": {
-                                "type": "integer",
-                                "description": "Page number (0-based). Page 0 always fetches fresh data from Datadog API.",
-                                "default": 0
-                            },
-                            "page_size": {
-                                "type": "integer",
-                                "description": "Number of monitors per page",
-                                "default": 50
-                            }
-                        }
-                    }
-                },
-                {
-                    "name": "datadog_monitors_get",
-                    "description": "Retrieve detailed information about a specific monitor by ID. Returns full monitor configuration, thresholds, notification settings, and current state.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "monitor_id": {
-                                "type": "integer",
-                                "description": "Monitor ID"
-                            }
-                        },
-                        "required": ["monitor_id"]
-                    }
-                },
-                {
-                    "name": "datadog_events_query",
-                    "description": "Query event stream from Datadog. Returns events with titles, text, timestamps, and alert types. Supports filtering by priority, sources, and tags. Page 0 fetches fresh data.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "from": {
-                                "type": "string",
-                                "description": "Start time (supports natural language like '1 hour ago', ISO8601, or Unix timestamps)",
-                                "default": "1 hour ago"
-                            },
-                            "to": {
-                                "type": "string",
-                                "description": "End time (supports natural language like 'now', ISO8601, or Unix timestamps)",
-                                "default": "now"
-                            },
-                            "priority": {
-                                "type": "string",
-                                "description": "Priority filter (normal, low)"
-                            },
-                            "sources": {
-                                "type": "string",
-                                "description": "Sources filter"
-                            },
-                            "tags": {
-                                "type": "string",
-                                "description": "Tags filter"
-                            },
-                            "page": {
-                                "type": "integer",
-                                "description": "Page number (0-based). Page 0 always fetches fresh data from Datadog API.",
-                                "default": 0
-                            },
-                            "page_size": {
-                                "type": "integer",
-                                "description": "Number of events per page",
-                                "default": 50
-                            }
-                        }
-                    }
-                },
-                {
-                    "name": "datadog_hosts_list",
-                    "description": "List infrastructure hosts from Datadog. Returns host names, status, applications, sources, and tags. Supports filtering and sorting by various fields.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "filter": {
-                                "type": "string",
-                                "description": "Host filter query"
-                            },
-                            "from": {
-                                "type": "string",
-                                "description": "From time (supports natural language like '1 hour ago', ISO8601, or Unix timestamps)",
-                                "default": "1 hour ago"
-                            },
-                            "sort_field": {
-                                "type": "string",
-                                "description": "Sort field"
-                            },
-                            "sort_dir": {
-                                "type": "string",
-                                "description": "Sort direction (asc, desc)"
-                            },
-                            "start": {
-                                "type": "integer",
-                                "description": "Starting index for pagination",
-                                "default": 0
-                            },
-                            "count": {
-                                "type": "integer",
-                                "description": "Number of hosts to return (max 1000)",
-                                "default": 100
-                            },
-                            "tag_filter": {
-                                "type": "string",
-                                "description": &tag_filter_desc
-                            }
-                        }
-                    }
-                },
-                {
-                    "name": "datadog_dashboards_list",
-                    "description": "List all dashboards from Datadog. Returns dashboard IDs, titles, and descriptions. Page 0 fetches fresh data, subsequent pages use cache.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "page": {
-                                "type": "integer",
-                                "description": "Page number (0-based). Page 0 fetches fresh data from Datadog API.",
-                                "default": 0
-                            },
-                            "page_size": {
-                                "type": "integer",
-                                "description": "Number of dashboards per page",
-                                "default": 50
-                            }
-                        }
-                    }
-                },
-                {
-                    "name": "datadog_dashboards_get",
-                    "description": "Retrieve full dashboard configuration by ID. Returns title, description, layout type, widgets, template variables, and author information.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "dashboard_id": {
-                                "type": "string",
-                                "description": "Dashboard ID"
-                            }
-                        },
-                        "required": ["dashboard_id"]
-                    }
-                },
-                {
-                    "name": "datadog_spans_search",
-                    "description": "Search APM trace spans from Datadog. Returns span details with timing, service information, and trace IDs. Error stack traces are truncated to 10 lines by default for readability (use full_stack_trace=true for complete traces). Supports cursor-based pagination and sorting.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "query": {
-                                "type": "string",
-                                "description": "Spans search query",
-                                "default": "*"
-                            },
-                            "from": {
-                                "type": "string",
-                                "description": "Start time (e.g., '1 hour ago', timestamp)"
-                            },
-                            "to": {
-                                "type": "string",
-                                "description": "End time (e.g., 'now', timestamp)"
-                            },
-                            "limit": {
-                                "type": "integer",
-                                "description": "Maximum number of spans to return",
-                                "default": 10
-                            },
-                            "cursor": {
-                                "type": "string",
-                                "description": "Pagination cursor"
-                            },
-                            "sort": {
-                                "type": "string",
-                                "description": "Sort order (e.g., 'timestamp')"
-                            },
-                            "page": {
-                                "type": "integer",
-                                "description": "Page number (0-based, for client-side pagination)",
-                                "default": 0
-                            },
-                            "page_size": {
-                                "type": "integer",
-                                "description": "Number of spans per page",
-                                "default": 10
-                            },
-                            "tag_filter": {
-                                "type": "string",
-                                "description": &tag_filter_desc
-                            },
-                            "full_stack_trace": {
-                                "type": "boolean",
-                                "description": "If true, include complete error stack traces. If false (default), truncate to first 10 lines.",
-                                "default": false
-                            }
-                        },
-                        "required": ["from", "to"]
-                    }
-                },
-                {
-                    "name": "datadog_services_list",
-                    "description": "List services from APM service catalog. Returns service names, teams, repositories, integrations, and metadata. Supports environment filtering.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "env": {
-                                "type": "string",
-                                "description": "Filter by environment (e.g., 'production', 'staging')"
-                            },
-                            "page": {
-                                "type": "integer",
-                                "description": "Page number (0-based, for client-side pagination)",
-                                "default": 0
-                            },
-                            "page_size": {
-                                "type": "integer",
-                                "description": "Number of services per page",
-                                "default": 50
-                            }
-                        }
-                    }
-                },
-                {
-                    "name": "datadog_logs_aggregate",
-                    "description": "Aggregate log events into buckets and compute metrics. Returns aggregated data with count, sum, avg, min, max, or percentiles. Supports grouping by log attributes.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "query": {
-                                "type": "string",
-                                "description": "Log search query",
-                                "default": "*"
-                            },
-                            "from": {
-                                "type": "string",
-                                "description": "Start time (e.g., '1 hour ago', timestamp)"
-                            },
-                            "to": {
-                                "type": "string",
-                                "description": "End time (e.g., 'now', timestamp)"
-                            },
-                            "compute": {
-                                "type": "array",
-                                "description": "Array of compute aggregations (count, sum, avg, min, max, pc99)",
-                                "items": {
-                                    "type": "object",
-                                    "properties": {
-                                        "aggregation": {"type": "string"},
-                                        "type": {"type": "string"},
-                                        "interval": {"type": "string"},
-                                        "metric": {"type": "string"}
-                                    }
-                                }
-                            },
-                            "group_by": {
-                                "type": "array",
-                                "description": "Array of fields to group by",
-                                "items": {
-                                    "type": "object",
-                                    "properties": {
-                                        "facet": {"type": "string"},
-                                        "limit": {"type": "integer"},
-                                        "sort": {"type": "object"}
-                                    }
-                                }
-                            },
-                            "timezone": {
-                                "type": "string",
-                                "description": "Timezone for time-based operations (e.g., 'UTC', 'America/New_York')"
-                            }
-                        },
-                        "required": ["from", "to"]
-                    }
-                },
-                {
-                    "name": "datadog_logs_timeseries",
-                    "description": "Generate time series data from log events. Returns bucketed metrics over time with configurable intervals (1m, 5m, 1h). Supports count, sum, avg, and percentile aggregations.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "query": {
-                                "type": "string",
-                                "description": "Log search query",
-                                "default": "*"
-                            },
-                            "from": {
-                                "type": "string",
-                                "description": "Start time (e.g., '1 hour ago', timestamp)"
-                            },
-                            "to": {
-                                "type": "string",
-                                "description": "End time (e.g., 'now', timestamp)"
-                            },
-                            "interval": {
-                                "type": "string",
-                                "description": "Time interval for timeseries (e.g., '1m', '5m', '1h')",
-                                "default": "1h"
-                            },
-                            "aggregation": {
-                                "type": "string",
-                                "description": "Aggregation type (count, sum, avg, min, max, pc99)",
-                                "default": "count"
-                            },
-                            "metric": {
-                                "type": "string",
-                                "description": "Field to aggregate on (for non-count aggregations)"
-                            },
-                            "group_by": {
-                                "type": "array",
-                                "description": "Array of fields to group by",
-                                "items": {
-                                    "type": "object",
-                                    "properties": {
-                                        "facet": {"type": "string"},
-                                        "limit": {"type": "integer"}
-                                    }
-                                }
-                            },
-                            "timezone": {
-                                "type": "string",
-                                "description": "Timezone for time-based operations (e.g., 'UTC', 'America/New_York')"
-                            }
-                        },
-                        "required": ["from", "to"]
-                    }
-                },
-                {
-                    "name": "datadog_rum_events_search",
-                    "description": "Search RUM (Real User Monitoring) events. Returns user experience data including sessions, views, actions, resources, and errors. Supports filtering by application, user behavior, and performance metrics.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "query": {
-                                "type": "string",
-                                "description": "RUM search query (e.g., '@type:session AND @session.type:user', '@view.url_path:/checkout')",
-                                "default": "*"
-                            },
-                            "from": {
-                                "type": "string",
-                                "description": "Start time (supports natural language like '1 hour ago', ISO8601, or Unix timestamps)",
-                                "default": "1 hour ago"
-                            },
-                            "to": {
-                                "type": "string",
-                                "description": "End time (supports natural language like 'now', ISO8601, or Unix timestamps)",
-                                "default": "now"
-                            },
-                            "limit": {
-                                "type": "integer",
-                                "description": "Maximum number of events to return",
-                                "default": 10
-                            },
-                            "cursor": {
-                                "type": "string",
-                                "description": "Pagination cursor from previous response"
-                            },
-                            "sort": {
-                                "type": "string",
-                                "description": "Sort order (e.g., 'timestamp', '-timestamp' for descending)"
-                            },
-                            "tag_filter": {
-                                "type": "string",
-                                "description": &tag_filter_desc
-                            },
-                            "full_stack_trace": {
-                                "type": "boolean",
-                                "description": "If true, include complete error stack traces. If false (default), truncate to first 10 lines.",
-                                "default": false
-                            }
-                        }
-                    }
-                }
-            ]
+    /// `{"tools": [...]}` as returned by tools/list; write tools only in read-write mode
+    pub(super) fn tool_definitions(&self) -> Value {
+        let tools: Vec<Value> = registry()
+            .iter()
+            .filter(|tool| !tool.is_mutating() || self.client.writes_allowed())
+            .map(|tool| self.listed_definition(tool))
+            .collect();
+        json!({ "tools": tools })
+    }
+
+    /// Declared `inputSchema` of a tool, as listed
+    pub(super) fn tool_input_schema(&self, tool: &dyn Tool) -> Value {
+        self.listed_definition(tool)["inputSchema"].take()
+    }
+
+    /// A tool's definition plus the arguments the router handles for every tool
+    fn listed_definition(&self, tool: &dyn Tool) -> Value {
+        let mut definition = tool.definition(self.client.as_ref());
+
+        // Rounding is applied to every tool's result in the router
+        let precision_desc = format!(
+            "Significant digits for floating-point values in the result (e.g., 3 turns 12.345678 into 12.3; integer digits are kept). Current default: {}",
+            self.precision
+                .map_or("full precision".to_string(), |p| p.to_string())
+        );
+        definition["inputSchema"]["properties"]["precision"] = json!({
+            "type": "integer",
+            "description": precision_desc,
+            "minimum": 0,
+            "maximum": MAX_PRECISION
+        });
+        definition["inputSchema"]["properties"]["timeout_secs"] = json!({
+            "type": "integer",
+            "description": "Time budget in seconds for the API requests of this call, retries included; raise it for slow queries over long ranges (default: the server's DD_HTTP_TIMEOUT_SECS per request)",
+            "minimum": 1,
+            "maximum": MAX_TIMEOUT_SECS
+        });
+        let budget_desc = format!(
+            "Cap in bytes on the size of this result; beyond it trailing data items are dropped and meta.truncated, total_items and returned_items say so. Current default: {}",
+            self.max_response_bytes
+                .map_or("no limit".to_string(), |b| b.to_string())
+        );
+        definition["inputSchema"]["properties"]["format"] = json!({
+            "type": "string",
+            "enum": OutputFormat::NAMES,
+            "description": "How the result is written: 'json' (default), or 'markdown'/'csv' for a compact table of the data list with nested fields as dotted columns (results without a list stay JSON)",
+            "default": "json"
+        });
+        definition["inputSchema"]["properties"]["max_response_bytes"] = json!({
+            "type": "integer",
+            "description": budget_desc,
+            "minimum": MIN_RESPONSE_BYTES
         });
 
-        let response = Self::create_success_response(tools_result, request.id.clone());
-        Ok(Some(response))
+        // Write tools stop short of the write under dry_run (see datadog::dry_run)
+        if tool.is_mutating() {
+            let description = definition["description"].as_str().unwrap_or_default();
+            definition["description"] = json!(format!("{} {}", description, WRITE_REQUIREMENT));
+            definition["inputSchema"]["properties"]["dry_run"] = json!({
+                "type": "boolean",
+                "description": "Check the arguments and return the request that would be sent, without sending it. Datadog does not validate the payload",
+                "default": false
+            });
+        }
+
+        // Every tool with a from/to window resolves it through TimeHandler::parse_time;
+        // set_context only stores defaults
+        if definition["inputSchema"]["properties"]["from"].is_object()
+            && tool.name() != "datadog_set_context"
+        {
+            definition["inputSchema"]["properties"]["range"] = json!({
+                "type": "string",
+                "description": "Shorthand for the whole time window, used instead of from/to: 'last 15m', 'last 24h', 'today', 'yesterday', 'this week' (UTC) or Grafana-style 'now-6h'"
+            });
+        }
+
+        // Only offered for tools with a query when DD_SAVED_QUERIES_FILE has entries for them
+        let saved: Vec<&str> = self
+            .saved_queries
+            .iter()
+            .filter(|(_, saved)| saved.tool.as_deref().is_none_or(|t| t == tool.name()))
+            .map(|(name, _)| name.as_str())
+            .collect();
+        if definition["inputSchema"]["properties"]["query"].is_object() && !saved.is_empty() {
+            definition["inputSchema"]["properties"]["saved_query"] = json!({
+                "type": "string",
+                "description": "Name of a saved query supplying 'query' and default arguments (see datadog_saved_views_list)",
+                "enum": saved
+            });
+            // Either argument provides the query
+            if let Some(required) = definition["inputSchema"]["required"].as_array_mut() {
+                required.retain(|name| name != "query");
+            }
+        }
+
+        // Only offered when DD_ORGS configures orgs besides the default one
+        let orgs = self.org_names();
+        if !orgs.is_empty() {
+            let sites: Vec<String> = self
+                .orgs
+                .iter()
+                .map(|(name, org)| format!("{} ({})", name, org.client.site()))
+                .collect();
+            definition["inputSchema"]["properties"]["org"] = json!({
+                "type": "string",
+                "description": format!(
+                    "Datadog organization to query instead of the default one ({}). Available: {}",
+                    self.client.site(),
+                    sites.join(", ")
+                ),
+                "enum": orgs
+            });
+        }
+
+        definition
     }
 }
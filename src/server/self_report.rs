@@ -0,0 +1,332 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::protocol::Server;
+use crate::datadog::DatadogApi;
+use crate::telemetry::{CallStats, ToolError, telemetry};
+
+/// Seconds between reports, overridable with DD_SELF_REPORT_INTERVAL_SECS
+const DEFAULT_INTERVAL_SECS: u64 = 60;
+
+/// Prefix of the submitted metric names
+const METRIC_PREFIX: &str = "mcp_datadog";
+
+/// `service` of the submitted metrics and logs
+const SERVICE: &str = "mcp-datadog";
+
+/// v2 series metric types
+const COUNT: u8 = 1;
+const GAUGE: u8 = 3;
+
+/// Settings of the opt-in self-report (DD_SELF_REPORT)
+struct SelfReport {
+    interval: Duration,
+    /// `service:mcp-datadog` plus DD_SELF_REPORT_TAGS
+    tags: Vec<String>,
+}
+
+impl SelfReport {
+    fn from_env() -> Option<Self> {
+        let enabled = std::env::var("DD_SELF_REPORT")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "true" | "1" | "yes"))
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+
+        let interval = std::env::var("DD_SELF_REPORT_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_INTERVAL_SECS);
+        let tags = std::iter::once(format!("service:{}", SERVICE))
+            .chain(
+                std::env::var("DD_SELF_REPORT_TAGS")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(String::from),
+            )
+            .collect();
+
+        Some(Self {
+            interval: Duration::from_secs(interval),
+            tags,
+        })
+    }
+}
+
+/// Counters as of the last accepted report; each report sends only what changed since
+#[derive(Default)]
+struct Reported {
+    tools: BTreeMap<String, CallStats>,
+    api: BTreeMap<String, CallStats>,
+}
+
+/// Count series of the calls and errors added since `previous`, one per tool or endpoint
+fn delta_series(
+    name: &str,
+    label: &str,
+    current: &BTreeMap<String, CallStats>,
+    previous: &BTreeMap<String, CallStats>,
+    point: impl Fn(&str, u8, f64, Vec<String>) -> Value,
+    tags: &[String],
+) -> Vec<Value> {
+    let mut series = Vec::new();
+    for (key, stats) in current {
+        let before = previous.get(key).cloned().unwrap_or_default();
+        let tags: Vec<String> = tags
+            .iter()
+            .cloned()
+            .chain(std::iter::once(format!("{}:{}", label, key)))
+            .collect();
+
+        let calls = stats.calls.saturating_sub(before.calls);
+        if calls > 0 {
+            series.push(point(
+                &format!("{}.calls", name),
+                COUNT,
+                calls as f64,
+                tags.clone(),
+            ));
+        }
+        let errors = stats.errors.saturating_sub(before.errors);
+        if errors > 0 {
+            series.push(point(
+                &format!("{}.errors", name),
+                COUNT,
+                errors as f64,
+                tags,
+            ));
+        }
+    }
+    series
+}
+
+/// Body of a v2 series submission for the counters since the last report
+fn series_body(
+    now: i64,
+    interval: Duration,
+    uptime: Duration,
+    current: &Reported,
+    previous: &Reported,
+    tags: &[String],
+) -> Value {
+    let point = |metric: &str, kind: u8, value: f64, tags: Vec<String>| {
+        json!({
+            "metric": format!("{}.{}", METRIC_PREFIX, metric),
+            "type": kind,
+            "interval": interval.as_secs(),
+            "points": [{"timestamp": now, "value": value}],
+            "tags": tags,
+        })
+    };
+
+    let mut series = vec![point(
+        "uptime_seconds",
+        GAUGE,
+        uptime.as_secs_f64(),
+        tags.to_vec(),
+    )];
+    series.extend(delta_series(
+        "tool",
+        "tool",
+        &current.tools,
+        &previous.tools,
+        point,
+        tags,
+    ));
+    series.extend(delta_series(
+        "api",
+        "endpoint",
+        &current.api,
+        &previous.api,
+        point,
+        tags,
+    ));
+
+    json!({ "series": series })
+}
+
+/// Logs intake entries for failed tool calls
+fn log_entries(errors: &[ToolError], tags: &[String]) -> Value {
+    errors
+        .iter()
+        .map(|error| {
+            json!({
+                "ddsource": SERVICE,
+                "service": SERVICE,
+                "status": "error",
+                "timestamp": error.timestamp,
+                "message": format!("{} failed: {}", error.tool, error.message),
+                "ddtags": tags
+                    .iter()
+                    .cloned()
+                    .chain(std::iter::once(format!("tool:{}", error.tool)))
+                    .collect::<Vec<_>>()
+                    .join(","),
+                "tool": error.tool,
+            })
+        })
+        .collect()
+}
+
+/// Submit the counters since the last report and the failed calls since then
+async fn report(client: &dyn DatadogApi, config: &SelfReport, reported: &mut Reported) {
+    let telemetry = telemetry();
+    let current = Reported {
+        tools: telemetry.tool_stats(),
+        api: telemetry.api_stats(),
+    };
+    let body = series_body(
+        chrono::Utc::now().timestamp(),
+        config.interval,
+        telemetry.uptime(),
+        &current,
+        reported,
+        &config.tags,
+    );
+
+    // Unsent counts stay pending and go out with the next report
+    match client.submit_series(&body).await {
+        Ok(_) => *reported = current,
+        Err(e) => log::warn!("Self-report: failed to submit metrics: {}", e),
+    }
+
+    let errors = telemetry.take_tool_errors();
+    if !errors.is_empty()
+        && let Err(e) = client
+            .submit_logs(&log_entries(&errors, &config.tags))
+            .await
+    {
+        log::warn!(
+            "Self-report: dropped {} tool error logs: {}",
+            errors.len(),
+            e
+        );
+    }
+}
+
+impl Server {
+    /// With DD_SELF_REPORT set, periodically submit the server's tool and API counters as
+    /// `mcp_datadog.*` metrics and its failed tool calls as logs to the default org
+    pub(super) fn spawn_self_report(&self) {
+        let Some(config) = SelfReport::from_env() else {
+            return;
+        };
+        log::info!(
+            "Reporting server metrics and errors to Datadog every {}s",
+            config.interval.as_secs()
+        );
+
+        let client = self.client.clone();
+        let shutdown = self.shutdown.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(config.interval);
+            interval.tick().await;
+            let mut reported = Reported::default();
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = shutdown.requested() => break,
+                }
+                report(client.as_ref(), &config, &mut reported).await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(calls: u64, errors: u64) -> CallStats {
+        CallStats {
+            calls,
+            errors,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_series_body_sends_deltas() {
+        let previous = Reported {
+            tools: BTreeMap::from([("datadog_logs_search".to_string(), stats(3, 1))]),
+            api: BTreeMap::new(),
+        };
+        let current = Reported {
+            tools: BTreeMap::from([
+                ("datadog_logs_search".to_string(), stats(5, 1)),
+                ("datadog_monitors_list".to_string(), stats(1, 1)),
+            ]),
+            api: BTreeMap::from([("/api/v2/logs".to_string(), stats(2, 0))]),
+        };
+        let tags = vec!["service:mcp-datadog".to_string()];
+
+        let body = series_body(
+            1_700_000_000,
+            Duration::from_secs(60),
+            Duration::from_secs(120),
+            &current,
+            &previous,
+            &tags,
+        );
+        let series: Vec<(String, f64, Value)> = body["series"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| {
+                (
+                    s["metric"].as_str().unwrap().to_string(),
+                    s["points"][0]["value"].as_f64().unwrap(),
+                    s["tags"].clone(),
+                )
+            })
+            .collect();
+
+        assert_eq!(series[0].0, "mcp_datadog.uptime_seconds");
+        assert_eq!(series[0].1, 120.0);
+        // Unchanged error count of datadog_logs_search is left out
+        let names: Vec<&str> = series.iter().map(|s| s.0.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "mcp_datadog.uptime_seconds",
+                "mcp_datadog.tool.calls",
+                "mcp_datadog.tool.calls",
+                "mcp_datadog.tool.errors",
+                "mcp_datadog.api.calls",
+            ]
+        );
+        assert_eq!(series[1].1, 2.0);
+        assert_eq!(
+            series[1].2,
+            json!(["service:mcp-datadog", "tool:datadog_logs_search"])
+        );
+        assert_eq!(series[4].2[1], "endpoint:/api/v2/logs");
+        assert_eq!(body["series"][1]["type"], COUNT);
+        assert_eq!(body["series"][1]["interval"], 60);
+    }
+
+    #[test]
+    fn test_log_entries() {
+        let errors = [ToolError {
+            tool: "datadog_logs_search".to_string(),
+            message: "Request timed out".to_string(),
+            timestamp: 1_700_000_000_000,
+        }];
+        let entries = log_entries(&errors, &["service:mcp-datadog".to_string()]);
+
+        assert_eq!(entries[0]["status"], "error");
+        assert_eq!(
+            entries[0]["message"],
+            "datadog_logs_search failed: Request timed out"
+        );
+        assert_eq!(
+            entries[0]["ddtags"],
+            "service:mcp-datadog,tool:datadog_logs_search"
+        );
+    }
+}
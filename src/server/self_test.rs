@@ -0,0 +1,399 @@
+use serde_json::{Value, json};
+use std::collections::HashSet;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+
+use crate::error::{DatadogError, Result};
+
+/// Protocol version requested in the handshake
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Longest wait for a single response before the server is considered hung
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// JSON-RPC client driving a server child process over stdio, as an MCP client would
+pub struct StdioClient {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    next_id: u64,
+}
+
+impl StdioClient {
+    /// Spawn `command` with piped stdin/stdout; stderr (logs) is passed through
+    pub fn spawn(mut command: Command) -> Result<Self> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| DatadogError::InvalidInput("Server process has no stdin".to_string()))?;
+        let stdout = child.stdout.take().ok_or_else(|| {
+            DatadogError::InvalidInput("Server process has no stdout".to_string())
+        })?;
+
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+            next_id: 0,
+        })
+    }
+
+    async fn send(&mut self, message: &Value) -> Result<()> {
+        self.stdin.write_all(message.to_string().as_bytes()).await?;
+        self.stdin.write_all(b"\n").await?;
+        self.stdin.flush().await?;
+        Ok(())
+    }
+
+    /// Send a request and wait for the response carrying its id
+    pub async fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.send(&json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}))
+            .await?;
+
+        loop {
+            let line = tokio::time::timeout(RESPONSE_TIMEOUT, self.stdout.next_line())
+                .await
+                .map_err(|_| DatadogError::TimeoutError)??
+                .ok_or_else(|| {
+                    DatadogError::InvalidInput(format!(
+                        "Server exited before answering '{}'",
+                        method
+                    ))
+                })?;
+
+            let message: Value = serde_json::from_str(&line)?;
+            if message["id"] == json!(id) {
+                return Ok(message);
+            }
+        }
+    }
+
+    pub async fn notify(&mut self, method: &str) -> Result<()> {
+        self.send(&json!({"jsonrpc": "2.0", "method": method}))
+            .await
+    }
+
+    pub async fn shutdown(mut self) -> Result<()> {
+        drop(self.stdin);
+        if tokio::time::timeout(Duration::from_secs(5), self.child.wait())
+            .await
+            .is_err()
+        {
+            self.child.kill().await?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of calling one tool
+#[derive(Debug)]
+pub struct ToolCheck {
+    pub name: String,
+    /// The response was a well-formed tools/call result
+    pub conforms: bool,
+    /// The tool reported an error (`isError`), e.g. the API rejected placeholder arguments
+    pub is_error: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Default)]
+pub struct ConformanceReport {
+    pub server_info: Value,
+    pub tool_count: usize,
+    pub tools: Vec<ToolCheck>,
+    /// Protocol violations found in the handshake, tool list or tool responses
+    pub problems: Vec<String>,
+}
+
+impl ConformanceReport {
+    pub fn passed(&self) -> bool {
+        self.problems.is_empty() && self.tools.iter().all(|t| t.conforms)
+    }
+
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!(
+            "{} {} — {} tools listed, {} called",
+            self.server_info["name"].as_str().unwrap_or("?"),
+            self.server_info["version"].as_str().unwrap_or("?"),
+            self.tool_count,
+            self.tools.len()
+        )];
+
+        for problem in &self.problems {
+            lines.push(format!("FAIL  {}", problem));
+        }
+        for tool in &self.tools {
+            let status = match (tool.conforms, tool.is_error) {
+                (false, _) => "FAIL",
+                (true, true) => "ERR ",
+                (true, false) => "OK  ",
+            };
+            lines.push(format!("{}  {}: {}", status, tool.name, tool.detail));
+        }
+
+        lines.push(if self.passed() {
+            "Conformance check passed".to_string()
+        } else {
+            "Conformance check failed".to_string()
+        });
+        lines.join("\n")
+    }
+}
+
+/// Arguments satisfying a tool's required properties, for calls that only check the protocol
+pub fn placeholder_arguments(input_schema: &Value) -> Value {
+    let mut arguments = json!({});
+
+    for name in input_schema["required"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+    {
+        let property = &input_schema["properties"][name];
+        let value = if let Some(first) = property["enum"].as_array().and_then(|e| e.first()) {
+            first.clone()
+        } else {
+            match (name, property["type"].as_str()) {
+                ("from", _) => json!("1 hour ago"),
+                ("to", _) => json!("now"),
+                (_, Some("integer" | "number")) => json!(1),
+                (_, Some("boolean")) => json!(false),
+                (_, Some("array")) => json!([]),
+                (_, Some("object")) => json!({}),
+                _ => json!("test"),
+            }
+        };
+        arguments[name] = value;
+    }
+
+    arguments
+}
+
+/// Problems with a tools/list entry
+fn check_tool_definition(tool: &Value) -> Vec<String> {
+    let name = tool["name"].as_str().unwrap_or("<unnamed>");
+    let schema = &tool["inputSchema"];
+    let mut problems = Vec::new();
+
+    if !name.starts_with("datadog_") {
+        problems.push(format!("tool '{}' is not prefixed with 'datadog_'", name));
+    }
+    if tool["description"].as_str().is_none_or(str::is_empty) {
+        problems.push(format!("tool '{}' has no description", name));
+    }
+    if schema["type"] != "object" || !schema["properties"].is_object() {
+        problems.push(format!(
+            "tool '{}' inputSchema is not an object schema",
+            name
+        ));
+    }
+    for required in schema["required"].as_array().into_iter().flatten() {
+        let required = required.as_str().unwrap_or_default();
+        if schema["properties"].get(required).is_none() {
+            problems.push(format!(
+                "tool '{}' requires undeclared property '{}'",
+                name, required
+            ));
+        }
+    }
+
+    problems
+}
+
+/// Check a tools/call response; Ok carries (is_error, detail)
+fn check_tool_response(response: &Value) -> std::result::Result<(bool, String), String> {
+    if let Some(error) = response.get("error") {
+        return Err(format!("JSON-RPC error {}", error));
+    }
+
+    let result = &response["result"];
+    let text = result["content"]
+        .as_array()
+        .and_then(|c| c.first())
+        .filter(|c| c["type"] == "text")
+        .and_then(|c| c["text"].as_str())
+        .ok_or_else(|| "result has no text content".to_string())?;
+
+    if result["isError"] == json!(true) {
+        if !result["structuredContent"]["error"]["category"].is_string() {
+            return Err("error result has no structured error category".to_string());
+        }
+        return Ok((true, text.lines().next().unwrap_or_default().to_string()));
+    }
+
+    let data: Value =
+        serde_json::from_str(text).map_err(|e| format!("result text is not JSON: {}", e))?;
+    let items = data["data"]
+        .as_array()
+        .map(|d| format!("{} items", d.len()))
+        .unwrap_or_else(|| "ok".to_string());
+    Ok((false, items))
+}
+
+/// Handshake, list tools, then call every tool. `arguments` maps tool names to arguments
+/// used instead of placeholders.
+pub async fn run(client: &mut StdioClient, arguments: &Value) -> Result<ConformanceReport> {
+    let mut report = ConformanceReport::default();
+
+    let initialize = client
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": {},
+                "clientInfo": {"name": "mcp-datadog-self-test", "version": env!("CARGO_PKG_VERSION")}
+            }),
+        )
+        .await?;
+    let result = &initialize["result"];
+    if result["protocolVersion"] != PROTOCOL_VERSION {
+        report
+            .problems
+            .push(format!("initialize returned {}", initialize));
+    }
+    if !result["capabilities"]["tools"].is_object() {
+        report
+            .problems
+            .push("initialize does not advertise the tools capability".to_string());
+    }
+    report.server_info = result["serverInfo"].clone();
+    client.notify("notifications/initialized").await?;
+
+    let list = client.request("tools/list", json!({})).await?;
+    let tools = list["result"]["tools"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    if tools.is_empty() {
+        report
+            .problems
+            .push("tools/list returned no tools".to_string());
+    }
+    report.tool_count = tools.len();
+
+    let mut names = HashSet::new();
+    for tool in &tools {
+        report.problems.extend(check_tool_definition(tool));
+        let name = tool["name"].as_str().unwrap_or_default().to_string();
+        if !names.insert(name.clone()) {
+            report
+                .problems
+                .push(format!("tool '{}' is listed twice", name));
+        }
+
+        let call_arguments = arguments
+            .get(&name)
+            .cloned()
+            .unwrap_or_else(|| placeholder_arguments(&tool["inputSchema"]));
+        let response = client
+            .request(
+                "tools/call",
+                json!({"name": name, "arguments": call_arguments}),
+            )
+            .await?;
+
+        report.tools.push(match check_tool_response(&response) {
+            Ok((is_error, detail)) => ToolCheck {
+                name,
+                conforms: true,
+                is_error,
+                detail,
+            },
+            Err(detail) => ToolCheck {
+                name,
+                conforms: false,
+                is_error: true,
+                detail,
+            },
+        });
+    }
+
+    Ok(report)
+}
+
+/// `--self-test`: run this binary as a stdio server and check it end to end against the
+/// configured Datadog site. Writes stay disabled in the child, so only reads reach the API.
+pub async fn self_test() -> Result<bool> {
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .env("MCP_TRANSPORT", "stdio")
+        .env("DD_MCP_MODE", "read_only");
+
+    let mut client = StdioClient::spawn(command)?;
+    let report = run(&mut client, &json!({})).await?;
+    client.shutdown().await?;
+
+    println!("{}", report.summary());
+    Ok(report.passed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholder_arguments() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "query": {"type": "string"},
+                "from": {"type": "string"},
+                "monitor_id": {"type": "integer"},
+                "sort": {"type": "string", "enum": ["asc", "desc"]},
+                "limit": {"type": "integer"}
+            },
+            "required": ["query", "from", "monitor_id", "sort"]
+        });
+
+        assert_eq!(
+            placeholder_arguments(&schema),
+            json!({"query": "test", "from": "1 hour ago", "monitor_id": 1, "sort": "asc"})
+        );
+    }
+
+    #[test]
+    fn test_check_tool_definition() {
+        let tool = json!({
+            "name": "datadog_logs_search",
+            "description": "Search logs",
+            "inputSchema": {"type": "object", "properties": {"query": {}}, "required": ["query"]}
+        });
+        assert!(check_tool_definition(&tool).is_empty());
+
+        let tool = json!({
+            "name": "logs_search",
+            "inputSchema": {"type": "object", "properties": {}, "required": ["query"]}
+        });
+        assert_eq!(check_tool_definition(&tool).len(), 3);
+    }
+
+    #[test]
+    fn test_check_tool_response() {
+        let ok = json!({"result": {"content": [{"type": "text", "text": "{\"data\": [1, 2]}"}]}});
+        assert_eq!(check_tool_response(&ok), Ok((false, "2 items".to_string())));
+
+        let tool_error = json!({"result": {
+            "content": [{"type": "text", "text": "Error [user_fixable]: Invalid input: x"}],
+            "structuredContent": {"error": {"category": "user_fixable"}},
+            "isError": true
+        }});
+        assert_eq!(
+            check_tool_response(&tool_error),
+            Ok((true, "Error [user_fixable]: Invalid input: x".to_string()))
+        );
+
+        assert!(check_tool_response(&json!({"error": {"code": -32603}})).is_err());
+        assert!(check_tool_response(&json!({"result": {}})).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tokio::sync::{Notify, watch};
+
+/// How long in-flight requests get to finish once shutdown starts
+pub(super) const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// Shutdown state shared by the transports, the cache cleanup task and signal handling
+pub struct Shutdown {
+    requested: watch::Sender<bool>,
+    in_flight: AtomicUsize,
+    idle: Notify,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self {
+            requested: watch::Sender::new(false),
+            in_flight: AtomicUsize::new(0),
+            idle: Notify::new(),
+        }
+    }
+}
+
+/// Marks a request as in flight until dropped
+pub(super) struct InFlight(Arc<Shutdown>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+impl Shutdown {
+    /// Start shutting down; transports stop taking new requests
+    pub fn trigger(&self) {
+        self.requested.send_replace(true);
+    }
+
+    /// Resolves once shutdown has been triggered
+    pub async fn requested(&self) {
+        let mut receiver = self.requested.subscribe();
+        // Only fails if the sender is dropped, which `self` prevents
+        let _ = receiver.wait_for(|requested| *requested).await;
+    }
+
+    /// Resolves `SHUTDOWN_GRACE` after shutdown was triggered
+    pub(super) async fn grace_expired(&self) {
+        self.requested().await;
+        tokio::time::sleep(SHUTDOWN_GRACE).await;
+    }
+
+    pub(super) fn track(self: &Arc<Self>) -> InFlight {
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        InFlight(self.clone())
+    }
+
+    /// Wait up to `timeout` for in-flight requests to finish; false if some were cut off
+    pub(super) async fn drain(&self, timeout: Duration) -> bool {
+        let idle = async {
+            loop {
+                let notified = self.idle.notified();
+                if self.in_flight.load(Ordering::Acquire) == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        tokio::time::timeout(timeout, idle).await.is_ok()
+    }
+
+    /// Trigger shutdown on SIGINT (Ctrl+C) or, on Unix, SIGTERM
+    pub(super) fn spawn_signal_handler(self: &Arc<Self>) {
+        let shutdown = self.clone();
+        tokio::spawn(async move {
+            termination_signal().await;
+            log::info!("Shutdown signal received");
+            shutdown.trigger();
+        });
+    }
+}
+
+#[cfg(unix)]
+fn termination_signal() -> impl Future<Output = ()> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    async {
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = sigterm.recv() => {}
+                }
+            }
+            Err(e) => {
+                log::warn!("Failed to install SIGTERM handler: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn termination_signal() -> impl Future<Output = ()> {
+    async {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain_waits_for_in_flight() {
+        let shutdown = Arc::new(Shutdown::default());
+        assert!(shutdown.drain(Duration::from_millis(10)).await);
+
+        let guard = shutdown.track();
+        assert!(!shutdown.drain(Duration::from_millis(10)).await);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(guard);
+        });
+        assert!(shutdown.drain(Duration::from_secs(5)).await);
+    }
+
+    #[tokio::test]
+    async fn test_requested_resolves_after_trigger() {
+        let shutdown = Arc::new(Shutdown::default());
+        let waiter = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.requested().await }
+        });
+        shutdown.trigger();
+        tokio::time::timeout(Duration::from_secs(5), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Later waiters resolve at once
+        tokio::time::timeout(Duration::from_secs(5), shutdown.requested())
+            .await
+            .unwrap();
+    }
+}
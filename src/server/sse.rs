@@ -0,0 +1,370 @@
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Body, Bytes, Frame, Incoming};
+use hyper::header::{self, HeaderValue};
+use hyper::{Method, Request, Response, StatusCode};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::net::TcpListener;
+use tokio::sync::{RwLock, mpsc};
+
+use super::http::{
+    accept_loop, allowed_origins_from_env, auth_token_from_env, authorized, origin_allowed,
+    unauthorized,
+};
+use super::protocol::{JsonRpcRequest, Server};
+use crate::error::Result;
+
+/// Endpoint opening the server-to-client event stream
+pub const SSE_ENDPOINT: &str = "/sse";
+
+/// Endpoint receiving client-to-server JSON-RPC messages
+pub const MESSAGES_ENDPOINT: &str = "/messages";
+
+/// Buffered events per session before senders wait
+const SSE_CHANNEL_CAPACITY: usize = 64;
+
+/// Interval between keepalive comments; also detects disconnected clients
+const KEEPALIVE_INTERVAL_SECS: u64 = 30;
+
+/// Maximum accepted message body size (4 MiB)
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+type SseResponse = Response<BoxBody<Bytes, Infallible>>;
+
+/// Event stream body fed by a session channel; ends once every sender is dropped
+struct EventStream {
+    rx: mpsc::Receiver<Bytes>,
+}
+
+impl Body for EventStream {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<std::result::Result<Frame<Bytes>, Infallible>>> {
+        self.rx
+            .poll_recv(cx)
+            .map(|event| event.map(|b| Ok(Frame::data(b))))
+    }
+}
+
+fn sse_event(event: &str, data: &str) -> Bytes {
+    Bytes::from(format!("event: {}\ndata: {}\n\n", event, data))
+}
+
+fn status_response(status: StatusCode) -> SseResponse {
+    let mut response = Response::new(Full::new(Bytes::new()).boxed());
+    *response.status_mut() = status;
+    response
+}
+
+fn session_from_query(query: Option<&str>) -> Option<&str> {
+    query?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("sessionId="))
+        .filter(|id| !id.is_empty())
+}
+
+/// Legacy MCP SSE transport (2024-11-05): `GET /sse` event stream plus `POST /messages`
+pub struct SseTransport {
+    server: Arc<Server>,
+    /// Each session's event stream and view of the server, keyed by session id
+    sessions: RwLock<HashMap<String, (mpsc::Sender<Bytes>, Server)>>,
+    allowed_origins: Vec<String>,
+    auth_token: Option<String>,
+}
+
+impl SseTransport {
+    pub fn new(server: Server) -> Self {
+        Self {
+            server: Arc::new(server),
+            sessions: RwLock::new(HashMap::new()),
+            allowed_origins: allowed_origins_from_env(),
+            auth_token: None,
+        }
+    }
+
+    pub async fn serve(mut self, addr: SocketAddr) -> Result<()> {
+        self.auth_token = auth_token_from_env(addr)?;
+        let listener = TcpListener::bind(addr).await?;
+        log::info!(
+            "SSE transport listening on http://{}{}",
+            listener.local_addr()?,
+            SSE_ENDPOINT
+        );
+
+        self.server.spawn_cache_cleanup();
+        let transport = Arc::new(self);
+
+        let shutdown = transport.server.shutdown.clone();
+        accept_loop(listener, shutdown, move |req| {
+            let transport = transport.clone();
+            async move { transport.handle(req).await }
+        })
+        .await
+    }
+
+    async fn handle(self: &Arc<Self>, req: Request<Incoming>) -> SseResponse {
+        let origin = req
+            .headers()
+            .get(header::ORIGIN)
+            .and_then(|v| v.to_str().ok());
+        if !origin_allowed(origin, &self.allowed_origins) {
+            return status_response(StatusCode::FORBIDDEN);
+        }
+
+        let authorization = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok());
+        if !authorized(authorization, self.auth_token.as_deref()) {
+            return unauthorized(Full::new(Bytes::new()).boxed());
+        }
+
+        match (req.method(), req.uri().path()) {
+            (&Method::GET, SSE_ENDPOINT) => self.open_stream().await,
+            (&Method::POST, MESSAGES_ENDPOINT) => {
+                let session_id = session_from_query(req.uri().query()).map(String::from);
+                let status = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+                    .collect()
+                    .await
+                {
+                    Ok(collected) => {
+                        self.handle_message(session_id.as_deref(), &collected.to_bytes())
+                            .await
+                    }
+                    Err(_) => StatusCode::PAYLOAD_TOO_LARGE,
+                };
+                status_response(status)
+            }
+            (_, SSE_ENDPOINT | MESSAGES_ENDPOINT) => {
+                status_response(StatusCode::METHOD_NOT_ALLOWED)
+            }
+            _ => status_response(StatusCode::NOT_FOUND),
+        }
+    }
+
+    /// Open a session stream; the first event tells the client where to POST messages
+    async fn open_stream(self: &Arc<Self>) -> SseResponse {
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let (tx, rx) = mpsc::channel(SSE_CHANNEL_CAPACITY);
+
+        let endpoint = format!("{}?sessionId={}", MESSAGES_ENDPOINT, session_id);
+        let _ = tx.try_send(sse_event("endpoint", &endpoint));
+        self.sessions
+            .write()
+            .await
+            .insert(session_id.clone(), (tx.clone(), self.server.for_session()));
+
+        let transport = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(tokio::time::Duration::from_secs(KEEPALIVE_INTERVAL_SECS));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if tx.send(Bytes::from_static(b": ping\n\n")).await.is_err() {
+                    transport.sessions.write().await.remove(&session_id);
+                    log::debug!("SSE session {} disconnected", session_id);
+                    break;
+                }
+            }
+        });
+
+        let mut response = Response::new(EventStream { rx }.boxed());
+        let headers = response.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/event-stream"),
+        );
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        response
+    }
+
+    /// Accept a client message; responses are delivered asynchronously on the event stream
+    async fn handle_message(&self, session_id: Option<&str>, body: &[u8]) -> StatusCode {
+        let Some(session_id) = session_id else {
+            return StatusCode::BAD_REQUEST;
+        };
+        let Some((tx, server)) = self.sessions.read().await.get(session_id).cloned() else {
+            return StatusCode::NOT_FOUND;
+        };
+
+        let messages = match serde_json::from_slice::<Value>(body) {
+            Ok(Value::Array(items)) => items,
+            Ok(single) => vec![single],
+            Err(_) => return StatusCode::BAD_REQUEST,
+        };
+
+        tokio::spawn(async move {
+            for message in messages {
+                // Client responses to server requests carry no method and need no reply
+                if message.get("method").is_none() {
+                    continue;
+                }
+
+                let id = message.get("id").cloned();
+                let response = match serde_json::from_value::<JsonRpcRequest>(message) {
+                    Ok(request) => server.process_request_or_error(request).await,
+                    Err(e) => Some(Server::create_error_response(
+                        -32600,
+                        format!("Invalid Request: {}", e),
+                        id,
+                    )),
+                };
+
+                if let Some(response) = response
+                    && let Ok(data) = serde_json::to_string(&response)
+                    && tx.send(sse_event("message", &data)).await.is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        StatusCode::ACCEPTED
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_transport() -> Arc<SseTransport> {
+        let server = Server::new("test_key".to_string(), "test_app_key".to_string(), None).unwrap();
+        Arc::new(SseTransport::new(server))
+    }
+
+    async fn next_event(body: &mut BoxBody<Bytes, Infallible>) -> String {
+        let frame = body.frame().await.unwrap().unwrap();
+        String::from_utf8(frame.into_data().unwrap().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_session_from_query() {
+        assert_eq!(session_from_query(Some("sessionId=abc")), Some("abc"));
+        assert_eq!(session_from_query(Some("x=1&sessionId=abc")), Some("abc"));
+        assert_eq!(session_from_query(Some("sessionId=")), None);
+        assert_eq!(session_from_query(None), None);
+    }
+
+    #[test]
+    fn test_sse_event_format() {
+        let event = sse_event("message", "{}");
+        assert_eq!(&event[..], b"event: message\ndata: {}\n\n");
+    }
+
+    #[tokio::test]
+    async fn test_open_stream_sends_endpoint_event() {
+        let transport = create_transport();
+        let response = transport.open_stream().await;
+
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+
+        let mut body = response.into_body();
+        let event = next_event(&mut body).await;
+        assert!(event.starts_with("event: endpoint\ndata: /messages?sessionId="));
+        assert_eq!(transport.sessions.read().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_message_response_delivered_on_stream() {
+        let transport = create_transport();
+        let mut body = transport.open_stream().await.into_body();
+        next_event(&mut body).await;
+
+        let session_id = transport
+            .sessions
+            .read()
+            .await
+            .keys()
+            .next()
+            .cloned()
+            .unwrap();
+        let message = json!({
+            "jsonrpc": "2.0",
+            "method": "initialize",
+            "params": {"protocolVersion": "2024-11-05"},
+            "id": 1
+        });
+
+        let status = transport
+            .handle_message(Some(&session_id), &serde_json::to_vec(&message).unwrap())
+            .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+
+        let event = next_event(&mut body).await;
+        assert!(event.starts_with("event: message\n"));
+        assert!(event.contains("\"protocolVersion\":\"2024-11-05\""));
+    }
+
+    #[tokio::test]
+    async fn test_context_is_per_session() {
+        let transport = create_transport();
+        let mut first_body = transport.open_stream().await.into_body();
+        let first = next_event(&mut first_body).await;
+        let first = first.trim_end().rsplit("sessionId=").next().unwrap();
+        let _second = transport.open_stream().await;
+
+        let message = json!([
+            {"jsonrpc": "2.0", "method": "notifications/initialized"},
+            {
+                "jsonrpc": "2.0",
+                "method": "tools/call",
+                "params": {"name": "datadog_set_context", "arguments": {"team": "payments"}},
+                "id": 1
+            }
+        ]);
+        transport
+            .handle_message(Some(first), &serde_json::to_vec(&message).unwrap())
+            .await;
+        next_event(&mut first_body).await;
+
+        let sessions = transport.sessions.read().await;
+        for (id, (_, server)) in sessions.iter() {
+            let team = server.context.read().await.team.clone();
+            let expected = (id == first).then(|| "payments".to_string());
+            assert_eq!(team, expected);
+        }
+        assert_eq!(sessions.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_message_unknown_session() {
+        let transport = create_transport();
+        let status = transport.handle_message(Some("missing"), b"{}").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let status = transport.handle_message(None, b"{}").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_message_parse_error() {
+        let transport = create_transport();
+        let _stream = transport.open_stream().await;
+        let session_id = transport
+            .sessions
+            .read()
+            .await
+            .keys()
+            .next()
+            .cloned()
+            .unwrap();
+
+        let status = transport.handle_message(Some(&session_id), b"{oops").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
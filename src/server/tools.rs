@@ -0,0 +1,2892 @@
+use futures::FutureExt;
+use serde_json::json;
+
+use super::registry::{HandlerTool, ToolRegistry};
+use crate::datadog::DatadogApi;
+use crate::handlers;
+
+/// Tag filter defaults come from DD_TAG_FILTER, overridable per product
+fn tag_filter_description(client: &dyn DatadogApi, product: &str) -> String {
+    format!(
+        "Comma-separated tag patterns: prefixes ('env:,service:'), '*-canary' (suffix), '*kube*' (contains) or 're:^env:(prod|staging)$' (regex, no commas). Prefix a pattern with '!' to drop matching tags instead (e.g., '!kube_'); with only '!' patterns every other tag is kept. Use '*' for all tags (default), '' (empty) to exclude all tags. Current default: '{}'",
+        client.get_tag_filter_for(product).unwrap_or("*")
+    )
+}
+
+/// `fields` argument of list tools, applied by `ResponseFilter::project_fields`
+fn fields_property(example: &str) -> serde_json::Value {
+    json!({
+        "type": "array",
+        "items": {"type": "string"},
+        "description": format!(
+            "Only return these fields of each item, as dotted paths (e.g., {}). Omit for all fields",
+            example
+        )
+    })
+}
+
+/// Arguments shared by datadog_logs_metric_suggest and datadog_logs_metric_create
+fn logs_metric_properties() -> serde_json::Value {
+    json!({
+        "query": {
+            "type": "string",
+            "description": "Log search query the metric should count (e.g., 'service:web status:error')",
+            "default": "*"
+        },
+        "compute": {
+            "type": "array",
+            "description": "Same shape as datadog_logs_aggregate compute; the first entry is used (count, sum, avg, min, max, median, pc75-pc99)",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "aggregation": {"type": "string"},
+                    "metric": {"type": "string"}
+                }
+            }
+        },
+        "group_by": {
+            "type": "array",
+            "description": "Facets to keep as metric tags (same shape as datadog_logs_aggregate group_by)",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "facet": {"type": "string"}
+                }
+            }
+        },
+        "metric_name": {
+            "type": "string",
+            "description": "Name for the new metric (default: derived from the query, e.g., 'logs.service_web_status_error.count')"
+        }
+    })
+}
+
+/// Every tool the server exposes, each definition next to the code that runs it.
+/// Registration order is the tools/list order.
+pub(super) fn register_tools(registry: &mut ToolRegistry) {
+    registry.register(HandlerTool::new(
+        "datadog_metrics_query",
+        |_| {
+            json!({
+                "description": "Query time series metrics from Datadog. Returns metric data points with timestamps and values. Supports natural language time expressions ('1 hour ago'), ISO8601, and Unix timestamps.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Metrics query (e.g., 'avg:system.cpu.user{*}')"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (supports natural language like '1 hour ago', ISO8601 timestamps, or Unix timestamps)",
+                            "default": "1 hour ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time (supports natural language like 'now', ISO8601 timestamps, or Unix timestamps)",
+                            "default": "now"
+                        },
+                        "max_points": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Maximum number of data points per series. Adds a rollup to the query and, where the API still returns more (arithmetic or already rolled-up queries), averages neighbouring points. Useful for large time ranges to reduce response size. If not specified, returns all points from API."
+                        },
+                        "compare_to": {
+                            "type": "string",
+                            "description": "Also fetch the same query shifted back by this offset (e.g., '1h', '1d', '1w') and add a per-series comparison with aligned previous values and percent changes"
+                        },
+                        "summarize": {
+                            "type": "boolean",
+                            "description": "Return per-series count, min, max, avg, p95, last value and trend (rising/falling/flat) instead of every datapoint; use when only the shape matters",
+                            "default": false
+                        },
+                        "sparkline": {
+                            "type": "boolean",
+                            "description": "With summarize, add a text sparkline (e.g., '▁▂▄▇█▆▃') of each series",
+                            "default": true
+                        },
+                        "detect_anomalies": {
+                            "type": "boolean",
+                            "description": "Flag outlier points of each series in meta.anomalies (timestamp, value, score) to show when it went wrong",
+                            "default": false
+                        },
+                        "anomaly_method": {
+                            "type": "string",
+                            "enum": ["iqr", "zscore"],
+                            "description": "Outlier test: 'iqr' (outside 1.5 IQR of the quartiles, robust to spikes) or 'zscore' (more than 3 standard deviations from the mean)",
+                            "default": "iqr"
+                        }
+                    },
+                    "required": ["query"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::metrics::MetricsHandler::query(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_metrics_compare",
+        |_| {
+            json!({
+                "description": "Compare a metric query over the current window with a baseline window shifted back by compare_to (e.g., the same hours last week). Returns per series the avg/min/max/last of both windows, delta_avg, change_pct and max_change_pct; use it to answer 'is this normal for a Monday'.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Metrics query (e.g., 'avg:trace.http.request.hits{service:web}')"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start of the current window (natural language, ISO8601, or Unix timestamp)",
+                            "default": "1 hour ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End of the current window",
+                            "default": "now"
+                        },
+                        "compare_to": {
+                            "type": "string",
+                            "description": "How far back the baseline window is: '1h', '1d', '1w' or '1 week ago'",
+                            "default": "1w"
+                        },
+                        "max_points": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Maximum number of data points per series and window"
+                        },
+                        "include_points": {
+                            "type": "boolean",
+                            "description": "Also return the aligned current/baseline points with per-point change_pct",
+                            "default": false
+                        }
+                    },
+                    "required": ["query"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::metrics::MetricsHandler::compare(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_metrics_multi_query",
+        |_| {
+            json!({
+                "description": "Run several metric queries over the same time range concurrently and return results keyed by query. Use for dashboard-style comparisons (e.g., CPU vs memory vs error rate) in a single call. A failing query is reported under its key without failing the others.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "queries": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Metrics queries (max 10, e.g., ['avg:system.cpu.user{*}', 'avg:system.mem.used{*}'])"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time shared by all queries (supports natural language like '1 hour ago', ISO8601 timestamps, or Unix timestamps)",
+                            "default": "1 hour ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time shared by all queries (supports natural language like 'now', ISO8601 timestamps, or Unix timestamps)",
+                            "default": "now"
+                        },
+                        "max_points": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Maximum number of data points per series (applies the same rollup to every query, averaging neighbouring points where the API still returns more)"
+                        }
+                    },
+                    "required": ["queries"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::metrics::MetricsHandler::multi_query(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_metrics_validate",
+        |_| {
+            json!({
+                "description": "Check a metrics query for syntax problems before running it: balanced braces/parentheses, known space aggregators, scope placement, rollup placement and arguments, and 'by {tag}' grouping. Optionally runs the query against the API over the last 5 minutes to confirm Datadog accepts it.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Metrics query to check (e.g., 'avg:system.cpu.user{env:prod} by {host}.rollup(max, 300)')"
+                        },
+                        "check_api": {
+                            "type": "boolean",
+                            "description": "Also run the query against the Datadog API over a 5-minute window",
+                            "default": false
+                        }
+                    },
+                    "required": ["query"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::metrics_lint::MetricsLintHandler::validate(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_metrics_search",
+        |_| {
+            json!({
+                "description": "Search metric names reported in the last 24 hours by substring (e.g., 'system.cpu'). Use to discover which metrics exist before building a query.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Metric name or fragment to search for (e.g., 'http.requests', 'postgresql')"
+                        },
+                        "page": {
+                            "type": "integer",
+                            "description": "Page number (0-indexed)",
+                            "default": 0
+                        },
+                        "page_size": {
+                            "type": "integer",
+                            "description": "Number of metric names per page",
+                            "default": 50
+                        }
+                    },
+                    "required": ["query"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::metrics_metadata::MetricsMetadataHandler::search(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_metrics_metadata",
+        |_| {
+            json!({
+                "description": "Get metadata for a metric: type (gauge/count/rate/distribution), unit, per-unit, description, and integration. Use to choose the right aggregation and interpret values.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "metric": {
+                            "type": "string",
+                            "description": "Exact metric name (e.g., 'system.cpu.user')"
+                        }
+                    },
+                    "required": ["metric"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::metrics_metadata::MetricsMetadataHandler::metadata(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_metrics_tags",
+        |_| {
+            json!({
+                "description": "List the tag keys and values submitted for a metric, grouped by key. Use before filtering or grouping a metrics query to pick valid tags (e.g., which env or service values exist). Optionally includes the tag keys and aggregations actively queried (custom metrics only).",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "metric": {
+                            "type": "string",
+                            "description": "Exact metric name (e.g., 'trace.http.request.hits')"
+                        },
+                        "tag_key": {
+                            "type": "string",
+                            "description": "Only return values for this tag key (e.g., 'env')"
+                        },
+                        "max_values": {
+                            "type": "integer",
+                            "description": "Maximum values listed per tag key",
+                            "default": 50
+                        },
+                        "include_active": {
+                            "type": "boolean",
+                            "description": "Also report actively queried tag keys and aggregations (custom metrics only)",
+                            "default": false
+                        },
+                        "window_seconds": {
+                            "type": "integer",
+                            "description": "Lookback for actively queried configurations in seconds (default: 30 days)"
+                        }
+                    },
+                    "required": ["metric"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::metrics_metadata::MetricsMetadataHandler::tags(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_metrics_submit",
+        |_| {
+            json!({
+                "description": "Submit points of one custom metric through the v2 series intake, e.g. to mark an experiment or record a computed score. Points may be up to an hour old and at most 10 minutes ahead; they become queryable after a minute or two.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "metric": {
+                            "type": "string",
+                            "description": "Metric name (e.g., 'experiment.checkout.score')"
+                        },
+                        "type": {
+                            "type": "string",
+                            "enum": ["gauge", "count", "rate"],
+                            "description": "Metric type; count and rate need 'interval'",
+                            "default": "gauge"
+                        },
+                        "value": {
+                            "type": "number",
+                            "description": "Value of a single point (use 'points' for several)"
+                        },
+                        "timestamp": {
+                            "type": ["string", "integer"],
+                            "description": "Time of the single point (e.g., '5 minutes ago' or Unix seconds). Default: now"
+                        },
+                        "points": {
+                            "type": "array",
+                            "items": {
+                                "oneOf": [
+                                    { "type": "number" },
+                                    {
+                                        "type": "object",
+                                        "properties": {
+                                            "timestamp": { "type": ["string", "integer"] },
+                                            "value": { "type": "number" }
+                                        },
+                                        "required": ["value"]
+                                    }
+                                ]
+                            },
+                            "description": "Several points as {timestamp, value}, or bare values taken at the current time (max 1000)"
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Tags of the series (e.g., ['env:prod', 'experiment:a'])"
+                        },
+                        "host": {
+                            "type": "string",
+                            "description": "Host the points are reported for"
+                        },
+                        "interval": {
+                            "type": "integer",
+                            "description": "Seconds each point covers (required for count and rate)"
+                        },
+                        "unit": {
+                            "type": "string",
+                            "description": "Unit of the values (e.g., 'second', 'request')"
+                        }
+                    },
+                    "required": ["metric"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::metrics_submit::MetricsSubmitHandler::submit(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    )
+    .mutating());
+
+    registry.register(HandlerTool::new(
+        "datadog_logs_search",
+        |client| {
+            json!({
+                "description": "Search log events in Datadog. Returns log entries with timestamps, messages, and metadata. Supports Datadog query syntax and natural language time expressions.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Log search query"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (supports natural language like '1 hour ago', ISO8601, or Unix timestamps)",
+                            "default": "1 hour ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time (supports natural language like 'now', ISO8601, or Unix timestamps)",
+                            "default": "now"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of logs to return",
+                            "default": 10
+                        },
+                        "fields": fields_property("['timestamp', 'service', 'message']"),
+                        "attributes": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Custom attributes to include in each log's 'attributes', as paths (e.g., ['@http.status_code', '@usr.id']); a path to an object returns the whole object. Omitted by default. Attributes on the server's deny list are never returned and are listed in meta.withheld_attributes"
+                        },
+                        "tag_filter": {
+                            "type": "string",
+                            "description": tag_filter_description(client, "logs")
+                        },
+                        "check_exclusions": {
+                            "type": "boolean",
+                            "description": "Also report enabled index exclusion filters in meta (useful when expected logs are missing)",
+                            "default": false
+                        }
+                    },
+                    "required": ["query"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::logs::LogsHandler::search(server.client.clone(), arguments).boxed()
+        },
+    )
+    .scrubbed());
+
+    registry.register(HandlerTool::new(
+        "datadog_logs_exclusion_filters",
+        |_| {
+            json!({
+                "description": "List log indexes with their filters, retention, daily quota, and exclusion filters. Logs matching an enabled exclusion filter are dropped before indexing - the most common reason logs seem to be missing from search.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "index": {
+                            "type": "string",
+                            "description": "Only show this index (e.g., 'main')"
+                        },
+                        "include_disabled": {
+                            "type": "boolean",
+                            "description": "Include disabled exclusion filters",
+                            "default": false
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::logs_indexes::LogsIndexesHandler::exclusion_filters(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_monitors_list",
+        |_| {
+            json!({
+                "description": "List all monitors from Datadog. Returns monitor names, types, queries, states, creator, and when the definition and state last changed. Monitors silenced by a downtime are flagged with downtimed=true and a matching_downtimes summary. Supports filtering by tags and modification time. Page 0 always fetches fresh data, subsequent pages use cache.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "modified_since": {
+                            "type": "string",
+                            "description": "Only monitors whose definition changed at or after this time (e.g., '30 days ago', ISO8601, Unix timestamp)"
+                        },
+                        "tags": {
+                            "type": "string",
+                            "description": "Filter by tags (comma-separated)"
+                        },
+                        "monitor_tags": {
+                            "type": "string",
+                            "description": "Filter by monitor tags"
+                        },
+                        "page": {
+                            "type": "integer",
+                            "description": "Page number (0-based). Page 0 always fetches fresh data from Datadog API.",
+                            "default": 0
+                        },
+                        "page_size": {
+                            "type": "integer",
+                            "description": "Number of monitors per page",
+                            "default": 50
+                        },
+                        "fields": fields_property("['id', 'name', 'status']")
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::monitors::MonitorsHandler::list(
+                server.client.clone(),
+                server.cache.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_monitors_get",
+        |_| {
+            json!({
+                "description": "Retrieve detailed information about a specific monitor by ID. Returns full monitor configuration, thresholds, notification settings, and current state, plus per-group states (counts by status and the non-OK groups, most urgent first).",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "monitor_id": {
+                            "type": "integer",
+                            "description": "Monitor ID"
+                        },
+                        "group_states": {
+                            "type": "string",
+                            "description": "Comma-separated group statuses to list (alert, warn, no data, ok) or 'all'",
+                            "default": "alert,warn,no data"
+                        }
+                    },
+                    "required": ["monitor_id"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::monitors::MonitorsHandler::get(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_monitors_search",
+        |_| {
+            json!({
+                "description": "Search monitors server-side with free text and facets (e.g., 'status:alert type:metric tag:\"service:checkout\" cpu'). Pages on the server, so it scales to large orgs; returns facet counts by status, type, tag, and muted.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Monitor search query: free text plus facets such as status:, type:, tag:, muted:, priority:, creator:, notification:"
+                        },
+                        "sort": {
+                            "type": "string",
+                            "description": "Sort as 'field,dir' (e.g., 'status,asc', 'name,desc')"
+                        },
+                        "page": {
+                            "type": "integer",
+                            "description": "Page number (0-based)",
+                            "default": 0
+                        },
+                        "page_size": {
+                            "type": "integer",
+                            "description": "Monitors per page (max 1000)",
+                            "default": 50
+                        },
+                        "fields": fields_property("['id', 'name', 'status']")
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::monitors::MonitorsHandler::search(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_monitors_history",
+        |_| {
+            json!({
+                "description": "State history of a monitor from its alert events: each transition (Triggered, Recovered, Warn, No Data, ...) with the groups involved, plus per-group transition counts flagging flapping groups. Use it to see when a monitor fired and which hosts/services triggered it.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "monitor_id": {
+                            "type": "integer",
+                            "description": "Monitor ID"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (supports natural language like '1 day ago', ISO8601, or Unix timestamps)",
+                            "default": "7 days ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time",
+                            "default": "now"
+                        },
+                        "group": {
+                            "type": "string",
+                            "description": "Only transitions of this group (e.g., 'host:web-1')"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of transitions listed, newest first (group counts cover all)",
+                            "default": 50
+                        }
+                    },
+                    "required": ["monitor_id"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::monitor_history::MonitorHistoryHandler::history(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_monitors_tune",
+        |_| {
+            json!({
+                "description": "Replay a metric monitor's query over the past N days and score candidate thresholds: alert count, time in breach, and false positives/negatives. Ground truth is the given incident windows, or the current threshold's alerts when none are given. Supports avg/max/min/sum evaluations.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "monitor_id": {
+                            "type": "integer",
+                            "description": "Monitor ID (must be a metric monitor)"
+                        },
+                        "days": {
+                            "type": "integer",
+                            "description": "Days of history to replay (max 30)",
+                            "default": 7
+                        },
+                        "thresholds": {
+                            "type": "array",
+                            "items": {"type": "number"},
+                            "description": "Candidate thresholds to score (default: percentiles of the replayed data plus current critical/warning)"
+                        },
+                        "incidents": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "from": {"type": "string"},
+                                    "to": {"type": "string"}
+                                },
+                                "required": ["from", "to"]
+                            },
+                            "description": "Known real incident windows used as ground truth; enables a threshold recommendation"
+                        }
+                    },
+                    "required": ["monitor_id"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::monitor_tuning::MonitorTuningHandler::tune(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_events_query",
+        |_| {
+            json!({
+                "description": "Query event stream from Datadog. Returns events with titles, text, timestamps, and alert types. Supports full-text search (v2) and filtering by priority, sources, and tags. v2 (default) pages with a cursor and ignores 'page'; pass api_version 'v1' for page numbers (page 0 fetches fresh data).",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Event search query, e.g. 'deploy service:checkout' (v2 only)"
+                        },
+                        "api_version": {
+                            "type": "string",
+                            "enum": ["v1", "v2"],
+                            "description": "Events API to use (default: DD_EVENTS_API_VERSION, else v2)"
+                        },
+                        "cursor": {
+                            "type": "string",
+                            "description": "Cursor from the previous response's pagination.next_cursor (v2 only)"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (supports natural language like '1 hour ago', ISO8601, or Unix timestamps)",
+                            "default": "1 hour ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time (supports natural language like 'now', ISO8601, or Unix timestamps)",
+                            "default": "now"
+                        },
+                        "priority": {
+                            "type": "string",
+                            "description": "Priority filter (normal, low)"
+                        },
+                        "sources": {
+                            "type": "string",
+                            "description": "Sources filter"
+                        },
+                        "tags": {
+                            "type": "string",
+                            "description": "Tags filter"
+                        },
+                        "page": {
+                            "type": "integer",
+                            "description": "Page number (0-based, v1 only). Page 0 always fetches fresh data from Datadog API.",
+                            "default": 0
+                        },
+                        "page_size": {
+                            "type": "integer",
+                            "description": "Number of events per page (max 1000 for v2)",
+                            "default": 50
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::events::EventsHandler::query(
+                server.client.clone(),
+                server.cache.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    )
+    .scrubbed());
+
+    registry.register(HandlerTool::new(
+        "datadog_events_sources",
+        |_| {
+            json!({
+                "description": "List event sources present in the event stream over a window, with event counts, last seen time, and an example title. Use the source names as the 'sources' filter of datadog_events_query.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (supports natural language like '7 days ago', ISO8601, or Unix timestamps)",
+                            "default": "7 days ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time",
+                            "default": "now"
+                        },
+                        "query": {
+                            "type": "string",
+                            "description": "Optional event search query to narrow the scan (e.g., 'env:prod')"
+                        },
+                        "tags": {
+                            "type": "string",
+                            "description": "Tags filter (comma-separated)"
+                        },
+                        "max_events": {
+                            "type": "integer",
+                            "description": "Most recent events to scan (max 20000; one request per 1000)",
+                            "default": 5000
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::events::EventsHandler::sources(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_insights_list",
+        |_| {
+            json!({
+                "description": "List anomalies Watchdog detected automatically (latency spikes, error bursts, traffic drops, log and infrastructure anomalies) in a time range, newest first, with counts per category and service. The entry point for \"is anything unusual going on?\"; follow up with the APM, logs or metrics tools on the services it names.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "service": {
+                            "type": "string",
+                            "description": "Only anomalies tagged with this service"
+                        },
+                        "env": {
+                            "type": "string",
+                            "description": "Only anomalies tagged with this environment"
+                        },
+                        "category": {
+                            "type": "string",
+                            "enum": ["latency", "error_rate", "throughput", "log_anomaly", "infrastructure", "other"],
+                            "description": "Only anomalies of this kind (applied to the fetched insights)"
+                        },
+                        "query": {
+                            "type": "string",
+                            "description": "Additional events query appended to source:watchdog"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (e.g., '24 hours ago', timestamp)",
+                            "default": "24 hours ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time (e.g., 'now', timestamp)",
+                            "default": "now"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of insights to fetch (max 1000)",
+                            "default": 25
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::insights::InsightsHandler::list(server.client.clone(), arguments).boxed()
+        },
+    )
+    .scrubbed());
+
+    registry.register(HandlerTool::new(
+        "datadog_events_post",
+        |_| {
+            json!({
+                "description": "Post an event to the event stream, e.g. a deployment marker or annotation. Title is required (max 100 characters); text is limited to 4000 characters.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "title": {
+                            "type": "string",
+                            "description": "Event title"
+                        },
+                        "text": {
+                            "type": "string",
+                            "description": "Event body (supports Markdown with a '%%% ... %%%' wrapper)"
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Tags (e.g., ['service:checkout', 'env:prod']); a comma-separated string is also accepted"
+                        },
+                        "alert_type": {
+                            "type": "string",
+                            "enum": ["error", "warning", "info", "success", "user_update", "recommendation", "snapshot"],
+                            "description": "Event alert type",
+                            "default": "info"
+                        },
+                        "priority": {
+                            "type": "string",
+                            "enum": ["normal", "low"],
+                            "description": "Event priority",
+                            "default": "normal"
+                        },
+                        "date_happened": {
+                            "type": "string",
+                            "description": "When the event happened (supports '10 minutes ago', ISO8601, Unix timestamps; default: now)"
+                        },
+                        "host": {
+                            "type": "string",
+                            "description": "Host name to associate with the event"
+                        },
+                        "aggregation_key": {
+                            "type": "string",
+                            "description": "Key to group related events together"
+                        },
+                        "source_type_name": {
+                            "type": "string",
+                            "description": "Event source (e.g., 'my_apps', 'jenkins')"
+                        }
+                    },
+                    "required": ["title"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::events::EventsHandler::post(server.client.clone(), arguments).boxed()
+        },
+    )
+    .mutating());
+
+    registry.register(HandlerTool::new(
+        "datadog_events_post_template",
+        |_| {
+            json!({
+                "description": "Post an event from a named template (built-in: deploy, rollback, maintenance; more via DD_EVENT_TEMPLATES). Pass placeholder values as arguments, e.g. {template: 'deploy', version: '1.2.3'}. Template tags are always applied and cannot be overridden.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "template": {
+                            "type": "string",
+                            "description": "Template name"
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Extra tags; tags whose key the template already sets are dropped"
+                        }
+                    },
+                    "required": ["template"],
+                    "additionalProperties": {
+                        "type": ["string", "number"],
+                        "description": "Placeholder values (e.g., version, summary)"
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::event_templates::EventTemplatesHandler::post(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    )
+    .mutating());
+
+    registry.register(HandlerTool::new(
+        "datadog_hosts_list",
+        |client| {
+            json!({
+                "description": "List infrastructure hosts from Datadog. Returns host names, status, applications, sources, and tags. Supports filtering and sorting by various fields. With group_by (a tag key), returns host counts per tag value instead, for fleet-shape questions.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "filter": {
+                            "type": "string",
+                            "description": "Host filter query"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "From time (supports natural language like '1 hour ago', ISO8601, or Unix timestamps)",
+                            "default": "1 hour ago"
+                        },
+                        "sort_field": {
+                            "type": "string",
+                            "description": "Sort field"
+                        },
+                        "sort_dir": {
+                            "type": "string",
+                            "description": "Sort direction (asc, desc)"
+                        },
+                        "start": {
+                            "type": "integer",
+                            "description": "Starting index for pagination",
+                            "default": 0
+                        },
+                        "count": {
+                            "type": "integer",
+                            "description": "Number of hosts to return (max 25000; beyond 1000 they are fetched in concurrent pages of 1000). Defaults to 100, or to the whole fleet up to 25000 with group_by",
+                            "default": 100
+                        },
+                        "fields": fields_property("['name', 'up', 'tags.Datadog']"),
+                        "tag_filter": {
+                            "type": "string",
+                            "description": tag_filter_description(client, "hosts")
+                        },
+                        "group_by": {
+                            "type": "string",
+                            "description": "Tag key to group the fetched hosts by (e.g., 'availability-zone', 'instance-type', 'env'). Returns per-value host counts with up/down/muted breakdown instead of host objects; hosts without the tag are grouped as '(none)'"
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::hosts::HostsHandler::list(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_hosts_metrics",
+        |_| {
+            json!({
+                "description": "Snapshot of standard system metrics for one host in a single batched query: CPU (user/system/iowait/normalized load), memory (usable %, used, swap free %), disk (usage and I/O utilization per device), and network (bytes in/out, packet errors). Each series reports latest, avg, and max.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "host": {
+                            "type": "string",
+                            "description": "Host name as reported to Datadog"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (e.g., '1 hour ago', timestamp)",
+                            "default": "1 hour ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time (e.g., 'now', timestamp)",
+                            "default": "now"
+                        }
+                    },
+                    "required": ["host"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::hosts::HostsHandler::metrics(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_hosts_mute",
+        |_| {
+            json!({
+                "description": "Mute a host so its monitors stop notifying (e.g., during maintenance). Mutes indefinitely unless duration or end is given.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "host": {
+                            "type": "string",
+                            "description": "Host name to mute"
+                        },
+                        "duration": {
+                            "type": "string",
+                            "description": "How long to mute (e.g., '30m', '2h', '1d'); takes precedence over end"
+                        },
+                        "end": {
+                            "type": "string",
+                            "description": "When the mute expires (ISO8601 or Unix timestamp)"
+                        },
+                        "message": {
+                            "type": "string",
+                            "description": "Reason for muting (e.g., 'Kernel upgrade')"
+                        },
+                        "override": {
+                            "type": "boolean",
+                            "description": "Replace an existing mute's end time and message",
+                            "default": false
+                        }
+                    },
+                    "required": ["host"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::hosts::HostsHandler::mute(server.client.clone(), arguments).boxed()
+        },
+    )
+    .mutating());
+
+    registry.register(
+        HandlerTool::new(
+            "datadog_hosts_unmute",
+            |_| {
+                json!({
+                    "description": "Unmute a previously muted host so its monitors notify again.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "host": {
+                                "type": "string",
+                                "description": "Host name to unmute"
+                            }
+                        },
+                        "required": ["host"]
+                    }
+                })
+            },
+            |server, arguments| {
+                handlers::hosts::HostsHandler::unmute(server.client.clone(), arguments).boxed()
+            },
+        )
+        .mutating(),
+    );
+
+    registry.register(HandlerTool::new(
+        "datadog_host_tags_get",
+        |_| {
+            json!({
+                "description": "Tags of one host, sorted, with the tag keys that carry more than one value (e.g. env:prod next to env:production) to spot tagging mistakes. Optionally limited to one tag source.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "host": {
+                            "type": "string",
+                            "description": "Host name as reported to Datadog"
+                        },
+                        "source": {
+                            "type": "string",
+                            "description": "Only tags from this source (e.g., 'users', 'chef', 'puppet')"
+                        }
+                    },
+                    "required": ["host"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::hosts::HostsHandler::tags(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_host_tags_update",
+        |_| {
+            json!({
+                "description": "Change the tags of one host: add tags, replace all tags of a source, or remove specific tags (the source's other tags are kept). Returns the host's resulting tags.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "host": {
+                            "type": "string",
+                            "description": "Host name as reported to Datadog"
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Tags (e.g., ['env:prod', 'team:web']); a comma-separated string is also accepted"
+                        },
+                        "mode": {
+                            "type": "string",
+                            "enum": ["add", "replace", "remove"],
+                            "description": "add: add the tags; replace: set the source's tags to exactly these (an empty list clears them); remove: remove these tags from the source",
+                            "default": "add"
+                        },
+                        "source": {
+                            "type": "string",
+                            "description": "Tag source to change (e.g., 'users'); Datadog's default when omitted"
+                        }
+                    },
+                    "required": ["host"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::hosts::HostsHandler::update_tags(server.client.clone(), arguments).boxed()
+        },
+    )
+    .mutating());
+
+    registry.register(HandlerTool::new(
+        "datadog_processes_list",
+        |client| {
+            json!({
+                "description": "List live processes reported by the Datadog Agent (process collection must be enabled): pid, parent pid, user, command line, start time, and tags, with a per-host count. Filter by host, tags, or a command-line search. The summaries carry no CPU or memory usage; pair with datadog_metrics_query on process.stat.* metrics for resource consumption.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "host": {
+                            "type": "string",
+                            "description": "Only processes on this host"
+                        },
+                        "tags": {
+                            "type": "string",
+                            "description": "Comma-separated tags to filter by (e.g., 'env:prod,service:api')"
+                        },
+                        "search": {
+                            "type": "string",
+                            "description": "String to search for in process command lines (e.g., 'java')"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum processes to return (max 1000)",
+                            "default": 100
+                        },
+                        "cursor": {
+                            "type": "string",
+                            "description": "Cursor from the previous response's pagination.next_cursor"
+                        },
+                        "tag_filter": {
+                            "type": "string",
+                            "description": tag_filter_description(client, "processes")
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::processes::ProcessesHandler::list(server.client.clone(), arguments).boxed()
+        },
+    )
+    .scrubbed());
+
+    registry.register(HandlerTool::new(
+        "datadog_dashboards_list",
+        |_| {
+            json!({
+                "description": "List all dashboards from Datadog. Returns dashboard IDs, titles, and descriptions. Page 0 fetches fresh data, subsequent pages use cache.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "page": {
+                            "type": "integer",
+                            "description": "Page number (0-based). Page 0 fetches fresh data from Datadog API.",
+                            "default": 0
+                        },
+                        "page_size": {
+                            "type": "integer",
+                            "description": "Number of dashboards per page",
+                            "default": 50
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::dashboards::DashboardsHandler::list(
+                server.client.clone(),
+                server.cache.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_dashboards_get",
+        |_| {
+            json!({
+                "description": "Retrieve full dashboard configuration by ID. Returns title, description, layout type, widgets (with nested group widgets), template variables, and author information. Each widget lists its metric/log queries as runnable tool calls with $template variables expanded.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "dashboard_id": {
+                            "type": "string",
+                            "description": "Dashboard ID"
+                        },
+                        "template_variables": {
+                            "type": "object",
+                            "description": "Template variable values by name (e.g., {\"env\": \"prod\"}) substituted into widget queries; overrides dashboard defaults"
+                        }
+                    },
+                    "required": ["dashboard_id"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::dashboards::DashboardsHandler::get(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_dashboards_widget_query",
+        |_| {
+            json!({
+                "description": "Translate a dashboard widget into runnable datadog_metrics_query / datadog_logs_aggregate tool call arguments. Pass dashboard_id + widget_id (widget ids come from datadog_dashboards_get) or a raw widget definition. Template variables are expanded with dashboard defaults unless overridden; metric-only formulas are inlined into a single query.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "dashboard_id": {
+                            "type": "string",
+                            "description": "Dashboard ID containing the widget"
+                        },
+                        "widget_id": {
+                            "type": "integer",
+                            "description": "Widget ID (nested group widgets are searched too)"
+                        },
+                        "widget": {
+                            "type": "object",
+                            "description": "Raw widget or widget definition JSON (alternative to dashboard_id + widget_id)"
+                        },
+                        "template_variables": {
+                            "type": "object",
+                            "description": "Template variable values by name (e.g., {\"env\": \"prod\"}); overrides dashboard defaults"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time for the generated calls (default: widget live span, else '1 hour ago')"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time for the generated calls",
+                            "default": "now"
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::dashboard_widgets::DashboardWidgetsHandler::widget_query(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_dashboards_widget_value",
+        |_| {
+            json!({
+                "description": "Evaluate a query_value or toplist widget the way the dashboard renders it: reduce each metric query with the widget's aggregator (avg/last/sum/min/max) and apply its conditional formats (first matching rule wins) to report the value and color (red/yellow/green/default). Toplists return per-group values sorted descending. Non-metric queries are returned as unsupported.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "dashboard_id": {
+                            "type": "string",
+                            "description": "Dashboard ID containing the widget"
+                        },
+                        "widget_id": {
+                            "type": "integer",
+                            "description": "Widget ID (nested group widgets are searched too)"
+                        },
+                        "widget": {
+                            "type": "object",
+                            "description": "Raw widget or widget definition JSON (alternative to dashboard_id + widget_id)"
+                        },
+                        "template_variables": {
+                            "type": "object",
+                            "description": "Template variable values by name (e.g., {\"env\": \"prod\"}); overrides dashboard defaults"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (default: widget live span, else '1 hour ago')"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time",
+                            "default": "now"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum toplist rows per query",
+                            "default": 10
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::dashboard_widgets::DashboardWidgetsHandler::widget_value(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_dashboards_run_widget",
+        |_| {
+            json!({
+                "description": "Run a dashboard widget's metric and log queries over a time range and return their data (series points, log aggregation buckets), with template variables expanded. Failing queries are reported per query; queries on other data sources are listed as unsupported.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "dashboard_id": {
+                            "type": "string",
+                            "description": "Dashboard ID containing the widget"
+                        },
+                        "widget_id": {
+                            "type": "integer",
+                            "description": "Widget ID (nested group widgets are searched too)"
+                        },
+                        "widget": {
+                            "type": "object",
+                            "description": "Raw widget or widget definition JSON (alternative to dashboard_id + widget_id)"
+                        },
+                        "template_variables": {
+                            "type": "object",
+                            "description": "Template variable values by name (e.g., {\"env\": \"prod\"}); overrides dashboard defaults"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (default: widget live span, else '1 hour ago')"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time",
+                            "default": "now"
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::dashboard_widgets::DashboardWidgetsHandler::run_widget(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_dashboards_create",
+        |_| {
+            json!({
+                "description": "Create a dashboard from widget definitions (Datadog dashboard JSON, e.g. {\"definition\": {\"type\": \"timeseries\", \"title\": \"Latency\", \"requests\": [{\"q\": \"avg:trace.http.request.duration{service:checkout}\"}]}}). Returns the new dashboard's ID and URL.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "title": {
+                            "type": "string",
+                            "description": "Dashboard title"
+                        },
+                        "description": {
+                            "type": "string",
+                            "description": "Dashboard description (markdown)"
+                        },
+                        "layout_type": {
+                            "type": "string",
+                            "enum": ["ordered", "free"],
+                            "description": "'ordered' flows widgets automatically; 'free' requires a layout on every widget",
+                            "default": "ordered"
+                        },
+                        "widgets": {
+                            "type": "array",
+                            "items": {"type": "object"},
+                            "description": "Widgets as {\"definition\": {...}, \"layout\": {\"x\", \"y\", \"width\", \"height\"}}"
+                        },
+                        "template_variables": {
+                            "type": "array",
+                            "items": {"type": "object"},
+                            "description": "Template variables as {\"name\", \"prefix\", \"default\"} (e.g., {\"name\": \"service\", \"prefix\": \"service\", \"default\": \"checkout\"})"
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Dashboard tags (e.g., ['team:payments'])"
+                        }
+                    },
+                    "required": ["title"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::dashboards::DashboardsHandler::create(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    )
+    .mutating());
+
+    registry.register(HandlerTool::new(
+        "datadog_dashboards_update",
+        |_| {
+            json!({
+                "description": "Update an existing dashboard: change title, description, tags or template variables, replace all widgets, or append widgets. Omitted fields keep their current values.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "dashboard_id": {
+                            "type": "string",
+                            "description": "Dashboard ID to update"
+                        },
+                        "title": {
+                            "type": "string",
+                            "description": "New title"
+                        },
+                        "description": {
+                            "type": "string",
+                            "description": "New description"
+                        },
+                        "widgets": {
+                            "type": "array",
+                            "items": {"type": "object"},
+                            "description": "Replace all widgets with these"
+                        },
+                        "add_widgets": {
+                            "type": "array",
+                            "items": {"type": "object"},
+                            "description": "Widgets appended after the existing (or replacing) widgets"
+                        },
+                        "template_variables": {
+                            "type": "array",
+                            "items": {"type": "object"},
+                            "description": "Replace all template variables"
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Replace dashboard tags"
+                        }
+                    },
+                    "required": ["dashboard_id"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::dashboards::DashboardsHandler::update(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    )
+    .mutating());
+
+    registry.register(HandlerTool::new(
+        "datadog_dashboards_clone",
+        |_| {
+            json!({
+                "description": "Copy a dashboard, optionally retargeting it by changing template variable defaults (e.g., clone a service template dashboard for service 'checkout').",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "dashboard_id": {
+                            "type": "string",
+                            "description": "Dashboard ID to copy"
+                        },
+                        "title": {
+                            "type": "string",
+                            "description": "Title of the copy (default: '<source title> (copy)')"
+                        },
+                        "description": {
+                            "type": "string",
+                            "description": "Description of the copy (default: the source's)"
+                        },
+                        "template_variable_defaults": {
+                            "type": "object",
+                            "description": "New defaults by template variable name (e.g., {\"service\": \"checkout\", \"env\": \"prod\"})"
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Tags of the copy (default: the source's)"
+                        }
+                    },
+                    "required": ["dashboard_id"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::dashboards::DashboardsHandler::clone(server.client.clone(), arguments).boxed()
+        },
+    )
+    .mutating());
+
+    registry.register(HandlerTool::new(
+        "datadog_shared_dashboards_list",
+        |_| {
+            json!({
+                "description": "List dashboards shared by invite or public URL, with title, author, and timestamps. Use datadog_dashboards_get for a dashboard's widgets.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "page": {
+                            "type": "integer",
+                            "description": "Page number (0-based)",
+                            "default": 0
+                        },
+                        "page_size": {
+                            "type": "integer",
+                            "description": "Number of dashboards per page",
+                            "default": 50
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::dashboards::DashboardsHandler::shared_list(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_powerpacks_list",
+        |_| {
+            json!({
+                "description": "List powerpacks (reusable widget groups) with name, description, tags, template variables, and the number and types of widgets they contain.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "page": {
+                            "type": "integer",
+                            "description": "Page number (0-based)",
+                            "default": 0
+                        },
+                        "page_size": {
+                            "type": "integer",
+                            "description": "Number of powerpacks per page",
+                            "default": 50
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::powerpacks::PowerpacksHandler::list(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_notebooks_list",
+        |_| {
+            json!({
+                "description": "List notebooks (investigation runbooks, postmortems) with name, author, status, and timestamps. Supports name search and author filtering.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Search notebook names"
+                        },
+                        "author_handle": {
+                            "type": "string",
+                            "description": "Only notebooks by this author (e.g., 'jane@example.com')"
+                        },
+                        "page": {
+                            "type": "integer",
+                            "description": "Page number (0-based)",
+                            "default": 0
+                        },
+                        "page_size": {
+                            "type": "integer",
+                            "description": "Number of notebooks per page",
+                            "default": 50
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::notebooks::NotebooksHandler::list(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_notebooks_get",
+        |_| {
+            json!({
+                "description": "Get a notebook with a summarized cell view: markdown cells as text, graph cells as ready-to-run tool calls (datadog_metrics_query, datadog_logs_aggregate, ...) over the notebook's time window.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "notebook_id": {
+                            "type": "integer",
+                            "description": "Notebook ID"
+                        },
+                        "max_text_length": {
+                            "type": "integer",
+                            "description": "Maximum characters kept per markdown cell",
+                            "default": 2000
+                        }
+                    },
+                    "required": ["notebook_id"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::notebooks::NotebooksHandler::get(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_spans_search",
+        |client| {
+            json!({
+                "description": "Search APM trace spans from Datadog. Returns a compact view of each span by default: span_id, trace_id, service, resource, start, duration_ms, status and error type/message (tags only when a tag_filter applies). Use verbose=true for the full span payload, where error stack traces are truncated to 10 lines unless full_stack_trace=true. Supports cursor-based pagination and sorting.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Spans search query",
+                            "default": "*"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (e.g., '1 hour ago', timestamp)"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time (e.g., 'now', timestamp)"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of spans to return",
+                            "default": 10
+                        },
+                        "cursor": {
+                            "type": "string",
+                            "description": "Pagination cursor"
+                        },
+                        "sort": {
+                            "type": "string",
+                            "description": "Sort order (e.g., 'timestamp')"
+                        },
+                        "page": {
+                            "type": "integer",
+                            "description": "Page number (0-based, for client-side pagination)",
+                            "default": 0
+                        },
+                        "page_size": {
+                            "type": "integer",
+                            "description": "Number of spans per page",
+                            "default": 10
+                        },
+                        "fields": fields_property("['span_id', 'service', 'duration_ms'], or ['attributes.service', 'attributes.custom.http'] with verbose=true"),
+                        "tag_filter": {
+                            "type": "string",
+                            "description": tag_filter_description(client, "spans")
+                        },
+                        "verbose": {
+                            "type": "boolean",
+                            "description": "If true, return the full span payload (attributes, custom metadata, tags) instead of the compact view",
+                            "default": false
+                        },
+                        "full_stack_trace": {
+                            "type": "boolean",
+                            "description": "With verbose=true: if true, include complete error stack traces. If false (default), truncate to first 10 lines.",
+                            "default": false
+                        }
+                    },
+                    "required": ["from", "to"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::spans::SpansHandler::list(server.client.clone(), arguments).boxed()
+        },
+    )
+    .scrubbed());
+
+    registry.register(HandlerTool::new(
+        "datadog_traces_get",
+        |_| {
+            json!({
+                "description": "Get a complete APM trace by trace ID. Fetches every span of the trace (following pagination), reassembles the parent/child span tree, and returns a hierarchical view with per-span durations, services, and error flags.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "trace_id": {
+                            "type": ["string", "integer"],
+                            "description": "Trace ID to retrieve"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start of the search window containing the trace (e.g., '1 hour ago', timestamp)",
+                            "default": "1 hour ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End of the search window containing the trace (e.g., 'now', timestamp)",
+                            "default": "now"
+                        }
+                    },
+                    "required": ["trace_id"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::traces::TracesHandler::get(server.client.clone(), arguments).boxed()
+        },
+    )
+    .scrubbed());
+
+    registry.register(HandlerTool::new(
+        "datadog_spans_summary",
+        |_| {
+            json!({
+                "description": "Latency breakdown of the spans matching a query: per service or per resource count, errors, error rate, avg/p95/max and total duration in milliseconds, plus each group's share of total time, largest first. Computed from up to max_spans of the latest spans, so it works for any span query; use it to answer \"where is the time going\" without paging raw spans.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Spans search query (e.g., 'env:prod @http.route:/checkout')",
+                            "default": "*"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (e.g., '1 hour ago', timestamp)"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time (e.g., 'now', timestamp)"
+                        },
+                        "group_by": {
+                            "type": "string",
+                            "enum": ["service", "resource"],
+                            "description": "Group per service, or per service and resource",
+                            "default": "resource"
+                        },
+                        "max_spans": {
+                            "type": "integer",
+                            "description": "Maximum number of spans to aggregate (up to 5000)",
+                            "default": 1000
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of groups to return",
+                            "default": 20
+                        }
+                    },
+                    "required": ["from", "to"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::spans::SpansHandler::summary(server.client.clone(), arguments).boxed()
+        },
+    )
+    .scrubbed());
+
+    registry.register(HandlerTool::new(
+        "datadog_service_health",
+        |_| {
+            json!({
+                "description": "One-call health summary of a service: its monitors (by status, with every monitor not OK), APM hits, error rate and avg/p95/p99 latency, the error log count and recent events (deployments, alerts) in the window, fetched concurrently. Returns an overall status (ok, degraded, critical, unknown) with the signals behind it. Start here when an incident involves a known service; a section that fails is reported in meta.warnings instead of failing the call.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "service": {
+                            "type": "string",
+                            "description": "Service name"
+                        },
+                        "env": {
+                            "type": "string",
+                            "description": "Environment (e.g., 'production'); scopes APM, logs and events"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (e.g., '1 hour ago', timestamp)",
+                            "default": "1 hour ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time (e.g., 'now', timestamp)",
+                            "default": "now"
+                        }
+                    },
+                    "required": ["service"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::service_health::ServiceHealthHandler::health(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    )
+    .scrubbed());
+
+    registry.register(HandlerTool::new(
+        "datadog_apm_resources_stats",
+        |_| {
+            json!({
+                "description": "Per-endpoint (resource) APM stats for a service: hits, errors, error rate, and avg/p95/p99 latency in milliseconds, sorted by a chosen column. Use to find which endpoint regressed without paging raw spans.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "service": {
+                            "type": "string",
+                            "description": "Service name"
+                        },
+                        "env": {
+                            "type": "string",
+                            "description": "Environment filter (e.g., 'production')"
+                        },
+                        "operation": {
+                            "type": "string",
+                            "description": "Operation name filter (e.g., 'http.request')"
+                        },
+                        "query": {
+                            "type": "string",
+                            "description": "Additional span query appended to the service filter"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (e.g., '1 hour ago', timestamp)",
+                            "default": "1 hour ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time (e.g., 'now', timestamp)",
+                            "default": "now"
+                        },
+                        "sort_by": {
+                            "type": "string",
+                            "enum": ["hits", "errors", "error_rate", "avg_ms", "p95_ms", "p99_ms"],
+                            "description": "Column to sort resources by",
+                            "default": "hits"
+                        },
+                        "order": {
+                            "type": "string",
+                            "enum": ["asc", "desc"],
+                            "default": "desc"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of resources to return",
+                            "default": 25
+                        }
+                    },
+                    "required": ["service"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::apm_resources::ApmResourcesHandler::stats(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_apm_sampling_diagnostics",
+        |_| {
+            json!({
+                "description": "Diagnose why traces may be missing: ingested vs indexed span counts and ratio, ingestion breakdown by ingestion_reason (auto, rule, manual, error, ...), and APM retention filter configuration with which filters may match the service. Check this before assuming a span query is wrong.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "service": {
+                            "type": "string",
+                            "description": "Service name to scope usage metrics and retention filter matching"
+                        },
+                        "env": {
+                            "type": "string",
+                            "description": "Environment to scope usage metrics (e.g., 'production')"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (e.g., '1 hour ago', timestamp)",
+                            "default": "1 hour ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time (e.g., 'now', timestamp)",
+                            "default": "now"
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::apm_sampling::ApmSamplingHandler::diagnostics(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_services_list",
+        |_| {
+            json!({
+                "description": "List services from APM service catalog. Returns service names, teams, repositories, integrations, and metadata. Supports environment filtering. Pass 'kind' to list Software Catalog schema v3 entities (services, datastores, queues, systems) with their typed spec (lifecycle, tier, type, dependencies, components).",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "kind": {
+                            "type": "string",
+                            "enum": ["service", "datastore", "queue", "system", "all"],
+                            "description": "List schema v3 catalog entities of this kind instead of service definitions ('all' for every kind)"
+                        },
+                        "env": {
+                            "type": "string",
+                            "description": "Filter by environment (e.g., 'production', 'staging'); service definitions only"
+                        },
+                        "page": {
+                            "type": "integer",
+                            "description": "Page number (0-based, for client-side pagination)",
+                            "default": 0
+                        },
+                        "page_size": {
+                            "type": "integer",
+                            "description": "Number of services per page",
+                            "default": 50
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::services::ServicesHandler::list(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_logs_aggregate",
+        |_| {
+            json!({
+                "description": "Aggregate log events into buckets and compute metrics. Returns aggregated data with count, sum, avg, min, max, or percentiles. Supports grouping by log attributes.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Log search query",
+                            "default": "*"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (e.g., '1 hour ago', timestamp)"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time (e.g., 'now', timestamp)"
+                        },
+                        "compute": {
+                            "type": "array",
+                            "description": "Array of compute aggregations (count, sum, avg, min, max, pc99)",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "aggregation": {"type": "string"},
+                                    "type": {"type": "string"},
+                                    "interval": {"type": "string"},
+                                    "metric": {"type": "string"}
+                                }
+                            }
+                        },
+                        "group_by": {
+                            "type": "array",
+                            "description": "Array of fields to group by",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "facet": {"type": "string"},
+                                    "limit": {"type": "integer"},
+                                    "sort": {"type": "object"}
+                                }
+                            }
+                        },
+                        "timezone": {
+                            "type": "string",
+                            "description": "Timezone for time-based operations (e.g., 'UTC', 'America/New_York')"
+                        }
+                    },
+                    "required": ["from", "to"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::logs_aggregate::LogsAggregateHandler::aggregate(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    )
+    .scrubbed());
+
+    registry.register(HandlerTool::new(
+        "datadog_logs_timeseries",
+        |_| {
+            json!({
+                "description": "Generate time series data from log events. Returns bucketed metrics over time with configurable intervals (1m, 5m, 1h). Supports count, sum, avg, and percentile aggregations.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Log search query",
+                            "default": "*"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (e.g., '1 hour ago', timestamp)"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time (e.g., 'now', timestamp)"
+                        },
+                        "interval": {
+                            "type": "string",
+                            "description": "Time interval for timeseries (e.g., '1m', '5m', '1h')",
+                            "default": "1h"
+                        },
+                        "aggregation": {
+                            "type": "string",
+                            "description": "Aggregation type (count, sum, avg, min, max, pc99)",
+                            "default": "count"
+                        },
+                        "metric": {
+                            "type": "string",
+                            "description": "Field to aggregate on (for non-count aggregations)"
+                        },
+                        "group_by": {
+                            "type": "array",
+                            "description": "Array of fields to group by",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "facet": {"type": "string"},
+                                    "limit": {"type": "integer"}
+                                }
+                            }
+                        },
+                        "timezone": {
+                            "type": "string",
+                            "description": "Timezone for time-based operations (e.g., 'UTC', 'America/New_York')"
+                        }
+                    },
+                    "required": ["from", "to"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::logs_timeseries::LogsTimeseriesHandler::timeseries(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    )
+    .scrubbed());
+
+    registry.register(HandlerTool::new(
+        "datadog_logs_patterns",
+        |_| {
+            json!({
+                "description": "Cluster a sample of log messages into patterns (Drain-style: variable tokens such as ids, numbers and addresses become <*>) with count, share of the sample, an example message, services and statuses. Answers 'what are the top error shapes' in one call instead of reading hundreds of raw lines.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Log search query (e.g., 'service:web status:error')",
+                            "default": "*"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (supports natural language like '1 hour ago', ISO8601, or Unix timestamps)",
+                            "default": "1 hour ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time",
+                            "default": "now"
+                        },
+                        "sample_size": {
+                            "type": "integer",
+                            "description": "Number of logs to sample and cluster (max 1000)",
+                            "default": 500
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of patterns to return, most frequent first",
+                            "default": 20
+                        },
+                        "similarity": {
+                            "type": "number",
+                            "description": "Share of tokens (0-1) two messages must have in common to form one pattern; lower merges more",
+                            "default": 0.5
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::logs_patterns::LogsPatternsHandler::patterns(server.client.clone(), arguments)
+                .boxed()
+        },
+    )
+    .scrubbed());
+
+    registry.register(HandlerTool::new(
+        "datadog_logs_facets",
+        |_| {
+            json!({
+                "description": "Discover the attributes and tag keys present on logs matching a query, with type, coverage and example values, by sampling recent logs. Use it to build valid @attribute:value queries and group_by facets instead of guessing field names.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Log search query scoping the sample (e.g., 'service:web')",
+                            "default": "*"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (supports natural language like '1 hour ago', ISO8601, or Unix timestamps)",
+                            "default": "1 hour ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time",
+                            "default": "now"
+                        },
+                        "sample_size": {
+                            "type": "integer",
+                            "description": "Number of logs to sample (max 1000)",
+                            "default": 200
+                        },
+                        "search": {
+                            "type": "string",
+                            "description": "Only facets whose name contains this text (case-insensitive, e.g., 'status')"
+                        },
+                        "include_tags": {
+                            "type": "boolean",
+                            "description": "Also list tag keys (env, version, ...)",
+                            "default": true
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::logs_facets::LogsFacetsHandler::facets(server.client.clone(), arguments)
+                .boxed()
+        },
+    )
+    .scrubbed());
+
+    registry.register(HandlerTool::new(
+        "datadog_saved_views_list",
+        |_| {
+            json!({
+                "description": "List saved queries: the server's named queries (DD_SAVED_QUERIES_FILE), usable as saved_query on tools with a query such as datadog_logs_search or datadog_spans_search. Use it instead of retyping long queries.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "tool": {
+                            "type": "string",
+                            "description": "Only views usable with this tool (e.g., 'datadog_logs_search')"
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| server.saved_views(arguments).boxed(),
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_logs_metrics_list",
+        |_| {
+            json!({
+                "description": "List existing logs-based metrics: log filter query, count or distribution (and over which measure), group-by tags, and the metrics query that reads each one. Check it before aggregating logs; an existing metric answers the same question faster and over 15 months.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Case-insensitive substring match on the metric name or its log filter query (e.g., 'checkout')"
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::logs_metrics::LogsMetricsHandler::list(server.client.clone(), arguments)
+                .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_logs_metrics_get",
+        |_| {
+            json!({
+                "description": "Get one logs-based metric by name: its log filter query, compute, group-by tags, and the metrics query that reads it.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "metric_id": {
+                            "type": "string",
+                            "description": "Name of the logs-based metric (e.g., 'logs.checkout.errors')"
+                        }
+                    },
+                    "required": ["metric_id"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::logs_metrics::LogsMetricsHandler::get(server.client.clone(), arguments)
+                .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_logs_metric_suggest",
+        |_| {
+            json!({
+                "description": "Turn a frequently-run log aggregation into a logs-based metric definition. Returns the metric payload and the metrics query to use instead of re-aggregating logs. Nothing is created; use datadog_logs_metric_create for that.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": logs_metric_properties()
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::logs_metrics::LogsMetricsHandler::suggest(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_logs_metric_create",
+        |_| {
+            json!({
+                "description": "Create the logs-based metric datadog_logs_metric_suggest describes for the same arguments. Returns the created metric and the metrics query to use.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": logs_metric_properties()
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::logs_metrics::LogsMetricsHandler::create(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    )
+    .mutating());
+
+    registry.register(HandlerTool::new(
+        "datadog_rum_events_search",
+        |client| {
+            json!({
+                "description": "Search RUM (Real User Monitoring) events. Returns user experience data including sessions, views, actions, resources, and errors. Supports filtering by application, user behavior, and performance metrics.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "RUM search query (e.g., '@type:session AND @session.type:user', '@view.url_path:/checkout')",
+                            "default": "*"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (supports natural language like '1 hour ago', ISO8601, or Unix timestamps)",
+                            "default": "1 hour ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time (supports natural language like 'now', ISO8601, or Unix timestamps)",
+                            "default": "now"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of events to return",
+                            "default": 10
+                        },
+                        "cursor": {
+                            "type": "string",
+                            "description": "Pagination cursor from previous response"
+                        },
+                        "sort": {
+                            "type": "string",
+                            "description": "Sort order (e.g., 'timestamp', '-timestamp' for descending)"
+                        },
+                        "fields": fields_property("['timestamp', 'type', 'view.url_path']"),
+                        "tag_filter": {
+                            "type": "string",
+                            "description": tag_filter_description(client, "rum")
+                        },
+                        "full_stack_trace": {
+                            "type": "boolean",
+                            "description": "If true, include complete error stack traces. If false (default), truncate to first 10 lines.",
+                            "default": false
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::rum::RumHandler::search_events(server.client.clone(), arguments).boxed()
+        },
+    )
+    .scrubbed());
+
+    registry.register(HandlerTool::new(
+        "datadog_error_tracking_issues",
+        |_| {
+            json!({
+                "description": "List Error Tracking issues (errors grouped by fingerprint) ordered by occurrence count, with error type, message, code location, first/last seen, versions, and impacted users/sessions. The top issues include a representative stack trace from their latest error event.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Search query (e.g., 'service:checkout env:prod')",
+                            "default": "*"
+                        },
+                        "track": {
+                            "type": "string",
+                            "enum": ["logs", "rum", "trace"],
+                            "description": "Error source: backend logs, RUM (browser/mobile), or APM traces",
+                            "default": "logs"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Start time (supports natural language like '1 day ago', ISO8601, or Unix timestamps)",
+                            "default": "1 hour ago"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "End time (supports natural language like 'now', ISO8601, or Unix timestamps)",
+                            "default": "now"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of issues to return (max 100)",
+                            "default": 10
+                        },
+                        "stack_samples": {
+                            "type": "integer",
+                            "description": "Number of top issues to fetch a representative stack trace for (max 10)",
+                            "default": 3
+                        },
+                        "full_stack_trace": {
+                            "type": "boolean",
+                            "description": "If true, include complete stack traces. If false (default), truncate to first 10 lines.",
+                            "default": false
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::error_tracking::ErrorTrackingHandler::issues(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    )
+    .scrubbed());
+
+    registry.register(HandlerTool::new(
+        "datadog_usage_spike_check",
+        |_| {
+            json!({
+                "description": "Compare the last 24 hours of billable usage (indexed logs, custom metrics, indexed spans) against a trailing baseline and flag spikes. For each spiking product, lists the tag combinations (from usage attribution) that contributed most to the increase. Usage attribution lags by up to 24-72 hours.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "products": {
+                            "type": "array",
+                            "items": { "type": "string", "enum": ["logs", "custom_metrics", "spans"] },
+                            "description": "Products to check (default: all)"
+                        },
+                        "baseline_days": {
+                            "type": "integer",
+                            "description": "Number of days before the last 24 hours used as the baseline (max 30)",
+                            "default": 7
+                        },
+                        "threshold": {
+                            "type": "number",
+                            "description": "Recent/baseline hourly average ratio that counts as a spike (must be > 1.0)",
+                            "default": 1.5
+                        },
+                        "top_n": {
+                            "type": "integer",
+                            "description": "Maximum number of contributing tag combinations per spiking product",
+                            "default": 5
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::usage::UsageHandler::spike_check(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_usage_summary",
+        |_| {
+            json!({
+                "description": "Monthly billable usage grouped by product family (infrastructure, apm, logs, custom_metrics, rum, ...). Returns non-zero usage quantities per month, not costs.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "start_month": {
+                            "type": "string",
+                            "description": "First month (YYYY-MM). Default: two months before the current month"
+                        },
+                        "end_month": {
+                            "type": "string",
+                            "description": "Last month (YYYY-MM). Default: current month"
+                        },
+                        "family": {
+                            "type": "string",
+                            "description": "Only include one product family (e.g., 'logs', 'apm', 'infrastructure')"
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::usage::UsageHandler::summary(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_usage_by_product",
+        |_| {
+            json!({
+                "description": "Monthly Datadog cost per product with committed/on-demand breakdown, sorted by cost, plus totals across months. Answers 'what is driving our Datadog bill'.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "start_month": {
+                            "type": "string",
+                            "description": "First month (YYYY-MM). Default: two months before the current month"
+                        },
+                        "end_month": {
+                            "type": "string",
+                            "description": "Last month (YYYY-MM). Default: current month"
+                        },
+                        "cost_type": {
+                            "type": "string",
+                            "enum": ["estimated", "historical"],
+                            "description": "estimated covers the current and previous month; historical covers closed months",
+                            "default": "estimated"
+                        },
+                        "product": {
+                            "type": "string",
+                            "description": "Only include products whose name contains this string (e.g., 'logs')"
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::usage::UsageHandler::by_product(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_security_rules_list",
+        |_| {
+            json!({
+                "description": "List Cloud SIEM / security monitoring detection rules with their queries, highest case severity, and enablement state. Meta includes enabled/disabled counts and rules per type for auditing active detections.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "enabled": {
+                            "type": "boolean",
+                            "description": "Only enabled (true) or disabled (false) rules"
+                        },
+                        "type": {
+                            "type": "string",
+                            "description": "Rule type (e.g., 'log_detection', 'signal_correlation', 'cloud_configuration')"
+                        },
+                        "severity": {
+                            "type": "string",
+                            "enum": ["info", "low", "medium", "high", "critical"],
+                            "description": "Only rules with a case at this severity"
+                        },
+                        "query": {
+                            "type": "string",
+                            "description": "Case-insensitive substring match on rule name"
+                        },
+                        "page": {
+                            "type": "integer",
+                            "description": "Page number (0-based)",
+                            "default": 0
+                        },
+                        "page_size": {
+                            "type": "integer",
+                            "description": "Number of rules per page",
+                            "default": 50
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::security_rules::SecurityRulesHandler::list(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_security_rules_get",
+        |_| {
+            json!({
+                "description": "Get a security detection rule by ID, including query aggregation/grouping, severity cases with conditions and notifications, options, and message.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "rule_id": {
+                            "type": "string",
+                            "description": "Detection rule ID"
+                        }
+                    },
+                    "required": ["rule_id"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::security_rules::SecurityRulesHandler::get(
+                server.client.clone(),
+                arguments,
+            )
+            .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_api_keys_list",
+        |_| {
+            json!({
+                "description": "List the organization's API keys for credential audits: name, creator, creation/modification dates and age in days. Key values are redacted to their last four characters.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "filter": {
+                            "type": "string",
+                            "description": "Only keys whose name contains this string"
+                        },
+                        "page": {
+                            "type": "integer",
+                            "description": "Page number (0-based)",
+                            "default": 0
+                        },
+                        "page_size": {
+                            "type": "integer",
+                            "description": "Number of keys per page (max 100)",
+                            "default": 50
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::keys::KeysHandler::api_keys(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_app_keys_list",
+        |_| {
+            json!({
+                "description": "List the organization's application keys for credential audits: name, owner, age in days, and scopes (unscoped keys carry every permission of their owner). Flags the key this server uses. Key values are redacted to their last four characters.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "filter": {
+                            "type": "string",
+                            "description": "Only keys whose name contains this string"
+                        },
+                        "page": {
+                            "type": "integer",
+                            "description": "Page number (0-based)",
+                            "default": 0
+                        },
+                        "page_size": {
+                            "type": "integer",
+                            "description": "Number of keys per page (max 100)",
+                            "default": 50
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::keys::KeysHandler::application_keys(server.client.clone(), arguments)
+                .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_orgs_list",
+        |_| {
+            json!({
+                "description": "List the Datadog organization the configured keys belong to and, for a parent org in a multi-org account, its child orgs: public ID, name, subscription and billing plan. Use it to confirm which org the server is operating against.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::orgs::OrgsHandler::list(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_orgs_get",
+        |_| {
+            json!({
+                "description": "Get one organization by public ID with its settings (SAML, widget sharing, ...). Child orgs are only visible from their parent org.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "public_id": {
+                            "type": "string",
+                            "description": "Public ID of the org (from datadog_orgs_list)"
+                        }
+                    },
+                    "required": ["public_id"]
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::orgs::OrgsHandler::get(server.client.clone(), arguments).boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_connection_status",
+        |_| {
+            json!({
+                "description": "Check the connection to Datadog: site and API URL in use, whether the API key is valid, and the application key's name and granted scopes. Use it to diagnose 401/403 errors before other tools.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            })
+        },
+        |server, arguments| {
+            handlers::connection::ConnectionHandler::status(server.client.clone(), arguments)
+                .boxed()
+        },
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_set_context",
+        |_| {
+            json!({
+                "description": "Set defaults (env, service, team, timezone, time window) that later tool calls on this server use when the argument is omitted. Scope is added to search queries as key:value terms unless the query already constrains that key; explicit arguments always win. Pass '' to clear a field or clear=true to reset. Returns the resulting context.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "env": {
+                            "type": "string",
+                            "description": "Default environment (e.g., 'prod')"
+                        },
+                        "service": {
+                            "type": "string",
+                            "description": "Default service name"
+                        },
+                        "team": {
+                            "type": "string",
+                            "description": "Default team tag"
+                        },
+                        "timezone": {
+                            "type": "string",
+                            "description": "UTC offset for wall-clock times like 'today 9am' (e.g., '+09:00', '-05:00', 'UTC')"
+                        },
+                        "from": {
+                            "type": "string",
+                            "description": "Default start time (e.g., '4 hours ago')"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "Default end time (e.g., 'now')"
+                        },
+                        "clear": {
+                            "type": "boolean",
+                            "description": "Reset all defaults before applying the given fields",
+                            "default": false
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| server.set_context(arguments).boxed(),
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_results_save",
+        |_| {
+            json!({
+                "description": "Save the most recent tool result server-side under a short handle (r1, r2, ...) so it can be referenced later without keeping it in context. Saved results expire after DD_RESULTS_TTL seconds (default 3600); at most 50 are kept. Each HTTP/SSE session has its own saved results.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "label": {
+                            "type": "string",
+                            "description": "Optional name to retrieve the result by (e.g., 'errors-before-deploy')"
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| server.save_result(arguments).boxed(),
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_results_get",
+        |_| {
+            json!({
+                "description": "Retrieve a saved result by handle or label, optionally narrowed to a JSON pointer path. Without a handle, lists saved results with their tool, arguments, and size.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "handle": {
+                            "type": "string",
+                            "description": "Handle ('r3' or '3') or label of the saved result"
+                        },
+                        "path": {
+                            "type": "string",
+                            "description": "JSON pointer into the result (e.g., '/data/0' or '/meta')"
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| server.get_result(arguments).boxed(),
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_cache_stats",
+        |_| {
+            json!({
+                "description": "Show the server's response caches (dashboards, monitors and events lists): entry counts, entries past their TTL kept as stale fallbacks, hit/miss/stale-hit/eviction counters since startup and an estimated size. Use it to diagnose stale-data complaints.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            })
+        },
+        |server, _| server.cache_stats().boxed(),
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_server_stats",
+        |_| {
+            json!({
+                "description": "Show this server's own telemetry since startup: per-tool call counts, errors and latency (avg/max ms), Datadog API requests per endpoint family with errors, retries and latency, and cache hit rates. Use it to find slow tools, failing endpoints or rate-limit retries.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            })
+        },
+        |server, _| server.server_stats().boxed(),
+    ));
+
+    registry.register(HandlerTool::new(
+        "datadog_cache_clear",
+        |_| {
+            json!({
+                "description": "Clear the server's response caches so the next list call fetches fresh data from Datadog. Clears all caches unless one is named. Returns the number of entries removed.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "cache": {
+                            "type": "string",
+                            "enum": crate::cache::CACHE_NAMES,
+                            "description": "Cache to clear (default: all)"
+                        }
+                    }
+                }
+            })
+        },
+        |server, arguments| server.clear_cache(arguments).boxed(),
+    ));
+}
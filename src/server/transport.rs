@@ -0,0 +1,185 @@
+use std::net::SocketAddr;
+
+use super::http::HttpTransport;
+use super::protocol::Server;
+use super::sse::SseTransport;
+use crate::error::{DatadogError, Result};
+
+const DEFAULT_HTTP_ADDR: &str = "127.0.0.1:8080";
+
+/// Transport used to exchange JSON-RPC messages with MCP clients
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transport {
+    /// Newline-delimited JSON-RPC over stdin/stdout (default)
+    Stdio,
+    /// Streamable HTTP on a single `/mcp` endpoint
+    Http(SocketAddr),
+    /// Legacy SSE transport (`GET /sse` + `POST /messages`) for older clients
+    Sse(SocketAddr),
+}
+
+impl Transport {
+    /// Resolve the transport from CLI args (`--http [addr]`, `--sse [addr]`),
+    /// then `MCP_TRANSPORT` / `MCP_HTTP_ADDR`
+    pub fn from_args_and_env(args: &[String]) -> Result<Self> {
+        Self::resolve(
+            args,
+            std::env::var("MCP_TRANSPORT").ok(),
+            std::env::var("MCP_HTTP_ADDR").ok(),
+        )
+    }
+
+    fn resolve(
+        args: &[String],
+        transport: Option<String>,
+        http_addr: Option<String>,
+    ) -> Result<Self> {
+        let default_addr = http_addr.as_deref().unwrap_or(DEFAULT_HTTP_ADDR);
+
+        if let Some(addr) = flag_addr(args, "--http", default_addr) {
+            return Ok(Transport::Http(parse_addr(addr)?));
+        }
+        if let Some(addr) = flag_addr(args, "--sse", default_addr) {
+            return Ok(Transport::Sse(parse_addr(addr)?));
+        }
+
+        match transport.as_deref().map(str::trim) {
+            None | Some("") | Some("stdio") => Ok(Transport::Stdio),
+            Some("http") => Ok(Transport::Http(parse_addr(default_addr)?)),
+            Some("sse") => Ok(Transport::Sse(parse_addr(default_addr)?)),
+            Some(other) => Err(DatadogError::InvalidInput(format!(
+                "Unknown MCP_TRANSPORT '{}' (expected 'stdio', 'http' or 'sse')",
+                other
+            ))),
+        }
+    }
+}
+
+/// Address given to `--flag addr` / `--flag=addr`, or `default` when the flag has no value
+fn flag_addr<'a>(args: &'a [String], flag: &str, default: &'a str) -> Option<&'a str> {
+    if let Some(pos) = args.iter().position(|a| a == flag) {
+        return Some(
+            args.get(pos + 1)
+                .filter(|a| !a.starts_with("--"))
+                .map(String::as_str)
+                .unwrap_or(default),
+        );
+    }
+
+    args.iter()
+        .find_map(|a| a.strip_prefix(flag).and_then(|rest| rest.strip_prefix('=')))
+}
+
+/// Parse a listen address; a bare `:port` binds loopback only
+fn parse_addr(addr: &str) -> Result<SocketAddr> {
+    let normalized = if addr.starts_with(':') {
+        format!("127.0.0.1{}", addr)
+    } else {
+        addr.to_string()
+    };
+
+    normalized
+        .parse()
+        .map_err(|_| DatadogError::InvalidInput(format!("Invalid listen address: '{}'", addr)))
+}
+
+impl Server {
+    /// Serve MCP requests over the selected transport until the client exits or
+    /// SIGINT/SIGTERM arrives
+    pub async fn serve(self, transport: Transport) -> Result<()> {
+        self.log_startup_banner();
+        self.spawn_credential_check();
+        self.spawn_self_report();
+        self.shutdown.spawn_signal_handler();
+        match transport {
+            Transport::Stdio => self.run().await,
+            Transport::Http(addr) => HttpTransport::new(self).serve(addr).await,
+            Transport::Sse(addr) => SseTransport::new(self).serve(addr).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_default_is_stdio() {
+        let transport = Transport::resolve(&args(&["mcp-datadog"]), None, None).unwrap();
+        assert_eq!(transport, Transport::Stdio);
+    }
+
+    #[test]
+    fn test_http_flag_with_port_only() {
+        let transport =
+            Transport::resolve(&args(&["mcp-datadog", "--http", ":8080"]), None, None).unwrap();
+        assert_eq!(
+            transport,
+            Transport::Http("127.0.0.1:8080".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_http_flag_with_equals() {
+        let transport =
+            Transport::resolve(&args(&["mcp-datadog", "--http=127.0.0.1:9000"]), None, None)
+                .unwrap();
+        assert_eq!(
+            transport,
+            Transport::Http("127.0.0.1:9000".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_http_flag_without_address_uses_default() {
+        let transport = Transport::resolve(&args(&["mcp-datadog", "--http"]), None, None).unwrap();
+        assert_eq!(
+            transport,
+            Transport::Http(DEFAULT_HTTP_ADDR.parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_env_selects_http() {
+        let transport = Transport::resolve(
+            &args(&["mcp-datadog"]),
+            Some("http".to_string()),
+            Some(":3000".to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            transport,
+            Transport::Http("127.0.0.1:3000".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_sse_flag_and_env() {
+        let transport =
+            Transport::resolve(&args(&["mcp-datadog", "--sse", ":8081"]), None, None).unwrap();
+        assert_eq!(transport, Transport::Sse("127.0.0.1:8081".parse().unwrap()));
+
+        let transport =
+            Transport::resolve(&args(&["mcp-datadog"]), Some("sse".to_string()), None).unwrap();
+        assert_eq!(
+            transport,
+            Transport::Sse(DEFAULT_HTTP_ADDR.parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_unknown_transport_rejected() {
+        let result = Transport::resolve(&args(&["mcp-datadog"]), Some("grpc".to_string()), None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_invalid_address_rejected() {
+        let result = Transport::resolve(&args(&["mcp-datadog", "--http", "nonsense"]), None, None);
+        assert!(result.is_err());
+    }
+}
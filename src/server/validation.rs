@@ -0,0 +1,204 @@
+use serde_json::Value;
+
+/// Most edits between an unknown argument and a declared one for it to count as a typo
+const MAX_TYPO_DISTANCE: usize = 2;
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Declared property closest to an unknown one (`form` → `from`), if any is close enough
+fn closest<'a>(name: &str, declared: &[&'a str]) -> Option<&'a str> {
+    declared
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= MAX_TYPO_DISTANCE)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+fn matches_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Problem with one argument, checked against its property schema
+fn check_property(name: &str, value: &Value, property: &Value) -> Option<String> {
+    let expected: Vec<&str> = match &property["type"] {
+        Value::String(t) => vec![t.as_str()],
+        Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !expected.is_empty() && !expected.iter().any(|t| matches_type(value, t)) {
+        return Some(format!(
+            "'{}' must be {}, got {}",
+            name,
+            expected.join(" or "),
+            type_name(value)
+        ));
+    }
+
+    if let Some(allowed) = property["enum"].as_array()
+        && !allowed.contains(value)
+    {
+        let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+        return Some(format!(
+            "'{}' must be one of {}, got {}",
+            name,
+            allowed.join(", "),
+            value
+        ));
+    }
+
+    None
+}
+
+/// Check tool arguments against the tool's declared input schema: no undeclared arguments,
+/// and declared ones of the right type and enum value. Nulls count as omitted; required
+/// arguments are left to the handlers, since session context may still supply them.
+pub(super) fn validate_arguments(input_schema: &Value, arguments: &Value) -> Result<(), String> {
+    let arguments = match arguments {
+        Value::Null => return Ok(()),
+        Value::Object(arguments) => arguments,
+        other => {
+            return Err(format!(
+                "arguments must be an object, got {}",
+                type_name(other)
+            ));
+        }
+    };
+    let Some(properties) = input_schema["properties"].as_object() else {
+        return Ok(());
+    };
+
+    let mut declared: Vec<&str> = properties.keys().map(String::as_str).collect();
+    declared.sort_unstable();
+
+    let mut problems = Vec::new();
+    for (name, value) in arguments {
+        if value.is_null() {
+            continue;
+        }
+        match properties.get(name) {
+            Some(property) => problems.extend(check_property(name, value, property)),
+            None => problems.push(match closest(name, &declared) {
+                Some(suggestion) => {
+                    format!(
+                        "unknown argument '{}' (did you mean '{}'?)",
+                        name, suggestion
+                    )
+                }
+                None => format!("unknown argument '{}'", name),
+            }),
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{}. Valid arguments: {}",
+            problems.join("; "),
+            declared.join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {"type": "string"},
+                "from": {"type": "string"},
+                "to": {"type": "string"},
+                "limit": {"type": "integer"},
+                "sort": {"type": "string", "enum": ["asc", "desc"]},
+                "trace_id": {"type": ["string", "integer"]}
+            },
+            "required": ["query"]
+        })
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("form", "from"), 2);
+        assert_eq!(edit_distance("qurey", "query"), 2);
+        assert_eq!(edit_distance("limit", "limit"), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_valid_arguments() {
+        assert!(validate_arguments(&schema(), &Value::Null).is_ok());
+        assert!(
+            validate_arguments(
+                &schema(),
+                &json!({"query": "status:error", "limit": 10, "sort": "desc", "to": null})
+            )
+            .is_ok()
+        );
+        assert!(validate_arguments(&schema(), &json!({"trace_id": 123})).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_arguments() {
+        let error =
+            validate_arguments(&schema(), &json!({"qurey": "x", "form": "1h"})).unwrap_err();
+        assert!(error.contains("unknown argument 'qurey' (did you mean 'query'?)"));
+        assert!(error.contains("unknown argument 'form' (did you mean 'from'?)"));
+        assert!(error.contains("Valid arguments: from, limit, query, sort, to, trace_id"));
+
+        let error = validate_arguments(&schema(), &json!({"service_name": "api"})).unwrap_err();
+        assert!(error.starts_with("unknown argument 'service_name'."));
+    }
+
+    #[test]
+    fn test_mistyped_arguments() {
+        let error = validate_arguments(&schema(), &json!({"limit": "10"})).unwrap_err();
+        assert!(error.starts_with("'limit' must be integer, got string"));
+
+        let error = validate_arguments(&schema(), &json!({"limit": 2.5})).unwrap_err();
+        assert!(error.starts_with("'limit' must be integer, got number"));
+
+        let error = validate_arguments(&schema(), &json!({"sort": "newest"})).unwrap_err();
+        assert!(error.starts_with(r#"'sort' must be one of "asc", "desc", got "newest""#));
+
+        assert!(validate_arguments(&schema(), &json!(["query"])).is_err());
+    }
+}
@@ -0,0 +1,391 @@
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::cache::CacheStats;
+
+/// Prefix of every metric on the Prometheus endpoint
+const METRIC_PREFIX: &str = "mcp_datadog";
+
+/// Failed tool calls kept for self-reporting; older ones are dropped beyond this
+const MAX_TOOL_ERRORS: usize = 100;
+
+/// A failed tool call, as sent to the logs intake when self-reporting
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolError {
+    pub tool: String,
+    pub message: String,
+    /// Milliseconds since the epoch
+    pub timestamp: i64,
+}
+
+/// Calls, failures and latency of one tool or one Datadog endpoint family
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CallStats {
+    pub calls: u64,
+    pub errors: u64,
+    /// Attempts repeated after a transient failure (API requests only)
+    pub retries: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl CallStats {
+    fn record(&mut self, elapsed: Duration, ok: bool) {
+        self.calls += 1;
+        if !ok {
+            self.errors += 1;
+        }
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+    }
+
+    fn to_json(&self) -> Value {
+        let avg_ms = if self.calls > 0 {
+            self.total.as_secs_f64() * 1000.0 / self.calls as f64
+        } else {
+            0.0
+        };
+        json!({
+            "calls": self.calls,
+            "errors": self.errors,
+            "retries": self.retries,
+            "avg_ms": avg_ms,
+            "max_ms": self.max.as_secs_f64() * 1000.0,
+            "total_ms": self.total.as_secs_f64() * 1000.0,
+        })
+    }
+}
+
+/// Process-wide counters since startup, shared by every org's client and every transport
+pub struct Telemetry {
+    started: Instant,
+    tools: Mutex<BTreeMap<String, CallStats>>,
+    /// Keyed by endpoint family, e.g. `/api/v1/monitor`
+    api: Mutex<BTreeMap<String, CallStats>>,
+    tool_errors: Mutex<VecDeque<ToolError>>,
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            tools: Mutex::new(BTreeMap::new()),
+            api: Mutex::new(BTreeMap::new()),
+            tool_errors: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
+impl Telemetry {
+    pub fn record_tool(&self, tool: &str, elapsed: Duration, ok: bool) {
+        let mut tools = self.tools.lock().unwrap_or_else(|e| e.into_inner());
+        tools
+            .entry(tool.to_string())
+            .or_default()
+            .record(elapsed, ok);
+    }
+
+    /// One HTTP attempt against Datadog; retries are recorded as attempts of their own
+    pub fn record_api(&self, family: &str, elapsed: Duration, ok: bool) {
+        let mut api = self.api.lock().unwrap_or_else(|e| e.into_inner());
+        api.entry(family.to_string())
+            .or_default()
+            .record(elapsed, ok);
+    }
+
+    pub fn record_retry(&self, family: &str) {
+        let mut api = self.api.lock().unwrap_or_else(|e| e.into_inner());
+        api.entry(family.to_string()).or_default().retries += 1;
+    }
+
+    pub fn record_tool_error(&self, tool: &str, message: &str) {
+        let mut errors = self.tool_errors.lock().unwrap_or_else(|e| e.into_inner());
+        if errors.len() == MAX_TOOL_ERRORS {
+            errors.pop_front();
+        }
+        errors.push_back(ToolError {
+            tool: tool.to_string(),
+            message: message.to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+
+    /// Failed tool calls since the last take, oldest first
+    pub fn take_tool_errors(&self) -> Vec<ToolError> {
+        let mut errors = self.tool_errors.lock().unwrap_or_else(|e| e.into_inner());
+        errors.drain(..).collect()
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn tool_stats(&self) -> BTreeMap<String, CallStats> {
+        self.tools.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn api_stats(&self) -> BTreeMap<String, CallStats> {
+        self.api.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Counters as JSON for `datadog_server_stats`
+    pub fn snapshot(&self) -> Value {
+        let section = |stats: BTreeMap<String, CallStats>| {
+            let mut totals = CallStats::default();
+            let mut by_name = Map::new();
+            for (name, stats) in stats {
+                totals.calls += stats.calls;
+                totals.errors += stats.errors;
+                totals.retries += stats.retries;
+                totals.total += stats.total;
+                totals.max = totals.max.max(stats.max);
+                by_name.insert(name, stats.to_json());
+            }
+            (totals.to_json(), Value::Object(by_name))
+        };
+        let (tool_totals, tools) = section(self.tool_stats());
+        let (api_totals, api) = section(self.api_stats());
+
+        json!({
+            "uptime_secs": self.uptime().as_secs(),
+            "tool_totals": tool_totals,
+            "tools": tools,
+            "api_totals": api_totals,
+            "api": api,
+        })
+    }
+
+    /// Counters in the Prometheus text exposition format, with the given caches' counters
+    pub fn render_prometheus(&self, caches: &[(&str, CacheStats)]) -> String {
+        let mut out = String::new();
+        metric(
+            &mut out,
+            "uptime_seconds",
+            "gauge",
+            "Seconds since the server started",
+        );
+        let _ = writeln!(
+            out,
+            "{}_uptime_seconds {}",
+            METRIC_PREFIX,
+            self.uptime().as_secs_f64()
+        );
+
+        let api_stats = self.api_stats();
+        render_calls(&mut out, "tool", "tool", "tool calls", &self.tool_stats());
+        render_calls(
+            &mut out,
+            "api",
+            "endpoint",
+            "Datadog API requests",
+            &api_stats,
+        );
+
+        metric(
+            &mut out,
+            "api_retries_total",
+            "counter",
+            "Datadog API requests retried after a transient failure",
+        );
+        for (family, stats) in &api_stats {
+            sample(
+                &mut out,
+                "api_retries_total",
+                "endpoint",
+                family,
+                stats.retries,
+            );
+        }
+
+        let cache_counters: [(&str, &str, CacheValue); 4] = [
+            (
+                "cache_hits_total",
+                "Cache lookups served from a fresh entry",
+                |s| s.hits,
+            ),
+            (
+                "cache_misses_total",
+                "Cache lookups that had to fetch",
+                |s| s.misses,
+            ),
+            (
+                "cache_stale_hits_total",
+                "Failed refreshes served from a stale entry",
+                |s| s.stale_hits,
+            ),
+            ("cache_entries", "Entries currently cached", |s| {
+                s.entries as u64
+            }),
+        ];
+        for (name, help, value) in cache_counters {
+            let kind = if name.ends_with("_total") {
+                "counter"
+            } else {
+                "gauge"
+            };
+            metric(&mut out, name, kind, help);
+            for (cache, stats) in caches {
+                sample(&mut out, name, "cache", cache, value(stats));
+            }
+        }
+
+        out
+    }
+}
+
+/// Reads one counter out of a cache's stats
+type CacheValue = fn(&CacheStats) -> u64;
+
+/// `# HELP` and `# TYPE` lines of one metric
+fn metric(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {}_{} {}", METRIC_PREFIX, name, help);
+    let _ = writeln!(out, "# TYPE {}_{} {}", METRIC_PREFIX, name, kind);
+}
+
+fn sample(out: &mut String, name: &str, label: &str, value: &str, sample: impl std::fmt::Display) {
+    let _ = writeln!(
+        out,
+        "{}_{}{{{}=\"{}\"}} {}",
+        METRIC_PREFIX,
+        name,
+        label,
+        escape_label(value),
+        sample
+    );
+}
+
+/// Calls, errors and a latency summary per tool or endpoint family
+fn render_calls(
+    out: &mut String,
+    prefix: &str,
+    label: &str,
+    what: &str,
+    stats: &BTreeMap<String, CallStats>,
+) {
+    let calls = format!("{}_calls_total", prefix);
+    metric(out, &calls, "counter", &format!("Completed {}", what));
+    for (name, s) in stats {
+        sample(out, &calls, label, name, s.calls);
+    }
+
+    let errors = format!("{}_errors_total", prefix);
+    metric(out, &errors, "counter", &format!("Failed {}", what));
+    for (name, s) in stats {
+        sample(out, &errors, label, name, s.errors);
+    }
+
+    let duration = format!("{}_duration_seconds", prefix);
+    metric(out, &duration, "summary", &format!("Latency of {}", what));
+    for (name, s) in stats {
+        sample(
+            out,
+            &format!("{}_sum", duration),
+            label,
+            name,
+            s.total.as_secs_f64(),
+        );
+        sample(out, &format!("{}_count", duration), label, name, s.calls);
+    }
+
+    let max = format!("{}_duration_max_seconds", prefix);
+    metric(out, &max, "gauge", &format!("Slowest of the {}", what));
+    for (name, s) in stats {
+        sample(out, &max, label, name, s.max.as_secs_f64());
+    }
+}
+
+/// Label values escape backslashes, quotes and newlines
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// The process-wide telemetry, created on first use
+pub fn telemetry() -> &'static Telemetry {
+    static TELEMETRY: OnceLock<Telemetry> = OnceLock::new();
+    TELEMETRY.get_or_init(Telemetry::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_snapshot() {
+        let telemetry = Telemetry::default();
+        telemetry.record_tool("datadog_logs_search", Duration::from_millis(100), true);
+        telemetry.record_tool("datadog_logs_search", Duration::from_millis(300), false);
+        telemetry.record_api("/api/v2/logs", Duration::from_millis(50), false);
+        telemetry.record_retry("/api/v2/logs");
+        telemetry.record_api("/api/v2/logs", Duration::from_millis(70), true);
+
+        let snapshot = telemetry.snapshot();
+        let logs = &snapshot["tools"]["datadog_logs_search"];
+        assert_eq!(logs["calls"], 2);
+        assert_eq!(logs["errors"], 1);
+        assert_eq!(logs["avg_ms"], 200.0);
+        assert_eq!(logs["max_ms"], 300.0);
+
+        let api = &snapshot["api"]["/api/v2/logs"];
+        assert_eq!(api["calls"], 2);
+        assert_eq!(api["retries"], 1);
+        assert_eq!(snapshot["api_totals"]["errors"], 1);
+        assert_eq!(snapshot["tool_totals"]["calls"], 2);
+    }
+
+    #[test]
+    fn test_render_prometheus() {
+        let telemetry = Telemetry::default();
+        telemetry.record_tool("datadog_monitors_list", Duration::from_millis(250), true);
+        telemetry.record_api("/api/v1/monitor", Duration::from_millis(200), true);
+
+        let caches = [(
+            "monitors",
+            CacheStats {
+                entries: 1,
+                expired_entries: 0,
+                hits: 3,
+                misses: 1,
+                stale_hits: 0,
+                evictions: 0,
+                coalesced: 0,
+                hit_rate: Some(0.75),
+                estimated_bytes: 10,
+            },
+        )];
+        let text = telemetry.render_prometheus(&caches);
+
+        assert!(text.contains("# TYPE mcp_datadog_tool_calls_total counter"));
+        assert!(text.contains("mcp_datadog_tool_calls_total{tool=\"datadog_monitors_list\"} 1"));
+        assert!(text.contains(
+            "mcp_datadog_tool_duration_seconds_sum{tool=\"datadog_monitors_list\"} 0.25"
+        ));
+        assert!(text.contains("mcp_datadog_api_calls_total{endpoint=\"/api/v1/monitor\"} 1"));
+        assert!(text.contains("mcp_datadog_api_retries_total{endpoint=\"/api/v1/monitor\"} 0"));
+        assert!(text.contains("mcp_datadog_cache_hits_total{cache=\"monitors\"} 3"));
+        assert!(text.contains("# TYPE mcp_datadog_cache_entries gauge"));
+    }
+
+    #[test]
+    fn test_tool_errors_are_bounded_and_taken_once() {
+        let telemetry = Telemetry::default();
+        for i in 0..MAX_TOOL_ERRORS + 5 {
+            telemetry.record_tool_error("datadog_logs_search", &format!("error {}", i));
+        }
+
+        let errors = telemetry.take_tool_errors();
+        assert_eq!(errors.len(), MAX_TOOL_ERRORS);
+        assert_eq!(errors[0].message, "error 5");
+        assert!(telemetry.take_tool_errors().is_empty());
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
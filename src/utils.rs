@@ -1,5 +1,5 @@
 use crate::error::{DatadogError, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Days, Utc};
 use interim::{Dialect, parse_date_string};
 
 /// Parse a time expression into a Unix timestamp
@@ -35,6 +35,89 @@ pub fn parse_time(input: &str) -> Result<i64> {
     )))
 }
 
+/// Parse a short duration such as "30m", "2h", "1d" or "1w" into seconds
+/// Returns None for unknown units or a zero duration
+pub fn parse_duration(input: &str) -> Option<i64> {
+    let input = input.trim();
+    let split = input.find(|c: char| !c.is_ascii_digit())?;
+    let (count, unit) = input.split_at(split);
+    let count: i64 = count.parse().ok()?;
+
+    let unit_secs = match unit {
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        "w" => 604800,
+        _ => return None,
+    };
+
+    count.checked_mul(unit_secs).filter(|secs| *secs > 0)
+}
+
+/// Parse a range shorthand into a (from, to) pair of Unix timestamps
+/// Supports:
+/// - "last 15m", "last 24h", "last 7d" (also "last 2 days")
+/// - "today", "yesterday", "this week" (calendar boundaries in UTC, weeks start on Monday)
+/// - Grafana-style "now-6h"
+pub fn parse_range(input: &str) -> Result<(i64, i64)> {
+    parse_range_at(input, Utc::now())
+}
+
+fn parse_range_at(input: &str, now: DateTime<Utc>) -> Result<(i64, i64)> {
+    let range = input.trim().to_lowercase();
+    let midnight = now
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .map(|dt| dt.and_utc())
+        .unwrap_or(now);
+    let now_ts = now.timestamp();
+
+    let window = match range.as_str() {
+        "today" => Some((midnight.timestamp(), now_ts)),
+        "yesterday" => midnight
+            .checked_sub_days(Days::new(1))
+            .map(|start| (start.timestamp(), midnight.timestamp())),
+        "this week" => {
+            let since_monday = u64::from(now.weekday().num_days_from_monday());
+            midnight
+                .checked_sub_days(Days::new(since_monday))
+                .map(|start| (start.timestamp(), now_ts))
+        }
+        _ => range
+            .strip_prefix("now-")
+            .and_then(parse_duration)
+            .or_else(|| {
+                let span = range.strip_prefix("last ")?.trim();
+                parse_duration(span).or_else(|| {
+                    parse_date_string(&format!("{} ago", span), now, Dialect::Us)
+                        .ok()
+                        .map(|start| now_ts - start.timestamp())
+                        .filter(|secs| *secs > 0)
+                })
+            })
+            .map(|secs| {
+                now_ts
+                    .checked_sub(secs)
+                    .filter(|from| DateTime::from_timestamp(*from, 0).is_some())
+                    .map(|from| (from, now_ts))
+                    .ok_or_else(|| {
+                        DatadogError::InvalidInput(format!(
+                            "Range '{}' reaches back further than any representable time",
+                            input
+                        ))
+                    })
+            })
+            .transpose()?,
+    };
+
+    window.ok_or_else(|| {
+        DatadogError::DateParseError(format!(
+            "Unable to parse range '{}' (expected e.g. 'last 15m', 'last 24h', 'today', 'yesterday', 'this week' or 'now-6h')",
+            input
+        ))
+    })
+}
+
 /// Convert timestamp to human-readable format
 pub fn format_timestamp(timestamp: i64) -> String {
     if let Some(dt) = DateTime::from_timestamp(timestamp, 0) {
@@ -44,6 +127,27 @@ pub fn format_timestamp(timestamp: i64) -> String {
     }
 }
 
+/// One URL path segment with everything but unreserved characters percent-encoded, so a
+/// caller-supplied ID can't add segments or a query. `.` and `..` are refused: URLs resolve
+/// them (encoded or not) as dot segments
+pub fn encode_segment(segment: &str) -> Result<String> {
+    if segment.is_empty() || segment == "." || segment == ".." {
+        return Err(DatadogError::InvalidInput(format!(
+            "Invalid path segment '{}'",
+            segment
+        )));
+    }
+    Ok(segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect())
+}
+
 /// Truncate error stack trace to specified number of lines
 /// If stack exceeds max_lines, truncates with continuation indicator
 pub fn truncate_stack_trace(stack: &str, max_lines: usize) -> String {
@@ -66,6 +170,19 @@ pub fn truncate_stack_trace(stack: &str, max_lines: usize) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_encode_segment() {
+        assert_eq!(encode_segment("abc-123_x.y~z").unwrap(), "abc-123_x.y~z");
+        assert_eq!(
+            encode_segment("../../v1/dashboard/abc").unwrap(),
+            "..%2F..%2Fv1%2Fdashboard%2Fabc"
+        );
+        assert_eq!(encode_segment("a b?c#d").unwrap(), "a%20b%3Fc%23d");
+        assert!(encode_segment("..").is_err());
+        assert!(encode_segment(".").is_err());
+        assert!(encode_segment("").is_err());
+    }
+
     #[test]
     fn test_parse_time_unix() {
         let result = parse_time("1704067200");
@@ -118,6 +235,62 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30m"), Some(1800));
+        assert_eq!(parse_duration("2h"), Some(7200));
+        assert_eq!(parse_duration(" 1d "), Some(86400));
+        assert_eq!(parse_duration("1w"), Some(604800));
+        assert_eq!(parse_duration("0h"), None);
+        assert_eq!(parse_duration("h"), None);
+        assert_eq!(parse_duration("5"), None);
+        assert_eq!(parse_duration("1y"), None);
+        assert_eq!(parse_duration("99999999999999w"), None);
+    }
+
+    #[test]
+    fn test_parse_range() {
+        // Wednesday 2024-01-03 15:30:00 UTC
+        let now = DateTime::from_timestamp(1_704_295_800, 0).unwrap();
+        let now_ts = now.timestamp();
+        let midnight = 1_704_240_000;
+
+        assert_eq!(
+            parse_range_at("last 15m", now).unwrap(),
+            (now_ts - 900, now_ts)
+        );
+        assert_eq!(
+            parse_range_at("Last 24h", now).unwrap(),
+            (now_ts - 86400, now_ts)
+        );
+        assert_eq!(
+            parse_range_at("last 2 days", now).unwrap(),
+            (now_ts - 172800, now_ts)
+        );
+        assert_eq!(
+            parse_range_at("now-6h", now).unwrap(),
+            (now_ts - 21600, now_ts)
+        );
+        assert_eq!(parse_range_at("today", now).unwrap(), (midnight, now_ts));
+        assert_eq!(
+            parse_range_at("yesterday", now).unwrap(),
+            (midnight - 86400, midnight)
+        );
+        // Monday 2024-01-01
+        assert_eq!(
+            parse_range_at("this week", now).unwrap(),
+            (1_704_067_200, now_ts)
+        );
+
+        assert!(parse_range_at("last", now).is_err());
+        assert!(parse_range_at("now-0h", now).is_err());
+        assert!(parse_range_at("next week", now).is_err());
+        assert!(matches!(
+            parse_range_at("last 15000000000000w", now),
+            Err(DatadogError::InvalidInput(_))
+        ));
+    }
+
     #[test]
     fn test_format_timestamp_valid() {
         let formatted = format_timestamp(1_704_067_200);
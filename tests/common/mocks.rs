@@ -1,6 +1,9 @@
 // Mock implementations for testing
 // Provides test doubles that don't make real HTTP calls
 
+use futures::future::BoxFuture;
+use mcp_datadog::datadog::api::{ApiRequest, DatadogApi};
+use mcp_datadog::{DatadogError, Result as DatadogResult};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
@@ -39,10 +42,13 @@ impl MockResponse {
     }
 }
 
+/// Queued responses per (method, endpoint)
+type Expectations = HashMap<(String, String), Vec<MockResponse>>;
+
 /// Mock Datadog client for testing without real API calls
 pub struct MockDatadogClient {
     /// Expected calls (method, endpoint) -> response
-    expectations: Arc<Mutex<HashMap<(String, String), Vec<MockResponse>>>>,
+    expectations: Arc<Mutex<Expectations>>,
     /// History of actual calls made
     call_history: Arc<Mutex<Vec<ApiCall>>>,
     /// Default response if no expectation set
@@ -59,7 +65,7 @@ impl MockDatadogClient {
     }
 
     /// Set up an expectation for a specific API call
-    pub fn expect_call(&self, method: &str, endpoint: &str) -> ExpectationBuilder {
+    pub fn expect_call(&self, method: &str, endpoint: &str) -> ExpectationBuilder<'_> {
         ExpectationBuilder {
             mock: self,
             method: method.to_string(),
@@ -73,7 +79,12 @@ impl MockDatadogClient {
     }
 
     /// Record an API call and return the mocked response
-    pub fn call(&self, method: &str, endpoint: &str, params: HashMap<String, String>) -> Result<MockResponse, String> {
+    pub fn call(
+        &self,
+        method: &str,
+        endpoint: &str,
+        params: HashMap<String, String>,
+    ) -> Result<MockResponse, String> {
         // Record the call
         self.call_history.lock().unwrap().push(ApiCall {
             method: method.to_string(),
@@ -85,10 +96,10 @@ impl MockDatadogClient {
         let key = (method.to_string(), endpoint.to_string());
         let mut expectations = self.expectations.lock().unwrap();
 
-        if let Some(responses) = expectations.get_mut(&key) {
-            if !responses.is_empty() {
-                return Ok(responses.remove(0));
-            }
+        if let Some(responses) = expectations.get_mut(&key)
+            && !responses.is_empty()
+        {
+            return Ok(responses.remove(0));
         }
 
         // Use default response if available
@@ -142,6 +153,57 @@ impl Default for MockDatadogClient {
     }
 }
 
+/// Lets handlers run against the mock: each request is a `call`, and error statuses come
+/// back as the errors `DatadogClient` returns for them
+impl DatadogApi for MockDatadogClient {
+    fn send_json<'a>(&'a self, request: ApiRequest<'a>) -> BoxFuture<'a, DatadogResult<Value>> {
+        let params = request
+            .query
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect();
+        let result = match self.call(request.method.as_str(), request.endpoint, params) {
+            Ok(response) if response.status < 400 => Ok(response.body),
+            Ok(response) => Err(match response.status {
+                401 | 403 => DatadogError::AuthError {
+                    status: Some(response.status),
+                    message: response.body.to_string(),
+                },
+                429 => DatadogError::RateLimitError { reset_after: None },
+                status => DatadogError::ApiError {
+                    status,
+                    body: response.body.to_string(),
+                },
+            }),
+            Err(message) => Err(DatadogError::ApiError {
+                status: 404,
+                body: message,
+            }),
+        };
+        Box::pin(async move { result })
+    }
+
+    fn site(&self) -> &str {
+        "datadoghq.com"
+    }
+
+    fn base_url(&self) -> &str {
+        "https://api.datadoghq.com"
+    }
+
+    fn writes_allowed(&self) -> bool {
+        false
+    }
+
+    fn get_tag_filter(&self) -> Option<&str> {
+        None
+    }
+
+    fn app_key_last4(&self) -> &str {
+        "mock"
+    }
+}
+
 /// Builder for setting up call expectations
 pub struct ExpectationBuilder<'a> {
     mock: &'a MockDatadogClient,
@@ -158,7 +220,7 @@ impl<'a> ExpectationBuilder<'a> {
             .lock()
             .unwrap()
             .entry(key)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(response);
     }
 
@@ -170,7 +232,7 @@ impl<'a> ExpectationBuilder<'a> {
             .lock()
             .unwrap()
             .entry(key)
-            .or_insert_with(Vec::new)
+            .or_default()
             .extend(responses);
     }
 
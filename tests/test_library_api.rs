@@ -0,0 +1,90 @@
+use mcp_datadog::api::{LogsSearchRequest, MetricsQueryRequest};
+use mcp_datadog::{Datadog, DatadogClient};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn datadog(mock_server: &MockServer) -> Datadog {
+    let client = DatadogClient::new("test_key".to_string(), "test_app_key".to_string(), None)
+        .unwrap()
+        .with_api_url(&mock_server.uri());
+    Datadog::new(client)
+}
+
+#[tokio::test]
+async fn test_query_metrics_typed() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/query"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "status": "ok",
+            "res_type": "time_series",
+            "from_date": 1700000000000i64,
+            "to_date": 1700003600000i64,
+            "query": "avg:system.cpu.user{*}",
+            "series": [{
+                "metric": "system.cpu.user",
+                "scope": "host:web-01",
+                "expression": "avg:system.cpu.user{host:web-01}",
+                "pointlist": [[1700000000000.0, 42.5], [1700000060000.0, null]],
+                "interval": 60
+            }]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let response = datadog(&mock_server)
+        .await
+        .query_metrics(
+            MetricsQueryRequest::new("avg:system.cpu.user{*}").between("1 hour ago", "now"),
+        )
+        .await
+        .unwrap();
+
+    let series = &response.data[0];
+    assert_eq!(series.metric.as_deref(), Some("system.cpu.user"));
+    assert_eq!(series.interval, Some(60));
+    let points = series.points.as_ref().unwrap();
+    assert_eq!(points.count, 2);
+    assert_eq!(points.data[0].value, Some(42.5));
+    assert_eq!(points.data[1].value, None);
+    assert_eq!(response.meta.unwrap()["status"], "ok");
+}
+
+#[tokio::test]
+async fn test_search_logs_typed() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v2/logs/events/search"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [{
+                "id": "log-1",
+                "attributes": {
+                    "timestamp": "2024-01-01T00:00:00Z",
+                    "message": "Payment failed",
+                    "service": "checkout",
+                    "status": "error",
+                    "tags": ["env:prod", "team:payments"]
+                }
+            }]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let request = LogsSearchRequest {
+        limit: Some(5),
+        tag_filter: Some("env:".to_string()),
+        ..LogsSearchRequest::new("service:checkout status:error")
+    };
+    let response = datadog(&mock_server)
+        .await
+        .search_logs(request)
+        .await
+        .unwrap();
+
+    let log = &response.data[0];
+    assert_eq!(log.id.as_deref(), Some("log-1"));
+    assert_eq!(log.message.as_deref(), Some("Payment failed"));
+    assert_eq!(log.tags, ["env:prod"]);
+    assert!(response.pagination.is_some());
+}
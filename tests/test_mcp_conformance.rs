@@ -0,0 +1,112 @@
+// MCP conformance: drives the real binary over stdio against a mocked Datadog API
+
+use mcp_datadog::server::self_test::{self, StdioClient};
+use serde_json::{Value, json};
+use tokio::process::Command;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Canned Datadog responses keyed by "METHOD /path"
+fn routes() -> serde_json::Map<String, Value> {
+    let content = std::fs::read_to_string("tests/fixtures/mcp_routes.json").unwrap();
+    serde_json::from_str(&content).unwrap()
+}
+
+async fn mock_datadog() -> MockServer {
+    let server = MockServer::start().await;
+
+    for (route, body) in routes() {
+        let (verb, route_path) = route.split_once(' ').unwrap();
+        Mock::given(method(verb))
+            .and(path(route_path))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&server)
+            .await;
+    }
+
+    // Anything else gets wiremock's 404; `unmatched_requests` reports it
+    server
+}
+
+/// Requests to the mocked API that no fixture route answered, as "METHOD /path"
+async fn unmatched_requests(datadog: &MockServer) -> Vec<String> {
+    let routes = routes();
+    let mut unmatched: Vec<String> = datadog
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(|request| format!("{} {}", request.method, request.url.path()))
+        .filter(|route| !routes.contains_key(route))
+        .collect();
+    unmatched.sort();
+    unmatched.dedup();
+    unmatched
+}
+
+fn server_command(api_url: &str) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_mcp-datadog"));
+    command
+        .env("DD_API_KEY", "test_key")
+        .env("DD_APP_KEY", "test_app_key")
+        .env("DD_API_URL", api_url)
+        .env("MCP_TRANSPORT", "stdio")
+        .env("DD_MCP_MODE", "read_only")
+        .env_remove("DD_SITE");
+    command
+}
+
+#[tokio::test]
+async fn test_stdio_conformance() {
+    let datadog = mock_datadog().await;
+    let mut client = StdioClient::spawn(server_command(&datadog.uri())).unwrap();
+
+    let arguments = json!({
+        "datadog_metrics_query": {
+            "query": "avg:system.cpu.user{*}",
+            "from": "1 hour ago",
+            "to": "now"
+        }
+    });
+    let report = self_test::run(&mut client, &arguments).await.unwrap();
+    client.shutdown().await.unwrap();
+
+    assert!(report.passed(), "{}", report.summary());
+    assert_eq!(report.server_info["name"], "datadog-mcp-server");
+    assert_eq!(report.tools.len(), report.tool_count);
+
+    // Tools may reject placeholder arguments, but every API call must hit a fixture route
+    let unmatched = unmatched_requests(&datadog).await;
+    assert!(
+        unmatched.is_empty(),
+        "no fixture in mcp_routes.json for: {}",
+        unmatched.join(", ")
+    );
+    for tool in report.tools.iter().filter(|t| t.is_error) {
+        assert!(
+            tool.detail.contains("Invalid input"),
+            "{} has no fixture in mcp_routes.json: {}",
+            tool.name,
+            tool.detail
+        );
+    }
+
+    let metrics = report
+        .tools
+        .iter()
+        .find(|t| t.name == "datadog_metrics_query")
+        .unwrap();
+    assert!(!metrics.is_error, "{}", metrics.detail);
+    assert_eq!(metrics.detail, "1 items");
+}
+
+#[tokio::test]
+async fn test_tools_require_initialize() {
+    let datadog = mock_datadog().await;
+    let mut client = StdioClient::spawn(server_command(&datadog.uri())).unwrap();
+
+    let response = client.request("tools/list", json!({})).await.unwrap();
+    assert_eq!(response["error"]["code"], -32002);
+
+    client.shutdown().await.unwrap();
+}
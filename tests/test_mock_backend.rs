@@ -0,0 +1,83 @@
+// Handlers driven by the in-memory MockDatadogClient through the DatadogApi trait,
+// without an HTTP server
+
+#[allow(dead_code)]
+#[path = "common/mocks.rs"]
+mod mocks;
+
+use mcp_datadog::DatadogError;
+use mcp_datadog::handlers::logs::LogsHandler;
+use mcp_datadog::handlers::metrics::MetricsHandler;
+use mcp_datadog::handlers::monitors::MonitorsHandler;
+use mocks::MockDatadogClient;
+use serde_json::json;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_metrics_query_against_mock() {
+    let mock = Arc::new(MockDatadogClient::new());
+    mock.expect_call("GET", "/api/v1/query").return_json(json!({
+        "status": "ok",
+        "res_type": "time_series",
+        "from_date": 1700000000000i64,
+        "to_date": 1700003600000i64,
+        "query": "avg:system.cpu.user{*}",
+        "series": [{
+            "metric": "system.cpu.user",
+            "scope": "host:web-01",
+            "expression": "avg:system.cpu.user{host:web-01}",
+            "pointlist": [[1700000000000.0, 42.5]]
+        }]
+    }));
+
+    let result = MetricsHandler::query(
+        mock.clone(),
+        &json!({"query": "avg:system.cpu.user{*}", "from": "1 hour ago"}),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result["data"][0]["metric"], "system.cpu.user");
+    let call = mock.get_last_call().unwrap();
+    assert_eq!(call.endpoint, "/api/v1/query");
+    assert_eq!(call.params["query"], "avg:system.cpu.user{*}");
+    assert!(call.params.contains_key("from"));
+}
+
+#[tokio::test]
+async fn test_logs_search_against_mock() {
+    let mock = Arc::new(MockDatadogClient::new());
+    mock.expect_call("POST", "/api/v2/logs/events/search")
+        .return_json(json!({
+            "data": [{
+                "id": "log-1",
+                "attributes": {"message": "Payment failed", "service": "checkout"}
+            }]
+        }));
+
+    let result = LogsHandler::search(mock.clone(), &json!({"query": "service:checkout"}))
+        .await
+        .unwrap();
+
+    assert_eq!(result["data"][0]["message"], "Payment failed");
+    assert_eq!(mock.call_count("/api/v2/logs/events/search"), 1);
+}
+
+#[tokio::test]
+async fn test_error_status_from_mock() {
+    let mock = Arc::new(MockDatadogClient::new());
+    mock.expect_call("GET", "/api/v1/monitor/42")
+        .return_error(403, "Forbidden");
+
+    let error = MonitorsHandler::get(mock.clone(), &json!({"monitor_id": 42}))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        DatadogError::AuthError {
+            status: Some(403),
+            ..
+        }
+    ));
+}